@@ -0,0 +1,143 @@
+//! Throughput of hot paths of core blocks
+//!
+//! Run with `cargo bench` (optionally with `--features simd`). Kernels are
+//! measured directly, while blocks are measured by passing chunks of several
+//! sizes through a running block, which includes the cost of the channels
+//! between blocks. The SoapySDR receive loop is measured separately with a
+//! mock stream (see `benches/soapysdr.rs`).
+
+use criterion::measurement::WallTime;
+use criterion::{
+    criterion_group, criterion_main, BatchSize, BenchmarkGroup, BenchmarkId, Criterion, Throughput,
+};
+use radiorust::blocks::analysis::Fourier;
+use radiorust::blocks::filters::{design, FftFirFilter, FirFilter};
+use radiorust::blocks::transform::FreqShifter;
+use radiorust::math::fft;
+use radiorust::prelude::*;
+use radiorust::simd;
+use radiorust::windowing::Hann;
+
+use tokio::runtime::Runtime;
+
+use std::hint::black_box;
+use std::sync::Arc;
+
+const CHUNK_LENS: [usize; 3] = [256, 4096, 65536];
+/// Number of chunks passed through a block per iteration
+const CHUNKS: usize = 16;
+const FIR_TAPS: usize = 64;
+
+fn test_signal(len: usize) -> Vec<Complex<f32>> {
+    (0..len)
+        .map(|i| Complex::new((i as f32 * 0.1).sin(), (i as f32 * 0.3).cos()))
+        .collect()
+}
+
+fn bench_kernels(c: &mut Criterion) {
+    let mut group = c.benchmark_group("kernels");
+    for chunk_len in CHUNK_LENS {
+        group.throughput(Throughput::Elements(chunk_len as u64));
+        let a = test_signal(chunk_len);
+        let b: Vec<Complex<f32>> = a.iter().rev().copied().collect();
+        let mut products = vec![Complex::new(0.0f32, 0.0); chunk_len];
+        group.bench_function(
+            BenchmarkId::new("simd::complex_mul", chunk_len),
+            |bencher| {
+                bencher.iter(|| simd::complex_mul(black_box(&a), black_box(&b), &mut products))
+            },
+        );
+        group.bench_function(BenchmarkId::new("fft::forward", chunk_len), |bencher| {
+            bencher.iter_batched_ref(
+                || a.clone(),
+                |data| fft::forward(black_box(data)),
+                BatchSize::SmallInput,
+            )
+        });
+    }
+    group.finish();
+}
+
+/// Send [`CHUNKS`] copies of `chunk` and wait until all samples have been
+/// received
+async fn pass_chunks(
+    sender: &Sender<Signal<Complex<f32>>>,
+    receiver: &mut Receiver<Signal<Complex<f32>>>,
+    chunk: &Chunk<Complex<f32>>,
+) {
+    let send = async {
+        for _ in 0..CHUNKS {
+            sender
+                .send(Signal::Samples {
+                    sample_rate: 1e6,
+                    chunk: chunk.clone(),
+                })
+                .await
+                .unwrap();
+        }
+    };
+    let recv = async {
+        let mut received = 0;
+        while received < CHUNKS * chunk.len() {
+            if let Signal::Samples { chunk, .. } = receiver.recv().await.unwrap() {
+                received += chunk.len();
+            }
+        }
+    };
+    tokio::join!(send, recv);
+}
+
+/// Measure blocks created by `new_block` with chunks of `chunk_len` samples
+///
+/// A new block is connected for each iteration (outside the measurement),
+/// such that samples delayed by a block don't carry over.
+fn bench_block<B, F>(
+    group: &mut BenchmarkGroup<WallTime>,
+    runtime: &Runtime,
+    name: &str,
+    new_block: F,
+    chunk_len: usize,
+) where
+    B: Consumer<Signal<Complex<f32>>> + Producer<Signal<Complex<f32>>>,
+    F: Fn() -> B,
+{
+    let chunk = Chunk::from(test_signal(chunk_len));
+    group.bench_function(BenchmarkId::new(name, chunk_len), |bencher| {
+        bencher.iter_batched(
+            || {
+                let (sender, sender_connector) = new_sender::<Signal<Complex<f32>>>();
+                let (receiver, receiver_connector) = new_receiver::<Signal<Complex<f32>>>();
+                let block = new_block();
+                block.feed_from(&sender_connector);
+                block.feed_into(&receiver_connector);
+                (block, sender, receiver)
+            },
+            |(block, sender, mut receiver)| {
+                runtime.block_on(pass_chunks(&sender, &mut receiver, &chunk));
+                (block, sender, receiver)
+            },
+            BatchSize::PerIteration,
+        )
+    });
+}
+
+fn bench_blocks(c: &mut Criterion) {
+    let runtime = Runtime::new().unwrap();
+    let _guard = runtime.enter();
+    let coeffs: Arc<[f32]> = design::lowpass(100e3, 1e6, FIR_TAPS, &Hann).into();
+    let mut group = c.benchmark_group("blocks");
+    for chunk_len in CHUNK_LENS {
+        group.throughput(Throughput::Elements((CHUNKS * chunk_len) as u64));
+        let shifter = || FreqShifter::with_shift(1234.5);
+        bench_block(&mut group, &runtime, "FreqShifter", shifter, chunk_len);
+        let fir = || FirFilter::new(coeffs.clone());
+        bench_block(&mut group, &runtime, "FirFilter", fir, chunk_len);
+        let fft_fir = || FftFirFilter::new(coeffs.clone());
+        bench_block(&mut group, &runtime, "FftFirFilter", fft_fir, chunk_len);
+        bench_block(&mut group, &runtime, "Fourier", Fourier::new, chunk_len);
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_kernels, bench_blocks);
+criterion_main!(benches);
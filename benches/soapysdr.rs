@@ -0,0 +1,51 @@
+//! Throughput of the receive path of the SoapySDR blocks
+//!
+//! Run with `cargo bench --features soapysdr`. Instead of hardware, a mock
+//! stream delivers [`MTU`] samples per read, and the chunks (see
+//! `SoapySdrRxHandle::set_chunk_duration`) are sent into a [`NullSink`].
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use radiorust::blocks::io::rf::soapysdr::{read_and_send, Error, RxRead};
+use radiorust::blocks::io::NullSink;
+use radiorust::prelude::*;
+
+use tokio::runtime::Runtime;
+
+const CHUNK_LENS: [usize; 4] = [4096, 16384, 65536, 262144];
+/// Number of chunks read per iteration
+const CHUNKS: usize = 16;
+/// Number of samples per read of [`MockStream`]
+const MTU: usize = 4096;
+
+/// Stream which returns up to [`MTU`] samples per read without waiting
+struct MockStream;
+
+impl RxRead<Complex<f32>> for MockStream {
+    fn read(&mut self, buffers: &[&mut [Complex<f32>]], _: i64) -> Result<usize, Error> {
+        Ok(buffers[0].len().min(MTU))
+    }
+}
+
+fn bench_rx_loop(c: &mut Criterion) {
+    let runtime = Runtime::new().unwrap();
+    let _guard = runtime.enter();
+    let (sender, sender_connector) = new_sender::<Signal<Complex<f32>>>();
+    let sink = NullSink::<Complex<f32>>::new();
+    sink.feed_from(&sender_connector);
+    let senders = [sender];
+    let mut group = c.benchmark_group("soapysdr");
+    for chunk_len in CHUNK_LENS {
+        group.throughput(Throughput::Elements((CHUNKS * chunk_len) as u64));
+        group.bench_function(BenchmarkId::new("read_and_send", chunk_len), |bencher| {
+            bencher.iter(|| {
+                runtime
+                    .block_on(read_and_send(MockStream, &senders, 20e6, chunk_len, CHUNKS))
+                    .unwrap()
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_rx_loop);
+criterion_main!(benches);
@@ -1,7 +1,5 @@
 use radiorust::{metering, prelude::*};
 
-use soapysdr::Direction::Rx;
-
 use clap::Parser;
 
 use std::collections::VecDeque;
@@ -42,13 +40,11 @@ async fn main() {
     let hw_frequency = frequency + freq_offset;
     let sample_rate = 1024000.0;
     let bandwidth = 1024000.0;
-    let device = soapysdr::Device::new(args.device_options.as_str()).unwrap();
+    let sdr_rx =
+        blocks::io::rf::soapysdr::SoapySdrRx::open(&args.device_options, 0, sample_rate).unwrap();
     println!("Hardware frequency: {hw_frequency}");
-    device.set_frequency(Rx, 0, hw_frequency, "").unwrap();
-    device.set_sample_rate(Rx, 0, sample_rate).unwrap();
-    device.set_bandwidth(Rx, 0, bandwidth).unwrap();
-    let rx_stream = device.rx_stream::<Complex<f32>>(&[0]).unwrap();
-    let sdr_rx = blocks::io::rf::soapysdr::SoapySdrRx::new(rx_stream, sample_rate);
+    sdr_rx.set_frequency(0, hw_frequency).unwrap();
+    sdr_rx.set_bandwidth(0, bandwidth).unwrap();
     sdr_rx.activate().await.unwrap();
     let freq_shifter = blocks::FreqShifter::<f32>::with_shift(freq_offset);
     println!("Frequency: {}", hw_frequency - freq_offset);
@@ -0,0 +1,90 @@
+use radiorust::{numbers::Complex, simd};
+
+use clap::Parser;
+
+use std::hint::black_box;
+use std::time::Instant;
+
+#[derive(Parser, Debug)]
+#[command(
+    version,
+    about = "Throughput comparison of scalar and SIMD bulk operations",
+    long_about = None,
+)]
+struct Args {
+    /// Number of samples per slice
+    #[arg(short = 'n', long, default_value = "4096")]
+    len: usize,
+    /// Number of iterations
+    #[arg(short = 'i', long, default_value = "20000")]
+    iterations: usize,
+}
+
+fn measure<F: FnMut()>(name: &str, samples: usize, mut f: F) -> f64 {
+    f();
+    let start = Instant::now();
+    f();
+    let rate = samples as f64 / start.elapsed().as_secs_f64();
+    println!("{name:<28} {:>10.1} Msamples/s", rate / 1e6);
+    rate
+}
+
+fn main() {
+    let args = Args::parse();
+    let samples = args.len * args.iterations;
+    let a: Vec<Complex<f32>> = (0..args.len)
+        .map(|i| Complex::new((i as f32 * 0.1).sin(), (i as f32 * 0.3).cos()))
+        .collect();
+    let b: Vec<Complex<f32>> = a.iter().rev().copied().collect();
+    let mut products = vec![Complex::new(0.0f32, 0.0); args.len];
+    let mut magnitudes = vec![0.0f32; args.len];
+    let mut scaled = a.clone();
+    println!("simd feature enabled: {}", cfg!(feature = "simd"));
+    for (name, scalar_rate, rate) in [
+        (
+            "complex_mul",
+            measure("scalar::complex_mul", samples, || {
+                for _ in 0..args.iterations {
+                    simd::scalar::complex_mul(black_box(&a), black_box(&b), &mut products);
+                    black_box(&mut products);
+                }
+            }),
+            measure("complex_mul", samples, || {
+                for _ in 0..args.iterations {
+                    simd::complex_mul(black_box(&a), black_box(&b), &mut products);
+                    black_box(&mut products);
+                }
+            }),
+        ),
+        (
+            "magnitude_squared",
+            measure("scalar::magnitude_squared", samples, || {
+                for _ in 0..args.iterations {
+                    simd::scalar::magnitude_squared(black_box(&a), &mut magnitudes);
+                    black_box(&mut magnitudes);
+                }
+            }),
+            measure("magnitude_squared", samples, || {
+                for _ in 0..args.iterations {
+                    simd::magnitude_squared(black_box(&a), &mut magnitudes);
+                    black_box(&mut magnitudes);
+                }
+            }),
+        ),
+        (
+            "scale",
+            measure("scalar::scale", samples, || {
+                for _ in 0..args.iterations {
+                    simd::scalar::scale(black_box(&mut scaled), 0.999);
+                }
+            }),
+            measure("scale", samples, || {
+                for _ in 0..args.iterations {
+                    simd::scale(black_box(&mut scaled), 1.001);
+                }
+            }),
+        ),
+    ] {
+        println!("{name:<28} {:>10.2}x speedup", rate / scalar_rate);
+    }
+}
@@ -0,0 +1,169 @@
+//! Running blocks without writing `async` code
+//!
+//! Blocks spawn background tasks and thus require a [tokio] runtime (see
+//! [`blocks`](crate::blocks#background-tasks)). For simple scripts, e.g.
+//! processing a file and writing the result to another file, [`run`]
+//! creates a single-threaded runtime, such that blocks can be created and
+//! connected in ordinary (synchronous) code:
+//!
+//! ```no_run
+//! use radiorust::blocking;
+//! use radiorust::blocks::io::raw::{RawSink, RawSource, SampleFormat};
+//! use radiorust::prelude::*;
+//!
+//! blocking::run(|runtime| {
+//!     let source = RawSource::new("in.cf32", SampleFormat::F32Le, 48000.0, 4096).unwrap();
+//!     let filter = blocks::Filter::new(|_, freq| {
+//!         if freq.abs() <= 5000.0 {
+//!             Complex::from(1.0)
+//!         } else {
+//!             Complex::from(0.0)
+//!         }
+//!     });
+//!     filter.feed_from(&source);
+//!     let sink = RawSink::new("out.cf32", SampleFormat::F32Le).unwrap();
+//!     runtime.run_until_end_of_file(&filter, &sink);
+//!     runtime.block_on(sink.finalize()).unwrap();
+//! });
+//! ```
+//!
+//! The background tasks only make progress while the runtime is driven,
+//! i.e. during [`Runtime::run_until_end_of_file`] or [`Runtime::block_on`].
+
+use crate::blocks::io::raw::events::EndOfFile;
+use crate::flow::*;
+use crate::signal::*;
+
+use tokio::runtime;
+
+use std::future::Future;
+use std::io;
+
+/// Single-threaded runtime for running blocks from synchronous code
+///
+/// Blocks must be created while the runtime is [entered] (which [`run`]
+/// does for the whole closure).
+///
+/// [entered]: Runtime::enter
+pub struct Runtime {
+    runtime: runtime::Runtime,
+}
+
+impl Runtime {
+    /// Create new single-threaded runtime with I/O and time drivers enabled
+    pub fn new() -> io::Result<Self> {
+        Ok(Self {
+            runtime: runtime::Builder::new_current_thread()
+                .enable_all()
+                .build()?,
+        })
+    }
+    /// Enter the runtime, such that blocks can be created until the returned
+    /// guard is dropped
+    pub fn enter(&self) -> runtime::EnterGuard<'_> {
+        self.runtime.enter()
+    }
+    /// Run the background tasks until the given `future` has completed and
+    /// return its output
+    ///
+    /// This may be used to call `async` methods, e.g. to finalize a file
+    /// sink.
+    pub fn block_on<F: Future>(&self, future: F) -> F::Output {
+        self.runtime.block_on(future)
+    }
+    /// Connect `producer` to `consumer` and run the background tasks until
+    /// the `consumer` has received an [`EndOfFile`] event
+    ///
+    /// All samples received before the event have been processed by the
+    /// `consumer` when this method returns, so a file sink may be finalized
+    /// afterwards. `producer` and `consumer` are disconnected again. If the
+    /// `producer` is dropped before the end of the file, this method returns
+    /// early.
+    pub fn run_until_end_of_file<T, P, C>(&self, producer: &P, consumer: &C)
+    where
+        T: Clone + Send + Sync + 'static,
+        P: Producer<Signal<T>>,
+        C: Consumer<Signal<T>>,
+    {
+        let (mut receiver, receiver_connector) = new_receiver::<Signal<T>>();
+        let (sender, sender_connector) = new_sender::<Signal<T>>();
+        receiver_connector.feed_from(producer);
+        sender_connector.feed_into(consumer);
+        self.block_on(async move {
+            loop {
+                let Ok(signal) = receiver.recv().await else { return; };
+                let end_of_file = match &signal {
+                    Signal::Samples { .. } => false,
+                    Signal::Event(event) => event.as_any().is::<EndOfFile>(),
+                };
+                let Ok(()) = sender.send(signal).await else { return; };
+                if end_of_file {
+                    // the next reservation succeeds once the event has been
+                    // received, i.e. all previous samples have been processed
+                    sender.reserve().await.ok();
+                    return;
+                }
+            }
+        });
+        consumer.feed_from_none();
+    }
+}
+
+/// Create a [`Runtime`], enter it, and call `f` with it
+///
+/// See the [module-level documentation](self) for an example.
+///
+/// # Panics
+///
+/// Panics if the runtime cannot be created.
+pub fn run<F, R>(f: F) -> R
+where
+    F: FnOnce(&Runtime) -> R,
+{
+    let runtime = Runtime::new().expect("could not create runtime");
+    let _guard = runtime.enter();
+    f(&runtime)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::blocks::io::raw::{RawSink, RawSource, SampleFormat};
+    use crate::blocks::transform::Gain;
+    use crate::numbers::Complex;
+    #[test]
+    fn test_run_until_end_of_file() {
+        let input = std::env::temp_dir().join(format!(
+            "radiorust_test_blocking_in_{}.bin",
+            std::process::id()
+        ));
+        let output = std::env::temp_dir().join(format!(
+            "radiorust_test_blocking_out_{}.bin",
+            std::process::id()
+        ));
+        let samples: Vec<u8> = (0..10000u32)
+            .flat_map(|i| [(i as f32).to_le_bytes(), 0.0f32.to_le_bytes()])
+            .flatten()
+            .collect();
+        std::fs::write(&input, &samples).unwrap();
+        run(|runtime| {
+            let source = RawSource::new(&input, SampleFormat::F32Le, 48000.0, 1000).unwrap();
+            let gain = Gain::<f32>::new(2.0);
+            gain.feed_from(&source);
+            let sink = RawSink::new(&output, SampleFormat::F32Le).unwrap();
+            runtime.run_until_end_of_file(&gain, &sink);
+            runtime.block_on(sink.finalize()).unwrap();
+        });
+        let written = std::fs::read(&output).unwrap();
+        std::fs::remove_file(&input).unwrap();
+        std::fs::remove_file(&output).unwrap();
+        assert_eq!(written.len(), samples.len());
+        let values: Vec<f32> = written
+            .chunks(4)
+            .map(|bytes| f32::from_le_bytes(bytes.try_into().unwrap()))
+            .collect();
+        for (i, pair) in values.chunks(2).enumerate() {
+            assert_eq!(Complex::new(pair[0], pair[1]), Complex::new(2.0 * i as f32, 0.0));
+        }
+    }
+}
@@ -1,14 +1,59 @@
 //! Signal analysis / metering
 
+use crate::blocks::filters::design;
 use crate::bufferpool::*;
 use crate::flow::*;
 use crate::impl_block_trait;
+use crate::math::fft;
 use crate::numbers::*;
 use crate::signal::*;
+use crate::simd;
 use crate::windowing::{self, Window};
 
-use easyfft::prelude::*;
+use tokio::sync::{broadcast, mpsc, watch};
 use tokio::task::spawn;
+use tokio::time::Instant as TokioInstant;
+
+use std::collections::VecDeque;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Events emitted by blocks in this module
+pub mod events {
+    use super::*;
+    /// Sent by [`Trigger`] when the signal power crossed the threshold
+    #[derive(Clone, Debug)]
+    pub struct Triggered {
+        /// Direction of the crossing ([`TriggerEdge::Rising`] or
+        /// [`TriggerEdge::Falling`])
+        pub edge: TriggerEdge,
+        /// Smoothed signal power in decibels at the time of the crossing
+        pub power_db: f64,
+    }
+    impl Event for Triggered {
+        fn as_any(&self) -> &(dyn std::any::Any + Send + Sync) {
+            self
+        }
+    }
+    /// Sent by [`Correlator`] when a peak of the normalized correlation has
+    /// been found
+    #[derive(Clone, Debug)]
+    pub struct CorrelationPeak {
+        /// Index of the first sample of the matching segment, counted from
+        /// the first sample received by the [`Correlator`]
+        pub position: u64,
+        /// Normalized correlation (between `0.0` and `1.0`)
+        pub magnitude: f64,
+        /// Phase of the signal relative to the template in radians
+        pub phase: f64,
+    }
+    impl Event for CorrelationPeak {
+        fn as_any(&self) -> &(dyn std::any::Any + Send + Sync) {
+            self
+        }
+    }
+}
+use events::*;
 
 /// Block performing a Fourier analysis
 ///
@@ -97,7 +142,7 @@ where
                         for idx in 0..n {
                             output_chunk[idx] *= window_values[idx];
                         }
-                        output_chunk.fft_mut();
+                        fft::forward(&mut output_chunk);
                         if center_dc {
                             output_chunk.rotate_right(n / 2);
                         }
@@ -120,79 +165,2474 @@ where
     }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::tests::assert_approx;
-    #[tokio::test]
-    async fn test_fourier() {
-        let (sender, sender_connector) = new_sender();
-        let fourier1 = Fourier::<f64>::new();
-        let fourier2 = Fourier::<f64>::new_center_dc();
-        let (mut receiver1, receiver1_connector) = new_receiver();
-        let (mut receiver2, receiver2_connector) = new_receiver();
-        fourier1.feed_from(&sender_connector);
-        fourier2.feed_from(&sender_connector);
-        fourier1.feed_into(&receiver1_connector);
-        fourier2.feed_into(&receiver2_connector);
-        sender
-            .send(Signal::Samples {
-                sample_rate: 48000.0,
-                chunk: Chunk::from(vec![
-                    Complex::new(1.0, 0.0),
-                    Complex::new(1.0, 0.0),
-                    Complex::new(1.0, 0.0),
-                ]),
-            })
-            .await
-            .unwrap();
-        let Signal::Samples { chunk: output1, .. } = receiver1.recv().await.unwrap()
-        else { panic!(); };
-        let Signal::Samples { chunk: output2, .. } = receiver2.recv().await.unwrap()
-        else { panic!(); };
-        assert_approx(output1[0].re, 3.0);
-        assert_approx(output1[0].im, 0.0);
-        assert_approx(output1[1].re, 0.0);
-        assert_approx(output1[1].im, 0.0);
-        assert_approx(output1[2].re, 0.0);
-        assert_approx(output1[2].im, 0.0);
-        assert_approx(output2[0].re, 0.0);
-        assert_approx(output2[0].im, 0.0);
-        assert_approx(output2[1].re, 3.0);
-        assert_approx(output2[1].im, 0.0);
-        assert_approx(output2[2].re, 0.0);
-        assert_approx(output2[2].im, 0.0);
-        sender
-            .send(Signal::Samples {
-                sample_rate: 48000.0,
-                chunk: Chunk::from(vec![
-                    Complex::new(1.0, 0.0),
-                    Complex::new(1.5, 0.0),
-                    Complex::new(1.0, 0.0),
-                    Complex::new(0.5, 0.0),
-                ]),
-            })
-            .await
-            .unwrap();
-        let Signal::Samples { chunk: output1, .. } = receiver1.recv().await.unwrap()
-        else { panic!(); };
-        let Signal::Samples { chunk: output2, .. } = receiver2.recv().await.unwrap()
-        else { panic!(); };
-        assert_approx(output1[0].re, 4.0);
-        assert_approx(output1[0].im, 0.0);
-        assert_approx(output1[1].re, 0.0);
-        assert_approx(output1[1].im, -1.0);
-        assert_approx(output1[2].re, 0.0);
-        assert_approx(output1[2].im, 0.0);
-        assert_approx(output1[3].re, 0.0);
-        assert_approx(output1[3].im, 1.0);
-        assert_approx(output2[0].re, 0.0);
-        assert_approx(output2[0].im, 0.0);
-        assert_approx(output2[1].re, 0.0);
-        assert_approx(output2[1].im, 1.0);
-        assert_approx(output2[2].re, 4.0);
-        assert_approx(output2[2].im, 0.0);
-        assert_approx(output2[3].re, 0.0);
-        assert_approx(output2[3].im, -1.0);
+/// Power spectrum published by the [`Spectrum`] block
+#[derive(Clone, Debug)]
+pub struct SpectrumFrame<Flt> {
+    /// Sample rate of the analyzed [`Signal::Samples`]
+    pub sample_rate: f64,
+    /// Most recently announced [`CenterFrequency`] (if any)
+    pub center_frequency: Option<f64>,
+    /// Power of each frequency bin with DC in the center
+    ///
+    /// For an even number of bins `n`, the DC bin is at index `n / 2`. The sum
+    /// of all bins equals the average power of the (windowed) input.
+    pub bins: Arc<[Flt]>,
+}
+
+impl<Flt> SpectrumFrame<Flt> {
+    /// Frequency in hertz (relative to the center frequency) of bin with
+    /// given index
+    pub fn frequency(&self, index: usize) -> f64 {
+        let n = self.bins.len();
+        (index as f64 - (n / 2) as f64) * self.sample_rate / n as f64
+    }
+    /// Absolute frequency in hertz of bin with given index (if the center
+    /// frequency is known)
+    pub fn absolute_frequency(&self, index: usize) -> Option<f64> {
+        Some(self.center_frequency? + self.frequency(index))
+    }
+}
+
+/// Block which computes power spectra, e.g. for a waterfall display
+///
+/// The received samples are split into overlapping windows of `fft_size`
+/// samples, where two subsequent windows share `overlap` samples. The
+/// resulting [`SpectrumFrame`]s can be obtained through a [`watch::Receiver`]
+/// returned by [`Spectrum::subscribe`]. At most `max_frame_rate` frames are
+/// calculated per second; excess windows are skipped.
+pub struct Spectrum<Flt> {
+    receiver_connector: ReceiverConnector<Signal<Complex<Flt>>>,
+    frame: watch::Receiver<SpectrumFrame<Flt>>,
+}
+
+impl_block_trait! { <Flt> Consumer<Signal<Complex<Flt>>> for Spectrum<Flt> }
+
+impl<Flt> Spectrum<Flt>
+where
+    Flt: Float,
+{
+    /// Create new `Spectrum` block using a [Hann window]
+    ///
+    /// [Hann window]: windowing::Hann
+    pub fn new(fft_size: usize, overlap: usize, max_frame_rate: f64) -> Self {
+        Self::with_window(fft_size, overlap, max_frame_rate, windowing::Hann)
+    }
+    /// Create new `Spectrum` block using given window function
+    pub fn with_window<W>(fft_size: usize, overlap: usize, max_frame_rate: f64, window: W) -> Self
+    where
+        W: Window + Send + 'static,
+    {
+        assert!(fft_size > 0, "FFT size must be positive");
+        assert!(overlap < fft_size, "overlap must be smaller than FFT size");
+        assert!(max_frame_rate > 0.0, "maximum frame rate must be positive");
+        let (mut receiver, receiver_connector) = new_receiver::<Signal<Complex<Flt>>>();
+        let (frame_send, frame) = watch::channel(SpectrumFrame {
+            sample_rate: 0.0,
+            center_frequency: None,
+            bins: vec![Flt::zero(); fft_size].into(),
+        });
+        let mut window_values: Vec<Flt> = Vec::with_capacity(fft_size);
+        let mut energy: f64 = 0.0;
+        for idx in 0..fft_size {
+            let value = window.relative_value_at(2.0 * (idx as f64 + 0.5) / fft_size as f64 - 1.0);
+            window_values.push(flt!(value));
+            energy += value * value;
+        }
+        let scale: Flt = flt!((energy * fft_size as f64).sqrt().recip());
+        for value in window_values.iter_mut() {
+            *value *= scale;
+        }
+        let min_interval = Duration::from_secs_f64(max_frame_rate.recip());
+        spawn(async move {
+            let mut buffer: Vec<Complex<Flt>> = Vec::with_capacity(2 * fft_size);
+            let mut scratch: Vec<Complex<Flt>> = Vec::with_capacity(fft_size);
+            let mut next_frame = Instant::now();
+            let mut center_frequency: Option<f64> = None;
+            loop {
+                let Ok(signal) = receiver.recv().await else { return; };
+                match signal {
+                    Signal::Samples {
+                        sample_rate,
+                        chunk: input_chunk,
+                    } => {
+                        buffer.extend_from_slice(&input_chunk);
+                        while buffer.len() >= fft_size {
+                            let now = Instant::now();
+                            if now >= next_frame {
+                                next_frame = next_frame.max(now) + min_interval;
+                                scratch.clear();
+                                scratch.extend(
+                                    buffer[0..fft_size]
+                                        .iter()
+                                        .zip(window_values.iter())
+                                        .map(|(&x, &w)| x * w),
+                                );
+                                fft::forward(&mut scratch);
+                                scratch.rotate_right(fft_size / 2);
+                                let mut bins: Vec<Flt> = vec![Flt::zero(); fft_size];
+                                simd::magnitude_squared(&scratch, &mut bins);
+                                let bins: Arc<[Flt]> = bins.into();
+                                frame_send.send_replace(SpectrumFrame {
+                                    sample_rate,
+                                    center_frequency,
+                                    bins,
+                                });
+                            }
+                            buffer.drain(0..fft_size - overlap);
+                        }
+                    }
+                    Signal::Event(event) => {
+                        if let Some(frequency) = event.as_any().downcast_ref::<CenterFrequency>() {
+                            center_frequency = Some(frequency.0);
+                        }
+                        if event.is_interrupt() {
+                            buffer.clear();
+                        }
+                    }
+                }
+            }
+        });
+        Self {
+            receiver_connector,
+            frame,
+        }
+    }
+    /// Get [`watch::Receiver`] of most recently calculated [`SpectrumFrame`]
+    pub fn subscribe(&self) -> watch::Receiver<SpectrumFrame<Flt>> {
+        self.frame.clone()
+    }
+}
+
+/// Averaged power spectrum published by the [`PowerSpectrum`] block
+#[derive(Clone, Debug)]
+pub struct PowerSpectrumFrame {
+    /// Sample rate of the analyzed [`Signal::Samples`]
+    pub sample_rate: f64,
+    /// Most recently announced [`CenterFrequency`] (if any)
+    pub center_frequency: Option<f64>,
+    /// Resolution bandwidth (equivalent noise bandwidth of the window) in
+    /// hertz
+    pub resolution_bandwidth: f64,
+    /// Number of averaged FFT frames
+    pub averages: usize,
+    /// Average power of each frequency bin in decibels with DC in the center
+    ///
+    /// For an even number of bins `n`, the DC bin is at index `n / 2`. A
+    /// value of 0 dB corresponds to a power of `1.0` (i.e. full scale). The
+    /// sum of the (linear) powers of all bins equals the average power of the
+    /// input.
+    pub bins: Arc<[f32]>,
+}
+
+impl PowerSpectrumFrame {
+    /// Frequency in hertz (relative to the center frequency) of bin with
+    /// given index
+    pub fn frequency(&self, index: usize) -> f64 {
+        let n = self.bins.len();
+        (index as f64 - (n / 2) as f64) * self.sample_rate / n as f64
+    }
+    /// Absolute frequency in hertz of bin with given index (if the center
+    /// frequency is known)
+    pub fn absolute_frequency(&self, index: usize) -> Option<f64> {
+        Some(self.center_frequency? + self.frequency(index))
+    }
+    /// Power spectral density in decibels per hertz of bin with given index
+    ///
+    /// As the powers of all bins add up to the total power, the density is
+    /// obtained by dividing the power through the bin spacing (and not through
+    /// the [`resolution_bandwidth`]).
+    ///
+    /// [`resolution_bandwidth`]: Self::resolution_bandwidth
+    pub fn density(&self, index: usize) -> f64 {
+        let bin_width = self.sample_rate / self.bins.len() as f64;
+        self.bins[index] as f64 - 10.0 * bin_width.log10()
+    }
+}
+
+/// Block which estimates the power spectral density using Welch's method
+///
+/// The received samples are split into overlapping windows of `fft_size`
+/// samples, where two subsequent windows share `overlap` samples. The power
+/// spectra of `averages` windows are averaged, and the result is published
+/// as [`PowerSpectrumFrame`], which can be obtained through a
+/// [`watch::Receiver`] returned by [`PowerSpectrum::subscribe`]. Afterwards,
+/// a new average is started.
+///
+/// The average is discarded when the sample rate changes, when an
+/// [interrupting] event or a [`CenterFrequency`] event is received, or when
+/// [`PowerSpectrum::reset`] is called.
+///
+/// [interrupting]: Event::is_interrupt
+pub struct PowerSpectrum<Flt> {
+    receiver_connector: ReceiverConnector<Signal<Complex<Flt>>>,
+    averages: watch::Sender<usize>,
+    reset: watch::Sender<()>,
+    frame: watch::Receiver<PowerSpectrumFrame>,
+}
+
+impl_block_trait! { <Flt> Consumer<Signal<Complex<Flt>>> for PowerSpectrum<Flt> }
+
+impl<Flt> PowerSpectrum<Flt>
+where
+    Flt: Float,
+{
+    /// Create new `PowerSpectrum` block using a [Hann window]
+    ///
+    /// [Hann window]: windowing::Hann
+    pub fn new(fft_size: usize, overlap: usize, averages: usize) -> Self {
+        Self::with_window(fft_size, overlap, averages, windowing::Hann)
+    }
+    /// Create new `PowerSpectrum` block using given window function
+    pub fn with_window<W>(fft_size: usize, overlap: usize, averages: usize, window: W) -> Self
+    where
+        W: Window + Send + 'static,
+    {
+        assert!(fft_size > 0, "FFT size must be positive");
+        assert!(overlap < fft_size, "overlap must be smaller than FFT size");
+        assert!(averages > 0, "number of averages must be positive");
+        let (mut receiver, receiver_connector) = new_receiver::<Signal<Complex<Flt>>>();
+        let (averages_send, mut averages_recv) = watch::channel(averages);
+        let (reset_send, mut reset_recv) = watch::channel(());
+        let (frame_send, frame) = watch::channel(PowerSpectrumFrame {
+            sample_rate: 0.0,
+            center_frequency: None,
+            resolution_bandwidth: 0.0,
+            averages: 0,
+            bins: vec![f32::NEG_INFINITY; fft_size].into(),
+        });
+        let mut window_values: Vec<Flt> = Vec::with_capacity(fft_size);
+        let mut sum: f64 = 0.0;
+        let mut energy: f64 = 0.0;
+        for idx in 0..fft_size {
+            let value = window.relative_value_at(2.0 * (idx as f64 + 0.5) / fft_size as f64 - 1.0);
+            window_values.push(flt!(value));
+            sum += value;
+            energy += value * value;
+        }
+        let enbw_bins = fft_size as f64 * energy / (sum * sum);
+        let scale: Flt = flt!((energy * fft_size as f64).sqrt().recip());
+        for value in window_values.iter_mut() {
+            *value *= scale;
+        }
+        spawn(async move {
+            let mut averages = averages;
+            let mut buffer: Vec<Complex<Flt>> = Vec::with_capacity(2 * fft_size);
+            let mut scratch: Vec<Complex<Flt>> = Vec::with_capacity(fft_size);
+            let mut powers: Vec<Flt> = vec![Flt::zero(); fft_size];
+            let mut accumulated: Vec<f64> = vec![0.0; fft_size];
+            let mut count: usize = 0;
+            let mut prev_sample_rate: Option<f64> = None;
+            let mut center_frequency: Option<f64> = None;
+            loop {
+                let Ok(signal) = receiver.recv().await else { return; };
+                match signal {
+                    Signal::Samples {
+                        sample_rate,
+                        chunk: input_chunk,
+                    } => {
+                        if averages_recv.has_changed().unwrap_or(false) {
+                            averages = *averages_recv.borrow_and_update();
+                        }
+                        if reset_recv.has_changed().unwrap_or(false)
+                            || Some(sample_rate) != prev_sample_rate
+                        {
+                            reset_recv.borrow_and_update();
+                            prev_sample_rate = Some(sample_rate);
+                            buffer.clear();
+                            count = 0;
+                        }
+                        buffer.extend_from_slice(&input_chunk);
+                        while buffer.len() >= fft_size {
+                            if count == 0 {
+                                accumulated.fill(0.0);
+                            }
+                            scratch.clear();
+                            scratch.extend(
+                                buffer[0..fft_size]
+                                    .iter()
+                                    .zip(window_values.iter())
+                                    .map(|(&x, &w)| x * w),
+                            );
+                            fft::forward(&mut scratch);
+                            simd::magnitude_squared(&scratch, &mut powers);
+                            for (acc, power) in accumulated.iter_mut().zip(powers.iter()) {
+                                *acc += power.to_f64().unwrap();
+                            }
+                            count += 1;
+                            if count >= averages {
+                                let mut bins: Vec<f32> = accumulated
+                                    .iter()
+                                    .map(|&x| to_db(x / count as f64) as f32)
+                                    .collect();
+                                bins.rotate_right(fft_size / 2);
+                                frame_send.send_replace(PowerSpectrumFrame {
+                                    sample_rate,
+                                    center_frequency,
+                                    resolution_bandwidth: enbw_bins * sample_rate / fft_size as f64,
+                                    averages: count,
+                                    bins: bins.into(),
+                                });
+                                count = 0;
+                            }
+                            buffer.drain(0..fft_size - overlap);
+                        }
+                    }
+                    Signal::Event(event) => {
+                        if let Some(frequency) = event.as_any().downcast_ref::<CenterFrequency>() {
+                            center_frequency = Some(frequency.0);
+                            buffer.clear();
+                            count = 0;
+                        }
+                        if event.is_interrupt() {
+                            buffer.clear();
+                            count = 0;
+                        }
+                    }
+                }
+            }
+        });
+        Self {
+            receiver_connector,
+            averages: averages_send,
+            reset: reset_send,
+            frame,
+        }
+    }
+    /// Get number of averaged FFT frames
+    pub fn averages(&self) -> usize {
+        *self.averages.borrow()
+    }
+    /// Set number of averaged FFT frames
+    ///
+    /// The new value is used starting with the next received chunk.
+    pub fn set_averages(&self, averages: usize) {
+        assert!(averages > 0, "number of averages must be positive");
+        self.averages.send_replace(averages);
+    }
+    /// Discard the current average and start a new one
+    ///
+    /// The reset takes effect when the next chunk is received.
+    pub fn reset(&self) {
+        self.reset.send_replace(());
+    }
+    /// Get [`watch::Receiver`] of most recently averaged
+    /// [`PowerSpectrumFrame`]
+    pub fn subscribe(&self) -> watch::Receiver<PowerSpectrumFrame> {
+        self.frame.clone()
+    }
+}
+
+/// Strongest carrier found by the [`PeakFinder`] block
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Peak {
+    /// Frequency in hertz (relative to the center frequency)
+    pub frequency: f64,
+    /// Absolute frequency in hertz (if the center frequency is known)
+    pub absolute_frequency: Option<f64>,
+    /// Power of the carrier in decibels, where 0 dB corresponds to a power of
+    /// `1.0` (i.e. full scale)
+    pub power_db: f64,
+}
+
+/// Block which finds the frequency of the strongest carrier
+///
+/// The power spectrum is calculated like with a [`PowerSpectrum`] block
+/// (using a Hann window with 50% overlap), averaging over `averages` FFT
+/// frames. For every averaged spectrum, the strongest bin within the
+/// configured frequency range is searched. Its frequency and power are
+/// refined with parabolic interpolation (of the decibel values) and published
+/// as [`Peak`], which can be obtained through a [`watch::Receiver`] returned
+/// by [`PeakFinder::subscribe`].
+///
+/// If the power of the strongest bin is below the configured minimum power,
+/// or if no bin lies within the frequency range, `None` is published.
+pub struct PeakFinder<Flt> {
+    spectrum: PowerSpectrum<Flt>,
+    range: watch::Sender<Option<(f64, f64)>>,
+    min_power_db: watch::Sender<f64>,
+    peak: watch::Receiver<Option<Peak>>,
+}
+
+impl<Flt> Consumer<Signal<Complex<Flt>>> for PeakFinder<Flt> {
+    fn receiver_connector(&self) -> &ReceiverConnector<Signal<Complex<Flt>>> {
+        self.spectrum.receiver_connector()
+    }
+}
+
+impl<Flt> PeakFinder<Flt>
+where
+    Flt: Float,
+{
+    /// Create new `PeakFinder` with given `fft_size` and number of averaged
+    /// FFT frames
+    ///
+    /// Initially, the whole spectrum is searched and no minimum power is
+    /// required.
+    pub fn new(fft_size: usize, averages: usize) -> Self {
+        assert!(fft_size >= 2, "FFT size must be at least 2");
+        let spectrum = PowerSpectrum::<Flt>::new(fft_size, fft_size / 2, averages);
+        let mut frames = spectrum.subscribe();
+        let (range_send, mut range_recv) = watch::channel::<Option<(f64, f64)>>(None);
+        let (min_power_db_send, mut min_power_db_recv) = watch::channel(f64::NEG_INFINITY);
+        let (peak_send, peak) = watch::channel::<Option<Peak>>(None);
+        spawn(async move {
+            let mut range: Option<(f64, f64)> = None;
+            let mut min_power_db: f64 = f64::NEG_INFINITY;
+            loop {
+                let Ok(()) = frames.changed().await else { return; };
+                let frame = frames.borrow_and_update().clone();
+                if range_recv.has_changed().unwrap_or(false) {
+                    range = *range_recv.borrow_and_update();
+                }
+                if min_power_db_recv.has_changed().unwrap_or(false) {
+                    min_power_db = *min_power_db_recv.borrow_and_update();
+                }
+                let bins = &frame.bins;
+                let in_range = |index: usize| match range {
+                    Some((low, high)) => (low..=high).contains(&frame.frequency(index)),
+                    None => true,
+                };
+                let peak_index = (0..bins.len())
+                    .filter(|&index| in_range(index))
+                    .max_by(|&a, &b| bins[a].total_cmp(&bins[b]));
+                let peak = peak_index.and_then(|index| {
+                    let value = bins[index] as f64;
+                    if value < min_power_db {
+                        return None;
+                    }
+                    let mut offset = 0.0;
+                    let mut peak_db = value;
+                    if index > 0 && index + 1 < bins.len() {
+                        let before = bins[index - 1] as f64;
+                        let after = bins[index + 1] as f64;
+                        let curvature = before - 2.0 * value + after;
+                        if curvature < 0.0 {
+                            offset = 0.5 * (before - after) / curvature;
+                            peak_db = value - 0.25 * (before - after) * offset;
+                        }
+                    }
+                    let bin_width = frame.sample_rate / bins.len() as f64;
+                    let frequency = frame.frequency(index) + offset * bin_width;
+                    Some(Peak {
+                        frequency,
+                        absolute_frequency: frame
+                            .center_frequency
+                            .map(|center_frequency| center_frequency + frequency),
+                        // the power of a carrier is spread over the noise
+                        // bandwidth of the window
+                        power_db: peak_db + to_db(frame.resolution_bandwidth / bin_width),
+                    })
+                });
+                peak_send.send_replace(peak);
+            }
+        });
+        Self {
+            spectrum,
+            range: range_send,
+            min_power_db: min_power_db_send,
+            peak,
+        }
+    }
+    /// Get searched frequency range (relative to the center frequency) in
+    /// hertz
+    pub fn range(&self) -> Option<(f64, f64)> {
+        *self.range.borrow()
+    }
+    /// Set searched frequency range (relative to the center frequency) in
+    /// hertz, or `None` to search the whole spectrum
+    pub fn set_range(&self, range: Option<(f64, f64)>) {
+        self.range.send_replace(range);
+    }
+    /// Get minimum power of a carrier in decibels
+    pub fn min_power_db(&self) -> f64 {
+        *self.min_power_db.borrow()
+    }
+    /// Set minimum power of a carrier in decibels
+    ///
+    /// Use [`f64::NEG_INFINITY`] to disable the power gate.
+    pub fn set_min_power_db(&self, min_power_db: f64) {
+        self.min_power_db.send_replace(min_power_db);
+    }
+    /// Get number of averaged FFT frames
+    pub fn averages(&self) -> usize {
+        self.spectrum.averages()
+    }
+    /// Set number of averaged FFT frames
+    pub fn set_averages(&self, averages: usize) {
+        self.spectrum.set_averages(averages);
+    }
+    /// Get [`watch::Receiver`] of most recently found [`Peak`]
+    pub fn subscribe(&self) -> watch::Receiver<Option<Peak>> {
+        self.peak.clone()
+    }
+}
+
+/// State of the Goertzel algorithm for a single frequency
+#[derive(Clone, Debug)]
+pub(crate) struct GoertzelState<Flt> {
+    coeff: Flt,
+    cos: Flt,
+    sin: Flt,
+    s1: Flt,
+    s2: Flt,
+}
+
+impl<Flt> GoertzelState<Flt>
+where
+    Flt: Float,
+{
+    /// Create state for detecting given `frequency` at given `sample_rate`
+    pub(crate) fn new(frequency: f64, sample_rate: f64) -> Self {
+        use std::f64::consts::TAU;
+        let omega = TAU * frequency / sample_rate;
+        Self {
+            coeff: flt!(2.0 * omega.cos()),
+            cos: flt!(omega.cos()),
+            sin: flt!(omega.sin()),
+            s1: Flt::zero(),
+            s2: Flt::zero(),
+        }
+    }
+    /// Process a single sample
+    pub(crate) fn push(&mut self, sample: Flt) {
+        let s0 = sample + self.coeff * self.s1 - self.s2;
+        self.s2 = self.s1;
+        self.s1 = s0;
+    }
+    /// Squared magnitude of DFT term for processed samples
+    pub(crate) fn power(&self) -> Flt {
+        let re = self.s1 - self.s2 * self.cos;
+        let im = self.s2 * self.sin;
+        re * re + im * im
+    }
+    /// Start new block
+    pub(crate) fn reset(&mut self) {
+        self.s1 = Flt::zero();
+        self.s2 = Flt::zero();
+    }
+}
+
+/// Block detecting a single tone using the Goertzel algorithm
+///
+/// The real part of the received samples is analyzed in blocks of
+/// `block_len` samples. After each block, the amplitude of the tone with the
+/// target frequency is published and can be obtained through a
+/// [`watch::Receiver`] returned by [`Goertzel::subscribe`]. A sinusoid with
+/// amplitude `1.0` (and a frequency matching a bin center) results in a
+/// magnitude of `1.0`.
+pub struct Goertzel<Flt> {
+    receiver_connector: ReceiverConnector<Signal<Complex<Flt>>>,
+    magnitude: watch::Receiver<Flt>,
+}
+
+impl_block_trait! { <Flt> Consumer<Signal<Complex<Flt>>> for Goertzel<Flt> }
+
+impl<Flt> Goertzel<Flt>
+where
+    Flt: Float,
+{
+    /// Create new `Goertzel` block for target `frequency` in hertz and given
+    /// block length
+    pub fn new(frequency: f64, block_len: usize) -> Self {
+        assert!(block_len > 0, "block length must be positive");
+        let (mut receiver, receiver_connector) = new_receiver::<Signal<Complex<Flt>>>();
+        let (magnitude_send, magnitude) = watch::channel(Flt::zero());
+        let scale: Flt = flt!(2.0 / block_len as f64);
+        spawn(async move {
+            let mut previous_sample_rate: Option<f64> = None;
+            let mut state = GoertzelState::<Flt>::new(frequency, 1.0);
+            let mut count: usize = 0;
+            loop {
+                let Ok(signal) = receiver.recv().await else { return; };
+                match signal {
+                    Signal::Samples {
+                        sample_rate,
+                        chunk: input_chunk,
+                    } => {
+                        if previous_sample_rate != Some(sample_rate) {
+                            previous_sample_rate = Some(sample_rate);
+                            state = GoertzelState::new(frequency, sample_rate);
+                            count = 0;
+                        }
+                        for &sample in input_chunk.iter() {
+                            state.push(sample.re);
+                            count += 1;
+                            if count == block_len {
+                                magnitude_send.send_replace(state.power().sqrt() * scale);
+                                state.reset();
+                                count = 0;
+                            }
+                        }
+                    }
+                    Signal::Event(event) => {
+                        if event.is_interrupt() {
+                            state.reset();
+                            count = 0;
+                        }
+                    }
+                }
+            }
+        });
+        Self {
+            receiver_connector,
+            magnitude,
+        }
+    }
+    /// Get [`watch::Receiver`] of most recently calculated magnitude
+    pub fn subscribe(&self) -> watch::Receiver<Flt> {
+        self.magnitude.clone()
+    }
+}
+
+/// Block which measures the signal level and passes the signal unchanged
+///
+/// The power of the received samples is averaged exponentially with a given
+/// time constant. Additionally a peak value is held, which decays with a
+/// given rate (in decibels per second). Both values are published in
+/// decibels relative to full scale (dBFS), where a sample with an absolute
+/// value of `1.0` corresponds to 0 dBFS.
+pub struct PowerMeter<Flt> {
+    receiver_connector: ReceiverConnector<Signal<Complex<Flt>>>,
+    sender_connector: SenderConnector<Signal<Complex<Flt>>>,
+    level: watch::Receiver<f64>,
+    peak: watch::Receiver<f64>,
+}
+
+impl_block_trait! { <Flt> Consumer<Signal<Complex<Flt>>> for PowerMeter<Flt> }
+impl_block_trait! { <Flt> Producer<Signal<Complex<Flt>>> for PowerMeter<Flt> }
+
+impl<Flt> PowerMeter<Flt>
+where
+    Flt: Float,
+{
+    /// Create new `PowerMeter` with given averaging time constant (in seconds)
+    /// and peak decay rate (in decibels per second)
+    pub fn new(time_constant: f64, peak_decay: f64) -> Self {
+        assert!(time_constant > 0.0, "time constant must be positive");
+        let (mut receiver, receiver_connector) = new_receiver::<Signal<Complex<Flt>>>();
+        let (sender, sender_connector) = new_sender::<Signal<Complex<Flt>>>();
+        let (level_send, level) = watch::channel(f64::NEG_INFINITY);
+        let (peak_send, peak) = watch::channel(f64::NEG_INFINITY);
+        spawn(async move {
+            let mut previous_sample_rate: Option<f64> = None;
+            let mut alpha: f64 = 0.0;
+            let mut decay: f64 = 0.0;
+            let mut power: f64 = 0.0;
+            let mut peak_power: f64 = 0.0;
+            let mut powers: Vec<Flt> = Vec::new();
+            loop {
+                let Ok(signal) = receiver.recv().await else { return; };
+                match signal {
+                    Signal::Samples {
+                        sample_rate,
+                        chunk: input_chunk,
+                    } => {
+                        if previous_sample_rate != Some(sample_rate) {
+                            previous_sample_rate = Some(sample_rate);
+                            alpha = 1.0 - (-1.0 / (time_constant * sample_rate)).exp();
+                            decay = from_db(-peak_decay / sample_rate);
+                        }
+                        powers.clear();
+                        powers.resize(input_chunk.len(), Flt::zero());
+                        simd::magnitude_squared(&input_chunk, &mut powers);
+                        for sample_power in powers.iter() {
+                            let sample_power = sample_power.to_f64().unwrap();
+                            power += (sample_power - power) * alpha;
+                            peak_power = (peak_power * decay).max(sample_power);
+                        }
+                        level_send.send_replace(to_db(power));
+                        peak_send.send_replace(to_db(peak_power));
+                        let Ok(()) = sender
+                            .send(Signal::Samples {
+                                sample_rate,
+                                chunk: input_chunk,
+                            })
+                            .await
+                        else { return; };
+                    }
+                    event @ Signal::Event { .. } => {
+                        let Ok(()) = sender.send(event).await else { return; };
+                    }
+                }
+            }
+        });
+        Self {
+            receiver_connector,
+            sender_connector,
+            level,
+            peak,
+        }
+    }
+    /// Get [`watch::Receiver`] of averaged level in dBFS
+    ///
+    /// The value is updated after each processed chunk.
+    pub fn level(&self) -> watch::Receiver<f64> {
+        self.level.clone()
+    }
+    /// Get [`watch::Receiver`] of peak level in dBFS
+    ///
+    /// The value is updated after each processed chunk.
+    pub fn peak(&self) -> watch::Receiver<f64> {
+        self.peak.clone()
+    }
+}
+
+/// Block which measures the actual sample rate (samples per second of wall
+/// clock time) and passes the signal unchanged
+///
+/// The measured rate is averaged over a given window (in seconds) and
+/// compared to the nominal [`sample_rate`] of the received samples, e.g. to
+/// detect clock drift between a receiver and a sound card. The measurement
+/// is restarted when the nominal sample rate changes or an [interrupting]
+/// event is received.
+///
+/// [`sample_rate`]: Signal::Samples::sample_rate
+/// [interrupting]: Event::is_interrupt
+pub struct RateMonitor<T> {
+    receiver_connector: ReceiverConnector<Signal<T>>,
+    sender_connector: SenderConnector<Signal<T>>,
+    window: watch::Sender<f64>,
+    rate: watch::Receiver<f64>,
+    ppm: watch::Receiver<f64>,
+}
+
+impl_block_trait! { <T> Consumer<Signal<T>> for RateMonitor<T> }
+impl_block_trait! { <T> Producer<Signal<T>> for RateMonitor<T> }
+
+impl<T> RateMonitor<T>
+where
+    T: Clone + Send + Sync + 'static,
+{
+    /// Create new `RateMonitor` which averages over given window (in seconds)
+    pub fn new(window: f64) -> Self {
+        assert!(window > 0.0, "window must be positive");
+        let (mut receiver, receiver_connector) = new_receiver::<Signal<T>>();
+        let (sender, sender_connector) = new_sender::<Signal<T>>();
+        let (window_send, mut window_recv) = watch::channel(window);
+        let (rate_send, rate) = watch::channel(f64::NAN);
+        let (ppm_send, ppm) = watch::channel(f64::NAN);
+        spawn(async move {
+            let mut window = Duration::from_secs_f64(window);
+            let mut previous_sample_rate: Option<f64> = None;
+            // arrival time of each chunk and number of samples received before
+            let mut arrivals: VecDeque<(TokioInstant, u64)> = VecDeque::new();
+            let mut count: u64 = 0;
+            loop {
+                let Ok(signal) = receiver.recv().await else { return; };
+                match signal {
+                    Signal::Samples { sample_rate, chunk } => {
+                        let now = TokioInstant::now();
+                        if window_recv.has_changed().unwrap_or(false) {
+                            window = Duration::from_secs_f64(*window_recv.borrow_and_update());
+                        }
+                        if previous_sample_rate != Some(sample_rate) {
+                            previous_sample_rate = Some(sample_rate);
+                            arrivals.clear();
+                        }
+                        arrivals.push_back((now, count));
+                        count += chunk.len() as u64;
+                        while arrivals.len() > 2 && now.duration_since(arrivals[1].0) >= window {
+                            arrivals.pop_front();
+                        }
+                        if arrivals.len() >= 2 {
+                            let (start, start_count) = arrivals[0];
+                            let (end, end_count) = *arrivals.back().unwrap();
+                            let elapsed = end.duration_since(start).as_secs_f64();
+                            if elapsed > 0.0 {
+                                let measured = (end_count - start_count) as f64 / elapsed;
+                                rate_send.send_replace(measured);
+                                ppm_send.send_replace((measured / sample_rate - 1.0) * 1e6);
+                            }
+                        }
+                        let Ok(()) = sender.send(Signal::Samples { sample_rate, chunk }).await
+                        else { return; };
+                    }
+                    Signal::Event(event) => {
+                        if event.is_interrupt() {
+                            arrivals.clear();
+                        }
+                        let Ok(()) = sender.send(Signal::Event(event)).await
+                        else { return; };
+                    }
+                }
+            }
+        });
+        Self {
+            receiver_connector,
+            sender_connector,
+            window: window_send,
+            rate,
+            ppm,
+        }
+    }
+    /// Get averaging window in seconds
+    pub fn window(&self) -> f64 {
+        *self.window.borrow()
+    }
+    /// Set averaging window in seconds
+    pub fn set_window(&self, window: f64) {
+        assert!(window > 0.0, "window must be positive");
+        self.window.send_replace(window);
+    }
+    /// Get [`watch::Receiver`] of the measured sample rate in hertz (`NaN`
+    /// until at least two chunks have been received)
+    ///
+    /// The value is updated after each processed chunk.
+    pub fn rate(&self) -> watch::Receiver<f64> {
+        self.rate.clone()
+    }
+    /// Get [`watch::Receiver`] of the deviation of the measured sample rate
+    /// from the nominal sample rate in parts per million (`NaN` until at
+    /// least two chunks have been received)
+    ///
+    /// The value is updated after each processed chunk.
+    pub fn ppm(&self) -> watch::Receiver<f64> {
+        self.ppm.clone()
+    }
+}
+
+/// Output of a [`Channelizer`] block, which acts as a
+/// [`Producer<Signal<Complex<Flt>>>`] for a single channel
+pub struct ChannelizerOutput<Flt> {
+    sender_connector: SenderConnector<Signal<Complex<Flt>>>,
+    relative_frequency: f64,
+}
+
+impl_block_trait! { <Flt> Producer<Signal<Complex<Flt>>> for ChannelizerOutput<Flt> }
+
+impl<Flt> ChannelizerOutput<Flt> {
+    /// Center frequency of channel relative to the center frequency of the
+    /// input, as a fraction of the input sample rate
+    ///
+    /// The value ranges from `-0.5` (inclusive) to `0.5` (exclusive).
+    pub fn relative_frequency(&self) -> f64 {
+        self.relative_frequency
+    }
+    /// Center frequency of channel in hertz relative to the center frequency
+    /// of the input, for given input sample rate
+    pub fn frequency_offset(&self, sample_rate: f64) -> f64 {
+        self.relative_frequency * sample_rate
+    }
+}
+
+/// Polyphase filter bank which splits a signal into equally spaced channels
+///
+/// An input with sample rate *f* is split into `num_channels` outputs, each
+/// with a sample rate of *f* / `num_channels`. Channel `k` is centered at
+/// *k* · *f* / `num_channels` (where indices in the upper half correspond to
+/// negative frequencies), see [`ChannelizerOutput::frequency_offset`].
+///
+/// The prototype low-pass filter has `num_channels * taps_per_channel` taps
+/// and a cutoff frequency (−6 dB) at half the channel spacing. As the filter
+/// bank is critically sampled, signals near the edge of a channel alias into
+/// the neighboring channel.
+///
+/// All outputs are sent in order, so each output must be connected to a
+/// consumer which keeps receiving (e.g. a [`Blackhole`]).
+///
+/// A received [`CenterFrequency`] event is translated into one event per
+/// output, announcing the center frequency of the respective channel.
+///
+/// [`Blackhole`]: crate::blocks::io::Blackhole
+pub struct Channelizer<Flt> {
+    receiver_connector: ReceiverConnector<Signal<Complex<Flt>>>,
+    outputs: Vec<ChannelizerOutput<Flt>>,
+}
+
+impl_block_trait! { <Flt> Consumer<Signal<Complex<Flt>>> for Channelizer<Flt> }
+
+impl<Flt> Channelizer<Flt>
+where
+    Flt: Float,
+{
+    /// Create new `Channelizer` with given number of channels and taps of the
+    /// prototype filter per channel
+    pub fn new(num_channels: usize, taps_per_channel: usize) -> Self {
+        assert!(num_channels > 0, "number of channels must be positive");
+        assert!(taps_per_channel > 0, "taps per channel must be positive");
+        let num_taps = num_channels * taps_per_channel;
+        let coeffs: Vec<Flt> = design::lowpass(
+            0.5 / num_channels as f64,
+            1.0,
+            num_taps,
+            &windowing::Hamming,
+        );
+        let (mut receiver, receiver_connector) = new_receiver::<Signal<Complex<Flt>>>();
+        let mut senders = Vec::with_capacity(num_channels);
+        let mut outputs = Vec::with_capacity(num_channels);
+        for k in 0..num_channels {
+            let (sender, sender_connector) = new_sender::<Signal<Complex<Flt>>>();
+            senders.push(sender);
+            let mut relative_frequency = k as f64 / num_channels as f64;
+            if relative_frequency >= 0.5 {
+                relative_frequency -= 1.0;
+            }
+            outputs.push(ChannelizerOutput {
+                sender_connector,
+                relative_frequency,
+            });
+        }
+        let relative_frequencies: Vec<f64> = outputs
+            .iter()
+            .map(|output| output.relative_frequency)
+            .collect();
+        spawn(async move {
+            let mut buf_pools: Vec<ChunkBufPool<Complex<Flt>>> =
+                (0..num_channels).map(|_| ChunkBufPool::new()).collect();
+            let initial_history = vec![Complex::from(Flt::zero()); num_taps - num_channels];
+            let mut buffer: Vec<Complex<Flt>> = initial_history.clone();
+            let mut branches: Vec<Complex<Flt>> = Vec::with_capacity(num_channels);
+            let mut center_frequency: Option<f64> = None;
+            let mut announced_sample_rate: Option<f64> = None;
+            loop {
+                let Ok(signal) = receiver.recv().await else { return; };
+                match signal {
+                    Signal::Samples {
+                        sample_rate,
+                        chunk: input_chunk,
+                    } => {
+                        buffer.extend_from_slice(&input_chunk);
+                        let output_len = buffer.len() / num_channels;
+                        let mut output_chunks: Vec<_> = buf_pools
+                            .iter_mut()
+                            .map(|buf_pool| buf_pool.get_with_capacity(output_len))
+                            .collect();
+                        while buffer.len() >= num_taps {
+                            branches.clear();
+                            for p in 0..num_channels {
+                                let mut sum: Complex<Flt> = Complex::from(Flt::zero());
+                                for q in 0..taps_per_channel {
+                                    let n = p + q * num_channels;
+                                    sum += buffer[num_taps - 1 - n] * coeffs[n];
+                                }
+                                branches.push(sum);
+                            }
+                            fft::forward(&mut branches);
+                            for (k, output_chunk) in output_chunks.iter_mut().enumerate() {
+                                output_chunk.push(branches[(num_channels - k) % num_channels]);
+                            }
+                            buffer.drain(0..num_channels);
+                        }
+                        let announce = match center_frequency {
+                            Some(_) => announced_sample_rate != Some(sample_rate),
+                            None => false,
+                        };
+                        announced_sample_rate = Some(sample_rate);
+                        for (k, (sender, output_chunk)) in
+                            senders.iter().zip(output_chunks).enumerate()
+                        {
+                            if announce {
+                                let frequency = center_frequency.unwrap()
+                                    + relative_frequencies[k] * sample_rate;
+                                let Ok(()) = sender
+                                    .send(Signal::new_event(CenterFrequency(frequency)))
+                                    .await
+                                else { return; };
+                            }
+                            if output_chunk.is_empty() {
+                                continue;
+                            }
+                            let Ok(()) = sender
+                                .send(Signal::Samples {
+                                    sample_rate: sample_rate / num_channels as f64,
+                                    chunk: output_chunk.finalize(),
+                                })
+                                .await
+                            else { return; };
+                        }
+                    }
+                    Signal::Event(event) => {
+                        if let Some(frequency) = event.as_any().downcast_ref::<CenterFrequency>() {
+                            center_frequency = Some(frequency.0);
+                            announced_sample_rate = None;
+                            continue;
+                        }
+                        if event.is_interrupt() {
+                            buffer.clear();
+                            buffer.extend_from_slice(&initial_history);
+                        }
+                        for sender in senders.iter() {
+                            let Ok(()) = sender.send(Signal::Event(event.clone())).await
+                            else { return; };
+                        }
+                    }
+                }
+            }
+        });
+        Self {
+            receiver_connector,
+            outputs,
+        }
+    }
+    /// Number of channels
+    pub fn num_channels(&self) -> usize {
+        self.outputs.len()
+    }
+    /// Output for channel with given index
+    pub fn channel(&self, index: usize) -> &ChannelizerOutput<Flt> {
+        &self.outputs[index]
+    }
+    /// Outputs of all channels
+    pub fn channels(&self) -> &[ChannelizerOutput<Flt>] {
+        &self.outputs
+    }
+}
+
+/// Edge on which a [`Trigger`] fires
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum TriggerEdge {
+    /// Power rises above threshold
+    Rising,
+    /// Power falls below threshold
+    Falling,
+    /// Power crosses threshold in either direction
+    Either,
+}
+
+/// Block which detects when the signal power crosses a threshold and passes
+/// the signal unchanged
+///
+/// The power is estimated by smoothing the squared magnitude of the samples
+/// with a time constant of 1 millisecond and compared with the threshold in
+/// decibels (where 0 dB corresponds to a power of `1.0`), similar to the
+/// trigger of an oscilloscope. When the trigger fires, a [`Triggered`] event
+/// is inserted into the stream right before the sample where the threshold
+/// was crossed (splitting the chunk if necessary), and the event is also sent
+/// to all [subscribers]. After firing, further crossings are ignored for the
+/// duration of the hold-off time.
+///
+/// Combined with a [`RingTap`], this allows event-driven recording of the
+/// signal before and after the trigger.
+///
+/// [subscribers]: Trigger::subscribe
+/// [`RingTap`]: crate::blocks::buffering::RingTap
+pub struct Trigger<Flt> {
+    receiver_connector: ReceiverConnector<Signal<Complex<Flt>>>,
+    sender_connector: SenderConnector<Signal<Complex<Flt>>>,
+    threshold_db: watch::Sender<f64>,
+    edge: watch::Sender<TriggerEdge>,
+    hold_off: watch::Sender<f64>,
+    triggered: broadcast::Sender<Triggered>,
+}
+
+impl_block_trait! { <Flt> Consumer<Signal<Complex<Flt>>> for Trigger<Flt> }
+impl_block_trait! { <Flt> Producer<Signal<Complex<Flt>>> for Trigger<Flt> }
+
+impl<Flt> Trigger<Flt>
+where
+    Flt: Float,
+{
+    /// Create new `Trigger` with given threshold in decibels, [`TriggerEdge`],
+    /// and hold-off time (i.e. minimum time before re-arming) in seconds
+    pub fn new(threshold_db: f64, edge: TriggerEdge, hold_off: f64) -> Self {
+        assert!(hold_off >= 0.0, "hold-off time must not be negative");
+        let (mut receiver, receiver_connector) = new_receiver::<Signal<Complex<Flt>>>();
+        let (sender, sender_connector) = new_sender::<Signal<Complex<Flt>>>();
+        let (threshold_db_send, mut threshold_db_recv) = watch::channel(threshold_db);
+        let (edge_send, mut edge_recv) = watch::channel(edge);
+        let (hold_off_send, mut hold_off_recv) = watch::channel(hold_off);
+        let (triggered_send, _) = broadcast::channel::<Triggered>(64);
+        let triggered_send_clone = triggered_send.clone();
+        spawn(async move {
+            let mut threshold: f64 = from_db(threshold_db);
+            let mut edge = edge;
+            let mut hold_off = hold_off;
+            let mut previous_sample_rate: Option<f64> = None;
+            let mut alpha: f64 = 0.0;
+            let mut power: f64 = 0.0;
+            let mut above: Option<bool> = None;
+            let mut hold_off_remaining: usize = 0;
+            let mut powers: Vec<Flt> = Vec::new();
+            loop {
+                let Ok(signal) = receiver.recv().await else { return; };
+                match signal {
+                    Signal::Samples {
+                        sample_rate,
+                        chunk: input_chunk,
+                    } => {
+                        if threshold_db_recv.has_changed().unwrap_or(false) {
+                            threshold = from_db(*threshold_db_recv.borrow_and_update());
+                        }
+                        if edge_recv.has_changed().unwrap_or(false) {
+                            edge = *edge_recv.borrow_and_update();
+                        }
+                        if hold_off_recv.has_changed().unwrap_or(false) {
+                            hold_off = *hold_off_recv.borrow_and_update();
+                        }
+                        if previous_sample_rate != Some(sample_rate) {
+                            previous_sample_rate = Some(sample_rate);
+                            alpha = 1.0 - (-1.0 / (1e-3 * sample_rate)).exp();
+                        }
+                        let hold_off_len = (hold_off * sample_rate).round() as usize;
+                        powers.clear();
+                        powers.resize(input_chunk.len(), Flt::zero());
+                        simd::magnitude_squared(&input_chunk, &mut powers);
+                        let mut sent: usize = 0;
+                        for (index, sample_power) in powers.iter().enumerate() {
+                            power += (sample_power.to_f64().unwrap() - power) * alpha;
+                            let is_above = power > threshold;
+                            let was_above = above.replace(is_above);
+                            if hold_off_remaining > 0 {
+                                hold_off_remaining -= 1;
+                                continue;
+                            }
+                            let crossed = match (was_above, is_above) {
+                                (Some(false), true) => Some(TriggerEdge::Rising),
+                                (Some(true), false) => Some(TriggerEdge::Falling),
+                                _ => None,
+                            };
+                            let Some(crossed) = crossed else { continue; };
+                            if edge != TriggerEdge::Either && edge != crossed {
+                                continue;
+                            }
+                            hold_off_remaining = hold_off_len;
+                            if index > sent {
+                                let Ok(()) = sender
+                                    .send(Signal::Samples {
+                                        sample_rate,
+                                        chunk: input_chunk.slice(sent..index),
+                                    })
+                                    .await
+                                else { return; };
+                                sent = index;
+                            }
+                            let triggered = Triggered {
+                                edge: crossed,
+                                power_db: to_db(power),
+                            };
+                            triggered_send_clone.send(triggered.clone()).ok();
+                            let Ok(()) = sender.send(Signal::new_event(triggered)).await
+                            else { return; };
+                        }
+                        let chunk = match sent {
+                            0 => input_chunk,
+                            _ => input_chunk.slice(sent..),
+                        };
+                        let Ok(()) = sender.send(Signal::Samples { sample_rate, chunk }).await
+                        else { return; };
+                    }
+                    Signal::Event(event) => {
+                        if event.is_interrupt() {
+                            above = None;
+                        }
+                        let Ok(()) = sender.send(Signal::Event(event)).await
+                        else { return; };
+                    }
+                }
+            }
+        });
+        Self {
+            receiver_connector,
+            sender_connector,
+            threshold_db: threshold_db_send,
+            edge: edge_send,
+            hold_off: hold_off_send,
+            triggered: triggered_send,
+        }
+    }
+    /// Get threshold in decibels
+    pub fn threshold_db(&self) -> f64 {
+        *self.threshold_db.borrow()
+    }
+    /// Set threshold in decibels
+    pub fn set_threshold_db(&self, threshold_db: f64) {
+        self.threshold_db.send_replace(threshold_db);
+    }
+    /// Get edge on which the trigger fires
+    pub fn edge(&self) -> TriggerEdge {
+        *self.edge.borrow()
+    }
+    /// Set edge on which the trigger fires
+    pub fn set_edge(&self, edge: TriggerEdge) {
+        self.edge.send_replace(edge);
+    }
+    /// Get hold-off time in seconds
+    pub fn hold_off(&self) -> f64 {
+        *self.hold_off.borrow()
+    }
+    /// Set hold-off time in seconds
+    pub fn set_hold_off(&self, hold_off: f64) {
+        assert!(hold_off >= 0.0, "hold-off time must not be negative");
+        self.hold_off.send_replace(hold_off);
+    }
+    /// Get [`broadcast::Receiver`] which receives a [`Triggered`] value
+    /// whenever the trigger fires
+    pub fn subscribe(&self) -> broadcast::Receiver<Triggered> {
+        self.triggered.subscribe()
+    }
+}
+
+/// Templates up to this length are correlated directly instead of using an FFT
+const CORRELATOR_DIRECT_MAX_LEN: usize = 32;
+
+/// Block which correlates the signal with a known template and reports peaks
+/// of the normalized correlation
+///
+/// The signal is passed unchanged. For each position `n`, the normalized
+/// correlation is calculated as
+/// `|Σ x[n+k]·conj(t[k])| / sqrt(Σ |x[n+k]|² · Σ |t[k]|²)`, which is a value
+/// between `0.0` and `1.0`. Where it exceeds the threshold, the position with
+/// the largest value (within the length of the template) is reported as a
+/// [`CorrelationPeak`] both as an event in the stream and to all
+/// [subscribers]. Because the search for the largest value must look ahead,
+/// the event is inserted into the stream after the matching segment, i.e.
+/// about twice the length of the template after
+/// [`CorrelationPeak::position`].
+///
+/// Long templates are correlated using an FFT.
+///
+/// Upon [interruption], the history is cleared and a pending peak is dropped.
+///
+/// [subscribers]: Correlator::subscribe
+/// [interruption]: Event::is_interrupt
+pub struct Correlator<Flt> {
+    receiver_connector: ReceiverConnector<Signal<Complex<Flt>>>,
+    sender_connector: SenderConnector<Signal<Complex<Flt>>>,
+    threshold: watch::Sender<f64>,
+    peaks: broadcast::Sender<CorrelationPeak>,
+}
+
+impl_block_trait! { <Flt> Consumer<Signal<Complex<Flt>>> for Correlator<Flt> }
+impl_block_trait! { <Flt> Producer<Signal<Complex<Flt>>> for Correlator<Flt> }
+
+impl<Flt> Correlator<Flt>
+where
+    Flt: Float,
+{
+    /// Create new `Correlator` with given template and threshold (between
+    /// `0.0` and `1.0`) for the normalized correlation
+    pub fn new(template: Arc<[Complex<Flt>]>, threshold: f64) -> Self {
+        assert!(!template.is_empty(), "template must not be empty");
+        let template_len = template.len();
+        let template_energy: f64 = template
+            .iter()
+            .map(|x| x.norm_sqr().to_f64().unwrap())
+            .sum();
+        assert!(template_energy > 0.0, "template must not be zero");
+        let (mut receiver, receiver_connector) = new_receiver::<Signal<Complex<Flt>>>();
+        let (sender, sender_connector) = new_sender::<Signal<Complex<Flt>>>();
+        let (threshold_send, mut threshold_recv) = watch::channel(threshold);
+        let (peaks_send, _) = broadcast::channel::<CorrelationPeak>(64);
+        let peaks_send_clone = peaks_send.clone();
+        spawn(async move {
+            let mut threshold = threshold;
+            let history_len = template_len - 1;
+            let mut extended: Vec<Complex<Flt>> = vec![Complex::from(Flt::zero()); history_len];
+            let mut correlation: Vec<Complex<Flt>> = Vec::new();
+            let mut response: Vec<Complex<Flt>> = Vec::new();
+            let mut segment: Vec<Complex<Flt>> = Vec::new();
+            let mut position: u64 = 0;
+            let mut valid_from: u64 = history_len as u64;
+            let mut candidate: Option<CorrelationPeak> = None;
+            loop {
+                let Ok(signal) = receiver.recv().await else { return; };
+                match signal {
+                    Signal::Samples {
+                        sample_rate,
+                        chunk: input_chunk,
+                    } => {
+                        if threshold_recv.has_changed().unwrap_or(false) {
+                            threshold = *threshold_recv.borrow_and_update();
+                        }
+                        extended.truncate(history_len);
+                        extended.extend_from_slice(&input_chunk);
+                        correlation.clear();
+                        if template_len <= CORRELATOR_DIRECT_MAX_LEN {
+                            correlation.extend(extended.windows(template_len).map(|window| {
+                                window
+                                    .iter()
+                                    .zip(template.iter())
+                                    .fold(Complex::from(Flt::zero()), |acc, (x, t)| {
+                                        acc + *x * t.conj()
+                                    })
+                            }));
+                        } else {
+                            let fft_size = extended
+                                .len()
+                                .next_power_of_two()
+                                .max((2 * template_len).next_power_of_two())
+                                .min((4 * template_len).next_power_of_two());
+                            if response.len() != fft_size {
+                                let scale: Flt = flt!(fft_size).recip();
+                                response.clear();
+                                response.extend(template.iter().rev().map(|t| t.conj() * scale));
+                                response.resize(fft_size, Complex::from(Flt::zero()));
+                                fft::forward(&mut response);
+                            }
+                            let step = fft_size - history_len;
+                            for start in (0..input_chunk.len()).step_by(step) {
+                                let end = (start + fft_size).min(extended.len());
+                                segment.clear();
+                                segment.extend_from_slice(&extended[start..end]);
+                                segment.resize(fft_size, Complex::from(Flt::zero()));
+                                fft::forward(&mut segment);
+                                for (x, &h) in segment.iter_mut().zip(response.iter()) {
+                                    *x *= h;
+                                }
+                                fft::inverse(&mut segment);
+                                correlation.extend_from_slice(&segment[history_len..end - start]);
+                            }
+                        }
+                        let mut energy: f64 = extended[0..history_len]
+                            .iter()
+                            .map(|x| x.norm_sqr().to_f64().unwrap())
+                            .sum();
+                        let mut sent: usize = 0;
+                        for (index, value) in correlation.iter().enumerate() {
+                            energy += extended[index + history_len].norm_sqr().to_f64().unwrap();
+                            let magnitude = if energy > 0.0 {
+                                value.norm().to_f64().unwrap() / (energy * template_energy).sqrt()
+                            } else {
+                                0.0
+                            };
+                            energy -= extended[index].norm_sqr().to_f64().unwrap();
+                            let current = position + index as u64;
+                            if current >= valid_from
+                                && magnitude >= threshold
+                                && !matches!(&candidate, Some(peak) if peak.magnitude >= magnitude)
+                            {
+                                candidate = Some(CorrelationPeak {
+                                    position: current - history_len as u64,
+                                    magnitude,
+                                    phase: value.arg().to_f64().unwrap(),
+                                });
+                            }
+                            let Some(peak) = candidate.as_ref() else { continue; };
+                            if current - peak.position < 2 * template_len as u64 - 1 {
+                                continue;
+                            }
+                            let peak = candidate.take().unwrap();
+                            if index + 1 > sent {
+                                let Ok(()) = sender
+                                    .send(Signal::Samples {
+                                        sample_rate,
+                                        chunk: input_chunk.slice(sent..index + 1),
+                                    })
+                                    .await
+                                else { return; };
+                                sent = index + 1;
+                            }
+                            peaks_send_clone.send(peak.clone()).ok();
+                            let Ok(()) = sender.send(Signal::new_event(peak)).await
+                            else { return; };
+                        }
+                        position += input_chunk.len() as u64;
+                        extended.drain(0..extended.len() - history_len);
+                        if sent < input_chunk.len() {
+                            let chunk = match sent {
+                                0 => input_chunk,
+                                _ => input_chunk.slice(sent..),
+                            };
+                            let Ok(()) = sender.send(Signal::Samples { sample_rate, chunk }).await
+                            else { return; };
+                        }
+                    }
+                    Signal::Event(event) => {
+                        if event.is_interrupt() {
+                            for sample in extended.iter_mut() {
+                                *sample = Complex::from(Flt::zero());
+                            }
+                            valid_from = position + history_len as u64;
+                            candidate = None;
+                        }
+                        let Ok(()) = sender.send(Signal::Event(event)).await
+                        else { return; };
+                    }
+                }
+            }
+        });
+        Self {
+            receiver_connector,
+            sender_connector,
+            threshold: threshold_send,
+            peaks: peaks_send,
+        }
+    }
+    /// Get threshold for the normalized correlation
+    pub fn threshold(&self) -> f64 {
+        *self.threshold.borrow()
+    }
+    /// Set threshold for the normalized correlation
+    pub fn set_threshold(&self, threshold: f64) {
+        self.threshold.send_replace(threshold);
+    }
+    /// Get [`broadcast::Receiver`] which receives a [`CorrelationPeak`]
+    /// whenever a peak has been found
+    pub fn subscribe(&self) -> broadcast::Receiver<CorrelationPeak> {
+        self.peaks.subscribe()
+    }
+}
+
+/// Time difference of arrival estimated by a [`Tdoa`] block
+#[derive(Clone, Copy, Debug)]
+pub struct TdoaEstimate {
+    /// Delay of the second input relative to the first input in samples
+    /// (positive if the signal arrives later at the second input)
+    pub delay_samples: f64,
+    /// Delay of the second input relative to the first input in seconds
+    pub delay_seconds: f64,
+    /// Normalized magnitude of the correlation peak (between `0.0` and
+    /// `1.0`)
+    pub correlation: f64,
+}
+
+/// Input of a [`Tdoa`] block, which acts as a
+/// [`Consumer<Signal<Complex<Flt>>>`]
+pub struct TdoaInput<Flt> {
+    receiver_connector: ReceiverConnector<Signal<Complex<Flt>>>,
+}
+
+impl_block_trait! { <Flt> Consumer<Signal<Complex<Flt>>> for TdoaInput<Flt> }
+
+/// Block which estimates the time difference of arrival (TDOA) between two
+/// synchronized inputs
+///
+/// Both inputs must be sample-aligned, i.e. their first samples must have
+/// been taken at the same time (e.g. the outputs of a `SoapySdrRxMulti`
+/// or captures of devices with synchronized clocks). Chunks of the inputs
+/// do not need to have the same length, as samples are buffered until a
+/// window of the configured length is available on both inputs.
+///
+/// For each window, the cross-correlation of the inputs is calculated (using
+/// an FFT) for lags up to half the window length. The position of the peak
+/// is refined with parabolic interpolation and published as a
+/// [`TdoaEstimate`]. The sample rate of the first input is used to convert
+/// the delay to seconds.
+///
+/// Upon [interruption] of either input, the buffered samples of both inputs
+/// are discarded.
+///
+/// [interruption]: Event::is_interrupt
+pub struct Tdoa<Flt> {
+    inputs: [TdoaInput<Flt>; 2],
+    window_len: watch::Sender<usize>,
+    estimate: watch::Receiver<Option<TdoaEstimate>>,
+}
+
+impl<Flt> Tdoa<Flt>
+where
+    Flt: Float,
+{
+    /// Create new `Tdoa` block correlating windows of `window_len` samples
+    pub fn new(window_len: usize) -> Self {
+        assert!(window_len >= 2, "window length must be at least 2");
+        let (window_len_send, mut window_len_recv) = watch::channel(window_len);
+        let (estimate_send, estimate) = watch::channel(None);
+        let (forward_send, mut forward_recv) =
+            mpsc::channel::<(usize, Signal<Complex<Flt>>)>(1);
+        let inputs = [0, 1].map(|index| {
+            let (mut receiver, receiver_connector) = new_receiver::<Signal<Complex<Flt>>>();
+            let forward_send = forward_send.clone();
+            spawn(async move {
+                loop {
+                    let Ok(signal) = receiver.recv().await else { return; };
+                    let Ok(()) = forward_send.send((index, signal)).await else { return; };
+                }
+            });
+            TdoaInput { receiver_connector }
+        });
+        spawn(async move {
+            let mut window_len = window_len;
+            let mut sample_rate: Option<f64> = None;
+            let mut buffers: [VecDeque<Complex<Flt>>; 2] = Default::default();
+            let mut spectra: [Vec<Complex<Flt>>; 2] = Default::default();
+            loop {
+                let Some((index, signal)) = forward_recv.recv().await else { return; };
+                match signal {
+                    Signal::Samples {
+                        sample_rate: input_sample_rate,
+                        chunk: input_chunk,
+                    } => {
+                        if index == 0 {
+                            sample_rate = Some(input_sample_rate);
+                        }
+                        buffers[index].extend(input_chunk.iter().copied());
+                        if window_len_recv.has_changed().unwrap_or(false) {
+                            window_len = *window_len_recv.borrow_and_update();
+                        }
+                        while buffers.iter().all(|buffer| buffer.len() >= window_len) {
+                            let fft_size = (2 * window_len).next_power_of_two();
+                            let mut energies = [0.0; 2];
+                            for ((spectrum, buffer), energy) in spectra
+                                .iter_mut()
+                                .zip(buffers.iter_mut())
+                                .zip(energies.iter_mut())
+                            {
+                                spectrum.clear();
+                                spectrum.extend(buffer.drain(0..window_len));
+                                *energy = spectrum
+                                    .iter()
+                                    .map(|x| x.norm_sqr().to_f64().unwrap())
+                                    .sum();
+                                spectrum.resize(fft_size, Complex::from(Flt::zero()));
+                                fft::forward(spectrum);
+                            }
+                            let [first, second] = &mut spectra;
+                            for (y, x) in second.iter_mut().zip(first.iter()) {
+                                *y *= x.conj();
+                            }
+                            fft::inverse(second);
+                            let max_lag = (window_len / 2) as isize;
+                            let magnitude = |lag: isize| -> f64 {
+                                second[lag.rem_euclid(fft_size as isize) as usize]
+                                    .norm()
+                                    .to_f64()
+                                    .unwrap()
+                            };
+                            let mut peak_lag: isize = 0;
+                            let mut peak: f64 = magnitude(0);
+                            for lag in -max_lag..=max_lag {
+                                let value = magnitude(lag);
+                                if value > peak {
+                                    peak_lag = lag;
+                                    peak = value;
+                                }
+                            }
+                            let mut offset = 0.0;
+                            if peak_lag.abs() < max_lag {
+                                let before = magnitude(peak_lag - 1);
+                                let after = magnitude(peak_lag + 1);
+                                let curvature = before - 2.0 * peak + after;
+                                if curvature < 0.0 {
+                                    offset = 0.5 * (before - after) / curvature;
+                                }
+                            }
+                            let energy = energies[0] * energies[1];
+                            let correlation = match energy > 0.0 {
+                                true => peak / fft_size as f64 / energy.sqrt(),
+                                false => 0.0,
+                            };
+                            let delay_samples = peak_lag as f64 + offset;
+                            estimate_send.send_replace(Some(TdoaEstimate {
+                                delay_samples,
+                                delay_seconds: delay_samples / sample_rate.unwrap_or(f64::NAN),
+                                correlation,
+                            }));
+                        }
+                    }
+                    Signal::Event(event) => {
+                        if event.is_interrupt() {
+                            for buffer in buffers.iter_mut() {
+                                buffer.clear();
+                            }
+                        }
+                    }
+                }
+            }
+        });
+        Self {
+            inputs,
+            window_len: window_len_send,
+            estimate,
+        }
+    }
+    /// Input with given index (`0` or `1`)
+    pub fn input(&self, index: usize) -> &TdoaInput<Flt> {
+        &self.inputs[index]
+    }
+    /// Both inputs
+    pub fn inputs(&self) -> &[TdoaInput<Flt>] {
+        &self.inputs
+    }
+    /// Get length of correlation window in samples
+    pub fn window_len(&self) -> usize {
+        *self.window_len.borrow()
+    }
+    /// Set length of correlation window in samples
+    pub fn set_window_len(&self, window_len: usize) {
+        assert!(window_len >= 2, "window length must be at least 2");
+        self.window_len.send_replace(window_len);
+    }
+    /// Get [`watch::Receiver`] of the most recent estimate (or `None` if no
+    /// window has been correlated yet)
+    pub fn estimate(&self) -> watch::Receiver<Option<TdoaEstimate>> {
+        self.estimate.clone()
+    }
+}
+
+/// Ideal constellation used by [`ConstellationSink`] for calculating the
+/// error vector magnitude
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Constellation {
+    /// Binary phase-shift keying with points on the real axis
+    Bpsk,
+    /// Quadrature phase-shift keying with points at 45°, 135°, 225°, and 315°
+    Qpsk,
+    /// 16-ary quadrature amplitude modulation
+    Qam16,
+}
+
+impl Constellation {
+    /// Nearest ideal point, where the constellation is scaled to unit
+    /// average power
+    pub fn nearest(self, point: Complex<f64>) -> Complex<f64> {
+        use std::f64::consts::FRAC_1_SQRT_2;
+        let slice = |x: f64, levels: &[f64]| -> f64 {
+            levels
+                .iter()
+                .copied()
+                .min_by(|a, b| (a - x).abs().total_cmp(&(b - x).abs()))
+                .unwrap()
+        };
+        match self {
+            Constellation::Bpsk => Complex::new(slice(point.re, &[-1.0, 1.0]), 0.0),
+            Constellation::Qpsk => {
+                let levels = [-FRAC_1_SQRT_2, FRAC_1_SQRT_2];
+                Complex::new(slice(point.re, &levels), slice(point.im, &levels))
+            }
+            Constellation::Qam16 => {
+                let scale = 10.0f64.sqrt().recip();
+                let levels = [-3.0 * scale, -scale, scale, 3.0 * scale];
+                Complex::new(slice(point.re, &levels), slice(point.im, &levels))
+            }
+        }
+    }
+}
+
+/// Block which keeps the most recent symbols for plotting a constellation
+/// (I/Q scatter) diagram and estimates the error vector magnitude (EVM)
+///
+/// The input is expected to carry one sample per symbol (e.g. the output of a
+/// [`SymbolSync`]) with the carrier phase already recovered (see
+/// [`CostasLoop`]). The most recent points are published through a
+/// [`watch::Receiver`] returned by [`ConstellationSink::points`], oldest
+/// first.
+///
+/// For the EVM, the recent points are normalized to unit average power and
+/// compared with the nearest point of the selected ideal [`Constellation`].
+/// The EVM is the RMS magnitude of the error vectors, which is `0.0` for a
+/// perfect signal (multiply by 100 for percent).
+///
+/// [`SymbolSync`]: crate::blocks::modulation::SymbolSync
+/// [`CostasLoop`]: crate::blocks::transform::CostasLoop
+pub struct ConstellationSink<Flt> {
+    receiver_connector: ReceiverConnector<Signal<Complex<Flt>>>,
+    constellation: watch::Sender<Constellation>,
+    points: watch::Receiver<Arc<[Complex<Flt>]>>,
+    evm: watch::Receiver<f64>,
+}
+
+impl_block_trait! { <Flt> Consumer<Signal<Complex<Flt>>> for ConstellationSink<Flt> }
+
+impl<Flt> ConstellationSink<Flt>
+where
+    Flt: Float,
+{
+    /// Create new `ConstellationSink` keeping `num_points` points and using
+    /// the given [`Constellation`] for the EVM
+    pub fn new(num_points: usize, constellation: Constellation) -> Self {
+        assert!(num_points > 0, "number of points must be positive");
+        let (mut receiver, receiver_connector) = new_receiver::<Signal<Complex<Flt>>>();
+        let (constellation_send, mut constellation_recv) = watch::channel(constellation);
+        let (points_send, points) = watch::channel::<Arc<[Complex<Flt>]>>(Vec::new().into());
+        let (evm_send, evm) = watch::channel(f64::NAN);
+        spawn(async move {
+            let mut constellation = constellation;
+            let mut history: VecDeque<Complex<Flt>> = VecDeque::with_capacity(num_points);
+            loop {
+                let Ok(signal) = receiver.recv().await else { return; };
+                match signal {
+                    Signal::Samples {
+                        chunk: input_chunk, ..
+                    } => {
+                        if constellation_recv.has_changed().unwrap_or(false) {
+                            constellation = *constellation_recv.borrow_and_update();
+                        }
+                        let skip = input_chunk.len().saturating_sub(num_points);
+                        for &point in input_chunk[skip..].iter() {
+                            if history.len() == num_points {
+                                history.pop_front();
+                            }
+                            history.push_back(point);
+                        }
+                        let points: Vec<Complex<f64>> = history
+                            .iter()
+                            .map(|x| Complex::new(x.re.to_f64().unwrap(), x.im.to_f64().unwrap()))
+                            .collect();
+                        let power = points.iter().map(|x| x.norm_sqr()).sum::<f64>()
+                            / points.len().max(1) as f64;
+                        if power > 0.0 {
+                            let scale = power.sqrt().recip();
+                            let error_power = points
+                                .iter()
+                                .map(|&x| {
+                                    let x = x * scale;
+                                    (x - constellation.nearest(x)).norm_sqr()
+                                })
+                                .sum::<f64>()
+                                / points.len() as f64;
+                            evm_send.send_replace(error_power.sqrt());
+                        }
+                        points_send.send_replace(history.iter().copied().collect());
+                    }
+                    Signal::Event(_) => (),
+                }
+            }
+        });
+        Self {
+            receiver_connector,
+            constellation: constellation_send,
+            points,
+            evm,
+        }
+    }
+    /// Get ideal [`Constellation`] used for the EVM
+    pub fn constellation(&self) -> Constellation {
+        *self.constellation.borrow()
+    }
+    /// Set ideal [`Constellation`] used for the EVM
+    pub fn set_constellation(&self, constellation: Constellation) {
+        self.constellation.send_replace(constellation);
+    }
+    /// Get [`watch::Receiver`] of the most recent points (oldest first)
+    ///
+    /// The value is updated after each processed chunk.
+    pub fn points(&self) -> watch::Receiver<Arc<[Complex<Flt>]>> {
+        self.points.clone()
+    }
+    /// Get [`watch::Receiver`] of the estimated error vector magnitude (RMS,
+    /// relative to the average power)
+    ///
+    /// The value is updated after each processed chunk and is NaN until a
+    /// non-zero signal has been received.
+    pub fn evm(&self) -> watch::Receiver<f64> {
+        self.evm.clone()
+    }
+}
+
+/// Value of the samples which is analyzed by a [`Histogram`]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum HistogramValue {
+    /// Real part (for real-valued signals)
+    Real,
+    /// Magnitude (absolute value)
+    Magnitude,
+}
+
+/// Histogram and statistics published by the [`Histogram`] block
+#[derive(Clone, Debug)]
+pub struct HistogramFrame {
+    /// Lower and upper limit of the values counted in [`counts`]
+    ///
+    /// [`counts`]: Self::counts
+    pub range: (f64, f64),
+    /// Number of values in each of the equally wide bins covering the range
+    pub counts: Arc<[usize]>,
+    /// Number of values below the range
+    pub below: usize,
+    /// Number of values above the range
+    pub above: usize,
+    /// Total number of values (including those outside the range)
+    pub count: usize,
+    /// Mean value
+    pub mean: f64,
+    /// Variance (mean squared deviation from the mean)
+    pub variance: f64,
+    /// Smallest value
+    pub min: f64,
+    /// Largest value
+    pub max: f64,
+    /// Kurtosis (fourth standardized moment), which is `3.0` for a normal
+    /// distribution and `1.5` for a sinusoid
+    pub kurtosis: f64,
+}
+
+impl HistogramFrame {
+    /// Value at the center of the bin with given index
+    pub fn bin_center(&self, index: usize) -> f64 {
+        let (low, high) = self.range;
+        low + (index as f64 + 0.5) * (high - low) / self.counts.len() as f64
+    }
+}
+
+/// Block which builds a histogram of the most recent sample values and
+/// calculates their statistics
+///
+/// Either the real part or the magnitude of the last `window_len` received
+/// samples is analyzed (see [`HistogramValue`]). The resulting
+/// [`HistogramFrame`] can be obtained through a [`watch::Receiver`] returned
+/// by [`Histogram::subscribe`]. The amplitude distribution helps to
+/// characterize a signal, e.g. the magnitude of Gaussian noise follows a
+/// Rayleigh distribution while a carrier has a constant magnitude.
+///
+/// An [interrupting] event clears the window.
+///
+/// [interrupting]: Event::is_interrupt
+pub struct Histogram<Flt> {
+    receiver_connector: ReceiverConnector<Signal<Complex<Flt>>>,
+    value: watch::Sender<HistogramValue>,
+    frame: watch::Receiver<HistogramFrame>,
+}
+
+impl_block_trait! { <Flt> Consumer<Signal<Complex<Flt>>> for Histogram<Flt> }
+
+impl<Flt> Histogram<Flt>
+where
+    Flt: Float,
+{
+    /// Create new `Histogram` with `num_bins` bins covering the `range` from
+    /// a lower to an upper limit, analyzing the last `window_len` values
+    pub fn new(
+        num_bins: usize,
+        range: (f64, f64),
+        window_len: usize,
+        value: HistogramValue,
+    ) -> Self {
+        assert!(num_bins > 0, "number of bins must be positive");
+        assert!(range.1 > range.0, "upper limit must exceed lower limit");
+        assert!(window_len > 0, "window length must be positive");
+        let (mut receiver, receiver_connector) = new_receiver::<Signal<Complex<Flt>>>();
+        let (value_send, mut value_recv) = watch::channel(value);
+        let (frame_send, frame) = watch::channel(Self::evaluate(num_bins, range, &VecDeque::new()));
+        spawn(async move {
+            let mut value = value;
+            let mut history: VecDeque<f64> = VecDeque::with_capacity(window_len);
+            loop {
+                let Ok(signal) = receiver.recv().await else { return; };
+                match signal {
+                    Signal::Samples {
+                        chunk: input_chunk, ..
+                    } => {
+                        if value_recv.has_changed().unwrap_or(false) {
+                            value = *value_recv.borrow_and_update();
+                            history.clear();
+                        }
+                        let skip = input_chunk.len().saturating_sub(window_len);
+                        for &sample in input_chunk[skip..].iter() {
+                            if history.len() == window_len {
+                                history.pop_front();
+                            }
+                            history.push_back(match value {
+                                HistogramValue::Real => sample.re.to_f64().unwrap(),
+                                HistogramValue::Magnitude => sample.norm().to_f64().unwrap(),
+                            });
+                        }
+                        frame_send.send_replace(Self::evaluate(num_bins, range, &history));
+                    }
+                    Signal::Event(event) => {
+                        if event.is_interrupt() {
+                            history.clear();
+                        }
+                    }
+                }
+            }
+        });
+        Self {
+            receiver_connector,
+            value: value_send,
+            frame,
+        }
+    }
+    fn evaluate(num_bins: usize, range: (f64, f64), values: &VecDeque<f64>) -> HistogramFrame {
+        let (low, high) = range;
+        let mut counts = vec![0; num_bins];
+        let (mut below, mut above) = (0, 0);
+        let (mut min, mut max) = (f64::NAN, f64::NAN);
+        for &x in values.iter() {
+            min = min.min(x);
+            max = max.max(x);
+            if x < low {
+                below += 1;
+            } else if x > high {
+                above += 1;
+            } else {
+                let index = ((x - low) / (high - low) * num_bins as f64) as usize;
+                counts[index.min(num_bins - 1)] += 1;
+            }
+        }
+        let count = values.len();
+        let mean = values.iter().sum::<f64>() / count as f64;
+        let moment =
+            |power: i32| values.iter().map(|x| (x - mean).powi(power)).sum::<f64>() / count as f64;
+        let variance = moment(2);
+        HistogramFrame {
+            range,
+            counts: counts.into(),
+            below,
+            above,
+            count,
+            mean,
+            variance,
+            min,
+            max,
+            kurtosis: moment(4) / (variance * variance),
+        }
+    }
+    /// Get analyzed value of the samples
+    pub fn value(&self) -> HistogramValue {
+        *self.value.borrow()
+    }
+    /// Set analyzed value of the samples
+    ///
+    /// The window is cleared when the next chunk is received.
+    pub fn set_value(&self, value: HistogramValue) {
+        self.value.send_replace(value);
+    }
+    /// Get [`watch::Receiver`] of the most recent [`HistogramFrame`]
+    ///
+    /// The value is updated after each processed chunk. Initially (and while
+    /// the window is empty), all counts are zero and the statistics are NaN.
+    pub fn subscribe(&self) -> watch::Receiver<HistogramFrame> {
+        self.frame.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::assert_approx;
+    #[tokio::test]
+    async fn test_fourier() {
+        let (sender, sender_connector) = new_sender();
+        let fourier1 = Fourier::<f64>::new();
+        let fourier2 = Fourier::<f64>::new_center_dc();
+        let (mut receiver1, receiver1_connector) = new_receiver();
+        let (mut receiver2, receiver2_connector) = new_receiver();
+        fourier1.feed_from(&sender_connector);
+        fourier2.feed_from(&sender_connector);
+        fourier1.feed_into(&receiver1_connector);
+        fourier2.feed_into(&receiver2_connector);
+        sender
+            .send(Signal::Samples {
+                sample_rate: 48000.0,
+                chunk: Chunk::from(vec![
+                    Complex::new(1.0, 0.0),
+                    Complex::new(1.0, 0.0),
+                    Complex::new(1.0, 0.0),
+                ]),
+            })
+            .await
+            .unwrap();
+        let Signal::Samples { chunk: output1, .. } = receiver1.recv().await.unwrap()
+        else { panic!(); };
+        let Signal::Samples { chunk: output2, .. } = receiver2.recv().await.unwrap()
+        else { panic!(); };
+        assert_approx(output1[0].re, 3.0);
+        assert_approx(output1[0].im, 0.0);
+        assert_approx(output1[1].re, 0.0);
+        assert_approx(output1[1].im, 0.0);
+        assert_approx(output1[2].re, 0.0);
+        assert_approx(output1[2].im, 0.0);
+        assert_approx(output2[0].re, 0.0);
+        assert_approx(output2[0].im, 0.0);
+        assert_approx(output2[1].re, 3.0);
+        assert_approx(output2[1].im, 0.0);
+        assert_approx(output2[2].re, 0.0);
+        assert_approx(output2[2].im, 0.0);
+        sender
+            .send(Signal::Samples {
+                sample_rate: 48000.0,
+                chunk: Chunk::from(vec![
+                    Complex::new(1.0, 0.0),
+                    Complex::new(1.5, 0.0),
+                    Complex::new(1.0, 0.0),
+                    Complex::new(0.5, 0.0),
+                ]),
+            })
+            .await
+            .unwrap();
+        let Signal::Samples { chunk: output1, .. } = receiver1.recv().await.unwrap()
+        else { panic!(); };
+        let Signal::Samples { chunk: output2, .. } = receiver2.recv().await.unwrap()
+        else { panic!(); };
+        assert_approx(output1[0].re, 4.0);
+        assert_approx(output1[0].im, 0.0);
+        assert_approx(output1[1].re, 0.0);
+        assert_approx(output1[1].im, -1.0);
+        assert_approx(output1[2].re, 0.0);
+        assert_approx(output1[2].im, 0.0);
+        assert_approx(output1[3].re, 0.0);
+        assert_approx(output1[3].im, 1.0);
+        assert_approx(output2[0].re, 0.0);
+        assert_approx(output2[0].im, 0.0);
+        assert_approx(output2[1].re, 0.0);
+        assert_approx(output2[1].im, 1.0);
+        assert_approx(output2[2].re, 4.0);
+        assert_approx(output2[2].im, 0.0);
+        assert_approx(output2[3].re, 0.0);
+        assert_approx(output2[3].im, -1.0);
+    }
+    #[tokio::test]
+    async fn test_spectrum() {
+        use std::f64::consts::TAU;
+        let (sender, sender_connector) = new_sender::<Signal<Complex<f64>>>();
+        let spectrum = Spectrum::<f64>::new(16, 8, f64::INFINITY);
+        spectrum.feed_from(&sender_connector);
+        let mut frames = spectrum.subscribe();
+        let samples: Vec<Complex<f64>> = (0..16)
+            .map(|i| Complex::from_polar(2.0, TAU * 4000.0 * i as f64 / 16000.0))
+            .collect();
+        sender
+            .send(Signal::new_event(CenterFrequency(7e6)))
+            .await
+            .unwrap();
+        sender
+            .send(Signal::Samples {
+                sample_rate: 16000.0,
+                chunk: Chunk::from(samples),
+            })
+            .await
+            .unwrap();
+        frames.changed().await.unwrap();
+        let frame = frames.borrow_and_update().clone();
+        assert_eq!(frame.sample_rate, 16000.0);
+        assert_eq!(frame.bins.len(), 16);
+        let (peak, _) = frame
+            .bins
+            .iter()
+            .enumerate()
+            .max_by(|a, b| a.1.partial_cmp(b.1).unwrap())
+            .unwrap();
+        assert_eq!(frame.frequency(peak), 4000.0);
+        assert_eq!(frame.absolute_frequency(peak), Some(7004000.0));
+        assert_approx(frame.bins.iter().sum(), 4.0);
+    }
+    #[tokio::test]
+    async fn test_power_spectrum() {
+        let (sender, sender_connector) = new_sender::<Signal<Complex<f64>>>();
+        let power_spectrum = PowerSpectrum::<f64>::new(64, 32, 128);
+        power_spectrum.feed_from(&sender_connector);
+        let mut frames = power_spectrum.subscribe();
+        let mut noise = crate::math::NoiseGenerator::with_seed(1);
+        let samples: Vec<Complex<f64>> = (0..32 * 129)
+            .map(|_| noise.complex_gaussian() * 0.1)
+            .collect();
+        sender
+            .send(Signal::Samples {
+                sample_rate: 64000.0,
+                chunk: Chunk::from(samples),
+            })
+            .await
+            .unwrap();
+        frames.changed().await.unwrap();
+        let frame = frames.borrow_and_update().clone();
+        assert_eq!(frame.averages, 128);
+        assert_eq!(frame.bins.len(), 64);
+        assert_approx(frame.resolution_bandwidth, 1500.0);
+        let expected = 10.0 * (0.01f64 / 64.0).log10();
+        for idx in 0..64 {
+            assert!((frame.bins[idx] as f64 - expected).abs() < 2.0);
+        }
+        assert!((frame.density(0) - 10.0 * (0.01f64 / 64000.0).log10()).abs() < 1.0);
+    }
+    #[tokio::test]
+    async fn test_peak_finder() {
+        use std::f64::consts::TAU;
+        let (sender, sender_connector) = new_sender::<Signal<Complex<f64>>>();
+        let peak_finder = PeakFinder::<f64>::new(1024, 4);
+        peak_finder.feed_from(&sender_connector);
+        let mut peak = peak_finder.subscribe();
+        tokio::spawn(async move {
+            let mut t: f64 = 0.0;
+            loop {
+                let mut chunk = Vec::with_capacity(4096);
+                for _ in 0..4096 {
+                    chunk.push(
+                        Complex::from_polar(0.5, TAU * 1234.5 * t)
+                            + Complex::from_polar(0.1, TAU * -5000.0 * t),
+                    );
+                    t += 1.0 / 48000.0;
+                }
+                let Ok(()) = sender
+                    .send(Signal::Samples {
+                        sample_rate: 48000.0,
+                        chunk: Chunk::from(chunk),
+                    })
+                    .await
+                else { return; };
+            }
+        });
+        async fn next_peak(peak: &mut watch::Receiver<Option<Peak>>) -> Option<Peak> {
+            // skip a result which may have been calculated with previous
+            // settings
+            peak.changed().await.unwrap();
+            peak.changed().await.unwrap();
+            *peak.borrow_and_update()
+        }
+        let result = next_peak(&mut peak).await.unwrap();
+        assert!((result.frequency - 1234.5).abs() < 2.0);
+        assert!((result.power_db - to_db(0.25)).abs() < 0.5);
+        assert_eq!(result.absolute_frequency, None);
+        peak_finder.set_range(Some((-8000.0, -2000.0)));
+        let result = next_peak(&mut peak).await.unwrap();
+        assert!((result.frequency + 5000.0).abs() < 2.0);
+        assert!((result.power_db - to_db(0.01)).abs() < 0.5);
+        peak_finder.set_min_power_db(-10.0);
+        assert_eq!(next_peak(&mut peak).await, None);
+    }
+    #[tokio::test]
+    async fn test_goertzel() {
+        use std::f64::consts::TAU;
+        let (sender, sender_connector) = new_sender::<Signal<Complex<f64>>>();
+        let goertzel = Goertzel::<f64>::new(1000.0, 80);
+        goertzel.feed_from(&sender_connector);
+        let mut magnitude = goertzel.subscribe();
+        for (sample_rate, amplitude) in [(8000.0, 0.5), (16000.0, 0.25)] {
+            let samples: Vec<Complex<f64>> = (0..80)
+                .map(|i| (amplitude * (TAU * 1000.0 * i as f64 / sample_rate).cos()).into())
+                .collect();
+            sender
+                .send(Signal::Samples {
+                    sample_rate,
+                    chunk: Chunk::from(samples),
+                })
+                .await
+                .unwrap();
+            magnitude.changed().await.unwrap();
+            assert_approx(*magnitude.borrow_and_update(), amplitude);
+        }
+    }
+    #[tokio::test]
+    async fn test_power_meter() {
+        let (sender, sender_connector) = new_sender::<Signal<Complex<f64>>>();
+        let meter = PowerMeter::<f64>::new(0.01, 10.0);
+        let (mut receiver, receiver_connector) = new_receiver::<Signal<Complex<f64>>>();
+        meter.feed_from(&sender_connector);
+        meter.feed_into(&receiver_connector);
+        let mut chunk = vec![Complex::new(0.0, 0.1); 48000];
+        chunk[0] = Complex::new(1.0, 0.0);
+        sender
+            .send(Signal::Samples {
+                sample_rate: 48000.0,
+                chunk: Chunk::from(chunk),
+            })
+            .await
+            .unwrap();
+        let Signal::Samples { chunk, .. } = receiver.recv().await.unwrap()
+        else { panic!(); };
+        assert_eq!(chunk.len(), 48000);
+        assert!((*meter.level().borrow() + 20.0).abs() < 1e-6);
+        assert!((*meter.peak().borrow() + 10.0).abs() < 1e-3);
+    }
+    #[tokio::test]
+    async fn test_channelizer() {
+        use std::f64::consts::TAU;
+        let (sender, sender_connector) = new_sender::<Signal<Complex<f64>>>();
+        let channelizer = Channelizer::<f64>::new(8, 16);
+        channelizer.feed_from(&sender_connector);
+        let mut receivers = Vec::new();
+        for channel in channelizer.channels() {
+            let (receiver, receiver_connector) = new_receiver::<Signal<Complex<f64>>>();
+            channel.feed_into(&receiver_connector);
+            receivers.push(receiver);
+        }
+        assert_eq!(channelizer.channel(3).frequency_offset(80000.0), 30000.0);
+        assert_eq!(channelizer.channel(6).frequency_offset(80000.0), -20000.0);
+        let samples: Vec<Complex<f64>> = (0..800)
+            .map(|i| Complex::from_polar(1.0, -TAU * 20000.0 * i as f64 / 80000.0))
+            .collect();
+        let join_handle = tokio::spawn(async move {
+            sender
+                .send(Signal::new_event(CenterFrequency(100e6)))
+                .await
+                .unwrap();
+            sender
+                .send(Signal::Samples {
+                    sample_rate: 80000.0,
+                    chunk: Chunk::from(samples),
+                })
+                .await
+                .unwrap();
+        });
+        for (k, receiver) in receivers.iter_mut().enumerate() {
+            assert_eq!(
+                receiver.recv().await.unwrap().center_frequency(),
+                Some(100e6 + channelizer.channel(k).frequency_offset(80000.0))
+            );
+            let Signal::Samples { sample_rate, chunk } = receiver.recv().await.unwrap()
+            else { panic!(); };
+            assert_eq!(sample_rate, 10000.0);
+            assert_eq!(chunk.len(), 100);
+            let power: f64 = chunk[20..].iter().map(|x| x.norm_sqr()).sum::<f64>() / 80.0;
+            if k == 6 {
+                assert!((power - 1.0).abs() < 0.01);
+            } else {
+                assert!(power < 1e-4);
+            }
+        }
+        join_handle.await.unwrap();
+    }
+    #[tokio::test]
+    async fn test_trigger() {
+        let (sender, sender_connector) = new_sender::<Signal<Complex<f64>>>();
+        let trigger = Trigger::<f64>::new(-10.0, TriggerEdge::Rising, 0.5);
+        let (mut receiver, receiver_connector) = new_receiver::<Signal<Complex<f64>>>();
+        trigger.feed_from(&sender_connector);
+        trigger.feed_into(&receiver_connector);
+        let mut subscriber = trigger.subscribe();
+        // bursts at 100 ms and 300 ms (ignored due to hold-off) and 900 ms
+        let samples: Vec<Complex<f64>> = (0..10000)
+            .map(|i| match i {
+                1000..=1999 | 3000..=3999 | 9000..=9999 => Complex::from(1.0),
+                _ => Complex::from(0.0),
+            })
+            .collect();
+        let join_handle = tokio::spawn(async move {
+            sender
+                .send(Signal::Samples {
+                    sample_rate: 10000.0,
+                    chunk: Chunk::from(samples),
+                })
+                .await
+                .unwrap();
+        });
+        let mut positions: Vec<usize> = Vec::new();
+        let mut position: usize = 0;
+        while position < 10000 {
+            match receiver.recv().await.unwrap() {
+                Signal::Samples { chunk, .. } => position += chunk.len(),
+                Signal::Event(event) => {
+                    let event = event.as_any().downcast_ref::<Triggered>().unwrap();
+                    assert_eq!(event.edge, TriggerEdge::Rising);
+                    positions.push(position);
+                }
+            }
+        }
+        join_handle.await.unwrap();
+        assert_eq!(positions.len(), 2);
+        assert!((1000..1010).contains(&positions[0]));
+        assert!((9000..9010).contains(&positions[1]));
+        for _ in 0..2 {
+            assert_eq!(subscriber.recv().await.unwrap().edge, TriggerEdge::Rising);
+        }
+    }
+    async fn correlator_peaks(template_len: usize) -> Vec<CorrelationPeak> {
+        let template: Vec<Complex<f64>> = (0..template_len)
+            .map(|i| Complex::from_polar(1.0, 0.05 * (i * i) as f64))
+            .collect();
+        let mut samples: Vec<Complex<f64>> = (0..10000)
+            .map(|i| {
+                let x = i as f64;
+                Complex::new((x * 12.9898).sin() * 0.05, (x * 78.233).sin() * 0.05)
+            })
+            .collect();
+        for (start, phase) in [(2000, 1.0), (7000, -2.0)] {
+            for (i, t) in template.iter().enumerate() {
+                samples[start + i] += t * Complex::from_polar(0.5, phase);
+            }
+        }
+        let (sender, sender_connector) = new_sender::<Signal<Complex<f64>>>();
+        let correlator = Correlator::<f64>::new(template.into(), 0.7);
+        let (mut receiver, receiver_connector) = new_receiver::<Signal<Complex<f64>>>();
+        correlator.feed_from(&sender_connector);
+        correlator.feed_into(&receiver_connector);
+        let mut subscriber = correlator.subscribe();
+        let join_handle = tokio::spawn(async move {
+            for chunk in samples.chunks(1500) {
+                sender
+                    .send(Signal::Samples {
+                        sample_rate: 48000.0,
+                        chunk: Chunk::from(chunk.to_vec()),
+                    })
+                    .await
+                    .unwrap();
+            }
+        });
+        let mut peaks: Vec<CorrelationPeak> = Vec::new();
+        let mut position: u64 = 0;
+        while position < 10000 {
+            match receiver.recv().await.unwrap() {
+                Signal::Samples { chunk, .. } => position += chunk.len() as u64,
+                Signal::Event(event) => {
+                    let peak = event.as_any().downcast_ref::<CorrelationPeak>().unwrap();
+                    assert!(position > peak.position + template_len as u64);
+                    peaks.push(peak.clone());
+                }
+            }
+        }
+        join_handle.await.unwrap();
+        for peak in peaks.iter() {
+            assert_eq!(subscriber.recv().await.unwrap().position, peak.position);
+        }
+        peaks
+    }
+    #[tokio::test]
+    async fn test_correlator() {
+        for template_len in [16, 255] {
+            let peaks = correlator_peaks(template_len).await;
+            assert_eq!(peaks.len(), 2);
+            assert_eq!(peaks[0].position, 2000);
+            assert_eq!(peaks[1].position, 7000);
+            assert!(peaks[0].magnitude > 0.9);
+            assert!((peaks[0].phase - 1.0).abs() < 0.05);
+            assert!((peaks[1].phase + 2.0).abs() < 0.05);
+        }
+    }
+    #[tokio::test]
+    async fn test_histogram() {
+        use crate::math::NoiseGenerator;
+        use std::f64::consts::TAU;
+        let (sender, sender_connector) = new_sender::<Signal<Complex<f32>>>();
+        let histogram = Histogram::<f32>::new(40, (-4.0, 4.0), 100000, HistogramValue::Real);
+        histogram.feed_from(&sender_connector);
+        let mut frames = histogram.subscribe();
+        assert!(frames.borrow().mean.is_nan());
+        let mut noise = NoiseGenerator::with_seed(1);
+        let samples: Vec<Complex<f32>> = (0..100000)
+            .map(|_| Complex::new(noise.gaussian() as f32, 0.0))
+            .collect();
+        sender
+            .send(Signal::Samples {
+                sample_rate: 1000.0,
+                chunk: Chunk::from(samples),
+            })
+            .await
+            .unwrap();
+        frames.changed().await.unwrap();
+        {
+            let frame = frames.borrow_and_update();
+            assert_eq!(frame.count, 100000);
+            let counted: usize = frame.counts.iter().sum();
+            assert_eq!(counted + frame.below + frame.above, frame.count);
+            assert!(frame.mean.abs() < 0.02);
+            assert!((frame.variance - 1.0).abs() < 0.02);
+            assert!((frame.kurtosis - 3.0).abs() < 0.1);
+            assert!(frame.min < -3.0 && frame.max > 3.0);
+            // bins next to zero contain most values
+            assert!((frame.bin_center(20) - 0.1).abs() < 1e-9);
+            assert!(frame.counts[19] > 10 * frame.counts[5]);
+        }
+        histogram.set_value(HistogramValue::Magnitude);
+        let samples: Vec<Complex<f32>> = (0..100000)
+            .map(|i| {
+                let phase = TAU * i as f64 * 0.01;
+                Complex::new(phase.cos() as f32, phase.sin() as f32)
+            })
+            .collect();
+        sender
+            .send(Signal::Samples {
+                sample_rate: 1000.0,
+                chunk: Chunk::from(samples),
+            })
+            .await
+            .unwrap();
+        frames.changed().await.unwrap();
+        let frame = frames.borrow_and_update();
+        assert!((frame.mean - 1.0).abs() < 1e-6);
+        assert!(frame.variance < 1e-9);
+        assert_eq!(frame.counts.iter().sum::<usize>(), 100000);
+    }
+    #[tokio::test]
+    async fn test_constellation_sink() {
+        use crate::math::NoiseGenerator;
+        let (sender, sender_connector) = new_sender::<Signal<Complex<f32>>>();
+        let sink = ConstellationSink::<f32>::new(500, Constellation::Qam16);
+        sink.feed_from(&sender_connector);
+        let mut noise = NoiseGenerator::with_seed(1);
+        let levels = [-3.0, -1.0, 1.0, 3.0];
+        let symbols: Vec<Complex<f32>> = (0..2000)
+            .map(|i| {
+                let x = Complex::new(levels[i % 4], levels[i * 3 / 4 % 4]) * 0.2
+                    + noise.complex_gaussian() * 0.01;
+                Complex::new(x.re as f32, x.im as f32)
+            })
+            .collect();
+        let mut points = sink.points();
+        let mut evm = sink.evm();
+        sender
+            .send(Signal::Samples {
+                sample_rate: 1000.0,
+                chunk: Chunk::from(symbols.clone()),
+            })
+            .await
+            .unwrap();
+        points.changed().await.unwrap();
+        evm.changed().await.unwrap();
+        assert_eq!(points.borrow()[..], symbols[1500..]);
+        // noise power relative to signal power (0.4 for 16-QAM scaled by 0.2)
+        let expected = (0.01f64.powi(2) / 0.4).sqrt();
+        assert!((*evm.borrow() - expected).abs() < 0.2 * expected);
+        sink.set_constellation(Constellation::Qpsk);
+        sender
+            .send(Signal::Samples {
+                sample_rate: 1000.0,
+                chunk: Chunk::from(vec![Complex::new(0.0, 0.0)]),
+            })
+            .await
+            .unwrap();
+        evm.changed().await.unwrap();
+        assert!(*evm.borrow() > 0.2);
+    }
+    #[tokio::test(start_paused = true)]
+    async fn test_rate_monitor() {
+        let (sender, sender_connector) = new_sender::<Signal<f32>>();
+        let monitor = RateMonitor::<f32>::new(10.0);
+        let (mut receiver, receiver_connector) = new_receiver::<Signal<f32>>();
+        monitor.feed_from(&sender_connector);
+        monitor.feed_into(&receiver_connector);
+        assert!(monitor.rate().borrow().is_nan());
+        let mut interval = tokio::time::interval(Duration::from_millis(10));
+        for _ in 0..31 {
+            interval.tick().await;
+            sender
+                .send(Signal::Samples {
+                    sample_rate: 20000.0,
+                    chunk: Chunk::from(vec![0.0; 100]),
+                })
+                .await
+                .unwrap();
+            receiver.recv().await.unwrap();
+        }
+        let rate = *monitor.rate().borrow();
+        assert!((rate - 10000.0).abs() < 1.0);
+        let ppm = *monitor.ppm().borrow();
+        assert!((ppm + 500000.0).abs() < 100.0);
+    }
+    #[tokio::test]
+    async fn test_tdoa() {
+        let (sender1, sender1_connector) = new_sender::<Signal<Complex<f64>>>();
+        let (sender2, sender2_connector) = new_sender::<Signal<Complex<f64>>>();
+        let delay = crate::blocks::transform::Delay::<f64>::with_fractional(7.5);
+        let tdoa = Tdoa::<f64>::new(1024);
+        tdoa.input(0).feed_from(&sender1_connector);
+        delay.feed_from(&sender2_connector);
+        tdoa.input(1).feed_from(&delay);
+        let mut estimate = tdoa.estimate();
+        // low-pass filtered noise, such that the fractional delay is accurate
+        let mut rng: u32 = 1;
+        let mut noise = Vec::new();
+        let mut average = Complex::new(0.0, 0.0);
+        for _ in 0..4096 {
+            let mut next = || {
+                rng = rng.wrapping_mul(1664525).wrapping_add(1013904223);
+                rng as f64 / u32::MAX as f64 - 0.5
+            };
+            average += (Complex::new(next(), next()) - average) * 0.3;
+            noise.push(average);
+        }
+        let send1 = async {
+            for chunk in noise.chunks(300) {
+                sender1
+                    .send(Signal::Samples {
+                        sample_rate: 1000.0,
+                        chunk: Chunk::from(chunk.to_vec()),
+                    })
+                    .await
+                    .unwrap();
+            }
+        };
+        let send2 = async {
+            for chunk in noise.chunks(700) {
+                sender2
+                    .send(Signal::Samples {
+                        sample_rate: 1000.0,
+                        chunk: Chunk::from(chunk.to_vec()),
+                    })
+                    .await
+                    .unwrap();
+            }
+        };
+        tokio::join!(send1, send2);
+        let mut count = 0;
+        while let Ok(Ok(())) =
+            tokio::time::timeout(Duration::from_millis(200), estimate.changed()).await
+        {
+            let estimate = estimate.borrow_and_update().unwrap();
+            assert!((estimate.delay_samples - 7.5).abs() < 0.1);
+            assert!((estimate.delay_seconds - 0.0075).abs() < 0.0001);
+            assert!(estimate.correlation > 0.9);
+            count += 1;
+        }
+        assert!(count > 0);
     }
 }
@@ -1,15 +1,19 @@
-//! Buffering
+//! Buffering and distribution of data
 
+use crate::bufferpool::Chunk;
 use crate::flow::*;
 use crate::impl_block_trait;
 use crate::signal::*;
 
 use tokio::select;
+use tokio::sync::{mpsc, watch};
 use tokio::task::spawn;
+use tokio::time::{sleep_until, Instant as TokioInstant};
 
 use std::collections::VecDeque;
 use std::future::pending;
-use std::time::Instant;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 
 const QUEUE_MAX_EVENTS: usize = 256;
 
@@ -27,9 +31,31 @@ pub mod events {
             self
         }
     }
+    /// Sent by [`Selector`] block when the active input has been changed
+    #[derive(Clone, Debug)]
+    pub struct InputChanged;
+    impl Event for InputChanged {
+        fn is_interrupt(&self) -> bool {
+            true
+        }
+        fn as_any(&self) -> &(dyn std::any::Any + Send + Sync) {
+            self
+        }
+    }
 }
 use events::*;
 
+/// Behavior of [`Buffer`] when its capacity is exhausted
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum OverflowPolicy {
+    /// Suspend receiving until data has been sent out
+    Block,
+    /// Discard oldest buffered data
+    DropOldest,
+    /// Discard newly received data
+    DropNewest,
+}
+
 struct TemporalQueueEntry<T> {
     instant: Instant,
     signal: Signal<T>,
@@ -109,6 +135,16 @@ impl<T> TemporalQueue<T> {
     pub fn leading_event(&self) -> bool {
         self.queue[0].signal.is_event()
     }
+    /// Check if last entry is a [`BufferOverflow`] event
+    pub fn trailing_overflow(&self) -> bool {
+        match self.queue.back() {
+            Some(TemporalQueueEntry {
+                signal: Signal::Event(event),
+                ..
+            }) => event.as_any().is::<BufferOverflow>(),
+            _ => false,
+        }
+    }
 }
 
 /// Buffer management
@@ -126,12 +162,20 @@ impl<T> TemporalQueue<T> {
 /// [`Buffer::new`], the buffer block will consume (and discard) data even when
 /// its connected consumer isn't fast enough.
 ///
+/// Alternatively, [`Buffer::with_overflow_policy`] creates a buffer with a
+/// fixed capacity and an [`OverflowPolicy`] which determines whether
+/// receiving is suspended or data is discarded when the buffer is full.
+///
+/// The duration of buffered data can be monitored with
+/// [`Buffer::fill_level`].
+///
 /// [`flow::Sender`]: crate::flow::Sender
 /// [`broadcast_bp`]: crate::sync::broadcast_bp
 /// [signal processing blocks]: crate::blocks
 pub struct Buffer<T> {
     receiver_connector: ReceiverConnector<Signal<T>>,
     sender_connector: SenderConnector<Signal<T>>,
+    fill_level: watch::Receiver<f64>,
 }
 
 impl_block_trait! { <T> Consumer<Signal<T>> for Buffer<T> }
@@ -152,15 +196,47 @@ where
     /// If buffered data is held longer than `max_age` seconds, it will be
     /// discarded.
     pub fn new(initial_capacity: f64, min_capacity: f64, max_capacity: f64, max_age: f64) -> Self {
+        Self::new_internal(
+            initial_capacity,
+            min_capacity,
+            max_capacity,
+            max_age,
+            OverflowPolicy::Block,
+        )
+    }
+    /// Create new [`Buffer`] holding up to `capacity` seconds of data and
+    /// using the given [`OverflowPolicy`]
+    ///
+    /// Received data is sent out without delay. The capacity in seconds
+    /// corresponds to a number of samples depending on the sample rate of the
+    /// stream. When data is discarded, a [`BufferOverflow`] event is sent
+    /// in place of the missing data.
+    pub fn with_overflow_policy(capacity: f64, policy: OverflowPolicy) -> Self {
+        Self::new_internal(0.0, 0.0, capacity, f64::INFINITY, policy)
+    }
+    fn new_internal(
+        initial_capacity: f64,
+        min_capacity: f64,
+        max_capacity: f64,
+        max_age: f64,
+        policy: OverflowPolicy,
+    ) -> Self {
         let (mut receiver, receiver_connector) = new_receiver::<Signal<T>>();
         let (sender, sender_connector) = new_sender::<Signal<T>>();
+        let (fill_level_send, fill_level) = watch::channel(0.0);
         spawn(async move {
             let mut initial = true;
             let mut underrun = true;
             let mut shutdown = false;
             let mut marked_missing = false;
+            let mut dropped_oldest = false;
             let mut queue = TemporalQueue::<T>::new();
             loop {
+                fill_level_send.send_if_modified(|fill_level| {
+                    let modified = *fill_level != queue.duration();
+                    *fill_level = queue.duration();
+                    modified
+                });
                 if queue.is_empty() && shutdown {
                     break;
                 }
@@ -172,7 +248,9 @@ where
                 }
                 match select! {
                     action = async {
-                        if shutdown || !(queue.duration() <= max_capacity && queue.event_count() < QUEUE_MAX_EVENTS) {
+                        let full = queue.duration() > max_capacity
+                            && policy == OverflowPolicy::Block;
+                        if shutdown || full || queue.event_count() >= QUEUE_MAX_EVENTS {
                             pending::<()>().await;
                         }
                         match receiver.recv().await {
@@ -191,7 +269,27 @@ where
                     } => action,
                 } {
                     Action::Fill(signal) => {
-                        queue.push(signal);
+                        match policy {
+                            OverflowPolicy::Block => queue.push(signal),
+                            OverflowPolicy::DropNewest => {
+                                if signal.is_event() {
+                                    queue.push(signal);
+                                } else if queue.duration() + signal.duration() > max_capacity {
+                                    if !queue.trailing_overflow() {
+                                        queue.push(Signal::new_event(BufferOverflow));
+                                    }
+                                } else {
+                                    queue.push(signal);
+                                }
+                            }
+                            OverflowPolicy::DropOldest => {
+                                queue.push(signal);
+                                while queue.duration() > max_capacity && !queue.leading_event() {
+                                    queue.pop();
+                                    dropped_oldest = true;
+                                }
+                            }
+                        }
                         if initial {
                             if queue.duration() >= initial_capacity {
                                 underrun = false;
@@ -205,7 +303,13 @@ where
                         match sender.try_reserve() {
                             Ok(Some(reservation)) => {
                                 let mut reservation = Some(reservation);
-                                if queue.len() > 1
+                                if dropped_oldest {
+                                    reservation
+                                        .take()
+                                        .unwrap()
+                                        .send(Signal::new_event(BufferOverflow));
+                                    dropped_oldest = false;
+                                } else if queue.len() > 1
                                     && queue.age() > max_age
                                     && !queue.leading_event()
                                 {
@@ -231,7 +335,13 @@ where
                     }
                     Action::Drain(reservation) => {
                         let mut reservation = Some(reservation);
-                        if queue.age() > max_age && !queue.leading_event() {
+                        if dropped_oldest {
+                            reservation
+                                .take()
+                                .unwrap()
+                                .send(Signal::new_event(BufferOverflow));
+                            dropped_oldest = false;
+                        } else if queue.age() > max_age && !queue.leading_event() {
                             while queue.age() > max_age {
                                 if queue.pop().is_none() {
                                     break;
@@ -262,9 +372,756 @@ where
         Self {
             receiver_connector,
             sender_connector,
+            fill_level,
+        }
+    }
+    /// Get [`watch::Receiver`] of the duration of buffered data in seconds
+    pub fn fill_level(&self) -> watch::Receiver<f64> {
+        self.fill_level.clone()
+    }
+}
+
+/// Behavior of a [`Splitter`] output when its consumers are not ready
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum BranchPolicy {
+    /// Wait until all connected consumers have received previous data
+    ///
+    /// Note that this also waits when no consumer is connected.
+    Lossless,
+    /// Discard data if the connected consumers are not ready
+    Lossy,
+}
+
+/// Output of a [`Splitter`] block, which acts as a [`Producer<Signal<T>>`]
+pub struct SplitterOutput<T> {
+    sender_connector: SenderConnector<Signal<T>>,
+    policy: BranchPolicy,
+}
+
+impl_block_trait! { <T> Producer<Signal<T>> for SplitterOutput<T> }
+
+impl<T> SplitterOutput<T> {
+    /// [`BranchPolicy`] of this output
+    pub fn policy(&self) -> BranchPolicy {
+        self.policy
+    }
+}
+
+/// Block which forwards data to several outputs with individual
+/// [`BranchPolicy`]
+///
+/// Each [`Producer`] may be connected to several [`Consumer`]s directly, in
+/// which case the slowest consumer determines the speed of all consumers.
+/// A `Splitter` allows having outputs which share the same input but discard
+/// data when their consumers are too slow (e.g. for a display), while other
+/// outputs are lossless (e.g. for recording). If all outputs are lossy, the
+/// `Splitter` never blocks its input.
+///
+/// When data has been discarded on a lossy output, a [`BufferOverflow`] event
+/// is sent on that output once its consumers are ready again. An event which
+/// arrives at that time is sent after the [`BufferOverflow`] event. Lossy
+/// outputs without consumers are skipped.
+pub struct Splitter<T> {
+    receiver_connector: ReceiverConnector<Signal<T>>,
+    outputs: Vec<SplitterOutput<T>>,
+}
+
+impl_block_trait! { <T> Consumer<Signal<T>> for Splitter<T> }
+
+impl<T> Splitter<T>
+where
+    T: Clone + Send + Sync + 'static,
+{
+    /// Create new `Splitter` with one output for each given [`BranchPolicy`]
+    pub fn new(policies: &[BranchPolicy]) -> Self {
+        let (mut receiver, receiver_connector) = new_receiver::<Signal<T>>();
+        let mut branches = Vec::with_capacity(policies.len());
+        let mut outputs = Vec::with_capacity(policies.len());
+        for &policy in policies {
+            let (sender, sender_connector) = new_sender::<Signal<T>>();
+            branches.push((sender, policy, false));
+            outputs.push(SplitterOutput {
+                sender_connector,
+                policy,
+            });
+        }
+        spawn(async move {
+            loop {
+                let Ok(signal) = receiver.recv().await else { return; };
+                for (sender, policy, dropped) in branches.iter_mut() {
+                    match policy {
+                        BranchPolicy::Lossless => {
+                            let Ok(()) = sender.send(signal.clone()).await else { return; };
+                        }
+                        BranchPolicy::Lossy => {
+                            let marked = if *dropped {
+                                let Ok(Some(reservation)) = sender.try_reserve() else {
+                                    continue;
+                                };
+                                reservation.send(Signal::new_event(BufferOverflow));
+                                *dropped = false;
+                                true
+                            } else {
+                                false
+                            };
+                            if marked && signal.is_event() {
+                                // consumers were ready, so don't discard the
+                                // event because of the marker
+                                let _ = sender.send(signal.clone()).await;
+                                continue;
+                            }
+                            match sender.try_reserve() {
+                                Ok(Some(reservation)) => reservation.send(signal.clone()),
+                                // data discarded right after the marker is
+                                // covered by the marker
+                                Ok(None) => *dropped = !marked,
+                                Err(_) => (),
+                            }
+                        }
+                    }
+                }
+            }
+        });
+        Self {
+            receiver_connector,
+            outputs,
+        }
+    }
+    /// Output with given index
+    pub fn output(&self, index: usize) -> &SplitterOutput<T> {
+        &self.outputs[index]
+    }
+    /// All outputs
+    pub fn outputs(&self) -> &[SplitterOutput<T>] {
+        &self.outputs
+    }
+}
+
+/// Input of a [`Selector`] block, which acts as a [`Consumer<Signal<T>>`]
+pub struct SelectorInput<T> {
+    receiver_connector: ReceiverConnector<Signal<T>>,
+}
+
+impl_block_trait! { <T> Consumer<Signal<T>> for SelectorInput<T> }
+
+/// Block which forwards data from one of several inputs
+///
+/// Data from inputs other than the active input is received and discarded,
+/// which includes data that is in flight when switching inputs. When the
+/// active input is changed with [`Selector::set_active`], an [`InputChanged`]
+/// event is sent before forwarding data from the new input. As each chunk
+/// carries its sample rate, the output's sample rate follows the active
+/// input.
+pub struct Selector<T> {
+    inputs: Vec<SelectorInput<T>>,
+    sender_connector: SenderConnector<Signal<T>>,
+    active: watch::Sender<usize>,
+}
+
+impl_block_trait! { <T> Producer<Signal<T>> for Selector<T> }
+
+impl<T> Selector<T>
+where
+    T: Clone + Send + Sync + 'static,
+{
+    /// Create new `Selector` with given number of inputs, where the first
+    /// input is active
+    pub fn new(num_inputs: usize) -> Self {
+        assert!(num_inputs > 0, "number of inputs must be positive");
+        let (sender, sender_connector) = new_sender::<Signal<T>>();
+        let (active_send, mut active_recv) = watch::channel(0usize);
+        let (forward_send, mut forward_recv) = mpsc::channel::<(usize, Signal<T>)>(1);
+        let mut inputs = Vec::with_capacity(num_inputs);
+        for index in 0..num_inputs {
+            let (mut receiver, receiver_connector) = new_receiver::<Signal<T>>();
+            let active_recv = active_send.subscribe();
+            let forward_send = forward_send.clone();
+            spawn(async move {
+                loop {
+                    let Ok(signal) = receiver.recv().await else { return; };
+                    if *active_recv.borrow() == index {
+                        let Ok(()) = forward_send.send((index, signal)).await else { return; };
+                    }
+                }
+            });
+            inputs.push(SelectorInput { receiver_connector });
+        }
+        spawn(async move {
+            let mut active: usize = 0;
+            loop {
+                let Some((index, signal)) = forward_recv.recv().await else { return; };
+                if active_recv.has_changed().unwrap_or(false) {
+                    let new_active = *active_recv.borrow_and_update();
+                    if new_active != active {
+                        active = new_active;
+                        let Ok(()) = sender.send(Signal::new_event(InputChanged)).await
+                        else { return; };
+                    }
+                }
+                if index != active {
+                    continue;
+                }
+                let Ok(()) = sender.send(signal).await else { return; };
+            }
+        });
+        Self {
+            inputs,
+            sender_connector,
+            active: active_send,
+        }
+    }
+    /// Input with given index
+    pub fn input(&self, index: usize) -> &SelectorInput<T> {
+        &self.inputs[index]
+    }
+    /// All inputs
+    pub fn inputs(&self) -> &[SelectorInput<T>] {
+        &self.inputs
+    }
+    /// Get index of active input
+    pub fn active(&self) -> usize {
+        *self.active.borrow()
+    }
+    /// Set index of active input
+    pub fn set_active(&self, index: usize) {
+        assert!(index < self.inputs.len(), "input index out of range");
+        self.active.send_replace(index);
+    }
+}
+
+/// Samples stored by a [`RingTap`]
+struct RingTapHistory<T> {
+    chunks: VecDeque<Chunk<T>>,
+    sample_rate: Option<f64>,
+    len: usize,
+    max_len: usize,
+}
+
+impl<T> RingTapHistory<T> {
+    /// Discard oldest chunks which are not needed to provide `max_len`
+    /// samples
+    fn trim(&mut self) {
+        while let Some(first) = self.chunks.front() {
+            if self.len - first.len() < self.max_len {
+                break;
+            }
+            self.len -= first.len();
+            self.chunks.pop_front();
         }
     }
 }
 
+/// Block which passes a [`Signal`] unchanged but keeps a history of the most
+/// recently passed samples in memory
+///
+/// The history covers the configured duration (see [`RingTap::set_duration`])
+/// and can be obtained with [`RingTap::snapshot`] at any time, e.g. to
+/// capture the signal preceding a transient event. The history is cleared
+/// when the sample rate changes. A [`Trigger`] can be used to detect such
+/// events.
+///
+/// [`Trigger`]: crate::blocks::analysis::Trigger
+pub struct RingTap<T> {
+    receiver_connector: ReceiverConnector<Signal<T>>,
+    sender_connector: SenderConnector<Signal<T>>,
+    duration: watch::Sender<f64>,
+    history: Arc<Mutex<RingTapHistory<T>>>,
+}
+
+impl_block_trait! { <T> Consumer<Signal<T>> for RingTap<T> }
+impl_block_trait! { <T> Producer<Signal<T>> for RingTap<T> }
+
+impl<T> RingTap<T>
+where
+    T: Clone + Send + Sync + 'static,
+{
+    /// Create new `RingTap` keeping the samples of the last `duration`
+    /// seconds
+    pub fn new(duration: f64) -> Self {
+        assert!(duration >= 0.0, "duration must not be negative");
+        let (mut receiver, receiver_connector) = new_receiver::<Signal<T>>();
+        let (sender, sender_connector) = new_sender::<Signal<T>>();
+        let (duration_send, mut duration_recv) = watch::channel(duration);
+        let history = Arc::new(Mutex::new(RingTapHistory {
+            chunks: VecDeque::new(),
+            sample_rate: None,
+            len: 0,
+            max_len: 0,
+        }));
+        let history_clone = history.clone();
+        spawn(async move {
+            let mut duration = duration;
+            loop {
+                let Ok(signal) = receiver.recv().await else { return; };
+                if let Signal::Samples { sample_rate, chunk } = &signal {
+                    if duration_recv.has_changed().unwrap_or(false) {
+                        duration = *duration_recv.borrow_and_update();
+                    }
+                    let mut history = history_clone.lock().unwrap();
+                    if history.sample_rate != Some(*sample_rate) {
+                        history.chunks.clear();
+                        history.len = 0;
+                        history.sample_rate = Some(*sample_rate);
+                    }
+                    history.max_len = (duration * sample_rate).round() as usize;
+                    history.len += chunk.len();
+                    history.chunks.push_back(chunk.clone());
+                    history.trim();
+                }
+                let Ok(()) = sender.send(signal).await else { return; };
+            }
+        });
+        Self {
+            receiver_connector,
+            sender_connector,
+            duration: duration_send,
+            history,
+        }
+    }
+    /// Get duration of kept history in seconds
+    pub fn duration(&self) -> f64 {
+        *self.duration.borrow()
+    }
+    /// Set duration of kept history in seconds
+    ///
+    /// The new duration takes effect with the next received chunk.
+    pub fn set_duration(&self, duration: f64) {
+        assert!(duration >= 0.0, "duration must not be negative");
+        self.duration.send_replace(duration);
+    }
+    /// Sample rate of the samples in the history (or `None` if no samples
+    /// have been received yet)
+    pub fn sample_rate(&self) -> Option<f64> {
+        self.history.lock().unwrap().sample_rate
+    }
+    /// Get samples of the last [`duration`] seconds (or less if fewer
+    /// samples have been received), oldest sample first
+    ///
+    /// [`duration`]: RingTap::duration
+    pub fn snapshot(&self) -> Vec<T> {
+        let history = self.history.lock().unwrap();
+        let skip = history.len.saturating_sub(history.max_len);
+        let mut samples = Vec::with_capacity(history.len - skip);
+        for chunk in history.chunks.iter() {
+            samples.extend_from_slice(chunk);
+        }
+        samples.drain(0..skip);
+        samples
+    }
+    /// Discard history
+    pub fn clear(&self) {
+        let mut history = self.history.lock().unwrap();
+        history.chunks.clear();
+        history.len = 0;
+    }
+}
+
+/// Block which passes a [`Signal`] unchanged and inserts [events] on demand
+///
+/// Events passed to [`EventInjector::inject`] are inserted between chunks,
+/// e.g. to test how downstream blocks handle a [`Flush`] or an
+/// interruption, or to insert control markers into a running stream.
+///
+/// Regarding ordering, the following is guaranteed:
+///
+/// * Injected events are sent in the order in which they were injected.
+/// * An injected event is sent before any [`Signal`] which the block
+///   receives after [`EventInjector::inject`] has returned. A `Signal`
+///   which has already been received (i.e. at most one, which is waiting to
+///   be sent) is sent before the event.
+///
+/// To send an event from a [`Sender`] directly, use [`Sender::send_event`].
+///
+/// [events]: Signal::Event
+pub struct EventInjector<T> {
+    receiver_connector: ReceiverConnector<Signal<T>>,
+    sender_connector: SenderConnector<Signal<T>>,
+    events: mpsc::UnboundedSender<Arc<dyn Event>>,
+}
+
+impl_block_trait! { <T> Consumer<Signal<T>> for EventInjector<T> }
+impl_block_trait! { <T> Producer<Signal<T>> for EventInjector<T> }
+
+impl<T> EventInjector<T>
+where
+    T: Clone + Send + Sync + 'static,
+{
+    /// Create new `EventInjector`
+    pub fn new() -> Self {
+        let (mut receiver, receiver_connector) = new_receiver::<Signal<T>>();
+        let (sender, sender_connector) = new_sender::<Signal<T>>();
+        let (events_send, mut events_recv) = mpsc::unbounded_channel::<Arc<dyn Event>>();
+        spawn(async move {
+            let mut injecting = true;
+            loop {
+                let signal = select! {
+                    biased;
+                    event = events_recv.recv(), if injecting => match event {
+                        Some(event) => Signal::Event(event),
+                        None => {
+                            injecting = false;
+                            continue;
+                        }
+                    },
+                    result = receiver.recv() => match result {
+                        Ok(signal) => signal,
+                        Err(_) => return,
+                    },
+                };
+                let Ok(()) = sender.send(signal).await else { return; };
+            }
+        });
+        Self {
+            receiver_connector,
+            sender_connector,
+            events: events_send,
+        }
+    }
+    /// Insert `event` into the stream
+    pub fn inject<E: Event>(&self, event: E) {
+        self.events.send(Arc::new(event)).ok();
+    }
+}
+
+/// Block which passes a [`Signal`] unchanged but paced in real time
+///
+/// Each chunk is released when the samples before it would have been played
+/// back at their [`sample_rate`], so e.g. a file source feeding an audio
+/// sink plays at natural speed. Chunks are not split, so they should be
+/// short compared to the desired timing accuracy. Events are passed
+/// immediately.
+///
+/// If the block falls behind (e.g. due to scheduling jitter or because the
+/// consumer was blocked), it catches up by sending chunks without delay, but
+/// only for up to `max_lag` seconds. If it is further behind, timing is
+/// restarted from the current time.
+///
+/// [`sample_rate`]: Signal::Samples::sample_rate
+pub struct Throttle<T> {
+    receiver_connector: ReceiverConnector<Signal<T>>,
+    sender_connector: SenderConnector<Signal<T>>,
+    max_lag: watch::Sender<f64>,
+}
+
+impl_block_trait! { <T> Consumer<Signal<T>> for Throttle<T> }
+impl_block_trait! { <T> Producer<Signal<T>> for Throttle<T> }
+
+impl<T> Throttle<T>
+where
+    T: Clone + Send + Sync + 'static,
+{
+    /// Create new `Throttle` which catches up for at most `max_lag` seconds
+    pub fn new(max_lag: f64) -> Self {
+        assert!(max_lag >= 0.0, "maximum lag must not be negative");
+        let (mut receiver, receiver_connector) = new_receiver::<Signal<T>>();
+        let (sender, sender_connector) = new_sender::<Signal<T>>();
+        let (max_lag_send, mut max_lag_recv) = watch::channel(max_lag);
+        spawn(async move {
+            let mut max_lag = Duration::from_secs_f64(max_lag);
+            let mut deadline: Option<TokioInstant> = None;
+            loop {
+                let Ok(signal) = receiver.recv().await else { return; };
+                if let Signal::Samples { sample_rate, chunk } = &signal {
+                    if max_lag_recv.has_changed().unwrap_or(false) {
+                        max_lag = Duration::from_secs_f64(*max_lag_recv.borrow_and_update());
+                    }
+                    let now = TokioInstant::now();
+                    let earliest = now.checked_sub(max_lag).unwrap_or(now);
+                    let start = match deadline {
+                        Some(deadline) => deadline.max(earliest),
+                        None => now,
+                    };
+                    sleep_until(start).await;
+                    deadline =
+                        Some(start + Duration::from_secs_f64(chunk.len() as f64 / sample_rate));
+                }
+                let Ok(()) = sender.send(signal).await else { return; };
+            }
+        });
+        Self {
+            receiver_connector,
+            sender_connector,
+            max_lag: max_lag_send,
+        }
+    }
+    /// Get maximum duration in seconds for which the block catches up
+    pub fn max_lag(&self) -> f64 {
+        *self.max_lag.borrow()
+    }
+    /// Set maximum duration in seconds for which the block catches up
+    pub fn set_max_lag(&self, max_lag: f64) {
+        assert!(max_lag >= 0.0, "maximum lag must not be negative");
+        self.max_lag.send_replace(max_lag);
+    }
+}
+
 #[cfg(test)]
-mod tests {}
+mod tests {
+    use super::*;
+    async fn fill_and_drain(policy: OverflowPolicy, expected_fill_level: f64) -> Vec<Option<f64>> {
+        use std::time::Duration;
+        use tokio::time::timeout;
+        let (sender, sender_connector) = new_sender::<Signal<f64>>();
+        let buffer = Buffer::<f64>::with_overflow_policy(3.0, policy);
+        let (mut receiver, receiver_connector) = new_receiver::<Signal<f64>>();
+        buffer.feed_from(&sender_connector);
+        buffer.feed_into(&receiver_connector);
+        for i in 0..8 {
+            let signal = Signal::Samples {
+                sample_rate: 1.0,
+                chunk: Chunk::from(vec![i as f64]),
+            };
+            let _ = timeout(Duration::from_millis(10), sender.send(signal)).await;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        assert_eq!(*buffer.fill_level().borrow(), expected_fill_level);
+        let mut received = Vec::new();
+        while let Ok(signal) = timeout(Duration::from_millis(10), receiver.recv()).await {
+            match signal.unwrap() {
+                Signal::Samples { chunk, .. } => received.push(Some(chunk[0])),
+                Signal::Event(event) => {
+                    assert!(event.as_any().is::<BufferOverflow>());
+                    received.push(None);
+                }
+            }
+        }
+        received
+    }
+    #[tokio::test(start_paused = true)]
+    async fn test_overflow_policy() {
+        assert_eq!(
+            fill_and_drain(OverflowPolicy::Block, 4.0).await,
+            vec![Some(0.0), Some(1.0), Some(2.0), Some(3.0), Some(4.0)]
+        );
+        assert_eq!(
+            fill_and_drain(OverflowPolicy::DropNewest, 3.0).await,
+            vec![Some(0.0), Some(1.0), Some(2.0), None]
+        );
+        assert_eq!(
+            fill_and_drain(OverflowPolicy::DropOldest, 3.0).await,
+            vec![None, Some(5.0), Some(6.0), Some(7.0)]
+        );
+    }
+    #[tokio::test(start_paused = true)]
+    async fn test_throttle() {
+        let (sender, sender_connector) = new_sender::<Signal<f64>>();
+        let throttle = Throttle::<f64>::new(0.05);
+        let (mut receiver, receiver_connector) = new_receiver::<Signal<f64>>();
+        throttle.feed_from(&sender_connector);
+        throttle.feed_into(&receiver_connector);
+        spawn(async move {
+            for _ in 0..40 {
+                let signal = Signal::Samples {
+                    sample_rate: 1000.0,
+                    chunk: Chunk::from(vec![0.0; 10]),
+                };
+                let Ok(()) = sender.send(signal).await else { return; };
+            }
+        });
+        let start = TokioInstant::now();
+        receiver.recv().await.unwrap();
+        for _ in 0..10 {
+            receiver.recv().await.unwrap();
+        }
+        let elapsed = start.elapsed().as_secs_f64();
+        assert!((0.099..0.101).contains(&elapsed));
+        // after the consumer has been blocked, only 50 ms are caught up (plus
+        // the chunks which are already waiting in the channels)
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        let start = TokioInstant::now();
+        for _ in 0..20 {
+            receiver.recv().await.unwrap();
+        }
+        let elapsed = start.elapsed().as_secs_f64();
+        assert!((0.119..0.121).contains(&elapsed));
+    }
+    #[tokio::test]
+    async fn test_splitter() {
+        let (sender, sender_connector) = new_sender::<Signal<f64>>();
+        let splitter = Splitter::<f64>::new(&[BranchPolicy::Lossless, BranchPolicy::Lossy]);
+        let (mut lossless, lossless_connector) = new_receiver::<Signal<f64>>();
+        let (mut lossy, lossy_connector) = new_receiver::<Signal<f64>>();
+        splitter.feed_from(&sender_connector);
+        splitter.output(0).feed_into(&lossless_connector);
+        splitter.output(1).feed_into(&lossy_connector);
+        let samples = |value: f64| Signal::Samples {
+            sample_rate: 1.0,
+            chunk: Chunk::from(vec![value]),
+        };
+        for i in 0..3 {
+            sender.send(samples(i as f64)).await.unwrap();
+            let Signal::Samples { chunk, .. } = lossless.recv().await.unwrap()
+            else { panic!(); };
+            assert_eq!(chunk[0], i as f64);
+        }
+        let (signal, ()) = tokio::join!(lossy.recv(), async {
+            sender.send(samples(3.0)).await.unwrap();
+            lossless.recv().await.unwrap();
+        });
+        let Signal::Event(event) = signal.unwrap()
+        else { panic!(); };
+        assert!(event.as_any().is::<BufferOverflow>());
+        let (signal, ()) = tokio::join!(lossy.recv(), async {
+            sender.send(samples(4.0)).await.unwrap();
+            lossless.recv().await.unwrap();
+        });
+        let Signal::Samples { chunk, .. } = signal.unwrap()
+        else { panic!(); };
+        assert_eq!(chunk[0], 4.0);
+    }
+    #[tokio::test]
+    async fn test_splitter_event_after_overflow() {
+        let (sender, sender_connector) = new_sender::<Signal<f64>>();
+        let splitter = Splitter::<f64>::new(&[BranchPolicy::Lossless, BranchPolicy::Lossy]);
+        let (mut lossless, lossless_connector) = new_receiver::<Signal<f64>>();
+        let (mut lossy, lossy_connector) = new_receiver::<Signal<f64>>();
+        splitter.feed_from(&sender_connector);
+        splitter.output(0).feed_into(&lossless_connector);
+        splitter.output(1).feed_into(&lossy_connector);
+        sender
+            .send(Signal::Samples {
+                sample_rate: 1.0,
+                chunk: Chunk::from(vec![0.0]),
+            })
+            .await
+            .unwrap();
+        lossless.recv().await.unwrap();
+        let ((), ()) = tokio::join!(
+            async {
+                let Signal::Event(event) = lossy.recv().await.unwrap() else { panic!(); };
+                assert!(event.as_any().is::<BufferOverflow>());
+                let Signal::Event(event) = lossy.recv().await.unwrap() else { panic!(); };
+                assert!(event.as_any().is::<Flush>());
+            },
+            async {
+                sender.send_event(Flush).await.unwrap();
+                lossless.recv().await.unwrap();
+            }
+        );
+    }
+    #[tokio::test]
+    async fn test_splitter_lossy_without_consumers() {
+        let (sender, sender_connector) = new_sender::<Signal<f64>>();
+        let splitter = Splitter::<f64>::new(&[BranchPolicy::Lossless, BranchPolicy::Lossy]);
+        let (mut lossless, lossless_connector) = new_receiver::<Signal<f64>>();
+        splitter.feed_from(&sender_connector);
+        splitter.output(0).feed_into(&lossless_connector);
+        drop(splitter);
+        for i in 0..3 {
+            sender
+                .send(Signal::Samples {
+                    sample_rate: 1.0,
+                    chunk: Chunk::from(vec![i as f64]),
+                })
+                .await
+                .unwrap();
+            let Signal::Samples { chunk, .. } = lossless.recv().await.unwrap()
+            else { panic!(); };
+            assert_eq!(chunk[0], i as f64);
+        }
+    }
+    #[tokio::test]
+    async fn test_ring_tap() {
+        let (sender, sender_connector) = new_sender::<Signal<f64>>();
+        let ring_tap = RingTap::<f64>::new(2.5);
+        let (mut receiver, receiver_connector) = new_receiver::<Signal<f64>>();
+        ring_tap.feed_from(&sender_connector);
+        ring_tap.feed_into(&receiver_connector);
+        assert_eq!(ring_tap.snapshot(), Vec::<f64>::new());
+        assert_eq!(ring_tap.sample_rate(), None);
+        for i in 0..4 {
+            sender
+                .send(Signal::Samples {
+                    sample_rate: 2.0,
+                    chunk: Chunk::from(vec![2.0 * i as f64, 2.0 * i as f64 + 1.0]),
+                })
+                .await
+                .unwrap();
+            let Signal::Samples { chunk, .. } = receiver.recv().await.unwrap()
+            else { panic!(); };
+            assert_eq!(chunk.len(), 2);
+        }
+        assert_eq!(ring_tap.sample_rate(), Some(2.0));
+        assert_eq!(ring_tap.snapshot(), vec![3.0, 4.0, 5.0, 6.0, 7.0]);
+        sender
+            .send(Signal::Samples {
+                sample_rate: 4.0,
+                chunk: Chunk::from(vec![8.0]),
+            })
+            .await
+            .unwrap();
+        receiver.recv().await.unwrap();
+        assert_eq!(ring_tap.sample_rate(), Some(4.0));
+        assert_eq!(ring_tap.snapshot(), vec![8.0]);
+        ring_tap.clear();
+        assert_eq!(ring_tap.snapshot(), Vec::<f64>::new());
+    }
+    #[tokio::test]
+    async fn test_selector() {
+        let (sender1, sender1_connector) = new_sender::<Signal<f64>>();
+        let (sender2, sender2_connector) = new_sender::<Signal<f64>>();
+        let selector = Selector::<f64>::new(2);
+        let (mut receiver, receiver_connector) = new_receiver::<Signal<f64>>();
+        selector.input(0).feed_from(&sender1_connector);
+        selector.input(1).feed_from(&sender2_connector);
+        selector.feed_into(&receiver_connector);
+        let samples = |sample_rate: f64| Signal::Samples {
+            sample_rate,
+            chunk: Chunk::from(vec![0.0]),
+        };
+        sender2.send(samples(2.0)).await.unwrap();
+        sender1.send(samples(1.0)).await.unwrap();
+        let Signal::Samples { sample_rate, .. } = receiver.recv().await.unwrap()
+        else { panic!(); };
+        assert_eq!(sample_rate, 1.0);
+        selector.set_active(1);
+        assert_eq!(selector.active(), 1);
+        sender1.send(samples(1.0)).await.unwrap();
+        sender2.send(samples(2.0)).await.unwrap();
+        let Signal::Event(event) = receiver.recv().await.unwrap()
+        else { panic!(); };
+        assert!(event.as_any().is::<InputChanged>());
+        let Signal::Samples { sample_rate, .. } = receiver.recv().await.unwrap()
+        else { panic!(); };
+        assert_eq!(sample_rate, 2.0);
+    }
+    #[tokio::test]
+    async fn test_event_injector() {
+        let (sender, sender_connector) = new_sender::<Signal<f64>>();
+        let injector = EventInjector::<f64>::new();
+        let (mut receiver, receiver_connector) = new_receiver::<Signal<f64>>();
+        injector.feed_from(&sender_connector);
+        injector.feed_into(&receiver_connector);
+        let samples = |value: f64| Signal::Samples {
+            sample_rate: 1.0,
+            chunk: Chunk::from(vec![value]),
+        };
+        tokio::join!(sender.send(samples(1.0)), async {
+            let Signal::Samples { chunk, .. } = receiver.recv().await.unwrap()
+            else { panic!(); };
+            assert_eq!(chunk[0], 1.0);
+        })
+        .0
+        .unwrap();
+        injector.inject(Flush);
+        injector.inject(Disconnection);
+        let (result, ()) = tokio::join!(
+            async {
+                sender.send(samples(2.0)).await?;
+                sender.send_event(InputChanged).await
+            },
+            async {
+                let Signal::Event(event) = receiver.recv().await.unwrap() else { panic!(); };
+                assert!(event.as_any().is::<Flush>());
+                let Signal::Event(event) = receiver.recv().await.unwrap() else { panic!(); };
+                assert!(event.as_any().is::<Disconnection>());
+                let Signal::Samples { chunk, .. } = receiver.recv().await.unwrap()
+                else { panic!(); };
+                assert_eq!(chunk[0], 2.0);
+                let Signal::Event(event) = receiver.recv().await.unwrap() else { panic!(); };
+                assert!(event.as_any().is::<InputChanged>());
+            }
+        );
+        result.unwrap();
+    }
+}
@@ -0,0 +1,1507 @@
+//! Pre-assembled chains of blocks for common tasks
+//!
+//! The chains adapt to changes of the input sample rate at runtime (e.g.
+//! when the sample rate of an SDR is changed). If a chain cannot process a
+//! sample rate, it sends an [`UnsupportedSampleRate`] event and discards the
+//! affected samples.
+//!
+//! A [`Scanner`] retunes the source of its samples and stops on channels with
+//! activity.
+//!
+//! The transmit chains [`FmModulator`] and [`SsbModulator`] convert audio to
+//! complex samples at the sample rate of a transmitter (e.g. a
+//! `SoapySdrTx` block).
+
+use crate::blocks::filters::{Deemphasis, Filter, Preemphasis, TAU_50US};
+use crate::blocks::guard::SampleRateTracker;
+use crate::blocks::modulation::{AmDemod, FmDemod, FmMod, Sideband, SsbDemod};
+use crate::blocks::resampling::{Downsampler, Upsampler};
+use crate::blocks::transform::{FnBlock, FreqShifter, Limiter, Squelch};
+use crate::bufferpool::ChunkBufPool;
+use crate::flow::*;
+use crate::impl_block_trait;
+use crate::numbers::*;
+use crate::signal::*;
+
+use tokio::sync::watch;
+use tokio::task::spawn;
+
+use std::sync::Mutex;
+
+/// Types used for [`Signal::Event`]
+pub mod events {
+    use super::*;
+    /// Sent by chains when the input sample rate is too low for the
+    /// configuration of the chain
+    ///
+    /// Samples with this sample rate are discarded.
+    #[derive(Clone, Debug)]
+    pub struct UnsupportedSampleRate {
+        /// Sample rate of the input
+        pub sample_rate: f64,
+        /// Sample rate which must be exceeded
+        pub minimum: f64,
+    }
+    impl Event for UnsupportedSampleRate {
+        fn is_interrupt(&self) -> bool {
+            true
+        }
+        fn as_any(&self) -> &(dyn std::any::Any + Send + Sync) {
+            self
+        }
+    }
+    /// Sent by [`Scanner`] when it stops on a channel because the squelch
+    /// opened
+    #[derive(Clone, Debug)]
+    pub struct ActivityDetected {
+        /// Frequency of the channel in hertz
+        pub frequency: f64,
+    }
+    impl Event for ActivityDetected {
+        fn as_any(&self) -> &(dyn std::any::Any + Send + Sync) {
+            self
+        }
+    }
+    /// Sent by [`Scanner`] when it resumes scanning because the signal on the
+    /// channel has ended
+    #[derive(Clone, Debug)]
+    pub struct ActivityEnded {
+        /// Frequency of the channel in hertz
+        pub frequency: f64,
+    }
+    impl Event for ActivityEnded {
+        fn as_any(&self) -> &(dyn std::any::Any + Send + Sync) {
+            self
+        }
+    }
+}
+use events::*;
+
+/// Block which discards [`Signal::Samples`] with a sample rate not higher than
+/// `minimum` and sends an [`UnsupportedSampleRate`] event instead
+struct RateCheck<T> {
+    receiver_connector: ReceiverConnector<Signal<T>>,
+    sender_connector: SenderConnector<Signal<T>>,
+}
+
+impl_block_trait! { <T> Consumer<Signal<T>> for RateCheck<T> }
+impl_block_trait! { <T> Producer<Signal<T>> for RateCheck<T> }
+
+impl<T> RateCheck<T>
+where
+    T: Clone + Send + Sync + 'static,
+{
+    fn new(minimum: f64) -> Self {
+        let (mut receiver, receiver_connector) = new_receiver::<Signal<T>>();
+        let (sender, sender_connector) = new_sender::<Signal<T>>();
+        spawn(async move {
+            let mut tracker = SampleRateTracker::new();
+            let mut supported = true;
+            loop {
+                let Ok(signal) = receiver.recv().await else { return; };
+                if let Signal::Samples { sample_rate, .. } = signal {
+                    if tracker.update(sample_rate) {
+                        supported = sample_rate > minimum;
+                        if !supported {
+                            event!(
+                                WARN,
+                                "unsupported sample rate {sample_rate} (must exceed {minimum})"
+                            );
+                            let Ok(()) = sender
+                                .send(Signal::new_event(UnsupportedSampleRate {
+                                    sample_rate,
+                                    minimum,
+                                }))
+                                .await
+                            else { return; };
+                        }
+                    }
+                    if !supported {
+                        continue;
+                    }
+                }
+                let Ok(()) = sender.send(signal).await else { return; };
+            }
+        });
+        Self {
+            receiver_connector,
+            sender_connector,
+        }
+    }
+}
+
+/// Block which compensates the gain of a [`Downsampler`] with given (maximum)
+/// output rate (which depends on the input sample rate)
+fn downsampler_gain<Flt: Float>(output_rate: f64) -> FnBlock<Complex<Flt>> {
+    FnBlock::new(move |sample_rate, input, output| {
+        let gain: Flt = flt!((output_rate / sample_rate).min(1.0).sqrt());
+        output.extend(input.iter().map(|&sample| sample * gain));
+    })
+}
+
+/// Block which compensates the gain of an [`Upsampler`] with given output rate
+/// (which depends on the input sample rate)
+fn upsampler_gain<Flt: Float>(output_rate: f64) -> FnBlock<Complex<Flt>> {
+    FnBlock::new(move |sample_rate, input, output| {
+        let gain: Flt = flt!((output_rate / sample_rate).sqrt());
+        output.extend(input.iter().map(|&sample| sample * gain));
+    })
+}
+
+/// Intermediate sample rate of [`WbfmReceiver`]
+const WBFM_IF_RATE: f64 = 256000.0;
+/// Bandwidth of the channel filter of [`WbfmReceiver`]
+const WBFM_BANDWIDTH: f64 = 200000.0;
+/// Frequency deviation of broadcast FM
+const WBFM_DEVIATION: f64 = 75000.0;
+/// Upper cut-off frequency of broadcast FM audio
+const WBFM_AUDIO_CUTOFF: f64 = 15000.0;
+
+/// Broadcast FM (WBFM) receiver
+///
+/// This block is composed of a [`FreqShifter`], which moves the channel at
+/// the given offset to zero frequency, a [`Downsampler`] and channel
+/// [`Filter`] reducing the sample rate to an intermediate rate, an
+/// [`FmDemod`] with 75 kHz deviation, an audio [`Filter`] (15 kHz),
+/// [`Deemphasis`], and a final [`Downsampler`] to the audio rate (with its
+/// gain compensated).
+///
+/// The intermediate rate and all filters are derived from the sample rate of
+/// the received [`Signal::Samples`], which may change at runtime. Sample
+/// rates not higher than 200 kHz (or the audio rate) are not supported and
+/// result in an [`UnsupportedSampleRate`] event.
+///
+/// The audio output (mono) is stored in the real part of the output samples,
+/// while the imaginary part is zero. Full deviation corresponds to an
+/// amplitude of `1.0`.
+pub struct WbfmReceiver<Flt> {
+    rate_check: RateCheck<Complex<Flt>>,
+    freq_shifter: FreqShifter<Flt>,
+    deemphasis: Deemphasis<Flt>,
+    audio_downsampler: Downsampler<Flt>,
+}
+
+impl<Flt> Consumer<Signal<Complex<Flt>>> for WbfmReceiver<Flt> {
+    fn receiver_connector(&self) -> &ReceiverConnector<Signal<Complex<Flt>>> {
+        self.rate_check.receiver_connector()
+    }
+}
+
+impl<Flt> Producer<Signal<Complex<Flt>>> for WbfmReceiver<Flt> {
+    fn sender_connector(&self) -> &SenderConnector<Signal<Complex<Flt>>> {
+        self.audio_downsampler.sender_connector()
+    }
+}
+
+impl<Flt> WbfmReceiver<Flt>
+where
+    Flt: Float,
+{
+    /// Create new `WbfmReceiver` for given `input_rate`, `channel_offset`
+    /// (frequency of the channel relative to the center of the input), and
+    /// `audio_rate`, all in hertz
+    ///
+    /// The input sample rate must be higher than 200 kHz. It is only used to
+    /// check the configuration, as the chain adapts to the sample rate of the
+    /// received [`Signal::Samples`].
+    pub fn new(input_rate: f64, channel_offset: f64, audio_rate: f64) -> Self {
+        assert!(
+            input_rate > WBFM_BANDWIDTH,
+            "input sample rate must be higher than channel bandwidth"
+        );
+        assert!(audio_rate > 0.0, "audio sample rate must be positive");
+        let rate_check = RateCheck::<Complex<Flt>>::new(WBFM_BANDWIDTH.max(audio_rate));
+        let freq_shifter = FreqShifter::<Flt>::with_shift(-channel_offset);
+        freq_shifter.feed_from(&rate_check);
+        let if_downsampler =
+            Downsampler::<Flt>::with_max_output_rate(16384, WBFM_IF_RATE, WBFM_BANDWIDTH);
+        if_downsampler.feed_from(&freq_shifter);
+        let channel_filter = Filter::<Flt>::new(|_, freq| {
+            if freq.abs() <= WBFM_BANDWIDTH / 2.0 {
+                Complex::from(1.0)
+            } else {
+                Complex::from(0.0)
+            }
+        });
+        channel_filter.feed_from(&if_downsampler);
+        let demodulator = FmDemod::<Flt>::new(WBFM_DEVIATION);
+        demodulator.feed_from(&channel_filter);
+        let audio_filter = Filter::<Flt>::new(|_, freq| {
+            if freq.abs() <= WBFM_AUDIO_CUTOFF {
+                Complex::from(1.0)
+            } else {
+                Complex::from(0.0)
+            }
+        });
+        audio_filter.feed_from(&demodulator);
+        let deemphasis = Deemphasis::<Flt>::new(TAU_50US);
+        deemphasis.feed_from(&audio_filter);
+        let volume = downsampler_gain::<Flt>(audio_rate);
+        volume.feed_from(&deemphasis);
+        let audio_bandwidth = (2.0 * WBFM_AUDIO_CUTOFF).min(0.9 * audio_rate);
+        let audio_downsampler = Downsampler::<Flt>::new(4096, audio_rate, audio_bandwidth);
+        audio_downsampler.feed_from(&volume);
+        Self {
+            rate_check,
+            freq_shifter,
+            deemphasis,
+            audio_downsampler,
+        }
+    }
+    /// Get frequency of received channel relative to the center of the input
+    pub fn channel_offset(&self) -> f64 {
+        -self.freq_shifter.shift()
+    }
+    /// Set frequency of received channel relative to the center of the input
+    pub fn set_channel_offset(&self, channel_offset: f64) {
+        self.freq_shifter.set_shift(-channel_offset);
+    }
+    /// Get de-emphasis time constant in seconds
+    pub fn deemphasis(&self) -> f64 {
+        self.deemphasis.tau()
+    }
+    /// Set de-emphasis time constant in seconds (e.g. [`TAU_50US`] or
+    /// [`TAU_75US`])
+    ///
+    /// [`TAU_75US`]: crate::blocks::filters::TAU_75US
+    pub fn set_deemphasis(&self, tau: f64) {
+        self.deemphasis.set_tau(tau);
+    }
+}
+
+/// Channel spacing and deviation used by [`NfmReceiver`]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum NfmBandwidth {
+    /// 12.5 kHz channel spacing with 2.5 kHz deviation
+    Narrow,
+    /// 25 kHz channel spacing with 5 kHz deviation
+    Wide,
+}
+
+impl NfmBandwidth {
+    /// Channel spacing in hertz
+    pub fn channel_spacing(self) -> f64 {
+        match self {
+            NfmBandwidth::Narrow => 12500.0,
+            NfmBandwidth::Wide => 25000.0,
+        }
+    }
+    /// Frequency deviation in hertz
+    pub fn deviation(self) -> f64 {
+        match self {
+            NfmBandwidth::Narrow => 2500.0,
+            NfmBandwidth::Wide => 5000.0,
+        }
+    }
+}
+
+/// Intermediate sample rate of [`NfmReceiver`]
+const NFM_IF_RATE: f64 = 48000.0;
+/// Lower cut-off frequency of narrowband FM audio (removing CTCSS tones)
+const NFM_AUDIO_LOW: f64 = 300.0;
+/// Upper cut-off frequency of narrowband FM audio
+const NFM_AUDIO_HIGH: f64 = 3000.0;
+/// De-emphasis time constant of narrowband FM
+const NFM_TAU: f64 = 750e-6;
+
+/// Narrowband FM (NFM) receiver
+///
+/// This block is composed of a [`FreqShifter`], which moves the channel at
+/// the given offset to zero frequency, a [`Downsampler`] and channel
+/// [`Filter`] (according to the [`NfmBandwidth`]), a [`Squelch`], an
+/// [`FmDemod`], an audio [`Filter`] (300 Hz to 3 kHz), [`Deemphasis`]
+/// (750 µs), a final [`Downsampler`] to the audio rate (usually 8 to 16 kHz)
+/// with its gain compensated, and a [`Limiter`] keeping the audio below full
+/// scale.
+///
+/// The intermediate rate and all filters are derived from the sample rate of
+/// the received [`Signal::Samples`], which may change at runtime. Sample
+/// rates not higher than the channel spacing (or the audio rate) are not
+/// supported and result in an [`UnsupportedSampleRate`] event.
+///
+/// The squelch thresholds refer to the power of the channel-filtered
+/// samples, where 0 dB corresponds to a power of `1.0`. Initially, the
+/// squelch is always open.
+///
+/// The audio output (mono) is stored in the real part of the output samples,
+/// while the imaginary part is zero. Full deviation corresponds to an
+/// amplitude of `1.0` (before de-emphasis).
+pub struct NfmReceiver<Flt> {
+    rate_check: RateCheck<Complex<Flt>>,
+    freq_shifter: FreqShifter<Flt>,
+    squelch: Squelch<Flt>,
+    limiter: Limiter<Flt>,
+}
+
+impl<Flt> Consumer<Signal<Complex<Flt>>> for NfmReceiver<Flt> {
+    fn receiver_connector(&self) -> &ReceiverConnector<Signal<Complex<Flt>>> {
+        self.rate_check.receiver_connector()
+    }
+}
+
+impl<Flt> Producer<Signal<Complex<Flt>>> for NfmReceiver<Flt> {
+    fn sender_connector(&self) -> &SenderConnector<Signal<Complex<Flt>>> {
+        self.limiter.sender_connector()
+    }
+}
+
+impl<Flt> NfmReceiver<Flt>
+where
+    Flt: Float,
+{
+    /// Create new `NfmReceiver` for given `input_rate`, `channel_offset`
+    /// (frequency of the channel relative to the center of the input), and
+    /// `audio_rate`, all in hertz
+    ///
+    /// The input sample rate must be higher than the channel spacing. It is
+    /// only used to check the configuration, as the chain adapts to the
+    /// sample rate of the received [`Signal::Samples`].
+    pub fn new(
+        input_rate: f64,
+        channel_offset: f64,
+        audio_rate: f64,
+        bandwidth: NfmBandwidth,
+    ) -> Self {
+        let channel_spacing = bandwidth.channel_spacing();
+        assert!(
+            input_rate > channel_spacing,
+            "input sample rate must be higher than channel spacing"
+        );
+        assert!(
+            audio_rate > 2.0 * NFM_AUDIO_HIGH,
+            "audio sample rate must be higher than 6 kHz"
+        );
+        let rate_check = RateCheck::<Complex<Flt>>::new(channel_spacing.max(audio_rate));
+        let freq_shifter = FreqShifter::<Flt>::with_shift(-channel_offset);
+        freq_shifter.feed_from(&rate_check);
+        let if_downsampler =
+            Downsampler::<Flt>::with_max_output_rate(4096, NFM_IF_RATE, channel_spacing);
+        if_downsampler.feed_from(&freq_shifter);
+        let channel_filter = Filter::<Flt>::new(move |_, freq| {
+            if freq.abs() <= channel_spacing / 2.0 {
+                Complex::from(1.0)
+            } else {
+                Complex::from(0.0)
+            }
+        });
+        channel_filter.feed_from(&if_downsampler);
+        let squelch = Squelch::<Flt>::new(f64::NEG_INFINITY, f64::NEG_INFINITY, 0.2);
+        squelch.feed_from(&channel_filter);
+        let demodulator = FmDemod::<Flt>::new(bandwidth.deviation());
+        demodulator.feed_from(&squelch);
+        let audio_filter = Filter::<Flt>::new(|_, freq| {
+            if freq.abs() >= NFM_AUDIO_LOW && freq.abs() <= NFM_AUDIO_HIGH {
+                Complex::from(1.0)
+            } else {
+                Complex::from(0.0)
+            }
+        });
+        audio_filter.feed_from(&demodulator);
+        let deemphasis = Deemphasis::<Flt>::new(NFM_TAU);
+        deemphasis.feed_from(&audio_filter);
+        let volume = downsampler_gain::<Flt>(audio_rate);
+        volume.feed_from(&deemphasis);
+        let audio_downsampler = Downsampler::<Flt>::new(1024, audio_rate, 2.0 * NFM_AUDIO_HIGH);
+        audio_downsampler.feed_from(&volume);
+        let limiter = Limiter::<Flt>::new(-1.0, 0.002, 0.05);
+        limiter.feed_from(&audio_downsampler);
+        Self {
+            rate_check,
+            freq_shifter,
+            squelch,
+            limiter,
+        }
+    }
+    /// Get frequency of received channel relative to the center of the input
+    pub fn channel_offset(&self) -> f64 {
+        -self.freq_shifter.shift()
+    }
+    /// Set frequency of received channel relative to the center of the input
+    pub fn set_channel_offset(&self, channel_offset: f64) {
+        self.freq_shifter.set_shift(-channel_offset);
+    }
+    /// Get open and close thresholds of the squelch in decibels
+    pub fn squelch_db(&self) -> (f64, f64) {
+        self.squelch.threshold_db()
+    }
+    /// Set open and close thresholds of the squelch in decibels
+    ///
+    /// Use [`f64::NEG_INFINITY`] for both thresholds to disable the squelch.
+    pub fn set_squelch_db(&self, open_db: f64, close_db: f64) {
+        self.squelch.set_threshold_db(open_db, close_db);
+    }
+    /// Get [`watch::Receiver`] indicating whether the squelch is open
+    pub fn squelch_open(&self) -> watch::Receiver<bool> {
+        self.squelch.open()
+    }
+}
+
+/// Demodulation mode of a [`Receiver`]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Mode {
+    /// Broadcast FM with 200 kHz bandwidth, 75 kHz deviation, and 50 µs
+    /// de-emphasis (see [`WbfmReceiver`])
+    Wbfm,
+    /// Narrowband FM with 12.5 kHz channel spacing, 2.5 kHz deviation, and
+    /// 750 µs de-emphasis (see [`NfmReceiver`])
+    Nfm,
+    /// AM with 10 kHz bandwidth
+    Am,
+    /// Upper sideband (300 Hz to 3 kHz above the channel frequency)
+    Usb,
+    /// Lower sideband (300 Hz to 3 kHz below the channel frequency)
+    Lsb,
+    /// CW with 500 Hz bandwidth, resulting in a 700 Hz tone
+    Cw,
+}
+
+/// Intermediate sample rate of [`Receiver`] for modes other than
+/// [`Mode::Wbfm`] (unless the audio rate is higher)
+const NARROW_IF_RATE: f64 = 48000.0;
+/// Frequency of the audio tone of [`Mode::Cw`]
+const CW_TONE: f64 = 700.0;
+
+impl Mode {
+    /// Lower and upper edge of the channel filter in hertz, relative to the
+    /// channel frequency
+    fn passband(self) -> (f64, f64) {
+        match self {
+            Mode::Wbfm => (-WBFM_BANDWIDTH / 2.0, WBFM_BANDWIDTH / 2.0),
+            Mode::Nfm => {
+                let spacing = NfmBandwidth::Narrow.channel_spacing();
+                (-spacing / 2.0, spacing / 2.0)
+            }
+            Mode::Am => (-5000.0, 5000.0),
+            Mode::Usb => (300.0, 3000.0),
+            Mode::Lsb => (-3000.0, -300.0),
+            Mode::Cw => (-250.0, 250.0),
+        }
+    }
+    /// Bandwidth of the channel filter in hertz
+    pub fn bandwidth(self) -> f64 {
+        let (low, high) = self.passband();
+        high - low
+    }
+}
+
+/// Demodulation stage of a [`Receiver`] for a particular [`Mode`]
+///
+/// The stage ends when it is dropped and disconnected.
+struct ModeChain<Flt> {
+    mode: Mode,
+    rate_check: RateCheck<Complex<Flt>>,
+    audio_downsampler: Downsampler<Flt>,
+}
+
+impl<Flt> ModeChain<Flt>
+where
+    Flt: Float,
+{
+    fn new(mode: Mode, audio_rate: f64) -> Self {
+        let (low, high) = mode.passband();
+        // two-sided bandwidth which must be kept when reducing the sample rate
+        let bandwidth = 2.0 * low.abs().max(high.abs());
+        let rate_check = RateCheck::<Complex<Flt>>::new(bandwidth.max(audio_rate));
+        let (if_rate, if_chunk_len) = match mode {
+            Mode::Wbfm => (WBFM_IF_RATE, 16384),
+            _ => (NARROW_IF_RATE.max(audio_rate), 4096),
+        };
+        // the gain matters for demodulators other than FM
+        let if_gain = downsampler_gain::<Flt>(if_rate);
+        if_gain.feed_from(&rate_check);
+        let if_downsampler =
+            Downsampler::<Flt>::with_max_output_rate(if_chunk_len, if_rate, bandwidth);
+        if_downsampler.feed_from(&if_gain);
+        let channel_filter = Filter::<Flt>::new(move |_, freq| {
+            if freq >= low && freq <= high {
+                Complex::from(1.0)
+            } else {
+                Complex::from(0.0)
+            }
+        });
+        channel_filter.feed_from(&if_downsampler);
+        let volume = downsampler_gain::<Flt>(audio_rate);
+        let audio_cutoff = match mode {
+            Mode::Wbfm | Mode::Nfm => {
+                let (deviation, audio_low, audio_high, tau) = match mode {
+                    Mode::Wbfm => (WBFM_DEVIATION, 0.0, WBFM_AUDIO_CUTOFF, TAU_50US),
+                    _ => (
+                        NfmBandwidth::Narrow.deviation(),
+                        NFM_AUDIO_LOW,
+                        NFM_AUDIO_HIGH,
+                        NFM_TAU,
+                    ),
+                };
+                let demodulator = FmDemod::<Flt>::new(deviation);
+                demodulator.feed_from(&channel_filter);
+                let audio_filter = Filter::<Flt>::new(move |_, freq| {
+                    if freq.abs() >= audio_low && freq.abs() <= audio_high {
+                        Complex::from(1.0)
+                    } else {
+                        Complex::from(0.0)
+                    }
+                });
+                audio_filter.feed_from(&demodulator);
+                let deemphasis = Deemphasis::<Flt>::new(tau);
+                deemphasis.feed_from(&audio_filter);
+                volume.feed_from(&deemphasis);
+                audio_high
+            }
+            Mode::Am => {
+                let demodulator = AmDemod::<Flt>::with_dc_blocker(0.999);
+                demodulator.feed_from(&channel_filter);
+                volume.feed_from(&demodulator);
+                high
+            }
+            Mode::Usb | Mode::Lsb => {
+                let sideband = match mode {
+                    Mode::Usb => Sideband::Usb,
+                    _ => Sideband::Lsb,
+                };
+                let demodulator = SsbDemod::<Flt>::new(sideband, 63);
+                demodulator.feed_from(&channel_filter);
+                volume.feed_from(&demodulator);
+                high.abs().max(low.abs())
+            }
+            Mode::Cw => {
+                let demodulator = SsbDemod::<Flt>::with_bfo_offset(Sideband::Usb, 63, -CW_TONE);
+                demodulator.feed_from(&channel_filter);
+                volume.feed_from(&demodulator);
+                CW_TONE + high
+            }
+        };
+        let audio_bandwidth = (2.0 * audio_cutoff).min(0.9 * audio_rate);
+        let audio_downsampler = Downsampler::<Flt>::new(1024, audio_rate, audio_bandwidth);
+        audio_downsampler.feed_from(&volume);
+        Self {
+            mode,
+            rate_check,
+            audio_downsampler,
+        }
+    }
+}
+
+/// Receiver with selectable demodulation [`Mode`]
+///
+/// This block is composed of a [`FreqShifter`], which moves the channel at
+/// the given offset to zero frequency, a demodulation stage, and a
+/// [`Limiter`] keeping the audio below full scale. The demodulation stage
+/// consists of a [`Downsampler`] and channel [`Filter`] (according to the
+/// [bandwidth] of the mode), the demodulator ([`FmDemod`] with audio
+/// [`Filter`] and [`Deemphasis`], [`AmDemod`], or [`SsbDemod`]), and a final
+/// [`Downsampler`] to the audio rate (with its gain compensated).
+///
+/// When the mode is changed with [`Receiver::set_mode`], the demodulation
+/// stage is replaced while the `FreqShifter` and the `Limiter` (and thus the
+/// connections of the block) are kept. Samples still in the previous stage
+/// are dropped, the new stage starts with a cleared state, and a
+/// [`Disconnection`] event (which is an [interrupting] event) is sent before
+/// the audio of the new mode.
+///
+/// The intermediate rate and all filters are derived from the sample rate of
+/// the received [`Signal::Samples`], which may change at runtime. Sample
+/// rates too low for the bandwidth of the mode (or not higher than the
+/// audio rate) are not supported and result in an [`UnsupportedSampleRate`]
+/// event.
+///
+/// The audio output (mono) is stored in the real part of the output samples,
+/// while the imaginary part is zero.
+///
+/// [bandwidth]: Mode::bandwidth
+/// [interrupting]: Event::is_interrupt
+pub struct Receiver<Flt> {
+    audio_rate: f64,
+    freq_shifter: FreqShifter<Flt>,
+    chain: Mutex<ModeChain<Flt>>,
+    limiter: Limiter<Flt>,
+}
+
+impl<Flt> Consumer<Signal<Complex<Flt>>> for Receiver<Flt> {
+    fn receiver_connector(&self) -> &ReceiverConnector<Signal<Complex<Flt>>> {
+        self.freq_shifter.receiver_connector()
+    }
+}
+
+impl<Flt> Producer<Signal<Complex<Flt>>> for Receiver<Flt> {
+    fn sender_connector(&self) -> &SenderConnector<Signal<Complex<Flt>>> {
+        self.limiter.sender_connector()
+    }
+}
+
+impl<Flt> Receiver<Flt>
+where
+    Flt: Float,
+{
+    /// Create new `Receiver` for given `channel_offset` (frequency of the
+    /// channel relative to the center of the input) and `audio_rate`, both in
+    /// hertz, using given demodulation `mode`
+    pub fn new(channel_offset: f64, audio_rate: f64, mode: Mode) -> Self {
+        assert!(audio_rate > 0.0, "audio sample rate must be positive");
+        let freq_shifter = FreqShifter::<Flt>::with_shift(-channel_offset);
+        let chain = ModeChain::<Flt>::new(mode, audio_rate);
+        chain.rate_check.feed_from(&freq_shifter);
+        let limiter = Limiter::<Flt>::new(-1.0, 0.002, 0.05);
+        limiter.feed_from(&chain.audio_downsampler);
+        Self {
+            audio_rate,
+            freq_shifter,
+            chain: Mutex::new(chain),
+            limiter,
+        }
+    }
+    /// Get frequency of received channel relative to the center of the input
+    pub fn channel_offset(&self) -> f64 {
+        -self.freq_shifter.shift()
+    }
+    /// Set frequency of received channel relative to the center of the input
+    pub fn set_channel_offset(&self, channel_offset: f64) {
+        self.freq_shifter.set_shift(-channel_offset);
+    }
+    /// Get demodulation mode
+    pub fn mode(&self) -> Mode {
+        self.chain.lock().unwrap().mode
+    }
+    /// Set demodulation mode
+    ///
+    /// Does nothing if the mode is unchanged. Otherwise, the demodulation
+    /// stage is replaced as described in the [type-level documentation].
+    ///
+    /// [type-level documentation]: Receiver
+    pub fn set_mode(&self, mode: Mode) {
+        let mut chain = self.chain.lock().unwrap();
+        if chain.mode == mode {
+            return;
+        }
+        let new_chain = ModeChain::<Flt>::new(mode, self.audio_rate);
+        new_chain.rate_check.feed_from(&self.freq_shifter);
+        self.limiter.feed_from(&new_chain.audio_downsampler);
+        // dropping the previous stage disconnects it from the `FreqShifter`
+        *chain = new_chain;
+    }
+}
+
+/// State of a [`Scanner`]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ScanState {
+    /// Stepping through the channels
+    Scanning,
+    /// Stopped on a channel with activity
+    Locked,
+}
+
+/// Progress of a [`Scanner`] on the current channel
+#[derive(Clone, Copy, Debug)]
+enum ScanPhase {
+    /// Waiting for the [`CenterFrequency`] event confirming the retune
+    Tuning,
+    /// Measuring the power for the dwell time
+    Dwelling { elapsed: usize },
+    /// Squelch is open or the signal ended less than the resume delay ago
+    Locked { quiet: usize },
+}
+
+/// Scanner which steps through a list of channels and stops on activity
+///
+/// The block receives the samples of a tunable source (e.g. a
+/// `SoapySdrRx` block) and retunes it by calling the `tune` closure passed
+/// to [`Scanner::new`] with the center frequency of the next channel. Samples
+/// are discarded until the source confirms the new frequency with a
+/// [`CenterFrequency`] event (which `SoapySdrRx` sends after
+/// `set_frequency`), such that samples still in the pipeline are not
+/// attributed to the wrong channel.
+///
+/// On each channel, the power of the samples within the channel bandwidth
+/// (around zero frequency) is measured for the [dwell time]. If it exceeds
+/// the open threshold of the squelch, the scanner stops, sends an
+/// [`ActivityDetected`] event, and passes the channel-filtered samples on.
+/// Once the power has been below the close threshold for the [resume delay],
+/// an [`ActivityEnded`] event is sent and scanning continues with the next
+/// channel. While scanning, zeros are emitted instead of the received
+/// samples. Events are sent after the chunk during which the state changed.
+///
+/// The thresholds refer to the power of the channel-filtered samples, where
+/// 0 dB corresponds to a power of `1.0`. An [interrupting] event resets the
+/// power estimate.
+///
+/// [dwell time]: Scanner::set_dwell_time
+/// [resume delay]: Scanner::set_resume_delay
+/// [interrupting]: Event::is_interrupt
+pub struct Scanner<Flt> {
+    channel_filter: Filter<Flt>,
+    sender_connector: SenderConnector<Signal<Complex<Flt>>>,
+    channels: watch::Sender<Vec<f64>>,
+    dwell_time: watch::Sender<f64>,
+    resume_delay: watch::Sender<f64>,
+    thresholds: watch::Sender<(f64, f64)>,
+    frequency: watch::Receiver<f64>,
+    state: watch::Receiver<ScanState>,
+}
+
+impl<Flt> Consumer<Signal<Complex<Flt>>> for Scanner<Flt> {
+    fn receiver_connector(&self) -> &ReceiverConnector<Signal<Complex<Flt>>> {
+        self.channel_filter.receiver_connector()
+    }
+}
+
+impl<Flt> Producer<Signal<Complex<Flt>>> for Scanner<Flt> {
+    fn sender_connector(&self) -> &SenderConnector<Signal<Complex<Flt>>> {
+        &self.sender_connector
+    }
+}
+
+impl<Flt> Scanner<Flt>
+where
+    Flt: Float,
+{
+    /// Create new `Scanner` for given channel frequencies and `bandwidth` in
+    /// hertz, squelch thresholds in decibels, and `tune` closure
+    ///
+    /// The dwell time is initially 50 ms and the resume delay 2 s. The first
+    /// channel is tuned to immediately. `tune` is called from the task of
+    /// the block and should return quickly.
+    pub fn new<F>(channels: Vec<f64>, bandwidth: f64, open_db: f64, close_db: f64, tune: F) -> Self
+    where
+        F: FnMut(f64) + Send + 'static,
+    {
+        assert!(!channels.is_empty(), "channel list must not be empty");
+        assert!(
+            close_db <= open_db,
+            "close threshold must not exceed open threshold"
+        );
+        let mut tune = tune;
+        let channel_filter = Filter::<Flt>::new(move |_, freq| {
+            if freq.abs() <= bandwidth / 2.0 {
+                Complex::from(1.0)
+            } else {
+                Complex::from(0.0)
+            }
+        });
+        let (mut receiver, receiver_connector) = new_receiver::<Signal<Complex<Flt>>>();
+        let (sender, sender_connector) = new_sender::<Signal<Complex<Flt>>>();
+        channel_filter.feed_into(&receiver_connector);
+        let (channels_send, mut channels_recv) = watch::channel(channels);
+        let (dwell_time_send, dwell_time_recv) = watch::channel(0.05);
+        let (resume_delay_send, resume_delay_recv) = watch::channel(2.0);
+        let (thresholds_send, thresholds_recv) = watch::channel((open_db, close_db));
+        let mut index = 0;
+        let mut target = channels_recv.borrow_and_update()[index];
+        let (frequency_send, frequency) = watch::channel(target);
+        let (state_send, state) = watch::channel(ScanState::Scanning);
+        spawn(async move {
+            let to_power = |db: f64| -> Flt { flt!(from_db(db)) };
+            let mut buf_pool = ChunkBufPool::<Complex<Flt>>::new();
+            let mut phase = ScanPhase::Tuning;
+            let mut power: Flt = Flt::zero();
+            tune(target);
+            loop {
+                let Ok(signal) = receiver.recv().await else { return; };
+                match signal {
+                    Signal::Samples {
+                        sample_rate,
+                        chunk: input_chunk,
+                    } => {
+                        let (open_db, close_db) = *thresholds_recv.borrow();
+                        let (open_power, close_power) = (to_power(open_db), to_power(close_db));
+                        let dwell_len = (*dwell_time_recv.borrow() * sample_rate).round() as usize;
+                        let resume_len =
+                            (*resume_delay_recv.borrow() * sample_rate).round() as usize;
+                        let coef: Flt = flt!(1.0 - (-(0.01 * sample_rate).recip()).exp());
+                        let mut event: Option<Signal<Complex<Flt>>> = None;
+                        let mut output_chunk = buf_pool.get_with_capacity(input_chunk.len());
+                        for &sample in input_chunk.iter() {
+                            let mut next_channel = false;
+                            match phase {
+                                ScanPhase::Tuning => (),
+                                ScanPhase::Dwelling { elapsed } => {
+                                    power += (sample.norm_sqr() - power) * coef;
+                                    if power > open_power {
+                                        phase = ScanPhase::Locked { quiet: 0 };
+                                        state_send.send_replace(ScanState::Locked);
+                                        event = Some(Signal::new_event(ActivityDetected {
+                                            frequency: target,
+                                        }));
+                                    } else if elapsed + 1 >= dwell_len {
+                                        next_channel = true;
+                                    } else {
+                                        phase = ScanPhase::Dwelling {
+                                            elapsed: elapsed + 1,
+                                        };
+                                    }
+                                }
+                                ScanPhase::Locked { quiet } => {
+                                    power += (sample.norm_sqr() - power) * coef;
+                                    if power >= close_power {
+                                        phase = ScanPhase::Locked { quiet: 0 };
+                                    } else if quiet + 1 >= resume_len {
+                                        next_channel = true;
+                                        state_send.send_replace(ScanState::Scanning);
+                                        event = Some(Signal::new_event(ActivityEnded {
+                                            frequency: target,
+                                        }));
+                                    } else {
+                                        phase = ScanPhase::Locked { quiet: quiet + 1 };
+                                    }
+                                }
+                            }
+                            if next_channel {
+                                let channels = channels_recv.borrow_and_update();
+                                index = (index + 1) % channels.len();
+                                target = channels[index];
+                                drop(channels);
+                                tune(target);
+                                frequency_send.send_replace(target);
+                                phase = ScanPhase::Tuning;
+                            }
+                            match phase {
+                                ScanPhase::Locked { .. } => output_chunk.push(sample),
+                                _ => output_chunk.push(Complex::from(Flt::zero())),
+                            }
+                        }
+                        let Ok(()) = sender
+                            .send(Signal::Samples {
+                                sample_rate,
+                                chunk: output_chunk.finalize(),
+                            })
+                            .await
+                        else { return; };
+                        if let Some(event) = event {
+                            let Ok(()) = sender.send(event).await else { return; };
+                        }
+                    }
+                    Signal::Event(event) => {
+                        if event.is_interrupt() {
+                            power = Flt::zero();
+                        }
+                        if let Some(&CenterFrequency(frequency)) =
+                            event.as_any().downcast_ref::<CenterFrequency>()
+                        {
+                            if let ScanPhase::Tuning = phase {
+                                if (frequency - target).abs() <= bandwidth / 2.0 {
+                                    phase = ScanPhase::Dwelling { elapsed: 0 };
+                                    power = Flt::zero();
+                                }
+                            }
+                        }
+                        let Ok(()) = sender.send(Signal::Event(event)).await else { return; };
+                    }
+                }
+            }
+        });
+        Self {
+            channel_filter,
+            sender_connector,
+            channels: channels_send,
+            dwell_time: dwell_time_send,
+            resume_delay: resume_delay_send,
+            thresholds: thresholds_send,
+            frequency,
+            state,
+        }
+    }
+    /// Get channel frequencies in hertz
+    pub fn channels(&self) -> Vec<f64> {
+        self.channels.borrow().clone()
+    }
+    /// Set channel frequencies in hertz
+    ///
+    /// The new list is used when stepping to the next channel.
+    pub fn set_channels(&self, channels: Vec<f64>) {
+        assert!(!channels.is_empty(), "channel list must not be empty");
+        self.channels.send_replace(channels);
+    }
+    /// Set channel frequencies to the range from `start` to `stop` (inclusive)
+    /// with given `step`, all in hertz
+    pub fn set_range(&self, start: f64, stop: f64, step: f64) {
+        assert!(step > 0.0, "step must be positive");
+        assert!(stop >= start, "stop frequency must not be lower than start");
+        let count = ((stop - start) / step + 1e-9).floor() as usize + 1;
+        self.set_channels((0..count).map(|i| start + i as f64 * step).collect());
+    }
+    /// Get dwell time in seconds
+    pub fn dwell_time(&self) -> f64 {
+        *self.dwell_time.borrow()
+    }
+    /// Set time in seconds for which each channel is checked for activity
+    pub fn set_dwell_time(&self, dwell_time: f64) {
+        assert!(dwell_time >= 0.0, "dwell time must not be negative");
+        self.dwell_time.send_replace(dwell_time);
+    }
+    /// Get resume delay in seconds
+    pub fn resume_delay(&self) -> f64 {
+        *self.resume_delay.borrow()
+    }
+    /// Set time in seconds after the end of a signal before scanning is
+    /// resumed
+    pub fn set_resume_delay(&self, resume_delay: f64) {
+        assert!(resume_delay >= 0.0, "resume delay must not be negative");
+        self.resume_delay.send_replace(resume_delay);
+    }
+    /// Get open and close thresholds of the squelch in decibels
+    pub fn squelch_db(&self) -> (f64, f64) {
+        *self.thresholds.borrow()
+    }
+    /// Set open and close thresholds of the squelch in decibels
+    pub fn set_squelch_db(&self, open_db: f64, close_db: f64) {
+        assert!(
+            close_db <= open_db,
+            "close threshold must not exceed open threshold"
+        );
+        self.thresholds.send_replace((open_db, close_db));
+    }
+    /// Get [`watch::Receiver`] of the frequency of the current channel in
+    /// hertz
+    pub fn frequency(&self) -> watch::Receiver<f64> {
+        self.frequency.clone()
+    }
+    /// Get [`watch::Receiver`] of the [`ScanState`]
+    pub fn state(&self) -> watch::Receiver<ScanState> {
+        self.state.clone()
+    }
+}
+
+/// Intermediate sample rate of [`FmModulator`] and [`SsbModulator`] (at
+/// least)
+const TX_IF_RATE: f64 = 48000.0;
+
+/// FM transmit chain
+///
+/// This block is composed of [`Preemphasis`], an audio [`Filter`], an
+/// [`Upsampler`] to an intermediate rate, an [`FmMod`], a final `Upsampler`
+/// to the output rate, and a [`FreqShifter`] placing the signal at the given
+/// offset from the center of the output. The gain of the upsamplers is
+/// compensated.
+///
+/// The audio (mono) is taken from the real part of the received
+/// [`Signal::Samples`], where an amplitude of `1.0` corresponds to full
+/// deviation (before pre-emphasis). The audio sample rate may change at
+/// runtime. It must not exceed 48 kHz (or the output rate) and must be
+/// higher than twice the audio cut-off frequency; otherwise the samples are
+/// discarded and an [`UnsupportedSampleRate`] event is sent.
+pub struct FmModulator<Flt> {
+    rate_check: RateCheck<Complex<Flt>>,
+    preemphasis: Preemphasis<Flt>,
+    modulator: FmMod<Flt>,
+    freq_shifter: FreqShifter<Flt>,
+}
+
+impl<Flt> Consumer<Signal<Complex<Flt>>> for FmModulator<Flt> {
+    fn receiver_connector(&self) -> &ReceiverConnector<Signal<Complex<Flt>>> {
+        self.rate_check.receiver_connector()
+    }
+}
+
+impl<Flt> Producer<Signal<Complex<Flt>>> for FmModulator<Flt> {
+    fn sender_connector(&self) -> &SenderConnector<Signal<Complex<Flt>>> {
+        self.freq_shifter.sender_connector()
+    }
+}
+
+impl<Flt> FmModulator<Flt>
+where
+    Flt: Float,
+{
+    /// Create new `FmModulator` for given `output_rate`, `channel_offset`
+    /// (frequency of the channel relative to the center of the output),
+    /// `deviation`, and `audio_cutoff` (all in hertz) and pre-emphasis time
+    /// constant `tau` in seconds
+    ///
+    /// Use the constants of [`NfmBandwidth`] or 75 kHz deviation with 15 kHz
+    /// audio for broadcast FM. The output rate must exceed the bandwidth of
+    /// the signal according to Carson's rule.
+    pub fn new(
+        output_rate: f64,
+        channel_offset: f64,
+        deviation: f64,
+        audio_cutoff: f64,
+        tau: f64,
+    ) -> Self {
+        let bandwidth = 2.0 * (deviation + audio_cutoff);
+        assert!(
+            output_rate > bandwidth,
+            "output sample rate must be higher than the bandwidth of the signal"
+        );
+        let audio_bandwidth = 2.0 * audio_cutoff;
+        let if_rate = (2.0 * bandwidth).max(TX_IF_RATE).min(output_rate);
+        let rate_check = RateCheck::<Complex<Flt>>::new(audio_bandwidth);
+        let preemphasis = Preemphasis::<Flt>::new(tau);
+        preemphasis.feed_from(&rate_check);
+        let audio_filter = Filter::<Flt>::new(move |_, freq| {
+            if freq.abs() <= audio_cutoff {
+                Complex::from(1.0)
+            } else {
+                Complex::from(0.0)
+            }
+        });
+        audio_filter.feed_from(&preemphasis);
+        let audio_gain = upsampler_gain::<Flt>(if_rate);
+        audio_gain.feed_from(&audio_filter);
+        let audio_upsampler = Upsampler::<Flt>::new(4096, if_rate, audio_bandwidth);
+        audio_upsampler.feed_from(&audio_gain);
+        let modulator = FmMod::<Flt>::new(deviation);
+        modulator.feed_from(&audio_upsampler);
+        let gain = upsampler_gain::<Flt>(output_rate);
+        gain.feed_from(&modulator);
+        let upsampler = Upsampler::<Flt>::new(16384, output_rate, bandwidth);
+        upsampler.feed_from(&gain);
+        let freq_shifter = FreqShifter::<Flt>::with_shift(channel_offset);
+        freq_shifter.feed_from(&upsampler);
+        Self {
+            rate_check,
+            preemphasis,
+            modulator,
+            freq_shifter,
+        }
+    }
+    /// Get frequency of the channel relative to the center of the output
+    pub fn channel_offset(&self) -> f64 {
+        self.freq_shifter.shift()
+    }
+    /// Set frequency of the channel relative to the center of the output
+    pub fn set_channel_offset(&self, channel_offset: f64) {
+        self.freq_shifter.set_shift(channel_offset);
+    }
+    /// Get frequency deviation in hertz
+    pub fn deviation(&self) -> f64 {
+        self.modulator.deviation()
+    }
+    /// Set frequency deviation in hertz
+    ///
+    /// The filters are not changed, so the deviation shouldn't be increased
+    /// beyond the value passed to [`FmModulator::new`].
+    pub fn set_deviation(&self, deviation: f64) {
+        self.modulator.set_deviation(deviation);
+    }
+    /// Get pre-emphasis time constant in seconds
+    pub fn preemphasis(&self) -> f64 {
+        self.preemphasis.tau()
+    }
+    /// Set pre-emphasis time constant in seconds
+    pub fn set_preemphasis(&self, tau: f64) {
+        self.preemphasis.set_tau(tau);
+    }
+}
+
+/// Lower cut-off frequency of SSB audio
+const SSB_AUDIO_LOW: f64 = 300.0;
+/// Upper cut-off frequency of SSB audio
+const SSB_AUDIO_HIGH: f64 = 3000.0;
+
+/// SSB transmit chain
+///
+/// This block is composed of an [`Upsampler`] to an intermediate rate, a
+/// [`Filter`] selecting the upper or lower sideband of the audio (300 Hz to
+/// 3 kHz), a final `Upsampler` to the output rate, and a [`FreqShifter`]
+/// placing the suppressed carrier at the given offset from the center of the
+/// output. The gain of the upsamplers is compensated.
+///
+/// The audio (mono) is taken from the real part of the received
+/// [`Signal::Samples`]. A tone with an amplitude of `1.0` results in an
+/// amplitude of `1.0` at the output. The audio sample rate may change at
+/// runtime. It must not exceed 48 kHz (or the output rate) and must be
+/// higher than 6 kHz; otherwise the samples are discarded and an
+/// [`UnsupportedSampleRate`] event is sent.
+pub struct SsbModulator<Flt> {
+    rate_check: RateCheck<Complex<Flt>>,
+    freq_shifter: FreqShifter<Flt>,
+}
+
+impl<Flt> Consumer<Signal<Complex<Flt>>> for SsbModulator<Flt> {
+    fn receiver_connector(&self) -> &ReceiverConnector<Signal<Complex<Flt>>> {
+        self.rate_check.receiver_connector()
+    }
+}
+
+impl<Flt> Producer<Signal<Complex<Flt>>> for SsbModulator<Flt> {
+    fn sender_connector(&self) -> &SenderConnector<Signal<Complex<Flt>>> {
+        self.freq_shifter.sender_connector()
+    }
+}
+
+impl<Flt> SsbModulator<Flt>
+where
+    Flt: Float,
+{
+    /// Create new `SsbModulator` for given `output_rate` and
+    /// `channel_offset` (frequency of the suppressed carrier relative to the
+    /// center of the output), both in hertz, and given `sideband`
+    ///
+    /// The output rate must be higher than 6 kHz.
+    pub fn new(output_rate: f64, channel_offset: f64, sideband: Sideband) -> Self {
+        let bandwidth = 2.0 * SSB_AUDIO_HIGH;
+        assert!(
+            output_rate > bandwidth,
+            "output sample rate must be higher than 6 kHz"
+        );
+        let if_rate = TX_IF_RATE.min(output_rate);
+        let rate_check = RateCheck::<Complex<Flt>>::new(bandwidth);
+        let audio_gain = upsampler_gain::<Flt>(if_rate);
+        audio_gain.feed_from(&rate_check);
+        let audio_upsampler = Upsampler::<Flt>::new(4096, if_rate, bandwidth);
+        audio_upsampler.feed_from(&audio_gain);
+        // real audio has both sidebands with half the amplitude each
+        let sideband_filter = Filter::<Flt>::new(move |_, freq| {
+            let freq = match sideband {
+                Sideband::Usb => freq,
+                Sideband::Lsb => -freq,
+            };
+            if (SSB_AUDIO_LOW..=SSB_AUDIO_HIGH).contains(&freq) {
+                Complex::from(2.0)
+            } else {
+                Complex::from(0.0)
+            }
+        });
+        sideband_filter.feed_from(&audio_upsampler);
+        let gain = upsampler_gain::<Flt>(output_rate);
+        gain.feed_from(&sideband_filter);
+        let upsampler = Upsampler::<Flt>::new(16384, output_rate, bandwidth);
+        upsampler.feed_from(&gain);
+        let freq_shifter = FreqShifter::<Flt>::with_shift(channel_offset);
+        freq_shifter.feed_from(&upsampler);
+        Self {
+            rate_check,
+            freq_shifter,
+        }
+    }
+    /// Get frequency of the suppressed carrier relative to the center of the
+    /// output
+    pub fn channel_offset(&self) -> f64 {
+        self.freq_shifter.shift()
+    }
+    /// Set frequency of the suppressed carrier relative to the center of the
+    /// output
+    pub fn set_channel_offset(&self, channel_offset: f64) {
+        self.freq_shifter.set_shift(channel_offset);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bufferpool::Chunk;
+    use crate::flow::Receiver;
+    async fn audio_rms(
+        receiver: &mut Receiver<Signal<Complex<f32>>>,
+        expected_rate: f64,
+        len: usize,
+    ) -> f32 {
+        let mut audio: Vec<f32> = Vec::new();
+        while audio.len() < len {
+            let Signal::Samples { sample_rate, chunk } = receiver.recv().await.unwrap()
+            else { panic!(); };
+            assert_eq!(sample_rate, expected_rate);
+            audio.extend(chunk.iter().map(|sample| sample.re));
+        }
+        let tail = &audio[len / 2..];
+        (tail.iter().map(|x| x * x).sum::<f32>() / tail.len() as f32).sqrt()
+    }
+    async fn skip_until_disconnection(receiver: &mut Receiver<Signal<Complex<f32>>>) {
+        loop {
+            let Signal::Event(event) = receiver.recv().await.unwrap() else { continue; };
+            if event.as_any().is::<Disconnection>() {
+                return;
+            }
+        }
+    }
+    #[tokio::test]
+    async fn test_nfm_receiver() {
+        use std::f64::consts::TAU;
+        let input_rate = 192000.0;
+        let receiver = NfmReceiver::<f32>::new(input_rate, 20000.0, 8000.0, NfmBandwidth::Narrow);
+        let (sender, sender_connector) = new_sender::<Signal<Complex<f32>>>();
+        let (mut audio_receiver, audio_connector) = new_receiver::<Signal<Complex<f32>>>();
+        receiver.feed_from(&sender_connector);
+        receiver.feed_into(&audio_connector);
+        tokio::spawn(async move {
+            let mut phase: f64 = 0.0;
+            let mut t: f64 = 0.0;
+            loop {
+                let mut chunk = Vec::with_capacity(4096);
+                for _ in 0..4096 {
+                    let audio = 0.5 * (TAU * 1000.0 * t).sin();
+                    let frequency = 20000.0 + 2500.0 * audio;
+                    phase = (phase + TAU * frequency / input_rate) % TAU;
+                    chunk.push(Complex::new(phase.cos() as f32, phase.sin() as f32));
+                    t += 1.0 / input_rate;
+                }
+                let Ok(()) = sender
+                    .send(Signal::Samples {
+                        sample_rate: input_rate,
+                        chunk: Chunk::from(chunk),
+                    })
+                    .await
+                else { return; };
+            }
+        });
+        let rms = audio_rms(&mut audio_receiver, 8000.0, 4096).await;
+        // 0.5 amplitude, attenuated by 750 us de-emphasis at 1 kHz
+        let expected = 0.5 / 2.0f32.sqrt() / (1.0 + (TAU * 1000.0 * 750e-6).powi(2)).sqrt() as f32;
+        assert!((rms - expected).abs() < 0.1 * expected);
+        assert!(*receiver.squelch_open().borrow());
+        receiver.set_squelch_db(20.0, 20.0);
+        audio_rms(&mut audio_receiver, 8000.0, 4096).await;
+        assert!(!*receiver.squelch_open().borrow());
+        // skip samples still in the pipeline
+        audio_rms(&mut audio_receiver, 8000.0, 8192).await;
+        assert_eq!(audio_rms(&mut audio_receiver, 8000.0, 4096).await, 0.0);
+    }
+    #[tokio::test]
+    async fn test_nfm_receiver_rate_change() {
+        use std::f64::consts::TAU;
+        let receiver = NfmReceiver::<f32>::new(192000.0, 5000.0, 8000.0, NfmBandwidth::Narrow);
+        let (sender, sender_connector) = new_sender::<Signal<Complex<f32>>>();
+        let (mut audio_receiver, audio_connector) = new_receiver::<Signal<Complex<f32>>>();
+        receiver.feed_from(&sender_connector);
+        receiver.feed_into(&audio_connector);
+        tokio::spawn(async move {
+            let mut phase: f64 = 0.0;
+            let mut t: f64 = 0.0;
+            let rates = std::iter::repeat_n(192000.0, 48)
+                .chain([10000.0])
+                .chain(std::iter::repeat(96000.0));
+            for input_rate in rates {
+                let mut chunk = Vec::with_capacity(4096);
+                for _ in 0..4096 {
+                    let audio = 0.5 * (TAU * 1000.0 * t).sin();
+                    let frequency = 5000.0 + 2500.0 * audio;
+                    phase = (phase + TAU * frequency / input_rate) % TAU;
+                    chunk.push(Complex::new(phase.cos() as f32, phase.sin() as f32));
+                    t += 1.0 / input_rate;
+                }
+                let Ok(()) = sender
+                    .send(Signal::Samples {
+                        sample_rate: input_rate,
+                        chunk: Chunk::from(chunk),
+                    })
+                    .await
+                else { return; };
+            }
+        });
+        let expected = 0.5 / 2.0f32.sqrt() / (1.0 + (TAU * 1000.0 * 750e-6).powi(2)).sqrt() as f32;
+        let rms = audio_rms(&mut audio_receiver, 8000.0, 4096).await;
+        assert!((rms - expected).abs() < 0.1 * expected);
+        loop {
+            match audio_receiver.recv().await.unwrap() {
+                Signal::Samples { .. } => (),
+                Signal::Event(event) => {
+                    if let Some(event) = event.as_any().downcast_ref::<UnsupportedSampleRate>() {
+                        assert_eq!(event.sample_rate, 10000.0);
+                        assert_eq!(event.minimum, 12500.0);
+                        break;
+                    }
+                }
+            }
+        }
+        // skip transient after the change of the sample rate
+        audio_rms(&mut audio_receiver, 8000.0, 8192).await;
+        let rms = audio_rms(&mut audio_receiver, 8000.0, 4096).await;
+        assert!((rms - expected).abs() < 0.1 * expected);
+    }
+    #[tokio::test]
+    async fn test_receiver_set_mode() {
+        use std::f64::consts::TAU;
+        let input_rate = 96000.0;
+        let receiver = super::Receiver::<f32>::new(10000.0, 8000.0, Mode::Usb);
+        assert_eq!(receiver.mode(), Mode::Usb);
+        let (sender, sender_connector) = new_sender::<Signal<Complex<f32>>>();
+        let (mut audio_receiver, audio_connector) = new_receiver::<Signal<Complex<f32>>>();
+        receiver.feed_from(&sender_connector);
+        receiver.feed_into(&audio_connector);
+        tokio::spawn(async move {
+            let mut phase: f64 = 0.0;
+            loop {
+                let mut chunk = Vec::with_capacity(4096);
+                for _ in 0..4096 {
+                    // tone 1 kHz above the channel frequency
+                    phase = (phase + TAU * 11000.0 / input_rate) % TAU;
+                    chunk.push(Complex::new(phase.cos() as f32, phase.sin() as f32) * 0.5);
+                }
+                let Ok(()) = sender
+                    .send(Signal::Samples {
+                        sample_rate: input_rate,
+                        chunk: Chunk::from(chunk),
+                    })
+                    .await
+                else { return; };
+            }
+        });
+        let expected = 0.5 / 2.0f32.sqrt();
+        let rms = audio_rms(&mut audio_receiver, 8000.0, 4096).await;
+        assert!((rms - expected).abs() < 0.1 * expected, "{rms}");
+        receiver.set_mode(Mode::Lsb);
+        assert_eq!(receiver.mode(), Mode::Lsb);
+        skip_until_disconnection(&mut audio_receiver).await;
+        audio_rms(&mut audio_receiver, 8000.0, 4096).await;
+        let rms = audio_rms(&mut audio_receiver, 8000.0, 4096).await;
+        assert!(rms < 0.05 * expected, "{rms}");
+        receiver.set_mode(Mode::Cw);
+        skip_until_disconnection(&mut audio_receiver).await;
+        audio_rms(&mut audio_receiver, 8000.0, 8192).await;
+        let rms = audio_rms(&mut audio_receiver, 8000.0, 4096).await;
+        assert!(rms < 0.05 * expected, "{rms}");
+    }
+    #[tokio::test]
+    async fn test_scanner() {
+        use std::sync::atomic::{AtomicBool, Ordering};
+        use std::sync::Arc;
+        let (tune_send, mut tune_recv) = watch::channel(0.0);
+        let scanner = Scanner::<f32>::new(
+            vec![145.0e6, 145.25e6, 145.5e6],
+            12500.0,
+            -20.0,
+            -25.0,
+            move |frequency| {
+                tune_send.send_replace(frequency);
+            },
+        );
+        scanner.set_resume_delay(0.2);
+        let mut state = scanner.state();
+        let (sender, sender_connector) = new_sender::<Signal<Complex<f32>>>();
+        let (mut receiver, receiver_connector) = new_receiver::<Signal<Complex<f32>>>();
+        scanner.feed_from(&sender_connector);
+        scanner.feed_into(&receiver_connector);
+        let active = Arc::new(AtomicBool::new(true));
+        let source_active = active.clone();
+        tokio::spawn(async move {
+            let mut frequency = 0.0;
+            loop {
+                if tune_recv.has_changed().unwrap_or(false) {
+                    frequency = *tune_recv.borrow_and_update();
+                    let event = Signal::new_event(CenterFrequency(frequency));
+                    let Ok(()) = sender.send(event).await else { return; };
+                }
+                let sample = match frequency == 145.5e6 && source_active.load(Ordering::Relaxed) {
+                    true => Complex::new(0.5, 0.0),
+                    false => Complex::new(0.0, 0.0),
+                };
+                let Ok(()) = sender
+                    .send(Signal::Samples {
+                        sample_rate: 48000.0,
+                        chunk: Chunk::from(vec![sample; 1024]),
+                    })
+                    .await
+                else { return; };
+            }
+        });
+        let detected = loop {
+            let Signal::Event(event) = receiver.recv().await.unwrap() else { continue; };
+            if let Some(event) = event.as_any().downcast_ref::<ActivityDetected>() {
+                break event.frequency;
+            }
+        };
+        assert_eq!(detected, 145.5e6);
+        assert_eq!(*state.borrow_and_update(), ScanState::Locked);
+        assert_eq!(*scanner.frequency().borrow(), 145.5e6);
+        active.store(false, Ordering::Relaxed);
+        let ended = loop {
+            let Signal::Event(event) = receiver.recv().await.unwrap() else { continue; };
+            if let Some(event) = event.as_any().downcast_ref::<ActivityEnded>() {
+                break event.frequency;
+            }
+        };
+        assert_eq!(ended, 145.5e6);
+        assert_eq!(*state.borrow_and_update(), ScanState::Scanning);
+        assert_eq!(*scanner.frequency().borrow(), 145.0e6);
+        scanner.set_range(144.0e6, 144.1e6, 0.025e6);
+        assert_eq!(
+            scanner.channels(),
+            vec![144.0e6, 144.025e6, 144.05e6, 144.075e6, 144.1e6]
+        );
+    }
+    fn spawn_tone_source(
+        sender: Sender<Signal<Complex<f32>>>,
+        sample_rate: f64,
+        frequency: f64,
+        amplitude: f64,
+    ) {
+        use std::f64::consts::TAU;
+        tokio::spawn(async move {
+            let mut t: f64 = 0.0;
+            loop {
+                let mut chunk = Vec::with_capacity(1024);
+                for _ in 0..1024 {
+                    let audio = amplitude * (TAU * frequency * t).sin();
+                    chunk.push(Complex::from(audio as f32));
+                    t += 1.0 / sample_rate;
+                }
+                let Ok(()) = sender
+                    .send(Signal::Samples {
+                        sample_rate,
+                        chunk: Chunk::from(chunk),
+                    })
+                    .await
+                else { return; };
+            }
+        });
+    }
+    #[tokio::test]
+    async fn test_fm_modulator() {
+        let bandwidth = NfmBandwidth::Narrow;
+        let modulator =
+            FmModulator::<f32>::new(192000.0, 20000.0, bandwidth.deviation(), 3000.0, 750e-6);
+        assert_eq!(modulator.channel_offset(), 20000.0);
+        let receiver = NfmReceiver::<f32>::new(192000.0, 20000.0, 8000.0, bandwidth);
+        receiver.feed_from(&modulator);
+        let (sender, sender_connector) = new_sender::<Signal<Complex<f32>>>();
+        let (mut audio_receiver, audio_connector) = new_receiver::<Signal<Complex<f32>>>();
+        modulator.feed_from(&sender_connector);
+        receiver.feed_into(&audio_connector);
+        spawn_tone_source(sender, 8000.0, 1000.0, 0.1);
+        audio_rms(&mut audio_receiver, 8000.0, 8192).await;
+        let rms = audio_rms(&mut audio_receiver, 8000.0, 4096).await;
+        // pre-emphasis and de-emphasis cancel
+        let expected = 0.1 / 2.0f32.sqrt();
+        assert!((rms - expected).abs() < 0.1 * expected, "{rms}");
+    }
+    #[tokio::test]
+    async fn test_ssb_modulator() {
+        for (sideband, expected) in [(Sideband::Usb, 0.5 / 2.0f32.sqrt()), (Sideband::Lsb, 0.0)] {
+            let modulator = SsbModulator::<f32>::new(96000.0, 10000.0, sideband);
+            let receiver = super::Receiver::<f32>::new(10000.0, 8000.0, Mode::Usb);
+            receiver.feed_from(&modulator);
+            let (sender, sender_connector) = new_sender::<Signal<Complex<f32>>>();
+            let (mut audio_receiver, audio_connector) = new_receiver::<Signal<Complex<f32>>>();
+            modulator.feed_from(&sender_connector);
+            receiver.feed_into(&audio_connector);
+            spawn_tone_source(sender, 8000.0, 1000.0, 0.5);
+            audio_rms(&mut audio_receiver, 8000.0, 8192).await;
+            let rms = audio_rms(&mut audio_receiver, 8000.0, 4096).await;
+            assert!((rms - expected).abs() < 0.02 + 0.1 * expected, "{rms}");
+        }
+    }
+    #[tokio::test]
+    async fn test_wbfm_receiver() {
+        use std::f64::consts::TAU;
+        let input_rate = 512000.0;
+        let receiver = WbfmReceiver::<f32>::new(input_rate, 100000.0, 48000.0);
+        assert_eq!(receiver.channel_offset(), 100000.0);
+        let (sender, sender_connector) = new_sender::<Signal<Complex<f32>>>();
+        let (mut audio_receiver, audio_connector) = new_receiver::<Signal<Complex<f32>>>();
+        receiver.feed_from(&sender_connector);
+        receiver.feed_into(&audio_connector);
+        tokio::spawn(async move {
+            let mut phase: f64 = 0.0;
+            let mut t: f64 = 0.0;
+            for _ in 0..32 {
+                let mut chunk = Vec::with_capacity(8192);
+                for _ in 0..8192 {
+                    let audio = 0.5 * (TAU * 1000.0 * t).sin();
+                    let frequency = 100000.0 + 75000.0 * audio;
+                    phase = (phase + TAU * frequency / input_rate) % TAU;
+                    chunk.push(Complex::new(phase.cos() as f32, phase.sin() as f32));
+                    t += 1.0 / input_rate;
+                }
+                sender
+                    .send(Signal::Samples {
+                        sample_rate: input_rate,
+                        chunk: Chunk::from(chunk),
+                    })
+                    .await
+                    .unwrap();
+            }
+        });
+        let rms = audio_rms(&mut audio_receiver, 48000.0, 16384).await;
+        // 0.5 amplitude, attenuated by 50 us de-emphasis at 1 kHz
+        let expected = 0.5 / 2.0f32.sqrt() / (1.0 + (TAU * 1000.0 * 50e-6).powi(2)).sqrt() as f32;
+        assert!((rms - expected).abs() < 0.05 * expected);
+    }
+}
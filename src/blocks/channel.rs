@@ -0,0 +1,657 @@
+//! Channel models for testing (e.g. noise, frequency offsets, or multipath
+//! propagation)
+
+use crate::bufferpool::*;
+use crate::flow::*;
+use crate::impl_block_trait;
+use crate::math::NoiseGenerator;
+use crate::numbers::*;
+use crate::signal::*;
+
+use tokio::sync::watch;
+use tokio::task::spawn;
+
+/// Noise level of an [`Awgn`] block
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum NoiseLevel {
+    /// Signal-to-noise ratio in decibels, relative to the measured power of
+    /// the signal
+    Snr(f64),
+    /// Absolute noise power in decibels (where 0 dB corresponds to a power
+    /// of `1.0`)
+    Absolute(f64),
+}
+
+/// Block adding complex white Gaussian noise (AWGN) to the signal
+///
+/// The noise power is either given as absolute level or as signal-to-noise
+/// ratio (see [`NoiseLevel`]). In the latter case, the signal power is
+/// measured for each chunk separately, such that chunks consisting of zeros
+/// remain unchanged.
+///
+/// For reproducible results (e.g. when measuring bit error rates), use
+/// [`Awgn::with_seed`].
+pub struct Awgn<Flt> {
+    receiver_connector: ReceiverConnector<Signal<Complex<Flt>>>,
+    sender_connector: SenderConnector<Signal<Complex<Flt>>>,
+    level: watch::Sender<NoiseLevel>,
+}
+
+impl_block_trait! { <Flt> Consumer<Signal<Complex<Flt>>> for Awgn<Flt> }
+impl_block_trait! { <Flt> Producer<Signal<Complex<Flt>>> for Awgn<Flt> }
+
+impl<Flt> Awgn<Flt>
+where
+    Flt: Float,
+{
+    /// Create new `Awgn` block with given [`NoiseLevel`], where noise is
+    /// seeded from the system time
+    pub fn new(level: NoiseLevel) -> Self {
+        Self::with_noise_generator(level, NoiseGenerator::new())
+    }
+    /// Create new `Awgn` block with given [`NoiseLevel`], where noise is
+    /// generated reproducibly from given `seed`
+    pub fn with_seed(level: NoiseLevel, seed: u64) -> Self {
+        Self::with_noise_generator(level, NoiseGenerator::with_seed(seed))
+    }
+    fn with_noise_generator(level: NoiseLevel, mut noise: NoiseGenerator) -> Self {
+        let (mut receiver, receiver_connector) = new_receiver::<Signal<Complex<Flt>>>();
+        let (sender, sender_connector) = new_sender::<Signal<Complex<Flt>>>();
+        let (level_send, mut level_recv) = watch::channel(level);
+        spawn(async move {
+            let mut buf_pool = ChunkBufPool::<Complex<Flt>>::new();
+            let mut level = level;
+            loop {
+                let Ok(signal) = receiver.recv().await else { return; };
+                match signal {
+                    Signal::Samples {
+                        sample_rate,
+                        chunk: input_chunk,
+                    } => {
+                        if level_recv.has_changed().unwrap_or(false) {
+                            level = *level_recv.borrow_and_update();
+                        }
+                        let noise_power = match level {
+                            NoiseLevel::Snr(snr_db) => {
+                                let signal_power = input_chunk
+                                    .iter()
+                                    .map(|x| x.norm_sqr().to_f64().unwrap())
+                                    .sum::<f64>()
+                                    / input_chunk.len().max(1) as f64;
+                                signal_power * from_db(-snr_db)
+                            }
+                            NoiseLevel::Absolute(noise_db) => from_db(noise_db),
+                        };
+                        let amplitude = noise_power.sqrt();
+                        let mut output_chunk = buf_pool.get_with_capacity(input_chunk.len());
+                        for &sample in input_chunk.iter() {
+                            let n = noise.complex_gaussian() * amplitude;
+                            output_chunk.push(sample + Complex::new(flt!(n.re), flt!(n.im)));
+                        }
+                        let Ok(()) = sender
+                            .send(Signal::Samples {
+                                sample_rate,
+                                chunk: output_chunk.finalize(),
+                            })
+                            .await
+                        else { return; };
+                    }
+                    event @ Signal::Event { .. } => {
+                        let Ok(()) = sender.send(event).await else { return; };
+                    }
+                }
+            }
+        });
+        Self {
+            receiver_connector,
+            sender_connector,
+            level: level_send,
+        }
+    }
+    /// Get [`NoiseLevel`]
+    pub fn level(&self) -> NoiseLevel {
+        *self.level.borrow()
+    }
+    /// Set [`NoiseLevel`]
+    pub fn set_level(&self, level: NoiseLevel) {
+        self.level.send_replace(level);
+    }
+}
+
+/// Block applying carrier impairments (frequency offset, phase offset, and
+/// phase noise) to the signal
+///
+/// The phase of the frequency offset accumulates continuously across chunks
+/// and when the offset is changed. Phase noise is modeled as a random walk
+/// (Wiener process) of the phase, which results in a Lorentzian spectrum
+/// with the given 3 dB linewidth in hertz (zero disables phase noise).
+///
+/// For reproducible results, use [`Impairments::with_seed`].
+pub struct Impairments<Flt> {
+    receiver_connector: ReceiverConnector<Signal<Complex<Flt>>>,
+    sender_connector: SenderConnector<Signal<Complex<Flt>>>,
+    frequency_offset: watch::Sender<f64>,
+    phase_offset: watch::Sender<f64>,
+    linewidth: watch::Sender<f64>,
+}
+
+impl_block_trait! { <Flt> Consumer<Signal<Complex<Flt>>> for Impairments<Flt> }
+impl_block_trait! { <Flt> Producer<Signal<Complex<Flt>>> for Impairments<Flt> }
+
+impl<Flt> Impairments<Flt>
+where
+    Flt: Float,
+{
+    /// Create new `Impairments` block with given frequency offset in hertz,
+    /// phase offset in radians, and phase noise linewidth in hertz, where
+    /// noise is seeded from the system time
+    pub fn new(frequency_offset: f64, phase_offset: f64, linewidth: f64) -> Self {
+        Self::with_noise_generator(
+            frequency_offset,
+            phase_offset,
+            linewidth,
+            NoiseGenerator::new(),
+        )
+    }
+    /// Create new `Impairments` block like [`Impairments::new`], where phase
+    /// noise is generated reproducibly from given `seed`
+    pub fn with_seed(frequency_offset: f64, phase_offset: f64, linewidth: f64, seed: u64) -> Self {
+        Self::with_noise_generator(
+            frequency_offset,
+            phase_offset,
+            linewidth,
+            NoiseGenerator::with_seed(seed),
+        )
+    }
+    fn with_noise_generator(
+        frequency_offset: f64,
+        phase_offset: f64,
+        linewidth: f64,
+        mut noise: NoiseGenerator,
+    ) -> Self {
+        use std::f64::consts::TAU;
+        assert!(linewidth >= 0.0, "linewidth must not be negative");
+        let (mut receiver, receiver_connector) = new_receiver::<Signal<Complex<Flt>>>();
+        let (sender, sender_connector) = new_sender::<Signal<Complex<Flt>>>();
+        let (frequency_offset_send, mut frequency_offset_recv) = watch::channel(frequency_offset);
+        let (phase_offset_send, mut phase_offset_recv) = watch::channel(phase_offset);
+        let (linewidth_send, mut linewidth_recv) = watch::channel(linewidth);
+        spawn(async move {
+            let mut buf_pool = ChunkBufPool::<Complex<Flt>>::new();
+            let mut frequency_offset = frequency_offset;
+            let mut phase_offset = phase_offset;
+            let mut linewidth = linewidth;
+            let mut phase: f64 = 0.0;
+            let mut phase_noise: f64 = 0.0;
+            loop {
+                let Ok(signal) = receiver.recv().await else { return; };
+                match signal {
+                    Signal::Samples {
+                        sample_rate,
+                        chunk: input_chunk,
+                    } => {
+                        if frequency_offset_recv.has_changed().unwrap_or(false) {
+                            frequency_offset = *frequency_offset_recv.borrow_and_update();
+                        }
+                        if phase_offset_recv.has_changed().unwrap_or(false) {
+                            phase_offset = *phase_offset_recv.borrow_and_update();
+                        }
+                        if linewidth_recv.has_changed().unwrap_or(false) {
+                            linewidth = *linewidth_recv.borrow_and_update();
+                        }
+                        let step = TAU * frequency_offset / sample_rate;
+                        let noise_deviation = (TAU * linewidth / sample_rate).sqrt();
+                        let mut output_chunk = buf_pool.get_with_capacity(input_chunk.len());
+                        for &sample in input_chunk.iter() {
+                            let (sin, cos) = (phase + phase_offset + phase_noise).sin_cos();
+                            output_chunk.push(sample * Complex::new(flt!(cos), flt!(sin)));
+                            phase = (phase + step) % TAU;
+                            if noise_deviation > 0.0 {
+                                phase_noise =
+                                    (phase_noise + noise.gaussian() * noise_deviation) % TAU;
+                            }
+                        }
+                        let Ok(()) = sender
+                            .send(Signal::Samples {
+                                sample_rate,
+                                chunk: output_chunk.finalize(),
+                            })
+                            .await
+                        else { return; };
+                    }
+                    event @ Signal::Event { .. } => {
+                        let Ok(()) = sender.send(event).await else { return; };
+                    }
+                }
+            }
+        });
+        Self {
+            receiver_connector,
+            sender_connector,
+            frequency_offset: frequency_offset_send,
+            phase_offset: phase_offset_send,
+            linewidth: linewidth_send,
+        }
+    }
+    /// Get frequency offset in hertz
+    pub fn frequency_offset(&self) -> f64 {
+        *self.frequency_offset.borrow()
+    }
+    /// Set frequency offset in hertz
+    pub fn set_frequency_offset(&self, frequency_offset: f64) {
+        self.frequency_offset.send_replace(frequency_offset);
+    }
+    /// Get phase offset in radians
+    pub fn phase_offset(&self) -> f64 {
+        *self.phase_offset.borrow()
+    }
+    /// Set phase offset in radians
+    pub fn set_phase_offset(&self, phase_offset: f64) {
+        self.phase_offset.send_replace(phase_offset);
+    }
+    /// Get phase noise linewidth in hertz
+    pub fn linewidth(&self) -> f64 {
+        *self.linewidth.borrow()
+    }
+    /// Set phase noise linewidth in hertz (zero disables phase noise)
+    pub fn set_linewidth(&self, linewidth: f64) {
+        assert!(linewidth >= 0.0, "linewidth must not be negative");
+        self.linewidth.send_replace(linewidth);
+    }
+}
+
+/// Delay of a [`MultipathTap`]
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum TapDelay {
+    /// Delay in samples
+    Samples(usize),
+    /// Delay in microseconds (rounded to whole samples)
+    Micros(f64),
+}
+
+impl TapDelay {
+    fn samples(self, sample_rate: f64) -> usize {
+        match self {
+            TapDelay::Samples(samples) => samples,
+            TapDelay::Micros(micros) => (micros * 1e-6 * sample_rate).round() as usize,
+        }
+    }
+}
+
+/// Time-varying fading of a [`MultipathTap`]
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Fading {
+    /// Constant gain
+    None,
+    /// Rayleigh fading (no line of sight) with given maximum Doppler
+    /// frequency in hertz
+    Rayleigh {
+        /// Maximum Doppler frequency in hertz
+        doppler: f64,
+    },
+    /// Rician fading with given K-factor (ratio of the power of the line of
+    /// sight component to the power of the scattered components, linear) and
+    /// maximum Doppler frequency in hertz
+    Rician {
+        /// Ratio of line of sight power to scattered power (linear)
+        k_factor: f64,
+        /// Maximum Doppler frequency in hertz
+        doppler: f64,
+    },
+}
+
+/// Path of a [`Multipath`] channel
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct MultipathTap {
+    /// Delay of the path
+    pub delay: TapDelay,
+    /// Complex gain of the path
+    pub gain: Complex<f64>,
+    /// Fading of the path, which has unit average power and is multiplied
+    /// with `gain`
+    pub fading: Fading,
+}
+
+impl MultipathTap {
+    /// Path with given delay and constant complex gain
+    pub fn new(delay: TapDelay, gain: Complex<f64>) -> Self {
+        Self {
+            delay,
+            gain,
+            fading: Fading::None,
+        }
+    }
+}
+
+/// Sum of sinusoids approximating the scattered component of a fading path
+/// (Clarke's model)
+struct FadingProcess {
+    /// Doppler frequency (in hertz) and initial phase of each sinusoid
+    components: Vec<(f64, f64)>,
+    /// Amplitude of the line of sight and of the scattered component
+    amplitudes: (f64, f64),
+}
+
+impl FadingProcess {
+    const NUM_COMPONENTS: usize = 16;
+    fn new(fading: Fading, noise: &mut NoiseGenerator) -> Self {
+        use std::f64::consts::TAU;
+        let (k_factor, doppler) = match fading {
+            Fading::None => {
+                return Self {
+                    components: Vec::new(),
+                    amplitudes: (1.0, 0.0),
+                }
+            }
+            Fading::Rayleigh { doppler } => (0.0, doppler),
+            Fading::Rician { k_factor, doppler } => (k_factor, doppler),
+        };
+        let components = (0..Self::NUM_COMPONENTS)
+            .map(|_| {
+                let angle = TAU * noise.uniform();
+                (doppler * angle.cos(), TAU * noise.uniform())
+            })
+            .collect();
+        let amplitudes = (
+            (k_factor / (k_factor + 1.0)).sqrt(),
+            (1.0 / ((k_factor + 1.0) * Self::NUM_COMPONENTS as f64)).sqrt(),
+        );
+        Self {
+            components,
+            amplitudes,
+        }
+    }
+    /// Gain at time `t` in seconds
+    fn gain(&self, t: f64) -> Complex<f64> {
+        use std::f64::consts::TAU;
+        let mut gain = Complex::from(self.amplitudes.0);
+        for &(frequency, phase) in self.components.iter() {
+            gain += Complex::from_polar(self.amplitudes.1, TAU * frequency * t + phase);
+        }
+        gain
+    }
+}
+
+/// Block convolving the signal with a multipath channel, i.e. a set of
+/// delayed paths with complex gains, which may be subject to (Rayleigh or
+/// Rician) fading
+///
+/// The delay line is kept across chunks, so there are no artifacts at chunk
+/// boundaries. It is cleared when an [interrupting] event is received. Fading
+/// is modeled as a sum of sinusoids with random Doppler shifts up to the
+/// maximum Doppler frequency (Clarke's model), where the random values are
+/// drawn whenever the taps are set. For reproducible results, use
+/// [`Multipath::with_seed`].
+///
+/// [interrupting]: Event::is_interrupt
+pub struct Multipath<Flt> {
+    receiver_connector: ReceiverConnector<Signal<Complex<Flt>>>,
+    sender_connector: SenderConnector<Signal<Complex<Flt>>>,
+    taps: watch::Sender<Vec<MultipathTap>>,
+}
+
+impl_block_trait! { <Flt> Consumer<Signal<Complex<Flt>>> for Multipath<Flt> }
+impl_block_trait! { <Flt> Producer<Signal<Complex<Flt>>> for Multipath<Flt> }
+
+impl<Flt> Multipath<Flt>
+where
+    Flt: Float,
+{
+    /// Create new `Multipath` block with given taps, where fading is seeded
+    /// from the system time
+    pub fn new(taps: Vec<MultipathTap>) -> Self {
+        Self::with_noise_generator(taps, NoiseGenerator::new())
+    }
+    /// Create new `Multipath` block with given taps, where fading is
+    /// generated reproducibly from given `seed`
+    pub fn with_seed(taps: Vec<MultipathTap>, seed: u64) -> Self {
+        Self::with_noise_generator(taps, NoiseGenerator::with_seed(seed))
+    }
+    fn with_noise_generator(taps: Vec<MultipathTap>, mut noise: NoiseGenerator) -> Self {
+        let (mut receiver, receiver_connector) = new_receiver::<Signal<Complex<Flt>>>();
+        let (sender, sender_connector) = new_sender::<Signal<Complex<Flt>>>();
+        let (taps_send, mut taps_recv) = watch::channel(taps.clone());
+        spawn(async move {
+            let mut buf_pool = ChunkBufPool::<Complex<Flt>>::new();
+            let mut taps = taps;
+            let mut processes: Vec<FadingProcess> = taps
+                .iter()
+                .map(|tap| FadingProcess::new(tap.fading, &mut noise))
+                .collect();
+            let mut prev_sample_rate: Option<f64> = None;
+            let mut delays: Vec<usize> = Vec::new();
+            let mut history: Vec<Complex<Flt>> = Vec::new();
+            let mut extended: Vec<Complex<Flt>> = Vec::new();
+            let mut time: f64 = 0.0;
+            loop {
+                let Ok(signal) = receiver.recv().await else { return; };
+                match signal {
+                    Signal::Samples {
+                        sample_rate,
+                        chunk: input_chunk,
+                    } => {
+                        let mut recalculate = prev_sample_rate != Some(sample_rate);
+                        if taps_recv.has_changed().unwrap_or(false) {
+                            taps = taps_recv.borrow_and_update().clone();
+                            processes = taps
+                                .iter()
+                                .map(|tap| FadingProcess::new(tap.fading, &mut noise))
+                                .collect();
+                            recalculate = true;
+                        }
+                        if recalculate {
+                            prev_sample_rate = Some(sample_rate);
+                            delays = taps
+                                .iter()
+                                .map(|tap| tap.delay.samples(sample_rate))
+                                .collect();
+                            let history_len = delays.iter().copied().max().unwrap_or(0);
+                            if history.len() > history_len {
+                                history.drain(0..history.len() - history_len);
+                            } else {
+                                let missing = history_len - history.len();
+                                history.splice(0..0, vec![Complex::from(Flt::zero()); missing]);
+                            }
+                        }
+                        let history_len = history.len();
+                        extended.clear();
+                        extended.extend_from_slice(&history);
+                        extended.extend_from_slice(&input_chunk);
+                        let mut output_chunk = buf_pool.get_with_capacity(input_chunk.len());
+                        for i in 0..input_chunk.len() {
+                            let t = time + i as f64 / sample_rate;
+                            let mut sum: Complex<f64> = Complex::from(0.0);
+                            for ((tap, process), &delay) in
+                                taps.iter().zip(processes.iter()).zip(delays.iter())
+                            {
+                                let x = extended[i + history_len - delay];
+                                let x =
+                                    Complex::new(x.re.to_f64().unwrap(), x.im.to_f64().unwrap());
+                                sum += x * tap.gain * process.gain(t);
+                            }
+                            output_chunk.push(Complex::new(flt!(sum.re), flt!(sum.im)));
+                        }
+                        time += input_chunk.len() as f64 / sample_rate;
+                        history.clear();
+                        history.extend_from_slice(&extended[extended.len() - history_len..]);
+                        let Ok(()) = sender
+                            .send(Signal::Samples {
+                                sample_rate,
+                                chunk: output_chunk.finalize(),
+                            })
+                            .await
+                        else { return; };
+                    }
+                    Signal::Event(event) => {
+                        if event.is_interrupt() {
+                            for sample in history.iter_mut() {
+                                *sample = Complex::from(Flt::zero());
+                            }
+                        }
+                        let Ok(()) = sender.send(Signal::Event(event)).await
+                        else { return; };
+                    }
+                }
+            }
+        });
+        Self {
+            receiver_connector,
+            sender_connector,
+            taps: taps_send,
+        }
+    }
+    /// Get taps
+    pub fn taps(&self) -> Vec<MultipathTap> {
+        self.taps.borrow().clone()
+    }
+    /// Set taps
+    ///
+    /// The most recent samples are kept in the delay line, such that the
+    /// output stays continuous.
+    pub fn set_taps(&self, taps: Vec<MultipathTap>) {
+        self.taps.send_replace(taps);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    async fn noise_powers(awgn: Awgn<f64>, inputs: Vec<Vec<Complex<f64>>>) -> Vec<f64> {
+        let (sender, sender_connector) = new_sender::<Signal<Complex<f64>>>();
+        let (mut receiver, receiver_connector) = new_receiver::<Signal<Complex<f64>>>();
+        awgn.feed_from(&sender_connector);
+        awgn.feed_into(&receiver_connector);
+        let mut powers: Vec<f64> = Vec::new();
+        for (index, input) in inputs.into_iter().enumerate() {
+            if index == 1 {
+                awgn.set_level(NoiseLevel::Absolute(-20.0));
+            }
+            sender
+                .send(Signal::Samples {
+                    sample_rate: 48000.0,
+                    chunk: Chunk::from(input.clone()),
+                })
+                .await
+                .unwrap();
+            let Signal::Samples { chunk, .. } = receiver.recv().await.unwrap()
+            else { panic!(); };
+            let power = chunk
+                .iter()
+                .zip(input.iter())
+                .map(|(y, x)| (y - x).norm_sqr())
+                .sum::<f64>()
+                / chunk.len() as f64;
+            powers.push(power);
+        }
+        powers
+    }
+    #[tokio::test]
+    async fn test_awgn() {
+        let input: Vec<Complex<f64>> = (0..20000)
+            .map(|i| Complex::from_polar(2.0, i as f64 * 0.1))
+            .collect();
+        let inputs = vec![input.clone(), input];
+        let first = noise_powers(Awgn::with_seed(NoiseLevel::Snr(10.0), 1), inputs.clone()).await;
+        assert!((first[0] - 0.4).abs() < 0.02);
+        assert!((first[1] - 0.01).abs() < 0.0005);
+        let second = noise_powers(Awgn::with_seed(NoiseLevel::Snr(10.0), 1), inputs).await;
+        assert_eq!(first, second);
+    }
+    #[tokio::test]
+    async fn test_impairments() {
+        use std::f64::consts::TAU;
+        let (sender, sender_connector) = new_sender::<Signal<Complex<f64>>>();
+        let impairments = Impairments::<f64>::with_seed(1000.0, 0.5, 0.0, 1);
+        let (mut receiver, receiver_connector) = new_receiver::<Signal<Complex<f64>>>();
+        impairments.feed_from(&sender_connector);
+        impairments.feed_into(&receiver_connector);
+        let mut output: Vec<Complex<f64>> = Vec::new();
+        for _ in 0..3 {
+            sender
+                .send(Signal::Samples {
+                    sample_rate: 48000.0,
+                    chunk: Chunk::from(vec![Complex::from(1.0); 100]),
+                })
+                .await
+                .unwrap();
+            let Signal::Samples { chunk, .. } = receiver.recv().await.unwrap()
+            else { panic!(); };
+            output.extend_from_slice(&chunk);
+        }
+        for (i, sample) in output.iter().enumerate() {
+            let expected = Complex::from_polar(1.0, TAU * 1000.0 * i as f64 / 48000.0 + 0.5);
+            assert!((sample - expected).norm() < 1e-9);
+        }
+        impairments.set_frequency_offset(0.0);
+        impairments.set_linewidth(100.0);
+        sender
+            .send(Signal::Samples {
+                sample_rate: 48000.0,
+                chunk: Chunk::from(vec![Complex::from(1.0); 48000]),
+            })
+            .await
+            .unwrap();
+        let Signal::Samples { chunk, .. } = receiver.recv().await.unwrap()
+        else { panic!(); };
+        let variance = chunk
+            .windows(2)
+            .map(|pair| (pair[1] / pair[0]).arg().powi(2))
+            .sum::<f64>()
+            / (chunk.len() - 1) as f64;
+        let expected = TAU * 100.0 / 48000.0;
+        assert!((variance - expected).abs() < 0.05 * expected);
+    }
+    #[tokio::test]
+    async fn test_multipath() {
+        let (sender, sender_connector) = new_sender::<Signal<Complex<f64>>>();
+        let multipath = Multipath::<f64>::with_seed(
+            vec![
+                MultipathTap::new(TapDelay::Samples(0), Complex::from(1.0)),
+                MultipathTap::new(TapDelay::Samples(3), Complex::new(0.0, 0.5)),
+                MultipathTap::new(TapDelay::Micros(100.0), Complex::from(-0.25)),
+            ],
+            1,
+        );
+        let (mut receiver, receiver_connector) = new_receiver::<Signal<Complex<f64>>>();
+        multipath.feed_from(&sender_connector);
+        multipath.feed_into(&receiver_connector);
+        let mut output: Vec<Complex<f64>> = Vec::new();
+        for chunk in [vec![0.0, 0.0, 0.0, 1.0], vec![0.0; 8]] {
+            let chunk: Vec<Complex<f64>> = chunk.into_iter().map(Complex::from).collect();
+            sender
+                .send(Signal::Samples {
+                    sample_rate: 48000.0,
+                    chunk: Chunk::from(chunk),
+                })
+                .await
+                .unwrap();
+            let Signal::Samples { chunk, .. } = receiver.recv().await.unwrap()
+            else { panic!(); };
+            output.extend_from_slice(&chunk);
+        }
+        let mut expected = vec![Complex::from(0.0); 12];
+        expected[3] = Complex::from(1.0);
+        expected[6] = Complex::new(0.0, 0.5);
+        expected[8] = Complex::from(-0.25);
+        assert_eq!(output, expected);
+        multipath.set_taps(vec![MultipathTap {
+            delay: TapDelay::Samples(0),
+            gain: Complex::from(1.0),
+            fading: Fading::Rayleigh { doppler: 100.0 },
+        }]);
+        sender
+            .send(Signal::Samples {
+                sample_rate: 48000.0,
+                chunk: Chunk::from(vec![Complex::from(1.0); 96000]),
+            })
+            .await
+            .unwrap();
+        let Signal::Samples { chunk, .. } = receiver.recv().await.unwrap()
+        else { panic!(); };
+        let powers: Vec<f64> = chunk.iter().map(|x| x.norm_sqr()).collect();
+        let mean = powers.iter().sum::<f64>() / powers.len() as f64;
+        assert!((mean - 1.0).abs() < 0.5);
+        assert!(powers.iter().any(|&power| power < 0.1));
+        assert!(powers.iter().any(|&power| power > 2.0));
+    }
+}
@@ -29,6 +29,55 @@ pub mod events {
 }
 use events::*;
 
+/// Handling of incomplete chunks in [`Rechunker`]
+///
+/// An incomplete chunk remains when an event is received (including
+/// end-of-stream through a [`Disconnection`]) or when the sample rate changes.
+/// Before a [flushing] event, an incomplete chunk is never discarded but
+/// sent with its shorter length (unless [`Remainder::Pad`] is used).
+///
+/// [flushing]: Event::is_flush
+#[derive(Clone, Debug)]
+pub enum Remainder<T> {
+    /// Discard incomplete chunk and send a [`SamplesLost`] event instead
+    Discard,
+    /// Send incomplete chunk with its shorter length
+    Flush,
+    /// Fill incomplete chunk with given value (e.g. zero) and send it
+    Pad(T),
+}
+
+/// Send incomplete chunk according to [`Remainder`]
+async fn send_remainder<T>(
+    sender: &Sender<Signal<T>>,
+    remainder: &Remainder<T>,
+    output_chunk_len: usize,
+    sample_rate: f64,
+    patchwork_chunk: ChunkBuf<T>,
+) -> Result<(), SendError<Signal<T>>>
+where
+    T: Clone,
+{
+    if let Remainder::Discard = remainder {
+        return sender.send(Signal::new_event(SamplesLost)).await;
+    }
+    let mut chunk = patchwork_chunk.finalize();
+    while chunk.len() > output_chunk_len {
+        sender
+            .send(Signal::Samples {
+                sample_rate,
+                chunk: chunk.separate_beginning(output_chunk_len),
+            })
+            .await?;
+    }
+    if let Remainder::Pad(value) = remainder {
+        let mut padded = chunk.to_vec();
+        padded.resize(output_chunk_len, value.clone());
+        chunk = Chunk::from(padded);
+    }
+    sender.send(Signal::Samples { sample_rate, chunk }).await
+}
+
 /// Block that receives [`Signal`] messages with arbitrary chunk lengths and
 /// produces fixed chunk lengths
 ///
@@ -36,6 +85,12 @@ use events::*;
 /// the [`Filter`] block, unless the chunk length can be adjusted
 /// otherwise, e.g. due to an existing [`Downsampler`] or [`Upsampler`].
 ///
+/// Events are passed through at chunk boundaries. Samples received before an
+/// event (or before the sample rate changes) which do not fill a complete
+/// chunk are handled according to the [`Remainder`] passed to
+/// [`Rechunker::with_remainder`] ([`Remainder::Discard`] when using
+/// [`Rechunker::new`]).
+///
 /// [`Filter`]: crate::blocks::filters::Filter
 /// [`Downsampler`]: crate::blocks::resampling::Downsampler
 /// [`Upsampler`]: crate::blocks::resampling::Upsampler
@@ -53,7 +108,12 @@ where
     T: Clone + Send + Sync + 'static,
 {
     /// Create new `Rechunker` block with given output chunk length
-    pub fn new(mut output_chunk_len: usize) -> Self {
+    pub fn new(output_chunk_len: usize) -> Self {
+        Self::with_remainder(output_chunk_len, Remainder::Discard)
+    }
+    /// Create new `Rechunker` block with given output chunk length and
+    /// handling of incomplete chunks
+    pub fn with_remainder(mut output_chunk_len: usize, remainder: Remainder<T>) -> Self {
         assert!(output_chunk_len > 0, "chunk length must be positive");
         let (mut receiver, receiver_connector) = new_receiver::<Signal<T>>();
         let (sender, sender_connector) = new_sender::<Signal<T>>();
@@ -71,24 +131,42 @@ where
                             Signal::Samples { sample_rate, chunk } => {
                                 if let Some((patchwork_sample_rate, _)) = patchwork_opt {
                                     if sample_rate != patchwork_sample_rate {
-                                        patchwork_opt = None;
-                                        let Ok(()) = sender.send(Signal::new_event(
-                                            SamplesLost,
-                                        )).await
+                                        let (_, patchwork_chunk) = patchwork_opt.take().unwrap();
+                                        let Ok(()) = send_remainder(
+                                            &sender,
+                                            &remainder,
+                                            output_chunk_len,
+                                            patchwork_sample_rate,
+                                            patchwork_chunk,
+                                        ).await
                                         else { return; };
                                     }
                                 }
                                 break (sample_rate, chunk);
                             }
-                            event @ Signal::Event { .. } => {
-                                if patchwork_opt.is_some() {
-                                    let Ok(()) = sender.send(Signal::new_event(
-                                        SamplesLost,
-                                    )).await
-                                    else { return; };
-                                    patchwork_opt = None;
+                            Signal::Event(event) => {
+                                if let Some((patchwork_sample_rate, patchwork_chunk)) =
+                                    patchwork_opt.take()
+                                {
+                                    if !patchwork_chunk.is_empty() {
+                                        let remainder = match remainder {
+                                            Remainder::Discard if event.is_flush() => {
+                                                &Remainder::Flush
+                                            }
+                                            _ => &remainder,
+                                        };
+                                        let Ok(()) = send_remainder(
+                                            &sender,
+                                            remainder,
+                                            output_chunk_len,
+                                            patchwork_sample_rate,
+                                            patchwork_chunk,
+                                        ).await
+                                        else { return; };
+                                    }
                                 }
-                                let Ok(()) = sender.send(event).await else { return; };
+                                let Ok(()) = sender.send(Signal::Event(event)).await
+                                else { return; };
                             }
                         }
                     },
@@ -269,4 +347,47 @@ mod tests {
             } => true,
         ));
     }
+    #[tokio::test]
+    async fn test_rechunker_flush() {
+        // incomplete chunks are not discarded before a flushing event
+        let rechunk = Rechunker::<u8>::new(1000);
+        let lens = crate::tests::flushed_lens(&rechunk, 1.0, vec![0; 2500], 700).await;
+        assert_eq!(lens, [2500; 2]);
+        let rechunk = Rechunker::<u8>::with_remainder(1000, Remainder::Pad(0));
+        let lens = crate::tests::flushed_lens(&rechunk, 1.0, vec![0; 2500], 700).await;
+        assert_eq!(lens, [3000; 2]);
+    }
+    #[tokio::test]
+    async fn test_rechunker_remainder() {
+        for (remainder, expected) in [
+            (Remainder::Flush, vec![4, 5]),
+            (Remainder::Pad(0), vec![4, 5, 0]),
+        ] {
+            let (sender, sender_connector) = new_sender::<Signal<u8>>();
+            let rechunk = Rechunker::<u8>::with_remainder(3, remainder);
+            let (mut receiver, receiver_connector) = new_receiver::<Signal<u8>>();
+            rechunk.feed_from(&sender_connector);
+            rechunk.feed_into(&receiver_connector);
+            let join_handle = tokio::spawn(async move {
+                sender
+                    .send(Signal::Samples {
+                        sample_rate: 1.0,
+                        chunk: Chunk::from(vec![1, 2, 3, 4, 5]),
+                    })
+                    .await
+                    .unwrap();
+                sender.send(Signal::new_event(SamplesLost)).await.unwrap();
+            });
+            let Signal::Samples { chunk, .. } = receiver.recv().await.unwrap()
+            else { panic!(); };
+            assert_eq!(&*chunk, &[1, 2, 3]);
+            let Signal::Samples { chunk, .. } = receiver.recv().await.unwrap()
+            else { panic!(); };
+            assert_eq!(&*chunk, &expected);
+            let Signal::Event(event) = receiver.recv().await.unwrap()
+            else { panic!(); };
+            assert!(event.as_any().is::<SamplesLost>());
+            join_handle.await.unwrap();
+        }
+    }
 }
@@ -0,0 +1,566 @@
+//! Forward error correction (e.g. convolutional codes)
+//!
+//! Coded bits are passed as `Signal<bool>` (hard decisions) or as
+//! `Signal<f32>` (soft decisions, e.g. from a [`Fsk2Slicer`] created with
+//! [`Fsk2Slicer::new_soft`]), where positive values correspond to `true`.
+//! Data is passed as `Signal<u8>`, where the most significant bit of each
+//! byte is sent first. The sample rate of each signal is given in values
+//! (bits or bytes) per second.
+//!
+//! [`Fsk2Slicer`]: crate::blocks::modulation::Fsk2Slicer
+//! [`Fsk2Slicer::new_soft`]: crate::blocks::modulation::Fsk2Slicer::new_soft
+
+use crate::bufferpool::*;
+use crate::flow::*;
+use crate::impl_block_trait;
+use crate::signal::*;
+
+use tokio::task::spawn;
+
+/// Parameters of a (punctured) convolutional code with rate `1/n`
+///
+/// The encoder has a shift register of `constraint_length` bits, which
+/// contains the current input bit and the `constraint_length - 1` previous
+/// input bits. For each input bit, one output bit per polynomial is
+/// calculated as parity of the register bits selected by the polynomial,
+/// where the most significant bit (bit `constraint_length - 1`) of a
+/// polynomial corresponds to the current input bit (as in the common octal
+/// notation).
+///
+/// A puncturing pattern selects which output bits are transmitted; it is
+/// applied repeatedly to the sequence of output bits (`n` output bits per
+/// input bit).
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct ConvolutionalCode {
+    constraint_length: usize,
+    polynomials: Vec<u32>,
+    puncturing: Vec<bool>,
+}
+
+impl Default for ConvolutionalCode {
+    fn default() -> Self {
+        Self::k7_rate_half()
+    }
+}
+
+impl ConvolutionalCode {
+    /// Create code with given `constraint_length` (from `2` to `16`) and
+    /// `polynomials` (one per output bit), without puncturing
+    pub fn new(constraint_length: usize, polynomials: &[u32]) -> Self {
+        assert!(
+            (2..=16).contains(&constraint_length),
+            "constraint length must be between 2 and 16"
+        );
+        assert!(!polynomials.is_empty(), "at least one polynomial required");
+        assert!(
+            polynomials
+                .iter()
+                .all(|&poly| poly != 0 && poly >> constraint_length == 0),
+            "polynomials must be non-zero and within constraint length"
+        );
+        Self {
+            constraint_length,
+            polynomials: polynomials.to_vec(),
+            puncturing: vec![true; polynomials.len()],
+        }
+    }
+    /// Common code with constraint length 7 and rate 1/2 (polynomials `171`
+    /// and `133` in octal notation)
+    pub fn k7_rate_half() -> Self {
+        Self::new(7, &[0o171, 0o133])
+    }
+    /// Apply puncturing `pattern`, where `true` marks transmitted output
+    /// bits
+    ///
+    /// The length of the pattern must be a multiple of the number of
+    /// polynomials. E.g. `[true, true, true, false]` turns a rate 1/2 code
+    /// into a rate 2/3 code.
+    pub fn with_puncturing(mut self, pattern: &[bool]) -> Self {
+        assert!(
+            !pattern.is_empty() && pattern.len() % self.polynomials.len() == 0,
+            "length of puncturing pattern must be a multiple of the number of polynomials"
+        );
+        assert!(
+            pattern.iter().any(|&x| x),
+            "puncturing pattern must transmit bits"
+        );
+        self.puncturing = pattern.to_vec();
+        self
+    }
+    /// Constraint length
+    pub fn constraint_length(&self) -> usize {
+        self.constraint_length
+    }
+    /// Polynomials
+    pub fn polynomials(&self) -> &[u32] {
+        &self.polynomials
+    }
+    /// Puncturing pattern (all `true` if not punctured)
+    pub fn puncturing(&self) -> &[bool] {
+        &self.puncturing
+    }
+    /// Code rate, i.e. number of input bits per transmitted bit
+    pub fn rate(&self) -> f64 {
+        let input_bits = self.puncturing.len() / self.polynomials.len();
+        let transmitted = self.puncturing.iter().filter(|&&x| x).count();
+        input_bits as f64 / transmitted as f64
+    }
+    /// Output bits for given shift `register` (as bit mask)
+    fn output_mask(&self, register: u32) -> usize {
+        self.polynomials
+            .iter()
+            .enumerate()
+            .map(|(index, &poly)| (((register & poly).count_ones() & 1) as usize) << index)
+            .sum()
+    }
+}
+
+/// Types which can be used as coded bits by the [`ViterbiDecoder`]
+///
+/// Implemented for `bool` (hard decisions) and `f32` (soft decisions, e.g.
+/// log-likelihood ratios).
+pub trait SoftBit: Clone + Send + Sync + 'static {
+    /// Soft value, which is positive for `true` and negative for `false`,
+    /// with a magnitude indicating confidence
+    fn soft_value(&self) -> f32;
+}
+
+impl SoftBit for bool {
+    fn soft_value(&self) -> f32 {
+        match self {
+            true => 1.0,
+            false => -1.0,
+        }
+    }
+}
+
+impl SoftBit for f32 {
+    fn soft_value(&self) -> f32 {
+        *self
+    }
+}
+
+/// Block encoding bytes with a [`ConvolutionalCode`]
+///
+/// The block acts as [`Consumer<Signal<u8>>`] and [`Producer<Signal<bool>>`]
+/// and emits the transmitted (i.e. not punctured) coded bits. The state of
+/// the encoder is reset when an [interrupting] event is received. The code is
+/// not terminated, i.e. no tail bits are appended.
+///
+/// [interrupting]: Event::is_interrupt
+pub struct ConvolutionalEncoder {
+    receiver_connector: ReceiverConnector<Signal<u8>>,
+    sender_connector: SenderConnector<Signal<bool>>,
+}
+
+impl_block_trait! { Consumer<Signal<u8>> for ConvolutionalEncoder }
+impl_block_trait! { Producer<Signal<bool>> for ConvolutionalEncoder }
+
+impl ConvolutionalEncoder {
+    /// Create new `ConvolutionalEncoder` block for given `code`
+    pub fn new(code: ConvolutionalCode) -> Self {
+        let (mut receiver, receiver_connector) = new_receiver::<Signal<u8>>();
+        let (sender, sender_connector) = new_sender::<Signal<bool>>();
+        spawn(async move {
+            let mut buf_pool = ChunkBufPool::<bool>::new();
+            let outputs = code.polynomials.len();
+            let shift = code.constraint_length - 1;
+            let mut register: u32 = 0;
+            let mut puncturing_pos: usize = 0;
+            loop {
+                let Ok(signal) = receiver.recv().await else { return; };
+                match signal {
+                    Signal::Samples {
+                        sample_rate,
+                        chunk: input_chunk,
+                    } => {
+                        let mut output_chunk = buf_pool.get();
+                        for &byte in input_chunk.iter() {
+                            for bit in (0..8).rev().map(|i| (byte >> i) & 1 != 0) {
+                                register = (register >> 1) | ((bit as u32) << shift);
+                                let mask = code.output_mask(register);
+                                for index in 0..outputs {
+                                    if code.puncturing[puncturing_pos] {
+                                        output_chunk.push((mask >> index) & 1 != 0);
+                                    }
+                                    puncturing_pos = (puncturing_pos + 1) % code.puncturing.len();
+                                }
+                            }
+                        }
+                        let Ok(()) = sender
+                            .send(Signal::Samples {
+                                sample_rate: sample_rate * 8.0 / code.rate(),
+                                chunk: output_chunk.finalize(),
+                            })
+                            .await
+                        else { return; };
+                    }
+                    Signal::Event(event) => {
+                        if event.is_interrupt() {
+                            register = 0;
+                            puncturing_pos = 0;
+                        }
+                        let Ok(()) = sender.send(Signal::Event(event)).await
+                        else { return; };
+                    }
+                }
+            }
+        });
+        Self {
+            receiver_connector,
+            sender_connector,
+        }
+    }
+}
+
+/// State of the Viterbi algorithm
+struct Viterbi {
+    /// Number of bits of the state (constraint length minus one)
+    state_bits: usize,
+    /// Expected output bits (as mask) for each shift register value
+    output_masks: Vec<usize>,
+    /// Accumulated metric for each state
+    metrics: Vec<f32>,
+    /// Decided oldest register bit for each step and state (as bit field)
+    decisions: Vec<u64>,
+    /// Size of the decisions for each step in `u64` words
+    words_per_step: usize,
+}
+
+impl Viterbi {
+    fn new(code: &ConvolutionalCode) -> Self {
+        let state_bits = code.constraint_length - 1;
+        let states = 1usize << state_bits;
+        Self {
+            state_bits,
+            output_masks: (0..2 * states as u32)
+                .map(|register| code.output_mask(register))
+                .collect(),
+            metrics: vec![0.0; states],
+            decisions: Vec::new(),
+            words_per_step: states.div_ceil(64),
+        }
+    }
+    fn reset(&mut self) {
+        self.metrics.fill(0.0);
+        self.decisions.clear();
+    }
+    /// Number of processed steps which have not been traced back yet
+    fn pending(&self) -> usize {
+        self.decisions.len() / self.words_per_step
+    }
+    /// Process soft values of the output bits for one input bit
+    fn step(&mut self, soft_values: &[f32]) {
+        let branch_metrics: Vec<f32> = (0..1usize << soft_values.len())
+            .map(|mask| {
+                soft_values
+                    .iter()
+                    .enumerate()
+                    .map(|(index, &value)| match mask >> index & 1 != 0 {
+                        true => value,
+                        false => -value,
+                    })
+                    .sum()
+            })
+            .collect();
+        let states = self.metrics.len();
+        let first_word = self.decisions.len();
+        self.decisions.resize(first_word + self.words_per_step, 0);
+        let mut new_metrics = vec![f32::NEG_INFINITY; states];
+        for (state, new_metric) in new_metrics.iter_mut().enumerate() {
+            // the new state contains the input bit as most significant bit
+            // and the previous state without its oldest bit
+            let input = state >> (self.state_bits - 1);
+            let mut best_oldest = 0;
+            for oldest in 0..2 {
+                let previous = (state << 1 & (states - 1)) | oldest;
+                let register = input << self.state_bits | previous;
+                let metric = self.metrics[previous] + branch_metrics[self.output_masks[register]];
+                if metric > *new_metric {
+                    *new_metric = metric;
+                    best_oldest = oldest;
+                }
+            }
+            if best_oldest != 0 {
+                self.decisions[first_word + state / 64] |= 1 << (state % 64);
+            }
+        }
+        let max = new_metrics
+            .iter()
+            .copied()
+            .fold(f32::NEG_INFINITY, f32::max);
+        for metric in new_metrics.iter_mut() {
+            *metric -= max;
+        }
+        self.metrics = new_metrics;
+    }
+    /// Trace back from the best state and return the oldest `count`
+    /// decoded bits, which are removed from the history
+    fn trace_back(&mut self, count: usize) -> Vec<bool> {
+        let states = self.metrics.len();
+        let mut state = (0..states)
+            .max_by(|&a, &b| self.metrics[a].total_cmp(&self.metrics[b]))
+            .unwrap();
+        let mut bits: Vec<bool> = Vec::with_capacity(self.pending());
+        for step in (0..self.pending()).rev() {
+            bits.push(state >> (self.state_bits - 1) != 0);
+            let word = self.decisions[step * self.words_per_step + state / 64];
+            let oldest = (word >> (state % 64) & 1) as usize;
+            state = (state << 1 & (states - 1)) | oldest;
+        }
+        bits.reverse();
+        bits.truncate(count);
+        self.decisions.drain(0..count * self.words_per_step);
+        bits
+    }
+}
+
+/// Collects bits (most significant bit first) into bytes
+#[derive(Default)]
+struct BytePacker {
+    byte: u8,
+    bits: usize,
+}
+
+impl BytePacker {
+    fn push(&mut self, bits: Vec<bool>, output: &mut Vec<u8>) {
+        for bit in bits {
+            self.byte = (self.byte << 1) | bit as u8;
+            self.bits += 1;
+            if self.bits == 8 {
+                output.push(self.byte);
+                self.bits = 0;
+            }
+        }
+    }
+}
+
+/// Block decoding a [`ConvolutionalCode`] with the Viterbi algorithm
+///
+/// The block acts as [`Consumer<Signal<In>>`], where `In` is either `bool`
+/// (hard decisions) or `f32` (soft decisions, see [`SoftBit`]), and as
+/// [`Producer<Signal<u8>>`]. Punctured bits are treated as erasures.
+///
+/// Decoded bits are emitted with a delay of up to
+/// [`TRACEBACK_LENGTH`](Self::TRACEBACK_LENGTH) times the constraint length
+/// (in decoded bits). When a [flushing] event is received, all pending bits
+/// are emitted (except for an incomplete byte) before the event is passed on.
+/// The state of the decoder is reset on any [interrupting] event.
+///
+/// [interrupting]: Event::is_interrupt
+/// [flushing]: Event::is_flush
+pub struct ViterbiDecoder<In> {
+    receiver_connector: ReceiverConnector<Signal<In>>,
+    sender_connector: SenderConnector<Signal<u8>>,
+}
+
+impl_block_trait! { <In> Consumer<Signal<In>> for ViterbiDecoder<In> }
+impl_block_trait! { <In> Producer<Signal<u8>> for ViterbiDecoder<In> }
+
+impl<In> ViterbiDecoder<In>
+where
+    In: SoftBit,
+{
+    /// Number of decoded bits (per constraint length) used for the decision
+    pub const TRACEBACK_LENGTH: usize = 8;
+    /// Create new `ViterbiDecoder` block for given `code`
+    pub fn new(code: ConvolutionalCode) -> Self {
+        let (mut receiver, receiver_connector) = new_receiver::<Signal<In>>();
+        let (sender, sender_connector) = new_sender::<Signal<u8>>();
+        spawn(async move {
+            let mut buf_pool = ChunkBufPool::<u8>::new();
+            let depth = Self::TRACEBACK_LENGTH * code.constraint_length;
+            let mut viterbi = Viterbi::new(&code);
+            let mut soft_values: Vec<f32> = Vec::with_capacity(code.polynomials.len());
+            let mut puncturing_pos: usize = 0;
+            let mut packer = BytePacker::default();
+            let mut prev_sample_rate: Option<f64> = None;
+            loop {
+                let Ok(signal) = receiver.recv().await else { return; };
+                match signal {
+                    Signal::Samples {
+                        sample_rate,
+                        chunk: input_chunk,
+                    } => {
+                        prev_sample_rate = Some(sample_rate);
+                        let mut output_chunk = buf_pool.get();
+                        for value in input_chunk.iter() {
+                            let mut value = Some(value.soft_value());
+                            while let Some(soft_value) = match code.puncturing[puncturing_pos] {
+                                true => value.take(),
+                                false => Some(0.0),
+                            } {
+                                soft_values.push(soft_value);
+                                puncturing_pos = (puncturing_pos + 1) % code.puncturing.len();
+                                if soft_values.len() == code.polynomials.len() {
+                                    viterbi.step(&soft_values);
+                                    soft_values.clear();
+                                }
+                            }
+                        }
+                        if viterbi.pending() >= 2 * depth {
+                            let count = viterbi.pending() - depth;
+                            packer.push(viterbi.trace_back(count), &mut output_chunk);
+                        }
+                        if !output_chunk.is_empty() {
+                            let Ok(()) = sender
+                                .send(Signal::Samples {
+                                    sample_rate: sample_rate * code.rate() / 8.0,
+                                    chunk: output_chunk.finalize(),
+                                })
+                                .await
+                            else { return; };
+                        }
+                    }
+                    Signal::Event(event) => {
+                        if event.is_flush() {
+                            let mut output_chunk = buf_pool.get();
+                            let count = viterbi.pending();
+                            packer.push(viterbi.trace_back(count), &mut output_chunk);
+                            if let Some(sample_rate) =
+                                prev_sample_rate.filter(|_| !output_chunk.is_empty())
+                            {
+                                let Ok(()) = sender
+                                    .send(Signal::Samples {
+                                        sample_rate: sample_rate * code.rate() / 8.0,
+                                        chunk: output_chunk.finalize(),
+                                    })
+                                    .await
+                                else { return; };
+                            }
+                        }
+                        if event.is_interrupt() {
+                            viterbi.reset();
+                            soft_values.clear();
+                            puncturing_pos = 0;
+                            packer = BytePacker::default();
+                        }
+                        let Ok(()) = sender.send(Signal::Event(event)).await
+                        else { return; };
+                    }
+                }
+            }
+        });
+        Self {
+            receiver_connector,
+            sender_connector,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    async fn encode(code: &ConvolutionalCode, data: &[u8]) -> Vec<bool> {
+        let (sender, sender_connector) = new_sender::<Signal<u8>>();
+        let encoder = ConvolutionalEncoder::new(code.clone());
+        let (mut receiver, receiver_connector) = new_receiver::<Signal<bool>>();
+        encoder.feed_from(&sender_connector);
+        encoder.feed_into(&receiver_connector);
+        let (result, signal) = tokio::join!(
+            sender.send(Signal::Samples {
+                sample_rate: 100.0,
+                chunk: Chunk::from(data.to_vec()),
+            }),
+            receiver.recv()
+        );
+        result.unwrap();
+        let Signal::Samples { sample_rate, chunk } = signal.unwrap() else { panic!(); };
+        assert_eq!(sample_rate, 800.0 / code.rate());
+        chunk.to_vec()
+    }
+    async fn decode<In: SoftBit>(code: &ConvolutionalCode, coded: Vec<In>) -> Vec<u8> {
+        let (sender, sender_connector) = new_sender::<Signal<In>>();
+        let decoder = ViterbiDecoder::<In>::new(code.clone());
+        let (mut receiver, receiver_connector) = new_receiver::<Signal<u8>>();
+        decoder.feed_from(&sender_connector);
+        decoder.feed_into(&receiver_connector);
+        let join_handle = tokio::spawn(async move {
+            for chunk in coded.chunks(100) {
+                sender
+                    .send(Signal::Samples {
+                        sample_rate: 1000.0,
+                        chunk: Chunk::from(chunk.to_vec()),
+                    })
+                    .await
+                    .unwrap();
+            }
+            sender.send(Signal::new_event(Flush)).await.unwrap();
+        });
+        let mut data = Vec::new();
+        while let Signal::Samples { chunk, .. } = receiver.recv().await.unwrap() {
+            data.extend_from_slice(&chunk);
+        }
+        join_handle.await.unwrap();
+        data
+    }
+    fn test_data() -> Vec<u8> {
+        let mut rng = 1u32;
+        (0..300)
+            .map(|_| {
+                rng = rng.wrapping_mul(1664525).wrapping_add(1013904223);
+                (rng >> 24) as u8
+            })
+            .collect()
+    }
+    #[test]
+    fn test_code_rate() {
+        assert_eq!(ConvolutionalCode::default().rate(), 0.5);
+        let punctured =
+            ConvolutionalCode::k7_rate_half().with_puncturing(&[true, true, true, false]);
+        assert!((punctured.rate() - 2.0 / 3.0).abs() < 1e-12);
+    }
+    #[tokio::test]
+    async fn test_convolutional_encoder() {
+        // impulse response of the encoder consists of the polynomials
+        let code = ConvolutionalCode::k7_rate_half();
+        let coded = encode(&code, &[0x80, 0x00]).await;
+        let expected: Vec<bool> = "11 10 11 11 00 01 11 00"
+            .chars()
+            .filter(|&c| c != ' ')
+            .map(|c| c == '1')
+            .collect();
+        assert_eq!(&coded[0..16], &expected[..]);
+        assert!(coded[16..].iter().all(|&bit| !bit));
+    }
+    #[tokio::test]
+    async fn test_viterbi_hard() {
+        let code = ConvolutionalCode::k7_rate_half();
+        let data = test_data();
+        let mut coded = encode(&code, &data).await;
+        // flip isolated bits (3% of all bits)
+        for (index, bit) in coded.iter_mut().enumerate() {
+            if index % 33 == 5 {
+                *bit = !*bit;
+            }
+        }
+        assert_eq!(decode(&code, coded).await, data);
+    }
+    #[tokio::test]
+    async fn test_viterbi_soft_punctured() {
+        let code = ConvolutionalCode::k7_rate_half().with_puncturing(&[true, true, true, false]);
+        let data = test_data();
+        let coded = encode(&code, &data).await;
+        assert_eq!(coded.len(), data.len() * 8 * 3 / 2);
+        // add noise with a standard deviation of 0.5
+        let mut rng = 7u32;
+        let mut random = move || {
+            rng = rng.wrapping_mul(1664525).wrapping_add(1013904223);
+            rng as f32 / u32::MAX as f32
+        };
+        let soft: Vec<f32> = coded
+            .iter()
+            .map(|&bit| {
+                let noise = ((0..12).map(|_| random()).sum::<f32>() - 6.0) * 0.5;
+                bit.soft_value() + noise
+            })
+            .collect();
+        let hard_errors = soft
+            .iter()
+            .zip(coded.iter())
+            .filter(|&(&value, &bit)| (value > 0.0) != bit)
+            .count();
+        assert!(hard_errors > 50);
+        assert_eq!(decode(&code, soft).await, data);
+    }
+}
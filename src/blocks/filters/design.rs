@@ -0,0 +1,225 @@
+//! Design of FIR filter coefficients (e.g. for [`FirFilter`] or [`FftFirFilter`])
+//!
+//! All functions in this module return the coefficients (impulse response)
+//! of a linear phase filter. Except for [`rrc`], they use the windowed-sinc
+//! method, where the passed [`Window`] determines the trade-off between
+//! transition width and stop band attenuation.
+//!
+//! [`FirFilter`]: super::FirFilter
+//! [`FftFirFilter`]: super::FftFirFilter
+
+use crate::math::*;
+use crate::numbers::*;
+use crate::windowing::Window;
+
+fn windowed_sinc(cutoff: f64, sample_rate: f64, num_taps: usize, window: &dyn Window) -> Vec<f64> {
+    let relative_cutoff = 2.0 * cutoff / sample_rate;
+    let n_flt = num_taps as f64;
+    (0..num_taps)
+        .map(|i| {
+            let x = i as f64 - (n_flt - 1.0) / 2.0;
+            relative_cutoff
+                * sinc(relative_cutoff * x)
+                * window.relative_value_at(2.0 * (i as f64 + 0.5) / n_flt - 1.0)
+        })
+        .collect()
+}
+
+fn gain_at(coeffs: &[f64], frequency: f64, sample_rate: f64) -> f64 {
+    use std::f64::consts::TAU;
+    let mut sum = Complex::<f64>::from(0.0);
+    for (i, &h) in coeffs.iter().enumerate() {
+        sum += Complex::from_polar(h, -TAU * frequency * i as f64 / sample_rate);
+    }
+    sum.norm()
+}
+
+fn normalize<Flt: Float>(coeffs: Vec<f64>, frequency: f64, sample_rate: f64) -> Vec<Flt> {
+    let scale = gain_at(&coeffs, frequency, sample_rate).recip();
+    coeffs.into_iter().map(|h| flt!(h * scale)).collect()
+}
+
+/// Low-pass filter with given `cutoff` frequency in hertz
+///
+/// The gain at DC is normalized to unity.
+pub fn lowpass<Flt: Float>(
+    cutoff: f64,
+    sample_rate: f64,
+    num_taps: usize,
+    window: &dyn Window,
+) -> Vec<Flt> {
+    assert!(num_taps > 0, "number of taps must be positive");
+    normalize(
+        windowed_sinc(cutoff, sample_rate, num_taps, window),
+        0.0,
+        sample_rate,
+    )
+}
+
+/// High-pass filter with given `cutoff` frequency in hertz
+///
+/// The number of taps must be odd. The gain at the Nyquist frequency is
+/// normalized to unity.
+pub fn highpass<Flt: Float>(
+    cutoff: f64,
+    sample_rate: f64,
+    num_taps: usize,
+    window: &dyn Window,
+) -> Vec<Flt> {
+    assert!(num_taps % 2 == 1, "number of taps must be odd");
+    let mut coeffs = windowed_sinc(cutoff, sample_rate, num_taps, window);
+    let dc_gain: f64 = coeffs.iter().sum();
+    for h in coeffs.iter_mut() {
+        *h = -*h / dc_gain;
+    }
+    coeffs[num_taps / 2] += 1.0;
+    normalize(coeffs, sample_rate / 2.0, sample_rate)
+}
+
+/// Band-pass filter passing frequencies between `low` and `high` (in hertz)
+///
+/// The gain at the center frequency is normalized to unity.
+pub fn bandpass<Flt: Float>(
+    low: f64,
+    high: f64,
+    sample_rate: f64,
+    num_taps: usize,
+    window: &dyn Window,
+) -> Vec<Flt> {
+    assert!(num_taps > 0, "number of taps must be positive");
+    assert!(
+        low < high,
+        "lower frequency must be smaller than higher frequency"
+    );
+    let coeffs: Vec<f64> = windowed_sinc(high, sample_rate, num_taps, window)
+        .into_iter()
+        .zip(windowed_sinc(low, sample_rate, num_taps, window))
+        .map(|(a, b)| a - b)
+        .collect();
+    normalize(coeffs, (low + high) / 2.0, sample_rate)
+}
+
+/// Hilbert transformer (phase shift of −90° for positive frequencies)
+///
+/// The number of taps must be odd. The gain at a quarter of the sample rate
+/// is normalized to unity.
+pub fn hilbert<Flt: Float>(num_taps: usize, window: &dyn Window) -> Vec<Flt> {
+    use std::f64::consts::PI;
+    assert!(num_taps % 2 == 1, "number of taps must be odd");
+    let n_flt = num_taps as f64;
+    let coeffs: Vec<f64> = (0..num_taps)
+        .map(|i| {
+            let k = i as isize - (num_taps / 2) as isize;
+            if k % 2 == 0 {
+                0.0
+            } else {
+                2.0 / (PI * k as f64)
+                    * window.relative_value_at(2.0 * (i as f64 + 0.5) / n_flt - 1.0)
+            }
+        })
+        .collect();
+    normalize(coeffs, 0.25, 1.0)
+}
+
+/// Root-raised-cosine filter (for pulse shaping and matched filtering)
+///
+/// The impulse response spans `span_symbols` symbols with
+/// `samples_per_symbol` samples each (plus one sample, such that the number
+/// of taps is odd if `span_symbols * samples_per_symbol` is even). The
+/// `rolloff` factor (between `0.0` and `1.0`) determines the excess
+/// bandwidth. The coefficients are normalized to unit energy, such that two
+/// concatenated filters result in a raised-cosine response with a gain of
+/// unity at the symbol center and zero intersymbol interference.
+pub fn rrc<Flt: Float>(span_symbols: usize, samples_per_symbol: usize, rolloff: f64) -> Vec<Flt> {
+    use std::f64::consts::{FRAC_1_SQRT_2, PI};
+    assert!(span_symbols > 0, "span must be positive");
+    assert!(
+        samples_per_symbol > 0,
+        "samples per symbol must be positive"
+    );
+    assert!(
+        (0.0..=1.0).contains(&rolloff),
+        "rolloff must be between 0 and 1"
+    );
+    let num_taps = span_symbols * samples_per_symbol + 1;
+    let beta = rolloff;
+    let coeffs: Vec<f64> = (0..num_taps)
+        .map(|i| {
+            let t = (i as f64 - (num_taps - 1) as f64 / 2.0) / samples_per_symbol as f64;
+            if t == 0.0 {
+                1.0 - beta + 4.0 * beta / PI
+            } else if beta > 0.0 && (4.0 * beta * t.abs() - 1.0).abs() < 1e-9 {
+                beta * FRAC_1_SQRT_2
+                    * ((1.0 + 2.0 / PI) * (PI / (4.0 * beta)).sin()
+                        + (1.0 - 2.0 / PI) * (PI / (4.0 * beta)).cos())
+            } else {
+                ((PI * t * (1.0 - beta)).sin() + 4.0 * beta * t * (PI * t * (1.0 + beta)).cos())
+                    / (PI * t * (1.0 - (4.0 * beta * t).powi(2)))
+            }
+        })
+        .collect();
+    let scale = coeffs.iter().map(|h| h * h).sum::<f64>().sqrt().recip();
+    coeffs.into_iter().map(|h| flt!(h * scale)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::assert_approx;
+    use crate::windowing::{Blackman, Hamming, Kaiser};
+    fn db_at(coeffs: &[f64], frequency: f64) -> f64 {
+        20.0 * gain_at(coeffs, frequency, 48000.0).log10()
+    }
+    #[test]
+    fn test_lowpass() {
+        let coeffs: Vec<f64> = lowpass(4000.0, 48000.0, 101, &Blackman);
+        assert_approx(gain_at(&coeffs, 0.0, 48000.0), 1.0);
+        assert!((db_at(&coeffs, 4000.0) + 6.0).abs() < 0.5);
+        assert!(db_at(&coeffs, 6000.0) < -70.0);
+        assert!(db_at(&coeffs, 12000.0) < -70.0);
+    }
+    #[test]
+    fn test_highpass() {
+        let coeffs: Vec<f64> = highpass(4000.0, 48000.0, 101, &Hamming);
+        assert_approx(gain_at(&coeffs, 24000.0, 48000.0), 1.0);
+        assert!(db_at(&coeffs, 0.0) < -50.0);
+        assert!(db_at(&coeffs, 2000.0) < -40.0);
+        assert!(db_at(&coeffs, 8000.0).abs() < 0.1);
+    }
+    #[test]
+    fn test_bandpass() {
+        let coeffs: Vec<f64> = bandpass(6000.0, 10000.0, 48000.0, 201, &Kaiser::with_beta(8.0));
+        assert_approx(gain_at(&coeffs, 8000.0, 48000.0), 1.0);
+        assert!(db_at(&coeffs, 0.0) < -70.0);
+        assert!(db_at(&coeffs, 3000.0) < -70.0);
+        assert!(db_at(&coeffs, 13000.0) < -70.0);
+    }
+    #[test]
+    fn test_hilbert() {
+        let coeffs: Vec<f64> = hilbert(65, &Hamming);
+        assert_approx(gain_at(&coeffs, 12000.0, 48000.0), 1.0);
+        assert!(db_at(&coeffs, 0.0) < -50.0);
+        assert!(db_at(&coeffs, 2000.0).abs() < 0.1);
+        assert!(db_at(&coeffs, 22000.0).abs() < 0.1);
+    }
+    #[test]
+    fn test_rrc() {
+        for (samples_per_symbol, rolloff) in [(4, 0.35), (8, 0.5), (5, 0.25)] {
+            let coeffs: Vec<f64> = rrc(12, samples_per_symbol, rolloff);
+            assert_eq!(coeffs.len(), 12 * samples_per_symbol + 1);
+            let mut raised_cosine = vec![0.0; 2 * coeffs.len() - 1];
+            for (i, a) in coeffs.iter().enumerate() {
+                for (j, b) in coeffs.iter().enumerate() {
+                    raised_cosine[i + j] += a * b;
+                }
+            }
+            let center = coeffs.len() - 1;
+            assert_approx(raised_cosine[center], 1.0);
+            for k in 1..6 {
+                assert!(raised_cosine[center + k * samples_per_symbol].abs() < 0.01);
+                assert!(raised_cosine[center - k * samples_per_symbol].abs() < 0.01);
+            }
+            assert!(raised_cosine[center + samples_per_symbol / 2].abs() > 0.3);
+        }
+    }
+}
@@ -0,0 +1,1970 @@
+//! Digital filters
+//!
+//! All filters clear their state (e.g. history, or adapted weights and
+//! noise estimates) when an [interrupting] event is received.
+//!
+//! [interrupting]: Event::is_interrupt
+
+use crate::bufferpool::*;
+use crate::flow::*;
+use crate::impl_block_trait;
+use crate::kernels::{BiquadKernel, FirKernel};
+use crate::math::{fft, window};
+use crate::numbers::*;
+use crate::signal::*;
+use crate::windowing::{Kaiser, Rectangular, Window};
+
+use tokio::sync::watch;
+use tokio::task::spawn;
+
+use std::sync::Arc;
+
+pub mod design;
+
+/// Complex amplification factor for deemphasis in frequency demodulation
+///
+/// The time constant `tau` corresponds to the product of the resistance *R*
+/// and the capacity *C* of a passive first-order RC low-pass, e.g. `50e-6`.
+pub fn deemphasis_factor(tau: f64, frequency: f64) -> Complex<f64> {
+    use std::f64::consts::TAU as TWO_PI;
+    Complex {
+        re: 1.0,
+        im: tau * TWO_PI * frequency,
+    }
+    .finv()
+}
+
+// TODO: use trait alias in public interface
+// (but this currently requires extra type annotations on usage)
+trait FreqRespFunc: Fn(isize, f64) -> Complex<f64> {}
+impl<T: ?Sized> FreqRespFunc for T where T: Fn(isize, f64) -> Complex<f64> {}
+
+struct FilterParams {
+    freq_resp: Box<dyn FreqRespFunc + Send + Sync>,
+    window: Box<dyn Window + Send + Sync>,
+}
+
+/// General purpose frequency filter using fast convolution
+///
+/// Behavior of the filter is controlled by passing a closure to one of the
+/// filter's methods. The closure is called with a DFT bin index (which may be
+/// ignored in most cases) and the corresponding *signed* frequency (as [`f64`]
+/// in hertz) as arguments. The closure must then return a complex
+/// amplification factor (as [`Complex<f64>`]) for the given frequency.
+///
+/// ```
+/// # fn doc() {
+/// use radiorust::{blocks, numbers::Complex};
+/// // low-pass filter with cutoff at 16 kHz
+/// let my_filter = blocks::filters::Filter::<f32>::new(|_, freq| {
+///     if freq.abs() <= 16e3 {
+///         Complex::from(1.0)
+///     } else {
+///         Complex::from(0.0)
+///     }
+/// });
+/// # }
+/// ```
+///
+/// This allows easy implementation of (linear phase) low-pass, high-pass,
+/// band-pass, band-stop, and sideband filters, as well as (non-linear phase)
+/// all-pass filters, or any combination thereof.
+///
+/// Frequency resolution depends on the [`sample_rate`] and [`chunk`]
+/// length of received [`Signal::Samples`] as well as the selected [`Window`]
+/// function.
+/// Using [`Kaiser::with_null_at_bin(x)`] results in a frequency resolution in
+/// hertz of `x * sample_rate / chunk.len()`, but higher `x` improve stop band
+/// attenuation (`x` must be `>= 1.0` and defaults to `2.0`).
+/// To increase frequency resolution without worsening stop band attenuation,
+/// increase the chunk length of the received samples. Note, however, that this
+/// will also increase the delay of the filter.
+///
+/// You may use a [`Rechunker`] block to adjust the chunk length which the
+/// filter is operating with if this cannot be achieved otherwise, e.g. through
+/// an existing [`Downsampler`] or [`Upsampler`] block.
+///
+/// The impulse response is equal to the `chunk` length and the delay of the
+/// filter is one `chunk`, i.e. after the second chunk has been received, the
+/// first output chunk is ready to be sent out. When a [flushing] event is
+/// received, the output of the last chunk is sent out before the event.
+///
+/// When implementing DC blockers or notch filters, frequency resolution plays
+/// an important role. To aid filter implementation, the closure calculating
+/// the frequency response gets the DFT bin number as first argument.
+/// A filter with DC blocker can, for example, be implemented as follows:
+///
+/// ```
+/// # fn doc() {
+/// use radiorust::{blocks, numbers::Complex};
+/// let dc_blocker = blocks::filters::Filter::<f32>::new(|bin, _| {
+///     // NOTE: window function defaults to `Kaiser::with_null_at_bin(2.0)`,
+///     // thus `2` is used as boundary below:
+///     if bin.abs() < 2 {
+///         Complex::from(0.0)
+///     } else {
+///         /* … */
+/// #       Complex::from(1.0)
+///     }
+/// });
+/// # }
+/// ```
+///
+/// [`sample_rate`]: Signal::Samples::sample_rate
+/// [`chunk`]: Signal::Samples::chunk
+/// [`Kaiser::with_null_at_bin(x)`]: Kaiser::with_null_at_bin
+/// [`Rechunker`]: crate::blocks::chunks::Rechunker
+/// [`Downsampler`]: crate::blocks::resampling::Downsampler
+/// [`Upsampler`]: crate::blocks::resampling::Upsampler
+/// [flushing]: Event::is_flush
+pub struct Filter<Flt> {
+    receiver_connector: ReceiverConnector<Signal<Complex<Flt>>>,
+    sender_connector: SenderConnector<Signal<Complex<Flt>>>,
+    params: watch::Sender<FilterParams>,
+}
+
+impl_block_trait! { <Flt> Consumer<Signal<Complex<Flt>>> for Filter<Flt> }
+impl_block_trait! { <Flt> Producer<Signal<Complex<Flt>>> for Filter<Flt> }
+
+impl<Flt> Filter<Flt>
+where
+    Flt: Float,
+{
+    /// Create new `Filter` block with given frequency response with Kaiser window
+    ///
+    /// The used [`Window`] function is [`Kaiser::with_null_at_bin(2.0)`].
+    ///
+    /// [`Kaiser::with_null_at_bin(2.0)`]: Kaiser::with_null_at_bin
+    pub fn new<F>(freq_resp: F) -> Self
+    where
+        F: Fn(isize, f64) -> Complex<f64> + Send + Sync + 'static,
+    {
+        Self::new_internal(Box::new(freq_resp), Box::new(Kaiser::with_null_at_bin(2.0)))
+    }
+    /// Create new `Filter` block with given frequency response with rectangular window
+    ///
+    /// Compared to [`Filter::new`], this creates a filter with better
+    /// frequency resolution but worse stop band attenuation.
+    pub fn new_rectangular<F>(freq_resp: F) -> Self
+    where
+        F: Fn(isize, f64) -> Complex<f64> + Send + Sync + 'static,
+    {
+        Self::new_internal(Box::new(freq_resp), Box::new(Rectangular))
+    }
+    /// Create new `Filter` block with given frequency response and window
+    /// function
+    pub fn with_window<F, W>(freq_resp: F, window: W) -> Self
+    where
+        F: Fn(isize, f64) -> Complex<f64> + Send + Sync + 'static,
+        W: Window + Send + Sync + 'static,
+    {
+        Self::new_internal(Box::new(freq_resp), Box::new(window))
+    }
+    fn new_internal(
+        freq_resp: Box<dyn FreqRespFunc + Send + Sync>,
+        window: Box<dyn Window + Send + Sync>,
+    ) -> Self {
+        let (mut receiver, receiver_connector) = new_receiver::<Signal<Complex<Flt>>>();
+        let (sender, sender_connector) = new_sender::<Signal<Complex<Flt>>>();
+        let (params_send, mut params_recv) = watch::channel(FilterParams { freq_resp, window });
+        spawn(async move {
+            let mut buf_pool = ChunkBufPool::<Complex<Flt>>::new();
+            let mut prev_sample_rate: Option<f64> = None;
+            let mut prev_input_chunk_len: Option<usize> = None;
+            let mut previous_chunk: Option<Chunk<Complex<Flt>>> = None;
+            let mut extended_response: Vec<Complex<Flt>> = Default::default();
+            loop {
+                let Ok(signal) = receiver.recv().await else { return; };
+                match signal {
+                    Signal::Samples {
+                        sample_rate,
+                        chunk: input_chunk,
+                    } => {
+                        let n = input_chunk.len();
+                        let recalculate: bool = params_recv.has_changed().unwrap_or(false)
+                            || Some(sample_rate) != prev_sample_rate
+                            || Some(n) != prev_input_chunk_len;
+                        prev_sample_rate = Some(sample_rate);
+                        prev_input_chunk_len = Some(n);
+                        if recalculate {
+                            let n_flt = n as f64;
+                            let scale = 2.0 * n_flt * n_flt;
+                            previous_chunk = None;
+                            let mut response = vec![Complex::<f64>::from(0.0); n];
+                            let freq_step: f64 = sample_rate / n_flt;
+                            let max_bin_abs = (n - 1) / 2;
+                            let params = params_recv.borrow_and_update();
+                            let freq_resp_func = &params.freq_resp;
+                            for i in 0..=max_bin_abs {
+                                let freq = i as f64 * freq_step;
+                                response[i] = freq_resp_func(i as isize, freq) / scale;
+                                if i > 0 {
+                                    response[n - i] = freq_resp_func(-(i as isize), -freq) / scale;
+                                }
+                            }
+                            fft::inverse(&mut response);
+                            for i in 0..(n / 2) {
+                                response.swap(i, i + n / 2);
+                            }
+                            let mut energy_pre: f64 = 0.0;
+                            let mut energy_post: f64 = 0.0;
+                            for i in 0..n {
+                                energy_pre += response[i].norm_sqr();
+                                response[i] *= Complex::from(
+                                    params
+                                        .window
+                                        .relative_value_at(2.0 * (i as f64 + 0.5) / n_flt - 1.0),
+                                );
+                                energy_post += response[i].norm_sqr();
+                            }
+                            drop(params);
+                            let scale = (energy_pre / energy_post).sqrt();
+                            for y in response.iter_mut() {
+                                *y *= scale;
+                            }
+                            extended_response.clear();
+                            extended_response.reserve(n * 2);
+                            extended_response.resize(n, Complex::from(flt!(0.0)));
+                            extended_response.extend(response.into_iter().map(|x| Complex {
+                                re: flt!(x.re),
+                                im: flt!(x.im),
+                            }));
+                            fft::forward(&mut extended_response);
+                        }
+                        if let Some(previous_chunk) = &previous_chunk {
+                            let mut output_chunk = buf_pool.get_with_capacity(n * 2);
+                            output_chunk.extend_from_slice(previous_chunk);
+                            output_chunk.extend_from_slice(&input_chunk);
+                            fft::forward(&mut output_chunk);
+                            for i in 0..n * 2 {
+                                output_chunk[i] *= extended_response[i];
+                            }
+                            fft::inverse(&mut output_chunk);
+                            output_chunk.truncate(n);
+                            let Ok(()) = sender.send(Signal::Samples {
+                                sample_rate,
+                                chunk: output_chunk.finalize(),
+                             }).await
+                            else { return; };
+                        }
+                        previous_chunk = Some(input_chunk);
+                    }
+                    Signal::Event(event) => {
+                        if event.is_flush() {
+                            if let Some(previous_chunk) = previous_chunk.take() {
+                                let n = previous_chunk.len();
+                                let mut output_chunk = buf_pool.get_with_capacity(n * 2);
+                                output_chunk.extend_from_slice(&previous_chunk);
+                                output_chunk.resize(n * 2, Complex::from(Flt::zero()));
+                                fft::forward(&mut output_chunk);
+                                for i in 0..n * 2 {
+                                    output_chunk[i] *= extended_response[i];
+                                }
+                                fft::inverse(&mut output_chunk);
+                                output_chunk.truncate(n);
+                                let Ok(()) = sender
+                                    .send(Signal::Samples {
+                                        sample_rate: prev_sample_rate.unwrap(),
+                                        chunk: output_chunk.finalize(),
+                                    })
+                                    .await
+                                else { return; };
+                            }
+                        }
+                        if event.is_interrupt() {
+                            previous_chunk = None;
+                        }
+                        let Ok(()) = sender.send(Signal::Event(event)).await
+                        else { return; };
+                    }
+                }
+            }
+        });
+        Self {
+            receiver_connector,
+            sender_connector,
+            params: params_send,
+        }
+    }
+    /// Update frequency response and leave window function unchanged
+    pub fn update<F>(&self, freq_resp: F)
+    where
+        F: Fn(isize, f64) -> Complex<f64> + Send + Sync + 'static,
+    {
+        self.params.send_modify(|params| {
+            params.freq_resp = Box::new(freq_resp);
+        });
+    }
+    /// Update frequency response and window function
+    pub fn update_with_window<F, W>(&self, freq_resp: F, window: W)
+    where
+        F: Fn(isize, f64) -> Complex<f64> + Send + Sync + 'static,
+        W: Window + Send + Sync + 'static,
+    {
+        self.params.send_replace(FilterParams {
+            freq_resp: Box::new(freq_resp),
+            window: Box::new(window),
+        });
+    }
+}
+
+/// Block which limits the slew rate of I/Q values
+///
+/// The `slew_rate` passed to the [`new`] function or [`set_slew_rate`] method
+/// is the norm of the difference between samples one second apart.
+///
+/// [`new`]: SlewRateLimiter::new
+/// [`set_slew_rate`]: SlewRateLimiter::set_slew_rate
+pub struct SlewRateLimiter<Flt> {
+    receiver_connector: ReceiverConnector<Signal<Complex<Flt>>>,
+    sender_connector: SenderConnector<Signal<Complex<Flt>>>,
+    slew_rate: watch::Sender<f64>,
+}
+
+impl_block_trait! { <Flt> Consumer<Signal<Complex<Flt>>> for SlewRateLimiter<Flt> }
+impl_block_trait! { <Flt> Producer<Signal<Complex<Flt>>> for SlewRateLimiter<Flt> }
+
+impl<Flt> SlewRateLimiter<Flt>
+where
+    Flt: Float,
+{
+    /// Create new `SlewRateLimiter` block
+    pub fn new(slew_rate: f64) -> Self {
+        let (mut receiver, receiver_connector) = new_receiver::<Signal<Complex<Flt>>>();
+        let (sender, sender_connector) = new_sender::<Signal<Complex<Flt>>>();
+        let (slew_rate_send, mut slew_rate_recv) = watch::channel(slew_rate);
+        spawn(async move {
+            let mut slew_rate = slew_rate;
+            let mut buf_pool = ChunkBufPool::<Complex<Flt>>::new();
+            let mut previous_sample: Complex<Flt> = Complex::from(Flt::zero());
+            loop {
+                let Ok(signal) = receiver.recv().await else { return; };
+                match signal {
+                    Signal::Samples {
+                        sample_rate,
+                        chunk: input_chunk,
+                    } => {
+                        if slew_rate_recv.has_changed().unwrap_or(false) {
+                            slew_rate = slew_rate_recv.borrow_and_update().clone();
+                        }
+                        let max_diff = flt!(slew_rate / sample_rate);
+                        let mut output_chunk = buf_pool.get_with_capacity(input_chunk.len());
+                        for &(mut sample) in input_chunk.iter() {
+                            let diff = sample - previous_sample;
+                            let norm = diff.norm();
+                            if norm > max_diff {
+                                sample = previous_sample + diff / norm * max_diff;
+                            }
+                            output_chunk.push(sample);
+                            previous_sample = sample;
+                        }
+                        let Ok(()) = sender.send(Signal::Samples {
+                            sample_rate,
+                            chunk: output_chunk.finalize(),
+                         }).await
+                        else { return; };
+                    }
+                    Signal::Event(event) => {
+                        if event.is_interrupt() {
+                            previous_sample = Complex::from(Flt::zero());
+                        }
+                        let Ok(()) = sender.send(Signal::Event(event)).await else { return; };
+                    }
+                }
+            }
+        });
+        Self {
+            receiver_connector,
+            sender_connector,
+            slew_rate: slew_rate_send,
+        }
+    }
+    /// Get slew rate
+    pub fn slew_rate(&self) -> f64 {
+        self.slew_rate.borrow().clone()
+    }
+    /// Set slew rate
+    pub fn set_slew_rate(&self, slew_rate: f64) {
+        self.slew_rate.send_replace(slew_rate);
+    }
+}
+
+/// Convolve `input` with `coeffs` using overlap-save, where `history`
+/// contains the previous `coeffs.len() - 1` input samples and is updated
+///
+/// The transformed `response` is recalculated if its length doesn't match
+/// the chosen FFT size (e.g. when it has been cleared).
+fn fast_convolve<Flt: Float>(
+    coeffs: &[Flt],
+    response: &mut Vec<Complex<Flt>>,
+    segment: &mut Vec<Complex<Flt>>,
+    history: &mut Vec<Complex<Flt>>,
+    input: &[Complex<Flt>],
+    output: &mut Vec<Complex<Flt>>,
+) {
+    let history_len = history.len();
+    let fft_size = (history_len + input.len())
+        .next_power_of_two()
+        .max((2 * coeffs.len()).next_power_of_two())
+        .min((4 * coeffs.len()).next_power_of_two());
+    if response.len() != fft_size {
+        let scale: Flt = flt!(fft_size).recip();
+        response.clear();
+        response.extend(coeffs.iter().map(|&h| Complex::from(h * scale)));
+        response.resize(fft_size, Complex::from(Flt::zero()));
+        fft::forward(response);
+    }
+    for input in input.chunks(fft_size - history_len) {
+        segment.clear();
+        segment.extend_from_slice(history);
+        segment.extend_from_slice(input);
+        history.clear();
+        history.extend_from_slice(&segment[segment.len() - history_len..]);
+        segment.resize(fft_size, Complex::from(Flt::zero()));
+        fft::forward(segment);
+        for (x, &h) in segment.iter_mut().zip(response.iter()) {
+            *x *= h;
+        }
+        fft::inverse(segment);
+        output.extend_from_slice(&segment[history_len..history_len + input.len()]);
+    }
+}
+
+/// FIR filter using direct convolution with given coefficients
+///
+/// Unlike [`Filter`], this block does not add a delay of one chunk and works
+/// with arbitrary chunk lengths. The end of each chunk is kept as history,
+/// such that the output is continuous. Coefficients may be changed at runtime
+/// with [`FirFilter::set_coeffs`].
+///
+/// The history is cleared when an [interrupting] event is received. When a
+/// [flushing] event is received, the tail of the impulse response (i.e. the
+/// response to the history) is sent out before the event.
+///
+/// [interrupting]: Event::is_interrupt
+/// [flushing]: Event::is_flush
+pub struct FirFilter<Flt> {
+    receiver_connector: ReceiverConnector<Signal<Complex<Flt>>>,
+    sender_connector: SenderConnector<Signal<Complex<Flt>>>,
+    coeffs: watch::Sender<Arc<[Flt]>>,
+}
+
+impl_block_trait! { <Flt> Consumer<Signal<Complex<Flt>>> for FirFilter<Flt> }
+impl_block_trait! { <Flt> Producer<Signal<Complex<Flt>>> for FirFilter<Flt> }
+
+impl<Flt> FirFilter<Flt>
+where
+    Flt: Float,
+{
+    /// Create new `FirFilter` block with given coefficients (impulse response)
+    pub fn new(coeffs: Arc<[Flt]>) -> Self {
+        assert!(!coeffs.is_empty(), "coefficients must not be empty");
+        let (mut receiver, receiver_connector) = new_receiver::<Signal<Complex<Flt>>>();
+        let (sender, sender_connector) = new_sender::<Signal<Complex<Flt>>>();
+        let (coeffs_send, mut coeffs_recv) = watch::channel(coeffs.clone());
+        spawn(async move {
+            let mut kernel = FirKernel::new(coeffs);
+            let mut buf_pool = ChunkBufPool::<Complex<Flt>>::new();
+            let mut unflushed: Option<f64> = None;
+            loop {
+                let Ok(signal) = receiver.recv().await else { return; };
+                match signal {
+                    Signal::Samples {
+                        sample_rate,
+                        chunk: input_chunk,
+                    } => {
+                        if coeffs_recv.has_changed().unwrap_or(false) {
+                            kernel.set_coeffs(coeffs_recv.borrow_and_update().clone());
+                        }
+                        unflushed = Some(sample_rate);
+                        let mut output_chunk = buf_pool.get_with_capacity(input_chunk.len());
+                        kernel.process(&input_chunk, &mut output_chunk);
+                        let Ok(()) = sender
+                            .send(Signal::Samples {
+                                sample_rate,
+                                chunk: output_chunk.finalize(),
+                            })
+                            .await
+                        else { return; };
+                    }
+                    Signal::Event(event) => {
+                        if event.is_flush() {
+                            if let Some(sample_rate) = unflushed.take() {
+                                let tail_len = kernel.coeffs().len() - 1;
+                                let mut output_chunk = buf_pool.get_with_capacity(tail_len);
+                                kernel.flush(&mut output_chunk);
+                                if !output_chunk.is_empty() {
+                                    let Ok(()) = sender
+                                        .send(Signal::Samples {
+                                            sample_rate,
+                                            chunk: output_chunk.finalize(),
+                                        })
+                                        .await
+                                    else { return; };
+                                }
+                            }
+                        }
+                        if event.is_interrupt() {
+                            unflushed = None;
+                            kernel.reset();
+                        }
+                        let Ok(()) = sender.send(Signal::Event(event)).await
+                        else { return; };
+                    }
+                }
+            }
+        });
+        Self {
+            receiver_connector,
+            sender_connector,
+            coeffs: coeffs_send,
+        }
+    }
+    /// Get coefficients
+    pub fn coeffs(&self) -> Arc<[Flt]> {
+        self.coeffs.borrow().clone()
+    }
+    /// Set coefficients
+    ///
+    /// The most recent samples are kept as history (or zero-padded if they
+    /// are fewer than needed), such that the output stays continuous.
+    pub fn set_coeffs(&self, coeffs: Arc<[Flt]>) {
+        assert!(!coeffs.is_empty(), "coefficients must not be empty");
+        self.coeffs.send_replace(coeffs);
+    }
+}
+
+/// FIR filter using fast convolution (overlap-save) with given coefficients
+///
+/// This block behaves like [`FirFilter`] but calculates the convolution using
+/// FFTs, which is considerably faster for long impulse responses (e.g. with
+/// thousands of coefficients). The FFT size is chosen depending on the number
+/// of coefficients and the chunk length. Like [`FirFilter`], this block does
+/// not add any delay and coefficients may be changed at runtime with
+/// [`FftFirFilter::set_coeffs`].
+///
+/// The history is cleared when an [interrupting] event is received. When a
+/// [flushing] event is received, the tail of the impulse response (i.e. the
+/// response to the history) is sent out before the event.
+///
+/// [interrupting]: Event::is_interrupt
+/// [flushing]: Event::is_flush
+pub struct FftFirFilter<Flt> {
+    receiver_connector: ReceiverConnector<Signal<Complex<Flt>>>,
+    sender_connector: SenderConnector<Signal<Complex<Flt>>>,
+    coeffs: watch::Sender<Arc<[Flt]>>,
+}
+
+impl_block_trait! { <Flt> Consumer<Signal<Complex<Flt>>> for FftFirFilter<Flt> }
+impl_block_trait! { <Flt> Producer<Signal<Complex<Flt>>> for FftFirFilter<Flt> }
+
+impl<Flt> FftFirFilter<Flt>
+where
+    Flt: Float,
+{
+    /// Create new `FftFirFilter` block with given coefficients (impulse
+    /// response)
+    pub fn new(coeffs: Arc<[Flt]>) -> Self {
+        assert!(!coeffs.is_empty(), "coefficients must not be empty");
+        let (mut receiver, receiver_connector) = new_receiver::<Signal<Complex<Flt>>>();
+        let (sender, sender_connector) = new_sender::<Signal<Complex<Flt>>>();
+        let (coeffs_send, mut coeffs_recv) = watch::channel(coeffs.clone());
+        spawn(async move {
+            let mut coeffs = coeffs;
+            let mut buf_pool = ChunkBufPool::<Complex<Flt>>::new();
+            let mut history: Vec<Complex<Flt>> = vec![Complex::from(Flt::zero()); coeffs.len() - 1];
+            let mut response: Vec<Complex<Flt>> = Vec::new();
+            let mut segment: Vec<Complex<Flt>> = Vec::new();
+            let mut unflushed: Option<f64> = None;
+            loop {
+                let Ok(signal) = receiver.recv().await else { return; };
+                match signal {
+                    Signal::Samples {
+                        sample_rate,
+                        chunk: input_chunk,
+                    } => {
+                        if coeffs_recv.has_changed().unwrap_or(false) {
+                            coeffs = coeffs_recv.borrow_and_update().clone();
+                            let history_len = coeffs.len() - 1;
+                            if history.len() > history_len {
+                                history.drain(0..history.len() - history_len);
+                            } else {
+                                let missing = history_len - history.len();
+                                history.splice(0..0, vec![Complex::from(Flt::zero()); missing]);
+                            }
+                            response.clear();
+                        }
+                        unflushed = Some(sample_rate);
+                        let mut output_chunk = buf_pool.get_with_capacity(input_chunk.len());
+                        fast_convolve(
+                            &coeffs,
+                            &mut response,
+                            &mut segment,
+                            &mut history,
+                            &input_chunk,
+                            &mut output_chunk,
+                        );
+                        let Ok(()) = sender
+                            .send(Signal::Samples {
+                                sample_rate,
+                                chunk: output_chunk.finalize(),
+                            })
+                            .await
+                        else { return; };
+                    }
+                    Signal::Event(event) => {
+                        if event.is_flush() {
+                            if let Some(sample_rate) = unflushed.take() {
+                                let tail = vec![Complex::from(Flt::zero()); history.len()];
+                                let mut output_chunk = buf_pool.get_with_capacity(tail.len());
+                                fast_convolve(
+                                    &coeffs,
+                                    &mut response,
+                                    &mut segment,
+                                    &mut history,
+                                    &tail,
+                                    &mut output_chunk,
+                                );
+                                if !output_chunk.is_empty() {
+                                    let Ok(()) = sender
+                                        .send(Signal::Samples {
+                                            sample_rate,
+                                            chunk: output_chunk.finalize(),
+                                        })
+                                        .await
+                                    else { return; };
+                                }
+                            }
+                        }
+                        if event.is_interrupt() {
+                            unflushed = None;
+                            for sample in history.iter_mut() {
+                                *sample = Complex::from(Flt::zero());
+                            }
+                        }
+                        let Ok(()) = sender.send(Signal::Event(event)).await
+                        else { return; };
+                    }
+                }
+            }
+        });
+        Self {
+            receiver_connector,
+            sender_connector,
+            coeffs: coeffs_send,
+        }
+    }
+    /// Get coefficients
+    pub fn coeffs(&self) -> Arc<[Flt]> {
+        self.coeffs.borrow().clone()
+    }
+    /// Set coefficients
+    ///
+    /// The most recent samples are kept as history (or zero-padded if they
+    /// are fewer than needed), such that the output stays continuous.
+    pub fn set_coeffs(&self, coeffs: Arc<[Flt]>) {
+        assert!(!coeffs.is_empty(), "coefficients must not be empty");
+        self.coeffs.send_replace(coeffs);
+    }
+}
+
+/// Root-raised-cosine filter for pulse shaping and matched filtering
+///
+/// This block is a [`FirFilter`] with coefficients from [`design::rrc`],
+/// which are normalized to unit energy. When used as pulse shaper, the input
+/// should be upsampled by inserting zeros between the symbols (rather than by
+/// repeating them). Using a second `RrcFilter` as matched filter results in a
+/// raised-cosine response with zero intersymbol interference, where each
+/// symbol is found at its original amplitude with a delay of
+/// `span_symbols * samples_per_symbol` samples (half of it per filter).
+pub struct RrcFilter<Flt> {
+    fir_filter: FirFilter<Flt>,
+    samples_per_symbol: usize,
+    rolloff: f64,
+}
+
+impl<Flt> Consumer<Signal<Complex<Flt>>> for RrcFilter<Flt> {
+    fn receiver_connector(&self) -> &ReceiverConnector<Signal<Complex<Flt>>> {
+        self.fir_filter.receiver_connector()
+    }
+}
+
+impl<Flt> Producer<Signal<Complex<Flt>>> for RrcFilter<Flt> {
+    fn sender_connector(&self) -> &SenderConnector<Signal<Complex<Flt>>> {
+        self.fir_filter.sender_connector()
+    }
+}
+
+impl<Flt> RrcFilter<Flt>
+where
+    Flt: Float,
+{
+    /// Create new `RrcFilter` with given samples per symbol, rolloff factor,
+    /// and span of the impulse response in symbols
+    pub fn new(samples_per_symbol: usize, rolloff: f64, span_symbols: usize) -> Self {
+        let coeffs = design::rrc::<Flt>(span_symbols, samples_per_symbol, rolloff);
+        Self {
+            fir_filter: FirFilter::new(coeffs.into()),
+            samples_per_symbol,
+            rolloff,
+        }
+    }
+    /// Samples per symbol
+    pub fn samples_per_symbol(&self) -> usize {
+        self.samples_per_symbol
+    }
+    /// Rolloff factor
+    pub fn rolloff(&self) -> f64 {
+        self.rolloff
+    }
+}
+
+/// DC blocker
+///
+/// Removes the DC component using a one-pole, one-zero high-pass filter
+/// `y[n] = x[n] - x[n-1] + r * y[n-1]`, where `r` is the pole (smaller than
+/// `1.0`). Values close to `1.0` result in a lower cutoff frequency.
+pub struct DcBlocker<Flt> {
+    receiver_connector: ReceiverConnector<Signal<Complex<Flt>>>,
+    sender_connector: SenderConnector<Signal<Complex<Flt>>>,
+}
+
+impl_block_trait! { <Flt> Consumer<Signal<Complex<Flt>>> for DcBlocker<Flt> }
+impl_block_trait! { <Flt> Producer<Signal<Complex<Flt>>> for DcBlocker<Flt> }
+
+impl<Flt> DcBlocker<Flt>
+where
+    Flt: Float,
+{
+    /// Create new `DcBlocker` block with a pole of `0.999`
+    pub fn new() -> Self {
+        Self::with_pole(0.999)
+    }
+    /// Create new `DcBlocker` block with given `pole`
+    pub fn with_pole(pole: f64) -> Self {
+        assert!(pole < 1.0, "pole must be smaller than 1.0");
+        let (mut receiver, receiver_connector) = new_receiver::<Signal<Complex<Flt>>>();
+        let (sender, sender_connector) = new_sender::<Signal<Complex<Flt>>>();
+        spawn(async move {
+            let pole: Flt = flt!(pole);
+            let mut buf_pool = ChunkBufPool::<Complex<Flt>>::new();
+            let mut previous_input: Complex<Flt> = Complex::from(Flt::zero());
+            let mut previous_output: Complex<Flt> = Complex::from(Flt::zero());
+            loop {
+                let Ok(signal) = receiver.recv().await else { return; };
+                match signal {
+                    Signal::Samples {
+                        sample_rate,
+                        chunk: input_chunk,
+                    } => {
+                        let mut output_chunk = buf_pool.get_with_capacity(input_chunk.len());
+                        for &sample in input_chunk.iter() {
+                            previous_output = sample - previous_input + previous_output * pole;
+                            previous_input = sample;
+                            output_chunk.push(previous_output);
+                        }
+                        let Ok(()) = sender
+                            .send(Signal::Samples {
+                                sample_rate,
+                                chunk: output_chunk.finalize(),
+                            })
+                            .await
+                        else { return; };
+                    }
+                    Signal::Event(event) => {
+                        if event.is_interrupt() {
+                            previous_input = Complex::from(Flt::zero());
+                            previous_output = Complex::from(Flt::zero());
+                        }
+                        let Ok(()) = sender.send(Signal::Event(event)).await else { return; };
+                    }
+                }
+            }
+        });
+        Self {
+            receiver_connector,
+            sender_connector,
+        }
+    }
+}
+
+/// Time constant of 50 µs for [`Deemphasis`] and [`Preemphasis`] (used for FM
+/// broadcast in most regions)
+pub const TAU_50US: f64 = 50e-6;
+
+/// Time constant of 75 µs for [`Deemphasis`] and [`Preemphasis`] (used for FM
+/// broadcast in the Americas and South Korea)
+pub const TAU_75US: f64 = 75e-6;
+
+fn emphasis_coefficient(tau: f64, sample_rate: f64) -> f64 {
+    1.0 - (-1.0 / (tau * sample_rate)).exp()
+}
+
+/// Deemphasis filter
+///
+/// One-pole low-pass `y[n] = y[n-1] + a * (x[n] - y[n-1])` with
+/// `a = 1 - exp(-1 / (tau * sample_rate))`, where `tau` is the time constant
+/// in seconds (e.g. [`TAU_50US`] or [`TAU_75US`]). The coefficient is
+/// recalculated when the sample rate or time constant changes.
+pub struct Deemphasis<Flt> {
+    receiver_connector: ReceiverConnector<Signal<Complex<Flt>>>,
+    sender_connector: SenderConnector<Signal<Complex<Flt>>>,
+    tau: watch::Sender<f64>,
+}
+
+impl_block_trait! { <Flt> Consumer<Signal<Complex<Flt>>> for Deemphasis<Flt> }
+impl_block_trait! { <Flt> Producer<Signal<Complex<Flt>>> for Deemphasis<Flt> }
+
+impl<Flt> Deemphasis<Flt>
+where
+    Flt: Float,
+{
+    /// Create new `Deemphasis` block with given time constant `tau` in seconds
+    pub fn new(tau: f64) -> Self {
+        let (mut receiver, receiver_connector) = new_receiver::<Signal<Complex<Flt>>>();
+        let (sender, sender_connector) = new_sender::<Signal<Complex<Flt>>>();
+        let (tau_send, mut tau_recv) = watch::channel(tau);
+        spawn(async move {
+            let mut tau = tau;
+            let mut prev_sample_rate: Option<f64> = None;
+            let mut coefficient: Flt = Flt::zero();
+            let mut buf_pool = ChunkBufPool::<Complex<Flt>>::new();
+            let mut previous_output: Complex<Flt> = Complex::from(Flt::zero());
+            loop {
+                let Ok(signal) = receiver.recv().await else { return; };
+                match signal {
+                    Signal::Samples {
+                        sample_rate,
+                        chunk: input_chunk,
+                    } => {
+                        let tau_changed = tau_recv.has_changed().unwrap_or(false);
+                        if tau_changed {
+                            tau = *tau_recv.borrow_and_update();
+                        }
+                        if tau_changed || Some(sample_rate) != prev_sample_rate {
+                            coefficient = flt!(emphasis_coefficient(tau, sample_rate));
+                        }
+                        prev_sample_rate = Some(sample_rate);
+                        let mut output_chunk = buf_pool.get_with_capacity(input_chunk.len());
+                        for &sample in input_chunk.iter() {
+                            previous_output += (sample - previous_output) * coefficient;
+                            output_chunk.push(previous_output);
+                        }
+                        let Ok(()) = sender
+                            .send(Signal::Samples {
+                                sample_rate,
+                                chunk: output_chunk.finalize(),
+                            })
+                            .await
+                        else { return; };
+                    }
+                    Signal::Event(event) => {
+                        if event.is_interrupt() {
+                            previous_output = Complex::from(Flt::zero());
+                        }
+                        let Ok(()) = sender.send(Signal::Event(event)).await else { return; };
+                    }
+                }
+            }
+        });
+        Self {
+            receiver_connector,
+            sender_connector,
+            tau: tau_send,
+        }
+    }
+    /// Get time constant in seconds
+    pub fn tau(&self) -> f64 {
+        *self.tau.borrow()
+    }
+    /// Set time constant in seconds
+    pub fn set_tau(&self, tau: f64) {
+        self.tau.send_replace(tau);
+    }
+}
+
+/// Preemphasis filter
+///
+/// Inverse of [`Deemphasis`] with the same time constant, i.e.
+/// `y[n] = (x[n] - (1 - a) * x[n-1]) / a` with
+/// `a = 1 - exp(-1 / (tau * sample_rate))`. The gain at zero frequency is
+/// unity, while higher frequencies are amplified.
+pub struct Preemphasis<Flt> {
+    receiver_connector: ReceiverConnector<Signal<Complex<Flt>>>,
+    sender_connector: SenderConnector<Signal<Complex<Flt>>>,
+    tau: watch::Sender<f64>,
+}
+
+impl_block_trait! { <Flt> Consumer<Signal<Complex<Flt>>> for Preemphasis<Flt> }
+impl_block_trait! { <Flt> Producer<Signal<Complex<Flt>>> for Preemphasis<Flt> }
+
+impl<Flt> Preemphasis<Flt>
+where
+    Flt: Float,
+{
+    /// Create new `Preemphasis` block with given time constant `tau` in
+    /// seconds
+    pub fn new(tau: f64) -> Self {
+        let (mut receiver, receiver_connector) = new_receiver::<Signal<Complex<Flt>>>();
+        let (sender, sender_connector) = new_sender::<Signal<Complex<Flt>>>();
+        let (tau_send, mut tau_recv) = watch::channel(tau);
+        spawn(async move {
+            let mut tau = tau;
+            let mut prev_sample_rate: Option<f64> = None;
+            let mut coefficient: Flt = Flt::one();
+            let mut buf_pool = ChunkBufPool::<Complex<Flt>>::new();
+            let mut previous_input: Complex<Flt> = Complex::from(Flt::zero());
+            loop {
+                let Ok(signal) = receiver.recv().await else { return; };
+                match signal {
+                    Signal::Samples {
+                        sample_rate,
+                        chunk: input_chunk,
+                    } => {
+                        let tau_changed = tau_recv.has_changed().unwrap_or(false);
+                        if tau_changed {
+                            tau = *tau_recv.borrow_and_update();
+                        }
+                        if tau_changed || Some(sample_rate) != prev_sample_rate {
+                            coefficient = flt!(emphasis_coefficient(tau, sample_rate));
+                        }
+                        prev_sample_rate = Some(sample_rate);
+                        let mut output_chunk = buf_pool.get_with_capacity(input_chunk.len());
+                        for &sample in input_chunk.iter() {
+                            output_chunk.push(
+                                (sample - previous_input * (Flt::one() - coefficient))
+                                    / coefficient,
+                            );
+                            previous_input = sample;
+                        }
+                        let Ok(()) = sender
+                            .send(Signal::Samples {
+                                sample_rate,
+                                chunk: output_chunk.finalize(),
+                            })
+                            .await
+                        else { return; };
+                    }
+                    Signal::Event(event) => {
+                        if event.is_interrupt() {
+                            previous_input = Complex::from(Flt::zero());
+                        }
+                        let Ok(()) = sender.send(Signal::Event(event)).await else { return; };
+                    }
+                }
+            }
+        });
+        Self {
+            receiver_connector,
+            sender_connector,
+            tau: tau_send,
+        }
+    }
+    /// Get time constant in seconds
+    pub fn tau(&self) -> f64 {
+        *self.tau.borrow()
+    }
+    /// Set time constant in seconds
+    pub fn set_tau(&self, tau: f64) {
+        self.tau.send_replace(tau);
+    }
+}
+
+/// Coefficients of a second-order IIR section (biquad)
+///
+/// The transfer function is
+/// *H(z) = (b0 + b1 z<sup>−1</sup> + b2 z<sup>−2</sup>) /
+/// (1 + a1 z<sup>−1</sup> + a2 z<sup>−2</sup>)*.
+/// The design functions follow the formulas of Robert Bristow-Johnson's
+/// "Audio EQ Cookbook", where frequencies are given in hertz and `q` is the
+/// quality factor (e.g. `std::f64::consts::FRAC_1_SQRT_2` for a Butterworth
+/// response of low- and high-pass filters).
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct BiquadCoeffs {
+    /// Feedforward coefficient for current input
+    pub b0: f64,
+    /// Feedforward coefficient for previous input
+    pub b1: f64,
+    /// Feedforward coefficient for second previous input
+    pub b2: f64,
+    /// Feedback coefficient for previous output
+    pub a1: f64,
+    /// Feedback coefficient for second previous output
+    pub a2: f64,
+}
+
+impl BiquadCoeffs {
+    /// Coefficients which pass the signal unchanged
+    pub const BYPASS: Self = Self {
+        b0: 1.0,
+        b1: 0.0,
+        b2: 0.0,
+        a1: 0.0,
+        a2: 0.0,
+    };
+    /// Normalize coefficients by `a0`
+    fn normalized(b: [f64; 3], a: [f64; 3]) -> Self {
+        Self {
+            b0: b[0] / a[0],
+            b1: b[1] / a[0],
+            b2: b[2] / a[0],
+            a1: a[1] / a[0],
+            a2: a[2] / a[0],
+        }
+    }
+    /// Return `(cos(w0), alpha)` for given `frequency`
+    fn prewarp(frequency: f64, q: f64, sample_rate: f64) -> (f64, f64) {
+        use std::f64::consts::TAU;
+        assert!(
+            frequency > 0.0 && frequency < sample_rate / 2.0,
+            "frequency must be between zero and the Nyquist frequency"
+        );
+        assert!(q > 0.0, "quality factor must be positive");
+        let w0 = TAU * frequency / sample_rate;
+        (w0.cos(), w0.sin() / (2.0 * q))
+    }
+    /// Low-pass filter with given `cutoff` frequency
+    pub fn lowpass(cutoff: f64, q: f64, sample_rate: f64) -> Self {
+        let (cos, alpha) = Self::prewarp(cutoff, q, sample_rate);
+        Self::normalized(
+            [(1.0 - cos) / 2.0, 1.0 - cos, (1.0 - cos) / 2.0],
+            [1.0 + alpha, -2.0 * cos, 1.0 - alpha],
+        )
+    }
+    /// High-pass filter with given `cutoff` frequency
+    pub fn highpass(cutoff: f64, q: f64, sample_rate: f64) -> Self {
+        let (cos, alpha) = Self::prewarp(cutoff, q, sample_rate);
+        Self::normalized(
+            [(1.0 + cos) / 2.0, -(1.0 + cos), (1.0 + cos) / 2.0],
+            [1.0 + alpha, -2.0 * cos, 1.0 - alpha],
+        )
+    }
+    /// Band-pass filter with unity gain at the `center` frequency
+    pub fn bandpass(center: f64, q: f64, sample_rate: f64) -> Self {
+        let (cos, alpha) = Self::prewarp(center, q, sample_rate);
+        Self::normalized([alpha, 0.0, -alpha], [1.0 + alpha, -2.0 * cos, 1.0 - alpha])
+    }
+    /// Notch filter removing the `center` frequency
+    pub fn notch(center: f64, q: f64, sample_rate: f64) -> Self {
+        let (cos, alpha) = Self::prewarp(center, q, sample_rate);
+        Self::normalized(
+            [1.0, -2.0 * cos, 1.0],
+            [1.0 + alpha, -2.0 * cos, 1.0 - alpha],
+        )
+    }
+    /// Peaking equalizer with a gain of `gain_db` decibels at the `center`
+    /// frequency
+    pub fn peaking(center: f64, q: f64, gain_db: f64, sample_rate: f64) -> Self {
+        let (cos, alpha) = Self::prewarp(center, q, sample_rate);
+        let a = 10f64.powf(gain_db / 40.0);
+        Self::normalized(
+            [1.0 + alpha * a, -2.0 * cos, 1.0 - alpha * a],
+            [1.0 + alpha / a, -2.0 * cos, 1.0 - alpha / a],
+        )
+    }
+    /// Frequency response at given `frequency`
+    pub fn response(&self, frequency: f64, sample_rate: f64) -> Complex<f64> {
+        use std::f64::consts::TAU;
+        let z1 = Complex::from_polar(1.0, -TAU * frequency / sample_rate);
+        let z2 = z1 * z1;
+        (self.b0 + z1 * self.b1 + z2 * self.b2) / (1.0 + z1 * self.a1 + z2 * self.a2)
+    }
+    /// Return true if both poles lie inside the unit circle
+    pub fn is_stable(&self) -> bool {
+        self.a2.abs() < 1.0 && self.a1.abs() < 1.0 + self.a2
+    }
+    /// Set coefficients of a [`BiquadKernel`]
+    pub fn apply_to<Flt: Float>(&self, kernel: &mut BiquadKernel<Flt>) {
+        kernel.set_coeffs(
+            [flt!(self.b0), flt!(self.b1), flt!(self.b2)],
+            [flt!(self.a1), flt!(self.a2)],
+        );
+    }
+}
+
+/// Specification of a second-order section of a [`Biquad`] block, from
+/// which the coefficients are calculated for the sample rate of the stream
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum BiquadDesign {
+    /// See [`BiquadCoeffs::lowpass`]
+    Lowpass {
+        /// Cutoff frequency in hertz
+        cutoff: f64,
+        /// Quality factor
+        q: f64,
+    },
+    /// See [`BiquadCoeffs::highpass`]
+    Highpass {
+        /// Cutoff frequency in hertz
+        cutoff: f64,
+        /// Quality factor
+        q: f64,
+    },
+    /// See [`BiquadCoeffs::bandpass`]
+    Bandpass {
+        /// Center frequency in hertz
+        center: f64,
+        /// Quality factor
+        q: f64,
+    },
+    /// See [`BiquadCoeffs::notch`]
+    Notch {
+        /// Center frequency in hertz
+        center: f64,
+        /// Quality factor
+        q: f64,
+    },
+    /// See [`BiquadCoeffs::peaking`]
+    Peaking {
+        /// Center frequency in hertz
+        center: f64,
+        /// Quality factor
+        q: f64,
+        /// Gain at center frequency in decibels
+        gain_db: f64,
+    },
+    /// Fixed coefficients (independent of the sample rate)
+    Coeffs(BiquadCoeffs),
+}
+
+impl BiquadDesign {
+    /// Calculate coefficients for given `sample_rate`
+    pub fn coeffs(&self, sample_rate: f64) -> BiquadCoeffs {
+        match *self {
+            BiquadDesign::Lowpass { cutoff, q } => BiquadCoeffs::lowpass(cutoff, q, sample_rate),
+            BiquadDesign::Highpass { cutoff, q } => BiquadCoeffs::highpass(cutoff, q, sample_rate),
+            BiquadDesign::Bandpass { center, q } => BiquadCoeffs::bandpass(center, q, sample_rate),
+            BiquadDesign::Notch { center, q } => BiquadCoeffs::notch(center, q, sample_rate),
+            BiquadDesign::Peaking { center, q, gain_db } => {
+                BiquadCoeffs::peaking(center, q, gain_db, sample_rate)
+            }
+            BiquadDesign::Coeffs(coeffs) => coeffs,
+        }
+    }
+}
+
+/// IIR filter consisting of a cascade of second-order sections (biquads)
+///
+/// Each section is specified by a [`BiquadDesign`], and coefficients are
+/// recalculated when the sample rate or the sections change. The state of
+/// the filter is kept across chunks (and when only coefficients change).
+///
+/// Sections with unstable coefficients are bypassed. If the state becomes
+/// non-finite (e.g. due to NaN input), it is reset to zero.
+///
+/// # Example
+///
+/// ```
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async move {
+/// use radiorust::blocks::filters::{Biquad, BiquadDesign};
+/// let hum_filter = Biquad::<f32>::new(vec![
+///     BiquadDesign::Notch { center: 50.0, q: 10.0 },
+///     BiquadDesign::Notch { center: 150.0, q: 10.0 },
+/// ]);
+/// # });
+/// ```
+pub struct Biquad<Flt> {
+    receiver_connector: ReceiverConnector<Signal<Complex<Flt>>>,
+    sender_connector: SenderConnector<Signal<Complex<Flt>>>,
+    sections: watch::Sender<Vec<BiquadDesign>>,
+}
+
+impl_block_trait! { <Flt> Consumer<Signal<Complex<Flt>>> for Biquad<Flt> }
+impl_block_trait! { <Flt> Producer<Signal<Complex<Flt>>> for Biquad<Flt> }
+
+impl<Flt> Biquad<Flt>
+where
+    Flt: Float,
+{
+    /// Create new `Biquad` block with given cascade of `sections`
+    pub fn new(sections: Vec<BiquadDesign>) -> Self {
+        let (mut receiver, receiver_connector) = new_receiver::<Signal<Complex<Flt>>>();
+        let (sender, sender_connector) = new_sender::<Signal<Complex<Flt>>>();
+        let (sections_send, mut sections_recv) = watch::channel(sections);
+        spawn(async move {
+            let mut prev_sample_rate: Option<f64> = None;
+            let mut states: Vec<BiquadKernel<Flt>> = Vec::new();
+            let mut buf_pool = ChunkBufPool::<Complex<Flt>>::new();
+            loop {
+                let Ok(signal) = receiver.recv().await else { return; };
+                match signal {
+                    Signal::Samples {
+                        sample_rate,
+                        chunk: input_chunk,
+                    } => {
+                        let sections_changed = sections_recv.has_changed().unwrap_or(false);
+                        if sections_changed || Some(sample_rate) != prev_sample_rate {
+                            let sections = sections_recv.borrow_and_update();
+                            states.resize_with(sections.len(), BiquadKernel::new);
+                            for (state, section) in states.iter_mut().zip(sections.iter()) {
+                                let coeffs = section.coeffs(sample_rate);
+                                if coeffs.is_stable() {
+                                    coeffs.apply_to(state);
+                                } else {
+                                    event!(WARN, "bypassing unstable biquad section {section:?}");
+                                    BiquadCoeffs::BYPASS.apply_to(state);
+                                }
+                            }
+                        }
+                        prev_sample_rate = Some(sample_rate);
+                        let mut output_chunk = buf_pool.get_with_capacity(input_chunk.len());
+                        for &sample in input_chunk.iter() {
+                            let mut value = sample;
+                            for state in states.iter_mut() {
+                                value = state.process(value);
+                            }
+                            output_chunk.push(value);
+                        }
+                        for state in states.iter_mut() {
+                            if !state.is_finite() {
+                                state.reset();
+                            }
+                        }
+                        let Ok(()) = sender
+                            .send(Signal::Samples {
+                                sample_rate,
+                                chunk: output_chunk.finalize(),
+                            })
+                            .await
+                        else { return; };
+                    }
+                    Signal::Event(event) => {
+                        if event.is_interrupt() {
+                            for state in states.iter_mut() {
+                                state.reset();
+                            }
+                        }
+                        let Ok(()) = sender.send(Signal::Event(event)).await else { return; };
+                    }
+                }
+            }
+        });
+        Self {
+            receiver_connector,
+            sender_connector,
+            sections: sections_send,
+        }
+    }
+    /// Get sections
+    pub fn sections(&self) -> Vec<BiquadDesign> {
+        self.sections.borrow().clone()
+    }
+    /// Set sections
+    pub fn set_sections(&self, sections: Vec<BiquadDesign>) {
+        self.sections.send_replace(sections);
+    }
+}
+
+/// Automatic notch filter which suppresses steady tones (e.g. heterodynes)
+/// in an audio signal
+///
+/// The filter uses an adaptive linear predictor (normalized LMS algorithm):
+/// Each sample of the real part is predicted from past samples which are
+/// delayed by one millisecond. Tones are predictable over this delay, while
+/// speech and noise are not, so subtracting the prediction removes the tones
+/// and passes broadband signals. The imaginary part is passed unchanged.
+///
+/// The predictor has `4 * notches` taps, where suppressing a single tone
+/// requires at least two taps. The adaptation `rate` (step size of the
+/// algorithm, between `0.0` and `2.0`, e.g. `0.005`) determines how fast
+/// tones are found and how much speech is affected.
+pub struct AutoNotch<Flt> {
+    receiver_connector: ReceiverConnector<Signal<Complex<Flt>>>,
+    sender_connector: SenderConnector<Signal<Complex<Flt>>>,
+    notches: watch::Sender<usize>,
+    rate: watch::Sender<f64>,
+}
+
+impl_block_trait! { <Flt> Consumer<Signal<Complex<Flt>>> for AutoNotch<Flt> }
+impl_block_trait! { <Flt> Producer<Signal<Complex<Flt>>> for AutoNotch<Flt> }
+
+impl<Flt> AutoNotch<Flt>
+where
+    Flt: Float,
+{
+    /// Delay between predicted sample and samples used for prediction in
+    /// seconds
+    const DELAY: f64 = 0.001;
+    /// Leakage of the predictor coefficients per sample
+    const LEAKAGE: f64 = 1e-5;
+    /// Create new `AutoNotch` block with given number of `notches` and
+    /// adaptation `rate`
+    pub fn new(notches: usize, rate: f64) -> Self {
+        assert!(notches > 0, "number of notches must be positive");
+        let (mut receiver, receiver_connector) = new_receiver::<Signal<Complex<Flt>>>();
+        let (sender, sender_connector) = new_sender::<Signal<Complex<Flt>>>();
+        let (notches_send, mut notches_recv) = watch::channel(notches);
+        let (rate_send, mut rate_recv) = watch::channel(rate);
+        spawn(async move {
+            let mut rate: Flt = flt!(rate);
+            let leak: Flt = flt!(1.0 - Self::LEAKAGE);
+            let epsilon: Flt = flt!(1e-9);
+            let mut prev_sample_rate: Option<f64> = None;
+            let mut taps: usize = 0;
+            let mut weights: Vec<Flt> = Vec::new();
+            // history is stored twice to allow contiguous access
+            let mut history: Vec<Flt> = Vec::new();
+            let mut history_pos: usize = 0;
+            let mut buf_pool = ChunkBufPool::<Complex<Flt>>::new();
+            loop {
+                let Ok(signal) = receiver.recv().await else { return; };
+                match signal {
+                    Signal::Samples {
+                        sample_rate,
+                        chunk: input_chunk,
+                    } => {
+                        if rate_recv.has_changed().unwrap_or(false) {
+                            rate = flt!(*rate_recv.borrow_and_update());
+                        }
+                        let notches_changed = notches_recv.has_changed().unwrap_or(false);
+                        if notches_changed || Some(sample_rate) != prev_sample_rate {
+                            taps = 4 * *notches_recv.borrow_and_update();
+                            let delay = ((Self::DELAY * sample_rate).round() as usize).max(1);
+                            weights = vec![Flt::zero(); taps];
+                            history = vec![Flt::zero(); 2 * (taps + delay)];
+                            history_pos = 0;
+                        }
+                        prev_sample_rate = Some(sample_rate);
+                        let len = history.len() / 2;
+                        let mut output_chunk = buf_pool.get_with_capacity(input_chunk.len());
+                        for &sample in input_chunk.iter() {
+                            // oldest samples of the window are used for prediction
+                            let window = &history[history_pos..history_pos + taps];
+                            let mut prediction = Flt::zero();
+                            let mut power = epsilon;
+                            for (&w, &x) in weights.iter().zip(window.iter()) {
+                                prediction += w * x;
+                                power += x * x;
+                            }
+                            let error = sample.re - prediction;
+                            let step = rate * error / power;
+                            for (w, &x) in weights.iter_mut().zip(window.iter()) {
+                                *w = *w * leak + step * x;
+                            }
+                            if !error.is_finite() {
+                                weights.fill(Flt::zero());
+                            }
+                            output_chunk.push(Complex::new(error, sample.im));
+                            history[history_pos] = sample.re;
+                            history[history_pos + len] = sample.re;
+                            history_pos += 1;
+                            if history_pos == len {
+                                history_pos = 0;
+                            }
+                        }
+                        let Ok(()) = sender
+                            .send(Signal::Samples {
+                                sample_rate,
+                                chunk: output_chunk.finalize(),
+                            })
+                            .await
+                        else { return; };
+                    }
+                    Signal::Event(event) => {
+                        if event.is_interrupt() {
+                            weights.fill(Flt::zero());
+                            history.fill(Flt::zero());
+                            history_pos = 0;
+                        }
+                        let Ok(()) = sender.send(Signal::Event(event)).await else { return; };
+                    }
+                }
+            }
+        });
+        Self {
+            receiver_connector,
+            sender_connector,
+            notches: notches_send,
+            rate: rate_send,
+        }
+    }
+    /// Get number of notches
+    pub fn notches(&self) -> usize {
+        *self.notches.borrow()
+    }
+    /// Set number of notches (restarts adaptation)
+    pub fn set_notches(&self, notches: usize) {
+        assert!(notches > 0, "number of notches must be positive");
+        self.notches.send_replace(notches);
+    }
+    /// Get adaptation rate
+    pub fn rate(&self) -> f64 {
+        *self.rate.borrow()
+    }
+    /// Set adaptation rate
+    pub fn set_rate(&self, rate: f64) {
+        self.rate.send_replace(rate);
+    }
+}
+
+/// Noise reduction by spectral subtraction
+///
+/// The real part of the signal is processed in frames of `frame_len`
+/// samples with 50% overlap, using a square-root Hann window for analysis
+/// and synthesis (overlap-add). The noise spectrum is estimated from frames
+/// whose energy is low compared to the current noise estimate, and the
+/// estimated noise power multiplied with `strength` is subtracted from the
+/// power of each frequency bin. The resulting amplitude gain of each bin is
+/// at least `floor` (between `0.0` and `1.0`), which limits the attenuation
+/// and avoids "musical noise" caused by isolated remaining bins.
+///
+/// The output is delayed by `frame_len` samples. The imaginary part of the
+/// output is zero.
+pub struct NoiseReduction<Flt> {
+    receiver_connector: ReceiverConnector<Signal<Complex<Flt>>>,
+    sender_connector: SenderConnector<Signal<Complex<Flt>>>,
+    strength: watch::Sender<f64>,
+    floor: watch::Sender<f64>,
+}
+
+impl_block_trait! { <Flt> Consumer<Signal<Complex<Flt>>> for NoiseReduction<Flt> }
+impl_block_trait! { <Flt> Producer<Signal<Complex<Flt>>> for NoiseReduction<Flt> }
+
+impl<Flt> NoiseReduction<Flt>
+where
+    Flt: Float,
+{
+    /// Frames with an energy below this factor times the estimated noise
+    /// energy are considered to contain only noise
+    const NOISE_THRESHOLD: f64 = 2.0;
+    /// Smoothing factor of the noise estimate per noise frame
+    const NOISE_SMOOTHING: f64 = 0.1;
+    /// Create new `NoiseReduction` block with given `frame_len` (even, e.g.
+    /// `256` at 8 kHz), `strength` (e.g. `2.0`), and `floor` (e.g. `0.1`)
+    pub fn new(frame_len: usize, strength: f64, floor: f64) -> Self {
+        assert!(
+            frame_len >= 2 && frame_len % 2 == 0,
+            "frame length must be even"
+        );
+        let (mut receiver, receiver_connector) = new_receiver::<Signal<Complex<Flt>>>();
+        let (sender, sender_connector) = new_sender::<Signal<Complex<Flt>>>();
+        let (strength_send, mut strength_recv) = watch::channel(strength);
+        let (floor_send, mut floor_recv) = watch::channel(floor);
+        let hop = frame_len / 2;
+        // square root of periodic Hann window
+        let analysis: Vec<Flt> = window::hann(frame_len + 1)[0..frame_len]
+            .iter()
+            .map(|&w| flt!((w as f64).sqrt()))
+            .collect();
+        spawn(async move {
+            let zero = Flt::zero();
+            let mut strength: Flt = flt!(strength);
+            let mut floor: Flt = flt!(floor);
+            let smoothing: Flt = flt!(Self::NOISE_SMOOTHING);
+            let threshold: Flt = flt!(Self::NOISE_THRESHOLD);
+            let scale: Flt = flt!((frame_len as f64).recip());
+            let mut input: Vec<Flt> = vec![zero; frame_len];
+            let mut input_pos: usize = frame_len - hop;
+            let mut overlap: Vec<Flt> = vec![zero; frame_len];
+            let mut ready: Vec<Flt> = vec![zero; hop];
+            let mut ready_pos: usize = 0;
+            let mut noise: Option<Vec<Flt>> = None;
+            let mut spectrum: Vec<Complex<Flt>> = vec![Complex::from(zero); frame_len];
+            let mut buf_pool = ChunkBufPool::<Complex<Flt>>::new();
+            loop {
+                let Ok(signal) = receiver.recv().await else { return; };
+                match signal {
+                    Signal::Samples {
+                        sample_rate,
+                        chunk: input_chunk,
+                    } => {
+                        if strength_recv.has_changed().unwrap_or(false) {
+                            strength = flt!(*strength_recv.borrow_and_update());
+                        }
+                        if floor_recv.has_changed().unwrap_or(false) {
+                            floor = flt!(*floor_recv.borrow_and_update());
+                        }
+                        let mut output_chunk = buf_pool.get_with_capacity(input_chunk.len());
+                        for &sample in input_chunk.iter() {
+                            output_chunk.push(Complex::from(ready[ready_pos]));
+                            ready_pos += 1;
+                            input[input_pos] = match sample.re.is_finite() {
+                                true => sample.re,
+                                false => zero,
+                            };
+                            input_pos += 1;
+                            if input_pos < frame_len {
+                                continue;
+                            }
+                            for ((bin, &x), &w) in
+                                spectrum.iter_mut().zip(input.iter()).zip(analysis.iter())
+                            {
+                                *bin = Complex::from(x * w);
+                            }
+                            fft::forward(&mut spectrum);
+                            let power: Vec<Flt> = spectrum.iter().map(|x| x.norm_sqr()).collect();
+                            let energy: Flt = power.iter().copied().fold(zero, |a, b| a + b);
+                            let noise = noise.get_or_insert_with(|| power.clone());
+                            let noise_energy: Flt = noise.iter().copied().fold(zero, |a, b| a + b);
+                            if energy < noise_energy * threshold {
+                                for (n, &p) in noise.iter_mut().zip(power.iter()) {
+                                    *n += (p - *n) * smoothing;
+                                }
+                            }
+                            for ((bin, &p), &n) in
+                                spectrum.iter_mut().zip(power.iter()).zip(noise.iter())
+                            {
+                                let gain = match p > zero {
+                                    true => (Flt::one() - strength * n / p).max(zero).sqrt(),
+                                    false => zero,
+                                };
+                                *bin *= gain.max(floor);
+                            }
+                            fft::inverse(&mut spectrum);
+                            for ((o, bin), &w) in
+                                overlap.iter_mut().zip(spectrum.iter()).zip(analysis.iter())
+                            {
+                                *o += bin.re * w * scale;
+                            }
+                            ready.copy_from_slice(&overlap[0..hop]);
+                            ready_pos = 0;
+                            overlap.copy_within(hop.., 0);
+                            overlap[hop..].fill(zero);
+                            input.copy_within(hop.., 0);
+                            input_pos = frame_len - hop;
+                        }
+                        let Ok(()) = sender
+                            .send(Signal::Samples {
+                                sample_rate,
+                                chunk: output_chunk.finalize(),
+                            })
+                            .await
+                        else { return; };
+                    }
+                    Signal::Event(event) => {
+                        if event.is_interrupt() {
+                            input.fill(zero);
+                            input_pos = frame_len - hop;
+                            overlap.fill(zero);
+                            ready.fill(zero);
+                            ready_pos = 0;
+                            noise = None;
+                        }
+                        let Ok(()) = sender.send(Signal::Event(event)).await else { return; };
+                    }
+                }
+            }
+        });
+        Self {
+            receiver_connector,
+            sender_connector,
+            strength: strength_send,
+            floor: floor_send,
+        }
+    }
+    /// Get strength (factor applied to the estimated noise power)
+    pub fn strength(&self) -> f64 {
+        *self.strength.borrow()
+    }
+    /// Set strength (factor applied to the estimated noise power)
+    pub fn set_strength(&self, strength: f64) {
+        self.strength.send_replace(strength);
+    }
+    /// Get minimum amplitude gain of each frequency bin
+    pub fn floor(&self) -> f64 {
+        *self.floor.borrow()
+    }
+    /// Set minimum amplitude gain of each frequency bin
+    pub fn set_floor(&self, floor: f64) {
+        self.floor.send_replace(floor);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::flushed_lens;
+    #[tokio::test]
+    async fn test_flush() {
+        let input = vec![Complex::new(1.0, 0.5); 3072];
+        let filter =
+            Filter::<f64>::new(|_, freq| Complex::from((freq.abs() < 5000.0) as u8 as f64));
+        // the delay of one chunk is flushed
+        let lens = flushed_lens(&filter, 48000.0, input.clone(), 1024).await;
+        assert_eq!(lens, [3072; 2]);
+        let coeffs: Arc<[f64]> = (0..31).map(|i| i as f64 / 100.0).collect();
+        // the tail of the impulse response is appended
+        let fir = FirFilter::<f64>::new(coeffs.clone());
+        let lens = flushed_lens(&fir, 48000.0, input.clone(), 1000).await;
+        assert_eq!(lens, [3102; 2]);
+        let fft_fir = FftFirFilter::<f64>::new(coeffs);
+        let lens = flushed_lens(&fft_fir, 48000.0, input.clone(), 1000).await;
+        assert_eq!(lens, [3102; 2]);
+        let rrc = RrcFilter::<f64>::new(4, 0.35, 10);
+        let lens = flushed_lens(&rrc, 48000.0, input, 1000).await;
+        assert_eq!(lens, [3112; 2]);
+    }
+    #[tokio::test]
+    async fn test_fir_filter() {
+        let (sender, sender_connector) = new_sender::<Signal<Complex<f32>>>();
+        let filter = FirFilter::<f32>::new(vec![1.0, 2.0, 3.0].into());
+        let (mut receiver, receiver_connector) = new_receiver::<Signal<Complex<f32>>>();
+        filter.feed_from(&sender_connector);
+        filter.feed_into(&receiver_connector);
+        let mut outputs: Vec<f32> = Vec::new();
+        for input in [vec![1.0, 0.0], vec![0.0, 0.0], vec![1.0]] {
+            let input: Vec<Complex<f32>> = input.into_iter().map(Complex::from).collect();
+            sender
+                .send(Signal::Samples {
+                    sample_rate: 48000.0,
+                    chunk: Chunk::from(input),
+                })
+                .await
+                .unwrap();
+            let Signal::Samples { chunk, .. } = receiver.recv().await.unwrap()
+            else { panic!(); };
+            outputs.extend(chunk.iter().map(|x| x.re));
+        }
+        assert_eq!(outputs, vec![1.0, 2.0, 3.0, 0.0, 1.0]);
+        filter.set_coeffs(vec![0.0, 1.0].into());
+        sender
+            .send(Signal::Samples {
+                sample_rate: 48000.0,
+                chunk: Chunk::from(vec![Complex::from(5.0)]),
+            })
+            .await
+            .unwrap();
+        let Signal::Samples { chunk, .. } = receiver.recv().await.unwrap()
+        else { panic!(); };
+        assert_eq!(chunk[0].re, 1.0);
+        sender.send(Signal::new_event(Flush)).await.unwrap();
+        let Signal::Samples { chunk, .. } = receiver.recv().await.unwrap()
+        else { panic!(); };
+        assert_eq!(chunk.len(), 1);
+        assert_eq!(chunk[0].re, 5.0);
+        let Signal::Event(event) = receiver.recv().await.unwrap()
+        else { panic!(); };
+        assert!(event.is_flush());
+        sender.send(Signal::new_event(Flush)).await.unwrap();
+        assert!(receiver.recv().await.unwrap().is_event());
+    }
+    #[tokio::test]
+    async fn test_fft_fir_filter() {
+        let coeffs: Arc<[f64]> = (0..1000).map(|i| (i as f64 * 0.37).sin() / 100.0).collect();
+        let (sender, sender_connector) = new_sender::<Signal<Complex<f64>>>();
+        let direct = FirFilter::<f64>::new(coeffs.clone());
+        let fast = FftFirFilter::<f64>::new(coeffs);
+        let (mut receiver1, receiver1_connector) = new_receiver::<Signal<Complex<f64>>>();
+        let (mut receiver2, receiver2_connector) = new_receiver::<Signal<Complex<f64>>>();
+        direct.feed_from(&sender_connector);
+        fast.feed_from(&sender_connector);
+        direct.feed_into(&receiver1_connector);
+        fast.feed_into(&receiver2_connector);
+        let mut offset = 0;
+        for (idx, len) in [3000, 17, 1, 700, 5000].into_iter().enumerate() {
+            if idx == 3 {
+                let coeffs: Arc<[f64]> = (0..300).map(|i| (i as f64 * 0.11).cos() / 50.0).collect();
+                direct.set_coeffs(coeffs.clone());
+                fast.set_coeffs(coeffs);
+            }
+            let samples: Vec<Complex<f64>> = (offset..offset + len)
+                .map(|i| Complex::new((i as f64 * 0.05).sin(), (i as f64 * 1.7).cos()))
+                .collect();
+            offset += len;
+            sender
+                .send(Signal::Samples {
+                    sample_rate: 48000.0,
+                    chunk: Chunk::from(samples),
+                })
+                .await
+                .unwrap();
+            let Signal::Samples { chunk: output1, .. } = receiver1.recv().await.unwrap()
+            else { panic!(); };
+            let Signal::Samples { chunk: output2, .. } = receiver2.recv().await.unwrap()
+            else { panic!(); };
+            assert_eq!(output2.len(), len);
+            for (x1, x2) in output1.iter().zip(output2.iter()) {
+                assert!((x1 - x2).norm() < 1e-9);
+            }
+        }
+        sender.send(Signal::new_event(Flush)).await.unwrap();
+        let Signal::Samples { chunk: output1, .. } = receiver1.recv().await.unwrap()
+        else { panic!(); };
+        let Signal::Samples { chunk: output2, .. } = receiver2.recv().await.unwrap()
+        else { panic!(); };
+        assert_eq!(output1.len(), 299);
+        assert_eq!(output2.len(), 299);
+        for (x1, x2) in output1.iter().zip(output2.iter()) {
+            assert!((x1 - x2).norm() < 1e-9);
+        }
+        assert!(receiver1.recv().await.unwrap().is_event());
+        assert!(receiver2.recv().await.unwrap().is_event());
+    }
+    #[tokio::test]
+    async fn test_rrc_filter() {
+        let (sender, sender_connector) = new_sender::<Signal<Complex<f64>>>();
+        let shaper = RrcFilter::<f64>::new(4, 0.35, 10);
+        let matched = RrcFilter::<f64>::new(4, 0.35, 10);
+        let (mut receiver, receiver_connector) = new_receiver::<Signal<Complex<f64>>>();
+        shaper.feed_from(&sender_connector);
+        matched.feed_from(&shaper);
+        matched.feed_into(&receiver_connector);
+        assert_eq!(matched.samples_per_symbol(), 4);
+        let symbols: Vec<Complex<f64>> = (0..100)
+            .map(|i| Complex::new([1.0, -1.0][i % 3 % 2], [-1.0, 1.0][i % 5 % 2]))
+            .collect();
+        let mut input: Vec<Complex<f64>> = vec![Complex::from(0.0); 4 * symbols.len()];
+        for (i, &symbol) in symbols.iter().enumerate() {
+            input[4 * i] = symbol;
+        }
+        sender
+            .send(Signal::Samples {
+                sample_rate: 48000.0,
+                chunk: Chunk::from(input),
+            })
+            .await
+            .unwrap();
+        let Signal::Samples { chunk, .. } = receiver.recv().await.unwrap()
+        else { panic!(); };
+        for (i, &symbol) in symbols.iter().enumerate().take(90).skip(10) {
+            assert!((chunk[4 * i + 40] - symbol).norm() < 0.02);
+        }
+    }
+    #[test]
+    fn test_biquad_coeffs() {
+        use crate::tests::assert_approx;
+        let fs = 48000.0;
+        let q = std::f64::consts::FRAC_1_SQRT_2;
+        let lowpass = BiquadCoeffs::lowpass(1000.0, q, fs);
+        assert_approx(lowpass.response(0.0, fs).norm(), 1.0);
+        assert_approx(lowpass.response(1000.0, fs).norm(), q);
+        assert!(lowpass.response(20000.0, fs).norm() < 0.01);
+        let highpass = BiquadCoeffs::highpass(1000.0, q, fs);
+        assert_approx(highpass.response(24000.0, fs).norm(), 1.0);
+        assert_approx(highpass.response(1000.0, fs).norm(), q);
+        let bandpass = BiquadCoeffs::bandpass(1000.0, 5.0, fs);
+        assert_approx(bandpass.response(1000.0, fs).norm(), 1.0);
+        assert!(bandpass.response(0.0, fs).norm() < 1e-9);
+        let notch = BiquadCoeffs::notch(1000.0, 5.0, fs);
+        assert!(notch.response(1000.0, fs).norm() < 1e-9);
+        assert_approx(notch.response(0.0, fs).norm(), 1.0);
+        let peaking = BiquadCoeffs::peaking(1000.0, 2.0, 6.0, fs);
+        assert_approx(peaking.response(1000.0, fs).norm(), 10f64.powf(6.0 / 20.0));
+        assert_approx(peaking.response(0.0, fs).norm(), 1.0);
+        for coeffs in [lowpass, highpass, bandpass, notch, peaking] {
+            assert!(coeffs.is_stable());
+        }
+        let mut unstable = lowpass;
+        unstable.a2 = 1.5;
+        assert!(!unstable.is_stable());
+    }
+    #[tokio::test]
+    async fn test_biquad() {
+        use std::f64::consts::TAU;
+        let (sender, sender_connector) = new_sender::<Signal<Complex<f64>>>();
+        let design = BiquadDesign::Notch {
+            center: 50.0,
+            q: 2.0,
+        };
+        let filter = Biquad::<f64>::new(vec![design]);
+        let (mut receiver, receiver_connector) = new_receiver::<Signal<Complex<f64>>>();
+        filter.feed_from(&sender_connector);
+        filter.feed_into(&receiver_connector);
+        let mut peaks = Vec::new();
+        for freq in [50.0, 200.0] {
+            let mut peak: f64 = 0.0;
+            for i in 0..10 {
+                let chunk: Vec<Complex<f64>> = (i * 1000..(i + 1) * 1000)
+                    .map(|t| Complex::from((TAU * freq * t as f64 / 8000.0).sin()))
+                    .collect();
+                sender
+                    .send(Signal::Samples {
+                        sample_rate: 8000.0,
+                        chunk: Chunk::from(chunk),
+                    })
+                    .await
+                    .unwrap();
+                let Signal::Samples { chunk, .. } = receiver.recv().await.unwrap()
+                else { panic!(); };
+                if i >= 5 {
+                    peak = chunk.iter().map(|x| x.re.abs()).fold(peak, f64::max);
+                }
+            }
+            peaks.push(peak);
+        }
+        assert!(peaks[0] < 1e-3);
+        let expected = design.coeffs(8000.0).response(200.0, 8000.0).norm();
+        assert!((peaks[1] - expected).abs() < 1e-3);
+        filter.set_sections(vec![]);
+        sender
+            .send(Signal::Samples {
+                sample_rate: 8000.0,
+                chunk: Chunk::from(vec![Complex::from(f64::NAN), Complex::from(2.0)]),
+            })
+            .await
+            .unwrap();
+        let Signal::Samples { chunk, .. } = receiver.recv().await.unwrap()
+        else { panic!(); };
+        assert_eq!(chunk[1].re, 2.0);
+    }
+    #[tokio::test]
+    async fn test_auto_notch() {
+        use std::f64::consts::TAU;
+        let (sender, sender_connector) = new_sender::<Signal<Complex<f64>>>();
+        let notch = AutoNotch::<f64>::new(2, 0.005);
+        let (mut receiver, receiver_connector) = new_receiver::<Signal<Complex<f64>>>();
+        notch.feed_from(&sender_connector);
+        notch.feed_into(&receiver_connector);
+        let mut rng_state: u32 = 1;
+        let mut noise = move || {
+            rng_state = rng_state.wrapping_mul(1664525).wrapping_add(1013904223);
+            rng_state as f64 / u32::MAX as f64 - 0.5
+        };
+        let mut tone_amplitude = 0.0;
+        let mut output_power = 0.0;
+        for i in 0..40 {
+            let chunk: Vec<Complex<f64>> = (i * 1000..(i + 1) * 1000)
+                .map(|t| Complex::from((TAU * 1000.0 * t as f64 / 8000.0).sin() + noise()))
+                .collect();
+            sender
+                .send(Signal::Samples {
+                    sample_rate: 8000.0,
+                    chunk: Chunk::from(chunk),
+                })
+                .await
+                .unwrap();
+            let Signal::Samples { chunk, .. } = receiver.recv().await.unwrap()
+            else { panic!(); };
+            if i == 39 {
+                let mut sum = Complex::new(0.0, 0.0);
+                for (j, x) in chunk.iter().enumerate() {
+                    let t = (i * 1000 + j) as f64;
+                    sum += Complex::from_polar(x.re, -TAU * 1000.0 * t / 8000.0);
+                }
+                tone_amplitude = 2.0 * sum.norm() / 1000.0;
+                output_power = chunk.iter().map(|x| x.re * x.re).sum::<f64>() / 1000.0;
+            }
+        }
+        assert!(tone_amplitude < 0.1);
+        // power of uniform noise in range -0.5..0.5 is 1/12
+        assert!((output_power * 12.0 - 1.0).abs() < 0.3);
+    }
+    #[tokio::test]
+    async fn test_noise_reduction() {
+        use std::f64::consts::TAU;
+        let (sender, sender_connector) = new_sender::<Signal<Complex<f64>>>();
+        let reduction = NoiseReduction::<f64>::new(256, 2.0, 0.1);
+        let (mut receiver, receiver_connector) = new_receiver::<Signal<Complex<f64>>>();
+        reduction.feed_from(&sender_connector);
+        reduction.feed_into(&receiver_connector);
+        let mut rng_state: u32 = 1;
+        let mut noise = move || {
+            rng_state = rng_state.wrapping_mul(1664525).wrapping_add(1013904223);
+            0.2 * (rng_state as f64 / u32::MAX as f64 - 0.5)
+        };
+        let mut chunk = Chunk::from(vec![]);
+        let mut noise_power = 0.0;
+        for i in 0..30 {
+            let amplitude = if i < 20 { 0.0 } else { 0.5 };
+            let samples: Vec<Complex<f64>> = (i * 1000..(i + 1) * 1000)
+                .map(|t| amplitude * (TAU * 1000.0 * t as f64 / 8000.0).sin() + noise())
+                .map(Complex::from)
+                .collect();
+            sender
+                .send(Signal::Samples {
+                    sample_rate: 8000.0,
+                    chunk: Chunk::from(samples),
+                })
+                .await
+                .unwrap();
+            let Signal::Samples { chunk: output, .. } = receiver.recv().await.unwrap()
+            else { panic!(); };
+            chunk = output;
+            if i == 19 {
+                noise_power = chunk.iter().map(|x| x.re * x.re).sum::<f64>() / 1000.0;
+            }
+        }
+        // power of uniform noise in range -0.1..0.1 is 1/300
+        assert!(noise_power * 300.0 < 0.1);
+        // output is delayed by 256 samples
+        let mut sum = Complex::new(0.0, 0.0);
+        for (j, x) in chunk.iter().enumerate() {
+            let t = (29 * 1000 + j - 256) as f64;
+            sum += Complex::from_polar(x.re, -TAU * 1000.0 * t / 8000.0);
+        }
+        let amplitude = 2.0 * sum.norm() / 1000.0;
+        assert!((amplitude - 0.5).abs() < 0.05);
+    }
+    #[tokio::test]
+    async fn test_dc_blocker() {
+        let (sender, sender_connector) = new_sender::<Signal<Complex<f64>>>();
+        let dc_blocker = DcBlocker::<f64>::with_pole(0.99);
+        let (mut receiver, receiver_connector) = new_receiver::<Signal<Complex<f64>>>();
+        dc_blocker.feed_from(&sender_connector);
+        dc_blocker.feed_into(&receiver_connector);
+        let mut mean = Complex::from(0.0);
+        for _ in 0..10 {
+            let samples: Vec<Complex<f64>> = (0..1000)
+                .map(|i| Complex::new(0.5 + (i as f64).sin(), -0.25))
+                .collect();
+            sender
+                .send(Signal::Samples {
+                    sample_rate: 48000.0,
+                    chunk: Chunk::from(samples),
+                })
+                .await
+                .unwrap();
+            let Signal::Samples { chunk, .. } = receiver.recv().await.unwrap()
+            else { panic!(); };
+            mean = chunk.iter().sum::<Complex<f64>>() / chunk.len() as f64;
+        }
+        assert!(mean.norm() < 1e-3);
+    }
+    #[tokio::test]
+    async fn test_deemphasis_reset() {
+        let (sender, sender_connector) = new_sender::<Signal<Complex<f64>>>();
+        let deemphasis = Deemphasis::<f64>::new(TAU_75US);
+        let (mut receiver, receiver_connector) = new_receiver::<Signal<Complex<f64>>>();
+        deemphasis.feed_from(&sender_connector);
+        deemphasis.feed_into(&receiver_connector);
+        let mut outputs = Vec::new();
+        for reset in [false, true] {
+            if reset {
+                deemphasis.reset();
+                let Signal::Event(event) = receiver.recv().await.unwrap()
+                else { panic!(); };
+                assert!(event.is_interrupt());
+            }
+            let (result, signal) = tokio::join!(
+                sender.send(Signal::Samples {
+                    sample_rate: 48000.0,
+                    chunk: Chunk::from(vec![Complex::from(1.0); 10]),
+                }),
+                receiver.recv()
+            );
+            result.unwrap();
+            let Signal::Samples { chunk, .. } = signal.unwrap()
+            else { panic!(); };
+            outputs.push(chunk);
+        }
+        assert!(outputs[1][9].re < 1.0);
+        assert_eq!(outputs[0][..], outputs[1][..]);
+    }
+    #[tokio::test]
+    async fn test_preemphasis_deemphasis() {
+        let (sender, sender_connector) = new_sender::<Signal<Complex<f64>>>();
+        let preemphasis = Preemphasis::<f64>::new(TAU_75US);
+        let deemphasis = Deemphasis::<f64>::new(TAU_75US);
+        let (mut receiver, receiver_connector) = new_receiver::<Signal<Complex<f64>>>();
+        preemphasis.feed_from(&sender_connector);
+        deemphasis.feed_from(&preemphasis);
+        deemphasis.feed_into(&receiver_connector);
+        for offset in [0, 500] {
+            let samples: Vec<Complex<f64>> = (offset..offset + 500)
+                .map(|i| Complex::from((i as f64 * 0.3).sin() + (i as f64 * 2.1).cos()))
+                .collect();
+            sender
+                .send(Signal::Samples {
+                    sample_rate: 48000.0,
+                    chunk: Chunk::from(samples.clone()),
+                })
+                .await
+                .unwrap();
+            let Signal::Samples { chunk, .. } = receiver.recv().await.unwrap()
+            else { panic!(); };
+            for (output, input) in chunk.iter().zip(samples.iter()) {
+                assert!((output - input).norm() < 1e-9);
+            }
+        }
+    }
+}
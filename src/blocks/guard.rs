@@ -0,0 +1,191 @@
+//! Detection of unexpected changes in a stream
+
+use crate::flow::*;
+use crate::impl_block_trait;
+use crate::signal::*;
+
+use tokio::task::spawn;
+
+/// Types used for [`Signal::Event`]
+pub mod events {
+    use super::*;
+    /// Sent by [`SampleRateGuard`] block when the sample rate has changed
+    #[derive(Clone, Debug)]
+    pub struct SampleRateChanged {
+        /// Previous (or expected) sample rate
+        pub previous: f64,
+        /// New sample rate
+        pub current: f64,
+    }
+    impl Event for SampleRateChanged {
+        fn is_interrupt(&self) -> bool {
+            true
+        }
+        fn as_any(&self) -> &(dyn std::any::Any + Send + Sync) {
+            self
+        }
+    }
+}
+use events::*;
+
+/// Helper for stateful blocks to detect changes of the sample rate
+///
+/// # Example
+///
+/// ```
+/// use radiorust::blocks::guard::SampleRateTracker;
+/// let mut tracker = SampleRateTracker::new();
+/// assert!(tracker.update(48000.0));
+/// assert!(!tracker.update(48000.0));
+/// assert!(tracker.update(44100.0));
+/// tracker.reset();
+/// assert!(tracker.update(44100.0));
+/// ```
+#[derive(Clone, Debug)]
+pub struct SampleRateTracker {
+    previous: Option<f64>,
+}
+
+impl SampleRateTracker {
+    /// Create new tracker which has not seen any sample rate yet
+    pub fn new() -> Self {
+        Self { previous: None }
+    }
+    /// Record sample rate and return true if it differs from the previously
+    /// recorded sample rate (or if no sample rate has been recorded yet)
+    ///
+    /// Blocks should reset (or recalculate) their internal state when this
+    /// method returns true.
+    pub fn update(&mut self, sample_rate: f64) -> bool {
+        let changed = self.previous != Some(sample_rate);
+        self.previous = Some(sample_rate);
+        changed
+    }
+    /// Most recently recorded sample rate
+    pub fn sample_rate(&self) -> Option<f64> {
+        self.previous
+    }
+    /// Forget recorded sample rate
+    pub fn reset(&mut self) {
+        self.previous = None;
+    }
+}
+
+/// Action performed by [`SampleRateGuard`] on an unexpected sample rate
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum GuardAction {
+    /// Send a [`SampleRateChanged`] event before the affected chunk
+    Event,
+    /// Panic (stopping the block)
+    Panic,
+}
+
+/// Block which passes a [`Signal`] unchanged but detects changes of the
+/// sample rate
+///
+/// If an expected sample rate is given, any other sample rate is considered
+/// unexpected. Otherwise, any change of the sample rate after the first chunk
+/// is considered unexpected.
+pub struct SampleRateGuard<T> {
+    receiver_connector: ReceiverConnector<Signal<T>>,
+    sender_connector: SenderConnector<Signal<T>>,
+}
+
+impl_block_trait! { <T> Consumer<Signal<T>> for SampleRateGuard<T> }
+impl_block_trait! { <T> Producer<Signal<T>> for SampleRateGuard<T> }
+
+impl<T> SampleRateGuard<T>
+where
+    T: Clone + Send + Sync + 'static,
+{
+    /// Create new `SampleRateGuard` which detects any change of the sample
+    /// rate
+    pub fn new(action: GuardAction) -> Self {
+        Self::new_internal(None, action)
+    }
+    /// Create new `SampleRateGuard` which expects the given sample rate
+    pub fn with_expected(sample_rate: f64, action: GuardAction) -> Self {
+        Self::new_internal(Some(sample_rate), action)
+    }
+    fn new_internal(expected: Option<f64>, action: GuardAction) -> Self {
+        let (mut receiver, receiver_connector) = new_receiver::<Signal<T>>();
+        let (sender, sender_connector) = new_sender::<Signal<T>>();
+        spawn(async move {
+            let mut tracker = SampleRateTracker::new();
+            if let Some(expected) = expected {
+                tracker.update(expected);
+            }
+            loop {
+                let Ok(signal) = receiver.recv().await else { return; };
+                if let Signal::Samples { sample_rate, .. } = signal {
+                    let previous = tracker.sample_rate();
+                    let allowed = match expected {
+                        Some(expected) => sample_rate == expected,
+                        None => previous.is_none(),
+                    };
+                    if tracker.update(sample_rate) && !allowed {
+                        let previous = previous.unwrap();
+                        match action {
+                            GuardAction::Event => {
+                                let Ok(()) = sender
+                                    .send(Signal::new_event(SampleRateChanged {
+                                        previous,
+                                        current: sample_rate,
+                                    }))
+                                    .await
+                                else { return; };
+                            }
+                            GuardAction::Panic => panic!(
+                                "unexpected sample rate change from {previous} to {sample_rate}"
+                            ),
+                        }
+                    }
+                }
+                let Ok(()) = sender.send(signal).await else { return; };
+            }
+        });
+        Self {
+            receiver_connector,
+            sender_connector,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bufferpool::Chunk;
+    #[tokio::test]
+    async fn test_sample_rate_guard() {
+        let (sender, sender_connector) = new_sender::<Signal<f32>>();
+        let guard = SampleRateGuard::<f32>::new(GuardAction::Event);
+        let (mut receiver, receiver_connector) = new_receiver::<Signal<f32>>();
+        guard.feed_from(&sender_connector);
+        guard.feed_into(&receiver_connector);
+        let join_handle = tokio::spawn(async move {
+            for sample_rate in [48000.0, 48000.0, 44100.0] {
+                sender
+                    .send(Signal::Samples {
+                        sample_rate,
+                        chunk: Chunk::from(vec![0.0]),
+                    })
+                    .await
+                    .unwrap();
+            }
+        });
+        for _ in 0..2 {
+            let Signal::Samples { sample_rate, .. } = receiver.recv().await.unwrap()
+            else { panic!(); };
+            assert_eq!(sample_rate, 48000.0);
+        }
+        let Signal::Event(event) = receiver.recv().await.unwrap()
+        else { panic!(); };
+        let event = event.as_any().downcast_ref::<SampleRateChanged>().unwrap();
+        assert_eq!(event.previous, 48000.0);
+        assert_eq!(event.current, 44100.0);
+        let Signal::Samples { sample_rate, .. } = receiver.recv().await.unwrap()
+        else { panic!(); };
+        assert_eq!(sample_rate, 44100.0);
+        join_handle.await.unwrap();
+    }
+}
@@ -7,6 +7,8 @@ use crate::numbers::*;
 use crate::signal::*;
 
 use cpal::traits::{DeviceTrait as _, HostTrait as _, StreamTrait as _};
+use tokio::select;
+use tokio::sync::watch;
 
 use std::error::Error as StdError;
 use std::fmt;
@@ -16,6 +18,7 @@ pub use cpal::Device;
 #[derive(Debug)]
 enum ErrorVariant {
     BuildStreamInvalidArgument(&'static str),
+    DevicesDriverError(cpal::DevicesError),
     BuildStreamDriverError(cpal::BuildStreamError),
     PlayStreamDriverError(cpal::PlayStreamError),
     PauseStreamDriverError(cpal::PauseStreamError),
@@ -32,6 +35,7 @@ impl fmt::Display for Error {
             BuildStreamInvalidArgument(s) => {
                 write!(f, "invalid argument when opening audio device: {s}")
             }
+            DevicesDriverError(_) => write!(f, "could not enumerate audio devices"),
             BuildStreamDriverError(_) => write!(f, "could not open audio device"),
             PlayStreamDriverError(_) => write!(f, "could start audio stream"),
             PauseStreamDriverError(_) => write!(f, "could pause audio stream"),
@@ -44,6 +48,7 @@ impl StdError for Error {
         use ErrorVariant::*;
         match &self.0 {
             BuildStreamInvalidArgument(_) => None,
+            DevicesDriverError(inner) => Some(inner),
             BuildStreamDriverError(inner) => Some(inner),
             PlayStreamDriverError(inner) => Some(inner),
             PauseStreamDriverError(inner) => Some(inner),
@@ -57,6 +62,12 @@ impl Error {
     }
 }
 
+impl From<cpal::DevicesError> for Error {
+    fn from(inner: cpal::DevicesError) -> Self {
+        Self(ErrorVariant::DevicesDriverError(inner))
+    }
+}
+
 impl From<cpal::BuildStreamError> for Error {
     fn from(inner: cpal::BuildStreamError) -> Self {
         Self(ErrorVariant::BuildStreamDriverError(inner))
@@ -93,11 +104,197 @@ pub fn default_input_device() -> cpal::Device {
         .expect("no audio input device available")
 }
 
+/// Information about an audio device, as returned by [`list_output_devices`]
+/// and [`list_input_devices`]
+pub struct AudioDeviceInfo {
+    /// Name of the device
+    pub name: String,
+    /// True if this is the default device of the host
+    pub is_default: bool,
+    /// Supported numbers of channels
+    pub channel_counts: Vec<u16>,
+    /// Supported ranges of sample rates (minimum and maximum, in samples per
+    /// second)
+    pub sample_rates: Vec<(u32, u32)>,
+    /// Underlying [`cpal::Device`]
+    pub device: cpal::Device,
+}
+
+impl AudioDeviceInfo {
+    /// Return true if the device supports the given sample rate (in samples
+    /// per second)
+    pub fn supports_sample_rate(&self, sample_rate: f64) -> bool {
+        let sample_rate = sample_rate.round() as u32;
+        self.sample_rates
+            .iter()
+            .any(|&(min, max)| (min..=max).contains(&sample_rate))
+    }
+}
+
+fn buffer_size_for_duration(sample_rate: f64, buffer_duration: f64) -> Result<usize, Error> {
+    let buffer_size = (sample_rate * buffer_duration).round();
+    if buffer_size.is_nan() || buffer_size < 1.0 {
+        return Err(Error::invalid_argument("invalid buffer duration"));
+    }
+    Ok(buffer_size as usize)
+}
+
+fn device_info<I>(
+    device: cpal::Device,
+    default_name: Option<&str>,
+    configs: Result<I, cpal::SupportedStreamConfigsError>,
+) -> AudioDeviceInfo
+where
+    I: Iterator<Item = cpal::SupportedStreamConfigRange>,
+{
+    let name = device.name().unwrap_or_default();
+    let mut channel_counts: Vec<u16> = Vec::new();
+    let mut sample_rates: Vec<(u32, u32)> = Vec::new();
+    for config in configs.into_iter().flatten() {
+        if !channel_counts.contains(&config.channels()) {
+            channel_counts.push(config.channels());
+        }
+        let range = (config.min_sample_rate().0, config.max_sample_rate().0);
+        if !sample_rates.contains(&range) {
+            sample_rates.push(range);
+        }
+    }
+    channel_counts.sort_unstable();
+    sample_rates.sort_unstable();
+    AudioDeviceInfo {
+        is_default: default_name == Some(name.as_str()),
+        name,
+        channel_counts,
+        sample_rates,
+        device,
+    }
+}
+
+/// Retrieve information about all available devices for audio output
+pub fn list_output_devices() -> Result<Vec<AudioDeviceInfo>, Error> {
+    let host = cpal::default_host();
+    let default_name = host.default_output_device().and_then(|d| d.name().ok());
+    Ok(host
+        .output_devices()?
+        .map(|device| {
+            let configs = device.supported_output_configs();
+            device_info(device, default_name.as_deref(), configs)
+        })
+        .collect())
+}
+
+/// Retrieve information about all available devices for audio input
+pub fn list_input_devices() -> Result<Vec<AudioDeviceInfo>, Error> {
+    let host = cpal::default_host();
+    let default_name = host.default_input_device().and_then(|d| d.name().ok());
+    Ok(host
+        .input_devices()?
+        .map(|device| {
+            let configs = device.supported_input_configs();
+            device_info(device, default_name.as_deref(), configs)
+        })
+        .collect())
+}
+
+fn stream_config(
+    channels: u16,
+    sample_rate: f64,
+    buffer_size: Option<usize>,
+) -> Result<cpal::StreamConfig, Error> {
+    Ok(cpal::StreamConfig {
+        channels,
+        sample_rate: cpal::SampleRate(sample_rate.round() as u32),
+        buffer_size: match buffer_size {
+            None => cpal::BufferSize::Default,
+            Some(value) => cpal::BufferSize::Fixed(
+                value
+                    .try_into()
+                    .or(Err(Error::invalid_argument("invalid buffer size")))?,
+            ),
+        },
+    })
+}
+
+/// Receiving side of a single audio channel used in playback callbacks
+struct PlaybackChannel {
+    receiver: Receiver<Signal<Complex<f32>>>,
+    current_chunk_and_pos: Option<(Chunk<Complex<f32>>, usize)>,
+    started: bool,
+}
+
+impl PlaybackChannel {
+    fn new(receiver: Receiver<Signal<Complex<f32>>>) -> Self {
+        Self {
+            receiver,
+            current_chunk_and_pos: None,
+            started: false,
+        }
+    }
+    /// Get next sample (blocking the current thread if necessary) or `None`
+    /// if the channel has been closed
+    fn next_sample(
+        &mut self,
+        rt: &tokio::runtime::Handle,
+        sample_rate: f64,
+        event_handlers: &EventHandlers,
+        underruns: &watch::Sender<u64>,
+    ) -> Option<f32> {
+        let (current_chunk, mut current_pos) = match self.current_chunk_and_pos.take() {
+            Some(x) => x,
+            None => loop {
+                let receiver = &mut self.receiver;
+                let ready = rt.block_on(async {
+                    select! {
+                        biased;
+                        result = receiver.recv() => Some(result),
+                        _ = async {} => None,
+                    }
+                });
+                let result = match ready {
+                    Some(result) => result,
+                    None => {
+                        if self.started {
+                            underruns.send_modify(|count| *count += 1);
+                            event!(DEBUG, "audio output underrun");
+                        }
+                        rt.block_on(receiver.recv())
+                    }
+                };
+                let Ok(signal) = result else { return None; };
+                match signal {
+                    Signal::Samples {
+                        sample_rate: rcvd_sample_rate,
+                        chunk,
+                    } => {
+                        assert_eq!(
+                            rcvd_sample_rate, sample_rate,
+                            "audio playback received samples with unexpected sample rate {rcvd_sample_rate} instead of {sample_rate}"
+                        );
+                        if chunk.is_empty() {
+                            continue;
+                        }
+                        self.started = true;
+                        break (chunk, 0);
+                    }
+                    Signal::Event(event) => event_handlers.invoke(&event),
+                }
+            },
+        };
+        let value = current_chunk[current_pos].re;
+        current_pos += 1;
+        if current_pos < current_chunk.len() {
+            self.current_chunk_and_pos = Some((current_chunk, current_pos))
+        }
+        Some(value)
+    }
+}
+
 /// Audio player block acting as a [`Consumer`]
 pub struct AudioPlayer {
     receiver_connector: ReceiverConnector<Signal<Complex<f32>>>,
     event_handlers: EventHandlers,
     stream: cpal::Stream,
+    underruns: watch::Receiver<u64>,
 }
 
 impl_block_trait! { Consumer<Signal<Complex<f32>>> for AudioPlayer }
@@ -109,6 +306,26 @@ impl AudioPlayer {
     pub fn new(sample_rate: f64, buffer_size: Option<usize>) -> Result<Self, Error> {
         Self::with_device(&default_output_device(), sample_rate, buffer_size)
     }
+    /// Create block for audio playback with given `sample_rate` on the device
+    /// described by [`AudioDeviceInfo`], using a buffer of `buffer_duration`
+    /// seconds
+    ///
+    /// Fails if the device doesn't support the given `sample_rate`. Note that
+    /// no resampling is performed: the block panics if it receives samples
+    /// with a different sample rate.
+    pub fn with_device_info(
+        device: &AudioDeviceInfo,
+        sample_rate: f64,
+        buffer_duration: f64,
+    ) -> Result<Self, Error> {
+        if !device.supports_sample_rate(sample_rate) {
+            return Err(Error::invalid_argument(
+                "sample rate not supported by audio device",
+            ));
+        }
+        let buffer_size = buffer_size_for_duration(sample_rate, buffer_duration)?;
+        Self::with_device(&device.device, sample_rate, Some(buffer_size))
+    }
     /// Create block for audio playback with given `sample_rate` and optionally
     /// requested `buffer_size` on given [`cpal::Device`]
     pub fn with_device(
@@ -116,50 +333,20 @@ impl AudioPlayer {
         sample_rate: f64,
         buffer_size: Option<usize>,
     ) -> Result<Self, Error> {
-        let config = cpal::StreamConfig {
-            channels: 1,
-            sample_rate: cpal::SampleRate(sample_rate.round() as u32),
-            buffer_size: match buffer_size {
-                None => cpal::BufferSize::Default,
-                Some(value) => cpal::BufferSize::Fixed(
-                    value
-                        .try_into()
-                        .or(Err(Error::invalid_argument("invalid buffer size")))?,
-                ),
-            },
-        };
+        let config = stream_config(1, sample_rate, buffer_size)?;
         let rt = tokio::runtime::Handle::current();
-        let (mut receiver, receiver_connector) = new_receiver::<Signal<Complex<f32>>>();
+        let (receiver, receiver_connector) = new_receiver::<Signal<Complex<f32>>>();
         let event_handlers = EventHandlers::new();
         let evhdl_clone = event_handlers.clone();
+        let (underruns_send, underruns) = watch::channel(0u64);
         let err_fn = move |err| panic!("error during audio playback: {err}");
-        let mut current_chunk_and_pos: Option<(Chunk<Complex<f32>>, usize)> = None;
+        let mut channel = PlaybackChannel::new(receiver);
         let write_audio = move |data: &mut [f32], _: &cpal::OutputCallbackInfo| {
             for sample in data.iter_mut() {
-                let (current_chunk, mut current_pos) = match current_chunk_and_pos.take() {
-                    Some(x) => x,
-                    None => loop {
-                        let Ok(signal) = rt.block_on(receiver.recv()) else { return; };
-                        match signal {
-                            Signal::Samples {
-                                sample_rate: rcvd_sample_rate,
-                                chunk,
-                            } => {
-                                assert_eq!(
-                                    rcvd_sample_rate, sample_rate,
-                                    "AudioPlayer block received samples with unexpected sample rate {rcvd_sample_rate} instead of {sample_rate}"
-                                );
-                                break (chunk, 0);
-                            }
-                            Signal::Event(event) => evhdl_clone.invoke(&event),
-                        }
-                    },
-                };
-                *sample = current_chunk[current_pos].re;
-                current_pos += 1;
-                if current_pos < current_chunk.len() {
-                    current_chunk_and_pos = Some((current_chunk, current_pos))
-                }
+                let Some(value) =
+                    channel.next_sample(&rt, sample_rate, &evhdl_clone, &underruns_send)
+                else { return; };
+                *sample = value;
             }
         };
         let stream = device.build_output_stream(&config, write_audio, err_fn)?;
@@ -168,8 +355,117 @@ impl AudioPlayer {
             receiver_connector,
             event_handlers,
             stream,
+            underruns,
         })
     }
+    /// Number of buffer underruns
+    ///
+    /// An underrun is counted when the audio device requests samples which
+    /// are not available yet (except before the first chunk has been played).
+    pub fn underruns(&self) -> watch::Receiver<u64> {
+        self.underruns.clone()
+    }
+    /// Resume playback
+    pub fn resume(&self) -> Result<(), Error> {
+        self.stream.play()?;
+        Ok(())
+    }
+    /// Pause playback
+    pub fn pause(&self) -> Result<(), Error> {
+        self.stream.pause()?;
+        Ok(())
+    }
+}
+
+/// Input of a [`StereoAudioPlayer`] block, which acts as a [`Consumer`]
+pub struct StereoAudioPlayerInput {
+    receiver_connector: ReceiverConnector<Signal<Complex<f32>>>,
+}
+
+impl_block_trait! { Consumer<Signal<Complex<f32>>> for StereoAudioPlayerInput }
+
+/// Audio player block for stereo playback with separate inputs for the left
+/// and right channel
+///
+/// The inputs are accessible through the [`left`] and [`right`] methods.
+/// Chunks are buffered per channel, such that chunks received on the left and
+/// right input may have different lengths. Both inputs must have the sample
+/// rate given on creation, otherwise the block panics. Events from either
+/// input are passed to the registered event handlers.
+///
+/// [`left`]: StereoAudioPlayer::left
+/// [`right`]: StereoAudioPlayer::right
+pub struct StereoAudioPlayer {
+    left: StereoAudioPlayerInput,
+    right: StereoAudioPlayerInput,
+    event_handlers: EventHandlers,
+    stream: cpal::Stream,
+    underruns: watch::Receiver<u64>,
+}
+
+impl_block_trait! { EventHandling for StereoAudioPlayer }
+
+impl StereoAudioPlayer {
+    /// Create block for stereo audio playback with given `sample_rate` and
+    /// optionally requested `buffer_size`
+    pub fn new(sample_rate: f64, buffer_size: Option<usize>) -> Result<Self, Error> {
+        Self::with_device(&default_output_device(), sample_rate, buffer_size)
+    }
+    /// Create block for stereo audio playback with given `sample_rate` and
+    /// optionally requested `buffer_size` on given [`cpal::Device`]
+    pub fn with_device(
+        device: &cpal::Device,
+        sample_rate: f64,
+        buffer_size: Option<usize>,
+    ) -> Result<Self, Error> {
+        let config = stream_config(2, sample_rate, buffer_size)?;
+        let rt = tokio::runtime::Handle::current();
+        let (left_receiver, left_receiver_connector) = new_receiver::<Signal<Complex<f32>>>();
+        let (right_receiver, right_receiver_connector) = new_receiver::<Signal<Complex<f32>>>();
+        let event_handlers = EventHandlers::new();
+        let evhdl_clone = event_handlers.clone();
+        let (underruns_send, underruns) = watch::channel(0u64);
+        let err_fn = move |err| panic!("error during audio playback: {err}");
+        let mut channels = [
+            PlaybackChannel::new(left_receiver),
+            PlaybackChannel::new(right_receiver),
+        ];
+        let write_audio = move |data: &mut [f32], _: &cpal::OutputCallbackInfo| {
+            for frame in data.chunks_exact_mut(2) {
+                for (sample, channel) in frame.iter_mut().zip(channels.iter_mut()) {
+                    let Some(value) =
+                        channel.next_sample(&rt, sample_rate, &evhdl_clone, &underruns_send)
+                    else { return; };
+                    *sample = value;
+                }
+            }
+        };
+        let stream = device.build_output_stream(&config, write_audio, err_fn)?;
+        stream.play()?;
+        Ok(Self {
+            left: StereoAudioPlayerInput {
+                receiver_connector: left_receiver_connector,
+            },
+            right: StereoAudioPlayerInput {
+                receiver_connector: right_receiver_connector,
+            },
+            event_handlers,
+            stream,
+            underruns,
+        })
+    }
+    /// Input for left channel
+    pub fn left(&self) -> &StereoAudioPlayerInput {
+        &self.left
+    }
+    /// Input for right channel
+    pub fn right(&self) -> &StereoAudioPlayerInput {
+        &self.right
+    }
+    /// Number of buffer underruns (counted for each channel)
+    pub fn underruns(&self) -> watch::Receiver<u64> {
+        self.underruns.clone()
+    }
     /// Resume playback
     pub fn resume(&self) -> Result<(), Error> {
         self.stream.play()?;
@@ -183,9 +479,12 @@ impl AudioPlayer {
 }
 
 /// Audio recorder block acting as a [`Producer`]
+///
+/// Recording stops when the block is dropped.
 pub struct AudioRecorder {
     sender_connector: SenderConnector<Signal<Complex<f32>>>,
     stream: cpal::Stream,
+    overruns: watch::Receiver<u64>,
 }
 
 impl Producer<Signal<Complex<f32>>> for AudioRecorder {
@@ -200,6 +499,41 @@ impl AudioRecorder {
     pub fn new(sample_rate: f64, buffer_size: Option<usize>) -> Result<Self, Error> {
         Self::with_device(&default_input_device(), sample_rate, buffer_size)
     }
+    /// Create block for audio recording with given `sample_rate` on the
+    /// device described by [`AudioDeviceInfo`], using a buffer of
+    /// `buffer_duration` seconds and recording only the given `channel`
+    /// (where `0` is the first channel)
+    ///
+    /// Fails if the device doesn't support the given `sample_rate` or
+    /// `channel`.
+    pub fn with_device_info(
+        device: &AudioDeviceInfo,
+        sample_rate: f64,
+        buffer_duration: f64,
+        channel: u16,
+    ) -> Result<Self, Error> {
+        if !device.supports_sample_rate(sample_rate) {
+            return Err(Error::invalid_argument(
+                "sample rate not supported by audio device",
+            ));
+        }
+        let num_channels = device
+            .channel_counts
+            .iter()
+            .copied()
+            .find(|&count| count > channel)
+            .ok_or(Error::invalid_argument(
+                "channel not supported by audio device",
+            ))?;
+        let buffer_size = buffer_size_for_duration(sample_rate, buffer_duration)?;
+        Self::with_channel(
+            &device.device,
+            sample_rate,
+            Some(buffer_size),
+            num_channels,
+            channel,
+        )
+    }
     /// Create block for audio recording with given `sample_rate` and
     /// optionally requested `buffer_size` on given [`cpal::Device`]
     pub fn with_device(
@@ -207,40 +541,69 @@ impl AudioRecorder {
         sample_rate: f64,
         buffer_size: Option<usize>,
     ) -> Result<Self, Error> {
-        let config = cpal::StreamConfig {
-            channels: 1,
-            sample_rate: cpal::SampleRate(sample_rate.round() as u32),
-            buffer_size: match buffer_size {
-                None => cpal::BufferSize::Default,
-                Some(value) => cpal::BufferSize::Fixed(
-                    value
-                        .try_into()
-                        .or(Err(Error::invalid_argument("invalid buffer size")))?,
-                ),
-            },
-        };
+        Self::with_channel(device, sample_rate, buffer_size, 1, 0)
+    }
+    /// Create block for audio recording with given `sample_rate` and
+    /// optionally requested `buffer_size` on given [`cpal::Device`], where
+    /// the device is opened with `num_channels` channels of which only
+    /// `channel` is recorded (`0` being the first channel)
+    pub fn with_channel(
+        device: &cpal::Device,
+        sample_rate: f64,
+        buffer_size: Option<usize>,
+        num_channels: u16,
+        channel: u16,
+    ) -> Result<Self, Error> {
+        if channel >= num_channels {
+            return Err(Error::invalid_argument("invalid channel"));
+        }
+        let config = stream_config(num_channels, sample_rate, buffer_size)?;
         let rt = tokio::runtime::Handle::current();
         let (sender, sender_connector) = new_sender::<Signal<Complex<f32>>>();
+        let (overruns_send, overruns) = watch::channel(0u64);
         let err_fn = move |err| panic!("error during audio recording: {err}");
         let mut buf_pool = ChunkBufPool::<Complex<f32>>::new();
+        let mut started = false;
         let read_audio = move |data: &[f32], _: &cpal::InputCallbackInfo| {
-            let mut output_chunk = buf_pool.get_with_capacity(data.len());
-            for &sample in data.iter() {
-                output_chunk.push(Complex::from(sample));
+            let frames = data.chunks_exact(num_channels as usize);
+            let mut output_chunk = buf_pool.get_with_capacity(frames.len());
+            for frame in frames {
+                output_chunk.push(Complex::from(frame[channel as usize]));
             }
-            let Ok(()) = rt.block_on(sender.send(Signal::Samples {
+            let signal = Signal::Samples {
                 sample_rate,
                 chunk: output_chunk.finalize(),
-            }))
-            else { return; };
+            };
+            let reservation = match sender.try_reserve() {
+                Ok(Some(reservation)) => reservation,
+                Ok(None) => {
+                    if started {
+                        overruns_send.send_modify(|count| *count += 1);
+                    }
+                    let Ok(reservation) = rt.block_on(sender.reserve()) else { return; };
+                    reservation
+                }
+                Err(_) => return,
+            };
+            reservation.send(signal);
+            started = true;
         };
         let stream = device.build_input_stream(&config, read_audio, err_fn)?;
         stream.play()?;
         Ok(Self {
             sender_connector,
             stream,
+            overruns,
         })
     }
+    /// Number of buffer overruns
+    ///
+    /// An overrun is counted when recorded samples could not be passed on
+    /// immediately (except before the first chunk has been passed on), in
+    /// which case the audio device may drop samples.
+    pub fn overruns(&self) -> watch::Receiver<u64> {
+        self.overruns.clone()
+    }
     /// Resume recording
     pub fn resume(&self) -> Result<(), Error> {
         self.stream.play()?;
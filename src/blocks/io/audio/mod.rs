@@ -1,9 +1,13 @@
 //! Blocks accessing audio interfaces
 //!
-//! Use feature "`cpal`" for audio interface support.
+//! Use feature "`audio`" for audio interface support (through the `cpal`
+//! crate). The feature "`cpal`" is an alias for "`audio`".
 
-#[cfg(feature = "cpal")]
+#[cfg(feature = "audio")]
 pub mod cpal;
 
+#[cfg(feature = "audio")]
+pub use self::cpal::{list_input_devices, list_output_devices, AudioDeviceInfo};
+
 #[cfg(test)]
 mod tests {}
@@ -0,0 +1,289 @@
+//! Writing spectrograms (waterfalls) to PNG image files
+//!
+//! The PNG files are written without compression, so no further
+//! dependencies are required.
+
+use crate::blocks::analysis::SpectrumFrame;
+use crate::numbers::*;
+
+use tokio::fs::File;
+use tokio::io::AsyncWriteExt as _;
+use tokio::select;
+use tokio::sync::watch;
+use tokio::task::{spawn, JoinHandle};
+
+use std::io;
+use std::path::Path;
+
+/// Mapping from normalized values to colors
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Colormap {
+    /// Black (lowest value) to white (highest value)
+    Grayscale,
+    /// Perceptually uniform colormap from dark blue over green to yellow
+    Viridis,
+}
+
+impl Colormap {
+    /// RGB color of a `value` in range `0.0..=1.0` (values outside the range
+    /// are clamped)
+    pub fn rgb(self, value: f32) -> [u8; 3] {
+        let t = value.clamp(0.0, 1.0);
+        let to_u8 = |x: f32| (x.clamp(0.0, 1.0) * 255.0).round() as u8;
+        match self {
+            Colormap::Grayscale => [to_u8(t); 3],
+            Colormap::Viridis => {
+                // polynomial approximation of the viridis colormap
+                const COEFFS: [[f32; 3]; 7] = [
+                    [0.277_727_33, 0.005_407_345, 0.334_099_8],
+                    [0.105_093_04, 1.404_613_5, 1.384_590_2],
+                    [-0.330_861_83, 0.214_847_56, 0.095_095_16],
+                    [-4.634_230_5, -5.799_101, -19.332_441],
+                    [6.228_27, 14.179_933, 56.690_55],
+                    [4.776_385, -13.745_145, -65.353_035],
+                    [-5.435_456, 4.645_852_6, 26.312_435],
+                ];
+                let mut rgb = [0.0f32; 3];
+                for coeffs in COEFFS.iter().rev() {
+                    for (color, coeff) in rgb.iter_mut().zip(coeffs) {
+                        *color = *color * t + coeff;
+                    }
+                }
+                rgb.map(to_u8)
+            }
+        }
+    }
+}
+
+/// Sink which records [`SpectrumFrame`]s (e.g. from a [`Spectrum`] block)
+/// and writes them as a spectrogram to a PNG file
+///
+/// Each frame becomes a row of the image (oldest frame at the top), and each
+/// bin a pixel (lowest frequency at the left), so the image width is the FFT
+/// size. The powers are converted to decibels and mapped to colors using a
+/// [`Colormap`], where the strongest bin of the whole image corresponds to
+/// the highest value and values more than `dynamic_range_db` below are
+/// clipped.
+///
+/// At most `max_rows` frames are recorded; later frames are ignored, as are
+/// frames with a different number of bins than the first frame. Frames are
+/// only recorded while the sink is waiting, so frames replaced in the
+/// [`watch`] channel before being seen are missed (use the `max_frame_rate`
+/// argument of the `Spectrum` block to limit the frame rate).
+///
+/// The file is written when [`ImageSink::finalize`] is called.
+///
+/// [`Spectrum`]: crate::blocks::analysis::Spectrum
+pub struct ImageSink {
+    rows: watch::Receiver<usize>,
+    drop_watch: watch::Sender<()>,
+    join_handle: JoinHandle<io::Result<()>>,
+}
+
+impl ImageSink {
+    /// Create image file and start recording `frames`
+    pub fn new<P, Flt>(
+        path: P,
+        mut frames: watch::Receiver<SpectrumFrame<Flt>>,
+        max_rows: usize,
+        dynamic_range_db: f64,
+        colormap: Colormap,
+    ) -> io::Result<Self>
+    where
+        P: AsRef<Path>,
+        Flt: Float,
+    {
+        assert!(dynamic_range_db > 0.0, "dynamic range must be positive");
+        let file = File::from_std(std::fs::File::create(path)?);
+        let (rows_send, rows) = watch::channel(0);
+        let (drop_watch, mut drop_watch_recv) = watch::channel(());
+        frames.borrow_and_update();
+        let join_handle = spawn(async move {
+            let mut width: Option<usize> = None;
+            let mut values: Vec<f32> = Vec::new();
+            loop {
+                select! {
+                    result = frames.changed() => {
+                        if result.is_err() {
+                            break;
+                        }
+                    }
+                    _ = drop_watch_recv.changed() => break,
+                }
+                if *rows_send.borrow() >= max_rows {
+                    continue;
+                }
+                let frame = frames.borrow_and_update().clone();
+                if *width.get_or_insert(frame.bins.len()) != frame.bins.len() {
+                    continue;
+                }
+                values.extend(
+                    frame
+                        .bins
+                        .iter()
+                        .map(|&power| to_db(power.to_f64().unwrap()) as f32),
+                );
+                rows_send.send_modify(|rows| *rows += 1);
+            }
+            let width = width.unwrap_or(0);
+            let height = *rows_send.borrow();
+            let peak = values
+                .iter()
+                .copied()
+                .filter(|x| x.is_finite())
+                .fold(f32::NEG_INFINITY, f32::max);
+            let floor = peak - dynamic_range_db as f32;
+            let pixels: Vec<u8> = values
+                .iter()
+                .flat_map(|&db| colormap.rgb((db - floor) / dynamic_range_db as f32))
+                .collect();
+            let mut file = file;
+            file.write_all(&encode_png(width, height, &pixels)).await?;
+            file.flush().await?;
+            Ok(())
+        });
+        Ok(Self {
+            rows,
+            drop_watch,
+            join_handle,
+        })
+    }
+    /// Get [`watch::Receiver`] of number of recorded rows
+    pub fn rows(&self) -> watch::Receiver<usize> {
+        self.rows.clone()
+    }
+    /// Stop recording and wait until the image has been written
+    pub async fn finalize(self) -> io::Result<()> {
+        let Self {
+            drop_watch,
+            join_handle,
+            ..
+        } = self;
+        drop(drop_watch);
+        join_handle.await.expect("image writing task panicked")
+    }
+}
+
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc: u32 = !0;
+    for &byte in data {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            crc = (crc >> 1) ^ (0xEDB88320 & (crc & 1).wrapping_neg());
+        }
+    }
+    !crc
+}
+
+fn adler32(data: &[u8]) -> u32 {
+    let (mut a, mut b): (u32, u32) = (1, 0);
+    for chunk in data.chunks(5552) {
+        for &byte in chunk {
+            a += byte as u32;
+            b += a;
+        }
+        a %= 65521;
+        b %= 65521;
+    }
+    (b << 16) | a
+}
+
+/// Encode 8 bit RGB `pixels` (row by row) as PNG
+fn encode_png(width: usize, height: usize, pixels: &[u8]) -> Vec<u8> {
+    fn push_chunk(png: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+        png.extend_from_slice(&(data.len() as u32).to_be_bytes());
+        let start = png.len();
+        png.extend_from_slice(kind);
+        png.extend_from_slice(data);
+        let crc = crc32(&png[start..]);
+        png.extend_from_slice(&crc.to_be_bytes());
+    }
+    let mut scanlines: Vec<u8> = Vec::with_capacity((3 * width + 1) * height);
+    for row in pixels.chunks(3 * width.max(1)) {
+        scanlines.push(0); // filter type "none"
+        scanlines.extend_from_slice(row);
+    }
+    // zlib stream with uncompressed ("stored") deflate blocks
+    let mut zlib: Vec<u8> = vec![0x78, 0x01];
+    let mut blocks = scanlines.chunks(65535).peekable();
+    if blocks.peek().is_none() {
+        zlib.extend_from_slice(&[1, 0, 0, 0xff, 0xff]);
+    }
+    while let Some(block) = blocks.next() {
+        zlib.push(blocks.peek().is_none() as u8);
+        zlib.extend_from_slice(&(block.len() as u16).to_le_bytes());
+        zlib.extend_from_slice(&(!(block.len() as u16)).to_le_bytes());
+        zlib.extend_from_slice(block);
+    }
+    zlib.extend_from_slice(&adler32(&scanlines).to_be_bytes());
+    let mut header: Vec<u8> = Vec::with_capacity(13);
+    header.extend_from_slice(&(width as u32).to_be_bytes());
+    header.extend_from_slice(&(height as u32).to_be_bytes());
+    header.extend_from_slice(&[8, 2, 0, 0, 0]); // 8 bit RGB
+    let mut png: Vec<u8> = b"\x89PNG\r\n\x1a\n".to_vec();
+    push_chunk(&mut png, b"IHDR", &header);
+    push_chunk(&mut png, b"IDAT", &zlib);
+    push_chunk(&mut png, b"IEND", &[]);
+    png
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    #[test]
+    fn test_checksums() {
+        assert_eq!(crc32(b"123456789"), 0xCBF43926);
+        assert_eq!(adler32(b"Wikipedia"), 0x11E60398);
+    }
+    #[test]
+    fn test_colormap() {
+        assert_eq!(Colormap::Grayscale.rgb(0.0), [0, 0, 0]);
+        assert_eq!(Colormap::Grayscale.rgb(2.0), [255, 255, 255]);
+        let [r, g, b] = Colormap::Viridis.rgb(0.0);
+        assert!(r.abs_diff(68) < 4 && g.abs_diff(1) < 4 && b.abs_diff(84) < 4);
+        let [r, g, b] = Colormap::Viridis.rgb(1.0);
+        assert!(r.abs_diff(253) < 4 && g.abs_diff(231) < 4 && b.abs_diff(37) < 6);
+    }
+    #[tokio::test]
+    async fn test_image_sink() {
+        let path = std::env::temp_dir().join("radiorust_test_image_sink.png");
+        let frame = |bins: Vec<f32>| SpectrumFrame {
+            sample_rate: 48000.0,
+            center_frequency: None,
+            bins: Arc::from(bins),
+        };
+        let (frame_send, frames) = watch::channel(frame(vec![0.0; 4]));
+        let sink = ImageSink::new(&path, frames, 2, 20.0, Colormap::Grayscale).unwrap();
+        let mut rows = sink.rows();
+        // frames which are ignored (wrong number of bins or too many rows)
+        // don't yield a row, and may also be replaced before being seen
+        for (bins, recorded) in [
+            (vec![1.0, 0.1, 0.01, 0.0], true),
+            (vec![0.0; 3], false),
+            (vec![0.1; 4], true),
+            (vec![1.0; 4], false),
+        ] {
+            frame_send.send_replace(frame(bins));
+            if recorded {
+                rows.changed().await.unwrap();
+            }
+        }
+        assert_eq!(*rows.borrow_and_update(), 2);
+        sink.finalize().await.unwrap();
+        let png = std::fs::read(&path).unwrap();
+        std::fs::remove_file(&path).ok();
+        assert_eq!(&png[0..8], b"\x89PNG\r\n\x1a\n");
+        assert_eq!(&png[12..16], b"IHDR");
+        assert_eq!(&png[16..24], &[0, 0, 0, 4, 0, 0, 0, 2]);
+        assert_eq!(&png[png.len() - 8..png.len() - 4], b"IEND");
+        let idat = 8 + 25;
+        assert_eq!(&png[idat + 4..idat + 8], b"IDAT");
+        let scanlines = &png[idat + 8 + 7..];
+        assert_eq!(
+            &scanlines[0..13],
+            &[0, 255, 255, 255, 128, 128, 128, 0, 0, 0, 0, 0, 0]
+        );
+        assert_eq!(scanlines[13..26].iter().filter(|&&x| x == 128).count(), 12);
+    }
+}
@@ -1,12 +1,22 @@
-//! External sources and sinks (plus [`Silence`] and [`Blackhole`])
+//! External sources and sinks (plus [`Silence`], [`Blackhole`], and
+//! [`CountingSink`])
 //!
 //! The [`audio`] and [`rf`] modules contain blocks that allow accessing
-//! hardware audio or radio interfaces.
+//! hardware audio or radio interfaces. The [`raw`] module contains blocks
+//! for reading and writing files. The [`testsrc`] module contains blocks
+//! which synthesize signals for testing. With the `image` feature, the
+//! `image` module allows writing spectrograms to image files. The
+//! [`thread`] module provides scheduling settings for I/O threads.
 //!
 //! **Note:** Blocks in this module will stop working when dropped.
 
 pub mod audio;
+#[cfg(feature = "image")]
+pub mod image;
+pub mod raw;
 pub mod rf;
+pub mod testsrc;
+pub mod thread;
 
 use crate::bufferpool::*;
 use crate::flow::*;
@@ -129,3 +139,85 @@ where
         }
     }
 }
+
+/// Alias for [`Blackhole`], a [`Consumer`] which discards all data
+pub type NullSink<T> = Blackhole<T>;
+
+/// [`Consumer`] which ignores all received [`Signal::Samples`] but counts the
+/// received samples and chunks (and allows [`EventHandling`])
+///
+/// This can be used to measure throughput of a chain of blocks.
+pub struct CountingSink<T> {
+    receiver_connector: ReceiverConnector<Signal<T>>,
+    event_handlers: EventHandlers,
+    samples: watch::Receiver<u64>,
+    chunks: watch::Receiver<u64>,
+    _drop_watch: watch::Sender<()>,
+}
+
+impl_block_trait! { <T> Consumer<Signal<T>> for CountingSink<T> }
+impl_block_trait! { <T> EventHandling for CountingSink<T> }
+
+impl<T> CountingSink<T>
+where
+    T: Clone + Send + Sync + 'static,
+{
+    /// Create new `CountingSink`
+    pub fn new() -> Self {
+        let (mut receiver, receiver_connector) = new_receiver::<Signal<T>>();
+        let (drop_watch_send, mut drop_watch_recv) = watch::channel(());
+        let (samples_send, samples) = watch::channel(0u64);
+        let (chunks_send, chunks) = watch::channel(0u64);
+        let event_handlers = EventHandlers::new();
+        let evhdl_clone = event_handlers.clone();
+        spawn(async move {
+            loop {
+                select! {
+                    _ = drop_watch_recv.changed() => return,
+                    result = receiver.recv() => match result {
+                        Ok(Signal::Samples { chunk, .. }) => {
+                            samples_send.send_modify(|count| *count += chunk.len() as u64);
+                            chunks_send.send_modify(|count| *count += 1);
+                        }
+                        Ok(Signal::Event(event)) => evhdl_clone.invoke(&event),
+                        Err(_) => return,
+                    },
+                }
+            }
+        });
+        Self {
+            receiver_connector,
+            event_handlers,
+            samples,
+            chunks,
+            _drop_watch: drop_watch_send,
+        }
+    }
+    /// Total number of received samples
+    pub fn samples(&self) -> watch::Receiver<u64> {
+        self.samples.clone()
+    }
+    /// Total number of received chunks
+    pub fn chunks(&self) -> watch::Receiver<u64> {
+        self.chunks.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[tokio::test]
+    async fn test_counting_sink() {
+        let silence = Silence::<f32>::new(16, 48000.0);
+        let sink = CountingSink::<f32>::new();
+        sink.feed_from(&silence);
+        let mut chunks = sink.chunks();
+        while *chunks.borrow_and_update() < 10 {
+            chunks.changed().await.unwrap();
+        }
+        let chunks = *sink.chunks().borrow();
+        let samples = *sink.samples().borrow();
+        assert_eq!(samples % 16, 0);
+        assert!(samples >= 16 * chunks);
+    }
+}
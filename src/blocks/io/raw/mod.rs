@@ -0,0 +1,2697 @@
+//! Reading and writing sample data from and to files or network sockets
+//!
+//! Samples are read as [`Complex<f32>`]. Where a file contains information
+//! about the sample rate, it is used for the emitted [`Signal::Samples`].
+//! Files with two channels are interpreted as interleaved I/Q data, while
+//! files with one channel result in samples with an imaginary part of zero.
+//!
+//! Supported file types are raw I/Q data in various [formats] (see
+//! [`RawSource`] and [`RawSink`], which also support a planar
+//! [layout]), WAV files (see [`WavSource`] and
+//! [`WavSink`]), and [SigMF] recordings (see [`SigMfSource`] and
+//! [`SigMfSink`]). For quick experiments, [`CaptureSink`] writes raw I/Q data
+//! with a JSON sidecar describing the capture, which [`CaptureSource`] reads
+//! back. Samples may also be transferred over TCP (see
+//! [`TcpSource`] and [`TcpSink`]) or UDP (see [`UdpSource`] and
+//! [`UdpSink`]).
+//!
+//! [formats]: SampleFormat
+//! [layout]: IqLayout
+//! [SigMF]: https://sigmf.org/
+
+use crate::bufferpool::*;
+use crate::flow::*;
+use crate::impl_block_trait;
+use crate::numbers::*;
+use crate::signal::*;
+
+use tokio::fs::File;
+use tokio::io::{AsyncReadExt as _, AsyncSeekExt as _, AsyncWriteExt as _, BufWriter};
+use tokio::net::{TcpListener, TcpStream, UdpSocket};
+use tokio::select;
+use tokio::sync::watch;
+use tokio::task::{spawn, JoinHandle};
+use tokio::time::sleep;
+
+use std::ffi::OsString;
+use std::io::{self, Read as _, Seek as _, SeekFrom};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, ToSocketAddrs};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+mod json;
+
+/// Types used for [`Signal::Event`]
+pub mod events {
+    use super::*;
+    /// Sent by [`RawSource`], [`WavSource`], [`SigMfSource`], and
+    /// [`CaptureSource`] when the end of the file has been reached
+    ///
+    /// This event requests a [flush], so that downstream blocks emit the tail
+    /// of their output.
+    ///
+    /// [flush]: Event::is_flush
+    #[derive(Clone, Debug)]
+    pub struct EndOfFile;
+    impl Event for EndOfFile {
+        fn is_flush(&self) -> bool {
+            true
+        }
+        fn as_any(&self) -> &(dyn std::any::Any + Send + Sync) {
+            self
+        }
+    }
+    /// Sent by [`TcpSource`] when the connection has been lost
+    ///
+    /// This event is an [interruption].
+    ///
+    /// [interruption]: Event::is_interrupt
+    #[derive(Clone, Debug)]
+    pub struct ConnectionLost;
+    impl Event for ConnectionLost {
+        fn is_interrupt(&self) -> bool {
+            true
+        }
+        fn as_any(&self) -> &(dyn std::any::Any + Send + Sync) {
+            self
+        }
+    }
+    /// Sent by [`UdpSource`] when packets have been lost
+    ///
+    /// This event is an [interruption].
+    ///
+    /// [interruption]: Event::is_interrupt
+    #[derive(Clone, Debug)]
+    pub struct Discontinuity {
+        /// Number of lost packets
+        pub lost_packets: u32,
+    }
+    impl Event for Discontinuity {
+        fn is_interrupt(&self) -> bool {
+            true
+        }
+        fn as_any(&self) -> &(dyn std::any::Any + Send + Sync) {
+            self
+        }
+    }
+    /// Sent by [`RawSource`], [`WavSource`], and [`SigMfSource`] when
+    /// reading continues at a different position after a seek (or after
+    /// looping)
+    ///
+    /// This event is an [interruption].
+    ///
+    /// [interruption]: Event::is_interrupt
+    #[derive(Clone, Debug)]
+    pub struct Repositioned {
+        /// Index of the next sample
+        pub position: u64,
+    }
+    impl Event for Repositioned {
+        fn is_interrupt(&self) -> bool {
+            true
+        }
+        fn as_any(&self) -> &(dyn std::any::Any + Send + Sync) {
+            self
+        }
+    }
+}
+
+use events::*;
+
+/// Sample format of raw sample data
+///
+/// Each complex sample consists of two values (real and imaginary part)
+/// using the respective format.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum SampleFormat {
+    /// 8 bit unsigned integer with an offset of 127.5 (as used by RTL-SDR)
+    U8,
+    /// 8 bit signed integer
+    I8,
+    /// 16 bit signed integer, little endian
+    I16Le,
+    /// 16 bit signed integer, big endian
+    I16Be,
+    /// 32 bit IEEE floating point, little endian
+    F32Le,
+    /// 32 bit IEEE floating point, big endian
+    F32Be,
+}
+
+impl SampleFormat {
+    /// Number of bytes used for each real value
+    pub fn bytes_per_value(self) -> usize {
+        match self {
+            SampleFormat::U8 | SampleFormat::I8 => 1,
+            SampleFormat::I16Le | SampleFormat::I16Be => 2,
+            SampleFormat::F32Le | SampleFormat::F32Be => 4,
+        }
+    }
+    fn decode(self, bytes: &[u8]) -> f32 {
+        match self {
+            SampleFormat::U8 => (bytes[0] as f32 - 127.5) / 127.5,
+            SampleFormat::I8 => bytes[0] as i8 as f32 / 128.0,
+            SampleFormat::I16Le => i16::from_le_bytes([bytes[0], bytes[1]]) as f32 / 32768.0,
+            SampleFormat::I16Be => i16::from_be_bytes([bytes[0], bytes[1]]) as f32 / 32768.0,
+            SampleFormat::F32Le => f32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]),
+            SampleFormat::F32Be => f32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]),
+        }
+    }
+    fn encode(self, value: f32, buf: &mut Vec<u8>) {
+        let to_i16 = |value: f32| (value * 32768.0).round().clamp(-32768.0, 32767.0) as i16;
+        match self {
+            SampleFormat::U8 => buf.push((value * 127.5 + 127.5).round().clamp(0.0, 255.0) as u8),
+            SampleFormat::I8 => buf.push((value * 128.0).round().clamp(-128.0, 127.0) as i8 as u8),
+            SampleFormat::I16Le => buf.extend_from_slice(&to_i16(value).to_le_bytes()),
+            SampleFormat::I16Be => buf.extend_from_slice(&to_i16(value).to_be_bytes()),
+            SampleFormat::F32Le => buf.extend_from_slice(&value.to_le_bytes()),
+            SampleFormat::F32Be => buf.extend_from_slice(&value.to_be_bytes()),
+        }
+    }
+    fn sigmf_suffix(self) -> &'static str {
+        match self {
+            SampleFormat::U8 => "u8",
+            SampleFormat::I8 => "i8",
+            SampleFormat::I16Le => "i16_le",
+            SampleFormat::I16Be => "i16_be",
+            SampleFormat::F32Le => "f32_le",
+            SampleFormat::F32Be => "f32_be",
+        }
+    }
+    fn from_sigmf_suffix(suffix: &str) -> Option<Self> {
+        match suffix {
+            "u8" => Some(SampleFormat::U8),
+            "i8" => Some(SampleFormat::I8),
+            "i16_le" => Some(SampleFormat::I16Le),
+            "i16_be" => Some(SampleFormat::I16Be),
+            "f32_le" => Some(SampleFormat::F32Le),
+            "f32_be" => Some(SampleFormat::F32Be),
+            _ => None,
+        }
+    }
+}
+
+/// Layout of I/Q data in raw files
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum IqLayout {
+    /// Real and imaginary part of each sample are stored next to each other
+    Interleaved,
+    /// Samples are stored in blocks, where each block consists of the number
+    /// of samples in the block (32 bit unsigned integer, little endian),
+    /// followed by the real parts of all samples in the block, followed by
+    /// the imaginary parts of all samples in the block
+    ///
+    /// [`RawSink`] writes one block for each received chunk.
+    Planar,
+}
+
+/// Sample format of a WAV file
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum WavFormat {
+    /// 16 bit signed integer PCM
+    Pcm16,
+    /// 32 bit IEEE floating point
+    Float32,
+}
+
+impl WavFormat {
+    fn format_tag(self) -> u16 {
+        match self {
+            WavFormat::Pcm16 => 1,
+            WavFormat::Float32 => 3,
+        }
+    }
+    fn sample_format(self) -> SampleFormat {
+        match self {
+            WavFormat::Pcm16 => SampleFormat::I16Le,
+            WavFormat::Float32 => SampleFormat::F32Le,
+        }
+    }
+}
+
+fn invalid_data(msg: &'static str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg)
+}
+
+fn wav_header(format: WavFormat, channels: u16, sample_rate: u32, data_len: u32) -> Vec<u8> {
+    let value_len = format.sample_format().bytes_per_value() as u16;
+    let block_align = channels * value_len;
+    let mut header = Vec::with_capacity(44);
+    header.extend_from_slice(b"RIFF");
+    header.extend_from_slice(&data_len.saturating_add(36).to_le_bytes());
+    header.extend_from_slice(b"WAVE");
+    header.extend_from_slice(b"fmt ");
+    header.extend_from_slice(&16u32.to_le_bytes());
+    header.extend_from_slice(&format.format_tag().to_le_bytes());
+    header.extend_from_slice(&channels.to_le_bytes());
+    header.extend_from_slice(&sample_rate.to_le_bytes());
+    header.extend_from_slice(&(sample_rate * block_align as u32).to_le_bytes());
+    header.extend_from_slice(&block_align.to_le_bytes());
+    header.extend_from_slice(&(value_len * 8).to_le_bytes());
+    header.extend_from_slice(b"data");
+    header.extend_from_slice(&data_len.to_le_bytes());
+    header
+}
+
+/// Location and format of interleaved sample data within a file
+struct DataLayout {
+    /// Center frequency which is announced with a [`CenterFrequency`] event
+    /// before the first samples and after each seek, if known
+    frequency: Option<f64>,
+    format: SampleFormat,
+    channels: usize,
+    /// Offset of the first sample in bytes
+    start: u64,
+    /// Length of the sample data in bytes (or `None` until the end of the
+    /// file)
+    len: Option<u64>,
+}
+
+/// Control channels of a task reading from a file, which end the task when
+/// dropped
+struct ReaderControl {
+    seek: watch::Sender<u64>,
+    position: watch::Receiver<u64>,
+    _drop_watch: watch::Sender<()>,
+}
+
+impl ReaderControl {
+    fn new() -> (Self, watch::Receiver<u64>, watch::Sender<u64>, watch::Receiver<()>) {
+        let (seek, seek_recv) = watch::channel(0);
+        let (position_send, position) = watch::channel(0);
+        let (drop_watch, drop_watch_recv) = watch::channel(());
+        let control = Self {
+            seek,
+            position,
+            _drop_watch: drop_watch,
+        };
+        (control, seek_recv, position_send, drop_watch_recv)
+    }
+    fn seek(&self, sample_index: u64) {
+        self.seek.send_replace(sample_index);
+    }
+    fn position(&self) -> u64 {
+        *self.position.borrow()
+    }
+}
+
+/// Send `signal` unless `drop_watch` signals that the block has been dropped
+///
+/// Returns false if the task should end.
+async fn send_or_drop(
+    sender: &Sender<Signal<Complex<f32>>>,
+    drop_watch: &mut watch::Receiver<()>,
+    signal: Signal<Complex<f32>>,
+) -> bool {
+    select! {
+        _ = drop_watch.changed() => false,
+        result = sender.send(signal) => result.is_ok(),
+    }
+}
+
+/// Wait until a seek is requested and return the requested sample index
+///
+/// Returns `None` if the task should end.
+async fn wait_for_seek(
+    seek: &mut watch::Receiver<u64>,
+    drop_watch: &mut watch::Receiver<()>,
+) -> Option<u64> {
+    select! {
+        _ = drop_watch.changed() => None,
+        result = seek.changed() => match result {
+            Ok(()) => Some(*seek.borrow_and_update()),
+            Err(_) => None,
+        },
+    }
+}
+
+/// Spawn task reading sample data from `file`, which must be positioned at
+/// the start of the data
+///
+/// If `looping` is true, the task seeks back to the start of the data after
+/// each [`EndOfFile`] event. Otherwise the task waits for a seek request
+/// after the end of the file.
+fn spawn_reader(
+    mut file: File,
+    data: DataLayout,
+    chunk_len: usize,
+    sample_rate: f64,
+    looping: bool,
+) -> (SenderConnector<Signal<Complex<f32>>>, ReaderControl) {
+    let (sender, sender_connector) = new_sender::<Signal<Complex<f32>>>();
+    let (control, mut seek_recv, position_send, mut drop_watch_recv) = ReaderControl::new();
+    let DataLayout {
+        format,
+        channels,
+        start: data_start,
+        len: data_len,
+        frequency,
+    } = data;
+    let value_len = format.bytes_per_value();
+    let block_align = value_len * channels;
+    let mut buf_pool = ChunkBufPool::<Complex<f32>>::new();
+    spawn(async move {
+        let mut remaining = data_len;
+        let mut position: u64 = 0;
+        let mut seek_to: Option<u64> = None;
+        let mut bytes = vec![0u8; chunk_len * block_align];
+        // avoids endless events when looping over a file without samples
+        let mut read_since_start = false;
+        let mut announce_frequency = true;
+        loop {
+            if seek_recv.has_changed().unwrap_or(false) {
+                seek_to = Some(*seek_recv.borrow_and_update());
+            }
+            if let Some(target) = seek_to.take() {
+                let len = match data_len {
+                    Some(len) => len,
+                    None => match file.metadata().await {
+                        Ok(metadata) => metadata.len().saturating_sub(data_start),
+                        Err(err) => panic!("error reading file metadata: {err}"),
+                    },
+                };
+                position = target.min(len / block_align as u64);
+                let offset = position * block_align as u64;
+                if let Err(err) = file.seek(SeekFrom::Start(data_start + offset)).await {
+                    panic!("error seeking file: {err}");
+                }
+                remaining = data_len.map(|len| len - offset);
+                position_send.send_replace(position);
+                let event = Signal::new_event(Repositioned { position });
+                if !send_or_drop(&sender, &mut drop_watch_recv, event).await {
+                    return;
+                }
+                announce_frequency = true;
+            }
+            if let (true, Some(frequency)) = (announce_frequency, frequency) {
+                let event = Signal::new_event(CenterFrequency(frequency));
+                if !send_or_drop(&sender, &mut drop_watch_recv, event).await {
+                    return;
+                }
+            }
+            announce_frequency = false;
+            let mut max_len = bytes.len();
+            if let Some(remaining) = remaining {
+                max_len = max_len.min(remaining as usize);
+            }
+            let filled = match read_full(&mut file, &mut bytes[0..max_len]).await {
+                Ok(n) => n,
+                Err(err) => panic!("error reading file: {err}"),
+            };
+            if let Some(remaining) = remaining.as_mut() {
+                *remaining -= filled as u64;
+            }
+            let frames = filled / block_align;
+            let signal = match frames {
+                0 => Signal::Event(Arc::new(EndOfFile)),
+                _ => {
+                    let mut output_chunk = buf_pool.get_with_capacity(frames);
+                    for frame in bytes[0..frames * block_align].chunks_exact(block_align) {
+                        let re = format.decode(&frame[0..value_len]);
+                        let im = match channels {
+                            2 => format.decode(&frame[value_len..]),
+                            _ => 0.0,
+                        };
+                        output_chunk.push(Complex::new(re, im));
+                    }
+                    position += frames as u64;
+                    position_send.send_replace(position);
+                    Signal::Samples {
+                        sample_rate,
+                        chunk: output_chunk.finalize(),
+                    }
+                }
+            };
+            if !send_or_drop(&sender, &mut drop_watch_recv, signal).await {
+                return;
+            }
+            if frames > 0 {
+                read_since_start = true;
+                continue;
+            }
+            if looping && read_since_start {
+                seek_to = Some(0);
+                read_since_start = false;
+                continue;
+            }
+            let Some(target) = wait_for_seek(&mut seek_recv, &mut drop_watch_recv).await
+            else { return; };
+            seek_to = Some(target);
+        }
+    });
+    (sender_connector, control)
+}
+
+/// Read into `bytes` until it is full or the end of the file is reached and
+/// return the number of bytes read
+async fn read_full(file: &mut File, bytes: &mut [u8]) -> io::Result<usize> {
+    let mut filled = 0;
+    while filled < bytes.len() {
+        match file.read(&mut bytes[filled..]).await? {
+            0 => break,
+            n => filled += n,
+        }
+    }
+    Ok(filled)
+}
+
+/// Spawn task reading planar sample data from `file`, which must be
+/// positioned at `data_start`
+///
+/// If `looping` is true, the task seeks back to `data_start` after each
+/// [`EndOfFile`] event. Otherwise the task waits for a seek request after
+/// the end of the file. Seeking requires skipping over the headers of all
+/// preceding blocks.
+fn spawn_planar_reader(
+    mut file: File,
+    format: SampleFormat,
+    data_start: u64,
+    chunk_len: usize,
+    sample_rate: f64,
+    looping: bool,
+) -> (SenderConnector<Signal<Complex<f32>>>, ReaderControl) {
+    let (sender, sender_connector) = new_sender::<Signal<Complex<f32>>>();
+    let (control, mut seek_recv, position_send, mut drop_watch_recv) = ReaderControl::new();
+    let value_len = format.bytes_per_value();
+    let mut buf_pool = ChunkBufPool::<Complex<f32>>::new();
+    spawn(async move {
+        let mut bytes: Vec<u8> = Vec::new();
+        let mut position: u64 = 0;
+        let mut seek_to: Option<u64> = None;
+        // number of samples to drop from the next block after seeking
+        let mut skip: usize = 0;
+        // avoids endless events when looping over a file without samples
+        let mut read_since_start = false;
+        loop {
+            if seek_recv.has_changed().unwrap_or(false) {
+                seek_to = Some(*seek_recv.borrow_and_update());
+            }
+            if let Some(target) = seek_to.take() {
+                let result = async {
+                    file.seek(SeekFrom::Start(data_start)).await?;
+                    position = 0;
+                    skip = 0;
+                    loop {
+                        let mut block_len = [0u8; 4];
+                        if read_full(&mut file, &mut block_len).await? < 4 {
+                            break;
+                        }
+                        let block_len = u32::from_le_bytes(block_len) as u64;
+                        if position + block_len > target {
+                            file.seek(SeekFrom::Current(-4)).await?;
+                            skip = (target - position) as usize;
+                            position = target;
+                            break;
+                        }
+                        let block_bytes = 2 * block_len * value_len as u64;
+                        file.seek(SeekFrom::Current(block_bytes as i64)).await?;
+                        position += block_len;
+                    }
+                    Ok::<(), io::Error>(())
+                }
+                .await;
+                if let Err(err) = result {
+                    panic!("error seeking file: {err}");
+                }
+                position_send.send_replace(position);
+                let event = Signal::new_event(Repositioned { position });
+                if !send_or_drop(&sender, &mut drop_watch_recv, event).await {
+                    return;
+                }
+            }
+            let mut block_len = [0u8; 4];
+            let block_len = match read_full(&mut file, &mut block_len).await {
+                Ok(4) => u32::from_le_bytes(block_len) as usize,
+                Ok(_) => 0,
+                Err(err) => panic!("error reading file: {err}"),
+            };
+            bytes.resize(2 * block_len * value_len, 0);
+            let frames = match read_full(&mut file, &mut bytes).await {
+                Ok(n) if n == bytes.len() => block_len,
+                Ok(_) => 0,
+                Err(err) => panic!("error reading file: {err}"),
+            };
+            read_since_start |= frames > 0;
+            let (re_bytes, im_bytes) = bytes.split_at(frames * value_len);
+            let mut values = re_bytes
+                .chunks_exact(value_len)
+                .zip(im_bytes.chunks_exact(value_len))
+                .skip(skip);
+            let mut remaining = frames.saturating_sub(skip);
+            skip = 0;
+            while remaining > 0 {
+                let len = remaining.min(chunk_len);
+                remaining -= len;
+                let mut output_chunk = buf_pool.get_with_capacity(len);
+                for (re, im) in values.by_ref().take(len) {
+                    output_chunk.push(Complex::new(format.decode(re), format.decode(im)));
+                }
+                position += len as u64;
+                position_send.send_replace(position);
+                let signal = Signal::Samples {
+                    sample_rate,
+                    chunk: output_chunk.finalize(),
+                };
+                if !send_or_drop(&sender, &mut drop_watch_recv, signal).await {
+                    return;
+                }
+            }
+            if frames > 0 {
+                continue;
+            }
+            let signal = Signal::Event(Arc::new(EndOfFile));
+            if !send_or_drop(&sender, &mut drop_watch_recv, signal).await {
+                return;
+            }
+            if looping && read_since_start {
+                seek_to = Some(0);
+                read_since_start = false;
+                continue;
+            }
+            let Some(target) = wait_for_seek(&mut seek_recv, &mut drop_watch_recv).await
+            else { return; };
+            seek_to = Some(target);
+        }
+    });
+    (sender_connector, control)
+}
+
+#[derive(Default)]
+struct WriteSummary {
+    sample_rate: Option<f64>,
+    /// Center frequency of the first samples, if announced by a
+    /// [`CenterFrequency`] event
+    frequency: Option<f64>,
+    start_time: Option<SystemTime>,
+    sample_count: u64,
+}
+
+async fn write_samples(
+    receiver: &mut Receiver<Signal<Complex<f32>>>,
+    drop_watch_recv: &mut watch::Receiver<()>,
+    writer: &mut BufWriter<File>,
+    format: SampleFormat,
+    layout: IqLayout,
+    scale: &watch::Receiver<f32>,
+    summary: &mut WriteSummary,
+) -> io::Result<()> {
+    let mut bytes: Vec<u8> = Vec::new();
+    let mut timestamps = TimestampTracker::new();
+    loop {
+        let signal = select! {
+            _ = drop_watch_recv.changed() => return Ok(()),
+            result = receiver.recv() => match result {
+                Ok(signal) => signal,
+                Err(_) => return Ok(()),
+            },
+        };
+        let timestamp = timestamps.update(&signal);
+        match signal {
+            Signal::Samples { sample_rate, chunk } => {
+                match summary.sample_rate {
+                    None => {
+                        summary.sample_rate = Some(sample_rate);
+                        summary.start_time = Some(match timestamp {
+                            Some(nanos) if nanos >= 0 => {
+                                UNIX_EPOCH + Duration::from_nanos(nanos as u64)
+                            }
+                            _ => SystemTime::now(),
+                        });
+                    }
+                    Some(file_sample_rate) if file_sample_rate != sample_rate => {
+                        return Err(invalid_data("sample rate changed while writing file"));
+                    }
+                    _ => (),
+                }
+                bytes.clear();
+                let scale = *scale.borrow();
+                match layout {
+                    IqLayout::Interleaved => {
+                        for sample in chunk.iter() {
+                            format.encode(sample.re * scale, &mut bytes);
+                            format.encode(sample.im * scale, &mut bytes);
+                        }
+                    }
+                    IqLayout::Planar => {
+                        let block_len: u32 = chunk.len().try_into().unwrap();
+                        bytes.extend_from_slice(&block_len.to_le_bytes());
+                        for sample in chunk.iter() {
+                            format.encode(sample.re * scale, &mut bytes);
+                        }
+                        for sample in chunk.iter() {
+                            format.encode(sample.im * scale, &mut bytes);
+                        }
+                    }
+                }
+                writer.write_all(&bytes).await?;
+                summary.sample_count += chunk.len() as u64;
+            }
+            Signal::Event(event) => {
+                if summary.sample_rate.is_none() {
+                    if let Some(frequency) = event.as_any().downcast_ref::<CenterFrequency>() {
+                        summary.frequency = Some(frequency.0);
+                    }
+                }
+            }
+        }
+    }
+}
+
+struct WavHeader {
+    format: WavFormat,
+    channels: u16,
+    sample_rate: u32,
+    data_len: Option<u64>,
+}
+
+fn read_wav_header(file: &mut std::fs::File) -> io::Result<WavHeader> {
+    let mut riff = [0u8; 12];
+    file.read_exact(&mut riff)?;
+    if &riff[0..4] != b"RIFF" || &riff[8..12] != b"WAVE" {
+        return Err(invalid_data("not a RIFF/WAVE file"));
+    }
+    let mut fmt: Option<(WavFormat, u16, u32)> = None;
+    loop {
+        let mut chunk_header = [0u8; 8];
+        file.read_exact(&mut chunk_header)?;
+        let chunk_len = u32::from_le_bytes(chunk_header[4..8].try_into().unwrap());
+        match &chunk_header[0..4] {
+            b"fmt " => {
+                if chunk_len < 16 {
+                    return Err(invalid_data("WAV format chunk too short"));
+                }
+                let mut body = vec![0u8; chunk_len as usize];
+                file.read_exact(&mut body)?;
+                let mut format_tag = u16::from_le_bytes([body[0], body[1]]);
+                if format_tag == 0xFFFE {
+                    if body.len() < 26 {
+                        return Err(invalid_data("WAV extensible format chunk too short"));
+                    }
+                    format_tag = u16::from_le_bytes([body[24], body[25]]);
+                }
+                let channels = u16::from_le_bytes([body[2], body[3]]);
+                let sample_rate = u32::from_le_bytes(body[4..8].try_into().unwrap());
+                let bits = u16::from_le_bytes([body[14], body[15]]);
+                let format = match (format_tag, bits) {
+                    (1, 16) => WavFormat::Pcm16,
+                    (3, 32) => WavFormat::Float32,
+                    _ => return Err(invalid_data("unsupported WAV sample format")),
+                };
+                if channels != 1 && channels != 2 {
+                    return Err(invalid_data("unsupported number of WAV channels"));
+                }
+                fmt = Some((format, channels, sample_rate));
+                if chunk_len % 2 != 0 {
+                    file.seek(SeekFrom::Current(1))?;
+                }
+            }
+            b"data" => {
+                let Some((format, channels, sample_rate)) = fmt
+                else { return Err(invalid_data("WAV data chunk before format chunk")); };
+                return Ok(WavHeader {
+                    format,
+                    channels,
+                    sample_rate,
+                    data_len: match chunk_len {
+                        0 | u32::MAX => None,
+                        len => Some(len as u64),
+                    },
+                });
+            }
+            _ => {
+                file.seek(SeekFrom::Current(chunk_len as i64 + (chunk_len % 2) as i64))?;
+            }
+        }
+    }
+}
+
+/// Options for reading raw files with [`RawSource::with_options`]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct RawSourceOptions {
+    /// Layout of the I/Q data (defaults to [`IqLayout::Interleaved`])
+    pub layout: IqLayout,
+    /// Number of bytes to skip at the beginning of the file, e.g. for a
+    /// header (defaults to zero)
+    pub skip_bytes: u64,
+    /// Replay the file endlessly (defaults to `false`)
+    ///
+    /// An [`events::EndOfFile`] event is sent each time the end of the file
+    /// has been reached, followed by an [`events::Repositioned`] event when
+    /// reading continues at the beginning (after the skipped bytes).
+    pub looping: bool,
+}
+
+impl Default for RawSourceOptions {
+    fn default() -> Self {
+        Self {
+            layout: IqLayout::Interleaved,
+            skip_bytes: 0,
+            looping: false,
+        }
+    }
+}
+
+/// Block which reads a file with raw I/Q data and acts as a [`Producer`]
+///
+/// As raw files don't contain any information about the sample rate, it has
+/// to be specified when creating the block. When the end of the file has been
+/// reached, an [`events::EndOfFile`] event is sent.
+///
+/// The data is expected to be interleaved unless a different [`IqLayout`] is
+/// given with [`RawSource::with_layout`]. Use [`RawSource::with_options`] to
+/// skip a header or to replay the file endlessly. Big endian files are read
+/// using the respective [`SampleFormat`] (e.g. [`SampleFormat::I16Be`]).
+pub struct RawSource {
+    sender_connector: SenderConnector<Signal<Complex<f32>>>,
+    reader: ReaderControl,
+}
+
+impl_block_trait! { Producer<Signal<Complex<f32>>> for RawSource }
+
+impl RawSource {
+    /// Open file with given sample `format` and create block which emits
+    /// chunks with `chunk_len` samples at given `sample_rate`
+    pub fn new<P: AsRef<Path>>(
+        path: P,
+        format: SampleFormat,
+        sample_rate: f64,
+        chunk_len: usize,
+    ) -> io::Result<Self> {
+        Self::with_layout(path, format, IqLayout::Interleaved, sample_rate, chunk_len)
+    }
+    /// Open file with given sample `format` and [`IqLayout`] and create block
+    /// which emits chunks with `chunk_len` samples at given `sample_rate`
+    ///
+    /// For [`IqLayout::Planar`], each block stored in the file results in one
+    /// or more chunks with at most `chunk_len` samples.
+    pub fn with_layout<P: AsRef<Path>>(
+        path: P,
+        format: SampleFormat,
+        layout: IqLayout,
+        sample_rate: f64,
+        chunk_len: usize,
+    ) -> io::Result<Self> {
+        let options = RawSourceOptions {
+            layout,
+            ..Default::default()
+        };
+        Self::with_options(path, format, sample_rate, chunk_len, options)
+    }
+    /// Open file with given sample `format` and [`RawSourceOptions`] and
+    /// create block which emits chunks with `chunk_len` samples at given
+    /// `sample_rate`
+    pub fn with_options<P: AsRef<Path>>(
+        path: P,
+        format: SampleFormat,
+        sample_rate: f64,
+        chunk_len: usize,
+        options: RawSourceOptions,
+    ) -> io::Result<Self> {
+        assert!(chunk_len > 0, "chunk length must be positive");
+        let RawSourceOptions {
+            layout,
+            skip_bytes,
+            looping,
+        } = options;
+        let mut file = std::fs::File::open(path)?;
+        file.seek(SeekFrom::Start(skip_bytes))?;
+        let file = File::from_std(file);
+        let (sender_connector, reader) = match layout {
+            IqLayout::Interleaved => spawn_reader(
+                file,
+                DataLayout {
+                    format,
+                    channels: 2,
+                    start: skip_bytes,
+                    len: None,
+                    frequency: None,
+                },
+                chunk_len,
+                sample_rate,
+                looping,
+            ),
+            IqLayout::Planar => {
+                spawn_planar_reader(file, format, skip_bytes, chunk_len, sample_rate, looping)
+            }
+        };
+        Ok(Self {
+            sender_connector,
+            reader,
+        })
+    }
+    /// Get index of the next sample to be read from the file
+    ///
+    /// As samples are read ahead of downstream blocks, this may exceed the
+    /// index of the sample which is currently being processed.
+    pub fn position(&self) -> u64 {
+        self.reader.position()
+    }
+    /// Continue reading at sample with given index
+    ///
+    /// A [`Repositioned`] event is sent before the samples from the new
+    /// position, such that downstream blocks reset their state. Positions
+    /// past the end of the file are clamped, which results in an
+    /// [`EndOfFile`] event after the [`Repositioned`] event. Seeking is also
+    /// possible after the end of the file has been reached.
+    pub fn seek(&self, sample_index: u64) {
+        self.reader.seek(sample_index);
+    }
+}
+
+/// Block which writes raw I/Q data to a file and acts as a [`Consumer`]
+///
+/// The sample rate of the received [`Signal::Samples`] is not stored but must
+/// not change. Values exceeding the range of integer formats are clipped.
+///
+/// The data is written interleaved unless a different [`IqLayout`] is given
+/// with [`RawSink::with_layout`].
+pub struct RawSink {
+    receiver_connector: ReceiverConnector<Signal<Complex<f32>>>,
+    scale: watch::Sender<f32>,
+    drop_watch: watch::Sender<()>,
+    join_handle: JoinHandle<io::Result<()>>,
+}
+
+impl_block_trait! { Consumer<Signal<Complex<f32>>> for RawSink }
+
+impl RawSink {
+    /// Create file with given sample `format`
+    pub fn new<P: AsRef<Path>>(path: P, format: SampleFormat) -> io::Result<Self> {
+        Self::with_layout(path, format, IqLayout::Interleaved)
+    }
+    /// Create file with given sample `format` and [`IqLayout`]
+    pub fn with_layout<P: AsRef<Path>>(
+        path: P,
+        format: SampleFormat,
+        layout: IqLayout,
+    ) -> io::Result<Self> {
+        let file = File::from_std(std::fs::File::create(path)?);
+        let (mut receiver, receiver_connector) = new_receiver::<Signal<Complex<f32>>>();
+        let (drop_watch, mut drop_watch_recv) = watch::channel(());
+        let (scale, scale_recv) = watch::channel(1.0);
+        let join_handle = spawn(async move {
+            let mut writer = BufWriter::new(file);
+            let result = write_samples(
+                &mut receiver,
+                &mut drop_watch_recv,
+                &mut writer,
+                format,
+                layout,
+                &scale_recv,
+                &mut WriteSummary::default(),
+            )
+            .await;
+            writer.flush().await?;
+            result
+        });
+        Ok(Self {
+            receiver_connector,
+            scale,
+            drop_watch,
+            join_handle,
+        })
+    }
+    /// Get factor which samples are multiplied with before being written
+    pub fn scale(&self) -> f32 {
+        *self.scale.borrow()
+    }
+    /// Set factor which samples are multiplied with before being written
+    ///
+    /// Defaults to `1.0`. Scaled values which exceed the range of integer
+    /// formats are clipped.
+    pub fn set_scale(&self, scale: f32) {
+        self.scale.send_replace(scale);
+    }
+    /// Stop writing and wait until the file has been written
+    pub async fn finalize(self) -> io::Result<()> {
+        let Self {
+            drop_watch,
+            join_handle,
+            ..
+        } = self;
+        drop(drop_watch);
+        join_handle.await.expect("file writing task panicked")
+    }
+}
+
+/// Block which reads a WAV file and acts as a [`Producer`]
+///
+/// 16 bit PCM and 32 bit floating point files with one or two channels are
+/// supported. When the end of the file has been reached, an
+/// [`events::EndOfFile`] event is sent.
+pub struct WavSource {
+    sender_connector: SenderConnector<Signal<Complex<f32>>>,
+    format: WavFormat,
+    sample_rate: f64,
+    reader: ReaderControl,
+}
+
+impl_block_trait! { Producer<Signal<Complex<f32>>> for WavSource }
+
+impl WavSource {
+    /// Open WAV file and create block which emits chunks with `chunk_len`
+    /// samples
+    pub fn new<P: AsRef<Path>>(path: P, chunk_len: usize) -> io::Result<Self> {
+        assert!(chunk_len > 0, "chunk length must be positive");
+        let mut file = std::fs::File::open(path)?;
+        let header = read_wav_header(&mut file)?;
+        let WavHeader {
+            format,
+            channels,
+            sample_rate,
+            data_len,
+        } = header;
+        let sample_rate = sample_rate as f64;
+        let data = DataLayout {
+            format: format.sample_format(),
+            channels: channels as usize,
+            start: file.stream_position()?,
+            len: data_len,
+            frequency: None,
+        };
+        let (sender_connector, reader) =
+            spawn_reader(File::from_std(file), data, chunk_len, sample_rate, false);
+        Ok(Self {
+            sender_connector,
+            format,
+            sample_rate,
+            reader,
+        })
+    }
+    /// Sample format of the file
+    pub fn format(&self) -> WavFormat {
+        self.format
+    }
+    /// Sample rate of the file in samples per second
+    pub fn sample_rate(&self) -> f64 {
+        self.sample_rate
+    }
+    /// Get index of the next sample to be read from the file
+    ///
+    /// As samples are read ahead of downstream blocks, this may exceed the
+    /// index of the sample which is currently being processed.
+    pub fn position(&self) -> u64 {
+        self.reader.position()
+    }
+    /// Continue reading at sample with given index
+    ///
+    /// A [`Repositioned`] event is sent before the samples from the new
+    /// position, such that downstream blocks reset their state. Positions
+    /// past the end of the file are clamped, which results in an
+    /// [`EndOfFile`] event after the [`Repositioned`] event. Seeking is also
+    /// possible after the end of the file has been reached.
+    pub fn seek(&self, sample_index: u64) {
+        self.reader.seek(sample_index);
+    }
+}
+
+/// Block which writes a WAV file with interleaved I/Q data and acts as a
+/// [`Consumer`]
+///
+/// The sample rate of the file is taken from the first received
+/// [`Signal::Samples`] and must not change afterwards. The size fields in the
+/// header are updated when the block is dropped or [finalized]. Values
+/// exceeding the range of [`WavFormat::Pcm16`] are clipped.
+///
+/// [finalized]: WavSink::finalize
+pub struct WavSink {
+    receiver_connector: ReceiverConnector<Signal<Complex<f32>>>,
+    scale: watch::Sender<f32>,
+    drop_watch: watch::Sender<()>,
+    join_handle: JoinHandle<io::Result<()>>,
+}
+
+impl_block_trait! { Consumer<Signal<Complex<f32>>> for WavSink }
+
+impl WavSink {
+    /// Create WAV file with given sample `format`
+    pub fn new<P: AsRef<Path>>(path: P, format: WavFormat) -> io::Result<Self> {
+        let file = File::from_std(std::fs::File::create(path)?);
+        let (mut receiver, receiver_connector) = new_receiver::<Signal<Complex<f32>>>();
+        let (drop_watch, mut drop_watch_recv) = watch::channel(());
+        let (scale, scale_recv) = watch::channel(1.0);
+        let join_handle = spawn(async move {
+            let mut writer = BufWriter::new(file);
+            writer.write_all(&wav_header(format, 2, 0, 0)).await?;
+            let mut summary = WriteSummary::default();
+            let result = write_samples(
+                &mut receiver,
+                &mut drop_watch_recv,
+                &mut writer,
+                format.sample_format(),
+                IqLayout::Interleaved,
+                &scale_recv,
+                &mut summary,
+            )
+            .await;
+            let sample_rate = summary.sample_rate.unwrap_or(0.0).round() as u32;
+            let data_len =
+                summary.sample_count * 2 * format.sample_format().bytes_per_value() as u64;
+            let data_len = u32::try_from(data_len).unwrap_or(u32::MAX);
+            writer.flush().await?;
+            let mut file = writer.into_inner();
+            file.seek(SeekFrom::Start(0)).await?;
+            file.write_all(&wav_header(format, 2, sample_rate, data_len))
+                .await?;
+            file.flush().await?;
+            result
+        });
+        Ok(Self {
+            receiver_connector,
+            scale,
+            drop_watch,
+            join_handle,
+        })
+    }
+    /// Get factor which samples are multiplied with before being written
+    pub fn scale(&self) -> f32 {
+        *self.scale.borrow()
+    }
+    /// Set factor which samples are multiplied with before being written
+    ///
+    /// Defaults to `1.0`. Scaled values which exceed the range of integer
+    /// formats are clipped.
+    pub fn set_scale(&self, scale: f32) {
+        self.scale.send_replace(scale);
+    }
+    /// Stop writing, update header, and wait until the file has been written
+    pub async fn finalize(self) -> io::Result<()> {
+        let Self {
+            drop_watch,
+            join_handle,
+            ..
+        } = self;
+        drop(drop_watch);
+        join_handle.await.expect("WAV writing task panicked")
+    }
+}
+
+fn sigmf_paths(path: &Path) -> (PathBuf, PathBuf) {
+    let base = match path.extension().and_then(|ext| ext.to_str()) {
+        Some("sigmf-meta" | "sigmf-data") => path.with_extension(""),
+        _ => path.to_path_buf(),
+    };
+    let with_suffix = |suffix: &str| {
+        let mut path: OsString = base.clone().into();
+        path.push(suffix);
+        PathBuf::from(path)
+    };
+    (with_suffix(".sigmf-meta"), with_suffix(".sigmf-data"))
+}
+
+fn format_datetime(time: SystemTime) -> String {
+    let duration = time.duration_since(UNIX_EPOCH).unwrap_or_default();
+    let secs = duration.as_secs();
+    let days = (secs / 86400) as i64;
+    let secs_of_day = secs % 86400;
+    // convert days since epoch to civil date (proleptic Gregorian calendar)
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z - era * 146097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + (month <= 2) as i64;
+    format!(
+        "{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}.{:03}Z",
+        secs_of_day / 3600,
+        secs_of_day / 60 % 60,
+        secs_of_day % 60,
+        duration.subsec_millis(),
+    )
+}
+
+/// Annotation of a segment in a SigMF recording
+#[derive(Clone, PartialEq, Default, Debug)]
+pub struct SigMfAnnotation {
+    /// Index of first sample of the segment
+    pub sample_start: u64,
+    /// Number of samples in the segment
+    pub sample_count: Option<u64>,
+    /// Lower frequency edge in hertz
+    pub freq_lower_edge: Option<f64>,
+    /// Upper frequency edge in hertz
+    pub freq_upper_edge: Option<f64>,
+    /// Human readable label
+    pub label: Option<String>,
+}
+
+impl SigMfAnnotation {
+    fn from_json(value: &json::Value) -> Option<Self> {
+        let opt_u64 = |key| {
+            value
+                .get(key)
+                .and_then(json::Value::as_f64)
+                .map(|x| x as u64)
+        };
+        let opt_f64 = |key| value.get(key).and_then(json::Value::as_f64);
+        Some(Self {
+            sample_start: opt_u64("core:sample_start")?,
+            sample_count: opt_u64("core:sample_count"),
+            freq_lower_edge: opt_f64("core:freq_lower_edge"),
+            freq_upper_edge: opt_f64("core:freq_upper_edge"),
+            label: value
+                .get("core:label")
+                .and_then(json::Value::as_str)
+                .map(ToOwned::to_owned),
+        })
+    }
+    fn to_json(&self) -> json::Value {
+        use json::Value;
+        let mut members = vec![(
+            "core:sample_start".to_owned(),
+            Value::Number(self.sample_start as f64),
+        )];
+        if let Some(sample_count) = self.sample_count {
+            members.push((
+                "core:sample_count".to_owned(),
+                Value::Number(sample_count as f64),
+            ));
+        }
+        if let Some(freq) = self.freq_lower_edge {
+            members.push(("core:freq_lower_edge".to_owned(), Value::Number(freq)));
+        }
+        if let Some(freq) = self.freq_upper_edge {
+            members.push(("core:freq_upper_edge".to_owned(), Value::Number(freq)));
+        }
+        if let Some(label) = &self.label {
+            members.push(("core:label".to_owned(), Value::String(label.clone())));
+        }
+        Value::Object(members)
+    }
+}
+
+/// Block which reads a [SigMF] recording and acts as a [`Producer`]
+///
+/// The `path` passed to [`SigMfSource::new`] may either be the base name of
+/// the recording or the name of the `.sigmf-meta` or `.sigmf-data` file.
+/// Complex (`c…`) and real (`r…`) datatypes using the formats listed in
+/// [`SampleFormat`] are supported. When the end of the file has been reached,
+/// an [`events::EndOfFile`] event is sent.
+///
+/// [SigMF]: https://sigmf.org/
+pub struct SigMfSource {
+    sender_connector: SenderConnector<Signal<Complex<f32>>>,
+    format: SampleFormat,
+    sample_rate: f64,
+    frequency: Option<f64>,
+    datetime: Option<String>,
+    annotations: Vec<SigMfAnnotation>,
+    reader: ReaderControl,
+}
+
+impl_block_trait! { Producer<Signal<Complex<f32>>> for SigMfSource }
+
+impl SigMfSource {
+    /// Open SigMF recording and create block which emits chunks with
+    /// `chunk_len` samples
+    pub fn new<P: AsRef<Path>>(path: P, chunk_len: usize) -> io::Result<Self> {
+        assert!(chunk_len > 0, "chunk length must be positive");
+        let (meta_path, data_path) = sigmf_paths(path.as_ref());
+        let meta = json::parse(&std::fs::read_to_string(meta_path)?)
+            .ok_or_else(|| invalid_data("invalid JSON in SigMF metadata"))?;
+        let global = meta
+            .get("global")
+            .ok_or_else(|| invalid_data("missing global object in SigMF metadata"))?;
+        let datatype = global
+            .get("core:datatype")
+            .and_then(json::Value::as_str)
+            .ok_or_else(|| invalid_data("missing datatype in SigMF metadata"))?;
+        let (channels, format) = match datatype.split_at(datatype.len().min(1)) {
+            ("c", suffix) => (2, SampleFormat::from_sigmf_suffix(suffix)),
+            ("r", suffix) => (1, SampleFormat::from_sigmf_suffix(suffix)),
+            _ => (0, None),
+        };
+        let format = format.ok_or_else(|| invalid_data("unsupported SigMF datatype"))?;
+        let sample_rate = global
+            .get("core:sample_rate")
+            .and_then(json::Value::as_f64)
+            .ok_or_else(|| invalid_data("missing sample rate in SigMF metadata"))?;
+        let capture = meta
+            .get("captures")
+            .and_then(json::Value::as_array)
+            .and_then(|captures| captures.first());
+        let frequency = capture
+            .and_then(|capture| capture.get("core:frequency"))
+            .and_then(json::Value::as_f64);
+        let datetime = capture
+            .and_then(|capture| capture.get("core:datetime"))
+            .and_then(json::Value::as_str)
+            .map(ToOwned::to_owned);
+        let annotations = meta
+            .get("annotations")
+            .and_then(json::Value::as_array)
+            .unwrap_or(&[])
+            .iter()
+            .filter_map(SigMfAnnotation::from_json)
+            .collect();
+        let file = std::fs::File::open(data_path)?;
+        let data = DataLayout {
+            format,
+            channels,
+            start: 0,
+            len: None,
+            frequency: None,
+        };
+        let (sender_connector, reader) =
+            spawn_reader(File::from_std(file), data, chunk_len, sample_rate, false);
+        Ok(Self {
+            sender_connector,
+            format,
+            sample_rate,
+            frequency,
+            datetime,
+            annotations,
+            reader,
+        })
+    }
+    /// Sample format of the recording
+    pub fn format(&self) -> SampleFormat {
+        self.format
+    }
+    /// Sample rate of the recording in samples per second
+    pub fn sample_rate(&self) -> f64 {
+        self.sample_rate
+    }
+    /// Center frequency of the (first) capture in hertz, if known
+    pub fn frequency(&self) -> Option<f64> {
+        self.frequency
+    }
+    /// Timestamp of the (first) capture as ISO 8601 string, if known
+    pub fn datetime(&self) -> Option<&str> {
+        self.datetime.as_deref()
+    }
+    /// Annotations contained in the metadata
+    pub fn annotations(&self) -> &[SigMfAnnotation] {
+        &self.annotations
+    }
+    /// Get index of the next sample to be read from the file
+    ///
+    /// As samples are read ahead of downstream blocks, this may exceed the
+    /// index of the sample which is currently being processed.
+    pub fn position(&self) -> u64 {
+        self.reader.position()
+    }
+    /// Continue reading at sample with given index
+    ///
+    /// A [`Repositioned`] event is sent before the samples from the new
+    /// position, such that downstream blocks reset their state. Positions
+    /// past the end of the file are clamped, which results in an
+    /// [`EndOfFile`] event after the [`Repositioned`] event. Seeking is also
+    /// possible after the end of the file has been reached.
+    pub fn seek(&self, sample_index: u64) {
+        self.reader.seek(sample_index);
+    }
+}
+
+/// Block which writes a [SigMF] recording with complex samples and acts as a
+/// [`Consumer`]
+///
+/// The `.sigmf-data` file is written while samples are received. The
+/// `.sigmf-meta` file is written when the block is dropped or [finalized]
+/// and contains the sample rate of the received [`Signal::Samples`] (which
+/// must not change), the datatype, the time when the first samples were
+/// received, and any [annotations] added. If the time of the first sample is
+/// known from a [`Timestamp`] event, it is used instead of the time of
+/// reception (interpreting the timestamp as nanoseconds since the UNIX
+/// epoch). Unless [set explicitly], the center frequency is taken from a
+/// [`CenterFrequency`] event preceding the first samples.
+///
+/// [SigMF]: https://sigmf.org/
+/// [set explicitly]: SigMfSink::set_frequency
+/// [finalized]: SigMfSink::finalize
+/// [annotations]: SigMfSink::add_annotation
+pub struct SigMfSink {
+    receiver_connector: ReceiverConnector<Signal<Complex<f32>>>,
+    frequency: watch::Sender<Option<f64>>,
+    annotations: watch::Sender<Vec<SigMfAnnotation>>,
+    scale: watch::Sender<f32>,
+    drop_watch: watch::Sender<()>,
+    join_handle: JoinHandle<io::Result<()>>,
+}
+
+impl_block_trait! { Consumer<Signal<Complex<f32>>> for SigMfSink }
+
+impl SigMfSink {
+    /// Create SigMF recording with given sample `format`
+    ///
+    /// The `path` is the base name of the recording, to which the extensions
+    /// `.sigmf-meta` and `.sigmf-data` are appended.
+    pub fn new<P: AsRef<Path>>(path: P, format: SampleFormat) -> io::Result<Self> {
+        let (meta_path, data_path) = sigmf_paths(path.as_ref());
+        let file = File::from_std(std::fs::File::create(data_path)?);
+        let (mut receiver, receiver_connector) = new_receiver::<Signal<Complex<f32>>>();
+        let (drop_watch, mut drop_watch_recv) = watch::channel(());
+        let (frequency_send, frequency_recv) = watch::channel(None);
+        let (annotations_send, annotations_recv) = watch::channel(Vec::new());
+        let (scale, scale_recv) = watch::channel(1.0);
+        let join_handle = spawn(async move {
+            use json::Value;
+            let mut writer = BufWriter::new(file);
+            let mut summary = WriteSummary::default();
+            let result = write_samples(
+                &mut receiver,
+                &mut drop_watch_recv,
+                &mut writer,
+                format,
+                IqLayout::Interleaved,
+                &scale_recv,
+                &mut summary,
+            )
+            .await;
+            writer.flush().await?;
+            let mut global = vec![
+                (
+                    "core:datatype".to_owned(),
+                    Value::String(format!("c{}", format.sigmf_suffix())),
+                ),
+                ("core:version".to_owned(), Value::String("1.0.0".to_owned())),
+                (
+                    "core:recorder".to_owned(),
+                    Value::String("radiorust".to_owned()),
+                ),
+            ];
+            if let Some(sample_rate) = summary.sample_rate {
+                global.push(("core:sample_rate".to_owned(), Value::Number(sample_rate)));
+            }
+            let mut capture = vec![("core:sample_start".to_owned(), Value::Number(0.0))];
+            if let Some(start_time) = summary.start_time {
+                capture.push((
+                    "core:datetime".to_owned(),
+                    Value::String(format_datetime(start_time)),
+                ));
+            }
+            if let Some(frequency) = frequency_recv.borrow().or(summary.frequency) {
+                capture.push(("core:frequency".to_owned(), Value::Number(frequency)));
+            }
+            let annotations = annotations_recv
+                .borrow()
+                .iter()
+                .map(SigMfAnnotation::to_json)
+                .collect();
+            let meta = Value::Object(vec![
+                ("global".to_owned(), Value::Object(global)),
+                (
+                    "captures".to_owned(),
+                    Value::Array(vec![Value::Object(capture)]),
+                ),
+                ("annotations".to_owned(), Value::Array(annotations)),
+            ]);
+            tokio::fs::write(meta_path, meta.to_string()).await?;
+            result
+        });
+        Ok(Self {
+            receiver_connector,
+            frequency: frequency_send,
+            annotations: annotations_send,
+            scale,
+            drop_watch,
+            join_handle,
+        })
+    }
+    /// Get center frequency in hertz to be stored in metadata
+    pub fn frequency(&self) -> Option<f64> {
+        *self.frequency.borrow()
+    }
+    /// Set center frequency in hertz to be stored in metadata
+    pub fn set_frequency(&self, frequency: Option<f64>) {
+        self.frequency.send_replace(frequency);
+    }
+    /// Add annotation to be stored in metadata
+    pub fn add_annotation(&self, annotation: SigMfAnnotation) {
+        self.annotations
+            .send_modify(|annotations| annotations.push(annotation));
+    }
+    /// Get factor which samples are multiplied with before being written
+    pub fn scale(&self) -> f32 {
+        *self.scale.borrow()
+    }
+    /// Set factor which samples are multiplied with before being written
+    ///
+    /// Defaults to `1.0`. Scaled values which exceed the range of integer
+    /// formats are clipped.
+    pub fn set_scale(&self, scale: f32) {
+        self.scale.send_replace(scale);
+    }
+    /// Stop writing and wait until data and metadata have been written
+    pub async fn finalize(self) -> io::Result<()> {
+        let Self {
+            drop_watch,
+            join_handle,
+            ..
+        } = self;
+        drop(drop_watch);
+        join_handle.await.expect("SigMF writing task panicked")
+    }
+}
+
+fn capture_paths(path: &Path) -> (PathBuf, PathBuf) {
+    let mut meta_path: OsString = path.as_os_str().to_owned();
+    meta_path.push(".json");
+    (PathBuf::from(meta_path), path.to_path_buf())
+}
+
+/// Block which reads a recording written by [`CaptureSink`] and acts as a
+/// [`Producer`]
+///
+/// The sample format and sample rate are taken from the JSON sidecar file,
+/// whose name is the `path` of the data file with `.json` appended. If the
+/// sidecar contains a center frequency, a [`CenterFrequency`] event is sent
+/// before the first samples and after each [seek]. When the end of the file
+/// has been reached, an [`events::EndOfFile`] event is sent.
+///
+/// [seek]: CaptureSource::seek
+pub struct CaptureSource {
+    sender_connector: SenderConnector<Signal<Complex<f32>>>,
+    format: SampleFormat,
+    sample_rate: f64,
+    frequency: Option<f64>,
+    gain: Option<f64>,
+    antenna: Option<String>,
+    datetime: Option<String>,
+    reader: ReaderControl,
+}
+
+impl_block_trait! { Producer<Signal<Complex<f32>>> for CaptureSource }
+
+impl CaptureSource {
+    /// Open recording and create block which emits chunks with `chunk_len`
+    /// samples
+    pub fn new<P: AsRef<Path>>(path: P, chunk_len: usize) -> io::Result<Self> {
+        assert!(chunk_len > 0, "chunk length must be positive");
+        let (meta_path, data_path) = capture_paths(path.as_ref());
+        let meta = json::parse(&std::fs::read_to_string(meta_path)?)
+            .ok_or_else(|| invalid_data("invalid JSON in capture metadata"))?;
+        let format = meta
+            .get("datatype")
+            .and_then(json::Value::as_str)
+            .and_then(|datatype| datatype.strip_prefix('c'))
+            .and_then(SampleFormat::from_sigmf_suffix)
+            .ok_or_else(|| invalid_data("missing or unsupported datatype in capture metadata"))?;
+        let sample_rate = meta
+            .get("sample_rate")
+            .and_then(json::Value::as_f64)
+            .ok_or_else(|| invalid_data("missing sample rate in capture metadata"))?;
+        let opt_f64 = |key| meta.get(key).and_then(json::Value::as_f64);
+        let opt_string = |key| {
+            meta.get(key)
+                .and_then(json::Value::as_str)
+                .map(ToOwned::to_owned)
+        };
+        let frequency = opt_f64("frequency");
+        let file = std::fs::File::open(data_path)?;
+        let data = DataLayout {
+            format,
+            channels: 2,
+            start: 0,
+            len: None,
+            frequency,
+        };
+        let (sender_connector, reader) =
+            spawn_reader(File::from_std(file), data, chunk_len, sample_rate, false);
+        Ok(Self {
+            sender_connector,
+            format,
+            sample_rate,
+            frequency,
+            gain: opt_f64("gain_db"),
+            antenna: opt_string("antenna"),
+            datetime: opt_string("datetime"),
+            reader,
+        })
+    }
+    /// Sample format of the recording
+    pub fn format(&self) -> SampleFormat {
+        self.format
+    }
+    /// Sample rate of the recording in samples per second
+    pub fn sample_rate(&self) -> f64 {
+        self.sample_rate
+    }
+    /// Center frequency of the recording in hertz, if known
+    pub fn frequency(&self) -> Option<f64> {
+        self.frequency
+    }
+    /// Receiver gain in decibels, if known
+    pub fn gain(&self) -> Option<f64> {
+        self.gain
+    }
+    /// Name of the antenna used, if known
+    pub fn antenna(&self) -> Option<&str> {
+        self.antenna.as_deref()
+    }
+    /// Time of the first sample as ISO 8601 string, if known
+    pub fn datetime(&self) -> Option<&str> {
+        self.datetime.as_deref()
+    }
+    /// Get index of the next sample to be read from the file
+    ///
+    /// As samples are read ahead of downstream blocks, this may exceed the
+    /// index of the sample which is currently being processed.
+    pub fn position(&self) -> u64 {
+        self.reader.position()
+    }
+    /// Continue reading at sample with given index
+    ///
+    /// Behaves like [`SigMfSource::seek`].
+    pub fn seek(&self, sample_index: u64) {
+        self.reader.seek(sample_index);
+    }
+}
+
+/// Block which writes interleaved I/Q data together with a JSON sidecar file
+/// describing the capture and acts as a [`Consumer`]
+///
+/// This is a lightweight alternative to [`SigMfSink`]: the data file at the
+/// given `path` contains raw samples (like written by [`RawSink`]), and the
+/// sidecar (`path` with `.json` appended) is written when the block is
+/// dropped or [finalized]. It contains the datatype, the sample rate of the
+/// received [`Signal::Samples`] (which must not change), the number of
+/// samples, and the time of the first sample (determined like in
+/// [`SigMfSink`]). The center frequency is taken from a [`CenterFrequency`]
+/// event preceding the first samples (as sent by the SoapySDR receiver
+/// blocks) unless [set explicitly]. Receiver gain and antenna can be set
+/// manually or, with the `soapysdr` feature, read from the device with
+/// `CaptureSink::record_settings`.
+///
+/// Recordings can be played back with [`CaptureSource`].
+///
+/// [finalized]: CaptureSink::finalize
+/// [set explicitly]: CaptureSink::set_frequency
+pub struct CaptureSink {
+    receiver_connector: ReceiverConnector<Signal<Complex<f32>>>,
+    frequency: watch::Sender<Option<f64>>,
+    gain: watch::Sender<Option<f64>>,
+    antenna: watch::Sender<Option<String>>,
+    scale: watch::Sender<f32>,
+    drop_watch: watch::Sender<()>,
+    join_handle: JoinHandle<io::Result<()>>,
+}
+
+impl_block_trait! { Consumer<Signal<Complex<f32>>> for CaptureSink }
+
+impl CaptureSink {
+    /// Create data file at `path` with given sample `format`
+    pub fn new<P: AsRef<Path>>(path: P, format: SampleFormat) -> io::Result<Self> {
+        let (meta_path, data_path) = capture_paths(path.as_ref());
+        let file = File::from_std(std::fs::File::create(data_path)?);
+        let (mut receiver, receiver_connector) = new_receiver::<Signal<Complex<f32>>>();
+        let (drop_watch, mut drop_watch_recv) = watch::channel(());
+        let (frequency_send, frequency_recv) = watch::channel(None);
+        let (gain_send, gain_recv) = watch::channel(None);
+        let (antenna_send, antenna_recv) = watch::channel::<Option<String>>(None);
+        let (scale, scale_recv) = watch::channel(1.0);
+        let join_handle = spawn(async move {
+            use json::Value;
+            let mut writer = BufWriter::new(file);
+            let mut summary = WriteSummary::default();
+            let result = write_samples(
+                &mut receiver,
+                &mut drop_watch_recv,
+                &mut writer,
+                format,
+                IqLayout::Interleaved,
+                &scale_recv,
+                &mut summary,
+            )
+            .await;
+            writer.flush().await?;
+            let mut members = vec![
+                (
+                    "datatype".to_owned(),
+                    Value::String(format!("c{}", format.sigmf_suffix())),
+                ),
+                ("recorder".to_owned(), Value::String("radiorust".to_owned())),
+                (
+                    "sample_count".to_owned(),
+                    Value::Number(summary.sample_count as f64),
+                ),
+            ];
+            if let Some(sample_rate) = summary.sample_rate {
+                members.push(("sample_rate".to_owned(), Value::Number(sample_rate)));
+            }
+            if let Some(frequency) = frequency_recv.borrow().or(summary.frequency) {
+                members.push(("frequency".to_owned(), Value::Number(frequency)));
+            }
+            if let Some(gain) = *gain_recv.borrow() {
+                members.push(("gain_db".to_owned(), Value::Number(gain)));
+            }
+            if let Some(antenna) = antenna_recv.borrow().clone() {
+                members.push(("antenna".to_owned(), Value::String(antenna)));
+            }
+            if let Some(start_time) = summary.start_time {
+                members.push((
+                    "datetime".to_owned(),
+                    Value::String(format_datetime(start_time)),
+                ));
+            }
+            tokio::fs::write(meta_path, Value::Object(members).to_string()).await?;
+            result
+        });
+        Ok(Self {
+            receiver_connector,
+            frequency: frequency_send,
+            gain: gain_send,
+            antenna: antenna_send,
+            scale,
+            drop_watch,
+            join_handle,
+        })
+    }
+    /// Get center frequency in hertz which overrides the frequency received
+    /// through [`CenterFrequency`] events
+    pub fn frequency(&self) -> Option<f64> {
+        *self.frequency.borrow()
+    }
+    /// Set center frequency in hertz which overrides the frequency received
+    /// through [`CenterFrequency`] events
+    pub fn set_frequency(&self, frequency: Option<f64>) {
+        self.frequency.send_replace(frequency);
+    }
+    /// Get receiver gain in decibels to be stored in metadata
+    pub fn gain(&self) -> Option<f64> {
+        *self.gain.borrow()
+    }
+    /// Set receiver gain in decibels to be stored in metadata
+    pub fn set_gain(&self, gain: Option<f64>) {
+        self.gain.send_replace(gain);
+    }
+    /// Get name of antenna to be stored in metadata
+    pub fn antenna(&self) -> Option<String> {
+        self.antenna.borrow().clone()
+    }
+    /// Set name of antenna to be stored in metadata
+    pub fn set_antenna(&self, antenna: Option<String>) {
+        self.antenna.send_replace(antenna);
+    }
+    /// Get factor which samples are multiplied with before being written
+    pub fn scale(&self) -> f32 {
+        *self.scale.borrow()
+    }
+    /// Set factor which samples are multiplied with before being written
+    ///
+    /// Defaults to `1.0`. Scaled values which exceed the range of integer
+    /// formats are clipped.
+    pub fn set_scale(&self, scale: f32) {
+        self.scale.send_replace(scale);
+    }
+    /// Read gain and antenna of given `channel` from device to be stored in
+    /// metadata
+    ///
+    /// The center frequency is not read, as it is received through
+    /// [`CenterFrequency`] events.
+    #[cfg(feature = "soapysdr")]
+    pub fn record_settings(
+        &self,
+        handle: &crate::blocks::io::rf::soapysdr::SoapySdrRxHandle,
+        channel: usize,
+    ) -> Result<(), crate::blocks::io::rf::soapysdr::Error> {
+        self.set_gain(Some(handle.gain(channel)?));
+        self.set_antenna(Some(handle.antenna(channel)?));
+        Ok(())
+    }
+    /// Stop writing and wait until data and metadata have been written
+    pub async fn finalize(self) -> io::Result<()> {
+        let Self {
+            drop_watch,
+            join_handle,
+            ..
+        } = self;
+        drop(drop_watch);
+        join_handle.await.expect("capture writing task panicked")
+    }
+}
+
+/// Maximum number of samples in a frame accepted by [`TcpSource`]
+const MAX_TCP_FRAME_LEN: usize = 1 << 24;
+
+/// Delay before [`TcpSource`] tries to (re)connect after a failure
+const TCP_RECONNECT_DELAY: Duration = Duration::from_secs(1);
+
+fn encode_tcp_frame(sample_rate: f64, chunk: &[Complex<f32>], bytes: &mut Vec<u8>) {
+    bytes.clear();
+    bytes.extend_from_slice(&(chunk.len() as u32).to_le_bytes());
+    bytes.extend_from_slice(&sample_rate.to_le_bytes());
+    for sample in chunk.iter() {
+        bytes.extend_from_slice(&sample.re.to_le_bytes());
+        bytes.extend_from_slice(&sample.im.to_le_bytes());
+    }
+}
+
+/// Block which receives samples over TCP (as sent by [`TcpSink`]) and acts as
+/// a [`Producer`]
+///
+/// Each chunk is transferred as a frame consisting of the number of samples
+/// (32 bit unsigned integer), the sample rate (64 bit floating point), and the
+/// samples as interleaved I/Q data (32 bit floating point), all little endian.
+///
+/// No data is read from the socket while the connected [`Consumer`]s are
+/// busy, such that backpressure propagates to the sending side. If the
+/// connection fails or is lost, an [`events::ConnectionLost`] event is sent
+/// (unless there have been no samples yet) and the block tries to reconnect.
+pub struct TcpSource {
+    sender_connector: SenderConnector<Signal<Complex<f32>>>,
+    _drop_watch: watch::Sender<()>,
+}
+
+impl_block_trait! { Producer<Signal<Complex<f32>>> for TcpSource }
+
+impl TcpSource {
+    /// Create block which connects to a [`TcpSink`] at the given address
+    ///
+    /// The address is resolved once when calling this function.
+    pub fn connect<A: ToSocketAddrs>(addr: A) -> io::Result<Self> {
+        let addrs: Vec<SocketAddr> = addr.to_socket_addrs()?.collect();
+        if addrs.is_empty() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "could not resolve address",
+            ));
+        }
+        let (sender, sender_connector) = new_sender::<Signal<Complex<f32>>>();
+        let (drop_watch_send, mut drop_watch_recv) = watch::channel(());
+        let mut buf_pool = ChunkBufPool::<Complex<f32>>::new();
+        let receive = async move {
+            let mut bytes: Vec<u8> = Vec::new();
+            let mut interrupted = true;
+            loop {
+                let result: io::Result<()> = async {
+                    let mut stream = TcpStream::connect(&addrs[..]).await?;
+                    loop {
+                        let mut header = [0u8; 12];
+                        stream.read_exact(&mut header).await?;
+                        let len = u32::from_le_bytes(header[0..4].try_into().unwrap()) as usize;
+                        let sample_rate = f64::from_le_bytes(header[4..12].try_into().unwrap());
+                        if len > MAX_TCP_FRAME_LEN {
+                            return Err(invalid_data("TCP frame too long"));
+                        }
+                        bytes.resize(len * 8, 0);
+                        stream.read_exact(&mut bytes).await?;
+                        let mut output_chunk = buf_pool.get_with_capacity(len);
+                        for value in bytes.chunks_exact(8) {
+                            output_chunk.push(Complex::new(
+                                f32::from_le_bytes(value[0..4].try_into().unwrap()),
+                                f32::from_le_bytes(value[4..8].try_into().unwrap()),
+                            ));
+                        }
+                        let Ok(()) = sender
+                            .send(Signal::Samples {
+                                sample_rate,
+                                chunk: output_chunk.finalize(),
+                            })
+                            .await
+                        else { return Ok(()); };
+                        interrupted = false;
+                    }
+                }
+                .await;
+                if result.is_ok() {
+                    return;
+                }
+                if !interrupted {
+                    interrupted = true;
+                    let Ok(()) = sender.send(Signal::Event(Arc::new(ConnectionLost))).await
+                    else { return; };
+                }
+                sleep(TCP_RECONNECT_DELAY).await;
+            }
+        };
+        spawn(async move {
+            select! {
+                _ = drop_watch_recv.changed() => (),
+                _ = receive => (),
+            }
+        });
+        Ok(Self {
+            sender_connector,
+            _drop_watch: drop_watch_send,
+        })
+    }
+}
+
+/// Block which sends samples over TCP (to a [`TcpSource`]) and acts as a
+/// [`Consumer`]
+///
+/// See [`TcpSource`] for a description of the used framing.
+/// [`Signal::Event`]s are not transferred.
+pub struct TcpSink {
+    receiver_connector: ReceiverConnector<Signal<Complex<f32>>>,
+    local_addr: Option<SocketAddr>,
+    _drop_watch: watch::Sender<()>,
+}
+
+impl_block_trait! { Consumer<Signal<Complex<f32>>> for TcpSink }
+
+impl TcpSink {
+    /// Create block which listens on the given address and sends samples to
+    /// connected clients
+    ///
+    /// One client is served at a time. No samples are received from the
+    /// connected [`Producer`] while no client is connected. When the client
+    /// disconnects, the next connection is accepted.
+    pub fn listen<A: ToSocketAddrs>(addr: A) -> io::Result<Self> {
+        let listener = std::net::TcpListener::bind(addr)?;
+        listener.set_nonblocking(true)?;
+        let local_addr = listener.local_addr()?;
+        let listener = TcpListener::from_std(listener)?;
+        let (mut receiver, receiver_connector) = new_receiver::<Signal<Complex<f32>>>();
+        let (drop_watch_send, mut drop_watch_recv) = watch::channel(());
+        spawn(async move {
+            let mut bytes: Vec<u8> = Vec::new();
+            loop {
+                let stream = select! {
+                    _ = drop_watch_recv.changed() => return,
+                    result = listener.accept() => match result {
+                        Ok((stream, _)) => stream,
+                        Err(_) => continue,
+                    },
+                };
+                if !send_to_stream(stream, &mut receiver, &mut drop_watch_recv, &mut bytes).await {
+                    return;
+                }
+            }
+        });
+        Ok(Self {
+            receiver_connector,
+            local_addr: Some(local_addr),
+            _drop_watch: drop_watch_send,
+        })
+    }
+    /// Create block which connects to the given address and sends samples
+    ///
+    /// The block stops working when the connection is lost.
+    pub fn connect<A: ToSocketAddrs>(addr: A) -> io::Result<Self> {
+        let stream = std::net::TcpStream::connect(addr)?;
+        stream.set_nonblocking(true)?;
+        let stream = TcpStream::from_std(stream)?;
+        let (mut receiver, receiver_connector) = new_receiver::<Signal<Complex<f32>>>();
+        let (drop_watch_send, mut drop_watch_recv) = watch::channel(());
+        spawn(async move {
+            let mut bytes: Vec<u8> = Vec::new();
+            send_to_stream(stream, &mut receiver, &mut drop_watch_recv, &mut bytes).await;
+        });
+        Ok(Self {
+            receiver_connector,
+            local_addr: None,
+            _drop_watch: drop_watch_send,
+        })
+    }
+    /// Local address the block is listening on (if created with
+    /// [`TcpSink::listen`])
+    pub fn local_addr(&self) -> Option<SocketAddr> {
+        self.local_addr
+    }
+}
+
+/// Send frames to `stream` until the connection is lost (returns `true`) or
+/// the block is dropped (returns `false`)
+async fn send_to_stream(
+    mut stream: TcpStream,
+    receiver: &mut Receiver<Signal<Complex<f32>>>,
+    drop_watch_recv: &mut watch::Receiver<()>,
+    bytes: &mut Vec<u8>,
+) -> bool {
+    let _ = stream.set_nodelay(true);
+    loop {
+        let signal = select! {
+            _ = drop_watch_recv.changed() => return false,
+            result = receiver.recv() => match result {
+                Ok(signal) => signal,
+                Err(_) => return false,
+            },
+        };
+        if let Signal::Samples { sample_rate, chunk } = signal {
+            encode_tcp_frame(sample_rate, &chunk, bytes);
+            if stream.write_all(bytes).await.is_err() {
+                return true;
+            }
+        }
+    }
+}
+
+/// Length of the header of packets sent by [`UdpSink`]
+const UDP_HEADER_LEN: usize = 16;
+
+/// Default maximum packet size of [`UdpSink`] (Ethernet MTU minus IPv4 and
+/// UDP headers)
+const DEFAULT_UDP_MTU: usize = 1472;
+
+/// Maximum size of UDP payload
+const MAX_UDP_LEN: usize = 65507;
+
+fn encode_udp_packet(
+    sequence: u32,
+    sample_rate: f64,
+    samples: &[Complex<f32>],
+    bytes: &mut Vec<u8>,
+) {
+    bytes.clear();
+    bytes.extend_from_slice(&sequence.to_le_bytes());
+    bytes.extend_from_slice(&(samples.len() as u32).to_le_bytes());
+    bytes.extend_from_slice(&sample_rate.to_le_bytes());
+    for sample in samples.iter() {
+        bytes.extend_from_slice(&sample.re.to_le_bytes());
+        bytes.extend_from_slice(&sample.im.to_le_bytes());
+    }
+}
+
+/// Block which receives samples over UDP (as sent by [`UdpSink`]) and acts as
+/// a [`Producer`]
+///
+/// Each packet consists of a sequence number (32 bit unsigned integer), the
+/// number of samples (32 bit unsigned integer), the sample rate (64 bit
+/// floating point), and the samples as interleaved I/Q data (32 bit floating
+/// point), all little endian. Each packet results in one chunk.
+///
+/// UDP doesn't provide backpressure. Packets which arrive while the
+/// connected [`Consumer`]s are busy may be dropped by the operating system.
+/// Lost packets are detected by means of the sequence numbers and reported
+/// with an [`events::Discontinuity`] event. Packets arriving out of order
+/// (after a later packet has been received) and malformed packets are
+/// ignored.
+pub struct UdpSource {
+    sender_connector: SenderConnector<Signal<Complex<f32>>>,
+    local_addr: SocketAddr,
+    _drop_watch: watch::Sender<()>,
+}
+
+impl_block_trait! { Producer<Signal<Complex<f32>>> for UdpSource }
+
+impl UdpSource {
+    /// Create block which receives packets on the given address
+    pub fn bind<A: ToSocketAddrs>(addr: A) -> io::Result<Self> {
+        let socket = std::net::UdpSocket::bind(addr)?;
+        socket.set_nonblocking(true)?;
+        let local_addr = socket.local_addr()?;
+        let socket = UdpSocket::from_std(socket)?;
+        let (sender, sender_connector) = new_sender::<Signal<Complex<f32>>>();
+        let (drop_watch_send, mut drop_watch_recv) = watch::channel(());
+        let mut buf_pool = ChunkBufPool::<Complex<f32>>::new();
+        let receive = async move {
+            let mut bytes = vec![0u8; MAX_UDP_LEN];
+            let mut expected: Option<u32> = None;
+            loop {
+                let Ok(packet_len) = socket.recv(&mut bytes).await else { continue; };
+                let packet = &bytes[0..packet_len];
+                if packet_len < UDP_HEADER_LEN {
+                    continue;
+                }
+                let sequence = u32::from_le_bytes(packet[0..4].try_into().unwrap());
+                let len = u32::from_le_bytes(packet[4..8].try_into().unwrap()) as usize;
+                let sample_rate = f64::from_le_bytes(packet[8..16].try_into().unwrap());
+                if packet_len != UDP_HEADER_LEN + len * 8 {
+                    continue;
+                }
+                if let Some(expected) = expected {
+                    let lost_packets = sequence.wrapping_sub(expected);
+                    if lost_packets >= 1 << 31 {
+                        continue;
+                    }
+                    if lost_packets > 0 {
+                        let event = Signal::Event(Arc::new(Discontinuity { lost_packets }));
+                        let Ok(()) = sender.send(event).await else { return; };
+                    }
+                }
+                expected = Some(sequence.wrapping_add(1));
+                let mut output_chunk = buf_pool.get_with_capacity(len);
+                for value in packet[UDP_HEADER_LEN..].chunks_exact(8) {
+                    output_chunk.push(Complex::new(
+                        f32::from_le_bytes(value[0..4].try_into().unwrap()),
+                        f32::from_le_bytes(value[4..8].try_into().unwrap()),
+                    ));
+                }
+                let Ok(()) = sender
+                    .send(Signal::Samples {
+                        sample_rate,
+                        chunk: output_chunk.finalize(),
+                    })
+                    .await
+                else { return; };
+            }
+        };
+        spawn(async move {
+            select! {
+                _ = drop_watch_recv.changed() => (),
+                _ = receive => (),
+            }
+        });
+        Ok(Self {
+            sender_connector,
+            local_addr,
+            _drop_watch: drop_watch_send,
+        })
+    }
+    /// Local address the block is receiving on
+    pub fn local_addr(&self) -> SocketAddr {
+        self.local_addr
+    }
+}
+
+/// Block which sends samples over UDP (to a [`UdpSource`]) and acts as a
+/// [`Consumer`]
+///
+/// See [`UdpSource`] for a description of the used packet format. Chunks are
+/// split into several packets, such that no packet exceeds the configured
+/// maximum packet size (see [`UdpSink::set_mtu`]). [`Signal::Event`]s are not
+/// transferred.
+pub struct UdpSink {
+    receiver_connector: ReceiverConnector<Signal<Complex<f32>>>,
+    mtu: watch::Sender<usize>,
+    _drop_watch: watch::Sender<()>,
+}
+
+impl_block_trait! { Consumer<Signal<Complex<f32>>> for UdpSink }
+
+impl UdpSink {
+    /// Create block which sends packets to the given address
+    ///
+    /// The address is resolved once when calling this function, and the
+    /// socket is bound to an unspecified address with an arbitrary port.
+    pub fn connect<A: ToSocketAddrs>(addr: A) -> io::Result<Self> {
+        let addrs: Vec<SocketAddr> = addr.to_socket_addrs()?.collect();
+        if addrs.is_empty() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "could not resolve address",
+            ));
+        }
+        let addr = addrs[0];
+        let bind_ip = match addr {
+            SocketAddr::V4(_) => IpAddr::V4(Ipv4Addr::UNSPECIFIED),
+            SocketAddr::V6(_) => IpAddr::V6(Ipv6Addr::UNSPECIFIED),
+        };
+        let socket = std::net::UdpSocket::bind(SocketAddr::new(bind_ip, 0))?;
+        socket.connect(addr)?;
+        socket.set_nonblocking(true)?;
+        let socket = UdpSocket::from_std(socket)?;
+        let (mut receiver, receiver_connector) = new_receiver::<Signal<Complex<f32>>>();
+        let (mtu, mtu_recv) = watch::channel(DEFAULT_UDP_MTU);
+        let (drop_watch_send, mut drop_watch_recv) = watch::channel(());
+        spawn(async move {
+            let mut bytes: Vec<u8> = Vec::new();
+            let mut sequence: u32 = 0;
+            loop {
+                let signal = select! {
+                    _ = drop_watch_recv.changed() => return,
+                    result = receiver.recv() => match result {
+                        Ok(signal) => signal,
+                        Err(_) => return,
+                    },
+                };
+                if let Signal::Samples { sample_rate, chunk } = signal {
+                    let max_len = (*mtu_recv.borrow() - UDP_HEADER_LEN) / 8;
+                    for samples in chunk.chunks(max_len) {
+                        encode_udp_packet(sequence, sample_rate, samples, &mut bytes);
+                        sequence = sequence.wrapping_add(1);
+                        // errors (e.g. no receiver) are ignored like lost packets
+                        socket.send(&bytes).await.ok();
+                    }
+                }
+            }
+        });
+        Ok(Self {
+            receiver_connector,
+            mtu,
+            _drop_watch: drop_watch_send,
+        })
+    }
+    /// Get maximum packet size (UDP payload) in bytes
+    pub fn mtu(&self) -> usize {
+        *self.mtu.borrow()
+    }
+    /// Set maximum packet size (UDP payload) in bytes
+    ///
+    /// Defaults to 1472 bytes, which avoids fragmentation on Ethernet
+    /// networks with IPv4. Use smaller values for IPv6 or tunneled
+    /// connections.
+    pub fn set_mtu(&self, mtu: usize) {
+        assert!(
+            (UDP_HEADER_LEN + 8..=MAX_UDP_LEN).contains(&mtu),
+            "MTU must be between {} and {MAX_UDP_LEN} bytes",
+            UDP_HEADER_LEN + 8
+        );
+        self.mtu.send_replace(mtu);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[tokio::test]
+    async fn test_raw_roundtrip() {
+        for format in [
+            SampleFormat::U8,
+            SampleFormat::I8,
+            SampleFormat::I16Le,
+            SampleFormat::I16Be,
+            SampleFormat::F32Le,
+            SampleFormat::F32Be,
+        ] {
+            let path = std::env::temp_dir().join(format!(
+                "radiorust_test_raw_{}_{format:?}.bin",
+                std::process::id()
+            ));
+            let samples: Vec<Complex<f32>> = (0..100)
+                .map(|i| Complex::new(i as f32 / 100.0, -(i as f32) / 200.0))
+                .collect();
+            let (sender, sender_connector) = new_sender::<Signal<Complex<f32>>>();
+            let sink = RawSink::new(&path, format).unwrap();
+            sink.feed_from(&sender_connector);
+            sender
+                .send(Signal::Samples {
+                    sample_rate: 2048000.0,
+                    chunk: Chunk::from(samples.clone()),
+                })
+                .await
+                .unwrap();
+            sender
+                .send(Signal::Event(Arc::new(EndOfFile)))
+                .await
+                .unwrap();
+            sink.finalize().await.unwrap();
+            assert_eq!(
+                std::fs::metadata(&path).unwrap().len(),
+                (samples.len() * 2 * format.bytes_per_value()) as u64
+            );
+            let source = RawSource::new(&path, format, 2048000.0, 30).unwrap();
+            let (mut receiver, receiver_connector) = new_receiver::<Signal<Complex<f32>>>();
+            source.feed_into(&receiver_connector);
+            let mut received: Vec<Complex<f32>> = Vec::new();
+            while let Signal::Samples { sample_rate, chunk } = receiver.recv().await.unwrap() {
+                assert_eq!(sample_rate, 2048000.0);
+                received.extend_from_slice(&chunk);
+            }
+            std::fs::remove_file(&path).unwrap();
+            let tolerance = match format.bytes_per_value() {
+                1 => 1.0 / 127.0,
+                _ => 1e-4,
+            };
+            assert_eq!(received.len(), samples.len());
+            for (a, b) in received.iter().zip(samples.iter()) {
+                assert!((a.re - b.re).abs() <= tolerance);
+                assert!((a.im - b.im).abs() <= tolerance);
+            }
+        }
+    }
+    #[tokio::test]
+    async fn test_raw_planar_roundtrip() {
+        for format in [SampleFormat::I16Le, SampleFormat::F32Be] {
+            let path = std::env::temp_dir().join(format!(
+                "radiorust_test_raw_planar_{}_{format:?}.bin",
+                std::process::id()
+            ));
+            let samples: Vec<Complex<f32>> = (0..140)
+                .map(|i| Complex::new(i as f32 / 200.0, -(i as f32) / 400.0))
+                .collect();
+            let (sender, sender_connector) = new_sender::<Signal<Complex<f32>>>();
+            let sink = RawSink::with_layout(&path, format, IqLayout::Planar).unwrap();
+            sink.feed_from(&sender_connector);
+            for part in [&samples[0..100], &samples[100..]] {
+                sender
+                    .send(Signal::Samples {
+                        sample_rate: 48000.0,
+                        chunk: Chunk::from(part.to_vec()),
+                    })
+                    .await
+                    .unwrap();
+            }
+            sender
+                .send(Signal::Event(Arc::new(EndOfFile)))
+                .await
+                .unwrap();
+            sink.finalize().await.unwrap();
+            let bytes = std::fs::read(&path).unwrap();
+            assert_eq!(
+                bytes.len(),
+                2 * 4 + samples.len() * 2 * format.bytes_per_value()
+            );
+            assert_eq!(&bytes[0..4], &100u32.to_le_bytes());
+            assert_eq!(format.decode(&bytes[4..]), samples[0].re);
+            assert_eq!(
+                format.decode(&bytes[4 + 100 * format.bytes_per_value()..]),
+                samples[0].im
+            );
+            let source =
+                RawSource::with_layout(&path, format, IqLayout::Planar, 48000.0, 30).unwrap();
+            let (mut receiver, receiver_connector) = new_receiver::<Signal<Complex<f32>>>();
+            source.feed_into(&receiver_connector);
+            let mut chunk_lens: Vec<usize> = Vec::new();
+            let mut received: Vec<Complex<f32>> = Vec::new();
+            while let Signal::Samples { sample_rate, chunk } = receiver.recv().await.unwrap() {
+                assert_eq!(sample_rate, 48000.0);
+                chunk_lens.push(chunk.len());
+                received.extend_from_slice(&chunk);
+            }
+            std::fs::remove_file(&path).unwrap();
+            assert_eq!(chunk_lens, [30, 30, 30, 10, 30, 10]);
+            assert_eq!(received.len(), samples.len());
+            for (a, b) in received.iter().zip(samples.iter()) {
+                assert!((a.re - b.re).abs() <= 1e-4);
+                assert!((a.im - b.im).abs() <= 1e-4);
+            }
+        }
+    }
+    #[tokio::test]
+    async fn test_raw_skip_and_loop() {
+        let path = std::env::temp_dir().join(format!(
+            "radiorust_test_raw_skip_{}.bin",
+            std::process::id()
+        ));
+        let mut bytes = b"HEADER".to_vec();
+        for value in [1000i16, -1000, 2000, -2000, 3000, -3000] {
+            bytes.extend_from_slice(&value.to_be_bytes());
+        }
+        std::fs::write(&path, bytes).unwrap();
+        let options = RawSourceOptions {
+            skip_bytes: 6,
+            looping: true,
+            ..Default::default()
+        };
+        let source =
+            RawSource::with_options(&path, SampleFormat::I16Be, 1000.0, 2, options).unwrap();
+        let (mut receiver, receiver_connector) = new_receiver::<Signal<Complex<f32>>>();
+        source.feed_into(&receiver_connector);
+        for _ in 0..3 {
+            let mut received: Vec<Complex<f32>> = Vec::new();
+            loop {
+                match receiver.recv().await.unwrap() {
+                    Signal::Samples { chunk, .. } => received.extend_from_slice(&chunk),
+                    Signal::Event(event) if event.as_any().is::<Repositioned>() => (),
+                    Signal::Event(event) => {
+                        assert!(event.as_any().is::<EndOfFile>());
+                        break;
+                    }
+                }
+            }
+            assert_eq!(received.len(), 3);
+            for (index, sample) in received.iter().enumerate() {
+                let expected = (index + 1) as f32 * 1000.0 / 32768.0;
+                assert_eq!(sample.re, expected);
+                assert_eq!(sample.im, -expected);
+            }
+        }
+        drop(source);
+        std::fs::remove_file(&path).unwrap();
+    }
+    #[tokio::test]
+    async fn test_raw_seek() {
+        for layout in [IqLayout::Interleaved, IqLayout::Planar] {
+            let path = std::env::temp_dir().join(format!(
+                "radiorust_test_raw_seek_{}_{layout:?}.bin",
+                std::process::id()
+            ));
+            let samples: Vec<Complex<f32>> =
+                (0..140).map(|i| Complex::new(i as f32, 0.0)).collect();
+            let (sender, sender_connector) = new_sender::<Signal<Complex<f32>>>();
+            let sink = RawSink::with_layout(&path, SampleFormat::F32Le, layout).unwrap();
+            sink.feed_from(&sender_connector);
+            for part in [&samples[0..100], &samples[100..]] {
+                sender
+                    .send(Signal::Samples {
+                        sample_rate: 48000.0,
+                        chunk: Chunk::from(part.to_vec()),
+                    })
+                    .await
+                    .unwrap();
+            }
+            sender
+                .send(Signal::Event(Arc::new(EndOfFile)))
+                .await
+                .unwrap();
+            sink.finalize().await.unwrap();
+            let source =
+                RawSource::with_layout(&path, SampleFormat::F32Le, layout, 48000.0, 10).unwrap();
+            let (mut receiver, receiver_connector) = new_receiver::<Signal<Complex<f32>>>();
+            source.feed_into(&receiver_connector);
+            let Signal::Samples { chunk, .. } = receiver.recv().await.unwrap()
+            else { panic!(); };
+            assert_eq!(chunk[0].re, 0.0);
+            for (target, expected) in [(120, 120), (50, 50), (1000, 140)] {
+                source.seek(target);
+                let position = loop {
+                    if let Signal::Event(event) = receiver.recv().await.unwrap() {
+                        if let Some(event) = event.as_any().downcast_ref::<Repositioned>() {
+                            break event.position;
+                        }
+                    }
+                };
+                assert_eq!(position, expected);
+                match receiver.recv().await.unwrap() {
+                    Signal::Samples { chunk, .. } => assert_eq!(chunk[0].re, expected as f32),
+                    Signal::Event(event) => {
+                        assert_eq!(expected, 140);
+                        assert!(event.as_any().is::<EndOfFile>());
+                    }
+                }
+            }
+            assert_eq!(source.position(), 140);
+            drop(source);
+            std::fs::remove_file(&path).unwrap();
+        }
+    }
+    #[tokio::test]
+    async fn test_raw_saturation() {
+        for (format, scale, expected) in [
+            (SampleFormat::I16Le, 1.0, [32767, -32768, 32767, 16384]),
+            (SampleFormat::I16Le, 0.5, [32767, -32768, 16384, 8192]),
+            (SampleFormat::I8, 1.0, [127, -128, 127, 64]),
+        ] {
+            let path = std::env::temp_dir().join(format!(
+                "radiorust_test_raw_saturation_{}_{format:?}_{scale}.bin",
+                std::process::id()
+            ));
+            let (sender, sender_connector) = new_sender::<Signal<Complex<f32>>>();
+            let sink = RawSink::new(&path, format).unwrap();
+            sink.set_scale(scale);
+            assert_eq!(sink.scale(), scale);
+            sink.feed_from(&sender_connector);
+            sender
+                .send(Signal::Samples {
+                    sample_rate: 48000.0,
+                    chunk: Chunk::from(vec![Complex::new(3.0, -3.0), Complex::new(1.0, 0.5)]),
+                })
+                .await
+                .unwrap();
+            sender
+                .send(Signal::Event(Arc::new(EndOfFile)))
+                .await
+                .unwrap();
+            sink.finalize().await.unwrap();
+            let bytes = std::fs::read(&path).unwrap();
+            std::fs::remove_file(&path).unwrap();
+            let values: Vec<i32> = match format {
+                SampleFormat::I8 => bytes.iter().map(|&x| x as i8 as i32).collect(),
+                _ => bytes
+                    .chunks_exact(2)
+                    .map(|x| i16::from_le_bytes([x[0], x[1]]) as i32)
+                    .collect(),
+            };
+            assert_eq!(values, expected);
+        }
+    }
+    #[test]
+    fn test_u8_offset() {
+        assert_eq!(SampleFormat::U8.decode(&[0]), -1.0);
+        assert_eq!(SampleFormat::U8.decode(&[255]), 1.0);
+        let mut buf = Vec::new();
+        SampleFormat::U8.encode(0.0, &mut buf);
+        SampleFormat::U8.encode(-1.0, &mut buf);
+        SampleFormat::U8.encode(1.0, &mut buf);
+        assert_eq!(buf, [128, 0, 255]);
+    }
+    #[tokio::test]
+    async fn test_wav_roundtrip() {
+        for format in [WavFormat::Pcm16, WavFormat::Float32] {
+            let path = std::env::temp_dir().join(format!(
+                "radiorust_test_wav_{}_{format:?}.wav",
+                std::process::id()
+            ));
+            let samples: Vec<Complex<f32>> = (0..100)
+                .map(|i| Complex::new(i as f32 / 200.0, -(i as f32) / 400.0))
+                .collect();
+            let (sender, sender_connector) = new_sender::<Signal<Complex<f32>>>();
+            let sink = WavSink::new(&path, format).unwrap();
+            sink.feed_from(&sender_connector);
+            sender
+                .send(Signal::Samples {
+                    sample_rate: 48000.0,
+                    chunk: Chunk::from(samples.clone()),
+                })
+                .await
+                .unwrap();
+            sender
+                .send(Signal::Event(Arc::new(EndOfFile)))
+                .await
+                .unwrap();
+            sink.finalize().await.unwrap();
+            let source = WavSource::new(&path, 64).unwrap();
+            assert_eq!(source.format(), format);
+            assert_eq!(source.sample_rate(), 48000.0);
+            let (mut receiver, receiver_connector) = new_receiver::<Signal<Complex<f32>>>();
+            source.feed_into(&receiver_connector);
+            let mut received: Vec<Complex<f32>> = Vec::new();
+            loop {
+                match receiver.recv().await.unwrap() {
+                    Signal::Samples { sample_rate, chunk } => {
+                        assert_eq!(sample_rate, 48000.0);
+                        received.extend_from_slice(&chunk);
+                    }
+                    Signal::Event(event) => {
+                        assert!(event.as_any().is::<EndOfFile>());
+                        break;
+                    }
+                }
+            }
+            std::fs::remove_file(&path).unwrap();
+            assert_eq!(received.len(), samples.len());
+            for (a, b) in received.iter().zip(samples.iter()) {
+                assert!((a - b).norm() < 1e-4);
+            }
+        }
+    }
+    #[tokio::test]
+    async fn test_end_of_file_flush() {
+        use crate::blocks::filters::FirFilter;
+        let raw_path =
+            std::env::temp_dir().join(format!("radiorust_test_flush_{}.bin", std::process::id()));
+        let wav_path =
+            std::env::temp_dir().join(format!("radiorust_test_flush_{}.wav", std::process::id()));
+        let samples: Vec<Complex<f32>> =
+            (0..100).map(|i| Complex::from(i as f32 / 100.0)).collect();
+        let bytes: Vec<u8> = samples
+            .iter()
+            .flat_map(|x| [x.re.to_le_bytes(), x.im.to_le_bytes()])
+            .flatten()
+            .collect();
+        std::fs::write(&raw_path, bytes).unwrap();
+        let (sender, sender_connector) = new_sender::<Signal<Complex<f32>>>();
+        let sink = WavSink::new(&wav_path, WavFormat::Float32).unwrap();
+        sink.feed_from(&sender_connector);
+        sender
+            .send(Signal::Samples {
+                sample_rate: 48000.0,
+                chunk: Chunk::from(samples.clone()),
+            })
+            .await
+            .unwrap();
+        sender.send_event(EndOfFile).await.unwrap();
+        sink.finalize().await.unwrap();
+        let raw_source = RawSource::new(&raw_path, SampleFormat::F32Le, 48000.0, 30).unwrap();
+        let wav_source = WavSource::new(&wav_path, 30).unwrap();
+        for source in [raw_source.sender_connector(), wav_source.sender_connector()] {
+            // a FIR filter with three taps adds a tail of two samples
+            let filter = FirFilter::<f32>::new(vec![1.0, 1.0, 1.0].into());
+            filter.feed_from(source);
+            let (mut receiver, receiver_connector) = new_receiver::<Signal<Complex<f32>>>();
+            filter.feed_into(&receiver_connector);
+            let mut received: Vec<Complex<f32>> = Vec::new();
+            loop {
+                match receiver.recv().await.unwrap() {
+                    Signal::Samples { chunk, .. } => received.extend_from_slice(&chunk),
+                    Signal::Event(event) => {
+                        assert!(event.is_flush());
+                        assert!(event.as_any().is::<EndOfFile>());
+                        break;
+                    }
+                }
+            }
+            assert_eq!(received.len(), 102);
+            assert!((received[100].re - 1.97).abs() < 1e-4);
+            assert!((received[101].re - 0.99).abs() < 1e-4);
+        }
+        std::fs::remove_file(&raw_path).unwrap();
+        std::fs::remove_file(&wav_path).unwrap();
+    }
+    #[test]
+    fn test_format_datetime() {
+        let time = UNIX_EPOCH + std::time::Duration::from_millis(951_868_800_123);
+        assert_eq!(format_datetime(time), "2000-03-01T00:00:00.123Z");
+        let time = UNIX_EPOCH + std::time::Duration::from_secs(1_790_000_000);
+        assert_eq!(format_datetime(time), "2026-09-21T14:13:20.000Z");
+    }
+    #[tokio::test]
+    async fn test_sigmf_roundtrip() {
+        let base =
+            std::env::temp_dir().join(format!("radiorust_test_sigmf_{}", std::process::id()));
+        let samples: Vec<Complex<f32>> = (0..100)
+            .map(|i| Complex::new(i as f32 / 200.0, -(i as f32) / 400.0))
+            .collect();
+        let (sender, sender_connector) = new_sender::<Signal<Complex<f32>>>();
+        let sink = SigMfSink::new(&base, SampleFormat::F32Le).unwrap();
+        sink.feed_from(&sender_connector);
+        sink.set_frequency(Some(433.92e6));
+        let annotation = SigMfAnnotation {
+            sample_start: 10,
+            sample_count: Some(20),
+            freq_lower_edge: Some(433.9e6),
+            freq_upper_edge: Some(433.94e6),
+            label: Some("burst".to_owned()),
+        };
+        sink.add_annotation(annotation.clone());
+        sender
+            .send(Signal::new_event(Timestamp(1_600_000_000_123_000_000)))
+            .await
+            .unwrap();
+        sender
+            .send(Signal::Samples {
+                sample_rate: 1e6,
+                chunk: Chunk::from(samples.clone()),
+            })
+            .await
+            .unwrap();
+        sender
+            .send(Signal::Event(Arc::new(EndOfFile)))
+            .await
+            .unwrap();
+        sink.finalize().await.unwrap();
+        let (meta_path, data_path) = sigmf_paths(&base);
+        let source = SigMfSource::new(&meta_path, 64).unwrap();
+        assert_eq!(source.format(), SampleFormat::F32Le);
+        assert_eq!(source.sample_rate(), 1e6);
+        assert_eq!(source.frequency(), Some(433.92e6));
+        assert_eq!(source.datetime(), Some("2020-09-13T12:26:40.123Z"));
+        assert_eq!(source.annotations(), &[annotation]);
+        let (mut receiver, receiver_connector) = new_receiver::<Signal<Complex<f32>>>();
+        source.feed_into(&receiver_connector);
+        let mut received: Vec<Complex<f32>> = Vec::new();
+        while let Signal::Samples { chunk, .. } = receiver.recv().await.unwrap() {
+            received.extend_from_slice(&chunk);
+        }
+        std::fs::remove_file(&meta_path).unwrap();
+        std::fs::remove_file(&data_path).unwrap();
+        assert_eq!(received, samples);
+    }
+    #[tokio::test]
+    async fn test_capture_roundtrip() {
+        let path =
+            std::env::temp_dir().join(format!("radiorust_test_capture_{}", std::process::id()));
+        let samples: Vec<Complex<f32>> = (0..100)
+            .map(|i| Complex::new(i as f32 / 200.0, -(i as f32) / 400.0))
+            .collect();
+        let (sender, sender_connector) = new_sender::<Signal<Complex<f32>>>();
+        let sink = CaptureSink::new(&path, SampleFormat::I16Le).unwrap();
+        sink.feed_from(&sender_connector);
+        sink.set_gain(Some(20.5));
+        sink.set_antenna(Some("RX2".to_owned()));
+        for event in [
+            Signal::new_event(Timestamp(1_600_000_000_123_000_000)),
+            Signal::new_event(CenterFrequency(145.8e6)),
+        ] {
+            sender.send(event).await.unwrap();
+        }
+        sender
+            .send(Signal::Samples {
+                sample_rate: 48e3,
+                chunk: Chunk::from(samples.clone()),
+            })
+            .await
+            .unwrap();
+        sender
+            .send(Signal::new_event(CenterFrequency(146e6)))
+            .await
+            .unwrap();
+        sender
+            .send(Signal::Event(Arc::new(EndOfFile)))
+            .await
+            .unwrap();
+        sink.finalize().await.unwrap();
+        let (meta_path, data_path) = capture_paths(&path);
+        let source = CaptureSource::new(&path, 64).unwrap();
+        assert_eq!(source.format(), SampleFormat::I16Le);
+        assert_eq!(source.sample_rate(), 48e3);
+        assert_eq!(source.frequency(), Some(145.8e6));
+        assert_eq!(source.gain(), Some(20.5));
+        assert_eq!(source.antenna(), Some("RX2"));
+        assert_eq!(source.datetime(), Some("2020-09-13T12:26:40.123Z"));
+        let (mut receiver, receiver_connector) = new_receiver::<Signal<Complex<f32>>>();
+        source.feed_into(&receiver_connector);
+        let Signal::Event(event) = receiver.recv().await.unwrap() else {
+            panic!("expected event");
+        };
+        let frequency = event.as_any().downcast_ref::<CenterFrequency>().unwrap();
+        assert_eq!(frequency.0, 145.8e6);
+        let mut received: Vec<Complex<f32>> = Vec::new();
+        while let Signal::Samples { sample_rate, chunk } = receiver.recv().await.unwrap() {
+            assert_eq!(sample_rate, 48e3);
+            received.extend_from_slice(&chunk);
+        }
+        std::fs::remove_file(&meta_path).unwrap();
+        std::fs::remove_file(&data_path).unwrap();
+        assert_eq!(received.len(), samples.len());
+        for (a, b) in received.iter().zip(samples.iter()) {
+            assert!((a - b).norm() < 1e-4);
+        }
+    }
+    #[tokio::test]
+    async fn test_udp_roundtrip() {
+        let source = UdpSource::bind("127.0.0.1:0").unwrap();
+        let sink = UdpSink::connect(source.local_addr()).unwrap();
+        sink.set_mtu(UDP_HEADER_LEN + 8 * 40);
+        let (sender, sender_connector) = new_sender::<Signal<Complex<f32>>>();
+        sink.feed_from(&sender_connector);
+        let (mut receiver, receiver_connector) = new_receiver::<Signal<Complex<f32>>>();
+        source.feed_into(&receiver_connector);
+        let samples: Vec<Complex<f32>> = (0..100)
+            .map(|i| Complex::new(i as f32, -(i as f32)))
+            .collect();
+        sender
+            .send(Signal::Samples {
+                sample_rate: 48000.0,
+                chunk: Chunk::from(samples.clone()),
+            })
+            .await
+            .unwrap();
+        let mut received: Vec<Complex<f32>> = Vec::new();
+        for expected_len in [40, 40, 20] {
+            let Signal::Samples { sample_rate, chunk } = receiver.recv().await.unwrap()
+            else { panic!(); };
+            assert_eq!(sample_rate, 48000.0);
+            assert_eq!(chunk.len(), expected_len);
+            received.extend_from_slice(&chunk);
+        }
+        assert_eq!(received, samples);
+        // simulate two lost packets
+        let socket = std::net::UdpSocket::bind("127.0.0.1:0").unwrap();
+        let mut bytes: Vec<u8> = Vec::new();
+        encode_udp_packet(5, 48000.0, &samples[0..1], &mut bytes);
+        socket.send_to(&bytes, source.local_addr()).unwrap();
+        let Signal::Event(event) = receiver.recv().await.unwrap()
+        else { panic!(); };
+        let event = event.as_any().downcast_ref::<Discontinuity>().unwrap();
+        assert_eq!(event.lost_packets, 2);
+        let Signal::Samples { chunk, .. } = receiver.recv().await.unwrap()
+        else { panic!(); };
+        assert_eq!(&*chunk, &samples[0..1]);
+    }
+    #[tokio::test]
+    async fn test_tcp_roundtrip() {
+        let (sender, sender_connector) = new_sender::<Signal<Complex<f32>>>();
+        let sink = TcpSink::listen("127.0.0.1:0").unwrap();
+        sink.feed_from(&sender_connector);
+        let source = TcpSource::connect(sink.local_addr().unwrap()).unwrap();
+        let (mut receiver, receiver_connector) = new_receiver::<Signal<Complex<f32>>>();
+        source.feed_into(&receiver_connector);
+        let samples: Vec<Complex<f32>> = (0..100)
+            .map(|i| Complex::new(i as f32, -(i as f32)))
+            .collect();
+        for sample_rate in [48000.0, 96000.0] {
+            sender
+                .send(Signal::Samples {
+                    sample_rate,
+                    chunk: Chunk::from(samples.clone()),
+                })
+                .await
+                .unwrap();
+            let Signal::Samples {
+                sample_rate: received_rate,
+                chunk,
+            } = receiver.recv().await.unwrap()
+            else { panic!(); };
+            assert_eq!(received_rate, sample_rate);
+            assert_eq!(&*chunk, &samples[..]);
+        }
+    }
+}
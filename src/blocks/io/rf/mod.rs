@@ -1,6 +1,12 @@
 //! Blocks interfacing RF hardware
 //!
-//! Use feature "`soapysdr`" for SoapySDR support.
+//! Use feature "`soapysdr`" for SoapySDR support. The [`RtlTcp`] block
+//! allows receiving samples from an `rtl_tcp` server without any further
+//! dependencies.
+
+pub mod rtl_tcp;
+
+pub use rtl_tcp::RtlTcp;
 
 #[cfg(feature = "soapysdr")]
 pub mod soapysdr;
@@ -0,0 +1,237 @@
+//! Client for the `rtl_tcp` protocol
+
+use crate::bufferpool::*;
+use crate::flow::*;
+use crate::impl_block_trait;
+use crate::numbers::*;
+use crate::signal::*;
+
+use tokio::io::{AsyncReadExt as _, AsyncWriteExt as _};
+use tokio::net::TcpStream;
+use tokio::select;
+use tokio::sync::watch;
+use tokio::task::spawn;
+
+use std::io::{self, Read as _};
+use std::net::ToSocketAddrs;
+
+const CMD_SET_FREQUENCY: u8 = 0x01;
+const CMD_SET_SAMPLE_RATE: u8 = 0x02;
+const CMD_SET_GAIN_MODE: u8 = 0x03;
+const CMD_SET_GAIN: u8 = 0x04;
+
+fn command(cmd: u8, param: u32) -> [u8; 5] {
+    let param = param.to_be_bytes();
+    [cmd, param[0], param[1], param[2], param[3]]
+}
+
+/// Block which receives samples from an `rtl_tcp` server and acts as a
+/// [`Producer`]
+///
+/// The unsigned 8 bit I/Q data sent by the server is converted to
+/// [`Complex<f32>`] with values ranging from `-1.0` to `1.0`.
+///
+/// Changing the frequency, sample rate, or gain sends the corresponding
+/// command to the server. Note that samples which have been buffered before
+/// a change of the sample rate may be emitted with the new sample rate.
+pub struct RtlTcp {
+    sender_connector: SenderConnector<Signal<Complex<f32>>>,
+    tuner_type: u32,
+    gain_count: u32,
+    frequency: watch::Sender<f64>,
+    sample_rate: watch::Sender<f64>,
+    gain: watch::Sender<Option<f64>>,
+    _drop_watch: watch::Sender<()>,
+}
+
+impl_block_trait! { Producer<Signal<Complex<f32>>> for RtlTcp }
+
+impl RtlTcp {
+    /// Connect to `rtl_tcp` server and create block which emits chunks with
+    /// `chunk_len` samples
+    ///
+    /// The tuner is set to the given `sample_rate` and `frequency` (in hertz)
+    /// with automatic gain.
+    pub fn connect<A: ToSocketAddrs>(
+        addr: A,
+        chunk_len: usize,
+        sample_rate: f64,
+        frequency: f64,
+    ) -> io::Result<Self> {
+        assert!(chunk_len > 0, "chunk length must be positive");
+        let mut stream = std::net::TcpStream::connect(addr)?;
+        let mut header = [0u8; 12];
+        stream.read_exact(&mut header)?;
+        if &header[0..4] != b"RTL0" {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "invalid rtl_tcp header",
+            ));
+        }
+        let tuner_type = u32::from_be_bytes(header[4..8].try_into().unwrap());
+        let gain_count = u32::from_be_bytes(header[8..12].try_into().unwrap());
+        stream.set_nonblocking(true)?;
+        let stream = TcpStream::from_std(stream)?;
+        let (mut read_half, mut write_half) = stream.into_split();
+        let (sender, sender_connector) = new_sender::<Signal<Complex<f32>>>();
+        let (frequency_send, mut frequency_recv) = watch::channel(frequency);
+        let (sample_rate_send, mut sample_rate_recv) = watch::channel(sample_rate);
+        let (gain_send, mut gain_recv) = watch::channel::<Option<f64>>(None);
+        let sample_rate_recv_reader = sample_rate_send.subscribe();
+        let (drop_watch_send, mut drop_watch_recv) = watch::channel(());
+        spawn(async move {
+            let mut commands: Vec<[u8; 5]> = vec![
+                command(CMD_SET_SAMPLE_RATE, sample_rate.round() as u32),
+                command(CMD_SET_FREQUENCY, frequency.round() as u32),
+                command(CMD_SET_GAIN_MODE, 0),
+            ];
+            loop {
+                for command in commands.drain(..) {
+                    let Ok(()) = write_half.write_all(&command).await else { return; };
+                }
+                select! {
+                    result = sample_rate_recv.changed() => match result {
+                        Ok(()) => {
+                            let sample_rate = *sample_rate_recv.borrow_and_update();
+                            commands.push(command(
+                                CMD_SET_SAMPLE_RATE,
+                                sample_rate.round() as u32,
+                            ));
+                        }
+                        Err(_) => return,
+                    },
+                    result = frequency_recv.changed() => match result {
+                        Ok(()) => {
+                            let frequency = *frequency_recv.borrow_and_update();
+                            commands.push(command(CMD_SET_FREQUENCY, frequency.round() as u32));
+                        }
+                        Err(_) => return,
+                    },
+                    result = gain_recv.changed() => match result {
+                        Ok(()) => match *gain_recv.borrow_and_update() {
+                            None => commands.push(command(CMD_SET_GAIN_MODE, 0)),
+                            Some(gain) => {
+                                commands.push(command(CMD_SET_GAIN_MODE, 1));
+                                commands.push(command(
+                                    CMD_SET_GAIN,
+                                    (gain * 10.0).round() as i32 as u32,
+                                ));
+                            }
+                        },
+                        Err(_) => return,
+                    },
+                }
+            }
+        });
+        spawn(async move {
+            let mut buf_pool = ChunkBufPool::<Complex<f32>>::new();
+            let mut bytes = vec![0u8; chunk_len * 2];
+            loop {
+                select! {
+                    _ = drop_watch_recv.changed() => return,
+                    result = read_half.read_exact(&mut bytes) => match result {
+                        Ok(_) => (),
+                        Err(_) => return,
+                    },
+                }
+                let mut output_chunk = buf_pool.get_with_capacity(chunk_len);
+                for pair in bytes.chunks_exact(2) {
+                    output_chunk.push(Complex::new(
+                        (pair[0] as f32 - 127.5) / 127.5,
+                        (pair[1] as f32 - 127.5) / 127.5,
+                    ));
+                }
+                let sample_rate = *sample_rate_recv_reader.borrow();
+                select! {
+                    _ = drop_watch_recv.changed() => return,
+                    result = sender.send(Signal::Samples {
+                        sample_rate,
+                        chunk: output_chunk.finalize(),
+                    }) => match result {
+                        Ok(()) => (),
+                        Err(_) => return,
+                    },
+                }
+            }
+        });
+        Ok(Self {
+            sender_connector,
+            tuner_type,
+            gain_count,
+            frequency: frequency_send,
+            sample_rate: sample_rate_send,
+            gain: gain_send,
+            _drop_watch: drop_watch_send,
+        })
+    }
+    /// Tuner type as reported by the server
+    pub fn tuner_type(&self) -> u32 {
+        self.tuner_type
+    }
+    /// Number of gain values supported by the tuner as reported by the server
+    pub fn gain_count(&self) -> u32 {
+        self.gain_count
+    }
+    /// Get frequency in hertz
+    pub fn frequency(&self) -> f64 {
+        *self.frequency.borrow()
+    }
+    /// Set frequency in hertz
+    pub fn set_frequency(&self, frequency: f64) {
+        self.frequency.send_replace(frequency);
+    }
+    /// Get sample rate in samples per second
+    pub fn sample_rate(&self) -> f64 {
+        *self.sample_rate.borrow()
+    }
+    /// Set sample rate in samples per second
+    pub fn set_sample_rate(&self, sample_rate: f64) {
+        self.sample_rate.send_replace(sample_rate);
+    }
+    /// Get gain in decibels (or `None` for automatic gain)
+    pub fn gain(&self) -> Option<f64> {
+        *self.gain.borrow()
+    }
+    /// Set gain in decibels (or `None` for automatic gain)
+    pub fn set_gain(&self, gain: Option<f64>) {
+        self.gain.send_replace(gain);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write as _;
+    #[tokio::test]
+    async fn test_rtl_tcp() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut header = b"RTL0".to_vec();
+            header.extend_from_slice(&5u32.to_be_bytes());
+            header.extend_from_slice(&29u32.to_be_bytes());
+            stream.write_all(&header).unwrap();
+            stream.write_all(&[0, 255, 255, 0]).unwrap();
+            let mut commands = [0u8; 15];
+            stream.read_exact(&mut commands).unwrap();
+            commands
+        });
+        let rtl_tcp = RtlTcp::connect(addr, 2, 2048000.0, 100e6).unwrap();
+        assert_eq!(rtl_tcp.tuner_type(), 5);
+        assert_eq!(rtl_tcp.gain_count(), 29);
+        let (mut receiver, receiver_connector) = new_receiver::<Signal<Complex<f32>>>();
+        rtl_tcp.feed_into(&receiver_connector);
+        let Signal::Samples { sample_rate, chunk } = receiver.recv().await.unwrap()
+        else { panic!(); };
+        assert_eq!(sample_rate, 2048000.0);
+        assert_eq!(&*chunk, &[Complex::new(-1.0, 1.0), Complex::new(1.0, -1.0)]);
+        let commands = tokio::task::spawn_blocking(move || server.join().unwrap())
+            .await
+            .unwrap();
+        let commands: Vec<&[u8]> = commands.chunks_exact(5).collect();
+        assert_eq!(commands[0], command(CMD_SET_SAMPLE_RATE, 2048000));
+        assert_eq!(commands[1], command(CMD_SET_FREQUENCY, 100000000));
+        assert_eq!(commands[2], command(CMD_SET_GAIN_MODE, 0));
+    }
+}
@@ -1,20 +1,83 @@
 //! Interface to RF hardware through SoapySDR (using the [`soapysdr`] crate)
 
+use crate::blocks::io::thread::{SharedThreadConfig, ThreadConfig};
 use crate::bufferpool::*;
 use crate::flow::*;
 use crate::impl_block_trait;
 use crate::numbers::*;
 use crate::signal::*;
 
+use tokio::runtime;
 use tokio::select;
-use tokio::sync::watch;
-use tokio::task::{spawn, spawn_blocking, JoinHandle};
+use tokio::sync::{mpsc, oneshot, watch};
+use tokio::task::{spawn_blocking, JoinHandle};
 use tokio::time::sleep;
 
-use std::time::{Duration, Instant};
+use std::ops::Deref;
+use std::panic::resume_unwind;
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
 pub use soapysdr::Error;
 
+/// Types used for [`Signal::Event`]
+pub mod events {
+    use super::*;
+    /// Sent by [`SoapySdrRx`] block when the hardware reported an overflow,
+    /// i.e. when samples have been dropped
+    #[derive(Clone, Debug)]
+    pub struct Overflow;
+    impl Event for Overflow {
+        fn is_interrupt(&self) -> bool {
+            true
+        }
+        fn as_any(&self) -> &(dyn std::any::Any + Send + Sync) {
+            self
+        }
+    }
+    /// Sent by [`SoapySdrRx`] block when reading from the hardware timed out
+    ///
+    /// This event is sent only once for several consecutive timeouts.
+    #[derive(Clone, Debug)]
+    pub struct ReadTimeout;
+    impl Event for ReadTimeout {
+        fn as_any(&self) -> &(dyn std::any::Any + Send + Sync) {
+            self
+        }
+    }
+    /// Sent by [`SoapySdrRx`] block when streaming has been resumed after a
+    /// stream error (see [`SoapySdrRxHandle::set_auto_recover`])
+    #[derive(Clone, Debug)]
+    pub struct Recovered {
+        /// Number of attempts needed to reactivate the stream
+        pub attempts: u32,
+    }
+    impl Event for Recovered {
+        fn is_interrupt(&self) -> bool {
+            true
+        }
+        fn as_any(&self) -> &(dyn std::any::Any + Send + Sync) {
+            self
+        }
+    }
+    /// When received by a [`SoapySdrTx`] block, the current burst is ended
+    /// (using the `END_BURST` flag), such that the transmitter may be
+    /// switched off until the next chunk is received
+    #[derive(Clone, Debug)]
+    pub struct EndOfBurst;
+    impl Event for EndOfBurst {
+        fn as_any(&self) -> &(dyn std::any::Any + Send + Sync) {
+            self
+        }
+    }
+}
+use events::*;
+
+const DEFAULT_READ_TIMEOUT: i64 = 1000000;
+
+/// Number of samples read per chunk if the driver doesn't report an MTU
+const FALLBACK_MTU: usize = 4096;
+
 #[derive(Clone, PartialEq, Eq, Debug)]
 enum Request {
     Deactivate,
@@ -29,29 +92,358 @@ enum State {
     Closed(Result<(), Error>),
 }
 
-/// Block which wraps an [`::soapysdr::RxStream`] and acts as a
-/// [`Producer<Signal<Complex<Flt>>>`]
-pub struct SoapySdrRx {
-    sender_connector: SenderConnector<Signal<Complex<f32>>>,
+/// Reason why a [`SoapySdrRx`] or [`SoapySdrRxMulti`] block stopped
+/// streaming
+///
+/// See [`SoapySdrRxHandle::stop_reason`].
+#[derive(Clone, Debug)]
+pub enum StopReason {
+    /// Streaming was deactivated on request
+    Deactivated,
+    /// The stream was closed, because the block has been consumed (e.g. by
+    /// [`SoapySdrRx::into_inner`]) or because the block and all handles
+    /// have been dropped
+    Closed,
+    /// Samples could not be sent, because the block has been dropped and no
+    /// consumers were connected anymore
+    ///
+    /// The stream is deactivated (but not closed) in this case.
+    Disconnected,
+    /// A stream error occurred (and could not be recovered from)
+    Failed(Error),
+}
+
+/// Send `signal` to all `senders`, unless a request to deactivate or close
+/// the stream is received while waiting for the consumers
+///
+/// Waiting for a slow consumer thus doesn't delay deactivation. Returns the
+/// reason to stop streaming, if any.
+async fn send_or_stop<T: Clone>(
+    senders: &[Sender<T>],
+    signal: T,
+    requests: &mut watch::Receiver<Request>,
+) -> Result<(), StopReason> {
+    for sender in senders {
+        let send = sender.send(signal.clone());
+        tokio::pin!(send);
+        loop {
+            select! {
+                result = &mut send => match result {
+                    Ok(()) => break,
+                    Err(_) => return Err(StopReason::Disconnected),
+                },
+                changed = requests.changed() => match changed {
+                    Ok(()) => match *requests.borrow_and_update() {
+                        Request::Deactivate => return Err(StopReason::Deactivated),
+                        Request::Activate => (),
+                        Request::Close => return Err(StopReason::Closed),
+                    },
+                    Err(_) => return Err(StopReason::Closed),
+                },
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Retry state while recovering from stream errors
+///
+/// The waiting time before the first attempt is `backoff`, and it doubles
+/// after each failed attempt.
+struct Backoff {
+    attempts: u32,
+    delay: Duration,
+}
+
+impl Backoff {
+    fn new() -> Self {
+        Self {
+            attempts: 0,
+            delay: Duration::ZERO,
+        }
+    }
+    /// Count next attempt and return the time to wait before it, or `None`
+    /// if `max_retries` attempts have been made already
+    fn next_delay(&mut self, max_retries: u32, backoff: Duration) -> Option<Duration> {
+        if self.attempts >= max_retries {
+            return None;
+        }
+        self.delay = match self.attempts {
+            0 => backoff,
+            _ => self.delay.saturating_mul(2),
+        };
+        self.attempts += 1;
+        Some(self.delay)
+    }
+    /// Reset after a successful attempt and return the number of attempts
+    fn succeeded(&mut self) -> u32 {
+        std::mem::replace(self, Self::new()).attempts
+    }
+}
+
+/// Current time in nanoseconds, using the hardware clock if available
+fn current_time_ns(device: &soapysdr::Device, hardware_time: bool) -> i64 {
+    if hardware_time {
+        if let Ok(time_ns) = device.get_hardware_time(None) {
+            return time_ns;
+        }
+    }
+    match SystemTime::now().duration_since(UNIX_EPOCH) {
+        Ok(duration) => duration.as_nanos() as i64,
+        Err(_) => 0,
+    }
+}
+
+/// Run blocking driver call in a separate thread
+///
+/// If the call panics, the panic is propagated with its original payload.
+async fn blocking<R, F>(f: F) -> R
+where
+    R: Send + 'static,
+    F: FnOnce() -> R + Send + 'static,
+{
+    match spawn_blocking(f).await {
+        Ok(result) => result,
+        Err(err) => resume_unwind(err.into_panic()),
+    }
+}
+
+/// Wait until `task` has finished and publish the reason if it failed
+///
+/// Returns the stream unless the task panicked.
+async fn supervise<S>(
+    task: JoinHandle<S>,
+    state: watch::Receiver<State>,
+    failure: watch::Sender<Option<crate::error::Error>>,
+) -> Option<S> {
+    match task.await {
+        Ok(stream) => {
+            if let State::Closed(Err(err)) = &*state.borrow() {
+                failure.send_replace(Some(err.clone().into()));
+            }
+            Some(stream)
+        }
+        Err(err) => {
+            if let Ok(payload) = err.try_into_panic() {
+                let err = crate::error::Error::from_panic(payload);
+                event!(ERROR, "{err}");
+                failure.send_replace(Some(err));
+            }
+            None
+        }
+    }
+}
+
+/// Error returned when the background task of a block has panicked
+fn task_panicked(task: &str) -> Error {
+    Error {
+        code: soapysdr::ErrorCode::Other,
+        message: format!("{task} panicked (see `failure` for details)"),
+    }
+}
+
+/// Error returned when a block is created outside a tokio runtime
+fn no_runtime(block: &str) -> Error {
+    Error {
+        code: soapysdr::ErrorCode::Other,
+        message: format!("{block} must be created within the context of a tokio runtime"),
+    }
+}
+
+/// Handle of the tokio runtime in whose context `block` is created
+///
+/// Panics with a descriptive message if there is no runtime.
+fn current_runtime(block: &str) -> runtime::Handle {
+    match runtime::Handle::try_current() {
+        Ok(handle) => handle,
+        Err(_) => panic!("{}", no_runtime(block).message),
+    }
+}
+
+/// Error returned when the device does not support the given `feature`
+fn not_supported(feature: &str) -> Error {
+    Error {
+        code: soapysdr::ErrorCode::NotSupported,
+        message: format!("{feature} not supported by device"),
+    }
+}
+
+/// Information about an SDR device, as returned by [`enumerate`] and
+/// [`enumerate_with`]
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct DeviceInfo {
+    /// Name of the SoapySDR driver (e.g. `"rtlsdr"`)
+    pub driver: String,
+    /// Human readable label of the device (may be empty)
+    pub label: String,
+    /// Serial number of the device, if reported by the driver
+    pub serial: Option<String>,
+    /// Argument string to open the device (see [`SoapySdrRx::open`])
+    pub args: String,
+}
+
+impl DeviceInfo {
+    fn from_args(args: &soapysdr::Args) -> Self {
+        let get = |key| args.get(key).map(str::to_owned);
+        Self {
+            driver: get("driver").unwrap_or_default(),
+            label: get("label").unwrap_or_default(),
+            serial: get("serial"),
+            args: args
+                .iter()
+                .map(|(key, value)| format!("{key}={value}"))
+                .collect::<Vec<_>>()
+                .join(","),
+        }
+    }
+}
+
+/// Retrieve information about all available SDR devices
+///
+/// Returns an empty vector if no devices are found.
+pub fn enumerate() -> Vec<DeviceInfo> {
+    enumerate_with("")
+}
+
+/// Retrieve information about available SDR devices matching the given
+/// `args` (e.g. `"driver=rtlsdr"`)
+///
+/// Returns an empty vector if no devices are found or if enumeration fails.
+pub fn enumerate_with(args: &str) -> Vec<DeviceInfo> {
+    match soapysdr::enumerate(args) {
+        Ok(devices) => devices.iter().map(DeviceInfo::from_args).collect(),
+        Err(err) => {
+            event!(WARN, error = %err, "could not enumerate SoapySDR devices");
+            Vec::new()
+        }
+    }
+}
+
+/// Sender of the streaming state, which is reset when dropped (e.g. when the
+/// task panics)
+struct StreamingSender(watch::Sender<bool>);
+
+impl StreamingSender {
+    fn set(&self, streaming: bool) {
+        self.0.send_if_modified(|value| std::mem::replace(value, streaming) != streaming);
+    }
+}
+
+impl Drop for StreamingSender {
+    fn drop(&mut self) {
+        self.set(false);
+    }
+}
+
+/// Center frequency which takes effect at a given time (see
+/// [`SoapySdrRxHandle::set_frequency_at`])
+struct ScheduledFrequency {
+    time_ns: i64,
+    /// Index of the channel within the stream
+    index: usize,
+    frequency: f64,
+}
+
+/// Control channels of the task reading from an [`::soapysdr::RxStream`]
+///
+/// Shared by [`RxControl`] and all [`SoapySdrRxHandle`]s of a block.
+struct RxShared {
+    mtu: Result<usize, Error>,
+    read_timeout: watch::Sender<i64>,
+    chunk_size: watch::Sender<Option<usize>>,
+    chunk_duration: watch::Sender<Option<Duration>>,
+    thread_config: watch::Sender<SharedThreadConfig>,
+    auto_recover: watch::Sender<Option<(u32, Duration)>>,
+    sample_rate: watch::Sender<f64>,
+    center_frequencies: watch::Sender<Vec<Option<f64>>>,
+    scheduled_frequencies: mpsc::UnboundedSender<ScheduledFrequency>,
     request_send: watch::Sender<Request>,
     state_recv: watch::Receiver<State>,
-    join_handle: JoinHandle<soapysdr::RxStream<Complex<f32>>>,
+    reconfiguring: tokio::sync::Mutex<()>,
+    streaming: watch::Receiver<bool>,
+    overflow_count: watch::Receiver<u64>,
+    failure: watch::Receiver<Option<crate::error::Error>>,
+    stop_reason: watch::Receiver<Option<StopReason>>,
 }
 
-impl_block_trait! { Producer<Signal<Complex<f32>>> for SoapySdrRx }
+/// Stream which the task of [`RxControl`] reads from, activates, and
+/// deactivates
+///
+/// Implemented for [`::soapysdr::RxStream`] and for mock streams in tests.
+trait RxStreamControl<T>: RxRead<T> + 'static {
+    fn mtu(&self) -> Result<usize, Error>;
+    fn activate(&mut self) -> Result<(), Error>;
+    fn deactivate(&mut self) -> Result<(), Error>;
+}
 
-impl SoapySdrRx {
-    /// Create new [`SoapySdrRx`] block
+impl<T> RxStreamControl<T> for soapysdr::RxStream<T>
+where
+    T: soapysdr::StreamSample + 'static,
+{
+    fn mtu(&self) -> Result<usize, Error> {
+        soapysdr::RxStream::mtu(self)
+    }
+    fn activate(&mut self) -> Result<(), Error> {
+        soapysdr::RxStream::activate(self, None)
+    }
+    fn deactivate(&mut self) -> Result<(), Error> {
+        soapysdr::RxStream::deactivate(self, None)
+    }
+}
+
+/// Task reading from an [`::soapysdr::RxStream`] and its control channels
+///
+/// Used by [`SoapySdrRx`] and [`SoapySdrRxMulti`].
+struct RxControl<S> {
+    shared: Arc<RxShared>,
+    join_handle: JoinHandle<Option<S>>,
+}
+
+impl<S> RxControl<S> {
+    /// Spawn task which reads all `channels` of `rx_stream` and sends the
+    /// samples of each channel to the [`Sender`] with the same index
     ///
-    /// The passed `rx_stream` should not have been activated at this point.
-    /// Instead, the stream must be activated by invoking
-    /// [`SoapySdrRx::activate`].
-    pub fn new(mut rx_stream: soapysdr::RxStream<Complex<f32>>, sample_rate: f64) -> Self {
-        let (sender, sender_connector) = new_sender::<Signal<Complex<f32>>>();
+    /// The (known) `center_frequencies` of the channels are announced with
+    /// [`CenterFrequency`] events after activation and whenever they change
+    /// (or when a [`ScheduledFrequency`] is due).
+    /// A [`Timestamp`] event is sent before the first chunk after activation
+    /// and after each overflow. The task is spawned on the given `runtime`.
+    fn spawn<Flt>(
+        runtime: &runtime::Handle,
+        device: soapysdr::Device,
+        mut rx_stream: S,
+        sample_rate: f64,
+        channels: Vec<usize>,
+        senders: Vec<Sender<Signal<Complex<Flt>>>>,
+        center_frequencies: Vec<Option<f64>>,
+    ) -> Self
+    where
+        Flt: RxSample,
+        S: RxStreamControl<Complex<Flt>>,
+    {
         let (request_send, mut request_recv) = watch::channel(Request::Deactivate);
         let (state_send, state_recv) = watch::channel(State::Inactive);
-        let mtu: usize = rx_stream.mtu().unwrap();
-        let join_handle = spawn(async move {
+        let (streaming_send, streaming) = watch::channel(false);
+        let streaming_send = StreamingSender(streaming_send);
+        let (overflow_count_send, overflow_count) = watch::channel(0u64);
+        let (read_timeout, mut read_timeout_recv) = watch::channel(DEFAULT_READ_TIMEOUT);
+        let (chunk_size, chunk_size_recv) = watch::channel(None);
+        let (chunk_duration, chunk_duration_recv) = watch::channel::<Option<Duration>>(None);
+        let (thread_config, thread_config_recv) =
+            watch::channel(SharedThreadConfig::new(ThreadConfig::default()));
+        let (auto_recover, auto_recover_recv) = watch::channel(None);
+        let (sample_rate, sample_rate_recv) = watch::channel(sample_rate);
+        let (center_frequencies, mut center_frequencies_recv) = watch::channel(center_frequencies);
+        let center_frequencies_send = center_frequencies.clone();
+        let (scheduled_frequencies, mut scheduled_frequencies_recv) =
+            mpsc::unbounded_channel::<ScheduledFrequency>();
+        let mtu = rx_stream.mtu();
+        let default_chunk_size = *mtu.as_ref().unwrap_or(&FALLBACK_MTU);
+        let hardware_time = device.has_hardware_time(None).unwrap_or(false);
+        let (failure_send, failure) = watch::channel(None);
+        let (stop_reason_send, stop_reason) = watch::channel(None);
+        let task = instrument!(INFO, "soapysdr_rx", { channels = ?channels }, async move {
+            let mut pending_frequencies: Vec<ScheduledFrequency> = Vec::new();
             let result = 'task: loop {
                 loop {
                     let Ok(()) = request_recv.changed().await else { break 'task Ok(()); };
@@ -63,91 +455,317 @@ impl SoapySdrRx {
                     }
                 }
                 let result;
-                (result, rx_stream) = spawn_blocking(move || {
-                    let result = rx_stream.activate(None);
+                (result, rx_stream) = blocking(move || {
+                    let result = rx_stream.activate();
                     (result, rx_stream)
                 })
-                .await
-                .unwrap();
+                .await;
                 if let Err(err) = result {
                     break 'task Err(err);
                 }
+                event!(DEBUG, "SoapySDR receive stream activated");
+                streaming_send.set(true);
                 state_send.send_replace(State::Active);
-                let mut buf_pool = ChunkBufPool::<Complex<f32>>::new();
-                loop {
+                let mut buf_pools: Vec<ChunkBufPool<Complex<Flt>>> =
+                    senders.iter().map(|_| ChunkBufPool::new()).collect();
+                let mut timed_out = false;
+                let mut announce = true;
+                let mut synchronized = false;
+                let mut pending_error: Option<Error> = None;
+                let mut backoff = Backoff::new();
+                let mut stream_active = true;
+                let mut timestamps = TimestampTracker::new();
+                // requests are checked before each read and while waiting
+                // for consumers, such that a deliberate stop takes precedence
+                let stop = 'active: loop {
                     match request_recv.has_changed() {
                         Ok(false) => (),
                         Ok(true) => {
                             let request = request_recv.borrow_and_update().clone();
                             match request {
-                                Request::Deactivate => break,
+                                Request::Deactivate => break StopReason::Deactivated,
                                 Request::Activate => (),
-                                Request::Close => {
-                                    let result;
-                                    (result, rx_stream) = spawn_blocking(move || {
-                                        let result = rx_stream.deactivate(None);
-                                        (result, rx_stream)
-                                    })
-                                    .await
-                                    .unwrap();
-                                    break 'task result;
-                                }
+                                Request::Close => break StopReason::Closed,
                             }
                         }
-                        Err(_) => break 'task Ok(()),
+                        Err(_) => break StopReason::Closed,
+                    }
+                    let timeout = *read_timeout_recv.borrow_and_update();
+                    let sample_rate = *sample_rate_recv.borrow();
+                    let chunk_size = match *chunk_duration_recv.borrow() {
+                        Some(duration) => {
+                            let samples = (duration.as_secs_f64() * sample_rate).round() as usize;
+                            samples.div_ceil(default_chunk_size).max(1) * default_chunk_size
+                        }
+                        None => chunk_size_recv.borrow().unwrap_or(default_chunk_size),
+                    };
+                    let mut buffers = get_buffers(&mut buf_pools, chunk_size);
+                    let device = match synchronized {
+                        true => None,
+                        false => Some(device.clone()),
+                    };
+                    let thread_config = thread_config_recv.borrow().clone();
+                    let (result, time_ns);
+                    if let Some(err) = pending_error.take() {
+                        // error occurred after a partially filled chunk
+                        (result, time_ns) = (Err(err), None);
+                    } else {
+                        (result, pending_error, time_ns, (rx_stream, buffers)) =
+                            blocking(move || {
+                                thread_config.apply_to_current_thread();
+                                // `soapysdr` doesn't expose the flags and the
+                                // time of the read
+                                let (result, pending_error) =
+                                    fill_buffers(&mut rx_stream, &mut buffers, timeout);
+                                let time_ns =
+                                    device.map(|device| current_time_ns(&device, hardware_time));
+                                (result, pending_error, time_ns, (rx_stream, buffers))
+                            })
+                            .await;
                     }
-                    let mut buffer = buf_pool.get();
-                    buffer.resize_with(mtu, Default::default);
-                    let result;
-                    (result, (rx_stream, buffer)) = spawn_blocking(move || {
-                        let result = rx_stream.read(&[&mut buffer], 1000000);
-                        (result, (rx_stream, buffer))
-                    })
-                    .await
-                    .unwrap();
                     let count = match result {
-                        Ok(x) => x,
+                        Ok(x) => {
+                            timed_out = false;
+                            x
+                        }
+                        Err(err) if err.code == soapysdr::ErrorCode::Timeout => {
+                            if !timed_out {
+                                timed_out = true;
+                                event!(WARN, channels = ?channels, "SoapySDR read timeout");
+                                let event = Signal::new_event(ReadTimeout);
+                                if let Err(stop) =
+                                    send_or_stop(&senders, event, &mut request_recv).await
+                                {
+                                    break 'active stop;
+                                }
+                            }
+                            continue;
+                        }
+                        Err(err) if err.code == soapysdr::ErrorCode::Overflow => {
+                            synchronized = false;
+                            overflow_count_send.send_modify(|count| *count += 1);
+                            event!(WARN, channels = ?channels, "SoapySDR receive overflow");
+                            let event = Signal::new_event(Overflow);
+                            timestamps.update(&event);
+                            if let Err(stop) =
+                                send_or_stop(&senders, event, &mut request_recv).await
+                            {
+                                break 'active stop;
+                            }
+                            continue;
+                        }
                         Err(err) => {
-                            rx_stream = spawn_blocking(move || {
-                                rx_stream.deactivate(None).ok();
+                            event!(
+                                WARN,
+                                channels = ?channels,
+                                error = %err,
+                                "SoapySDR receive stream error"
+                            );
+                            streaming_send.set(false);
+                            rx_stream = blocking(move || {
+                                rx_stream.deactivate().ok();
                                 rx_stream
                             })
-                            .await
-                            .unwrap();
-                            break 'task Err(err);
+                            .await;
+                            stream_active = false;
+                            let Some((max_retries, initial_delay)) = *auto_recover_recv.borrow()
+                            else { break 'task Err(err); };
+                            loop {
+                                let Some(delay) = backoff.next_delay(max_retries, initial_delay)
+                                else { break 'task Err(err); };
+                                let wait = sleep(delay);
+                                tokio::pin!(wait);
+                                // a deliberate stop ends recovery without error
+                                loop {
+                                    select! {
+                                        _ = &mut wait => break,
+                                        changed = request_recv.changed() => match changed {
+                                            Ok(()) => match *request_recv.borrow_and_update() {
+                                                Request::Deactivate => {
+                                                    break 'active StopReason::Deactivated
+                                                }
+                                                Request::Activate => (),
+                                                Request::Close => break 'active StopReason::Closed,
+                                            },
+                                            Err(_) => break 'active StopReason::Closed,
+                                        },
+                                    }
+                                }
+                                event!(
+                                    INFO,
+                                    channels = ?channels,
+                                    attempt = backoff.attempts,
+                                    max_retries = max_retries,
+                                    "reactivating SoapySDR receive stream"
+                                );
+                                let result;
+                                (result, rx_stream) = blocking(move || {
+                                    let result = rx_stream.activate();
+                                    (result, rx_stream)
+                                })
+                                .await;
+                                if result.is_ok() {
+                                    break;
+                                }
+                            }
+                            stream_active = true;
+                            let attempts = backoff.succeeded();
+                            event!(
+                                INFO,
+                                channels = ?channels,
+                                attempts = attempts,
+                                "SoapySDR receive stream recovered"
+                            );
+                            streaming_send.set(true);
+                            announce = true;
+                            synchronized = false;
+                            let event = Signal::new_event(Recovered { attempts });
+                            timestamps.update(&event);
+                            if let Err(stop) =
+                                send_or_stop(&senders, event, &mut request_recv).await
+                            {
+                                break 'active stop;
+                            }
+                            continue;
                         }
                     };
-                    buffer.truncate(count);
-                    let signal = Signal::Samples {
-                        sample_rate,
-                        chunk: buffer.finalize(),
-                    };
-                    let Ok(()) = sender.send(signal).await else { break 'task Ok(()); };
+                    if let Some(time_ns) = time_ns {
+                        if count > 0 {
+                            synchronized = true;
+                            let elapsed = (count as f64 * 1e9 / sample_rate).round() as i64;
+                            let event = Signal::new_event(Timestamp(time_ns - elapsed));
+                            timestamps.update(&event);
+                            if let Err(stop) =
+                                send_or_stop(&senders, event, &mut request_recv).await
+                            {
+                                break 'active stop;
+                            }
+                        }
+                    }
+                    let signals = samples_signals(buffers, count, sample_rate);
+                    // scheduled frequencies are announced before the first
+                    // chunk which starts at or after their time
+                    while let Ok(scheduled) = scheduled_frequencies_recv.try_recv() {
+                        pending_frequencies.push(scheduled);
+                    }
+                    if let Some(chunk_time_ns) =
+                        signals.first().and_then(|signal| timestamps.update(signal))
+                    {
+                        pending_frequencies.retain(|scheduled| {
+                            let due = scheduled.time_ns <= chunk_time_ns;
+                            if due {
+                                center_frequencies_send.send_modify(|frequencies| {
+                                    frequencies[scheduled.index] = Some(scheduled.frequency);
+                                });
+                            }
+                            !due
+                        });
+                    }
+                    if announce || center_frequencies_recv.has_changed().unwrap_or(false) {
+                        announce = false;
+                        let frequencies = center_frequencies_recv.borrow_and_update().clone();
+                        for (sender, frequency) in senders.iter().zip(frequencies) {
+                            if let Some(frequency) = frequency {
+                                let event = Signal::new_event(CenterFrequency(frequency));
+                                let sender = std::slice::from_ref(sender);
+                                if let Err(stop) =
+                                    send_or_stop(sender, event, &mut request_recv).await
+                                {
+                                    break 'active stop;
+                                }
+                            }
+                        }
+                    }
+                    for (sender, signal) in senders.iter().zip(signals) {
+                        let sender = std::slice::from_ref(sender);
+                        if let Err(stop) = send_or_stop(sender, signal, &mut request_recv).await {
+                            break 'active stop;
+                        }
+                    }
+                };
+                if stream_active {
+                    let result;
+                    (result, rx_stream) = blocking(move || {
+                        let result = rx_stream.deactivate();
+                        (result, rx_stream)
+                    })
+                    .await;
+                    if let Err(err) = result {
+                        break 'task Err(err);
+                    }
                 }
-                let result;
-                (result, rx_stream) = spawn_blocking(move || {
-                    let result = rx_stream.deactivate(None);
-                    (result, rx_stream)
-                })
-                .await
-                .unwrap();
-                if let Err(err) = result {
-                    break 'task Err(err);
+                if let StopReason::Closed = stop {
+                    break 'task Ok(());
+                }
+                match stop {
+                    StopReason::Disconnected => event!(
+                        WARN,
+                        "SoapySDR receive stream deactivated because consumers are gone"
+                    ),
+                    _ => event!(DEBUG, "SoapySDR receive stream deactivated"),
                 }
+                streaming_send.set(false);
                 state_send.send_replace(State::Inactive);
+                stop_reason_send.send_replace(Some(stop));
             };
+            match &result {
+                Ok(()) => event!(DEBUG, "SoapySDR receive stream closed"),
+                Err(err) => event!(
+                    ERROR,
+                    channels = ?channels,
+                    error = %err,
+                    "SoapySDR receive stream failed"
+                ),
+            }
+            stop_reason_send.send_replace(Some(match &result {
+                Ok(()) => StopReason::Closed,
+                Err(err) => StopReason::Failed(err.clone()),
+            }));
+            streaming_send.set(false);
             state_send.send_replace(State::Closed(result));
             rx_stream
         });
+        let task = runtime.spawn(task);
+        let join_handle = runtime.spawn(supervise(task, state_recv.clone(), failure_send));
         Self {
-            sender_connector,
-            request_send,
-            state_recv,
+            shared: Arc::new(RxShared {
+                mtu,
+                read_timeout,
+                chunk_size,
+                chunk_duration,
+                thread_config,
+                auto_recover,
+                sample_rate,
+                center_frequencies,
+                scheduled_frequencies,
+                request_send,
+                state_recv,
+                reconfiguring: Default::default(),
+                streaming,
+                overflow_count,
+                failure,
+                stop_reason,
+            }),
             join_handle,
         }
     }
-    /// Activate streaming
-    pub async fn activate(&self) -> Result<(), Error> {
+    async fn into_inner(self) -> Result<S, Error> {
+        self.shared.request_send.send_replace(Request::Close);
+        let rx_stream = self.join_handle.await.ok().flatten();
+        let state = self.shared.state_recv.borrow().clone();
+        match (state, rx_stream) {
+            (State::Closed(Ok(())), Some(rx_stream)) => Ok(rx_stream),
+            (State::Closed(Err(err)), _) => Err(err),
+            _ => Err(task_panicked("SoapySDR receive task")),
+        }
+    }
+}
+
+impl RxShared {
+    fn is_active(&self) -> bool {
+        matches!(*self.state_recv.borrow(), State::Active)
+    }
+    async fn activate(&self) -> Result<(), Error> {
         let mut state_recv = self.state_recv.clone();
         let state = state_recv.borrow_and_update().clone();
         match state {
@@ -159,7 +777,7 @@ impl SoapySdrRx {
                         let state = state_recv.borrow_and_update().clone();
                         match state {
                             State::Closed(result) => return result,
-                            _ => panic!("SoapySdrRx task ended unexpectedly"),
+                            _ => return Err(task_panicked("SoapySDR receive task")),
                         }
                     }
                     let state = state_recv.borrow_and_update().clone();
@@ -173,8 +791,7 @@ impl SoapySdrRx {
             State::Closed(result) => result,
         }
     }
-    /// Deactivate streaming
-    pub async fn deactivate(&self) -> Result<(), Error> {
+    async fn deactivate(&self) -> Result<(), Error> {
         let mut state_recv = self.state_recv.clone();
         let state = state_recv.borrow_and_update().clone();
         match state {
@@ -185,7 +802,7 @@ impl SoapySdrRx {
                         let state = state_recv.borrow_and_update().clone();
                         match state {
                             State::Closed(result) => return result,
-                            _ => panic!("SoapySdrRx task ended unexpectedly"),
+                            _ => return Err(task_panicked("SoapySDR receive task")),
                         }
                     }
                     let state = state_recv.borrow_and_update().clone();
@@ -200,21 +817,1121 @@ impl SoapySdrRx {
             State::Closed(result) => result,
         }
     }
+}
+
+/// Component type of the complex samples read by [`SoapySdrRx`] and
+/// [`SoapySdrRxMulti`]
+///
+/// This trait is implemented for [`f32`] (stream format `CF32`), [`f64`]
+/// (`CF64`), and [`i16`] (`CS16`).
+pub trait RxSample: Copy + num::Zero + Send + Sync + 'static {}
+
+impl RxSample for f32 {}
+impl RxSample for f64 {}
+impl RxSample for i16 {}
+
+/// Stream which the receive task of [`SoapySdrRx`] and [`SoapySdrRxMulti`]
+/// reads from
+///
+/// This trait is implemented for [`::soapysdr::RxStream`]. Other
+/// implementations (e.g. a mock stream which generates samples without
+/// hardware) can be passed to [`read_and_send`] to test or benchmark the
+/// receive path.
+pub trait RxRead<T>: Send {
+    /// Read samples into `buffers` (one per channel) and return the number
+    /// of samples written to each buffer
+    ///
+    /// See [`::soapysdr::RxStream::read`].
+    fn read(&mut self, buffers: &[&mut [T]], timeout_us: i64) -> Result<usize, Error>;
+}
+
+impl<T> RxRead<T> for soapysdr::RxStream<T>
+where
+    T: soapysdr::StreamSample,
+{
+    fn read(&mut self, buffers: &[&mut [T]], timeout_us: i64) -> Result<usize, Error> {
+        soapysdr::RxStream::read(self, buffers, timeout_us)
+    }
+}
+
+/// Take a buffer of `chunk_size` (zeroed) samples from each of the
+/// `buf_pools`
+fn get_buffers<Flt: RxSample>(
+    buf_pools: &mut [ChunkBufPool<Complex<Flt>>],
+    chunk_size: usize,
+) -> Vec<ChunkBuf<Complex<Flt>>> {
+    buf_pools
+        .iter_mut()
+        .map(|buf_pool| {
+            let mut buffer = buf_pool.get();
+            buffer.resize(chunk_size, Complex::new(Flt::zero(), Flt::zero()));
+            buffer
+        })
+        .collect()
+}
+
+/// Fill `buffers` (one per channel, of equal length) with samples read from
+/// `stream`
+///
+/// Reads are repeated until the buffers are full or the stream returns no
+/// samples. Returns the number of samples per channel. An error which occurs
+/// after a partially filled chunk is returned as second value, such that the
+/// shorter chunk can be sent before the error is handled.
+fn fill_buffers<T>(
+    stream: &mut dyn RxRead<T>,
+    buffers: &mut [ChunkBuf<T>],
+    timeout_us: i64,
+) -> (Result<usize, Error>, Option<Error>) {
+    let chunk_size = buffers.first().map_or(0, |buffer| buffer.len());
+    let mut count = 0;
+    let mut error = None;
+    while count < chunk_size {
+        let slices: Vec<&mut [T]> = buffers
+            .iter_mut()
+            .map(|buffer| &mut buffer[count..])
+            .collect();
+        match stream.read(&slices, timeout_us) {
+            Ok(0) => break,
+            Ok(n) => count += n,
+            Err(err) => {
+                error = Some(err);
+                break;
+            }
+        }
+    }
+    match error {
+        Some(err) if count == 0 => (Err(err), None),
+        error => (Ok(count), error),
+    }
+}
+
+/// Truncate `buffers` to `count` samples and turn them into
+/// [`Signal::Samples`]
+fn samples_signals<T>(buffers: Vec<ChunkBuf<T>>, count: usize, sample_rate: f64) -> Vec<Signal<T>> {
+    buffers
+        .into_iter()
+        .map(|mut buffer| {
+            buffer.truncate(count);
+            Signal::Samples {
+                sample_rate,
+                chunk: buffer.finalize(),
+            }
+        })
+        .collect()
+}
+
+/// Read `chunks` chunks of `chunk_size` samples per channel from `stream`
+/// and send them to the `senders` (one per channel)
+///
+/// Like the receive task of [`SoapySdrRx`] and [`SoapySdrRxMulti`], each
+/// chunk is read in a blocking thread into pooled buffers, but the stream is
+/// neither activated nor deactivated, and no events are sent. This allows
+/// benchmarking the receive path with a mock [`RxRead`] implementation.
+///
+/// Returns the stream, or the first error reported by the stream (after
+/// sending a partially filled chunk) or by the `senders`.
+pub async fn read_and_send<Flt, S>(
+    mut stream: S,
+    senders: &[Sender<Signal<Complex<Flt>>>],
+    sample_rate: f64,
+    chunk_size: usize,
+    chunks: usize,
+) -> Result<S, Error>
+where
+    Flt: RxSample,
+    S: RxRead<Complex<Flt>> + 'static,
+{
+    let mut buf_pools: Vec<ChunkBufPool<Complex<Flt>>> =
+        senders.iter().map(|_| ChunkBufPool::new()).collect();
+    for _ in 0..chunks {
+        let mut buffers = get_buffers(&mut buf_pools, chunk_size);
+        let (result, pending_error);
+        (result, pending_error, (stream, buffers)) = blocking(move || {
+            let (result, pending_error) =
+                fill_buffers(&mut stream, &mut buffers, DEFAULT_READ_TIMEOUT);
+            (result, pending_error, (stream, buffers))
+        })
+        .await;
+        let count = result?;
+        let signals = samples_signals(buffers, count, sample_rate);
+        for (sender, signal) in senders.iter().zip(signals) {
+            if sender.send(signal).await.is_err() {
+                return Err(Error {
+                    code: soapysdr::ErrorCode::Other,
+                    message: "consumers are gone".to_owned(),
+                });
+            }
+        }
+        if let Some(err) = pending_error {
+            return Err(err);
+        }
+    }
+    Ok(stream)
+}
+
+/// Block which wraps an [`::soapysdr::RxStream`] and acts as a
+/// [`Producer<Signal<Complex<Flt>>>`]
+///
+/// The [`::soapysdr::Device`] which the stream belongs to is kept by the
+/// block, such that settings like the center frequency can be changed while
+/// streaming. The methods controlling the device and the stream are
+/// provided by [`SoapySdrRxHandle`], which the block dereferences to. Use
+/// [`SoapySdrRx::handle`] to control the block from other tasks.
+///
+/// When the hardware reports an overflow, an [`Overflow`] event is sent and
+/// streaming continues. Timeouts when reading are not fatal either and
+/// result in a [`ReadTimeout`] event. Other errors end streaming, unless
+/// recovery has been enabled with [`SoapySdrRxHandle::set_auto_recover`].
+///
+/// The center frequency of the channel is announced with a
+/// [`CenterFrequency`] event when streaming is activated and when the
+/// frequency is changed with [`SoapySdrRxHandle::set_frequency`].
+///
+/// Before the first chunk after activation and after each overflow, a
+/// [`Timestamp`] event is sent. The time is estimated when reading from the
+/// stream and uses the hardware clock of the device if available, and the
+/// system time (in nanoseconds since the UNIX epoch) otherwise. The flags
+/// and the time reported by the driver for each read (e.g. `HAS_TIME` or
+/// `END_BURST`) are not available through the `soapysdr` crate, so they are
+/// neither used for the timestamps nor forwarded as events.
+///
+/// The sample type `Flt` is an [`RxSample`], i.e. [`f32`] (default), [`f64`],
+/// or [`i16`], and must match the format of the `rx_stream` (i.e. `CF32`,
+/// `CF64`, or `CS16`). Using `f64` allows processing the whole chain with
+/// double precision (e.g. for measurements), but doubles memory bandwidth
+/// and usually reduces the throughput of subsequent blocks. Most hardware
+/// delivers samples with 16 bits or less, so `f32` is sufficient in most
+/// cases.
+///
+/// Using `i16` avoids the conversion in the driver (if `CS16` is the native
+/// format of the hardware) and halves memory bandwidth at the front end of
+/// wideband captures. Only few blocks support `Signal<Complex<i16>>`:
+///
+/// * [`ConvertInt`] converts the samples to floats (with full scale
+///   corresponding to `1.0`),
+/// * [`IntCicDecimator`] decimates with integer arithmetic and outputs
+///   floats,
+/// * blocks which are generic over the transported type, e.g. [`Buffer`],
+///   [`Splitter`], [`Throttle`], [`SampleRateGuard`], [`NullSink`], or
+///   [`CountingSink`].
+///
+/// Samples are read directly into the (pooled) buffers of the sent chunks,
+/// so apart from the conversion in the driver, no samples are copied. The
+/// direct buffer access of SoapySDR (`acquireReadBuffer` and
+/// `releaseReadBuffer`) is not exposed by the `soapysdr` crate and thus not
+/// used.
+///
+/// [`ConvertInt`]: crate::blocks::transform::ConvertInt
+/// [`IntCicDecimator`]: crate::blocks::resampling::IntCicDecimator
+/// [`Buffer`]: crate::blocks::buffering::Buffer
+/// [`Splitter`]: crate::blocks::buffering::Splitter
+/// [`Throttle`]: crate::blocks::buffering::Throttle
+/// [`SampleRateGuard`]: crate::blocks::guard::SampleRateGuard
+/// [`NullSink`]: crate::blocks::io::NullSink
+/// [`CountingSink`]: crate::blocks::io::CountingSink
+pub struct SoapySdrRx<Flt = f32>
+where
+    Complex<Flt>: soapysdr::StreamSample,
+{
+    sender_connector: SenderConnector<Signal<Complex<Flt>>>,
+    handle: SoapySdrRxHandle,
+    control: RxControl<soapysdr::RxStream<Complex<Flt>>>,
+}
+
+impl<Flt> Producer<Signal<Complex<Flt>>> for SoapySdrRx<Flt>
+where
+    Complex<Flt>: soapysdr::StreamSample,
+{
+    fn sender_connector(&self) -> &SenderConnector<Signal<Complex<Flt>>> {
+        &self.sender_connector
+    }
+}
+
+impl<Flt> SoapySdrRx<Flt>
+where
+    Flt: RxSample,
+    Complex<Flt>: soapysdr::StreamSample,
+{
+    /// Create new [`SoapySdrRx`] block
+    ///
+    /// The passed `rx_stream` should have been created from the passed
+    /// `device` and should not have been activated at this point. Instead,
+    /// the stream must be activated by invoking [`SoapySdrRxHandle::activate`].
+    ///
+    /// The stream is assumed to read channel `0` of the device. Use
+    /// [`SoapySdrRx::with_channel`] for other channels.
+    ///
+    /// # Panics
+    ///
+    /// Panics if not called within the context of a tokio runtime (see
+    /// [`SoapySdrRx::open`] for a fallible alternative).
+    pub fn new(
+        device: soapysdr::Device,
+        rx_stream: soapysdr::RxStream<Complex<Flt>>,
+        sample_rate: f64,
+    ) -> Self {
+        Self::with_channel(device, rx_stream, sample_rate, 0)
+    }
+    /// Create new [`SoapySdrRx`] block for a stream reading given `channel`
+    /// of the device
+    ///
+    /// # Panics
+    ///
+    /// Panics if not called within the context of a tokio runtime.
+    pub fn with_channel(
+        device: soapysdr::Device,
+        rx_stream: soapysdr::RxStream<Complex<Flt>>,
+        sample_rate: f64,
+        channel: usize,
+    ) -> Self {
+        let runtime = current_runtime("SoapySdrRx");
+        let (sender, sender_connector) = new_sender::<Signal<Complex<Flt>>>();
+        let frequency = device.frequency(soapysdr::Direction::Rx, channel).ok();
+        let control = RxControl::spawn(
+            &runtime,
+            device.clone(),
+            rx_stream,
+            sample_rate,
+            vec![channel],
+            vec![sender],
+            vec![frequency],
+        );
+        let handle = SoapySdrRxHandle {
+            device,
+            channels: vec![channel],
+            shared: control.shared.clone(),
+        };
+        Self {
+            sender_connector,
+            handle,
+            control,
+        }
+    }
+    /// Open device with given `args` (e.g. `"driver=rtlsdr"`) and create
+    /// new [`SoapySdrRx`] block reading given `channel`
+    ///
+    /// The sample rate of the channel is set to `sample_rate` and an
+    /// (inactive) stream is set up. Like with [`SoapySdrRx::new`], streaming
+    /// must be started with [`SoapySdrRxHandle::activate`].
+    ///
+    /// An error is returned if not called within the context of a tokio
+    /// runtime.
+    pub fn open(args: &str, channel: usize, sample_rate: f64) -> Result<Self, Error> {
+        runtime::Handle::try_current().map_err(|_| no_runtime("SoapySdrRx"))?;
+        let device = soapysdr::Device::new(args)?;
+        device.set_sample_rate(soapysdr::Direction::Rx, channel, sample_rate)?;
+        let rx_stream = device.rx_stream::<Complex<Flt>>(&[channel])?;
+        Ok(Self::with_channel(device, rx_stream, sample_rate, channel))
+    }
+    /// Get a [`SoapySdrRxHandle`] which controls this block
+    ///
+    /// The handle may be cloned and used from other tasks (e.g. a user
+    /// interface) while the block is connected elsewhere.
+    pub fn handle(&self) -> SoapySdrRxHandle {
+        self.handle.clone()
+    }
     /// Deactivate streaming and return inner [`::soapysdr::RxStream`]
-    pub async fn into_inner(mut self) -> Result<soapysdr::RxStream<Complex<f32>>, Error> {
-        self.request_send.send_replace(Request::Close);
-        let rx_stream = self.join_handle.await.unwrap();
-        let state = self.state_recv.borrow_and_update().clone();
-        match state {
-            State::Closed(Ok(())) => Ok(rx_stream),
-            State::Closed(Err(err)) => Err(err),
-            _ => panic!("SoapySdrRx task ended unexpectedly"),
+    pub async fn into_inner(self) -> Result<soapysdr::RxStream<Complex<Flt>>, Error> {
+        self.control.into_inner().await
+    }
+}
+
+impl<Flt> Deref for SoapySdrRx<Flt>
+where
+    Complex<Flt>: soapysdr::StreamSample,
+{
+    type Target = SoapySdrRxHandle;
+    fn deref(&self) -> &SoapySdrRxHandle {
+        &self.handle
+    }
+}
+
+/// Cloneable handle which controls a [`SoapySdrRx`] or [`SoapySdrRxMulti`]
+/// block
+///
+/// The handle shares the [`::soapysdr::Device`] and the control channels of
+/// the block. All its methods are also available on the block itself
+/// through [`Deref`]. Handles stay usable after the block has been dropped,
+/// but methods controlling the stream return an error then.
+#[derive(Clone)]
+pub struct SoapySdrRxHandle {
+    device: soapysdr::Device,
+    channels: Vec<usize>,
+    shared: Arc<RxShared>,
+}
+
+impl SoapySdrRxHandle {
+    /// [`::soapysdr::Device`] which the stream belongs to
+    ///
+    /// Settings may be changed while streaming is active.
+    pub fn device(&self) -> &soapysdr::Device {
+        &self.device
+    }
+    /// Get timeout for reading from the hardware in microseconds
+    pub fn read_timeout(&self) -> i64 {
+        *self.shared.read_timeout.borrow()
+    }
+    /// Set timeout for reading from the hardware in microseconds
+    ///
+    /// Defaults to one second.
+    pub fn set_read_timeout(&self, micros: i64) {
+        self.shared.read_timeout.send_replace(micros);
+    }
+    /// Get MTU of the stream, i.e. the number of samples the driver
+    /// delivers per read (queried when the block was created)
+    pub fn mtu(&self) -> Result<usize, Error> {
+        self.shared.mtu.clone()
+    }
+    /// Get number of samples per chunk (or `None` if the [MTU] is used)
+    ///
+    /// [MTU]: Self::mtu
+    pub fn chunk_size(&self) -> Option<usize> {
+        *self.shared.chunk_size.borrow()
+    }
+    /// Set number of samples per chunk (or `None` to use the [MTU])
+    ///
+    /// Larger chunks are filled with several reads from the stream, which
+    /// reduces overhead at the cost of latency. Multiples of the MTU are
+    /// recommended. If an error (e.g. an overflow) occurs while a chunk is
+    /// partially filled, the shorter chunk is sent before the error is
+    /// handled. The setting applies to subsequently read chunks.
+    ///
+    /// [MTU]: Self::mtu
+    pub fn set_chunk_size(&self, chunk_size: Option<usize>) {
+        self.shared.chunk_size.send_replace(chunk_size);
+    }
+    /// Get target duration of each chunk (if set)
+    pub fn chunk_duration(&self) -> Option<Duration> {
+        *self.shared.chunk_duration.borrow()
+    }
+    /// Set target duration of each chunk (or `None` to use the
+    /// [chunk size])
+    ///
+    /// Like a larger [chunk size], this reduces the number of sent chunks
+    /// (and thus channel traffic and wakeups) at high sample rates, while
+    /// the added latency stays at about `duration`. The number of samples
+    /// per chunk is rounded up to a multiple of the [MTU] (with a minimum of
+    /// one MTU). If set, the duration takes precedence over the chunk size.
+    ///
+    /// [chunk size]: Self::set_chunk_size
+    /// [MTU]: Self::mtu
+    pub fn set_chunk_duration(&self, duration: Option<Duration>) {
+        self.shared.chunk_duration.send_replace(duration);
+    }
+    /// Get scheduling settings of the threads reading from the hardware
+    pub fn thread_config(&self) -> ThreadConfig {
+        self.shared.thread_config.borrow().config().clone()
+    }
+    /// Set priority and CPU affinity of the threads reading from the hardware
+    ///
+    /// The settings are applied to each (blocking) thread before it reads
+    /// from the stream. Settings which cannot be applied are skipped. See
+    /// the [`thread`] module for details.
+    ///
+    /// [`thread`]: crate::blocks::io::thread
+    pub fn set_thread_config(&self, config: ThreadConfig) {
+        self.shared
+            .thread_config
+            .send_replace(SharedThreadConfig::new(config));
+    }
+    /// Get maximum number of retries and initial backoff for recovering from
+    /// stream errors (or `None` if disabled)
+    pub fn auto_recover(&self) -> Option<(u32, Duration)> {
+        *self.shared.auto_recover.borrow()
+    }
+    /// Enable or disable recovery from stream errors
+    ///
+    /// When enabled, an error reading from the stream (other than a timeout
+    /// or an overflow) is not fatal. Instead, the stream is deactivated and
+    /// reactivated up to `max_retries` times, waiting `backoff` before the
+    /// first attempt and doubling the waiting time after each failed attempt.
+    /// Once streaming has resumed, a [`Recovered`] event is sent. Requesting
+    /// deactivation (or closing the stream) during recovery aborts it and
+    /// stops streaming without an error.
+    ///
+    /// Recovery is disabled by default.
+    pub fn set_auto_recover(&self, enabled: bool, max_retries: u32, backoff: Duration) {
+        self.shared
+            .auto_recover
+            .send_replace(enabled.then_some((max_retries, backoff)));
+    }
+    /// Get [`watch::Receiver`] of the error which ended streaming (if any)
+    ///
+    /// The value is set when the background task ends because of a stream
+    /// error or a panic (e.g. in the driver), such that a supervisor can
+    /// react. Afterwards, all methods controlling the stream return an error.
+    pub fn failure(&self) -> watch::Receiver<Option<crate::error::Error>> {
+        self.shared.failure.clone()
+    }
+    /// Get [`watch::Receiver`] of the reason why streaming stopped most
+    /// recently (or `None` if it never stopped)
+    ///
+    /// Unlike [`SoapySdrRxHandle::failure`], this also tells a deliberate
+    /// deactivation or closing of the stream apart from the consumers being
+    /// gone.
+    pub fn stop_reason(&self) -> watch::Receiver<Option<StopReason>> {
+        self.shared.stop_reason.clone()
+    }
+    /// Return true if samples are currently streamed
+    ///
+    /// See [`SoapySdrRxHandle::state_changes`].
+    pub fn is_active(&self) -> bool {
+        *self.shared.streaming.borrow()
+    }
+    /// Get [`watch::Receiver`] indicating whether samples are currently
+    /// streamed
+    ///
+    /// The value becomes `true` when streaming has been activated or has
+    /// been recovered after a stream error (see
+    /// [`SoapySdrRxHandle::set_auto_recover`]), and `false` upon
+    /// deactivation, a stream error, or when the background task has ended.
+    /// In the latter case, [`SoapySdrRxHandle::failure`] reports the reason
+    /// (if any).
+    pub fn state_changes(&self) -> watch::Receiver<bool> {
+        self.shared.streaming.clone()
+    }
+    /// Get [`watch::Receiver`] of total number of reported overflows
+    pub fn overflow_count(&self) -> watch::Receiver<u64> {
+        self.shared.overflow_count.clone()
+    }
+    /// Get sample rate in hertz which is reported in [`Signal::Samples`]
+    pub fn sample_rate(&self) -> f64 {
+        *self.shared.sample_rate.borrow()
+    }
+    /// Change sample rate of given `channel` to `hz`
+    ///
+    /// If streaming is active, the stream is deactivated before changing the
+    /// sample rate of the device and reactivated afterwards, because not all
+    /// drivers support changing the sample rate while streaming. Thus the
+    /// rate changes at a chunk boundary, and all subsequent chunks report the
+    /// sample rate which the device actually applied. Like after any
+    /// activation, a [`Timestamp`] event is sent before the first chunk.
+    pub async fn set_sample_rate(&self, channel: usize, hz: f64) -> Result<(), Error> {
+        self.reconfigure(|device| device.set_sample_rate(soapysdr::Direction::Rx, channel, hz))
+            .await
+    }
+    /// Change several device settings with `configure` while streaming is
+    /// paused
+    ///
+    /// If streaming is active, the stream is deactivated, `configure` is
+    /// called with the [`::soapysdr::Device`], and the stream is reactivated
+    /// afterwards (even if `configure` failed), such that all chunks read
+    /// after reactivation reflect all changes. The sample rate reported in
+    /// [`Signal::Samples`] and the center frequency are read back from the
+    /// device, and like after any activation, [`CenterFrequency`] and
+    /// [`Timestamp`] events are sent before the first chunk. If streaming is
+    /// inactive, `configure` is called right away.
+    ///
+    /// Reconfigurations (including [`SoapySdrRxHandle::set_sample_rate`])
+    /// are performed one at a time. Note that most settings (e.g. the gain
+    /// or the center frequency) can also be changed while streaming without
+    /// pausing, and that there are no pending settings which
+    /// [`SoapySdrRxHandle::activate`] would apply.
+    pub async fn reconfigure<F>(&self, configure: F) -> Result<(), Error>
+    where
+        F: FnOnce(&soapysdr::Device) -> Result<(), Error>,
+    {
+        let _guard = self.shared.reconfiguring.lock().await;
+        let was_active = self.shared.is_active();
+        if was_active {
+            self.shared.deactivate().await?;
+        }
+        let result = configure(&self.device).and_then(|()| {
+            let direction = soapysdr::Direction::Rx;
+            let sample_rate = self.device.sample_rate(direction, self.channels[0])?;
+            self.shared.sample_rate.send_replace(sample_rate);
+            let frequencies = self
+                .channels
+                .iter()
+                .map(|&channel| Ok(Some(self.device.frequency(direction, channel)?)))
+                .collect::<Result<_, Error>>()?;
+            self.shared.center_frequencies.send_replace(frequencies);
+            Ok(())
+        });
+        if was_active {
+            self.shared.activate().await?;
+        }
+        result
+    }
+    /// Get center frequency of given `channel` in hertz
+    pub fn frequency(&self, channel: usize) -> Result<f64, Error> {
+        self.device.frequency(soapysdr::Direction::Rx, channel)
+    }
+    /// Tune given `channel` to center frequency `freq_hz`
+    ///
+    /// This method may be called while streaming is active. SoapySDR allows
+    /// changing device settings concurrently to reading from a stream, so no
+    /// deactivation of the stream is required.
+    pub fn set_frequency(&self, channel: usize, freq_hz: f64) -> Result<(), Error> {
+        self.device
+            .set_frequency(soapysdr::Direction::Rx, channel, freq_hz, ())?;
+        if self.channels.contains(&channel) {
+            let frequency = self.device.frequency(soapysdr::Direction::Rx, channel)?;
+            self.shared.center_frequencies.send_modify(|frequencies| {
+                for (index, &stream_channel) in self.channels.iter().enumerate() {
+                    if stream_channel == channel {
+                        frequencies[index] = Some(frequency);
+                    }
+                }
+            });
+        }
+        Ok(())
+    }
+    /// Tune given `channel` away from the `desired_hz` center frequency by
+    /// `offset_hz`, such that the DC spike of the hardware lies outside the
+    /// signal of interest
+    ///
+    /// The hardware is tuned to `desired_hz - offset_hz`. The returned value
+    /// is the frequency shift in hertz (taking into account the frequency
+    /// which the hardware actually tuned to), which must be applied in DSP to
+    /// move `desired_hz` back to zero, e.g. by passing it to
+    /// [`FreqShifter::set_shift`] of a block fed by this block.
+    ///
+    /// The [`CenterFrequency`] event sent by this block refers to the
+    /// frequency of the hardware. A [`FreqShifter`] corrects it, such that
+    /// blocks downstream of the shifter see `desired_hz` as center frequency.
+    ///
+    /// [`FreqShifter`]: crate::blocks::transform::FreqShifter
+    /// [`FreqShifter::set_shift`]: crate::blocks::transform::FreqShifter::set_shift
+    pub fn set_center_frequency_with_offset(
+        &self,
+        channel: usize,
+        desired_hz: f64,
+        offset_hz: f64,
+    ) -> Result<f64, Error> {
+        self.set_frequency(channel, desired_hz - offset_hz)?;
+        Ok(self.frequency(channel)? - desired_hz)
+    }
+    /// Return true if the device has a hardware clock
+    ///
+    /// `what` selects the clock (`None` for the default clock). Support
+    /// depends on the driver: e.g. UHD (USRP), LimeSuite, and bladeRF
+    /// devices have a hardware clock which can be synchronized to a PPS
+    /// input, while other drivers (e.g. RTL-SDR) only derive the time from
+    /// the number of received samples or don't support hardware time at all.
+    pub fn has_hardware_time(&self, what: Option<&str>) -> Result<bool, Error> {
+        self.device.has_hardware_time(what)
+    }
+    /// Get time of the hardware clock in nanoseconds
+    ///
+    /// See [`Self::has_hardware_time`] for supported devices.
+    pub fn hardware_time(&self, what: Option<&str>) -> Result<i64, Error> {
+        self.device.get_hardware_time(what)
+    }
+    /// Set time of the hardware clock in nanoseconds
+    ///
+    /// With UHD, `what` may be `Some("PPS")` to set the time at the next PPS
+    /// edge, which allows synchronizing several devices sharing a PPS
+    /// signal. See [`Self::has_hardware_time`] for supported devices.
+    pub fn set_hardware_time(&self, time_ns: i64, what: Option<&str>) -> Result<(), Error> {
+        self.device.set_hardware_time(what, time_ns)
+    }
+    /// Tune given `channel` to center frequency `freq_hz` at hardware time
+    /// `time_ns`
+    ///
+    /// This uses a timed command (hardware time `"CMD"`), such that several
+    /// devices with synchronized clocks (see [`Self::set_hardware_time`])
+    /// retune coherently. Timed commands are only supported by some drivers
+    /// (e.g. UHD); other drivers either return an error or tune immediately.
+    ///
+    /// Unlike with [`Self::set_frequency`], the [`CenterFrequency`] event is
+    /// not sent right away. It reports `freq_hz` (because the frequency which
+    /// the hardware tunes to cannot be read back before the command has been
+    /// executed) and is sent before the first chunk starting at or after
+    /// `time_ns` according to the [`Timestamp`] events of the block. Thus the
+    /// event may be late by up to one chunk, and it is late as well if the
+    /// driver tunes immediately.
+    pub fn set_frequency_at(
+        &self,
+        channel: usize,
+        freq_hz: f64,
+        time_ns: i64,
+    ) -> Result<(), Error> {
+        self.device.set_hardware_time(Some("CMD"), time_ns)?;
+        let result = self
+            .device
+            .set_frequency(soapysdr::Direction::Rx, channel, freq_hz, ());
+        // a command time of zero clears the command time
+        let cleared = self.device.set_hardware_time(Some("CMD"), 0);
+        result.and(cleared)?;
+        for (index, &stream_channel) in self.channels.iter().enumerate() {
+            if stream_channel == channel {
+                self.shared
+                    .scheduled_frequencies
+                    .send(ScheduledFrequency {
+                        time_ns,
+                        index,
+                        frequency: freq_hz,
+                    })
+                    .ok();
+            }
+        }
+        Ok(())
+    }
+    /// Get overall gain of given `channel` in decibels
+    pub fn gain(&self, channel: usize) -> Result<f64, Error> {
+        self.device.gain(soapysdr::Direction::Rx, channel)
+    }
+    /// Set overall gain of given `channel` in decibels
+    ///
+    /// Like [`Self::set_frequency`], this method may be called while
+    /// streaming is active.
+    pub fn set_gain(&self, channel: usize, gain_db: f64) -> Result<(), Error> {
+        self.device
+            .set_gain(soapysdr::Direction::Rx, channel, gain_db)
+    }
+    /// Set gain of named amplification element of given `channel` in decibels
+    pub fn set_gain_element(&self, channel: usize, name: &str, value: f64) -> Result<(), Error> {
+        self.device
+            .set_gain_element(soapysdr::Direction::Rx, channel, name, value)
+    }
+    /// Enable or disable automatic gain control (AGC) of the hardware
+    pub fn set_gain_mode(&self, channel: usize, automatic: bool) -> Result<(), Error> {
+        self.device
+            .set_gain_mode(soapysdr::Direction::Rx, channel, automatic)
+    }
+    /// Get analog filter bandwidth of given `channel` in hertz
+    pub fn bandwidth(&self, channel: usize) -> Result<f64, Error> {
+        self.device.bandwidth(soapysdr::Direction::Rx, channel)
+    }
+    /// Set analog filter bandwidth of given `channel` in hertz
+    ///
+    /// Like [`Self::set_frequency`], this method may be called while
+    /// streaming is active.
+    pub fn set_bandwidth(&self, channel: usize, bandwidth_hz: f64) -> Result<(), Error> {
+        self.device
+            .set_bandwidth(soapysdr::Direction::Rx, channel, bandwidth_hz)
+    }
+    /// Get name of selected antenna of given `channel`
+    pub fn antenna(&self, channel: usize) -> Result<String, Error> {
+        self.device.antenna(soapysdr::Direction::Rx, channel)
+    }
+    /// Select antenna of given `channel` by name
+    ///
+    /// Like [`Self::set_frequency`], this method may be called while
+    /// streaming is active.
+    pub fn set_antenna(&self, channel: usize, name: &str) -> Result<(), Error> {
+        self.device
+            .set_antenna(soapysdr::Direction::Rx, channel, name)
+    }
+    /// Get names of available antennas of given `channel`
+    pub fn list_antennas(&self, channel: usize) -> Result<Vec<String>, Error> {
+        self.device.antennas(soapysdr::Direction::Rx, channel)
+    }
+    fn require_dc_offset_mode(&self, channel: usize) -> Result<(), Error> {
+        match self
+            .device
+            .has_dc_offset_mode(soapysdr::Direction::Rx, channel)?
+        {
+            true => Ok(()),
+            false => Err(not_supported("automatic DC offset correction")),
+        }
+    }
+    fn require_iq_balance(&self, channel: usize) -> Result<(), Error> {
+        match self
+            .device
+            .has_iq_balance(soapysdr::Direction::Rx, channel)?
+        {
+            true => Ok(()),
+            false => Err(not_supported("IQ balance correction")),
+        }
+    }
+    /// Get whether automatic DC offset correction of given `channel` is
+    /// enabled
+    pub fn dc_offset_mode(&self, channel: usize) -> Result<bool, Error> {
+        self.require_dc_offset_mode(channel)?;
+        self.device.dc_offset_mode(soapysdr::Direction::Rx, channel)
+    }
+    /// Enable or disable automatic DC offset correction of given `channel`
+    ///
+    /// Returns an error with [`ErrorCode::NotSupported`] if the device does
+    /// not support automatic DC offset correction.
+    ///
+    /// [`ErrorCode::NotSupported`]: soapysdr::ErrorCode::NotSupported
+    pub fn set_dc_offset_mode(&self, channel: usize, automatic: bool) -> Result<(), Error> {
+        self.require_dc_offset_mode(channel)?;
+        self.device
+            .set_dc_offset_mode(soapysdr::Direction::Rx, channel, automatic)
+    }
+    /// Get IQ balance correction of given `channel`
+    pub fn iq_balance(&self, channel: usize) -> Result<Complex<f64>, Error> {
+        self.require_iq_balance(channel)?;
+        let (re, im) = self.device.iq_balance(soapysdr::Direction::Rx, channel)?;
+        Ok(Complex::new(re, im))
+    }
+    /// Set IQ balance correction of given `channel`
+    ///
+    /// Returns an error with [`ErrorCode::NotSupported`] if the device does
+    /// not support IQ balance correction.
+    ///
+    /// [`ErrorCode::NotSupported`]: soapysdr::ErrorCode::NotSupported
+    pub fn set_iq_balance(&self, channel: usize, balance: Complex<f64>) -> Result<(), Error> {
+        self.require_iq_balance(channel)?;
+        self.device
+            .set_iq_balance(soapysdr::Direction::Rx, channel, balance.re, balance.im)
+    }
+    /// Write driver specific device setting
+    ///
+    /// Keys and values depend on the driver, e.g. `"biastee"` with value
+    /// `"true"` enables the bias-tee of an RTL-SDR. Like
+    /// [`Self::set_frequency`], this method may be called while
+    /// streaming is active.
+    ///
+    /// The available settings can't be listed, because the `soapysdr` crate
+    /// doesn't expose `getSettingInfo` of SoapySDR. Use `SoapySDRUtil
+    /// --probe` or the documentation of the driver to find keys and values.
+    pub fn write_setting(&self, key: &str, value: &str) -> Result<(), Error> {
+        self.device.write_setting(key, value)
+    }
+    /// Read driver specific device setting
+    ///
+    /// See [`Self::write_setting`] for keys and values.
+    pub fn read_setting(&self, key: &str) -> Result<String, Error> {
+        self.device.read_setting(key)
+    }
+    /// Activate streaming
+    ///
+    /// If streaming is already active, this does nothing. Settings changed
+    /// through this handle take effect immediately (or, like
+    /// [`SoapySdrRxHandle::set_sample_rate`], pause streaming as needed),
+    /// so activation never applies pending settings. Use
+    /// [`SoapySdrRxHandle::reconfigure`] to change device settings while
+    /// streaming is paused.
+    pub async fn activate(&self) -> Result<(), Error> {
+        instrument!(
+            INFO,
+            "soapysdr_rx_activate",
+            { channels = ?self.channels },
+            self.shared.activate()
+        )
+        .await
+    }
+    /// Deactivate streaming
+    pub async fn deactivate(&self) -> Result<(), Error> {
+        instrument!(
+            INFO,
+            "soapysdr_rx_deactivate",
+            { channels = ?self.channels },
+            self.shared.deactivate()
+        )
+        .await
+    }
+}
+
+/// Output of a [`SoapySdrRxMulti`] block, which acts as a
+/// [`Producer<Signal<Complex<Flt>>>`] for a single channel
+pub struct SoapySdrRxOutput<Flt = f32> {
+    sender_connector: SenderConnector<Signal<Complex<Flt>>>,
+}
+
+impl_block_trait! { <Flt> Producer<Signal<Complex<Flt>>> for SoapySdrRxOutput<Flt> }
+
+/// Block which wraps an [`::soapysdr::RxStream`] with several channels
+///
+/// All channels are read with a single call of
+/// [`::soapysdr::RxStream::read`], such that the [`Signal::Samples`] sent by
+/// the [outputs] stay sample-aligned. Otherwise the block behaves like
+/// [`SoapySdrRx`].
+///
+/// The block dereferences to a [`SoapySdrRxHandle`], which controls the
+/// stream of all channels. Changes of the center frequency are only
+/// announced through [`CenterFrequency`] events if they are made with
+/// [`SoapySdrRxHandle::set_frequency`] (or another method of the handle).
+///
+/// [outputs]: SoapySdrRxMulti::outputs
+pub struct SoapySdrRxMulti<Flt = f32>
+where
+    Complex<Flt>: soapysdr::StreamSample,
+{
+    outputs: Vec<SoapySdrRxOutput<Flt>>,
+    handle: SoapySdrRxHandle,
+    control: RxControl<soapysdr::RxStream<Complex<Flt>>>,
+}
+
+impl<Flt> SoapySdrRxMulti<Flt>
+where
+    Flt: RxSample,
+    Complex<Flt>: soapysdr::StreamSample,
+{
+    /// Create new [`SoapySdrRxMulti`] block
+    ///
+    /// The passed `rx_stream` should have been created from the passed
+    /// `device` with `channel_count` channels and should not have been
+    /// activated at this point. Instead, the stream must be activated by
+    /// invoking [`SoapySdrRxHandle::activate`].
+    ///
+    /// The stream is assumed to read the channels `0..channel_count` of the
+    /// device. Use [`SoapySdrRxMulti::with_channels`] for other channels.
+    ///
+    /// # Panics
+    ///
+    /// Panics if not called within the context of a tokio runtime.
+    pub fn new(
+        device: soapysdr::Device,
+        rx_stream: soapysdr::RxStream<Complex<Flt>>,
+        channel_count: usize,
+        sample_rate: f64,
+    ) -> Self {
+        let channels: Vec<usize> = (0..channel_count).collect();
+        Self::with_channels(device, rx_stream, &channels, sample_rate)
+    }
+    /// Create new [`SoapySdrRxMulti`] block for a stream reading the given
+    /// `channels` of the device (in the same order as passed to
+    /// [`::soapysdr::Device::rx_stream`])
+    ///
+    /// # Panics
+    ///
+    /// Panics if not called within the context of a tokio runtime.
+    pub fn with_channels(
+        device: soapysdr::Device,
+        rx_stream: soapysdr::RxStream<Complex<Flt>>,
+        channels: &[usize],
+        sample_rate: f64,
+    ) -> Self {
+        let runtime = current_runtime("SoapySdrRxMulti");
+        let mut outputs = Vec::with_capacity(channels.len());
+        let mut senders = Vec::with_capacity(channels.len());
+        for _ in channels {
+            let (sender, sender_connector) = new_sender::<Signal<Complex<Flt>>>();
+            outputs.push(SoapySdrRxOutput { sender_connector });
+            senders.push(sender);
+        }
+        let frequencies: Vec<Option<f64>> = channels
+            .iter()
+            .map(|&channel| device.frequency(soapysdr::Direction::Rx, channel).ok())
+            .collect();
+        let control = RxControl::spawn(
+            &runtime,
+            device.clone(),
+            rx_stream,
+            sample_rate,
+            channels.to_vec(),
+            senders,
+            frequencies,
+        );
+        let handle = SoapySdrRxHandle {
+            device,
+            channels: channels.to_vec(),
+            shared: control.shared.clone(),
+        };
+        Self {
+            outputs,
+            handle,
+            control,
+        }
+    }
+    /// Outputs, one for each channel of the stream
+    pub fn outputs(&self) -> &[SoapySdrRxOutput<Flt>] {
+        &self.outputs
+    }
+    /// Output for the channel with given `index` within the stream
+    pub fn output(&self, index: usize) -> &SoapySdrRxOutput<Flt> {
+        &self.outputs[index]
+    }
+    /// Get a [`SoapySdrRxHandle`] which controls this block
+    ///
+    /// The handle may be cloned and used from other tasks (e.g. a user
+    /// interface) while the outputs are connected elsewhere.
+    pub fn handle(&self) -> SoapySdrRxHandle {
+        self.handle.clone()
+    }
+    /// Deactivate streaming and return inner [`::soapysdr::RxStream`]
+    pub async fn into_inner(self) -> Result<soapysdr::RxStream<Complex<Flt>>, Error> {
+        self.control.into_inner().await
+    }
+}
+
+impl<Flt> Deref for SoapySdrRxMulti<Flt>
+where
+    Complex<Flt>: soapysdr::StreamSample,
+{
+    type Target = SoapySdrRxHandle;
+    fn deref(&self) -> &SoapySdrRxHandle {
+        &self.handle
+    }
+}
+
+/// When [`SoapySdrTx`] applies a [`TxRamp`]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum RampMode {
+    /// Ramp up at the start and ramp down at the end of each burst (ended by
+    /// an [`EndOfBurst`] event or deactivation), and apply the ramp to each
+    /// burst passed to [`SoapySdrTx::transmit_burst`]
+    PerBurst,
+    /// Ramp up only after activation and ramp down only upon deactivation
+    Activation,
+}
+
+/// Raised-cosine amplitude ramp applied by [`SoapySdrTx`] when keying the
+/// transmitter, which avoids key clicks (spectral splatter)
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct TxRamp {
+    /// Duration of the ramp (up or down) in milliseconds
+    pub duration_ms: f64,
+    /// When the ramp is applied
+    pub mode: RampMode,
+}
+
+impl TxRamp {
+    /// Length of the ramp in samples
+    fn len(&self, sample_rate: f64) -> usize {
+        (self.duration_ms * 1e-3 * sample_rate).round() as usize
+    }
+}
+
+/// Gain of a raised-cosine ramp of `len` samples at index `pos`
+fn ramp_gain(len: usize, pos: usize) -> f32 {
+    0.5 - 0.5 * (std::f32::consts::PI * (pos as f32 + 0.5) / len as f32).cos()
+}
+
+/// Apply ramp of `len` samples to the start and end of `samples`
+fn apply_ramp(samples: &mut [Complex<f32>], len: usize) {
+    let len = len.min(samples.len() / 2);
+    let total = samples.len();
+    for pos in 0..len {
+        let gain = ramp_gain(len, pos);
+        samples[pos] *= gain;
+        samples[total - 1 - pos] *= gain;
+    }
+}
+
+/// State of a [`TxRamp`] while streaming
+///
+/// As the end of a burst is only known afterwards, the last samples (one
+/// ramp length) are held back until more samples are received or the burst
+/// ends.
+#[derive(Debug)]
+struct RampState {
+    /// Whether a burst is in progress
+    keyed: bool,
+    /// Whether the next burst is ramped up
+    ramp_up: bool,
+    /// Length of the ramp of the current burst
+    len: usize,
+    /// Number of samples of the current burst (saturating at `len`)
+    pos: usize,
+    /// Held back samples
+    tail: Vec<Complex<f32>>,
+}
+
+impl RampState {
+    fn new() -> Self {
+        Self {
+            keyed: false,
+            ramp_up: true,
+            len: 0,
+            pos: 0,
+            tail: Vec::new(),
+        }
+    }
+    /// Discard held back samples and ramp up the next burst
+    fn reset(&mut self) {
+        *self = Self::new();
+    }
+    /// Return true if `chunk` can be written as is
+    fn is_passthrough(&self, ramp: Option<TxRamp>) -> bool {
+        ramp.is_none() && self.tail.is_empty()
+    }
+    /// Append `chunk` to `output` while ramping up and holding back samples
+    fn process(
+        &mut self,
+        ramp: Option<TxRamp>,
+        sample_rate: f64,
+        chunk: &[Complex<f32>],
+        output: &mut Vec<Complex<f32>>,
+    ) {
+        if !self.keyed {
+            self.keyed = true;
+            self.len = ramp.map_or(0, |ramp| ramp.len(sample_rate));
+            self.pos = if self.ramp_up { 0 } else { self.len };
         }
+        output.append(&mut self.tail);
+        for &sample in chunk {
+            if self.pos < self.len {
+                output.push(sample * ramp_gain(self.len, self.pos));
+                self.pos += 1;
+            } else {
+                output.push(sample);
+            }
+        }
+        let keep = self.len.min(output.len());
+        self.tail.extend(output.drain(output.len() - keep..));
+    }
+    /// End current burst, appending the held back samples to `output`
+    ///
+    /// If `ramp_down` is true, the held back samples are ramped down and the
+    /// next burst is ramped up.
+    fn end(&mut self, ramp_down: bool, output: &mut Vec<Complex<f32>>) {
+        let count = self.tail.len();
+        for (idx, sample) in self.tail.drain(..).enumerate() {
+            if ramp_down {
+                output.push(sample * ramp_gain(self.len, count - 1 - idx));
+            } else {
+                output.push(sample);
+            }
+        }
+        self.keyed = false;
+        self.ramp_up = ramp_down;
+    }
+}
+
+/// Burst to be transmitted by [`SoapySdrTx::transmit_burst`]
+struct Burst {
+    samples: Vec<Complex<f32>>,
+    time_ns: Option<i64>,
+    reply: oneshot::Sender<Result<(), Error>>,
+}
+
+/// Stream which the task of [`SoapySdrTx`] writes to, activates, and
+/// deactivates
+///
+/// Implemented for [`::soapysdr::TxStream`] and for mock streams in tests.
+trait TxStreamControl: Send + 'static {
+    fn activate(&mut self) -> Result<(), Error>;
+    fn deactivate(&mut self) -> Result<(), Error>;
+    fn write_all(
+        &mut self,
+        buffers: &[&[Complex<f32>]],
+        at_ns: Option<i64>,
+        end_burst: bool,
+        timeout_us: i64,
+    ) -> Result<(), Error>;
+}
+
+impl TxStreamControl for soapysdr::TxStream<Complex<f32>> {
+    fn activate(&mut self) -> Result<(), Error> {
+        soapysdr::TxStream::activate(self, None)
+    }
+    fn deactivate(&mut self) -> Result<(), Error> {
+        soapysdr::TxStream::deactivate(self, None)
+    }
+    fn write_all(
+        &mut self,
+        buffers: &[&[Complex<f32>]],
+        at_ns: Option<i64>,
+        end_burst: bool,
+        timeout_us: i64,
+    ) -> Result<(), Error> {
+        soapysdr::TxStream::write_all(self, buffers, at_ns, end_burst, timeout_us)
     }
 }
 
 /// Block which wraps an [`::soapysdr::TxStream`] and acts as a
-/// [`Producer<Signal<Complex<Flt>>>`]
+/// [`Consumer<Signal<Complex<Flt>>>`]
+///
+/// Underflows and timeouts reported by the driver when writing are not
+/// considered fatal; streaming continues with the next received chunk. They
+/// are counted (see [`SoapySdrTx::underflow_count`] and
+/// [`SoapySdrTx::write_timeout_count`]).
+///
+/// Receiving an [`EndOfBurst`] event ends the current burst, such that the
+/// amplifier is only keyed while samples are streamed. Alternatively,
+/// finite bursts, optionally at a given hardware time, can be transmitted
+/// with [`SoapySdrTx::transmit_burst`].
+///
+/// A [`TxRamp`] can be configured with [`SoapySdrTx::set_ramp`] to ramp the
+/// amplitude up and down when keying. This delays streamed samples by the
+/// length of the ramp.
 ///
 /// As a workaround for bad driver implementations, the following extra
 /// measures are taken by the `SoapySdrTx` block:
@@ -228,7 +1945,13 @@ pub struct SoapySdrTx {
     event_handlers: EventHandlers,
     request_send: watch::Sender<Request>,
     state_recv: watch::Receiver<State>,
-    join_handle: JoinHandle<soapysdr::TxStream<Complex<f32>>>,
+    burst_send: mpsc::UnboundedSender<Burst>,
+    ramp: watch::Sender<Option<TxRamp>>,
+    underflow_count: watch::Receiver<u64>,
+    write_timeout_count: watch::Receiver<u64>,
+    thread_config: watch::Sender<SharedThreadConfig>,
+    failure: watch::Receiver<Option<crate::error::Error>>,
+    join_handle: JoinHandle<Option<soapysdr::TxStream<Complex<f32>>>>,
 }
 
 impl_block_trait! { Consumer<Signal<Complex<f32>>> for SoapySdrTx }
@@ -244,31 +1967,55 @@ impl SoapySdrTx {
     /// Use [`::soapysdr::TxStream::mtu`] to determine optimal chunk size
     /// before passing the [`TxStream`] to this `new` function.
     ///
+    /// # Panics
+    ///
+    /// Panics if not called within the context of a tokio runtime.
+    ///
     /// [`TxStream`]: ::soapysdr::TxStream
-    pub fn new(mut tx_stream: soapysdr::TxStream<Complex<f32>>) -> Self {
+    pub fn new(tx_stream: soapysdr::TxStream<Complex<f32>>) -> Self {
+        Self::spawn(tx_stream, Some)
+    }
+    /// Create block writing to `tx_stream`, where `into_inner` extracts the
+    /// stream returned by [`SoapySdrTx::into_inner`] (if any)
+    fn spawn<S>(
+        mut tx_stream: S,
+        into_inner: fn(S) -> Option<soapysdr::TxStream<Complex<f32>>>,
+    ) -> Self
+    where
+        S: TxStreamControl,
+    {
+        let runtime = current_runtime("SoapySdrTx");
         let (mut receiver, receiver_connector) = new_receiver::<Signal<Complex<f32>>>();
         let (request_send, mut request_recv) = watch::channel(Request::Deactivate);
         let (state_send, state_recv) = watch::channel(State::Inactive);
+        let (burst_send, mut burst_recv) = mpsc::unbounded_channel::<Burst>();
+        let (ramp, ramp_recv) = watch::channel::<Option<TxRamp>>(None);
+        let (underflow_count_send, underflow_count) = watch::channel(0u64);
+        let (write_timeout_count_send, write_timeout_count) = watch::channel(0u64);
+        let (thread_config, thread_config_recv) =
+            watch::channel(SharedThreadConfig::new(ThreadConfig::default()));
         let event_handlers = EventHandlers::new();
         let evhdl_clone = event_handlers.clone();
-        let join_handle = spawn(async move {
+        let (failure_send, failure) = watch::channel(None);
+        let task = instrument!(INFO, "soapysdr_tx", async move {
             let mut first_run = true;
+            let mut ramp_state = RampState::new();
+            let mut last_sample_rate: Option<f64> = None;
             let result = 'task: loop {
                 if first_run {
                     let result;
-                    (result, tx_stream) = spawn_blocking(move || {
-                        let result = tx_stream.activate(None);
+                    (result, tx_stream) = blocking(move || {
+                        let result = tx_stream.activate();
                         (result, tx_stream)
                     })
-                    .await
-                    .unwrap();
+                    .await;
                     if let Err(err) = result {
                         break 'task Err(err);
                     }
                     first_run = false;
                 }
                 let result;
-                (result, tx_stream) = spawn_blocking(move || {
+                (result, tx_stream) = blocking(move || {
                     let result = tx_stream.write_all(
                         &[&[Complex::new(0.0f32, 0.0f32)]],
                         None,
@@ -277,27 +2024,25 @@ impl SoapySdrTx {
                     );
                     (result, tx_stream)
                 })
-                .await
-                .unwrap();
+                .await;
                 if let Err(err) = result {
-                    tx_stream = spawn_blocking(move || {
-                        tx_stream.deactivate(None).ok();
+                    tx_stream = blocking(move || {
+                        tx_stream.deactivate().ok();
                         tx_stream
                     })
-                    .await
-                    .unwrap();
+                    .await;
                     break 'task Err(err);
                 }
                 let result;
-                (result, tx_stream) = spawn_blocking(move || {
-                    let result = tx_stream.deactivate(None);
+                (result, tx_stream) = blocking(move || {
+                    let result = tx_stream.deactivate();
                     (result, tx_stream)
                 })
-                .await
-                .unwrap();
+                .await;
                 if let Err(err) = result {
                     break 'task Err(err);
                 }
+                event!(DEBUG, "SoapySDR transmit stream deactivated");
                 state_send.send_replace(State::Inactive);
                 loop {
                     let Ok(()) = request_recv.changed().await else { break 'task Ok(()); };
@@ -309,17 +2054,31 @@ impl SoapySdrTx {
                     }
                 }
                 let result;
-                (result, tx_stream) = spawn_blocking(move || {
-                    let result = tx_stream.activate(None);
+                (result, tx_stream) = blocking(move || {
+                    let result = tx_stream.activate();
                     (result, tx_stream)
                 })
-                .await
-                .unwrap();
+                .await;
                 if let Err(err) = result {
                     break 'task Err(err);
                 }
+                event!(DEBUG, "SoapySDR transmit stream activated");
                 state_send.send_replace(State::Active);
+                ramp_state.reset();
                 let mut block_until: Option<Instant> = None;
+                let count_error = |err: &Error| match err.code {
+                    soapysdr::ErrorCode::Underflow => {
+                        underflow_count_send.send_modify(|count| *count += 1);
+                        event!(DEBUG, "SoapySDR transmit underflow");
+                        true
+                    }
+                    soapysdr::ErrorCode::Timeout => {
+                        write_timeout_count_send.send_modify(|count| *count += 1);
+                        event!(WARN, "SoapySDR write timeout");
+                        true
+                    }
+                    _ => false,
+                };
                 loop {
                     select! {
                         changed = request_recv.changed() => match changed {
@@ -327,7 +2086,24 @@ impl SoapySdrTx {
                             Ok(()) => {
                                 let request = request_recv.borrow_and_update().clone();
                                 match request {
-                                    Request::Deactivate => break,
+                                    Request::Deactivate => {
+                                        let mut tail = Vec::new();
+                                        ramp_state.end(true, &mut tail);
+                                        if !tail.is_empty() {
+                                            let result;
+                                            (result, tx_stream) = blocking(move || {
+                                                let result = tx_stream.write_all(
+                                                    &[&tail], None, false, 1000000,
+                                                );
+                                                (result, tx_stream)
+                                            })
+                                            .await;
+                                            if let Err(err) = result {
+                                                count_error(&err);
+                                            }
+                                        }
+                                        break;
+                                    }
                                     Request::Activate => (),
                                     Request::Close => break 'task Ok(()),
                                 }
@@ -337,6 +2113,15 @@ impl SoapySdrTx {
                             Err(_) => break 'task Ok(()),
                             Ok(signal) => match signal {
                                 Signal::Samples { sample_rate, chunk } => {
+                                    last_sample_rate = Some(sample_rate);
+                                    let ramp = *ramp_recv.borrow();
+                                    let chunk = if ramp_state.is_passthrough(ramp) {
+                                        chunk
+                                    } else {
+                                        let mut samples = Vec::with_capacity(chunk.len());
+                                        ramp_state.process(ramp, sample_rate, &chunk, &mut samples);
+                                        Chunk::from(samples)
+                                    };
                                     let duration =
                                         Duration::from_secs_f64(chunk.len() as f64 / sample_rate);
                                     let now = Instant::now();
@@ -354,51 +2139,194 @@ impl SoapySdrTx {
                                             block_until = Some(now + duration);
                                         }
                                     }
+                                    let thread_config = thread_config_recv.borrow().clone();
                                     let result;
-                                    (result, tx_stream) = spawn_blocking(move || {
+                                    (result, tx_stream) = blocking(move || {
+                                        thread_config.apply_to_current_thread();
                                         let result = tx_stream.write_all(
                                             &[&chunk], None, false, 1000000,
                                         );
                                         (result, tx_stream)
                                     })
-                                    .await
-                                    .unwrap();
-                                    if let Err(err) = result {
-                                        tx_stream = spawn_blocking(move || {
-                                            tx_stream.write_all(
-                                                &[&[Complex::new(0.0f32, 0.0f32)]],
-                                                None, false, 1000000,
-                                            ).ok();
-                                            tx_stream
-                                        })
-                                        .await
-                                        .unwrap();
-                                        tx_stream = spawn_blocking(move || {
-                                            tx_stream.deactivate(None).ok();
-                                            tx_stream
+                                    .await;
+                                    match result {
+                                        Ok(()) => (),
+                                        Err(err) if count_error(&err) => (),
+                                        Err(err) => {
+                                            tx_stream = blocking(move || {
+                                                tx_stream.write_all(
+                                                    &[&[Complex::new(0.0f32, 0.0f32)]],
+                                                    None, false, 1000000,
+                                                ).ok();
+                                                tx_stream
+                                            })
+                                            .await;
+                                            tx_stream = blocking(move || {
+                                                tx_stream.deactivate().ok();
+                                                tx_stream
+                                            })
+                                            .await;
+                                            break 'task Err(err);
+                                        }
+                                    }
+                                }
+                                Signal::Event(event) => {
+                                    if event.as_any().is::<EndOfBurst>() {
+                                        let ramp_down = matches!(
+                                            *ramp_recv.borrow(),
+                                            Some(TxRamp { mode: RampMode::PerBurst, .. })
+                                        );
+                                        let mut samples = Vec::new();
+                                        ramp_state.end(ramp_down, &mut samples);
+                                        samples.push(Complex::new(0.0f32, 0.0f32));
+                                        let result;
+                                        (result, tx_stream) = blocking(move || {
+                                            let result = tx_stream.write_all(
+                                                &[&samples], None, true, 1000000,
+                                            );
+                                            (result, tx_stream)
                                         })
-                                        .await
-                                        .unwrap();
-                                        break 'task Err(err);
+                                        .await;
+                                        if let Err(err) = result {
+                                            count_error(&err);
+                                        }
+                                        block_until = None;
                                     }
+                                    evhdl_clone.invoke(&event);
                                 }
-                                Signal::Event(event) => evhdl_clone.invoke(&event),
                             }
                         },
+                        Some(burst) = burst_recv.recv() => {
+                            let Burst { mut samples, time_ns, reply } = burst;
+                            if let (
+                                Some(ramp @ TxRamp { mode: RampMode::PerBurst, .. }),
+                                Some(sample_rate),
+                            ) = (*ramp_recv.borrow(), last_sample_rate)
+                            {
+                                apply_ramp(&mut samples, ramp.len(sample_rate));
+                            }
+                            let result;
+                            (result, tx_stream) = blocking(move || {
+                                let result = tx_stream.write_all(
+                                    &[&samples], time_ns, true, 1000000,
+                                );
+                                (result, tx_stream)
+                            })
+                            .await;
+                            if let Err(err) = &result {
+                                count_error(err);
+                            }
+                            reply.send(result).ok();
+                            block_until = None;
+                        },
                     }
                 }
             };
+            match &result {
+                Ok(()) => event!(DEBUG, "SoapySDR transmit stream closed"),
+                Err(err) => event!(ERROR, error = %err, "SoapySDR transmit stream failed"),
+            }
             state_send.send_replace(State::Closed(result));
-            tx_stream
+            into_inner(tx_stream)
         });
+        let task = runtime.spawn(task);
+        let supervisor = supervise(task, state_recv.clone(), failure_send);
+        let join_handle = runtime.spawn(async move { supervisor.await.flatten() });
         Self {
             receiver_connector,
             event_handlers,
             request_send,
             state_recv,
+            burst_send,
+            ramp,
+            underflow_count,
+            write_timeout_count,
+            thread_config,
+            failure,
             join_handle,
         }
     }
+    /// Get [`watch::Receiver`] of total number of reported underflows
+    pub fn underflow_count(&self) -> watch::Receiver<u64> {
+        self.underflow_count.clone()
+    }
+    /// Get amplitude ramp applied when keying (if any)
+    pub fn ramp(&self) -> Option<TxRamp> {
+        *self.ramp.borrow()
+    }
+    /// Set amplitude ramp applied when keying, or `None` to key abruptly
+    ///
+    /// A changed ramp length takes effect with the next burst. Bursts passed
+    /// to [`SoapySdrTx::transmit_burst`] are ramped (in [`RampMode::PerBurst`])
+    /// using the sample rate of the most recently streamed chunk; they are not
+    /// ramped if no chunk has been streamed yet.
+    pub fn set_ramp(&self, ramp: Option<TxRamp>) {
+        self.ramp.send_replace(ramp);
+    }
+    /// Get [`watch::Receiver`] of total number of timeouts when writing to
+    /// the hardware
+    pub fn write_timeout_count(&self) -> watch::Receiver<u64> {
+        self.write_timeout_count.clone()
+    }
+    /// Get [`watch::Receiver`] of the error which ended streaming (if any)
+    ///
+    /// The value is set when the background task ends because of a stream
+    /// error or a panic (e.g. in the driver), such that a supervisor can
+    /// react. Afterwards, all methods controlling the stream return an error.
+    pub fn failure(&self) -> watch::Receiver<Option<crate::error::Error>> {
+        self.failure.clone()
+    }
+    /// Get scheduling settings of the threads writing to the hardware
+    pub fn thread_config(&self) -> ThreadConfig {
+        self.thread_config.borrow().config().clone()
+    }
+    /// Set priority and CPU affinity of the threads writing to the hardware
+    ///
+    /// The settings are applied to each (blocking) thread before it writes
+    /// streamed chunks. Settings which cannot be applied are skipped. See
+    /// the [`thread`] module for details.
+    ///
+    /// [`thread`]: crate::blocks::io::thread
+    pub fn set_thread_config(&self, config: ThreadConfig) {
+        self.thread_config
+            .send_replace(SharedThreadConfig::new(config));
+    }
+    /// Transmit a finite burst of `samples`, optionally starting at the given
+    /// hardware time `time_ns` (in nanoseconds, see
+    /// [`::soapysdr::Device::get_hardware_time`])
+    ///
+    /// The samples are written with the `HAS_TIME` flag (if `time_ns` is
+    /// given) and the `END_BURST` flag, such that the transmitter may be
+    /// switched off after the burst. The burst is written in between
+    /// chunks received by the block, and streaming must be active.
+    ///
+    /// Errors when writing the burst (including underflows, timeouts, or late
+    /// timestamps) are returned but do not end streaming.
+    pub async fn transmit_burst(
+        &self,
+        samples: Vec<Complex<f32>>,
+        time_ns: Option<i64>,
+    ) -> Result<(), Error> {
+        let task_ended = || Error {
+            code: soapysdr::ErrorCode::Other,
+            message: "SoapySdrTx task ended".into(),
+        };
+        if !matches!(*self.state_recv.borrow(), State::Active) {
+            return Err(Error {
+                code: soapysdr::ErrorCode::StreamError,
+                message: "stream not active".into(),
+            });
+        }
+        let (reply, reply_recv) = oneshot::channel();
+        self.burst_send
+            .send(Burst {
+                samples,
+                time_ns,
+                reply,
+            })
+            .map_err(|_| task_ended())?;
+        reply_recv.await.unwrap_or_else(|_| Err(task_ended()))
+    }
     /// Activate streaming
     pub async fn activate(&self) -> Result<(), soapysdr::Error> {
         let mut state_recv = self.state_recv.clone();
@@ -412,7 +2340,7 @@ impl SoapySdrTx {
                         let state = state_recv.borrow_and_update().clone();
                         match state {
                             State::Closed(result) => return result,
-                            _ => panic!("SoapySdrTx task ended unexpectedly"),
+                            _ => return Err(task_panicked("SoapySdrTx task")),
                         }
                     }
                     let state = state_recv.borrow_and_update().clone();
@@ -438,7 +2366,7 @@ impl SoapySdrTx {
                         let state = state_recv.borrow_and_update().clone();
                         match state {
                             State::Closed(result) => return result,
-                            _ => panic!("SoapySdrTx task ended unexpectedly"),
+                            _ => return Err(task_panicked("SoapySdrTx task")),
                         }
                     }
                     let state = state_recv.borrow_and_update().clone();
@@ -456,15 +2384,429 @@ impl SoapySdrTx {
     /// Deactivate streaming and return inner [`::soapysdr::TxStream`]
     pub async fn into_inner(mut self) -> Result<soapysdr::TxStream<Complex<f32>>, Error> {
         self.request_send.send_replace(Request::Close);
-        let tx_stream = self.join_handle.await.unwrap();
+        let tx_stream = self.join_handle.await.ok().flatten();
         let state = self.state_recv.borrow_and_update().clone();
-        match state {
-            State::Closed(Ok(())) => Ok(tx_stream),
-            State::Closed(Err(err)) => Err(err),
-            _ => panic!("SoapySdrTx task ended unexpectedly"),
+        match (state, tx_stream) {
+            (State::Closed(Ok(())), Some(tx_stream)) => Ok(tx_stream),
+            (State::Closed(Err(err)), _) => Err(err),
+            _ => Err(task_panicked("SoapySdrTx task")),
         }
     }
 }
 
 #[cfg(test)]
-mod tests {}
+mod tests {
+    use super::*;
+    /// Stream which returns the results of `reads` in reverse order
+    struct MockStream {
+        reads: Vec<Result<usize, Error>>,
+    }
+    impl<T: Send> RxRead<T> for MockStream {
+        fn read(&mut self, buffers: &[&mut [T]], _: i64) -> Result<usize, Error> {
+            let result = self.reads.pop().unwrap_or(Ok(0));
+            Ok(result?.min(buffers[0].len()))
+        }
+    }
+    impl<T: Send + 'static> RxStreamControl<T> for MockStream {
+        fn mtu(&self) -> Result<usize, Error> {
+            Ok(16)
+        }
+        fn activate(&mut self) -> Result<(), Error> {
+            Ok(())
+        }
+        fn deactivate(&mut self) -> Result<(), Error> {
+            Ok(())
+        }
+    }
+    /// Length, time, and end-of-burst flag of each write to a
+    /// [`MockTxStream`]
+    type WriteLog = Arc<std::sync::Mutex<Vec<(usize, Option<i64>, bool)>>>;
+    /// Transmit stream which logs all writes and returns the (popped)
+    /// `results`
+    struct MockTxStream {
+        writes: WriteLog,
+        results: Vec<Result<(), Error>>,
+    }
+    impl TxStreamControl for MockTxStream {
+        fn activate(&mut self) -> Result<(), Error> {
+            Ok(())
+        }
+        fn deactivate(&mut self) -> Result<(), Error> {
+            Ok(())
+        }
+        fn write_all(
+            &mut self,
+            buffers: &[&[Complex<f32>]],
+            at_ns: Option<i64>,
+            end_burst: bool,
+            _: i64,
+        ) -> Result<(), Error> {
+            let write = (buffers[0].len(), at_ns, end_burst);
+            self.writes.lock().unwrap().push(write);
+            self.results.pop().unwrap_or(Ok(()))
+        }
+    }
+    /// Spawn receive task which reads channel `0` of a `null` device at
+    /// 48 kHz from a [`MockStream`] with given `reads`
+    fn spawn_mock<Flt: RxSample>(
+        reads: Vec<Result<usize, Error>>,
+    ) -> (
+        RxControl<MockStream>,
+        SoapySdrRxHandle,
+        crate::sync::broadcast_bp::Receiver<Signal<Complex<Flt>>>,
+    ) {
+        let device = soapysdr::Device::new("driver=null").unwrap();
+        let (sender, sender_connector) = new_sender::<Signal<Complex<Flt>>>();
+        let receiver = sender_connector.subscribe();
+        let control = RxControl::spawn(
+            &runtime::Handle::current(),
+            device.clone(),
+            MockStream { reads },
+            48000.0,
+            vec![0],
+            vec![sender],
+            vec![None],
+        );
+        let handle = SoapySdrRxHandle {
+            device,
+            channels: vec![0],
+            shared: control.shared.clone(),
+        };
+        (control, handle, receiver)
+    }
+    #[test]
+    fn test_ramp_state() {
+        let ramp = Some(TxRamp {
+            duration_ms: 1.0,
+            mode: RampMode::PerBurst,
+        });
+        let mut state = RampState::new();
+        let mut output = Vec::new();
+        let ones = vec![Complex::new(1.0f32, 0.0); 6];
+        state.process(ramp, 4000.0, &ones, &mut output);
+        assert_eq!(output.len(), 2);
+        for (pos, sample) in output.iter().enumerate() {
+            assert_eq!(sample.re, ramp_gain(4, pos));
+        }
+        assert!(output[0].re < output[1].re && output[1].re < 0.5);
+        output.clear();
+        state.process(ramp, 4000.0, &ones, &mut output);
+        assert_eq!(output.len(), 6);
+        assert_eq!(output[1].re, ramp_gain(4, 3));
+        assert!(output[2..].iter().all(|sample| sample.re == 1.0));
+        output.clear();
+        state.end(true, &mut output);
+        assert_eq!(output.len(), 4);
+        for (idx, sample) in output.iter().enumerate() {
+            assert_eq!(sample.re, ramp_gain(4, 3 - idx));
+        }
+        // next burst is ramped up again
+        output.clear();
+        state.process(ramp, 4000.0, &ones[..1], &mut output);
+        state.end(false, &mut output);
+        assert_eq!(output, vec![Complex::new(ramp_gain(4, 0), 0.0)]);
+        // without ramping down, the next burst is not ramped up
+        output.clear();
+        state.process(ramp, 4000.0, &ones, &mut output);
+        state.end(false, &mut output);
+        assert_eq!(output, ones);
+        let mut samples = ones.clone();
+        apply_ramp(&mut samples, 4);
+        assert_eq!(samples[0], samples[5]);
+        assert_eq!(samples[2].re, ramp_gain(3, 2));
+    }
+    #[test]
+    fn test_backoff() {
+        let second = Duration::from_secs(1);
+        let mut backoff = Backoff::new();
+        assert_eq!(backoff.next_delay(3, second), Some(second));
+        assert_eq!(backoff.next_delay(3, second), Some(2 * second));
+        assert_eq!(backoff.succeeded(), 2);
+        // attempts and delay start over after success
+        assert_eq!(backoff.next_delay(3, second), Some(second));
+        assert_eq!(backoff.next_delay(3, second), Some(2 * second));
+        assert_eq!(backoff.next_delay(3, second), Some(4 * second));
+        assert_eq!(backoff.next_delay(3, second), None);
+        assert_eq!(backoff.attempts, 3);
+        assert_eq!(backoff.succeeded(), 3);
+        assert_eq!(backoff.next_delay(0, second), None);
+        assert_eq!(backoff.succeeded(), 0);
+    }
+    #[test]
+    fn test_fill_buffers() {
+        let overflow = || Error {
+            code: soapysdr::ErrorCode::Overflow,
+            message: String::new(),
+        };
+        let mut buf_pools: Vec<ChunkBufPool<Complex<f32>>> =
+            (0..2).map(|_| ChunkBufPool::new()).collect();
+        let mut buffers = get_buffers(&mut buf_pools, 10);
+        let mut stream = MockStream {
+            reads: vec![Ok(8), Ok(4), Ok(4)],
+        };
+        let (result, pending_error) = fill_buffers(&mut stream, &mut buffers, 0);
+        assert_eq!(result.unwrap(), 10);
+        assert!(pending_error.is_none());
+        assert_eq!(stream.reads.len(), 0);
+        let mut stream = MockStream {
+            reads: vec![Err(overflow()), Ok(3)],
+        };
+        let (result, pending_error) = fill_buffers(&mut stream, &mut buffers, 0);
+        assert_eq!(result.unwrap(), 3);
+        assert_eq!(pending_error.unwrap().code, soapysdr::ErrorCode::Overflow);
+        let mut stream = MockStream {
+            reads: vec![Err(overflow())],
+        };
+        let (result, pending_error) = fill_buffers(&mut stream, &mut buffers, 0);
+        assert_eq!(result.unwrap_err().code, soapysdr::ErrorCode::Overflow);
+        assert!(pending_error.is_none());
+        let signals = samples_signals(buffers, 3, 48000.0);
+        assert_eq!(signals.len(), 2);
+        for signal in signals {
+            let Signal::Samples { sample_rate, chunk } = signal else { panic!() };
+            assert_eq!((sample_rate, chunk.len()), (48000.0, 3));
+        }
+    }
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_set_gain_while_streaming() {
+        let (control, handle, mut receiver) = spawn_mock::<f32>(vec![Ok(16); 100]);
+        tokio::time::timeout(Duration::from_secs(10), async {
+            handle.activate().await.unwrap();
+            let mut chunks = 0;
+            while chunks < 10 {
+                if let Signal::Samples { .. } = receiver.recv().await.unwrap() {
+                    chunks += 1;
+                    handle.set_gain(0, chunks as f64).unwrap();
+                }
+            }
+            handle.deactivate().await.unwrap();
+        })
+        .await
+        .expect("set_gain blocked the receive task");
+        let stream = control.into_inner().await.unwrap();
+        assert!(stream.reads.len() < 90);
+    }
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_rx_sample_types() {
+        async fn receive<Flt: RxSample + PartialEq + std::fmt::Debug>() {
+            let (control, handle, mut receiver) = spawn_mock::<Flt>(vec![Ok(10), Ok(16)]);
+            handle.activate().await.unwrap();
+            let Signal::Event(event) = receiver.recv().await.unwrap() else { panic!() };
+            assert!(event.as_any().is::<Timestamp>());
+            for len in [16, 10] {
+                let signal = receiver.recv().await.unwrap();
+                let Signal::Samples { sample_rate, chunk } = signal else { panic!() };
+                assert_eq!(sample_rate, 48000.0);
+                assert_eq!(chunk.len(), len);
+                assert!(chunk
+                    .iter()
+                    .all(|x| x.re == Flt::zero() && x.im == Flt::zero()));
+            }
+            handle.deactivate().await.unwrap();
+            assert!(control.into_inner().await.unwrap().reads.is_empty());
+        }
+        receive::<f32>().await;
+        receive::<f64>().await;
+        receive::<i16>().await;
+    }
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_set_sample_rate_while_streaming() {
+        let (control, handle, mut receiver) = spawn_mock::<f32>(vec![Ok(16); 100]);
+        handle.activate().await.unwrap();
+        let Signal::Event(event) = receiver.recv().await.unwrap() else { panic!() };
+        assert!(event.as_any().is::<Timestamp>());
+        handle.set_sample_rate(0, 96000.0).await.unwrap();
+        assert!(handle.is_active());
+        let applied = handle
+            .device()
+            .sample_rate(soapysdr::Direction::Rx, 0)
+            .unwrap();
+        assert_eq!(handle.sample_rate(), applied);
+        // chunks read before the change may still be in the channel
+        loop {
+            match receiver.recv().await.unwrap() {
+                Signal::Samples { sample_rate, .. } => assert_eq!(sample_rate, 48000.0),
+                Signal::Event(event) if event.as_any().is::<Timestamp>() => break,
+                Signal::Event(_) => (),
+            }
+        }
+        let sample_rate = loop {
+            if let Signal::Samples { sample_rate, .. } = receiver.recv().await.unwrap() {
+                break sample_rate;
+            }
+        };
+        assert_eq!(sample_rate, applied);
+        handle.deactivate().await.unwrap();
+        control.into_inner().await.unwrap();
+    }
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_tx_bursts() {
+        let error = |code| Error {
+            code,
+            message: "mock".into(),
+        };
+        let writes = WriteLog::default();
+        let tx_stream = MockTxStream {
+            writes: writes.clone(),
+            results: vec![
+                Err(error(soapysdr::ErrorCode::Timeout)),
+                Err(error(soapysdr::ErrorCode::Underflow)),
+            ],
+        };
+        let tx = SoapySdrTx::spawn(tx_stream, |_| None);
+        let (sender, sender_connector) = new_sender::<Signal<Complex<f32>>>();
+        tx.feed_from(&sender_connector);
+        let mut underflow_count = tx.underflow_count();
+        let mut write_timeout_count = tx.write_timeout_count();
+        tx.activate().await.unwrap();
+        sender
+            .send(Signal::Samples {
+                sample_rate: 48000.0,
+                chunk: vec![Complex::new(1.0, 0.0); 16].into(),
+            })
+            .await
+            .unwrap();
+        underflow_count.wait_for(|&count| count == 1).await.unwrap();
+        let burst = vec![Complex::new(1.0, 0.0); 8];
+        let result = tx.transmit_burst(burst, Some(1234)).await;
+        assert_eq!(result.unwrap_err().code, soapysdr::ErrorCode::Timeout);
+        assert_eq!(*write_timeout_count.borrow_and_update(), 1);
+        let end_of_burst = tx.wait_for_event(|event| event.as_any().is::<EndOfBurst>());
+        sender.send(Signal::new_event(EndOfBurst)).await.unwrap();
+        end_of_burst.await;
+        tx.deactivate().await.unwrap();
+        // a zero sample is written upon creation and deactivation
+        assert_eq!(
+            *writes.lock().unwrap(),
+            [
+                (1, None, false),
+                (16, None, false),
+                (8, Some(1234), true),
+                (1, None, true),
+                (1, None, false),
+            ]
+        );
+    }
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_chunk_duration() {
+        /// Activate and return length of first chunk read afterwards
+        async fn first_chunk_len(
+            handle: &SoapySdrRxHandle,
+            receiver: &mut crate::sync::broadcast_bp::Receiver<Signal<Complex<f32>>>,
+        ) -> usize {
+            handle.activate().await.unwrap();
+            // chunks read before a previous deactivation may still be in the
+            // channel
+            loop {
+                match receiver.recv().await.unwrap() {
+                    Signal::Event(event) if event.as_any().is::<Timestamp>() => break,
+                    _ => (),
+                }
+            }
+            let len = loop {
+                if let Signal::Samples { chunk, .. } = receiver.recv().await.unwrap() {
+                    break chunk.len();
+                }
+            };
+            handle.deactivate().await.unwrap();
+            len
+        }
+        let (control, handle, mut receiver) = spawn_mock::<f32>(vec![Ok(16); 100]);
+        assert_eq!(handle.mtu().unwrap(), 16);
+        assert_eq!(first_chunk_len(&handle, &mut receiver).await, 16);
+        handle.set_chunk_size(Some(40));
+        assert_eq!(first_chunk_len(&handle, &mut receiver).await, 40);
+        // 52.8 samples are rounded up to a multiple of the MTU
+        handle.set_chunk_duration(Some(Duration::from_micros(1100)));
+        assert_eq!(first_chunk_len(&handle, &mut receiver).await, 64);
+        handle.set_chunk_duration(None);
+        assert_eq!(first_chunk_len(&handle, &mut receiver).await, 40);
+        control.into_inner().await.unwrap();
+    }
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_reconfigure_while_streaming() {
+        let (control, handle, mut receiver) = spawn_mock::<f32>(vec![Ok(16); 100]);
+        handle.activate().await.unwrap();
+        let Signal::Event(event) = receiver.recv().await.unwrap() else { panic!() };
+        assert!(event.as_any().is::<Timestamp>());
+        let mut paused = false;
+        handle
+            .reconfigure(|_| {
+                paused = !handle.is_active();
+                Ok(())
+            })
+            .await
+            .unwrap();
+        assert!(paused);
+        assert!(handle.is_active());
+        // chunks read before reconfiguring may still be in the channel
+        loop {
+            match receiver.recv().await.unwrap() {
+                Signal::Event(event) if event.as_any().is::<Timestamp>() => break,
+                _ => (),
+            }
+        }
+        // the center frequency is read back from the device
+        let Signal::Event(event) = receiver.recv().await.unwrap() else { panic!() };
+        let frequency = event.as_any().downcast_ref::<CenterFrequency>().unwrap().0;
+        assert_eq!(frequency, handle.frequency(0).unwrap());
+        let Signal::Samples { .. } = receiver.recv().await.unwrap() else { panic!() };
+        // streaming is resumed even if configuring fails
+        let result = handle
+            .reconfigure(|_| Err(not_supported("reconfiguring")))
+            .await;
+        assert_eq!(result.unwrap_err().code, soapysdr::ErrorCode::NotSupported);
+        assert!(handle.is_active());
+        handle.deactivate().await.unwrap();
+        control.into_inner().await.unwrap();
+    }
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_state_changes() {
+        let error = Error {
+            code: soapysdr::ErrorCode::Other,
+            message: "mock".into(),
+        };
+        let reads = vec![Err(error), Ok(16), Ok(16)];
+        let (control, handle, mut receiver) = spawn_mock::<f32>(reads);
+        let mut state_changes = handle.state_changes();
+        assert!(!handle.is_active());
+        handle.activate().await.unwrap();
+        assert!(handle.is_active());
+        assert!(state_changes.has_changed().unwrap());
+        assert!(*state_changes.borrow_and_update());
+        handle.deactivate().await.unwrap();
+        assert!(!handle.is_active());
+        assert!(!*state_changes.borrow_and_update());
+        handle.activate().await.unwrap();
+        assert!(*state_changes.borrow_and_update());
+        // the stream error ends streaming once the chunks are consumed
+        let drain = async {
+            while receiver.recv().await.is_ok() {}
+            std::future::pending::<()>().await
+        };
+        tokio::select! {
+            result = state_changes.wait_for(|&streaming| !streaming) => {
+                result.unwrap();
+            }
+            _ = drain => unreachable!(),
+        }
+        assert!(!handle.is_active());
+        let Err(err) = control.into_inner().await else { panic!() };
+        assert_eq!(err.code, soapysdr::ErrorCode::Other);
+        assert!(handle.failure().borrow().is_some());
+    }
+    #[test]
+    fn test_device_info() {
+        let args = soapysdr::Args::from("driver=rtlsdr, label=Generic RTL2832U, serial=42");
+        let info = DeviceInfo::from_args(&args);
+        assert_eq!(info.driver, "rtlsdr");
+        assert_eq!(info.label, "Generic RTL2832U");
+        assert_eq!(info.serial.as_deref(), Some("42"));
+        assert_eq!(
+            soapysdr::Args::from(info.args.as_str()).get("label"),
+            Some("Generic RTL2832U")
+        );
+        let info = DeviceInfo::from_args(&soapysdr::Args::new());
+        assert!(info.driver.is_empty() && info.serial.is_none() && info.args.is_empty());
+    }
+}
@@ -0,0 +1,484 @@
+//! Synthetic signal sources and an in-memory loopback channel for testing
+
+use crate::bufferpool::*;
+use crate::flow::*;
+use crate::impl_block_trait;
+use crate::math::NoiseGenerator;
+use crate::numbers::*;
+use crate::signal::*;
+
+use tokio::sync::watch;
+use tokio::task::spawn;
+
+use std::collections::VecDeque;
+
+/// Waveform emitted by a [`SignalGenerator`]
+#[derive(Clone, PartialEq, Debug)]
+pub enum Waveform {
+    /// Complex sinusoid at the generator's frequency
+    Tone,
+    /// Sum of complex sinusoids at the given frequencies (in hertz) relative
+    /// to the generator's frequency, each with the generator's amplitude
+    MultiTone(Vec<f64>),
+    /// Two complex sinusoids spaced by the given frequency (in hertz)
+    /// symmetrically around the generator's frequency, each with half the
+    /// generator's amplitude (such that the peak envelope equals the
+    /// amplitude), e.g. for intermodulation testing
+    TwoTone(f64),
+    /// Repeated linear frequency sweep (chirp) from `start` to `stop` (in
+    /// hertz relative to the generator's frequency) within `duration`
+    /// seconds
+    ///
+    /// A [`SweepStart`] event is sent before the first sample of each sweep.
+    /// Chunks end at the end of each sweep, thus the last chunk of a sweep
+    /// may be shorter than the configured chunk length.
+    Sweep {
+        /// Start frequency in hertz
+        start: f64,
+        /// Stop frequency in hertz
+        stop: f64,
+        /// Duration of each sweep in seconds
+        duration: f64,
+    },
+    /// Complex white Gaussian noise, where the amplitude is the RMS value
+    Noise,
+}
+
+/// Event sent by a [`SignalGenerator`] before the first sample of each
+/// [sweep]
+///
+/// [sweep]: Waveform::Sweep
+#[derive(Clone, Debug)]
+pub struct SweepStart;
+
+impl Event for SweepStart {
+    fn as_any(&self) -> &(dyn std::any::Any + Send + Sync) {
+        self
+    }
+}
+
+/// [`Producer`] which synthesizes a [`Waveform`] with adjustable frequency
+/// and amplitude
+///
+/// Chunks are sent as fast as they are consumed. The phase of the generated
+/// sinusoids is continuous across chunks and when changing the frequency.
+pub struct SignalGenerator<Flt> {
+    sender_connector: SenderConnector<Signal<Complex<Flt>>>,
+    frequency: watch::Sender<f64>,
+    amplitude: watch::Sender<f64>,
+}
+
+impl_block_trait! { <Flt> Producer<Signal<Complex<Flt>>> for SignalGenerator<Flt> }
+
+impl<Flt> SignalGenerator<Flt>
+where
+    Flt: Float,
+{
+    /// Create new `SignalGenerator` emitting a complex sinusoid ([`Tone`])
+    /// with given `frequency` in hertz and `amplitude` in chunks of
+    /// `chunk_len` samples
+    ///
+    /// [`Tone`]: Waveform::Tone
+    pub fn new(chunk_len: usize, sample_rate: f64, frequency: f64, amplitude: f64) -> Self {
+        Self::with_waveform(Waveform::Tone, chunk_len, sample_rate, frequency, amplitude)
+    }
+    /// Create new `SignalGenerator` emitting given [`Waveform`] with given
+    /// `frequency` in hertz and `amplitude` in chunks of `chunk_len` samples
+    pub fn with_waveform(
+        waveform: Waveform,
+        chunk_len: usize,
+        sample_rate: f64,
+        frequency: f64,
+        amplitude: f64,
+    ) -> Self {
+        use std::f64::consts::TAU;
+        assert!(chunk_len > 0, "chunk length must be positive");
+        let (sender, sender_connector) = new_sender::<Signal<Complex<Flt>>>();
+        let (frequency_send, mut frequency_recv) = watch::channel(frequency);
+        let (amplitude_send, mut amplitude_recv) = watch::channel(amplitude);
+        spawn(async move {
+            let mut buf_pool = ChunkBufPool::<Complex<Flt>>::new();
+            let mut frequency = frequency;
+            let mut amplitude = amplitude;
+            let (offsets, scale): (Vec<f64>, f64) = match &waveform {
+                Waveform::Tone => (vec![0.0], 1.0),
+                Waveform::MultiTone(offsets) => (offsets.clone(), 1.0),
+                Waveform::TwoTone(spacing) => (vec![-spacing / 2.0, spacing / 2.0], 0.5),
+                Waveform::Sweep { .. } | Waveform::Noise => (Vec::new(), 1.0),
+            };
+            let mut phases: Vec<f64> = vec![0.0; offsets.len()];
+            let mut noise = NoiseGenerator::new();
+            let sweep = match waveform {
+                Waveform::Sweep {
+                    start,
+                    stop,
+                    duration,
+                } => {
+                    let sweep_len = (duration * sample_rate).round() as usize;
+                    Some((start, stop, sweep_len.max(1)))
+                }
+                _ => None,
+            };
+            let mut sweep_phase: f64 = 0.0;
+            let mut sweep_pos: usize = 0;
+            loop {
+                match frequency_recv.has_changed() {
+                    Ok(false) => (),
+                    Ok(true) => frequency = *frequency_recv.borrow_and_update(),
+                    Err(_) => return,
+                }
+                match amplitude_recv.has_changed() {
+                    Ok(false) => (),
+                    Ok(true) => amplitude = *amplitude_recv.borrow_and_update(),
+                    Err(_) => return,
+                }
+                let mut output_chunk = buf_pool.get_with_capacity(chunk_len);
+                if waveform == Waveform::Noise {
+                    for _ in 0..chunk_len {
+                        let sample = noise.complex_gaussian() * amplitude;
+                        output_chunk.push(Complex::new(flt!(sample.re), flt!(sample.im)));
+                    }
+                } else if let Some((start, stop, sweep_len)) = sweep {
+                    if sweep_pos == 0 {
+                        let Ok(()) = sender.send(Signal::new_event(SweepStart)).await
+                        else { return; };
+                    }
+                    for _ in 0..chunk_len.min(sweep_len - sweep_pos) {
+                        let offset = start + (stop - start) * sweep_pos as f64 / sweep_len as f64;
+                        let sample = Complex::from_polar(amplitude, sweep_phase);
+                        output_chunk.push(Complex::new(flt!(sample.re), flt!(sample.im)));
+                        sweep_phase =
+                            (sweep_phase + TAU * (frequency + offset) / sample_rate) % TAU;
+                        sweep_pos += 1;
+                    }
+                    if sweep_pos == sweep_len {
+                        sweep_pos = 0;
+                    }
+                } else {
+                    let steps: Vec<f64> = offsets
+                        .iter()
+                        .map(|offset| TAU * (frequency + offset) / sample_rate)
+                        .collect();
+                    for _ in 0..chunk_len {
+                        let mut sample: Complex<f64> = Complex::from(0.0);
+                        for (phase, step) in phases.iter_mut().zip(steps.iter()) {
+                            sample += Complex::from_polar(amplitude * scale, *phase);
+                            *phase = (*phase + step) % TAU;
+                        }
+                        output_chunk.push(Complex::new(flt!(sample.re), flt!(sample.im)));
+                    }
+                }
+                let Ok(()) = sender
+                    .send(Signal::Samples {
+                        sample_rate,
+                        chunk: output_chunk.finalize(),
+                    })
+                    .await
+                else { return; };
+            }
+        });
+        Self {
+            sender_connector,
+            frequency: frequency_send,
+            amplitude: amplitude_send,
+        }
+    }
+    /// Get frequency in hertz
+    pub fn frequency(&self) -> f64 {
+        *self.frequency.borrow()
+    }
+    /// Set frequency in hertz
+    pub fn set_frequency(&self, frequency: f64) {
+        self.frequency.send_replace(frequency);
+    }
+    /// Get amplitude
+    pub fn amplitude(&self) -> f64 {
+        *self.amplitude.borrow()
+    }
+    /// Set amplitude
+    pub fn set_amplitude(&self, amplitude: f64) {
+        self.amplitude.send_replace(amplitude);
+    }
+}
+
+/// Block which simulates a radio channel between a transmitter and a
+/// receiver, e.g. to test modulator and demodulator chains without hardware
+///
+/// Samples fed into the block (as if they were sent to a transmit block like
+/// `SoapySdrTx`) are emitted (as if they were received by a block like
+/// `SoapySdrRx`) after applying the following channel model in this order:
+///
+/// * delay by a given number of samples (initially filled with zeros),
+/// * gain (usually negative, i.e. attenuation) in decibels,
+/// * frequency offset in hertz (with continuous phase),
+/// * additive white Gaussian noise with a given power in decibels (where
+///   0 dB corresponds to a power of `1.0`).
+///
+/// All parameters can be changed at runtime. The default model is an ideal
+/// channel without delay, attenuation, offset, or noise. Events are passed
+/// through (without delay).
+pub struct Loopback<Flt> {
+    receiver_connector: ReceiverConnector<Signal<Complex<Flt>>>,
+    sender_connector: SenderConnector<Signal<Complex<Flt>>>,
+    delay: watch::Sender<usize>,
+    gain_db: watch::Sender<f64>,
+    frequency_offset: watch::Sender<f64>,
+    noise_db: watch::Sender<f64>,
+}
+
+impl_block_trait! { <Flt> Consumer<Signal<Complex<Flt>>> for Loopback<Flt> }
+impl_block_trait! { <Flt> Producer<Signal<Complex<Flt>>> for Loopback<Flt> }
+
+impl<Flt> Loopback<Flt>
+where
+    Flt: Float,
+{
+    /// Create new `Loopback` with an ideal channel, where noise is seeded from
+    /// the system time
+    pub fn new() -> Self {
+        Self::with_noise_generator(NoiseGenerator::new())
+    }
+    /// Create new `Loopback` with an ideal channel, where noise is generated
+    /// reproducibly from given `seed`
+    pub fn with_seed(seed: u64) -> Self {
+        Self::with_noise_generator(NoiseGenerator::with_seed(seed))
+    }
+    fn with_noise_generator(mut noise: NoiseGenerator) -> Self {
+        use std::f64::consts::TAU;
+        let (mut receiver, receiver_connector) = new_receiver::<Signal<Complex<Flt>>>();
+        let (sender, sender_connector) = new_sender::<Signal<Complex<Flt>>>();
+        let (delay_send, mut delay_recv) = watch::channel(0usize);
+        let (gain_db_send, mut gain_db_recv) = watch::channel(0.0);
+        let (frequency_offset_send, mut frequency_offset_recv) = watch::channel(0.0);
+        let (noise_db_send, mut noise_db_recv) = watch::channel(f64::NEG_INFINITY);
+        spawn(async move {
+            let mut buf_pool = ChunkBufPool::<Complex<Flt>>::new();
+            let mut delay_line: VecDeque<Complex<Flt>> = VecDeque::new();
+            let mut gain: f64 = 1.0;
+            let mut frequency_offset: f64 = 0.0;
+            let mut noise_amplitude: f64 = 0.0;
+            let mut phase: f64 = 0.0;
+            loop {
+                let Ok(signal) = receiver.recv().await else { return; };
+                match signal {
+                    Signal::Samples {
+                        sample_rate,
+                        chunk: input_chunk,
+                    } => {
+                        if delay_recv.has_changed().unwrap_or(false) {
+                            let delay = *delay_recv.borrow_and_update();
+                            if delay_line.len() > delay {
+                                delay_line.drain(0..delay_line.len() - delay);
+                            } else {
+                                for _ in delay_line.len()..delay {
+                                    delay_line.push_front(Complex::from(Flt::zero()));
+                                }
+                            }
+                        }
+                        if gain_db_recv.has_changed().unwrap_or(false) {
+                            gain = amplitude_from_db(*gain_db_recv.borrow_and_update());
+                        }
+                        if frequency_offset_recv.has_changed().unwrap_or(false) {
+                            frequency_offset = *frequency_offset_recv.borrow_and_update();
+                        }
+                        if noise_db_recv.has_changed().unwrap_or(false) {
+                            noise_amplitude = amplitude_from_db(*noise_db_recv.borrow_and_update());
+                        }
+                        let step = TAU * frequency_offset / sample_rate;
+                        let mut output_chunk = buf_pool.get_with_capacity(input_chunk.len());
+                        for &sample in input_chunk.iter() {
+                            delay_line.push_back(sample);
+                            let sample = delay_line.pop_front().unwrap();
+                            let sample = Complex::new(
+                                sample.re.to_f64().unwrap(),
+                                sample.im.to_f64().unwrap(),
+                            ) * Complex::from_polar(gain, phase);
+                            phase = (phase + step) % TAU;
+                            let sample = match noise_amplitude > 0.0 {
+                                true => sample + noise.complex_gaussian() * noise_amplitude,
+                                false => sample,
+                            };
+                            output_chunk.push(Complex::new(flt!(sample.re), flt!(sample.im)));
+                        }
+                        let Ok(()) = sender
+                            .send(Signal::Samples {
+                                sample_rate,
+                                chunk: output_chunk.finalize(),
+                            })
+                            .await
+                        else { return; };
+                    }
+                    event @ Signal::Event { .. } => {
+                        let Ok(()) = sender.send(event).await else { return; };
+                    }
+                }
+            }
+        });
+        Self {
+            receiver_connector,
+            sender_connector,
+            delay: delay_send,
+            gain_db: gain_db_send,
+            frequency_offset: frequency_offset_send,
+            noise_db: noise_db_send,
+        }
+    }
+    /// Get delay in samples
+    pub fn delay(&self) -> usize {
+        *self.delay.borrow()
+    }
+    /// Set delay in samples
+    ///
+    /// When the delay is increased, zeros are inserted. When it is decreased,
+    /// the oldest delayed samples are dropped.
+    pub fn set_delay(&self, delay: usize) {
+        self.delay.send_replace(delay);
+    }
+    /// Get gain in decibels
+    pub fn gain_db(&self) -> f64 {
+        *self.gain_db.borrow()
+    }
+    /// Set gain in decibels
+    pub fn set_gain_db(&self, gain_db: f64) {
+        assert!(!gain_db.is_nan(), "gain must not be NaN");
+        self.gain_db.send_replace(gain_db);
+    }
+    /// Get frequency offset in hertz
+    pub fn frequency_offset(&self) -> f64 {
+        *self.frequency_offset.borrow()
+    }
+    /// Set frequency offset in hertz
+    pub fn set_frequency_offset(&self, frequency_offset: f64) {
+        self.frequency_offset.send_replace(frequency_offset);
+    }
+    /// Get noise power in decibels
+    pub fn noise_db(&self) -> f64 {
+        *self.noise_db.borrow()
+    }
+    /// Set noise power in decibels (or [`f64::NEG_INFINITY`] for no noise)
+    pub fn set_noise_db(&self, noise_db: f64) {
+        assert!(!noise_db.is_nan(), "noise power must not be NaN");
+        self.noise_db.send_replace(noise_db);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::assert_approx;
+    #[tokio::test]
+    async fn test_signal_generator() {
+        use std::f64::consts::TAU;
+        let generator = SignalGenerator::<f64>::new(16, 48000.0, 1000.0, 0.5);
+        let (mut receiver, receiver_connector) = new_receiver::<Signal<Complex<f64>>>();
+        generator.feed_into(&receiver_connector);
+        for i in 0..2 {
+            let Signal::Samples { sample_rate, chunk } = receiver.recv().await.unwrap()
+            else { panic!(); };
+            assert_eq!(sample_rate, 48000.0);
+            assert_eq!(chunk.len(), 16);
+            for (j, sample) in chunk.iter().enumerate() {
+                let expected =
+                    Complex::from_polar(0.5, TAU * 1000.0 * (16 * i + j) as f64 / 48000.0);
+                assert_approx(sample.re, expected.re);
+                assert_approx(sample.im, expected.im);
+            }
+        }
+        generator.set_amplitude(2.0);
+        loop {
+            let Signal::Samples { chunk, .. } = receiver.recv().await.unwrap()
+            else { panic!(); };
+            if (chunk[0].norm() - 2.0).abs() < 1e-9 {
+                break;
+            }
+        }
+    }
+    #[tokio::test]
+    async fn test_sweep_and_two_tone() {
+        let waveform = Waveform::Sweep {
+            start: -1000.0,
+            stop: 1000.0,
+            duration: 0.01,
+        };
+        let generator = SignalGenerator::<f64>::with_waveform(waveform, 64, 48000.0, 0.0, 1.0);
+        let (mut receiver, receiver_connector) = new_receiver::<Signal<Complex<f64>>>();
+        generator.feed_into(&receiver_connector);
+        let mut samples: Vec<Complex<f64>> = Vec::new();
+        let mut starts: Vec<usize> = Vec::new();
+        while samples.len() < 1200 {
+            match receiver.recv().await.unwrap() {
+                Signal::Samples { chunk, .. } => samples.extend_from_slice(&chunk),
+                Signal::Event(event) => {
+                    assert!(event.as_any().is::<SweepStart>());
+                    starts.push(samples.len());
+                }
+            }
+        }
+        assert_eq!(starts, vec![0, 480, 960]);
+        for pair in samples.windows(2) {
+            let step = (pair[1] / pair[0]).arg();
+            let expected = std::f64::consts::TAU * 1000.0 / 48000.0;
+            assert!(step.abs() <= expected + 1e-9);
+        }
+        let waveform = Waveform::TwoTone(2000.0);
+        let generator = SignalGenerator::<f64>::with_waveform(waveform, 480, 48000.0, 0.0, 1.0);
+        let (mut receiver, receiver_connector) = new_receiver::<Signal<Complex<f64>>>();
+        generator.feed_into(&receiver_connector);
+        let Signal::Samples { chunk, .. } = receiver.recv().await.unwrap()
+        else { panic!(); };
+        let peak = chunk.iter().map(|x| x.norm()).fold(0.0, f64::max);
+        assert_approx(peak, 1.0);
+        assert_approx(chunk[12].norm(), 0.0);
+    }
+    #[tokio::test]
+    async fn test_loopback() {
+        use std::f64::consts::TAU;
+        let (sender, sender_connector) = new_sender::<Signal<Complex<f64>>>();
+        let loopback = Loopback::<f64>::with_seed(3);
+        let (mut receiver, receiver_connector) = new_receiver::<Signal<Complex<f64>>>();
+        loopback.feed_from(&sender_connector);
+        loopback.feed_into(&receiver_connector);
+        loopback.set_delay(10);
+        loopback.set_gain_db(-20.0);
+        loopback.set_frequency_offset(100.0);
+        let input: Vec<Complex<f64>> = (0..100).map(|i| Complex::new(i as f64, 1.0)).collect();
+        for _ in 0..2 {
+            sender
+                .send(Signal::Samples {
+                    sample_rate: 48000.0,
+                    chunk: Chunk::from(input.clone()),
+                })
+                .await
+                .unwrap();
+        }
+        let mut output: Vec<Complex<f64>> = Vec::new();
+        for _ in 0..2 {
+            let Signal::Samples { chunk, .. } = receiver.recv().await.unwrap()
+            else { panic!(); };
+            output.extend_from_slice(&chunk);
+        }
+        for (i, sample) in output.iter().enumerate() {
+            let expected = match i {
+                0..=9 => Complex::from(0.0),
+                _ => {
+                    input[(i - 10) % 100]
+                        * Complex::from_polar(0.1, TAU * 100.0 * i as f64 / 48000.0)
+                }
+            };
+            assert!((sample - expected).norm() < 1e-9);
+        }
+        loopback.set_gain_db(f64::NEG_INFINITY);
+        loopback.set_noise_db(-10.0);
+        sender
+            .send(Signal::Samples {
+                sample_rate: 48000.0,
+                chunk: Chunk::from(vec![Complex::from(1.0); 10000]),
+            })
+            .await
+            .unwrap();
+        let Signal::Samples { chunk, .. } = receiver.recv().await.unwrap()
+        else { panic!(); };
+        let power = chunk.iter().map(|x| x.norm_sqr()).sum::<f64>() / chunk.len() as f64;
+        assert!((power - 0.1).abs() < 0.01);
+    }
+}
@@ -0,0 +1,180 @@
+//! Scheduling settings for threads which perform blocking hardware I/O
+//!
+//! Blocks like `SoapySdrRx` read from the hardware in blocking threads.
+//! On a loaded system, raising the priority of these threads or pinning them
+//! to dedicated CPU cores can avoid overflows. Priorities and CPU affinity
+//! are supported on Linux; other Unix systems only support priorities, and
+//! other platforms support neither.
+//!
+//! Raising the priority usually requires privileges (e.g. `CAP_SYS_NICE` on
+//! Linux). Settings which cannot be applied are skipped (and reported through
+//! `tracing` if the `tracing` feature is enabled), so that streaming continues with default
+//! scheduling.
+
+use std::cell::Cell;
+use std::io;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+/// Scheduling priority of a thread
+#[derive(Clone, Copy, PartialEq, Eq, Default, Debug)]
+pub enum ThreadPriority {
+    /// Keep the priority of the thread unchanged
+    #[default]
+    Inherit,
+    /// Nice value (from `-20`, highest priority, to `19`, lowest priority)
+    Nice(i32),
+    /// Real-time (FIFO) scheduling with given priority (usually from `1` to
+    /// `99`)
+    Realtime(i32),
+}
+
+/// Priority and CPU affinity of a thread
+#[derive(Clone, PartialEq, Eq, Default, Debug)]
+pub struct ThreadConfig {
+    /// Scheduling priority
+    pub priority: ThreadPriority,
+    /// Indices of CPU cores which the thread may run on (or `None` to keep
+    /// the affinity unchanged)
+    pub cpu_affinity: Option<Vec<usize>>,
+}
+
+#[cfg(not(target_os = "linux"))]
+fn unsupported(setting: &str) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::Unsupported,
+        format!("{setting} not supported on this platform"),
+    )
+}
+
+impl ThreadConfig {
+    /// Apply settings to the current thread
+    ///
+    /// Settings are not reverted when the thread is used for other purposes
+    /// later.
+    pub fn apply(&self) -> io::Result<()> {
+        self.apply_priority()?;
+        self.apply_affinity()
+    }
+    #[cfg(unix)]
+    fn apply_priority(&self) -> io::Result<()> {
+        match self.priority {
+            ThreadPriority::Inherit => Ok(()),
+            #[cfg(target_os = "linux")]
+            ThreadPriority::Nice(nice) => {
+                // On Linux, the nice value is a per-thread attribute.
+                // SAFETY: system calls which only change scheduling parameters
+                match unsafe {
+                    let tid = libc::syscall(libc::SYS_gettid) as libc::id_t;
+                    libc::setpriority(libc::PRIO_PROCESS, tid, nice)
+                } {
+                    0 => Ok(()),
+                    _ => Err(io::Error::last_os_error()),
+                }
+            }
+            #[cfg(not(target_os = "linux"))]
+            ThreadPriority::Nice(_) => Err(unsupported("per-thread nice value")),
+            ThreadPriority::Realtime(priority) => {
+                let param = libc::sched_param {
+                    sched_priority: priority,
+                };
+                // SAFETY: `param` is a valid `sched_param`
+                match unsafe {
+                    libc::pthread_setschedparam(libc::pthread_self(), libc::SCHED_FIFO, &param)
+                } {
+                    0 => Ok(()),
+                    errno => Err(io::Error::from_raw_os_error(errno)),
+                }
+            }
+        }
+    }
+    #[cfg(not(unix))]
+    fn apply_priority(&self) -> io::Result<()> {
+        match self.priority {
+            ThreadPriority::Inherit => Ok(()),
+            _ => Err(unsupported("thread priority")),
+        }
+    }
+    #[cfg(target_os = "linux")]
+    fn apply_affinity(&self) -> io::Result<()> {
+        let Some(cpus) = &self.cpu_affinity else { return Ok(()); };
+        // SAFETY: `set` is a valid `cpu_set_t` and only CPU indices within
+        // its size are set
+        unsafe {
+            let mut set: libc::cpu_set_t = std::mem::zeroed();
+            let capacity = 8 * std::mem::size_of::<libc::cpu_set_t>();
+            for &cpu in cpus.iter().filter(|&&cpu| cpu < capacity) {
+                libc::CPU_SET(cpu, &mut set);
+            }
+            match libc::sched_setaffinity(0, std::mem::size_of::<libc::cpu_set_t>(), &set) {
+                0 => Ok(()),
+                _ => Err(io::Error::last_os_error()),
+            }
+        }
+    }
+    #[cfg(not(target_os = "linux"))]
+    fn apply_affinity(&self) -> io::Result<()> {
+        match self.cpu_affinity {
+            None => Ok(()),
+            Some(_) => Err(unsupported("CPU affinity")),
+        }
+    }
+}
+
+#[cfg_attr(not(feature = "soapysdr"), allow(dead_code))]
+static NEXT_ID: AtomicU64 = AtomicU64::new(1);
+
+thread_local! {
+    static APPLIED_ID: Cell<u64> = const { Cell::new(0) };
+}
+
+/// [`ThreadConfig`] which is applied at most once to each thread
+#[derive(Clone, Debug)]
+#[cfg_attr(not(feature = "soapysdr"), allow(dead_code))]
+pub(crate) struct SharedThreadConfig {
+    id: u64,
+    config: Arc<ThreadConfig>,
+}
+
+#[cfg_attr(not(feature = "soapysdr"), allow(dead_code))]
+impl SharedThreadConfig {
+    pub(crate) fn new(config: ThreadConfig) -> Self {
+        Self {
+            id: NEXT_ID.fetch_add(1, Ordering::Relaxed),
+            config: Arc::new(config),
+        }
+    }
+    pub(crate) fn config(&self) -> &ThreadConfig {
+        &self.config
+    }
+    /// Apply configuration to the current thread unless already done
+    pub(crate) fn apply_to_current_thread(&self) {
+        if APPLIED_ID.with(|id| id.replace(self.id)) != self.id {
+            if let Err(err) = self.config.apply() {
+                event!(WARN, error = %err, "could not apply thread configuration");
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn test_thread_config() {
+        std::thread::spawn(|| {
+            assert!(ThreadConfig::default().apply().is_ok());
+            let lower_priority = ThreadConfig {
+                priority: ThreadPriority::Nice(19),
+                cpu_affinity: None,
+            };
+            #[cfg(target_os = "linux")]
+            assert!(lower_priority.apply().is_ok());
+            let shared = SharedThreadConfig::new(lower_priority);
+            shared.apply_to_current_thread();
+            assert_eq!(APPLIED_ID.with(|id| id.get()), shared.id);
+        })
+        .join()
+        .unwrap();
+    }
+}
@@ -63,8 +63,12 @@
 
 pub mod analysis;
 pub mod buffering;
+pub mod chains;
+pub mod channel;
 pub mod chunks;
+pub mod fec;
 pub mod filters;
+pub mod guard;
 pub mod io;
 pub mod modulation;
 pub mod morse;
@@ -74,12 +78,12 @@ pub mod transform;
 /// Re-export of basic blocks
 pub mod prelude {
     pub use super::analysis::Fourier;
-    pub use super::buffering::Buffer;
+    pub use super::buffering::{Buffer, Selector, Splitter};
     pub use super::chunks::{Overlapper, Rechunker};
     pub use super::filters::Filter;
     pub use super::io::{Blackhole, Silence};
     pub use super::resampling::{Downsampler, Upsampler};
-    pub use super::transform::{FreqShifter, GainControl, MapSample};
+    pub use super::transform::{FnBlock, FreqShifter, GainControl, MapSample};
 }
 
 pub use self::prelude::*;
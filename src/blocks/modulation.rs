@@ -1,12 +1,17 @@
-//! Modulators and demodulators (e.g. FM)
+//! Modulators and demodulators (e.g. FM or AM)
 
+use crate::blocks::analysis::GoertzelState;
+use crate::blocks::filters::design;
+use crate::blocks::morse::events::{EndOfMessages, StartOfMessages};
+use crate::blocks::morse::{self, EncodeError, Speed, Unit};
 use crate::bufferpool::*;
 use crate::flow::*;
 use crate::impl_block_trait;
 use crate::numbers::*;
 use crate::signal::*;
+use crate::windowing::Hamming;
 
-use tokio::sync::watch;
+use tokio::sync::{broadcast, mpsc, watch};
 use tokio::task::spawn;
 
 /// FM modulator block
@@ -157,5 +162,4141 @@ where
     }
 }
 
+/// AM demodulator block
+///
+/// The envelope (magnitude) of each input sample is emitted as real part of
+/// the output samples. Optionally, the DC component caused by the carrier
+/// may be removed (see [`AmDemod::with_dc_blocker`]), in which case the
+/// filter state is reset when an [interrupting] event is received.
+///
+/// [interrupting]: Event::is_interrupt
+pub struct AmDemod<Flt> {
+    receiver_connector: ReceiverConnector<Signal<Complex<Flt>>>,
+    sender_connector: SenderConnector<Signal<Complex<Flt>>>,
+}
+
+impl_block_trait! { <Flt> Consumer<Signal<Complex<Flt>>> for AmDemod<Flt> }
+impl_block_trait! { <Flt> Producer<Signal<Complex<Flt>>> for AmDemod<Flt> }
+
+impl<Flt> AmDemod<Flt>
+where
+    Flt: Float,
+{
+    /// Create new AM demodulator which keeps the DC component
+    pub fn new() -> Self {
+        Self::new_internal(None)
+    }
+    /// Create new AM demodulator which removes the DC component
+    ///
+    /// DC is removed with a one-pole high-pass filter
+    /// `y[n] = x[n] - x[n-1] + alpha * y[n-1]`, where `alpha` must be smaller
+    /// than `1.0`. Values close to `1.0` result in a low cutoff frequency.
+    pub fn with_dc_blocker(alpha: f64) -> Self {
+        assert!(alpha < 1.0, "alpha must be smaller than 1.0");
+        Self::new_internal(Some(alpha))
+    }
+    fn new_internal(dc_blocker: Option<f64>) -> Self {
+        let (mut receiver, receiver_connector) = new_receiver::<Signal<Complex<Flt>>>();
+        let (sender, sender_connector) = new_sender::<Signal<Complex<Flt>>>();
+        let mut buf_pool = ChunkBufPool::<Complex<Flt>>::new();
+        let alpha: Option<Flt> = dc_blocker.map(|alpha| flt!(alpha));
+        let mut previous_input = Flt::zero();
+        let mut previous_output = Flt::zero();
+        spawn(async move {
+            loop {
+                let Ok(signal) = receiver.recv().await else { return; };
+                match signal {
+                    Signal::Samples {
+                        sample_rate,
+                        chunk: input_chunk,
+                    } => {
+                        let mut output_chunk = buf_pool.get_with_capacity(input_chunk.len());
+                        for &sample in input_chunk.iter() {
+                            let magnitude = sample.norm();
+                            match alpha {
+                                Some(alpha) => {
+                                    previous_output =
+                                        magnitude - previous_input + alpha * previous_output;
+                                    previous_input = magnitude;
+                                    output_chunk.push(Complex::from(previous_output));
+                                }
+                                None => output_chunk.push(Complex::from(magnitude)),
+                            }
+                        }
+                        let Ok(()) = sender
+                            .send(Signal::Samples {
+                                sample_rate,
+                                chunk: output_chunk.finalize(),
+                            })
+                            .await
+                        else { return; };
+                    }
+                    Signal::Event(event) => {
+                        if event.is_interrupt() {
+                            previous_input = Flt::zero();
+                            previous_output = Flt::zero();
+                        }
+                        let Ok(()) = sender.send(Signal::Event(event)).await else { return; };
+                    }
+                }
+            }
+        });
+        Self {
+            receiver_connector,
+            sender_connector,
+        }
+    }
+}
+
+/// Sideband selected by an [`SsbDemod`] block
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Sideband {
+    /// Upper sideband
+    Usb,
+    /// Lower sideband
+    Lsb,
+}
+
+/// SSB demodulator block
+///
+/// The received samples are shifted by the negated BFO offset (such that the
+/// suppressed carrier is at zero frequency) and the selected sideband is
+/// extracted using the phasing method: the imaginary part is passed through a
+/// Hilbert transformer (see [`design::hilbert`]) and added to or subtracted
+/// from the (equally delayed) real part. The demodulated audio is emitted as
+/// real part of the output samples.
+///
+/// The oscillator phase and the filter history are kept across chunks. The
+/// history is cleared when an [interrupting] event is received.
+///
+/// [interrupting]: Event::is_interrupt
+pub struct SsbDemod<Flt> {
+    receiver_connector: ReceiverConnector<Signal<Complex<Flt>>>,
+    sender_connector: SenderConnector<Signal<Complex<Flt>>>,
+    mode: watch::Sender<Sideband>,
+    bfo_offset: watch::Sender<f64>,
+}
+
+impl_block_trait! { <Flt> Consumer<Signal<Complex<Flt>>> for SsbDemod<Flt> }
+impl_block_trait! { <Flt> Producer<Signal<Complex<Flt>>> for SsbDemod<Flt> }
+
+impl<Flt> SsbDemod<Flt>
+where
+    Flt: Float,
+{
+    /// Create new SSB demodulator for given sideband with a Hilbert
+    /// transformer of given (odd) number of taps and a BFO offset of zero
+    pub fn new(mode: Sideband, num_taps: usize) -> Self {
+        Self::with_bfo_offset(mode, num_taps, 0.0)
+    }
+    /// Create new SSB demodulator for given sideband with a Hilbert
+    /// transformer of given (odd) number of taps and given BFO offset in
+    /// hertz
+    pub fn with_bfo_offset(mode: Sideband, num_taps: usize, bfo_offset: f64) -> Self {
+        use std::f64::consts::TAU;
+        let coeffs: Vec<Flt> = design::hilbert(num_taps, &Hamming);
+        let (mut receiver, receiver_connector) = new_receiver::<Signal<Complex<Flt>>>();
+        let (sender, sender_connector) = new_sender::<Signal<Complex<Flt>>>();
+        let (mode_send, mut mode_recv) = watch::channel(mode);
+        let (bfo_offset_send, mut bfo_offset_recv) = watch::channel(bfo_offset);
+        spawn(async move {
+            let mut buf_pool = ChunkBufPool::<Complex<Flt>>::new();
+            let mut mode = mode;
+            let mut bfo_offset = bfo_offset;
+            let mut phase: f64 = 0.0;
+            let history_len = num_taps - 1;
+            let mut extended: Vec<Complex<Flt>> = vec![Complex::from(Flt::zero()); history_len];
+            loop {
+                let Ok(signal) = receiver.recv().await else { return; };
+                match signal {
+                    Signal::Samples {
+                        sample_rate,
+                        chunk: input_chunk,
+                    } => {
+                        if mode_recv.has_changed().unwrap_or(false) {
+                            mode = *mode_recv.borrow_and_update();
+                        }
+                        if bfo_offset_recv.has_changed().unwrap_or(false) {
+                            bfo_offset = *bfo_offset_recv.borrow_and_update();
+                        }
+                        let step = -TAU * bfo_offset / sample_rate;
+                        for &sample in input_chunk.iter() {
+                            let (im, re) = phase.sin_cos();
+                            extended.push(sample * Complex::new(flt!(re), flt!(im)));
+                            phase = (phase + step) % TAU;
+                        }
+                        let sign: Flt = match mode {
+                            Sideband::Usb => -Flt::one(),
+                            Sideband::Lsb => Flt::one(),
+                        };
+                        let mut output_chunk = buf_pool.get_with_capacity(input_chunk.len());
+                        for i in 0..input_chunk.len() {
+                            let window = &extended[i..=i + history_len];
+                            let mut hilbert: Flt = Flt::zero();
+                            for (&x, &h) in window.iter().rev().zip(coeffs.iter()) {
+                                hilbert += x.im * h;
+                            }
+                            let audio = (window[num_taps / 2].re + sign * hilbert) / flt!(2);
+                            output_chunk.push(Complex::from(audio));
+                        }
+                        extended.drain(0..input_chunk.len());
+                        let Ok(()) = sender
+                            .send(Signal::Samples {
+                                sample_rate,
+                                chunk: output_chunk.finalize(),
+                            })
+                            .await
+                        else { return; };
+                    }
+                    Signal::Event(event) => {
+                        if event.is_interrupt() {
+                            for x in extended.iter_mut() {
+                                *x = Complex::from(Flt::zero());
+                            }
+                        }
+                        let Ok(()) = sender.send(Signal::Event(event)).await
+                        else { return; };
+                    }
+                }
+            }
+        });
+        Self {
+            receiver_connector,
+            sender_connector,
+            mode: mode_send,
+            bfo_offset: bfo_offset_send,
+        }
+    }
+    /// Get selected sideband
+    pub fn mode(&self) -> Sideband {
+        *self.mode.borrow()
+    }
+    /// Set selected sideband
+    pub fn set_mode(&self, mode: Sideband) {
+        self.mode.send_replace(mode);
+    }
+    /// Get BFO offset in hertz
+    pub fn bfo_offset(&self) -> f64 {
+        *self.bfo_offset.borrow()
+    }
+    /// Set BFO offset in hertz (frequency of the suppressed carrier relative
+    /// to the center of the received signal)
+    pub fn set_bfo_offset(&self, bfo_offset: f64) {
+        self.bfo_offset.send_replace(bfo_offset);
+    }
+}
+
+/// Output of an [`FmStereoDecoder`] block, which acts as a
+/// [`Producer<Signal<Complex<Flt>>>`] for a single audio channel
+pub struct FmStereoOutput<Flt> {
+    sender_connector: SenderConnector<Signal<Complex<Flt>>>,
+}
+
+impl_block_trait! { <Flt> Producer<Signal<Complex<Flt>>> for FmStereoOutput<Flt> }
+
+/// Stereo decoder for FM broadcast
+///
+/// The block receives the demodulated multiplex signal (as real part of the
+/// samples, e.g. from an [`FmDemod`] block) and sends out the left and right
+/// audio channels through the [`left`] and [`right`] outputs (as real part of
+/// the samples). The sample rate is not changed and must be high enough to
+/// contain the 38 kHz subcarrier with its side bands (e.g. 192 kHz or more).
+///
+/// The 19 kHz pilot tone is recovered with a phase-locked loop and used to
+/// regenerate the subcarrier. The multiplex signal is expected to be
+/// *(L + R) / 2 + (L − R) / 2 · cos(2ωt) + p · cos(ωt)*, where *ω* is the
+/// angular frequency of the pilot. If no pilot is detected, both outputs
+/// carry the mono signal *(L + R) / 2*. De-emphasis is applied to both
+/// channels.
+///
+/// [`left`]: FmStereoDecoder::left
+/// [`right`]: FmStereoDecoder::right
+pub struct FmStereoDecoder<Flt> {
+    receiver_connector: ReceiverConnector<Signal<Complex<Flt>>>,
+    left: FmStereoOutput<Flt>,
+    right: FmStereoOutput<Flt>,
+    pilot_lock: watch::Receiver<bool>,
+}
+
+impl_block_trait! { <Flt> Consumer<Signal<Complex<Flt>>> for FmStereoDecoder<Flt> }
+
+struct StereoState<Flt> {
+    sample_rate: f64,
+    coeffs: Vec<Flt>,
+    history: Vec<(Flt, Flt)>,
+    history_pos: usize,
+    pilot_phase: f64,
+    pilot_freq: f64,
+    pilot_iq: Complex<f64>,
+    power: f64,
+    locked: bool,
+    deemphasized: (Flt, Flt),
+}
+
+impl<Flt: Float> StereoState<Flt> {
+    const PILOT_FREQ: f64 = 19000.0;
+    const AUDIO_CUTOFF: f64 = 15000.0;
+    const LOOP_BANDWIDTH: f64 = 20.0;
+    const PILOT_FILTER_CUTOFF: f64 = 200.0;
+    const LOCK_THRESHOLD: f64 = 0.05;
+    const UNLOCK_THRESHOLD: f64 = 0.03;
+    fn new(sample_rate: f64) -> Self {
+        use std::f64::consts::TAU;
+        assert!(
+            sample_rate > 2.0 * (2.0 * Self::PILOT_FREQ + Self::AUDIO_CUTOFF),
+            "sample rate too low for FM stereo decoding"
+        );
+        let num_taps = 2 * (sample_rate / 4000.0).round() as usize + 1;
+        let coeffs = design::lowpass(Self::AUDIO_CUTOFF, sample_rate, num_taps, &Hamming);
+        Self {
+            sample_rate,
+            coeffs,
+            history: vec![(Flt::zero(), Flt::zero()); 2 * num_taps],
+            history_pos: 0,
+            pilot_phase: 0.0,
+            pilot_freq: TAU * Self::PILOT_FREQ / sample_rate,
+            pilot_iq: Complex::from(0.0),
+            power: 0.0,
+            locked: false,
+            deemphasized: (Flt::zero(), Flt::zero()),
+        }
+    }
+    fn process(&mut self, sample: Flt, deemphasis: Flt) -> (Flt, Flt) {
+        use std::f64::consts::TAU;
+        let x = sample.to_f64().unwrap();
+        // phase-locked loop for the pilot
+        let pilot_alpha = 1.0 - (-TAU * Self::PILOT_FILTER_CUTOFF / self.sample_rate).exp();
+        self.pilot_iq += (Complex::from_polar(x, -self.pilot_phase) - self.pilot_iq) * pilot_alpha;
+        self.power += (x * x - self.power) * pilot_alpha;
+        let omega_n = TAU * Self::LOOP_BANDWIDTH / self.sample_rate;
+        let error = self.pilot_iq.arg();
+        let nominal_freq = TAU * Self::PILOT_FREQ / self.sample_rate;
+        self.pilot_freq = (self.pilot_freq + omega_n * omega_n * error)
+            .clamp(nominal_freq * 0.99, nominal_freq * 1.01);
+        let subcarrier: Flt = flt!(2.0 * (2.0 * self.pilot_phase).cos());
+        self.pilot_phase = (self.pilot_phase
+            + self.pilot_freq
+            + 2.0 * std::f64::consts::FRAC_1_SQRT_2 * omega_n * error)
+            % TAU;
+        // lock detection with hysteresis
+        let pilot_level = 2.0 * self.pilot_iq.re / self.power.sqrt().max(1e-30);
+        if self.locked {
+            self.locked = pilot_level > Self::UNLOCK_THRESHOLD;
+        } else {
+            self.locked = pilot_level > Self::LOCK_THRESHOLD;
+        }
+        // low-pass filtering of sum and difference signal
+        let n = self.coeffs.len();
+        let entry = (sample, sample * subcarrier);
+        self.history[self.history_pos] = entry;
+        self.history[self.history_pos + n] = entry;
+        self.history_pos += 1;
+        if self.history_pos == n {
+            self.history_pos = 0;
+        }
+        let mut sum = Flt::zero();
+        let mut diff = Flt::zero();
+        for (&coeff, &(s, d)) in self
+            .coeffs
+            .iter()
+            .zip(self.history[self.history_pos..self.history_pos + n].iter())
+        {
+            sum += coeff * s;
+            diff += coeff * d;
+        }
+        let (left, right) = match self.locked {
+            true => (sum + diff, sum - diff),
+            false => (sum, sum),
+        };
+        self.deemphasized.0 += (left - self.deemphasized.0) * deemphasis;
+        self.deemphasized.1 += (right - self.deemphasized.1) * deemphasis;
+        self.deemphasized
+    }
+}
+
+impl<Flt> FmStereoDecoder<Flt>
+where
+    Flt: Float,
+{
+    /// Create new stereo decoder with given de-emphasis time constant `tau`
+    /// (e.g. `50e-6` or `75e-6`)
+    pub fn new(tau: f64) -> Self {
+        let (mut receiver, receiver_connector) = new_receiver::<Signal<Complex<Flt>>>();
+        let (left_sender, left_connector) = new_sender::<Signal<Complex<Flt>>>();
+        let (right_sender, right_connector) = new_sender::<Signal<Complex<Flt>>>();
+        let (pilot_lock_send, pilot_lock_recv) = watch::channel(false);
+        let mut left_pool = ChunkBufPool::<Complex<Flt>>::new();
+        let mut right_pool = ChunkBufPool::<Complex<Flt>>::new();
+        spawn(async move {
+            let mut state: Option<StereoState<Flt>> = None;
+            loop {
+                let Ok(signal) = receiver.recv().await else { return; };
+                match signal {
+                    Signal::Samples {
+                        sample_rate,
+                        chunk: input_chunk,
+                    } => {
+                        let state = match state.as_mut() {
+                            Some(state) if state.sample_rate == sample_rate => state,
+                            _ => state.insert(StereoState::new(sample_rate)),
+                        };
+                        let deemphasis: Flt = flt!(1.0 - (-1.0 / (tau * sample_rate)).exp());
+                        let mut left_chunk = left_pool.get_with_capacity(input_chunk.len());
+                        let mut right_chunk = right_pool.get_with_capacity(input_chunk.len());
+                        for &sample in input_chunk.iter() {
+                            let (left, right) = state.process(sample.re, deemphasis);
+                            left_chunk.push(Complex::from(left));
+                            right_chunk.push(Complex::from(right));
+                        }
+                        pilot_lock_send.send_if_modified(|locked| {
+                            let changed = *locked != state.locked;
+                            *locked = state.locked;
+                            changed
+                        });
+                        let Ok(()) = left_sender
+                            .send(Signal::Samples {
+                                sample_rate,
+                                chunk: left_chunk.finalize(),
+                            })
+                            .await
+                        else { return; };
+                        let Ok(()) = right_sender
+                            .send(Signal::Samples {
+                                sample_rate,
+                                chunk: right_chunk.finalize(),
+                            })
+                            .await
+                        else { return; };
+                    }
+                    Signal::Event(event) => {
+                        if event.is_interrupt() {
+                            state = None;
+                            pilot_lock_send.send_replace(false);
+                        }
+                        let Ok(()) = left_sender.send(Signal::Event(event.clone())).await
+                        else { return; };
+                        let Ok(()) = right_sender.send(Signal::Event(event)).await
+                        else { return; };
+                    }
+                }
+            }
+        });
+        Self {
+            receiver_connector,
+            left: FmStereoOutput {
+                sender_connector: left_connector,
+            },
+            right: FmStereoOutput {
+                sender_connector: right_connector,
+            },
+            pilot_lock: pilot_lock_recv,
+        }
+    }
+    /// Output for the left audio channel
+    pub fn left(&self) -> &FmStereoOutput<Flt> {
+        &self.left
+    }
+    /// Output for the right audio channel
+    pub fn right(&self) -> &FmStereoOutput<Flt> {
+        &self.right
+    }
+    /// [`watch::Receiver`] indicating whether the pilot tone is locked
+    pub fn pilot_lock(&self) -> watch::Receiver<bool> {
+        self.pilot_lock.clone()
+    }
+}
+
+/// Group of four blocks received by an [`RdsDecoder`]
+///
+/// Blocks which could not be received or which failed the CRC check are
+/// `None`.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct RdsGroup {
+    /// Information words of blocks A, B, C (or C'), and D
+    pub blocks: [Option<u16>; 4],
+}
+
+impl Message for RdsGroup {
+    fn disconnection() -> Option<Self> {
+        None
+    }
+}
+
+impl RdsGroup {
+    /// Program identification code
+    pub fn pi(&self) -> Option<u16> {
+        self.blocks[0]
+    }
+    /// Group type (`0` to `15`), if block B has been received
+    pub fn group_type(&self) -> Option<u8> {
+        self.blocks[1].map(|b| (b >> 12) as u8)
+    }
+    /// Whether the group is of version B, if block B has been received
+    pub fn is_version_b(&self) -> Option<bool> {
+        self.blocks[1].map(|b| b & 0x0800 != 0)
+    }
+}
+
+const RDS_BIT_RATE: f64 = 1187.5;
+const RDS_TIMING_CANDIDATES: usize = 8;
+const RDS_SUBCARRIER: f64 = 57000.0;
+const RDS_OFFSET_A: u16 = 0x0FC;
+const RDS_OFFSET_B: u16 = 0x198;
+const RDS_OFFSET_C: u16 = 0x168;
+const RDS_OFFSET_C_PRIME: u16 = 0x350;
+const RDS_OFFSET_D: u16 = 0x1B4;
+
+/// Calculate the 10 bit check word (without offset) for an information word
+fn rds_check(info: u16) -> u16 {
+    const POLY: u32 = 0x5B9;
+    let mut reg = (info as u32) << 10;
+    for i in (10..26).rev() {
+        if reg & (1 << i) != 0 {
+            reg ^= POLY << (i - 10);
+        }
+    }
+    (reg & 0x3FF) as u16
+}
+
+/// Position of block within group if a 26 bit block is valid
+fn rds_block_position(block: u32) -> Option<usize> {
+    let info = (block >> 10) as u16;
+    let offset = rds_check(info) ^ (block & 0x3FF) as u16;
+    match offset {
+        RDS_OFFSET_A => Some(0),
+        RDS_OFFSET_B => Some(1),
+        RDS_OFFSET_C | RDS_OFFSET_C_PRIME => Some(2),
+        RDS_OFFSET_D => Some(3),
+        _ => None,
+    }
+}
+
+struct RdsDemodState<Flt> {
+    sample_rate: f64,
+    coeffs: Vec<Flt>,
+    history: Vec<Complex<Flt>>,
+    history_pos: usize,
+    carrier_phase: f64,
+    bit_phase: f64,
+    accumulators: [Complex<Flt>; RDS_TIMING_CANDIDATES],
+    previous_symbols: [Complex<Flt>; RDS_TIMING_CANDIDATES],
+    energies: [Flt; RDS_TIMING_CANDIDATES],
+    selected: usize,
+}
+
+impl<Flt: Float> RdsDemodState<Flt> {
+    fn new(sample_rate: f64) -> Self {
+        assert!(
+            sample_rate > 2.0 * (RDS_SUBCARRIER + 2.0 * RDS_BIT_RATE),
+            "sample rate too low for RDS decoding"
+        );
+        let num_taps = 2 * (sample_rate / 8000.0).round() as usize + 1;
+        let coeffs = design::lowpass(2.0 * RDS_BIT_RATE, sample_rate, num_taps, &Hamming);
+        let zero = Complex::from(Flt::zero());
+        Self {
+            sample_rate,
+            coeffs,
+            history: vec![zero; 2 * num_taps],
+            history_pos: 0,
+            carrier_phase: 0.0,
+            bit_phase: 0.0,
+            accumulators: [zero; RDS_TIMING_CANDIDATES],
+            previous_symbols: [zero; RDS_TIMING_CANDIDATES],
+            energies: [Flt::zero(); RDS_TIMING_CANDIDATES],
+            selected: 0,
+        }
+    }
+    /// Process one sample of the multiplex signal and return a differentially
+    /// decoded bit if available
+    fn process(&mut self, sample: Flt) -> Option<bool> {
+        use std::f64::consts::TAU;
+        // mix subcarrier down to baseband and apply low-pass filter
+        let (im, re) = (-self.carrier_phase).sin_cos();
+        self.carrier_phase = (self.carrier_phase + TAU * RDS_SUBCARRIER / self.sample_rate) % TAU;
+        let mixed = Complex::<Flt>::new(flt!(re), flt!(im)) * sample;
+        let n = self.coeffs.len();
+        self.history[self.history_pos] = mixed;
+        self.history[self.history_pos + n] = mixed;
+        self.history_pos += 1;
+        if self.history_pos == n {
+            self.history_pos = 0;
+        }
+        let mut baseband = Complex::from(Flt::zero());
+        for (&coeff, &value) in self
+            .coeffs
+            .iter()
+            .zip(self.history[self.history_pos..self.history_pos + n].iter())
+        {
+            baseband += value * coeff;
+        }
+        // biphase integrate and dump for several timing candidates
+        let previous_phase = self.bit_phase;
+        self.bit_phase += RDS_BIT_RATE / self.sample_rate;
+        self.bit_phase -= self.bit_phase.floor();
+        let mut result = None;
+        for candidate in 0..RDS_TIMING_CANDIDATES {
+            let offset = candidate as f64 / RDS_TIMING_CANDIDATES as f64;
+            let local_phase = (self.bit_phase - offset).rem_euclid(1.0);
+            let previous_local_phase = (previous_phase - offset).rem_euclid(1.0);
+            if local_phase < previous_local_phase {
+                let symbol = self.accumulators[candidate];
+                self.accumulators[candidate] = Complex::from(Flt::zero());
+                let energy = &mut self.energies[candidate];
+                *energy += (symbol.norm_sqr() - *energy) * flt!(0.05);
+                let product = symbol * self.previous_symbols[candidate].conj();
+                self.previous_symbols[candidate] = symbol;
+                if candidate == self.selected {
+                    result = Some(product.re < Flt::zero());
+                    let best = (0..RDS_TIMING_CANDIDATES)
+                        .max_by(|&a, &b| {
+                            self.energies[a]
+                                .partial_cmp(&self.energies[b])
+                                .unwrap_or(std::cmp::Ordering::Equal)
+                        })
+                        .unwrap();
+                    if self.energies[best] > self.energies[self.selected] * flt!(1.2) {
+                        self.selected = best;
+                    }
+                }
+            }
+            if local_phase < 0.5 {
+                self.accumulators[candidate] += baseband;
+            } else {
+                self.accumulators[candidate] -= baseband;
+            }
+        }
+        result
+    }
+}
+
+#[derive(Default)]
+struct RdsSync {
+    register: u32,
+    synced: bool,
+    bit_count: usize,
+    position: usize,
+    bad_blocks: usize,
+    blocks: [Option<u16>; 4],
+}
+
+impl RdsSync {
+    const MAX_BAD_BLOCKS: usize = 8;
+    /// Process one bit and return group if complete
+    fn process(&mut self, bit: bool) -> Option<RdsGroup> {
+        self.register = ((self.register << 1) | bit as u32) & 0x3FFFFFF;
+        if !self.synced {
+            if let Some(position) = rds_block_position(self.register) {
+                self.synced = true;
+                self.bad_blocks = 0;
+                self.bit_count = 0;
+                self.blocks = [None; 4];
+                self.blocks[position] = Some((self.register >> 10) as u16);
+                self.position = position;
+                return self.next_block();
+            }
+            return None;
+        }
+        self.bit_count += 1;
+        if self.bit_count < 26 {
+            return None;
+        }
+        self.bit_count = 0;
+        match rds_block_position(self.register) {
+            Some(position) if position == self.position => {
+                self.bad_blocks = 0;
+                self.blocks[position] = Some((self.register >> 10) as u16);
+            }
+            _ => {
+                self.bad_blocks += 1;
+                if self.bad_blocks >= Self::MAX_BAD_BLOCKS {
+                    self.synced = false;
+                }
+            }
+        }
+        self.next_block()
+    }
+    fn next_block(&mut self) -> Option<RdsGroup> {
+        let mut result = None;
+        if self.position == 3 {
+            if self.blocks.iter().any(Option::is_some) {
+                result = Some(RdsGroup {
+                    blocks: self.blocks,
+                });
+            }
+            self.blocks = [None; 4];
+        }
+        self.position = (self.position + 1) % 4;
+        result
+    }
+}
+
+struct RdsText {
+    program_service: [u8; 8],
+    radiotext: [u8; 64],
+    radiotext_ab: Option<bool>,
+}
+
+impl RdsText {
+    fn new() -> Self {
+        Self {
+            program_service: [b' '; 8],
+            radiotext: [0; 64],
+            radiotext_ab: None,
+        }
+    }
+    fn to_string(chars: &[u8]) -> String {
+        chars
+            .iter()
+            .take_while(|&&c| c != b'\r')
+            .map(|&c| match c {
+                0x20..=0x7E => c as char,
+                0 => ' ',
+                _ => '?',
+            })
+            .collect()
+    }
+    /// Process group and return which strings changed
+    fn process(&mut self, group: &RdsGroup) -> (bool, bool) {
+        let (Some(b), Some(version_b)) = (group.blocks[1], group.is_version_b())
+        else { return (false, false); };
+        match group.group_type() {
+            Some(0) => {
+                let Some(d) = group.blocks[3] else { return (false, false); };
+                let pos = (b & 0x3) as usize * 2;
+                let chars = d.to_be_bytes();
+                let changed = self.program_service[pos..pos + 2] != chars;
+                self.program_service[pos..pos + 2].copy_from_slice(&chars);
+                (changed, false)
+            }
+            Some(2) => {
+                let ab = b & 0x10 != 0;
+                let mut changed = false;
+                if self.radiotext_ab != Some(ab) {
+                    self.radiotext_ab = Some(ab);
+                    self.radiotext = [0; 64];
+                    changed = true;
+                }
+                let address = (b & 0xF) as usize;
+                let mut store = |pos: usize, word: Option<u16>| {
+                    if let Some(word) = word {
+                        let chars = word.to_be_bytes();
+                        if self.radiotext[pos..pos + 2] != chars {
+                            self.radiotext[pos..pos + 2].copy_from_slice(&chars);
+                            changed = true;
+                        }
+                    }
+                };
+                if version_b {
+                    store(address * 2, group.blocks[3]);
+                } else {
+                    store(address * 4, group.blocks[2]);
+                    store(address * 4 + 2, group.blocks[3]);
+                }
+                (false, changed)
+            }
+            _ => (false, false),
+        }
+    }
+}
+
+/// RDS decoder for FM broadcast
+///
+/// The block receives the demodulated multiplex signal (as real part of the
+/// samples, e.g. from an [`FmDemod`] block) and acts as a
+/// [`Producer<RdsGroup>`], which sends out the decoded [groups]. Blocks with
+/// invalid CRC are dropped (i.e. set to `None`). If the connected
+/// [`Consumer`]s are not ready to receive a group, the group is skipped
+/// instead of stalling the decoder.
+///
+/// The sample rate must be high enough to contain the 57 kHz subcarrier with
+/// its side bands (e.g. 192 kHz or more).
+///
+/// The program service name (PS) and radiotext (RT) are additionally made
+/// available through [`watch::Receiver`]s.
+///
+/// [groups]: RdsGroup
+pub struct RdsDecoder<Flt> {
+    receiver_connector: ReceiverConnector<Signal<Complex<Flt>>>,
+    sender_connector: SenderConnector<RdsGroup>,
+    program_service: watch::Receiver<String>,
+    radiotext: watch::Receiver<String>,
+}
+
+impl_block_trait! { <Flt> Consumer<Signal<Complex<Flt>>> for RdsDecoder<Flt> }
+impl_block_trait! { <Flt> Producer<RdsGroup> for RdsDecoder<Flt> }
+
+impl<Flt> RdsDecoder<Flt>
+where
+    Flt: Float,
+{
+    /// Create new RDS decoder
+    pub fn new() -> Self {
+        let (mut receiver, receiver_connector) = new_receiver::<Signal<Complex<Flt>>>();
+        let (sender, sender_connector) = new_sender::<RdsGroup>();
+        let (program_service_send, program_service_recv) = watch::channel(String::new());
+        let (radiotext_send, radiotext_recv) = watch::channel(String::new());
+        spawn(async move {
+            let mut state: Option<RdsDemodState<Flt>> = None;
+            let mut sync = RdsSync::default();
+            let mut text = RdsText::new();
+            loop {
+                let Ok(signal) = receiver.recv().await else { return; };
+                match signal {
+                    Signal::Samples {
+                        sample_rate,
+                        chunk: input_chunk,
+                    } => {
+                        let state = match state.as_mut() {
+                            Some(state) if state.sample_rate == sample_rate => state,
+                            _ => state.insert(RdsDemodState::new(sample_rate)),
+                        };
+                        for &sample in input_chunk.iter() {
+                            let Some(bit) = state.process(sample.re) else { continue; };
+                            let Some(group) = sync.process(bit) else { continue; };
+                            let (ps_changed, rt_changed) = text.process(&group);
+                            if ps_changed {
+                                program_service_send
+                                    .send_replace(RdsText::to_string(&text.program_service));
+                            }
+                            if rt_changed {
+                                radiotext_send.send_replace(RdsText::to_string(&text.radiotext));
+                            }
+                            match sender.try_reserve() {
+                                Ok(Some(reservation)) => reservation.send(group),
+                                Ok(None) => (),
+                                Err(_) => (),
+                            }
+                        }
+                    }
+                    Signal::Event(event) => {
+                        if event.is_interrupt() {
+                            state = None;
+                            sync = RdsSync::default();
+                        }
+                    }
+                }
+            }
+        });
+        Self {
+            receiver_connector,
+            sender_connector,
+            program_service: program_service_recv,
+            radiotext: radiotext_recv,
+        }
+    }
+    /// [`watch::Receiver`] for the program service name (PS)
+    pub fn program_service(&self) -> watch::Receiver<String> {
+        self.program_service.clone()
+    }
+    /// [`watch::Receiver`] for the radiotext (RT)
+    pub fn radiotext(&self) -> watch::Receiver<String> {
+        self.radiotext.clone()
+    }
+}
+
+/// Characters of the Baudot (ITA2) letters shift
+///
+/// `'\0'` marks codes without character (including the shift codes).
+const BAUDOT_LETTERS: [char; 32] = [
+    '\0', 'E', '\n', 'A', ' ', 'S', 'I', 'U', '\r', 'D', 'R', 'J', 'N', 'F', 'C', 'K', 'T', 'Z',
+    'L', 'W', 'H', 'Y', 'P', 'Q', 'O', 'B', 'G', '\0', 'M', 'X', 'V', '\0',
+];
+
+/// Characters of the Baudot figures shift (US TTY variant)
+const BAUDOT_FIGURES: [char; 32] = [
+    '\0', '3', '\n', '-', ' ', '\x07', '8', '7', '\r', '$', '4', '\'', ',', '!', ':', '(', '5',
+    '"', ')', '2', '#', '6', '0', '1', '9', '?', '&', '\0', '.', '/', ';', '\0',
+];
+
+const BAUDOT_FIGS: u8 = 0x1B;
+const BAUDOT_LTRS: u8 = 0x1F;
+
+/// Position within an RTTY character frame
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum RttyFrame {
+    /// Waiting for stop polarity (mark) before the next start bit
+    WaitMark,
+    /// Waiting for start bit
+    Idle,
+    /// Receiving character, with number of samples since the start bit
+    Receiving(f64),
+}
+
+/// Framing of asynchronously sent Baudot characters
+struct RttyFraming {
+    bit_len: f64,
+    frame: RttyFrame,
+    code: u8,
+}
+
+impl RttyFraming {
+    fn new(bit_len: f64) -> Self {
+        Self {
+            bit_len,
+            frame: RttyFrame::WaitMark,
+            code: 0,
+        }
+    }
+    /// Process one demodulated sample and return Baudot code if a character
+    /// is complete
+    fn process(&mut self, mark: bool) -> Option<u8> {
+        let position = match self.frame {
+            RttyFrame::WaitMark => {
+                if mark {
+                    self.frame = RttyFrame::Idle;
+                }
+                return None;
+            }
+            RttyFrame::Idle => {
+                if !mark {
+                    self.frame = RttyFrame::Receiving(0.0);
+                }
+                return None;
+            }
+            RttyFrame::Receiving(position) => position + 1.0,
+        };
+        self.frame = RttyFrame::Receiving(position);
+        // the detector integrates over one bit, thus bits are complete half
+        // a bit after each (delayed) transition
+        let bit = (position / self.bit_len - 0.5).floor();
+        if bit == ((position - 1.0) / self.bit_len - 0.5).floor() {
+            return None;
+        }
+        match bit as i32 {
+            0 => {
+                if mark {
+                    // false start bit
+                    self.frame = RttyFrame::Idle;
+                }
+            }
+            1..=5 => self.code = self.code >> 1 | (mark as u8) << 4,
+            _ => {
+                if mark {
+                    self.frame = RttyFrame::Idle;
+                    return Some(self.code);
+                }
+                // framing error
+                self.frame = RttyFrame::WaitMark;
+            }
+        }
+        None
+    }
+}
+
+/// RTTY decoder block
+///
+/// The block receives an audio signal (as real part of the samples) or a
+/// complex baseband signal, in which the mark and space tones are located at
+/// `center_frequency ∓ shift / 2`. The tones are detected by mixing them to
+/// zero frequency and integrating over one bit. Characters consist of a start
+/// bit, five data bits in Baudot code, and a stop bit. Letters (LTRS) and
+/// figures (FIGS) shift codes are obeyed, and a space shifts back to letters
+/// (unshift on space).
+///
+/// Because transmitters and receivers differ in which tone is considered
+/// mark, the polarity can be inverted with [`RttyDecoder::set_inverted`].
+///
+/// Decoded text is sent through a [`broadcast`] channel, see
+/// [`RttyDecoder::subscribe`].
+pub struct RttyDecoder<Flt> {
+    receiver_connector: ReceiverConnector<Signal<Complex<Flt>>>,
+    center_frequency: watch::Sender<f64>,
+    shift: watch::Sender<f64>,
+    baud_rate: watch::Sender<f64>,
+    inverted: watch::Sender<bool>,
+    text: broadcast::Sender<String>,
+}
+
+impl_block_trait! { <Flt> Consumer<Signal<Complex<Flt>>> for RttyDecoder<Flt> }
+
+impl<Flt> RttyDecoder<Flt>
+where
+    Flt: Float,
+{
+    /// Create new RTTY decoder for given center frequency (in hertz) with a
+    /// shift of 170 Hz and a baud rate of 45.45
+    pub fn new(center_frequency: f64) -> Self {
+        Self::with_params(center_frequency, 170.0, 45.45)
+    }
+    /// Create new RTTY decoder for given center frequency and shift (both in
+    /// hertz) and given baud rate
+    pub fn with_params(center_frequency: f64, shift: f64, baud_rate: f64) -> Self {
+        use std::f64::consts::TAU;
+        let (mut receiver, receiver_connector) = new_receiver::<Signal<Complex<Flt>>>();
+        let (center_frequency_send, mut center_frequency_recv) = watch::channel(center_frequency);
+        let (shift_send, mut shift_recv) = watch::channel(shift);
+        let (baud_rate_send, mut baud_rate_recv) = watch::channel(baud_rate);
+        let (inverted_send, inverted_recv) = watch::channel(false);
+        let (text_send, _) = broadcast::channel::<String>(256);
+        let text_send_clone = text_send.clone();
+        spawn(async move {
+            let mut previous_sample_rate: Option<f64> = None;
+            let mut center_frequency = center_frequency;
+            let mut shift = shift;
+            let mut baud_rate = baud_rate;
+            let mut bit_len: f64 = 0.0;
+            let mut phases: [f64; 2] = [0.0; 2];
+            let mut steps: [f64; 2] = [0.0; 2];
+            let mut history: Vec<[Complex<Flt>; 2]> = Vec::new();
+            let mut history_pos: usize = 0;
+            let mut sums: [Complex<Flt>; 2] = [Complex::from(Flt::zero()); 2];
+            let mut framing = RttyFraming::new(1.0);
+            let mut figures = false;
+            loop {
+                let Ok(signal) = receiver.recv().await else { return; };
+                match signal {
+                    Signal::Samples {
+                        sample_rate,
+                        chunk: input_chunk,
+                    } => {
+                        let mut recalculate = false;
+                        if center_frequency_recv.has_changed().unwrap_or(false) {
+                            center_frequency = *center_frequency_recv.borrow_and_update();
+                            recalculate = true;
+                        }
+                        if shift_recv.has_changed().unwrap_or(false) {
+                            shift = *shift_recv.borrow_and_update();
+                            recalculate = true;
+                        }
+                        if baud_rate_recv.has_changed().unwrap_or(false) {
+                            baud_rate = *baud_rate_recv.borrow_and_update();
+                            recalculate = true;
+                        }
+                        if previous_sample_rate != Some(sample_rate) {
+                            previous_sample_rate = Some(sample_rate);
+                            recalculate = true;
+                        }
+                        if recalculate {
+                            bit_len = sample_rate / baud_rate;
+                            steps = [
+                                TAU * (center_frequency - shift / 2.0) / sample_rate,
+                                TAU * (center_frequency + shift / 2.0) / sample_rate,
+                            ];
+                            let zero = Complex::from(Flt::zero());
+                            history = vec![[zero; 2]; (bit_len.round() as usize).max(1)];
+                            history_pos = 0;
+                            sums = [zero; 2];
+                            framing = RttyFraming::new(bit_len);
+                        }
+                        let inverted = *inverted_recv.borrow();
+                        let mut output = String::new();
+                        for &sample in input_chunk.iter() {
+                            // mix tones to zero frequency and integrate over
+                            // one bit
+                            for tone in 0..2 {
+                                let (im, re) = (-phases[tone]).sin_cos();
+                                phases[tone] = (phases[tone] + steps[tone]) % TAU;
+                                let mixed = sample * Complex::new(flt!(re), flt!(im));
+                                sums[tone] += mixed - history[history_pos][tone];
+                                history[history_pos][tone] = mixed;
+                            }
+                            history_pos = (history_pos + 1) % history.len();
+                            let mark = (sums[0].norm_sqr() > sums[1].norm_sqr()) != inverted;
+                            let Some(code) = framing.process(mark) else { continue; };
+                            match code {
+                                BAUDOT_FIGS => figures = true,
+                                BAUDOT_LTRS => figures = false,
+                                _ => {
+                                    let c = match figures {
+                                        false => BAUDOT_LETTERS[code as usize],
+                                        true => BAUDOT_FIGURES[code as usize],
+                                    };
+                                    if c == ' ' {
+                                        figures = false;
+                                    }
+                                    if c != '\0' {
+                                        output.push(c);
+                                    }
+                                }
+                            }
+                        }
+                        if !output.is_empty() {
+                            let _ = text_send_clone.send(output);
+                        }
+                    }
+                    Signal::Event(event) => {
+                        if event.is_interrupt() {
+                            framing = RttyFraming::new(bit_len);
+                        }
+                    }
+                }
+            }
+        });
+        Self {
+            receiver_connector,
+            center_frequency: center_frequency_send,
+            shift: shift_send,
+            baud_rate: baud_rate_send,
+            inverted: inverted_send,
+            text: text_send,
+        }
+    }
+    /// Get center frequency in hertz
+    pub fn center_frequency(&self) -> f64 {
+        *self.center_frequency.borrow()
+    }
+    /// Set center frequency in hertz
+    pub fn set_center_frequency(&self, center_frequency: f64) {
+        self.center_frequency.send_replace(center_frequency);
+    }
+    /// Get shift (distance between mark and space) in hertz
+    pub fn shift(&self) -> f64 {
+        *self.shift.borrow()
+    }
+    /// Set shift (distance between mark and space) in hertz
+    pub fn set_shift(&self, shift: f64) {
+        self.shift.send_replace(shift);
+    }
+    /// Get baud rate in bits per second
+    pub fn baud_rate(&self) -> f64 {
+        *self.baud_rate.borrow()
+    }
+    /// Set baud rate in bits per second
+    pub fn set_baud_rate(&self, baud_rate: f64) {
+        self.baud_rate.send_replace(baud_rate);
+    }
+    /// Get whether polarity is inverted
+    pub fn inverted(&self) -> bool {
+        *self.inverted.borrow()
+    }
+    /// Invert polarity
+    ///
+    /// By default, the lower tone is mark. If inverted, the higher tone is
+    /// mark.
+    pub fn set_inverted(&self, inverted: bool) {
+        self.inverted.send_replace(inverted);
+    }
+    /// Subscribe to decoded text
+    pub fn subscribe(&self) -> broadcast::Receiver<String> {
+        self.text.subscribe()
+    }
+}
+
+/// Decision emitted by [`Fsk2Slicer`] for each symbol
+///
+/// Implemented for `bool` (hard decisions) and `f32` (soft decisions).
+pub trait SymbolDecision: Clone + Send + Sync + 'static {
+    /// Decide symbol with sampled `value`, given the estimated mean
+    /// `amplitude` of the symbols and the estimated `noise_variance`
+    fn decide(value: f64, amplitude: f64, noise_variance: f64) -> Self;
+}
+
+impl SymbolDecision for bool {
+    fn decide(value: f64, _amplitude: f64, _noise_variance: f64) -> Self {
+        value > 0.0
+    }
+}
+
+/// Log-likelihood ratio `ln(P(true)/P(false))` assuming additive white
+/// Gaussian noise, i.e. positive for `true` with a magnitude that indicates
+/// confidence
+impl SymbolDecision for f32 {
+    fn decide(value: f64, amplitude: f64, noise_variance: f64) -> Self {
+        match noise_variance > 0.0 {
+            true => (2.0 * amplitude * value / noise_variance) as f32,
+            false => 0.0,
+        }
+    }
+}
+
+/// Slicer with symbol timing recovery for binary (2-level) baseband signals
+///
+/// The block receives a demodulated baseband signal (as real part of the
+/// samples, e.g. from an [`FmDemod`] block) and acts as a
+/// [`Producer<Signal<Out>>`], which emits one [decision] per symbol. The
+/// sample rate of the output is the baud rate.
+///
+/// By default ([`Fsk2Slicer::new`]), hard decisions are emitted, where
+/// positive values are sliced to `true`. Soft decisions for forward error
+/// correction decoders are emitted when created with [`Fsk2Slicer::new_soft`]:
+/// each symbol is output as log-likelihood ratio (positive for `true`), which
+/// is scaled with the amplitude and noise variance estimated from the recent
+/// symbols (with the same smoothing as the [quality]).
+///
+/// The DC component of the input is removed with a time constant of 32
+/// symbols. Symbol timing is recovered with a Gardner timing error detector
+/// and a second order loop, which also follows small deviations of the baud
+/// rate. The input signal should be band-limited (e.g. by the filter of the
+/// demodulator) and must have at least four samples per symbol.
+///
+/// The state of the timing recovery is reset when an [interrupting] event is
+/// received.
+///
+/// [interrupting]: Event::is_interrupt
+/// [decision]: SymbolDecision
+/// [quality]: Fsk2Slicer::quality
+pub struct Fsk2Slicer<Flt, Out = bool> {
+    receiver_connector: ReceiverConnector<Signal<Complex<Flt>>>,
+    sender_connector: SenderConnector<Signal<Out>>,
+    baud_rate: watch::Sender<f64>,
+    clock_phase: watch::Receiver<f64>,
+    quality: watch::Receiver<f64>,
+}
+
+impl<Flt, Out> Consumer<Signal<Complex<Flt>>> for Fsk2Slicer<Flt, Out> {
+    fn receiver_connector(&self) -> &ReceiverConnector<Signal<Complex<Flt>>> {
+        &self.receiver_connector
+    }
+}
+
+impl<Flt, Out> Producer<Signal<Out>> for Fsk2Slicer<Flt, Out> {
+    fn sender_connector(&self) -> &SenderConnector<Signal<Out>> {
+        &self.sender_connector
+    }
+}
+
+impl<Flt> Fsk2Slicer<Flt>
+where
+    Flt: Float,
+{
+    /// Create new `Fsk2Slicer` block with hard decisions for given baud rate
+    /// (in symbols per second)
+    pub fn new(baud_rate: f64) -> Self {
+        Self::with_decisions(baud_rate)
+    }
+}
+
+impl<Flt> Fsk2Slicer<Flt, f32>
+where
+    Flt: Float,
+{
+    /// Create new `Fsk2Slicer` block with soft decisions for given baud rate
+    /// (in symbols per second)
+    pub fn new_soft(baud_rate: f64) -> Self {
+        Self::with_decisions(baud_rate)
+    }
+}
+
+impl<Flt, Out> Fsk2Slicer<Flt, Out>
+where
+    Flt: Float,
+    Out: SymbolDecision,
+{
+    /// Proportional gain of the timing loop
+    const PHASE_GAIN: f64 = 0.05;
+    /// Integral gain of the timing loop
+    const FREQUENCY_GAIN: f64 = 0.0005;
+    /// Smoothing factor (per symbol) of the power and quality estimates
+    const SMOOTHING: f64 = 0.05;
+    /// Create new `Fsk2Slicer` block emitting decisions of type `Out` for
+    /// given baud rate (in symbols per second)
+    pub fn with_decisions(baud_rate: f64) -> Self {
+        let (mut receiver, receiver_connector) = new_receiver::<Signal<Complex<Flt>>>();
+        let (sender, sender_connector) = new_sender::<Signal<Out>>();
+        let (baud_rate_send, mut baud_rate_recv) = watch::channel(baud_rate);
+        let (clock_phase_send, clock_phase) = watch::channel(0.0);
+        let (quality_send, quality) = watch::channel(0.0);
+        spawn(async move {
+            let mut buf_pool = ChunkBufPool::<Out>::new();
+            let mut baud_rate = baud_rate;
+            let mut dc: f64 = 0.0;
+            let mut previous: f64 = 0.0;
+            let mut phase: f64 = 0.0;
+            let mut frequency: f64 = 0.0;
+            let mut middle: f64 = 0.0;
+            let mut last_symbol: f64 = 0.0;
+            let mut power: f64 = 0.0;
+            let mut magnitude: f64 = 0.0;
+            loop {
+                let Ok(signal) = receiver.recv().await else { return; };
+                match signal {
+                    Signal::Samples {
+                        sample_rate,
+                        chunk: input_chunk,
+                    } => {
+                        if baud_rate_recv.has_changed().unwrap_or(false) {
+                            baud_rate = *baud_rate_recv.borrow_and_update();
+                            frequency = 0.0;
+                        }
+                        assert!(
+                            sample_rate >= 4.0 * baud_rate,
+                            "sample rate too low for baud rate"
+                        );
+                        let step = baud_rate / sample_rate;
+                        let dc_coef = 1.0 - (-step / 32.0).exp();
+                        let mut output_chunk = buf_pool.get();
+                        for &sample in input_chunk.iter() {
+                            let value = sample.re.to_f64().unwrap();
+                            dc += (value - dc) * dc_coef;
+                            let value = value - dc;
+                            let previous_phase = phase;
+                            phase += step * (1.0 + frequency);
+                            // interpolate linearly at the middle and at the
+                            // end of the symbol
+                            let interpolate = |target: f64| {
+                                let t = (target - previous_phase) / (phase - previous_phase);
+                                previous + (value - previous) * t
+                            };
+                            if previous_phase < 0.5 && phase >= 0.5 {
+                                middle = interpolate(0.5);
+                            }
+                            if phase >= 1.0 {
+                                let symbol = interpolate(1.0);
+                                phase -= 1.0;
+                                power += (symbol * symbol - power) * Self::SMOOTHING;
+                                magnitude += (symbol.abs() - magnitude) * Self::SMOOTHING;
+                                if power > 0.0 {
+                                    let error = middle * (last_symbol - symbol) / power;
+                                    let error = error.clamp(-1.0, 1.0);
+                                    phase -= error * Self::PHASE_GAIN;
+                                    frequency -= error * Self::FREQUENCY_GAIN;
+                                    frequency = frequency.clamp(-0.05, 0.05);
+                                }
+                                last_symbol = symbol;
+                                // limit confidence for noiseless signals
+                                let noise_variance =
+                                    (power - magnitude * magnitude).max(power * 1e-4);
+                                output_chunk.push(Out::decide(symbol, magnitude, noise_variance));
+                            }
+                            previous = value;
+                        }
+                        clock_phase_send.send_replace(phase);
+                        if power > 0.0 {
+                            quality_send.send_replace(magnitude * magnitude / power);
+                        }
+                        if !output_chunk.is_empty() {
+                            let Ok(()) = sender
+                                .send(Signal::Samples {
+                                    sample_rate: baud_rate,
+                                    chunk: output_chunk.finalize(),
+                                })
+                                .await
+                            else { return; };
+                        }
+                    }
+                    Signal::Event(event) => {
+                        if event.is_interrupt() {
+                            phase = 0.0;
+                            frequency = 0.0;
+                            power = 0.0;
+                            magnitude = 0.0;
+                        }
+                        let Ok(()) = sender.send(Signal::Event(event)).await
+                        else { return; };
+                    }
+                }
+            }
+        });
+        Self {
+            receiver_connector,
+            sender_connector,
+            baud_rate: baud_rate_send,
+            clock_phase,
+            quality,
+        }
+    }
+    /// Get baud rate in symbols per second
+    pub fn baud_rate(&self) -> f64 {
+        *self.baud_rate.borrow()
+    }
+    /// Set baud rate in symbols per second
+    pub fn set_baud_rate(&self, baud_rate: f64) {
+        self.baud_rate.send_replace(baud_rate);
+    }
+    /// Get [`watch::Receiver`] of the recovered clock phase
+    ///
+    /// The phase is given as fraction of a symbol (from `0.0` to `1.0`) at the
+    /// end of the most recently processed chunk, where `1.0` corresponds to
+    /// the sampling instant.
+    pub fn clock_phase(&self) -> watch::Receiver<f64> {
+        self.clock_phase.clone()
+    }
+    /// Get [`watch::Receiver`] of the sync quality
+    ///
+    /// The quality is the squared mean magnitude of the sampled symbols
+    /// divided by their mean power. It approaches `1.0` for a wide open eye
+    /// and is lower for noisy or badly synchronized signals.
+    pub fn quality(&self) -> watch::Receiver<f64> {
+        self.quality.clone()
+    }
+}
+
+/// Symbol synchronizer using a Mueller-Muller timing error detector
+///
+/// The (matched-filtered) input signal with an arbitrary, not necessarily
+/// integer, number of samples per symbol is interpolated (cubic Lagrange
+/// interpolation) at the recovered symbol timing, such that the output
+/// contains one complex sample per symbol. The sample rate of the output is
+/// the nominal symbol rate.
+///
+/// The timing error is determined with a decision-directed Mueller-Muller
+/// detector (deciding the sign of the real and imaginary part), which is
+/// suitable for BPSK and QPSK, and normalized to the signal amplitude. It
+/// drives a second order loop with a damping factor of `1/√2` and a
+/// configurable bandwidth in hertz, which tracks deviations of the symbol
+/// rate of up to 1%.
+///
+/// The state of the loop is reset when an [interrupting] event is received.
+///
+/// [interrupting]: Event::is_interrupt
+pub struct SymbolSync<Flt> {
+    receiver_connector: ReceiverConnector<Signal<Complex<Flt>>>,
+    sender_connector: SenderConnector<Signal<Complex<Flt>>>,
+    loop_bandwidth: watch::Sender<f64>,
+    timing_error: watch::Receiver<f64>,
+}
+
+impl_block_trait! { <Flt> Consumer<Signal<Complex<Flt>>> for SymbolSync<Flt> }
+impl_block_trait! { <Flt> Producer<Signal<Complex<Flt>>> for SymbolSync<Flt> }
+
+impl<Flt> SymbolSync<Flt>
+where
+    Flt: Float,
+{
+    /// Maximum relative deviation of the symbol rate
+    const MAX_DEVIATION: f64 = 0.01;
+    /// Create new `SymbolSync` with given (nominal) number of samples per
+    /// symbol and loop bandwidth in hertz
+    pub fn new(samples_per_symbol: f64, loop_bandwidth: f64) -> Self {
+        assert!(
+            samples_per_symbol > 1.0,
+            "samples per symbol must be greater than one"
+        );
+        assert!(loop_bandwidth > 0.0, "loop bandwidth must be positive");
+        let (mut receiver, receiver_connector) = new_receiver::<Signal<Complex<Flt>>>();
+        let (sender, sender_connector) = new_sender::<Signal<Complex<Flt>>>();
+        let (loop_bandwidth_send, mut loop_bandwidth_recv) = watch::channel(loop_bandwidth);
+        let (timing_error_send, timing_error) = watch::channel(0.0);
+        spawn(async move {
+            let slice = |x: f64| -> f64 {
+                if x > 0.0 {
+                    1.0
+                } else if x < 0.0 {
+                    -1.0
+                } else {
+                    0.0
+                }
+            };
+            let mut buf_pool = ChunkBufPool::<Complex<Flt>>::new();
+            let mut loop_bandwidth = loop_bandwidth;
+            let mut buffer: Vec<Complex<f64>> = Vec::new();
+            // position of next symbol in `buffer` (minus one)
+            let mut position: f64 = 0.0;
+            let mut period: f64 = samples_per_symbol;
+            let mut power: f64 = 0.0;
+            let mut error_power: f64 = 0.0;
+            let mut previous: Option<(Complex<f64>, Complex<f64>)> = None;
+            loop {
+                let Ok(signal) = receiver.recv().await else { return; };
+                match signal {
+                    Signal::Samples {
+                        sample_rate,
+                        chunk: input_chunk,
+                    } => {
+                        if loop_bandwidth_recv.has_changed().unwrap_or(false) {
+                            loop_bandwidth = *loop_bandwidth_recv.borrow_and_update();
+                        }
+                        let symbol_rate = sample_rate / samples_per_symbol;
+                        let zeta = std::f64::consts::FRAC_1_SQRT_2;
+                        let omega_n = 2.0 * loop_bandwidth / symbol_rate / (zeta + 0.25 / zeta);
+                        let alpha = 2.0 * zeta * omega_n * samples_per_symbol;
+                        let beta = omega_n * omega_n * samples_per_symbol;
+                        let coef = (1.0 - (-loop_bandwidth / symbol_rate).exp()).min(0.1);
+                        buffer.extend(
+                            input_chunk.iter().map(|x| {
+                                Complex::new(x.re.to_f64().unwrap(), x.im.to_f64().unwrap())
+                            }),
+                        );
+                        let mut output_chunk = buf_pool
+                            .get_with_capacity((input_chunk.len() as f64 / period).ceil() as usize);
+                        while position.floor() as usize + 4 <= buffer.len() {
+                            let index = position.floor() as usize;
+                            let mu = position - index as f64;
+                            let x = &buffer[index..index + 4];
+                            let symbol = x[0] * (-mu * (mu - 1.0) * (mu - 2.0) / 6.0)
+                                + x[1] * ((mu + 1.0) * (mu - 1.0) * (mu - 2.0) / 2.0)
+                                + x[2] * (-(mu + 1.0) * mu * (mu - 2.0) / 2.0)
+                                + x[3] * ((mu + 1.0) * mu * (mu - 1.0) / 6.0);
+                            output_chunk.push(Complex::new(flt!(symbol.re), flt!(symbol.im)));
+                            power += (symbol.norm_sqr() - power) * coef;
+                            let decision = Complex::new(slice(symbol.re), slice(symbol.im));
+                            let mut error: f64 = 0.0;
+                            if let Some((previous_symbol, previous_decision)) = previous {
+                                if power > 0.0 {
+                                    error = (previous_decision.re * symbol.re
+                                        - decision.re * previous_symbol.re
+                                        + previous_decision.im * symbol.im
+                                        - decision.im * previous_symbol.im)
+                                        / (2.0 * power).sqrt();
+                                }
+                            }
+                            previous = Some((symbol, decision));
+                            error_power += (error * error - error_power) * coef;
+                            period = (period + beta * error).clamp(
+                                samples_per_symbol * (1.0 - Self::MAX_DEVIATION),
+                                samples_per_symbol * (1.0 + Self::MAX_DEVIATION),
+                            );
+                            position += period + alpha * error;
+                        }
+                        let consumed = (position.floor() as usize).min(buffer.len());
+                        buffer.drain(0..consumed);
+                        position -= consumed as f64;
+                        timing_error_send.send_replace(error_power.sqrt());
+                        let Ok(()) = sender
+                            .send(Signal::Samples {
+                                sample_rate: symbol_rate,
+                                chunk: output_chunk.finalize(),
+                            })
+                            .await
+                        else { return; };
+                    }
+                    Signal::Event(event) => {
+                        if event.is_interrupt() {
+                            buffer.clear();
+                            position = 0.0;
+                            period = samples_per_symbol;
+                            power = 0.0;
+                            error_power = 0.0;
+                            previous = None;
+                        }
+                        let Ok(()) = sender.send(Signal::Event(event)).await
+                        else { return; };
+                    }
+                }
+            }
+        });
+        Self {
+            receiver_connector,
+            sender_connector,
+            loop_bandwidth: loop_bandwidth_send,
+            timing_error,
+        }
+    }
+    /// Get loop bandwidth in hertz
+    pub fn loop_bandwidth(&self) -> f64 {
+        *self.loop_bandwidth.borrow()
+    }
+    /// Set loop bandwidth in hertz
+    pub fn set_loop_bandwidth(&self, loop_bandwidth: f64) {
+        assert!(loop_bandwidth > 0.0, "loop bandwidth must be positive");
+        self.loop_bandwidth.send_replace(loop_bandwidth);
+    }
+    /// Get [`watch::Receiver`] of the timing error
+    ///
+    /// The value is the smoothed RMS output of the (normalized) timing error
+    /// detector, which is approximately proportional to the RMS timing error
+    /// in symbols. It is updated after each processed chunk.
+    pub fn timing_error(&self) -> watch::Receiver<f64> {
+        self.timing_error.clone()
+    }
+}
+
+/// Message received by a [`PocsagDecoder`]
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct PocsagMessage {
+    /// Bit rate of the transmission (`512`, `1200`, or `2400`)
+    pub bit_rate: u32,
+    /// Capcode (21 bit address including the frame number)
+    pub capcode: u32,
+    /// Function bits (`0` to `3`), which commonly select the message type
+    pub function: u8,
+    /// Data of the message codewords (20 bits each)
+    pub payload: Vec<u32>,
+    /// Number of message codewords with uncorrectable errors
+    pub bad_codewords: usize,
+}
+
+impl Message for PocsagMessage {
+    fn disconnection() -> Option<Self> {
+        None
+    }
+}
+
+impl PocsagMessage {
+    /// Iterator over payload bits in the order of transmission
+    fn bits(&self) -> impl Iterator<Item = bool> + '_ {
+        self.payload
+            .iter()
+            .flat_map(|&word| (0..20).rev().map(move |i| word & (1 << i) != 0))
+    }
+    /// Collect payload into characters of `width` bits (sent LSB first)
+    fn characters(&self, width: usize) -> Vec<u8> {
+        let bits: Vec<bool> = self.bits().collect();
+        bits.chunks_exact(width)
+            .map(|chunk| {
+                chunk
+                    .iter()
+                    .enumerate()
+                    .fold(0u8, |acc, (i, &bit)| acc | (bit as u8) << i)
+            })
+            .collect()
+    }
+    /// Payload decoded as numeric message
+    pub fn numeric(&self) -> String {
+        const DIGITS: &[u8; 16] = b"0123456789*U -)(";
+        let text: String = self
+            .characters(4)
+            .into_iter()
+            .map(|digit| DIGITS[digit as usize] as char)
+            .collect();
+        text.trim_end().to_string()
+    }
+    /// Payload decoded as alphanumeric message (7 bit ASCII)
+    ///
+    /// Decoding stops at the first NUL, ETX, or EOT character.
+    pub fn alphanumeric(&self) -> String {
+        self.characters(7)
+            .into_iter()
+            .take_while(|&c| !matches!(c, 0x00 | 0x03 | 0x04))
+            .map(|c| c as char)
+            .collect()
+    }
+}
+
+const POCSAG_BIT_RATES: [u32; 3] = [512, 1200, 2400];
+const POCSAG_SYNC: u32 = 0x7CD215D8;
+const POCSAG_IDLE: u32 = 0x7A89C197;
+
+/// Calculate the remainder of a (31 bit) BCH(31,21) codeword
+fn pocsag_bch_remainder(mut value: u32) -> u32 {
+    const POLY: u32 = 0x769;
+    for i in (10..31).rev() {
+        if value & (1 << i) != 0 {
+            value ^= POLY << (i - 10);
+        }
+    }
+    value
+}
+
+/// Whether a 32 bit codeword (including the parity bit) is valid
+fn pocsag_is_valid(codeword: u32) -> bool {
+    pocsag_bch_remainder(codeword >> 1) == 0 && codeword.count_ones() & 1 == 0
+}
+
+/// Correct up to two bit errors in a 32 bit codeword
+fn pocsag_correct(codeword: u32) -> Option<u32> {
+    if pocsag_is_valid(codeword) {
+        return Some(codeword);
+    }
+    for i in 0..32 {
+        let candidate = codeword ^ (1 << i);
+        if pocsag_is_valid(candidate) {
+            return Some(candidate);
+        }
+    }
+    for i in 0..32 {
+        for j in i + 1..32 {
+            let candidate = codeword ^ (1 << i) ^ (1 << j);
+            if pocsag_is_valid(candidate) {
+                return Some(candidate);
+            }
+        }
+    }
+    None
+}
+
+/// Clock recovery and frame synchronization for a single bit rate
+struct PocsagReceiver {
+    bit_rate: u32,
+    step: f64,
+    phase: f64,
+    integral: f64,
+    previous: f64,
+    register: u32,
+    inverted: Option<bool>,
+    bit_count: usize,
+    codeword_index: usize,
+    message: Option<PocsagMessage>,
+}
+
+impl PocsagReceiver {
+    /// Maximum number of bit errors in a sync codeword
+    const MAX_SYNC_ERRORS: u32 = 2;
+    /// Loop gain of the clock recovery
+    const CLOCK_GAIN: f64 = 0.2;
+    fn new(bit_rate: u32, sample_rate: f64) -> Self {
+        assert!(
+            sample_rate >= 4.0 * bit_rate as f64,
+            "sample rate too low for POCSAG decoding"
+        );
+        Self {
+            bit_rate,
+            step: bit_rate as f64 / sample_rate,
+            phase: 0.0,
+            integral: 0.0,
+            previous: 0.0,
+            register: 0,
+            inverted: None,
+            bit_count: 0,
+            codeword_index: 0,
+            message: None,
+        }
+    }
+    /// Process one sample (with DC removed) and return message if complete
+    fn process(&mut self, sample: f64) -> Option<PocsagMessage> {
+        if (sample < 0.0) != (self.previous < 0.0) {
+            // transitions are expected at phase zero
+            let error = match self.phase < 0.5 {
+                true => self.phase,
+                false => self.phase - 1.0,
+            };
+            self.phase -= error * Self::CLOCK_GAIN;
+        }
+        self.previous = sample;
+        self.integral += sample;
+        self.phase += self.step;
+        if self.phase < 1.0 {
+            return None;
+        }
+        self.phase -= 1.0;
+        // lower frequency (negative value) is a binary one
+        let bit = self.integral < 0.0;
+        self.integral = 0.0;
+        self.process_bit(bit)
+    }
+    fn process_bit(&mut self, bit: bool) -> Option<PocsagMessage> {
+        self.register = (self.register << 1) | bit as u32;
+        let Some(inverted) = self.inverted else {
+            if (self.register ^ POCSAG_SYNC).count_ones() <= Self::MAX_SYNC_ERRORS {
+                self.inverted = Some(false);
+            } else if (!self.register ^ POCSAG_SYNC).count_ones() <= Self::MAX_SYNC_ERRORS {
+                self.inverted = Some(true);
+            }
+            self.bit_count = 0;
+            self.codeword_index = 0;
+            return None;
+        };
+        self.bit_count += 1;
+        if self.bit_count < 32 {
+            return None;
+        }
+        self.bit_count = 0;
+        let codeword = match inverted {
+            false => self.register,
+            true => !self.register,
+        };
+        if self.codeword_index == 16 {
+            if (codeword ^ POCSAG_SYNC).count_ones() <= Self::MAX_SYNC_ERRORS {
+                self.codeword_index = 0;
+                return None;
+            }
+            self.inverted = None;
+            return self.message.take();
+        }
+        let frame = (self.codeword_index / 2) as u32;
+        self.codeword_index += 1;
+        let Some(codeword) = pocsag_correct(codeword) else {
+            if codeword & 0x80000000 != 0 {
+                if let Some(message) = self.message.as_mut() {
+                    message.payload.push((codeword >> 11) & 0xFFFFF);
+                    message.bad_codewords += 1;
+                }
+            }
+            return None;
+        };
+        if codeword == POCSAG_IDLE {
+            return self.message.take();
+        }
+        if codeword & 0x80000000 == 0 {
+            let finished = self.message.take();
+            self.message = Some(PocsagMessage {
+                bit_rate: self.bit_rate,
+                capcode: ((codeword >> 13) & 0x3FFFF) << 3 | frame,
+                function: ((codeword >> 11) & 0x3) as u8,
+                payload: Vec::new(),
+                bad_codewords: 0,
+            });
+            return finished;
+        }
+        if let Some(message) = self.message.as_mut() {
+            message.payload.push((codeword >> 11) & 0xFFFFF);
+        }
+        None
+    }
+}
+
+/// POCSAG pager decoder
+///
+/// The block receives the FM demodulated signal (as real part of the
+/// samples, e.g. from an [`FmDemod`] block) and acts as a
+/// [`Producer<PocsagMessage>`]. Transmissions with 512, 1200, and 2400 bits
+/// per second are decoded simultaneously, and both polarities of the signal
+/// are detected through the sync codeword. Up to two bit errors per codeword
+/// are corrected. If the connected [`Consumer`]s are not ready to receive a
+/// message, the message is skipped instead of stalling the decoder.
+///
+/// The sample rate must be at least 9600 samples per second.
+pub struct PocsagDecoder<Flt> {
+    receiver_connector: ReceiverConnector<Signal<Complex<Flt>>>,
+    sender_connector: SenderConnector<PocsagMessage>,
+}
+
+impl_block_trait! { <Flt> Consumer<Signal<Complex<Flt>>> for PocsagDecoder<Flt> }
+impl_block_trait! { <Flt> Producer<PocsagMessage> for PocsagDecoder<Flt> }
+
+impl<Flt> PocsagDecoder<Flt>
+where
+    Flt: Float,
+{
+    /// Time constant for DC removal in seconds
+    const DC_TIME_CONSTANT: f64 = 0.1;
+    /// Create new POCSAG decoder
+    pub fn new() -> Self {
+        let (mut receiver, receiver_connector) = new_receiver::<Signal<Complex<Flt>>>();
+        let (sender, sender_connector) = new_sender::<PocsagMessage>();
+        spawn(async move {
+            let mut previous_sample_rate: Option<f64> = None;
+            let mut receivers: Vec<PocsagReceiver> = Vec::new();
+            let mut dc_coef: f64 = 0.0;
+            let mut dc: f64 = 0.0;
+            loop {
+                let Ok(signal) = receiver.recv().await else { return; };
+                match signal {
+                    Signal::Samples {
+                        sample_rate,
+                        chunk: input_chunk,
+                    } => {
+                        if previous_sample_rate != Some(sample_rate) {
+                            previous_sample_rate = Some(sample_rate);
+                            receivers = POCSAG_BIT_RATES
+                                .iter()
+                                .map(|&bit_rate| PocsagReceiver::new(bit_rate, sample_rate))
+                                .collect();
+                            dc_coef = 1.0 - (-(Self::DC_TIME_CONSTANT * sample_rate).recip()).exp();
+                        }
+                        for &sample in input_chunk.iter() {
+                            let value = sample.re.to_f64().unwrap();
+                            dc += (value - dc) * dc_coef;
+                            for receiver in receivers.iter_mut() {
+                                let Some(message) = receiver.process(value - dc) else { continue; };
+                                match sender.try_reserve() {
+                                    Ok(Some(reservation)) => reservation.send(message),
+                                    Ok(None) => (),
+                                    Err(_) => (),
+                                }
+                            }
+                        }
+                    }
+                    Signal::Event(event) => {
+                        if event.is_interrupt() {
+                            previous_sample_rate = None;
+                        }
+                    }
+                }
+            }
+        });
+        Self {
+            receiver_connector,
+            sender_connector,
+        }
+    }
+}
+
+/// Demodulator for audio frequency-shift keying (AFSK), e.g. Bell 202
+///
+/// The block receives an audio signal (as real part of the samples) or a
+/// complex baseband signal with a mark and a space tone. Both tones are mixed
+/// to zero frequency and integrated over one bit. The output (as real part of
+/// the samples, with the input sample rate) is the difference of the tone
+/// powers normalized to their sum, i.e. it is positive (up to `1.0`) for mark
+/// and negative (down to `-1.0`) for space. It can be fed into an
+/// [`Fsk2Slicer`].
+pub struct AfskDemod<Flt> {
+    receiver_connector: ReceiverConnector<Signal<Complex<Flt>>>,
+    sender_connector: SenderConnector<Signal<Complex<Flt>>>,
+}
+
+impl_block_trait! { <Flt> Consumer<Signal<Complex<Flt>>> for AfskDemod<Flt> }
+impl_block_trait! { <Flt> Producer<Signal<Complex<Flt>>> for AfskDemod<Flt> }
+
+impl<Flt> AfskDemod<Flt>
+where
+    Flt: Float,
+{
+    /// Create new AFSK demodulator for Bell 202 (mark 1200 Hz, space 2200 Hz,
+    /// 1200 baud), as used for AX.25 packet radio (APRS)
+    pub fn bell202() -> Self {
+        Self::new(1200.0, 2200.0, 1200.0)
+    }
+    /// Create new AFSK demodulator with given mark and space frequencies (in
+    /// hertz) and baud rate
+    pub fn new(mark_frequency: f64, space_frequency: f64, baud_rate: f64) -> Self {
+        use std::f64::consts::TAU;
+        let (mut receiver, receiver_connector) = new_receiver::<Signal<Complex<Flt>>>();
+        let (sender, sender_connector) = new_sender::<Signal<Complex<Flt>>>();
+        spawn(async move {
+            let mut buf_pool = ChunkBufPool::<Complex<Flt>>::new();
+            let zero = Complex::from(Flt::zero());
+            let mut previous_sample_rate: Option<f64> = None;
+            let mut phases: [f64; 2] = [0.0; 2];
+            let mut steps: [f64; 2] = [0.0; 2];
+            let mut history: Vec<[Complex<Flt>; 2]> = Vec::new();
+            let mut history_pos: usize = 0;
+            let mut sums: [Complex<Flt>; 2] = [zero; 2];
+            loop {
+                let Ok(signal) = receiver.recv().await else { return; };
+                match signal {
+                    Signal::Samples {
+                        sample_rate,
+                        chunk: input_chunk,
+                    } => {
+                        if previous_sample_rate != Some(sample_rate) {
+                            previous_sample_rate = Some(sample_rate);
+                            steps = [
+                                TAU * mark_frequency / sample_rate,
+                                TAU * space_frequency / sample_rate,
+                            ];
+                            let bit_len = (sample_rate / baud_rate).round() as usize;
+                            history = vec![[zero; 2]; bit_len.max(1)];
+                            history_pos = 0;
+                            sums = [zero; 2];
+                        }
+                        let mut output_chunk = buf_pool.get_with_capacity(input_chunk.len());
+                        for &sample in input_chunk.iter() {
+                            for tone in 0..2 {
+                                let (im, re) = (-phases[tone]).sin_cos();
+                                phases[tone] = (phases[tone] + steps[tone]) % TAU;
+                                let mixed = sample * Complex::new(flt!(re), flt!(im));
+                                sums[tone] += mixed - history[history_pos][tone];
+                                history[history_pos][tone] = mixed;
+                            }
+                            history_pos = (history_pos + 1) % history.len();
+                            let mark = sums[0].norm_sqr();
+                            let space = sums[1].norm_sqr();
+                            let total = mark + space;
+                            output_chunk.push(Complex::from(match total > Flt::zero() {
+                                true => (mark - space) / total,
+                                false => Flt::zero(),
+                            }));
+                        }
+                        let Ok(()) = sender
+                            .send(Signal::Samples {
+                                sample_rate,
+                                chunk: output_chunk.finalize(),
+                            })
+                            .await
+                        else { return; };
+                    }
+                    Signal::Event(event) => {
+                        if event.is_interrupt() {
+                            previous_sample_rate = None;
+                        }
+                        let Ok(()) = sender.send(Signal::Event(event)).await
+                        else { return; };
+                    }
+                }
+            }
+        });
+        Self {
+            receiver_connector,
+            sender_connector,
+        }
+    }
+}
+
+/// Address (callsign and SSID) in an [`Ax25Frame`]
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Ax25Address {
+    /// Callsign (up to 6 characters, without padding)
+    pub callsign: String,
+    /// Secondary station identifier (`0` to `15`)
+    pub ssid: u8,
+    /// C bit (command/response) for destination and source, or H bit (has
+    /// been repeated) for addresses in the path
+    pub flag: bool,
+}
+
+impl Ax25Address {
+    /// Create address with given callsign and SSID and cleared flag
+    pub fn new<S: Into<String>>(callsign: S, ssid: u8) -> Self {
+        Self {
+            callsign: callsign.into(),
+            ssid,
+            flag: false,
+        }
+    }
+    fn parse(bytes: &[u8]) -> Self {
+        let callsign: String = bytes[0..6]
+            .iter()
+            .map(|&byte| (byte >> 1) as char)
+            .collect();
+        Self {
+            callsign: callsign.trim_end().to_string(),
+            ssid: (bytes[6] >> 1) & 0x0F,
+            flag: bytes[6] & 0x80 != 0,
+        }
+    }
+    fn write(&self, last: bool, bytes: &mut Vec<u8>) {
+        let mut callsign = self.callsign.bytes().chain(std::iter::repeat(b' '));
+        bytes.extend((0..6).map(|_| callsign.next().unwrap() << 1));
+        bytes.push((self.flag as u8) << 7 | 0x60 | (self.ssid & 0x0F) << 1 | last as u8);
+    }
+}
+
+impl std::fmt::Display for Ax25Address {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.callsign)?;
+        if self.ssid != 0 {
+            write!(f, "-{}", self.ssid)?;
+        }
+        Ok(())
+    }
+}
+
+/// Frame received by an [`Ax25Decoder`]
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Ax25Frame {
+    /// Destination address
+    pub destination: Ax25Address,
+    /// Source address
+    pub source: Ax25Address,
+    /// Digipeater path (up to 8 addresses)
+    pub path: Vec<Ax25Address>,
+    /// Control field (`0x03` for UI frames, as used by APRS)
+    pub control: u8,
+    /// Protocol identifier (`0xF0` for no layer 3 protocol, as used by APRS),
+    /// which is only present in I and UI frames
+    pub pid: Option<u8>,
+    /// Information field
+    pub payload: Vec<u8>,
+}
+
+impl Message for Ax25Frame {
+    fn disconnection() -> Option<Self> {
+        None
+    }
+}
+
+impl Ax25Frame {
+    /// Create UI frame with PID `0xF0` (as used by APRS)
+    pub fn ui(
+        destination: Ax25Address,
+        source: Ax25Address,
+        path: Vec<Ax25Address>,
+        payload: Vec<u8>,
+    ) -> Self {
+        Self {
+            destination,
+            source,
+            path,
+            control: 0x03,
+            pid: Some(0xF0),
+            payload,
+        }
+    }
+    /// Parse frame (without flags and frame check sequence)
+    pub fn parse(bytes: &[u8]) -> Option<Self> {
+        let address_len = bytes.iter().position(|&byte| byte & 1 != 0)? + 1;
+        if address_len % 7 != 0 || !(14..=70).contains(&address_len) {
+            return None;
+        }
+        let mut addresses = bytes[0..address_len].chunks(7).map(Ax25Address::parse);
+        let destination = addresses.next().unwrap();
+        let source = addresses.next().unwrap();
+        let path = addresses.collect();
+        let control = *bytes.get(address_len)?;
+        let has_pid = control & 0x01 == 0 || control & 0xEF == 0x03;
+        let pid = match has_pid {
+            true => Some(*bytes.get(address_len + 1)?),
+            false => None,
+        };
+        let payload = bytes[address_len + 1 + has_pid as usize..].to_vec();
+        Some(Self {
+            destination,
+            source,
+            path,
+            control,
+            pid,
+            payload,
+        })
+    }
+    /// Serialize frame (without flags and frame check sequence)
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(16 + 7 * self.path.len() + self.payload.len());
+        self.destination.write(false, &mut bytes);
+        self.source.write(self.path.is_empty(), &mut bytes);
+        for (index, address) in self.path.iter().enumerate() {
+            address.write(index == self.path.len() - 1, &mut bytes);
+        }
+        bytes.push(self.control);
+        bytes.extend(self.pid);
+        bytes.extend_from_slice(&self.payload);
+        bytes
+    }
+}
+
+/// Formats frame in the common monitor format, e.g.
+/// `N0CALL-7>APRS,WIDE1-1*:payload`, where `*` marks repeated path entries
+impl std::fmt::Display for Ax25Frame {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}>{}", self.source, self.destination)?;
+        for address in self.path.iter() {
+            write!(f, ",{address}")?;
+            if address.flag {
+                write!(f, "*")?;
+            }
+        }
+        write!(f, ":{}", String::from_utf8_lossy(&self.payload))
+    }
+}
+
+/// Frame check sequence (CRC-16 as used by HDLC) of `bytes`
+fn hdlc_fcs(bytes: &[u8]) -> u16 {
+    let mut crc: u16 = 0xFFFF;
+    for &byte in bytes {
+        crc ^= byte as u16;
+        for _ in 0..8 {
+            crc = (crc >> 1) ^ (0x8408 & (crc & 1).wrapping_neg());
+        }
+    }
+    !crc
+}
+
+/// NRZI decoding, HDLC framing, and FCS check
+struct HdlcReceiver {
+    previous_level: bool,
+    shift: u8,
+    ones: usize,
+    in_frame: bool,
+    byte: u8,
+    bit_count: usize,
+    frame: Vec<u8>,
+}
+
+impl HdlcReceiver {
+    /// Maximum frame length (including frame check sequence)
+    const MAX_FRAME_LEN: usize = 400;
+    fn new() -> Self {
+        Self {
+            previous_level: false,
+            shift: 0,
+            ones: 0,
+            in_frame: false,
+            byte: 0,
+            bit_count: 0,
+            frame: Vec::new(),
+        }
+    }
+    /// Process received level and return frame (without frame check
+    /// sequence) if a valid frame has been completed
+    fn process(&mut self, level: bool) -> Option<Vec<u8>> {
+        // NRZI: no transition for a one, transition for a zero
+        let bit = level == self.previous_level;
+        self.previous_level = level;
+        // bits are sent LSB first
+        self.shift = (self.shift >> 1) | (bit as u8) << 7;
+        if self.shift == 0x7E {
+            // the flag except for its last bit has been collected already
+            let result = match self.in_frame && self.bit_count == 7 && self.frame.len() >= 17 {
+                true => {
+                    let (data, fcs) = self.frame.split_at(self.frame.len() - 2);
+                    (hdlc_fcs(data) == u16::from_le_bytes([fcs[0], fcs[1]])).then(|| data.to_vec())
+                }
+                false => None,
+            };
+            self.in_frame = true;
+            self.ones = 0;
+            self.bit_count = 0;
+            self.frame.clear();
+            return result;
+        }
+        if bit {
+            self.ones += 1;
+            if self.ones > 6 {
+                // abort
+                self.in_frame = false;
+                return None;
+            }
+        } else {
+            let stuffed = self.ones == 5;
+            self.ones = 0;
+            if stuffed {
+                return None;
+            }
+        }
+        if self.in_frame {
+            self.byte = (self.byte >> 1) | (bit as u8) << 7;
+            self.bit_count += 1;
+            if self.bit_count == 8 {
+                self.bit_count = 0;
+                self.frame.push(self.byte);
+                if self.frame.len() > Self::MAX_FRAME_LEN {
+                    self.in_frame = false;
+                }
+            }
+        }
+        None
+    }
+}
+
+/// AX.25 (packet radio) frame decoder, e.g. for APRS
+///
+/// The block receives the sliced bits of an AFSK or FSK signal (e.g. from an
+/// [`AfskDemod`] followed by an [`Fsk2Slicer`] with a baud rate of 1200), and
+/// acts as a [`Producer<Ax25Frame>`]. The bits are NRZI decoded, frames are
+/// delimited by HDLC flags, stuffed bits are removed, and frames with an
+/// invalid frame check sequence are discarded. As NRZI is used, the polarity
+/// of the input doesn't matter. If the connected [`Consumer`]s are not ready
+/// to receive a frame, the frame is skipped instead of stalling the decoder.
+///
+/// The state of the decoder is reset when an [interrupting] event is
+/// received.
+///
+/// [interrupting]: Event::is_interrupt
+pub struct Ax25Decoder {
+    receiver_connector: ReceiverConnector<Signal<bool>>,
+    sender_connector: SenderConnector<Ax25Frame>,
+}
+
+impl_block_trait! { Consumer<Signal<bool>> for Ax25Decoder }
+impl_block_trait! { Producer<Ax25Frame> for Ax25Decoder }
+
+impl Ax25Decoder {
+    /// Create new AX.25 decoder
+    pub fn new() -> Self {
+        let (mut receiver, receiver_connector) = new_receiver::<Signal<bool>>();
+        let (sender, sender_connector) = new_sender::<Ax25Frame>();
+        spawn(async move {
+            let mut hdlc = HdlcReceiver::new();
+            loop {
+                let Ok(signal) = receiver.recv().await else { return; };
+                match signal {
+                    Signal::Samples {
+                        chunk: input_chunk, ..
+                    } => {
+                        for &level in input_chunk.iter() {
+                            let Some(bytes) = hdlc.process(level) else { continue; };
+                            let Some(frame) = Ax25Frame::parse(&bytes) else { continue; };
+                            match sender.try_reserve() {
+                                Ok(Some(reservation)) => reservation.send(frame),
+                                Ok(None) => (),
+                                Err(_) => (),
+                            }
+                        }
+                    }
+                    Signal::Event(event) => {
+                        if event.is_interrupt() {
+                            hdlc = HdlcReceiver::new();
+                        }
+                    }
+                }
+            }
+        });
+        Self {
+            receiver_connector,
+            sender_connector,
+        }
+    }
+}
+
+/// Mode S message received by an [`AdsbDemod`]
+#[derive(Clone, PartialEq, Debug)]
+pub struct ModeSMessage {
+    /// Message bytes (7 bytes for short or 14 bytes for long messages),
+    /// including the parity field
+    pub data: Vec<u8>,
+    /// Number of bits which have been corrected
+    pub corrected_bits: usize,
+    /// Mean magnitude of the preamble pulses relative to the noise floor
+    pub signal_to_noise: f64,
+}
+
+impl Message for ModeSMessage {
+    fn disconnection() -> Option<Self> {
+        None
+    }
+}
+
+impl ModeSMessage {
+    /// Downlink format (`17` for ADS-B)
+    pub fn downlink_format(&self) -> u8 {
+        self.data[0] >> 3
+    }
+    /// ICAO address of the aircraft (for downlink formats 11, 17, and 18)
+    pub fn address(&self) -> Option<u32> {
+        matches!(self.downlink_format(), 11 | 17 | 18)
+            .then(|| u32::from_be_bytes([0, self.data[1], self.data[2], self.data[3]]))
+    }
+    /// ADS-B message type code (for downlink formats 17 and 18)
+    pub fn type_code(&self) -> Option<u8> {
+        matches!(self.downlink_format(), 17 | 18).then(|| self.data[4] >> 3)
+    }
+}
+
+/// CRC-24 generator polynomial of Mode S
+const MODE_S_POLYNOMIAL: u32 = 0xFFF409;
+
+/// Parity of Mode S message (checksum of all bits XOR parity field)
+fn mode_s_residual(data: &[u8]) -> u32 {
+    let (payload, parity) = data.split_at(data.len() - 3);
+    let mut crc: u32 = 0;
+    for &byte in payload {
+        crc ^= (byte as u32) << 16;
+        for _ in 0..8 {
+            crc <<= 1;
+            if crc & 0x1000000 != 0 {
+                crc ^= MODE_S_POLYNOMIAL;
+            }
+        }
+    }
+    (crc ^ u32::from_be_bytes([0, parity[0], parity[1], parity[2]])) & 0xFFFFFF
+}
+
+/// ADS-B / Mode S demodulator for 1090 MHz
+///
+/// The block receives a complex baseband signal centered at 1090 MHz with a
+/// sample rate of at least 2 MHz and acts as a [`Producer<ModeSMessage>`].
+///
+/// A preamble is detected if its four pulses are higher than the adjacent
+/// gaps, at least twice as high (6 dB) as the mean magnitude of the gaps, and
+/// at least [`MIN_SNR`](Self::MIN_SNR) times as high as the noise floor
+/// (which is estimated from the mean magnitude of the signal). The following
+/// bits are demodulated by comparing the magnitude of the two halves of each
+/// bit period (pulse position modulation).
+///
+/// Only messages which can be validated through their parity field are
+/// emitted: extended squitters (downlink formats 17 and 18, including ADS-B),
+/// where single bit errors are corrected, and all-call replies (downlink
+/// format 11, with an interrogator identifier of zero or overlaid on the
+/// parity). Other downlink formats overlay the parity with the address of
+/// the aircraft and are not checked. If the connected [`Consumer`]s are not
+/// ready to receive a message, the message is skipped instead of stalling
+/// the demodulator.
+pub struct AdsbDemod<Flt> {
+    receiver_connector: ReceiverConnector<Signal<Complex<Flt>>>,
+    sender_connector: SenderConnector<ModeSMessage>,
+}
+
+impl_block_trait! { <Flt> Consumer<Signal<Complex<Flt>>> for AdsbDemod<Flt> }
+impl_block_trait! { <Flt> Producer<ModeSMessage> for AdsbDemod<Flt> }
+
+impl<Flt> AdsbDemod<Flt>
+where
+    Flt: Float,
+{
+    /// Minimum ratio of preamble pulse magnitude to noise floor
+    pub const MIN_SNR: f64 = 3.0;
+    /// Number of samples (at 2 MHz) for estimating the noise floor
+    const NOISE_SAMPLES: f64 = 4096.0;
+    /// Create new ADS-B demodulator
+    pub fn new() -> Self {
+        let (mut receiver, receiver_connector) = new_receiver::<Signal<Complex<Flt>>>();
+        let (sender, sender_connector) = new_sender::<ModeSMessage>();
+        spawn(async move {
+            // syndromes of single bit errors in long messages
+            let syndromes: Vec<u32> = (0..112)
+                .map(|bit| {
+                    let mut data = [0u8; 14];
+                    data[bit / 8] = 0x80 >> (bit % 8);
+                    mode_s_residual(&data)
+                })
+                .collect();
+            let mut magnitudes: Vec<f32> = Vec::new();
+            let mut noise: f64 = 0.0;
+            let mut noise_pos: usize = 0;
+            let mut pos: usize = 0;
+            loop {
+                let Ok(signal) = receiver.recv().await else { return; };
+                match signal {
+                    Signal::Samples {
+                        sample_rate,
+                        chunk: input_chunk,
+                    } => {
+                        assert!(sample_rate >= 2e6, "sample rate must be at least 2 MHz");
+                        // samples per half microsecond (chip)
+                        let chip = sample_rate / 2e6;
+                        let noise_coef = (chip * Self::NOISE_SAMPLES).recip();
+                        magnitudes.extend(input_chunk.iter().map(|x| x.norm().to_f32().unwrap()));
+                        // mean magnitude of the chip starting at `start`
+                        // chips after `pos`
+                        let chip_at = |magnitudes: &[f32], pos: usize, start: f64| -> f64 {
+                            let first = pos + (start * chip).round() as usize;
+                            let last = pos + ((start + 1.0) * chip).round() as usize;
+                            let samples = &magnitudes[first..last.max(first + 1)];
+                            samples.iter().map(|&x| x as f64).sum::<f64>() / samples.len() as f64
+                        };
+                        let message_len = ((16.0 + 224.0 + 1.0) * chip).ceil() as usize;
+                        while pos + message_len <= magnitudes.len() {
+                            while noise_pos <= pos {
+                                noise += (magnitudes[noise_pos] as f64 - noise) * noise_coef;
+                                noise_pos += 1;
+                            }
+                            let p: [f64; 16] =
+                                std::array::from_fn(|i| chip_at(&magnitudes, pos, i as f64));
+                            let high = [p[0], p[2], p[7], p[9]];
+                            let low_mean =
+                                (p.iter().sum::<f64>() - high.iter().sum::<f64>()) / 12.0;
+                            let high_min = high.into_iter().fold(f64::INFINITY, f64::min);
+                            let high_mean = high.iter().sum::<f64>() / 4.0;
+                            let is_preamble = p[0] > p[1]
+                                && p[2] > p[1]
+                                && p[2] > p[3]
+                                && p[7] > p[6]
+                                && p[7] > p[8]
+                                && p[9] > p[8]
+                                && p[9] > p[10]
+                                && high_min > 2.0 * low_mean
+                                && high_mean > Self::MIN_SNR * noise;
+                            if !is_preamble {
+                                pos += 1;
+                                continue;
+                            }
+                            let mut data = [0u8; 14];
+                            for bit in 0..112 {
+                                let first = chip_at(&magnitudes, pos, 16.0 + 2.0 * bit as f64);
+                                let second = chip_at(&magnitudes, pos, 17.0 + 2.0 * bit as f64);
+                                if first > second {
+                                    data[bit / 8] |= 0x80 >> (bit % 8);
+                                }
+                            }
+                            let downlink_format = data[0] >> 3;
+                            let len = match downlink_format >= 16 {
+                                true => 14,
+                                false => 7,
+                            };
+                            let residual = mode_s_residual(&data[0..len]);
+                            let corrected_bits = match downlink_format {
+                                17 | 18 if residual == 0 => Some(0),
+                                17 | 18 => {
+                                    syndromes.iter().position(|&x| x == residual).map(|bit| {
+                                        data[bit / 8] ^= 0x80 >> (bit % 8);
+                                        1
+                                    })
+                                }
+                                11 if residual < 0x80 => Some(0),
+                                _ => None,
+                            };
+                            let Some(corrected_bits) = corrected_bits else {
+                                pos += 1;
+                                continue;
+                            };
+                            let message = ModeSMessage {
+                                data: data[0..len].to_vec(),
+                                corrected_bits,
+                                signal_to_noise: high_mean / noise,
+                            };
+                            match sender.try_reserve() {
+                                Ok(Some(reservation)) => reservation.send(message),
+                                Ok(None) => (),
+                                Err(_) => (),
+                            }
+                            pos += ((16.0 + 2.0 * 8.0 * len as f64) * chip) as usize;
+                        }
+                        magnitudes.drain(0..pos);
+                        noise_pos -= pos.min(noise_pos);
+                        pos = 0;
+                    }
+                    Signal::Event(event) => {
+                        if event.is_interrupt() {
+                            magnitudes.clear();
+                            noise_pos = 0;
+                            pos = 0;
+                        }
+                    }
+                }
+            }
+        });
+        Self {
+            receiver_connector,
+            sender_connector,
+        }
+    }
+}
+
+/// Morse (CW) decoder block
+///
+/// The block receives an audio signal (as real part of the samples) and
+/// detects a tone with given frequency using the Goertzel algorithm. The
+/// length of a dit is estimated adaptively, such that changes of the
+/// sending speed are followed. Decoded characters (and spaces between words)
+/// are sent through a [`broadcast`] channel, see [`CwDecoder::subscribe`].
+///
+/// Characters that cannot be decoded are reported as `'*'`.
+pub struct CwDecoder<Flt> {
+    receiver_connector: ReceiverConnector<Signal<Complex<Flt>>>,
+    tone_frequency: watch::Sender<f64>,
+    text: broadcast::Sender<String>,
+    speed: watch::Receiver<Speed>,
+}
+
+impl_block_trait! { <Flt> Consumer<Signal<Complex<Flt>>> for CwDecoder<Flt> }
+
+impl<Flt> CwDecoder<Flt>
+where
+    Flt: Float,
+{
+    /// Duration of blocks analyzed by the Goertzel algorithm in seconds
+    const BLOCK_DURATION: f64 = 0.004;
+    /// Create new CW decoder for given tone frequency in hertz and initially
+    /// expected speed
+    pub fn new(tone_frequency: f64, speed: Speed) -> Self {
+        let (mut receiver, receiver_connector) = new_receiver::<Signal<Complex<Flt>>>();
+        let (tone_frequency_send, mut tone_frequency_recv) = watch::channel(tone_frequency);
+        let (text_send, _) = broadcast::channel::<String>(256);
+        let text_send_clone = text_send.clone();
+        let (speed_send, speed_recv) = watch::channel(speed);
+        spawn(async move {
+            let mut tone_frequency = tone_frequency;
+            let mut previous_sample_rate: Option<f64> = None;
+            let mut block_len: usize = 0;
+            let mut goertzel = GoertzelState::<Flt>::new(tone_frequency, 1.0);
+            let mut block_pos: usize = 0;
+            let mut peak: f64 = 0.0;
+            let mut noise: f64 = 0.0;
+            let mut is_on = false;
+            let mut state_blocks: usize = 0;
+            let mut dit_blocks: f64 = 0.0;
+            let mut symbols: Vec<Unit> = Vec::new();
+            let mut word_pending = false;
+            loop {
+                let Ok(signal) = receiver.recv().await else { return; };
+                match signal {
+                    Signal::Samples {
+                        sample_rate,
+                        chunk: input_chunk,
+                    } => {
+                        let mut recalculate = false;
+                        if tone_frequency_recv.has_changed().unwrap_or(false) {
+                            tone_frequency = *tone_frequency_recv.borrow_and_update();
+                            recalculate = true;
+                        }
+                        if previous_sample_rate != Some(sample_rate) {
+                            previous_sample_rate = Some(sample_rate);
+                            block_len =
+                                ((Self::BLOCK_DURATION * sample_rate).round() as usize).max(1);
+                            dit_blocks =
+                                speed_send.borrow().samples_per_dit(sample_rate) / block_len as f64;
+                            recalculate = true;
+                        }
+                        if recalculate {
+                            goertzel = GoertzelState::new(tone_frequency, sample_rate);
+                            block_pos = 0;
+                        }
+                        for &sample in input_chunk.iter() {
+                            goertzel.push(sample.re);
+                            block_pos += 1;
+                            if block_pos < block_len {
+                                continue;
+                            }
+                            let magnitude = goertzel.power().sqrt().to_f64().unwrap();
+                            goertzel.reset();
+                            block_pos = 0;
+                            // adaptive threshold with a fast rising peak level
+                            // and a slowly following noise floor
+                            if magnitude > peak {
+                                peak = magnitude;
+                            } else {
+                                peak += (magnitude - peak) * 0.002;
+                            }
+                            if magnitude < noise {
+                                noise = magnitude;
+                            } else {
+                                noise += (magnitude - noise) * 0.002;
+                            }
+                            let threshold = match is_on {
+                                true => noise + (peak - noise) * 0.4,
+                                false => noise + (peak - noise) * 0.6,
+                            };
+                            let now_on = magnitude > threshold && peak > noise * 2.0;
+                            if now_on == is_on {
+                                state_blocks += 1;
+                                if !is_on {
+                                    let gap = state_blocks as f64;
+                                    let mut output = String::new();
+                                    if !symbols.is_empty() && gap > 2.0 * dit_blocks {
+                                        output.push(morse::decode(&symbols).unwrap_or('*'));
+                                        symbols.clear();
+                                        word_pending = true;
+                                    }
+                                    if word_pending && gap > 5.0 * dit_blocks {
+                                        output.push(' ');
+                                        word_pending = false;
+                                    }
+                                    if !output.is_empty() {
+                                        let _ = text_send_clone.send(output);
+                                    }
+                                }
+                                continue;
+                            }
+                            let duration = state_blocks as f64;
+                            if is_on {
+                                if duration < 2.0 * dit_blocks {
+                                    symbols.push(Unit::Dit);
+                                    dit_blocks += (duration - dit_blocks) * 0.2;
+                                } else {
+                                    symbols.push(Unit::Dah);
+                                    dit_blocks += (duration / 3.0 - dit_blocks) * 0.2;
+                                }
+                                dit_blocks = dit_blocks.max(1.0);
+                                let seconds_per_dit = dit_blocks * block_len as f64 / sample_rate;
+                                speed_send.send_replace(Speed::from_dits_per_minute(
+                                    60.0 / seconds_per_dit,
+                                ));
+                            }
+                            is_on = now_on;
+                            state_blocks = 1;
+                        }
+                    }
+                    Signal::Event(event) => {
+                        if event.is_interrupt() {
+                            symbols.clear();
+                            is_on = false;
+                            state_blocks = 0;
+                        }
+                    }
+                }
+            }
+        });
+        Self {
+            receiver_connector,
+            tone_frequency: tone_frequency_send,
+            text: text_send,
+            speed: speed_recv,
+        }
+    }
+    /// Get tone frequency in hertz
+    pub fn tone_frequency(&self) -> f64 {
+        *self.tone_frequency.borrow()
+    }
+    /// Set tone frequency in hertz
+    pub fn set_tone_frequency(&self, tone_frequency: f64) {
+        self.tone_frequency.send_replace(tone_frequency);
+    }
+    /// Subscribe to decoded text
+    pub fn subscribe(&self) -> broadcast::Receiver<String> {
+        self.text.subscribe()
+    }
+    /// Estimated speed
+    pub fn speed(&self) -> Speed {
+        *self.speed.borrow()
+    }
+    /// Estimated speed in words per minute (using word "PARIS")
+    pub fn wpm(&self) -> f64 {
+        self.speed().paris_wpm()
+    }
+}
+
+/// Morse (CW) encoder block, which keys a sine tone
+///
+/// Text queued with [`CwEncoder::send`] is converted to morse code and sent
+/// as an audio tone (in the real part of the samples). Each keying
+/// transition is shaped with a raised cosine of 5 ms to avoid key clicks.
+/// Like the [`Keyer`] block, the encoder sends silence while no message is
+/// queued and reports [`StartOfMessages`] and [`EndOfMessages`] events.
+///
+/// If a Farnsworth speed is set (see [`CwEncoder::set_farnsworth_speed`])
+/// which is slower than the character speed, characters are sent with the
+/// character speed, but the spaces between characters and words are
+/// extended such that the overall speed matches the Farnsworth speed.
+///
+/// [`Keyer`]: crate::blocks::morse::Keyer
+pub struct CwEncoder<Flt> {
+    sender_connector: SenderConnector<Signal<Complex<Flt>>>,
+    tone_frequency: watch::Sender<f64>,
+    speed: watch::Sender<Speed>,
+    farnsworth_speed: watch::Sender<Option<Speed>>,
+    messages: mpsc::UnboundedSender<Vec<Unit>>,
+}
+
+impl_block_trait! { <Flt> Producer<Signal<Complex<Flt>>> for CwEncoder<Flt> }
+
+/// Duration of a [`Unit`] in seconds with optional Farnsworth timing
+fn farnsworth_duration(unit: Unit, speed: Speed, farnsworth_speed: Option<Speed>) -> f64 {
+    match (unit, farnsworth_speed) {
+        (Unit::CharSpace | Unit::WordSpace | Unit::Padding, Some(slow)) if slow < speed => {
+            // spread the delay of the word "PARIS" (19 space units) evenly
+            let (c, s) = (speed.paris_wpm(), slow.paris_wpm());
+            let space_unit = (60.0 * c - 37.2 * s) / (s * c) / 19.0;
+            unit.relative_duration() * space_unit
+        }
+        _ => unit.relative_duration() * speed.seconds_per_dit(),
+    }
+}
+
+impl<Flt> CwEncoder<Flt>
+where
+    Flt: Float,
+{
+    /// Duration of the raised cosine shaping of each keying transition in
+    /// seconds
+    const RISE_TIME: f64 = 0.005;
+    /// Create new CW encoder with given tone frequency in hertz and
+    /// character speed
+    pub fn new(chunk_len: usize, sample_rate: f64, tone_frequency: f64, speed: Speed) -> Self {
+        use std::f64::consts::{PI, TAU};
+        let (sender, sender_connector) = new_sender::<Signal<Complex<Flt>>>();
+        let (tone_frequency_send, mut tone_frequency_recv) = watch::channel(tone_frequency);
+        let (speed_send, mut speed_recv) = watch::channel(speed);
+        let (farnsworth_speed_send, mut farnsworth_speed_recv) = watch::channel(None);
+        let (messages_send, mut messages_recv) = mpsc::unbounded_channel::<Vec<Unit>>();
+        let ramp_len = ((Self::RISE_TIME * sample_rate).round() as usize).max(1);
+        spawn(async move {
+            let mut buf_pool = ChunkBufPool::<Complex<Flt>>::new();
+            let mut silence_buf = buf_pool.get_with_capacity(chunk_len);
+            silence_buf.resize(chunk_len, Complex::from(Flt::zero()));
+            let silence = silence_buf.finalize();
+            let mut output_chunk = buf_pool.get_with_capacity(chunk_len);
+            let mut speed = speed;
+            let mut farnsworth_speed: Option<Speed> = None;
+            let mut step = TAU * tone_frequency / sample_rate;
+            let mut phase: f64 = 0.0;
+            let mut ramp_pos: usize = 0;
+            let mut idle = true;
+            loop {
+                match messages_recv.try_recv() {
+                    Ok(units) => {
+                        if idle {
+                            let Ok(()) = sender.send(Signal::new_event(StartOfMessages)).await
+                            else { return; };
+                            idle = false;
+                        }
+                        for unit in units {
+                            if tone_frequency_recv.has_changed().unwrap_or(false) {
+                                step = TAU * *tone_frequency_recv.borrow_and_update() / sample_rate;
+                            }
+                            if speed_recv.has_changed().unwrap_or(false) {
+                                speed = *speed_recv.borrow_and_update();
+                            }
+                            if farnsworth_speed_recv.has_changed().unwrap_or(false) {
+                                farnsworth_speed = *farnsworth_speed_recv.borrow_and_update();
+                            }
+                            let duration = farnsworth_duration(unit, speed, farnsworth_speed);
+                            for _ in 0..(duration * sample_rate).round() as usize {
+                                ramp_pos = match unit.on() {
+                                    true => (ramp_pos + 1).min(ramp_len),
+                                    false => ramp_pos.saturating_sub(1),
+                                };
+                                let envelope =
+                                    0.5 - 0.5 * (PI * ramp_pos as f64 / ramp_len as f64).cos();
+                                output_chunk.push(Complex::from(flt!(envelope * phase.sin())));
+                                phase = (phase + step) % TAU;
+                                if output_chunk.len() >= chunk_len {
+                                    let Ok(()) = sender.send(Signal::Samples {
+                                        sample_rate,
+                                        chunk: output_chunk.finalize(),
+                                    }).await
+                                    else { return; };
+                                    output_chunk = buf_pool.get_with_capacity(chunk_len);
+                                }
+                            }
+                        }
+                    }
+                    Err(mpsc::error::TryRecvError::Empty) => {
+                        if !output_chunk.is_empty() {
+                            output_chunk.resize(chunk_len, Complex::from(Flt::zero()));
+                            let Ok(()) = sender.send(Signal::Samples {
+                                sample_rate,
+                                chunk: output_chunk.finalize(),
+                            }).await
+                            else { return; };
+                            output_chunk = buf_pool.get_with_capacity(chunk_len);
+                        }
+                        if idle {
+                            let Ok(()) = sender.send(Signal::Samples {
+                                sample_rate,
+                                chunk: silence.clone(),
+                            }).await
+                            else { return; };
+                        } else {
+                            let Ok(()) = sender.send(Signal::new_event(EndOfMessages)).await
+                            else { return; };
+                            idle = true;
+                        }
+                    }
+                    Err(mpsc::error::TryRecvError::Disconnected) => return,
+                }
+            }
+        });
+        Self {
+            sender_connector,
+            tone_frequency: tone_frequency_send,
+            speed: speed_send,
+            farnsworth_speed: farnsworth_speed_send,
+            messages: messages_send,
+        }
+    }
+    /// Send text as morse code
+    pub fn send(&self, text: &str) -> Result<(), EncodeError> {
+        self.messages.send(morse::encode(text)?).unwrap();
+        Ok(())
+    }
+    /// Get tone frequency in hertz
+    pub fn tone_frequency(&self) -> f64 {
+        *self.tone_frequency.borrow()
+    }
+    /// Set tone frequency in hertz
+    pub fn set_tone_frequency(&self, tone_frequency: f64) {
+        self.tone_frequency.send_replace(tone_frequency);
+    }
+    /// Get character speed
+    pub fn speed(&self) -> Speed {
+        *self.speed.borrow()
+    }
+    /// Set character speed
+    pub fn set_speed(&self, speed: Speed) {
+        self.speed.send_replace(speed);
+    }
+    /// Get Farnsworth speed (overall speed including extended spaces)
+    pub fn farnsworth_speed(&self) -> Option<Speed> {
+        *self.farnsworth_speed.borrow()
+    }
+    /// Set Farnsworth speed or disable Farnsworth timing with `None`
+    pub fn set_farnsworth_speed(&self, farnsworth_speed: Option<Speed>) {
+        self.farnsworth_speed.send_replace(farnsworth_speed);
+    }
+}
+
+/// Standard CTCSS tone frequencies in hertz
+pub const CTCSS_FREQUENCIES: [f64; 50] = [
+    67.0, 69.3, 71.9, 74.4, 77.0, 79.7, 82.5, 85.4, 88.5, 91.5, 94.8, 97.4, 100.0, 103.5, 107.2,
+    110.9, 114.8, 118.8, 123.0, 127.3, 131.8, 136.5, 141.3, 146.2, 150.0, 151.4, 156.7, 159.8,
+    162.2, 165.5, 167.9, 171.3, 173.8, 177.3, 179.9, 183.5, 186.2, 189.9, 192.8, 196.6, 199.5,
+    203.5, 206.5, 210.7, 218.1, 225.7, 229.1, 233.6, 241.8, 250.3,
+];
+
+/// CTCSS encoder block, which adds a sub-audible tone to an audio signal
+///
+/// The tone is added to the real part of the received samples. Setting the
+/// frequency to `None` disables the tone.
+pub struct CtcssEncoder<Flt> {
+    receiver_connector: ReceiverConnector<Signal<Complex<Flt>>>,
+    sender_connector: SenderConnector<Signal<Complex<Flt>>>,
+    frequency: watch::Sender<Option<f64>>,
+    amplitude: watch::Sender<f64>,
+}
+
+impl_block_trait! { <Flt> Consumer<Signal<Complex<Flt>>> for CtcssEncoder<Flt> }
+impl_block_trait! { <Flt> Producer<Signal<Complex<Flt>>> for CtcssEncoder<Flt> }
+
+impl<Flt> CtcssEncoder<Flt>
+where
+    Flt: Float,
+{
+    /// Create new CTCSS encoder with given tone frequency in hertz (see
+    /// [`CTCSS_FREQUENCIES`]) and amplitude
+    pub fn new(frequency: Option<f64>, amplitude: f64) -> Self {
+        use std::f64::consts::TAU;
+        let (mut receiver, receiver_connector) = new_receiver::<Signal<Complex<Flt>>>();
+        let (sender, sender_connector) = new_sender::<Signal<Complex<Flt>>>();
+        let (frequency_send, mut frequency_recv) = watch::channel(frequency);
+        let (amplitude_send, mut amplitude_recv) = watch::channel(amplitude);
+        spawn(async move {
+            let mut buf_pool = ChunkBufPool::<Complex<Flt>>::new();
+            let mut frequency = frequency;
+            let mut amplitude = amplitude;
+            let mut phase: f64 = 0.0;
+            loop {
+                let Ok(signal) = receiver.recv().await else { return; };
+                match signal {
+                    Signal::Samples {
+                        sample_rate,
+                        chunk: input_chunk,
+                    } => {
+                        if frequency_recv.has_changed().unwrap_or(false) {
+                            frequency = *frequency_recv.borrow_and_update();
+                        }
+                        if amplitude_recv.has_changed().unwrap_or(false) {
+                            amplitude = *amplitude_recv.borrow_and_update();
+                        }
+                        let mut output_chunk = buf_pool.get_with_capacity(input_chunk.len());
+                        match frequency {
+                            None => output_chunk.extend_from_slice(&input_chunk),
+                            Some(frequency) => {
+                                let step = TAU * frequency / sample_rate;
+                                for &sample in input_chunk.iter() {
+                                    output_chunk.push(Complex::new(
+                                        sample.re + flt!(amplitude * phase.sin()),
+                                        sample.im,
+                                    ));
+                                    phase = (phase + step) % TAU;
+                                }
+                            }
+                        }
+                        let Ok(()) = sender.send(Signal::Samples {
+                            sample_rate,
+                            chunk: output_chunk.finalize(),
+                        }).await
+                        else { return; };
+                    }
+                    event @ Signal::Event { .. } => {
+                        let Ok(()) = sender.send(event).await else { return; };
+                    }
+                }
+            }
+        });
+        Self {
+            receiver_connector,
+            sender_connector,
+            frequency: frequency_send,
+            amplitude: amplitude_send,
+        }
+    }
+    /// Get tone frequency in hertz
+    pub fn frequency(&self) -> Option<f64> {
+        *self.frequency.borrow()
+    }
+    /// Set tone frequency in hertz
+    pub fn set_frequency(&self, frequency: Option<f64>) {
+        self.frequency.send_replace(frequency);
+    }
+    /// Get tone amplitude
+    pub fn amplitude(&self) -> f64 {
+        *self.amplitude.borrow()
+    }
+    /// Set tone amplitude
+    pub fn set_amplitude(&self, amplitude: f64) {
+        self.amplitude.send_replace(amplitude);
+    }
+}
+
+/// CTCSS decoder block, which detects which of the [`CTCSS_FREQUENCIES`] is
+/// present in an audio signal
+///
+/// The real part of the received samples is analyzed in blocks of half a
+/// second. A tone is considered present if its power exceeds the median power
+/// of all standard tones by 10 dB. The detected tone is only changed after
+/// the same result has been obtained for two consecutive blocks.
+pub struct CtcssDecoder<Flt> {
+    receiver_connector: ReceiverConnector<Signal<Complex<Flt>>>,
+    tone: watch::Receiver<Option<f64>>,
+}
+
+impl_block_trait! { <Flt> Consumer<Signal<Complex<Flt>>> for CtcssDecoder<Flt> }
+
+impl<Flt> CtcssDecoder<Flt>
+where
+    Flt: Float,
+{
+    /// Duration of analyzed blocks in seconds
+    const BLOCK_DURATION: f64 = 0.5;
+    /// Minimum ratio between power of detected tone and median power
+    const THRESHOLD: f64 = 10.0;
+    /// Minimum amplitude of detected tone
+    const MIN_AMPLITUDE: f64 = 1e-3;
+    /// Create new CTCSS decoder
+    pub fn new() -> Self {
+        let (mut receiver, receiver_connector) = new_receiver::<Signal<Complex<Flt>>>();
+        let (tone_send, tone) = watch::channel::<Option<f64>>(None);
+        spawn(async move {
+            let mut previous_sample_rate: Option<f64> = None;
+            let mut states: Vec<GoertzelState<Flt>> = Vec::new();
+            let mut block_len: usize = 0;
+            let mut block_pos: usize = 0;
+            let mut powers: Vec<f64> = Vec::with_capacity(CTCSS_FREQUENCIES.len());
+            let mut candidate: Option<Option<f64>> = None;
+            loop {
+                let Ok(signal) = receiver.recv().await else { return; };
+                match signal {
+                    Signal::Samples {
+                        sample_rate,
+                        chunk: input_chunk,
+                    } => {
+                        if previous_sample_rate != Some(sample_rate) {
+                            previous_sample_rate = Some(sample_rate);
+                            states = CTCSS_FREQUENCIES
+                                .iter()
+                                .map(|&frequency| GoertzelState::new(frequency, sample_rate))
+                                .collect();
+                            block_len =
+                                ((Self::BLOCK_DURATION * sample_rate).round() as usize).max(1);
+                            block_pos = 0;
+                        }
+                        for &sample in input_chunk.iter() {
+                            for state in states.iter_mut() {
+                                state.push(sample.re);
+                            }
+                            block_pos += 1;
+                            if block_pos < block_len {
+                                continue;
+                            }
+                            block_pos = 0;
+                            powers.clear();
+                            powers.extend(states.iter().map(|state| {
+                                state.power().to_f64().unwrap() / (block_len * block_len) as f64
+                            }));
+                            for state in states.iter_mut() {
+                                state.reset();
+                            }
+                            let (best, &best_power) = powers
+                                .iter()
+                                .enumerate()
+                                .max_by(|a, b| a.1.total_cmp(b.1))
+                                .unwrap();
+                            let mut sorted = powers.clone();
+                            sorted.sort_by(f64::total_cmp);
+                            let median = sorted[sorted.len() / 2];
+                            let detected = if best_power > Self::THRESHOLD * median
+                                && best_power.sqrt() * 2.0 > Self::MIN_AMPLITUDE
+                            {
+                                Some(CTCSS_FREQUENCIES[best])
+                            } else {
+                                None
+                            };
+                            if candidate == Some(detected) {
+                                tone_send.send_if_modified(|tone| {
+                                    let modified = *tone != detected;
+                                    *tone = detected;
+                                    modified
+                                });
+                            }
+                            candidate = Some(detected);
+                        }
+                    }
+                    Signal::Event(event) => {
+                        if event.is_interrupt() {
+                            for state in states.iter_mut() {
+                                state.reset();
+                            }
+                            block_pos = 0;
+                            candidate = None;
+                        }
+                    }
+                }
+            }
+        });
+        Self {
+            receiver_connector,
+            tone,
+        }
+    }
+    /// Get [`watch::Receiver`] of detected tone frequency in hertz
+    pub fn tone(&self) -> watch::Receiver<Option<f64>> {
+        self.tone.clone()
+    }
+}
+
+/// DTMF frequencies of the low (row) group in hertz
+pub const DTMF_LOW_FREQUENCIES: [f64; 4] = [697.0, 770.0, 852.0, 941.0];
+
+/// DTMF frequencies of the high (column) group in hertz
+pub const DTMF_HIGH_FREQUENCIES: [f64; 4] = [1209.0, 1336.0, 1477.0, 1633.0];
+
+/// DTMF keys, indexed by low (row) and high (column) frequency
+pub const DTMF_KEYS: [[char; 4]; 4] = [
+    ['1', '2', '3', 'A'],
+    ['4', '5', '6', 'B'],
+    ['7', '8', '9', 'C'],
+    ['*', '0', '#', 'D'],
+];
+
+/// DTMF decoder block
+///
+/// The real part of the received samples is analyzed in blocks of 15
+/// milliseconds, using the Goertzel algorithm for each of the
+/// [`DTMF_LOW_FREQUENCIES`] and [`DTMF_HIGH_FREQUENCIES`]. A block contains a
+/// valid tone pair if
+///
+/// * the strongest tone of each group exceeds the other tones of its group
+///   by 6 dB,
+/// * the two tones carry most of the energy of the block (which rejects
+///   noise and speech), and
+/// * the twist (level of the high tone relative to the low tone) lies
+///   between −8 dB and +4 dB.
+///
+/// A digit is accepted when the same tone pair has been detected in two
+/// consecutive blocks. Before the next digit is accepted, no tone pair must
+/// be detected for two consecutive blocks.
+///
+/// Accepted digits are sent through a [`broadcast`] channel (see
+/// [`DtmfDecoder::subscribe`]) and published as last digit (see
+/// [`DtmfDecoder::last_digit`]).
+pub struct DtmfDecoder<Flt> {
+    receiver_connector: ReceiverConnector<Signal<Complex<Flt>>>,
+    digits: broadcast::Sender<char>,
+    last_digit: watch::Receiver<Option<char>>,
+}
+
+impl_block_trait! { <Flt> Consumer<Signal<Complex<Flt>>> for DtmfDecoder<Flt> }
+
+impl<Flt> DtmfDecoder<Flt>
+where
+    Flt: Float,
+{
+    /// Duration of analyzed blocks in seconds
+    const BLOCK_DURATION: f64 = 0.015;
+    /// Minimum ratio between power of strongest and other tones of a group
+    const PEAK_RATIO: f64 = 4.0;
+    /// Minimum fraction of the block's energy carried by the tone pair
+    const ENERGY_FRACTION: f64 = 0.6;
+    /// Minimum and maximum twist in decibels
+    const TWIST_DB: (f64, f64) = (-8.0, 4.0);
+    /// Create new DTMF decoder
+    pub fn new() -> Self {
+        let (mut receiver, receiver_connector) = new_receiver::<Signal<Complex<Flt>>>();
+        let (digits_send, _) = broadcast::channel::<char>(256);
+        let digits_send_clone = digits_send.clone();
+        let (last_digit_send, last_digit) = watch::channel::<Option<char>>(None);
+        spawn(async move {
+            let mut previous_sample_rate: Option<f64> = None;
+            let mut states: Vec<GoertzelState<Flt>> = Vec::new();
+            let mut block_len: usize = 0;
+            let mut block_pos: usize = 0;
+            let mut energy: f64 = 0.0;
+            let mut candidate: Option<char> = None;
+            let mut count: usize = 0;
+            let mut locked = false;
+            loop {
+                let Ok(signal) = receiver.recv().await else { return; };
+                match signal {
+                    Signal::Samples {
+                        sample_rate,
+                        chunk: input_chunk,
+                    } => {
+                        if previous_sample_rate != Some(sample_rate) {
+                            previous_sample_rate = Some(sample_rate);
+                            states = DTMF_LOW_FREQUENCIES
+                                .iter()
+                                .chain(DTMF_HIGH_FREQUENCIES.iter())
+                                .map(|&frequency| GoertzelState::new(frequency, sample_rate))
+                                .collect();
+                            block_len =
+                                ((Self::BLOCK_DURATION * sample_rate).round() as usize).max(1);
+                            block_pos = 0;
+                            energy = 0.0;
+                        }
+                        for &sample in input_chunk.iter() {
+                            for state in states.iter_mut() {
+                                state.push(sample.re);
+                            }
+                            let value = sample.re.to_f64().unwrap();
+                            energy += value * value;
+                            block_pos += 1;
+                            if block_pos < block_len {
+                                continue;
+                            }
+                            block_pos = 0;
+                            // normalize such that a sinusoid's power equals
+                            // its energy within the block
+                            let powers: Vec<f64> = states
+                                .iter()
+                                .map(|state| {
+                                    state.power().to_f64().unwrap() * 2.0 / block_len as f64
+                                })
+                                .collect();
+                            for state in states.iter_mut() {
+                                state.reset();
+                            }
+                            let detected = Self::detect(&powers[0..4], &powers[4..8], energy);
+                            energy = 0.0;
+                            if detected == candidate {
+                                count += 1;
+                            } else {
+                                candidate = detected;
+                                count = 1;
+                            }
+                            match candidate {
+                                Some(digit) if count == 2 && !locked => {
+                                    locked = true;
+                                    let _ = digits_send_clone.send(digit);
+                                    last_digit_send.send_replace(Some(digit));
+                                }
+                                None if count >= 2 => locked = false,
+                                _ => (),
+                            }
+                        }
+                    }
+                    Signal::Event(event) => {
+                        if event.is_interrupt() {
+                            for state in states.iter_mut() {
+                                state.reset();
+                            }
+                            block_pos = 0;
+                            energy = 0.0;
+                            candidate = None;
+                            count = 0;
+                        }
+                    }
+                }
+            }
+        });
+        Self {
+            receiver_connector,
+            digits: digits_send,
+            last_digit,
+        }
+    }
+    /// Index of strongest tone if it exceeds the other tones sufficiently
+    fn strongest(powers: &[f64]) -> Option<usize> {
+        let (best, &best_power) = powers
+            .iter()
+            .enumerate()
+            .max_by(|a, b| a.1.total_cmp(b.1))
+            .unwrap();
+        powers
+            .iter()
+            .enumerate()
+            .all(|(idx, &power)| idx == best || power * Self::PEAK_RATIO < best_power)
+            .then_some(best)
+    }
+    /// Validate tone pair of a block with given total `energy`
+    fn detect(low_powers: &[f64], high_powers: &[f64], energy: f64) -> Option<char> {
+        let row = Self::strongest(low_powers)?;
+        let column = Self::strongest(high_powers)?;
+        let (low_power, high_power) = (low_powers[row], high_powers[column]);
+        if low_power + high_power <= Self::ENERGY_FRACTION * energy {
+            return None;
+        }
+        let twist_db = 10.0 * (high_power / low_power).log10();
+        if twist_db < Self::TWIST_DB.0 || twist_db > Self::TWIST_DB.1 {
+            return None;
+        }
+        Some(DTMF_KEYS[row][column])
+    }
+    /// Subscribe to decoded digits
+    pub fn subscribe(&self) -> broadcast::Receiver<char> {
+        self.digits.subscribe()
+    }
+    /// Get [`watch::Receiver`] of the most recently decoded digit
+    pub fn last_digit(&self) -> watch::Receiver<Option<char>> {
+        self.last_digit.clone()
+    }
+}
+
 #[cfg(test)]
-mod tests {}
+mod tests {
+    use super::*;
+    use crate::tests::assert_approx;
+    #[tokio::test]
+    async fn test_ssb_demod() {
+        use std::f64::consts::TAU;
+        let (sender, sender_connector) = new_sender::<Signal<Complex<f64>>>();
+        let demod = SsbDemod::<f64>::with_bfo_offset(Sideband::Usb, 127, 500.0);
+        let (mut receiver, receiver_connector) = new_receiver::<Signal<Complex<f64>>>();
+        demod.feed_from(&sender_connector);
+        demod.feed_into(&receiver_connector);
+        // carrier at 500 Hz, tone at 500 Hz + 2000 Hz
+        let tone = |i: usize| Complex::from_polar(1.0, TAU * 2500.0 * i as f64 / 48000.0);
+        let mut rms = Vec::new();
+        for mode in [Sideband::Usb, Sideband::Lsb] {
+            demod.set_mode(mode);
+            sender
+                .send(Signal::Samples {
+                    sample_rate: 48000.0,
+                    chunk: Chunk::from((0..4800).map(tone).collect::<Vec<_>>()),
+                })
+                .await
+                .unwrap();
+            let Signal::Samples { chunk, .. } = receiver.recv().await.unwrap()
+            else { panic!(); };
+            let tail = &chunk[200..];
+            assert!(tail.iter().all(|x| x.im == 0.0));
+            rms.push((tail.iter().map(|x| x.re * x.re).sum::<f64>() / tail.len() as f64).sqrt());
+        }
+        assert!((rms[0] - 0.5f64.sqrt()).abs() < 0.01);
+        assert!(rms[1] < 0.01);
+    }
+    #[tokio::test]
+    async fn test_fm_demod_across_chunks() {
+        use std::f64::consts::TAU;
+        let (sender, sender_connector) = new_sender::<Signal<Complex<f64>>>();
+        let demod = FmDemod::<f64>::new(5000.0);
+        let (mut receiver, receiver_connector) = new_receiver::<Signal<Complex<f64>>>();
+        demod.feed_from(&sender_connector);
+        demod.feed_into(&receiver_connector);
+        let tone = |i: usize| Complex::from_polar(1.0, TAU * 1000.0 * i as f64 / 48000.0);
+        for range in [0..16, 16..32] {
+            sender
+                .send(Signal::Samples {
+                    sample_rate: 48000.0,
+                    chunk: Chunk::from(range.map(tone).collect::<Vec<_>>()),
+                })
+                .await
+                .unwrap();
+        }
+        let Signal::Samples { chunk, .. } = receiver.recv().await.unwrap()
+        else { panic!(); };
+        for sample in chunk[1..].iter() {
+            assert_approx(sample.re, 0.2);
+        }
+        let Signal::Samples { chunk, .. } = receiver.recv().await.unwrap()
+        else { panic!(); };
+        for sample in chunk.iter() {
+            assert_approx(sample.re, 0.2);
+        }
+    }
+    #[tokio::test]
+    async fn test_am_demod() {
+        let (sender, sender_connector) = new_sender::<Signal<Complex<f32>>>();
+        let demod = AmDemod::<f32>::new();
+        let (mut receiver, receiver_connector) = new_receiver::<Signal<Complex<f32>>>();
+        demod.feed_from(&sender_connector);
+        demod.feed_into(&receiver_connector);
+        sender
+            .send(Signal::Samples {
+                sample_rate: 48000.0,
+                chunk: Chunk::from(vec![Complex::new(3.0, 4.0), Complex::new(0.0, -1.0)]),
+            })
+            .await
+            .unwrap();
+        let Signal::Samples { chunk, .. } = receiver.recv().await.unwrap()
+        else { panic!(); };
+        assert_eq!(chunk[0], Complex::new(5.0, 0.0));
+        assert_eq!(chunk[1], Complex::new(1.0, 0.0));
+    }
+    async fn run_stereo_decoder(pilot: f64) -> (bool, f64, f64) {
+        use std::f64::consts::TAU;
+        let (sender, sender_connector) = new_sender::<Signal<Complex<f64>>>();
+        let decoder = FmStereoDecoder::<f64>::new(50e-6);
+        let (mut left_receiver, left_connector) = new_receiver::<Signal<Complex<f64>>>();
+        let (mut right_receiver, right_connector) = new_receiver::<Signal<Complex<f64>>>();
+        decoder.feed_from(&sender_connector);
+        decoder.left().feed_into(&left_connector);
+        decoder.right().feed_into(&right_connector);
+        let sample_rate = 192000.0;
+        let mpx = |i: usize| {
+            let t = i as f64 / sample_rate;
+            let left = 0.4 * (TAU * 1000.0 * t).sin();
+            left / 2.0
+                + left / 2.0 * (2.0 * TAU * 19000.0 * t).cos()
+                + pilot * (TAU * 19000.0 * t).cos()
+        };
+        let (mut left_energy, mut right_energy) = (0.0, 0.0);
+        for n in 0..30 {
+            let chunk: Vec<_> = (n * 1920..(n + 1) * 1920)
+                .map(|i| Complex::from(mpx(i)))
+                .collect();
+            sender
+                .send(Signal::Samples {
+                    sample_rate,
+                    chunk: Chunk::from(chunk),
+                })
+                .await
+                .unwrap();
+            let Signal::Samples { chunk: left, .. } = left_receiver.recv().await.unwrap()
+            else { panic!(); };
+            let Signal::Samples { chunk: right, .. } = right_receiver.recv().await.unwrap()
+            else { panic!(); };
+            if n >= 20 {
+                left_energy += left.iter().map(|x| x.re * x.re).sum::<f64>();
+                right_energy += right.iter().map(|x| x.re * x.re).sum::<f64>();
+            }
+        }
+        let locked = *decoder.pilot_lock().borrow();
+        (locked, left_energy, right_energy)
+    }
+    #[tokio::test]
+    async fn test_fm_stereo_decoder() {
+        let (locked, left_energy, right_energy) = run_stereo_decoder(0.1).await;
+        assert!(locked);
+        assert!(right_energy < left_energy * 0.01);
+        let (locked, left_energy, right_energy) = run_stereo_decoder(0.0).await;
+        assert!(!locked);
+        assert_eq!(left_energy, right_energy);
+    }
+    #[test]
+    fn test_rds_check() {
+        for info in [0x0000, 0x1234, 0xFFFF, 0xD4A1] {
+            for (offset, position) in [
+                (RDS_OFFSET_A, 0),
+                (RDS_OFFSET_B, 1),
+                (RDS_OFFSET_C, 2),
+                (RDS_OFFSET_C_PRIME, 2),
+                (RDS_OFFSET_D, 3),
+            ] {
+                let block = (info as u32) << 10 | (rds_check(info) ^ offset) as u32;
+                assert_eq!(rds_block_position(block), Some(position));
+                assert_eq!(rds_block_position(block ^ 0x100), None);
+            }
+        }
+    }
+    #[tokio::test]
+    async fn test_rds_decoder() {
+        use std::f64::consts::TAU;
+        let mut bits: Vec<bool> = Vec::new();
+        for _ in 0..3 {
+            for (segment, chars) in b"RADIORST".chunks(2).enumerate() {
+                let words = [
+                    0xD4A1,
+                    segment as u16,
+                    0xD4A1,
+                    u16::from_be_bytes([chars[0], chars[1]]),
+                ];
+                let offsets = [RDS_OFFSET_A, RDS_OFFSET_B, RDS_OFFSET_C, RDS_OFFSET_D];
+                for (word, offset) in words.into_iter().zip(offsets) {
+                    let block = (word as u32) << 10 | (rds_check(word) ^ offset) as u32;
+                    for i in (0..26).rev() {
+                        bits.push(block & (1 << i) != 0);
+                    }
+                }
+            }
+        }
+        let mut symbols: Vec<bool> = Vec::with_capacity(bits.len());
+        let mut previous = false;
+        for bit in bits {
+            previous ^= bit;
+            symbols.push(previous);
+        }
+        let sample_rate = 192000.0;
+        let sample_count = (symbols.len() as f64 / RDS_BIT_RATE * sample_rate) as usize;
+        let mpx: Vec<Complex<f64>> = (0..sample_count)
+            .map(|i| {
+                let t = i as f64 / sample_rate;
+                let bit_pos = t * RDS_BIT_RATE;
+                let symbol = match symbols[bit_pos as usize] {
+                    true => 1.0,
+                    false => -1.0,
+                };
+                let half = match bit_pos.fract() < 0.5 {
+                    true => 1.0,
+                    false => -1.0,
+                };
+                Complex::from(
+                    0.3 * (TAU * 1000.0 * t).sin()
+                        + 0.1 * (TAU * 19000.0 * t).cos()
+                        + 0.05 * symbol * half * (TAU * RDS_SUBCARRIER * t).cos(),
+                )
+            })
+            .collect();
+        let (sender, sender_connector) = new_sender::<Signal<Complex<f64>>>();
+        let decoder = RdsDecoder::<f64>::new();
+        decoder.feed_from(&sender_connector);
+        let mut program_service = decoder.program_service();
+        for chunk in mpx.chunks(4096) {
+            sender
+                .send(Signal::Samples {
+                    sample_rate,
+                    chunk: Chunk::from(chunk.to_vec()),
+                })
+                .await
+                .unwrap();
+        }
+        sender
+            .send(Signal::Samples {
+                sample_rate,
+                chunk: Chunk::from(vec![Complex::from(0.0); 16]),
+            })
+            .await
+            .unwrap();
+        program_service
+            .wait_for(|ps| ps == "RADIORST")
+            .await
+            .unwrap();
+    }
+    #[tokio::test]
+    async fn test_rtty_decoder() {
+        use std::f64::consts::TAU;
+        let sample_rate = 8000.0;
+        for inverted in [false, true] {
+            let decoder = RttyDecoder::<f32>::new(1000.0);
+            decoder.set_inverted(inverted);
+            let (sender, sender_connector) = new_sender::<Signal<Complex<f32>>>();
+            decoder.feed_from(&sender_connector);
+            let mut text_receiver = decoder.subscribe();
+            let mut codes: Vec<u8> = vec![BAUDOT_LTRS];
+            let mut figures = false;
+            for c in "RYRY CQ DE TEST 73 K".chars() {
+                let letter = BAUDOT_LETTERS.iter().position(|&x| x == c);
+                let figure = BAUDOT_FIGURES.iter().position(|&x| x == c);
+                match (letter, figure) {
+                    (Some(code), _) if !figures || c == ' ' => codes.push(code as u8),
+                    (_, Some(code)) if figures => codes.push(code as u8),
+                    (Some(code), _) => {
+                        codes.extend([BAUDOT_LTRS, code as u8]);
+                        figures = false;
+                    }
+                    (_, Some(code)) => {
+                        codes.extend([BAUDOT_FIGS, code as u8]);
+                        figures = true;
+                    }
+                    _ => panic!(),
+                }
+                if c == ' ' {
+                    figures = false;
+                }
+            }
+            // idle mark, characters with start bit, five data bits, and
+            // 1.5 stop bits, then idle mark again
+            let mut levels: Vec<(bool, f64)> = vec![(true, 10.0)];
+            for code in codes {
+                levels.push((false, 1.0));
+                levels.extend((0..5).map(|i| (code & (1 << i) != 0, 1.0)));
+                levels.push((true, 1.5));
+            }
+            levels.push((true, 10.0));
+            let bit_len = sample_rate / 45.45;
+            let mut phase: f64 = 0.0;
+            let mut samples: Vec<Complex<f32>> = Vec::new();
+            for (mark, bits) in levels {
+                let frequency = match mark != inverted {
+                    true => 915.0,
+                    false => 1085.0,
+                };
+                for _ in 0..(bits * bit_len).round() as usize {
+                    phase = (phase + TAU * frequency / sample_rate) % TAU;
+                    samples.push(Complex::new(0.5 * phase.sin() as f32, 0.0));
+                }
+            }
+            for chunk in samples.chunks(1024) {
+                sender
+                    .send(Signal::Samples {
+                        sample_rate,
+                        chunk: Chunk::from(chunk.to_vec()),
+                    })
+                    .await
+                    .unwrap();
+            }
+            let mut text = String::new();
+            while !text.ends_with('K') {
+                text.push_str(&text_receiver.recv().await.unwrap());
+            }
+            assert_eq!(text, "RYRY CQ DE TEST 73 K");
+        }
+    }
+    #[tokio::test]
+    async fn test_fsk2_slicer() {
+        let sample_rate = 44100.0;
+        let baud_rate = 1200.0;
+        let (sender, sender_connector) = new_sender::<Signal<Complex<f64>>>();
+        let slicer = Fsk2Slicer::<f64>::new(baud_rate);
+        let (mut receiver, receiver_connector) = new_receiver::<Signal<bool>>();
+        slicer.feed_from(&sender_connector);
+        slicer.feed_into(&receiver_connector);
+        let mut rng = 1u32;
+        let bits: Vec<bool> = (0..2000)
+            .map(|_| {
+                rng = rng.wrapping_mul(1664525).wrapping_add(1013904223);
+                rng & 0x10000 != 0
+            })
+            .collect();
+        // NRZ with slightly faster clock, smoothed over one symbol
+        let samples_per_bit = sample_rate / (baud_rate * 1.002);
+        let nrz: Vec<f64> = (0..(bits.len() as f64 * samples_per_bit) as usize)
+            .map(|i| match bits[(i as f64 / samples_per_bit) as usize] {
+                true => 1.3,
+                false => -0.7,
+            })
+            .collect();
+        let window = samples_per_bit as usize;
+        let samples: Vec<Complex<f64>> = (0..nrz.len() - window)
+            .map(|i| Complex::from(nrz[i..i + window].iter().sum::<f64>() / window as f64))
+            .collect();
+        let join_handle = tokio::spawn(async move {
+            for chunk in samples.chunks(4096) {
+                sender
+                    .send(Signal::Samples {
+                        sample_rate,
+                        chunk: Chunk::from(chunk.to_vec()),
+                    })
+                    .await
+                    .unwrap();
+            }
+        });
+        let mut output = Vec::new();
+        while output.len() < 1900 {
+            let Signal::Samples { sample_rate, chunk } = receiver.recv().await.unwrap()
+            else { panic!(); };
+            assert_eq!(sample_rate, baud_rate);
+            output.extend_from_slice(&chunk);
+        }
+        join_handle.await.unwrap();
+        // compare last 1000 bits (allowing for the delay of the smoothing)
+        let tail = &output[800..1800];
+        assert!((0..20).any(|delay| tail == &bits[800 + delay..1800 + delay]));
+        assert!(*slicer.quality().borrow() > 0.9);
+    }
+    #[tokio::test]
+    async fn test_fsk2_slicer_soft() {
+        use std::f64::consts::PI;
+        let sample_rate = 48000.0;
+        let baud_rate = 1200.0;
+        let (sender, sender_connector) = new_sender::<Signal<Complex<f64>>>();
+        let slicer = Fsk2Slicer::<f64, f32>::new_soft(baud_rate);
+        let (mut receiver, receiver_connector) = new_receiver::<Signal<f32>>();
+        slicer.feed_from(&sender_connector);
+        slicer.feed_into(&receiver_connector);
+        let mut rng = 1u32;
+        let mut random = move || {
+            rng = rng.wrapping_mul(1664525).wrapping_add(1013904223);
+            rng as f64 / u32::MAX as f64
+        };
+        let bits: Vec<bool> = (0..2000).map(|_| random() < 0.5).collect();
+        // raised cosine transitions between symbols, plus noise with a
+        // standard deviation of 0.4
+        let samples: Vec<Complex<f64>> = (0..bits.len() * 40 - 40)
+            .map(|i| {
+                let (a, b) = (bits[i / 40] as i32 as f64, bits[i / 40 + 1] as i32 as f64);
+                let t = (i % 40) as f64 / 40.0;
+                let value = 2.0 * (a + (b - a) * (1.0 - (PI * t).cos()) / 2.0) - 1.0;
+                let noise = ((0..12).map(|_| random()).sum::<f64>() - 6.0) * 0.4;
+                Complex::from(value + noise)
+            })
+            .collect();
+        let join_handle = tokio::spawn(async move {
+            for chunk in samples.chunks(4096) {
+                sender
+                    .send(Signal::Samples {
+                        sample_rate,
+                        chunk: Chunk::from(chunk.to_vec()),
+                    })
+                    .await
+                    .unwrap();
+            }
+        });
+        let mut output: Vec<f32> = Vec::new();
+        while output.len() < 1900 {
+            let Signal::Samples { chunk, .. } = receiver.recv().await.unwrap()
+            else { panic!(); };
+            output.extend_from_slice(&chunk);
+        }
+        join_handle.await.unwrap();
+        let tail = &output[800..1800];
+        let delay = (0..40)
+            .find(|&delay| {
+                let matching = tail
+                    .iter()
+                    .zip(&bits[800 + delay..1800 + delay])
+                    .filter(|&(&llr, &bit)| (llr > 0.0) == bit)
+                    .count();
+                matching > 990
+            })
+            .unwrap();
+        // expected LLR magnitude is about 2/0.4^2 for the symbol values
+        let mean_llr = tail.iter().map(|llr| llr.abs()).sum::<f32>() / 1000.0;
+        assert!((10.0..25.0).contains(&mean_llr));
+        let max_wrong_llr = tail
+            .iter()
+            .zip(&bits[800 + delay..1800 + delay])
+            .filter(|&(&llr, &bit)| (llr > 0.0) != bit)
+            .map(|(llr, _)| llr.abs())
+            .fold(0.0f32, f32::max);
+        // wrong decisions have low confidence
+        assert!(max_wrong_llr < mean_llr);
+    }
+    /// HDLC framing and NRZI encoding of AX.25 frame (as levels)
+    fn ax25_levels(frame: &Ax25Frame, flags: usize) -> Vec<bool> {
+        let mut bytes = frame.to_bytes();
+        bytes.extend_from_slice(&hdlc_fcs(&bytes).to_le_bytes());
+        let flag_bits = (0..8).map(|i| 0x7E >> i & 1 != 0);
+        let mut bits: Vec<bool> = flag_bits.clone().cycle().take(8 * flags).collect();
+        let mut ones = 0;
+        for bit in bytes
+            .iter()
+            .flat_map(|&byte| (0..8).map(move |i| byte >> i & 1 != 0))
+        {
+            bits.push(bit);
+            ones = if bit { ones + 1 } else { 0 };
+            if ones == 5 {
+                bits.push(false);
+                ones = 0;
+            }
+        }
+        bits.extend(flag_bits.cycle().take(16));
+        let mut level = false;
+        bits.iter()
+            .map(|&bit| {
+                level ^= !bit;
+                level
+            })
+            .collect()
+    }
+    fn ax25_test_frame() -> Ax25Frame {
+        let mut repeated = Ax25Address::new("WIDE1", 1);
+        repeated.flag = true;
+        Ax25Frame::ui(
+            Ax25Address::new("APRS", 0),
+            Ax25Address::new("N0CALL", 7),
+            vec![repeated, Ax25Address::new("WIDE2", 1)],
+            b"!4903.50N/07201.75W-Test ~~~~~ \xff".to_vec(),
+        )
+    }
+    #[test]
+    fn test_ax25_frame() {
+        assert_eq!(hdlc_fcs(b"123456789"), 0x906E);
+        let frame = ax25_test_frame();
+        let bytes = frame.to_bytes();
+        assert_eq!(&bytes[0..7], &[0x82, 0xA0, 0xA4, 0xA6, 0x40, 0x40, 0x60]);
+        assert_eq!(bytes[27], 0x63);
+        assert_eq!(Ax25Frame::parse(&bytes), Some(frame.clone()));
+        assert_eq!(
+            frame.to_string(),
+            "N0CALL-7>APRS,WIDE1-1*,WIDE2-1:!4903.50N/07201.75W-Test ~~~~~ \u{fffd}"
+        );
+        assert_eq!(Ax25Frame::parse(&bytes[0..10]), None);
+    }
+    #[tokio::test]
+    async fn test_ax25_decoder() {
+        let (sender, sender_connector) = new_sender::<Signal<bool>>();
+        let decoder = Ax25Decoder::new();
+        let (mut receiver, receiver_connector) = new_receiver::<Ax25Frame>();
+        decoder.feed_from(&sender_connector);
+        decoder.feed_into(&receiver_connector);
+        let frame = ax25_test_frame();
+        let mut levels = ax25_levels(&frame, 4);
+        // corrupted frame is discarded
+        let mut corrupted = levels.clone();
+        corrupted[100] = !corrupted[100];
+        levels.splice(0..0, corrupted.iter().map(|&level| !level));
+        let send = async {
+            // empty chunk at the end ensures that all levels have been
+            // processed
+            for chunk in levels.chunks(50).chain([&[][..]]) {
+                sender
+                    .send(Signal::Samples {
+                        sample_rate: 1200.0,
+                        chunk: Chunk::from(chunk.to_vec()),
+                    })
+                    .await
+                    .unwrap();
+            }
+        };
+        let ((), received) = tokio::join!(send, receiver.recv());
+        assert_eq!(received.unwrap(), frame);
+        assert_eq!(decoder.sender_connector().observer().sent_count(), 1);
+    }
+    #[tokio::test]
+    async fn test_afsk_ax25() {
+        use std::f64::consts::TAU;
+        let sample_rate = 48000.0;
+        let (sender, sender_connector) = new_sender::<Signal<Complex<f32>>>();
+        let demod = AfskDemod::<f32>::bell202();
+        let slicer = Fsk2Slicer::<f32>::new(1200.0);
+        let decoder = Ax25Decoder::new();
+        let (mut receiver, receiver_connector) = new_receiver::<Ax25Frame>();
+        demod.feed_from(&sender_connector);
+        slicer.feed_from(&demod);
+        decoder.feed_from(&slicer);
+        decoder.feed_into(&receiver_connector);
+        let frame = ax25_test_frame();
+        let mut phase: f64 = 0.0;
+        let samples: Vec<Complex<f32>> = ax25_levels(&frame, 30)
+            .iter()
+            .flat_map(|&level| std::iter::repeat_n(level, 40))
+            .map(|level| {
+                phase += TAU * if level { 1200.0 } else { 2200.0 } / sample_rate;
+                Complex::from(0.5 * phase.sin() as f32)
+            })
+            .collect();
+        let join_handle = tokio::spawn(async move {
+            for chunk in samples.chunks(4800) {
+                sender
+                    .send(Signal::Samples {
+                        sample_rate,
+                        chunk: Chunk::from(chunk.to_vec()),
+                    })
+                    .await
+                    .unwrap();
+            }
+        });
+        assert_eq!(receiver.recv().await.unwrap(), frame);
+        join_handle.await.unwrap();
+    }
+    #[test]
+    fn test_mode_s_residual() {
+        let text = "8D4840D6202CC371C32CE0576098";
+        let data: Vec<u8> = (0..14)
+            .map(|i| u8::from_str_radix(&text[2 * i..2 * i + 2], 16).unwrap())
+            .collect();
+        assert_eq!(mode_s_residual(&data), 0);
+        let message = ModeSMessage {
+            data,
+            corrected_bits: 0,
+            signal_to_noise: 10.0,
+        };
+        assert_eq!(message.downlink_format(), 17);
+        assert_eq!(message.address(), Some(0x4840D6));
+        assert_eq!(message.type_code(), Some(4));
+    }
+    #[tokio::test]
+    async fn test_adsb_demod() {
+        let (sender, sender_connector) = new_sender::<Signal<Complex<f32>>>();
+        let demod = AdsbDemod::<f32>::new();
+        let (mut receiver, receiver_connector) = new_receiver::<ModeSMessage>();
+        demod.feed_from(&sender_connector);
+        demod.feed_into(&receiver_connector);
+        let hex = |text: &str| -> Vec<u8> {
+            (0..text.len() / 2)
+                .map(|i| u8::from_str_radix(&text[2 * i..2 * i + 2], 16).unwrap())
+                .collect()
+        };
+        let long = hex("8D4840D6202CC371C32CE0576098");
+        let mut corrupted = long.clone();
+        corrupted[6] ^= 0x10;
+        let mut bad = long.clone();
+        bad[6] ^= 0x30;
+        // all-call reply (DF11) with zero interrogator identifier
+        let mut short = hex("5D4840D6000000");
+        let residual = mode_s_residual(&short);
+        short[4..7].copy_from_slice(&residual.to_be_bytes()[1..4]);
+        let mut rng = 3u32;
+        let mut random = move || {
+            rng = rng.wrapping_mul(1664525).wrapping_add(1013904223);
+            rng as f32 / u32::MAX as f32 - 0.5
+        };
+        let mut samples: Vec<Complex<f32>> = Vec::new();
+        for data in [&long, &bad, &corrupted, &short] {
+            samples.extend((0..1000).map(|_| Complex::new(0.0, 0.0)));
+            let chips = [1, 0, 1, 0, 0, 0, 0, 1, 0, 1, 0, 0, 0, 0, 0, 0]
+                .into_iter()
+                .map(|chip| chip != 0)
+                .chain(
+                    data.iter()
+                        .flat_map(|&byte| (0..8).rev().map(move |i| byte >> i & 1 != 0))
+                        .flat_map(|bit| [bit, !bit]),
+                );
+            samples.extend(chips.map(|chip| match chip {
+                true => Complex::new(0.3, 0.4),
+                false => Complex::new(0.0, 0.0),
+            }));
+        }
+        samples.extend((0..1000).map(|_| Complex::new(0.0, 0.0)));
+        // noise with a magnitude of about 0.1
+        for sample in samples.iter_mut() {
+            *sample += Complex::new(random(), random()) * 0.25;
+        }
+        let send = async {
+            for chunk in samples.chunks(777).chain([&[][..]]) {
+                sender
+                    .send(Signal::Samples {
+                        sample_rate: 2e6,
+                        chunk: Chunk::from(chunk.to_vec()),
+                    })
+                    .await
+                    .unwrap();
+            }
+        };
+        let receive = async {
+            let mut messages = Vec::new();
+            for _ in 0..3 {
+                messages.push(receiver.recv().await.unwrap());
+            }
+            messages
+        };
+        let ((), messages) = tokio::join!(send, receive);
+        assert_eq!(messages[0].data, long);
+        assert_eq!(messages[0].corrected_bits, 0);
+        assert!(messages[0].signal_to_noise > 3.0);
+        assert_eq!(messages[1].data, long);
+        assert_eq!(messages[1].corrected_bits, 1);
+        assert_eq!(messages[2].data, short);
+        assert_eq!(messages[2].address(), Some(0x4840D6));
+        assert_eq!(demod.sender_connector().observer().sent_count(), 3);
+    }
+    #[test]
+    fn test_pocsag_correct() {
+        let data = 0x12345;
+        let codeword = (data << 10 | pocsag_bch_remainder(data << 10)) << 1;
+        let codeword = codeword | (codeword.count_ones() & 1);
+        assert!(pocsag_is_valid(codeword));
+        assert_eq!(pocsag_correct(codeword ^ 0x00100000), Some(codeword));
+        assert_eq!(pocsag_correct(codeword ^ 0x40000001), Some(codeword));
+        assert!(pocsag_correct(POCSAG_SYNC).is_some());
+    }
+    #[tokio::test]
+    async fn test_pocsag_decoder() {
+        fn codeword(data: u32) -> u32 {
+            let codeword = (data << 10 | pocsag_bch_remainder(data << 10)) << 1;
+            codeword | (codeword.count_ones() & 1)
+        }
+        fn address(capcode: u32, function: u32) -> u32 {
+            codeword((capcode >> 3) << 2 | function)
+        }
+        fn message(bits: &[bool]) -> Vec<u32> {
+            bits.chunks(20)
+                .map(|chunk| {
+                    let data = chunk
+                        .iter()
+                        .chain(std::iter::repeat(&false))
+                        .take(20)
+                        .fold(0, |acc, &bit| acc << 1 | bit as u32);
+                    codeword(1 << 20 | data)
+                })
+                .collect()
+        }
+        fn lsb_first(chars: &[u8], width: usize) -> Vec<bool> {
+            chars
+                .iter()
+                .flat_map(|&c| (0..width).map(move |i| c & (1 << i) != 0))
+                .collect()
+        }
+        let mut alpha = message(&lsb_first(b"HELLO WORLD\x04", 7));
+        alpha[0] ^= 0x00300000;
+        let numeric = message(&lsb_first(&[1, 2, 3, 4, 5, 0xC, 0xC, 0xC, 0xC, 0xC], 4));
+        let mut batch1 = vec![POCSAG_IDLE; 14];
+        batch1.push(address(1234567, 3) ^ 0x00000100);
+        batch1.push(alpha[0]);
+        let mut batch2 = alpha[1..].to_vec();
+        batch2.resize(6, POCSAG_IDLE);
+        batch2.push(address(1003, 0));
+        batch2.extend_from_slice(&numeric);
+        batch2.resize(16, POCSAG_IDLE);
+        let mut codewords = vec![0xAAAAAAAA; 18];
+        codewords.push(POCSAG_SYNC);
+        codewords.extend(batch1);
+        codewords.push(POCSAG_SYNC);
+        codewords.extend(batch2);
+        codewords.extend([0xAAAAAAAA; 2]);
+        for (bit_rate, polarity) in [(512, 1.0), (1200, -1.0), (2400, 1.0)] {
+            let sample_rate = 48000.0;
+            let (sender, sender_connector) = new_sender::<Signal<Complex<f32>>>();
+            let decoder = PocsagDecoder::<f32>::new();
+            let (mut receiver, receiver_connector) = new_receiver::<PocsagMessage>();
+            decoder.feed_from(&sender_connector);
+            decoder.feed_into(&receiver_connector);
+            let samples_per_bit = sample_rate / bit_rate as f64;
+            let bits: Vec<bool> = codewords
+                .iter()
+                .flat_map(|&word| (0..32).rev().map(move |i| word & (1 << i) != 0))
+                .collect();
+            let len = (bits.len() as f64 * samples_per_bit) as usize;
+            let samples: Vec<Complex<f32>> = (0..len)
+                .map(|i| {
+                    let bit = bits[(i as f64 / samples_per_bit) as usize];
+                    let level = if bit { -polarity } else { polarity };
+                    Complex::new((0.2 + level) as f32, 0.0)
+                })
+                .collect();
+            // first message is complete after the idle codeword following it
+            let split = (42.0 * 32.0 * samples_per_bit) as usize;
+            let (messages_send, mut messages_recv) = tokio::sync::mpsc::unbounded_channel();
+            tokio::spawn(async move {
+                while let Ok(message) = receiver.recv().await {
+                    messages_send.send(message).unwrap();
+                }
+            });
+            let mut messages = Vec::new();
+            for part in [&samples[..split], &samples[split..]] {
+                for chunk in part.chunks(1000) {
+                    sender
+                        .send(Signal::Samples {
+                            sample_rate,
+                            chunk: Chunk::from(chunk.to_vec()),
+                        })
+                        .await
+                        .unwrap();
+                }
+                messages.push(messages_recv.recv().await.unwrap());
+            }
+            assert!(messages.iter().all(|message| message.bit_rate == bit_rate));
+            assert_eq!(messages[0].capcode, 1234567);
+            assert_eq!(messages[0].function, 3);
+            assert_eq!(messages[0].bad_codewords, 0);
+            assert_eq!(messages[0].alphanumeric(), "HELLO WORLD");
+            assert_eq!(messages[1].capcode, 1003);
+            assert_eq!(messages[1].function, 0);
+            assert_eq!(messages[1].numeric(), "12345");
+        }
+    }
+    #[tokio::test]
+    async fn test_cw_decoder() {
+        use crate::blocks::morse::Keyer;
+        use crate::blocks::FreqShifter;
+        let keyer = Keyer::<f32>::with_message(
+            256,
+            8000.0,
+            Speed::from_paris_wpm(25.0),
+            "CQ TEST DE DL1ABC",
+        )
+        .unwrap();
+        let shifter = FreqShifter::<f32>::with_shift(700.0);
+        let decoder = CwDecoder::<f32>::new(700.0, Speed::from_paris_wpm(20.0));
+        let mut text_receiver = decoder.subscribe();
+        shifter.feed_from(&keyer);
+        decoder.feed_from(&shifter);
+        let mut text = String::new();
+        while !text.ends_with("DL1ABC") {
+            text.push_str(&text_receiver.recv().await.unwrap());
+        }
+        assert_eq!(text, "CQ TEST DE DL1ABC");
+        assert!((decoder.wpm() - 25.0).abs() < 2.5);
+    }
+    #[tokio::test]
+    async fn test_cw_encoder() {
+        let encoder = CwEncoder::<f32>::new(256, 8000.0, 700.0, Speed::from_paris_wpm(25.0));
+        let decoder = CwDecoder::<f32>::new(700.0, Speed::from_paris_wpm(20.0));
+        let mut text_receiver = decoder.subscribe();
+        decoder.feed_from(&encoder);
+        encoder.send("CQ DE DL1ABC").unwrap();
+        let mut text = String::new();
+        while !text.ends_with("DL1ABC") {
+            text.push_str(&text_receiver.recv().await.unwrap());
+        }
+        assert_eq!(text, "CQ DE DL1ABC");
+        assert!((decoder.wpm() - 25.0).abs() < 2.5);
+    }
+    #[tokio::test]
+    async fn test_cw_encoder_timing() {
+        let encoder = CwEncoder::<f64>::new(10, 1000.0, 137.0, Speed::from_paris_wpm(20.0));
+        let (mut receiver, receiver_connector) = new_receiver::<Signal<Complex<f64>>>();
+        encoder.feed_into(&receiver_connector);
+        for (farnsworth_speed, expected_len) in [(None, 720), (Some(10.0), 2300)] {
+            encoder.set_farnsworth_speed(farnsworth_speed.map(Speed::from_paris_wpm));
+            encoder.send("EE").unwrap();
+            let mut samples: Vec<Complex<f64>> = Vec::new();
+            let mut started = false;
+            loop {
+                match receiver.recv().await.unwrap() {
+                    Signal::Samples { chunk, .. } if started => samples.extend_from_slice(&chunk),
+                    Signal::Samples { .. } => (),
+                    Signal::Event(event) => {
+                        if event.as_any().is::<EndOfMessages>() {
+                            break;
+                        }
+                        started |= event.as_any().is::<StartOfMessages>();
+                    }
+                }
+            }
+            assert!(samples.len().abs_diff(expected_len) < 10);
+            let on_len = samples.iter().filter(|x| x.re.abs() > 1e-9).count();
+            assert!(on_len.abs_diff(120) < 15);
+            assert!(samples[0].re.abs() < 1e-9 && samples[samples.len() - 1].re.abs() < 1e-9);
+            let peak = samples.iter().map(|x| x.re.abs()).fold(0.0, f64::max);
+            assert!((peak - 1.0).abs() < 0.01);
+        }
+    }
+    #[tokio::test]
+    async fn test_ctcss() {
+        let (sender, sender_connector) = new_sender::<Signal<Complex<f32>>>();
+        let encoder = CtcssEncoder::<f32>::new(Some(151.4), 0.1);
+        let decoder = CtcssDecoder::<f32>::new();
+        encoder.feed_from(&sender_connector);
+        decoder.feed_from(&encoder);
+        let mut tone = decoder.tone();
+        let join_handle = tokio::spawn(async move {
+            for i in 0..8000 {
+                let samples: Vec<Complex<f32>> = (0..8)
+                    .map(|j| Complex::new(0.5 * ((i * 8 + j) as f32 * 0.7).sin(), 0.0))
+                    .collect();
+                sender
+                    .send(Signal::Samples {
+                        sample_rate: 8000.0,
+                        chunk: Chunk::from(samples),
+                    })
+                    .await
+                    .unwrap();
+            }
+        });
+        tone.changed().await.unwrap();
+        assert_eq!(*tone.borrow_and_update(), Some(151.4));
+        encoder.set_frequency(None);
+        tone.changed().await.unwrap();
+        assert_eq!(*tone.borrow_and_update(), None);
+        join_handle.await.unwrap();
+    }
+    #[tokio::test]
+    async fn test_dtmf_decoder() {
+        use std::f64::consts::TAU;
+        let (sender, sender_connector) = new_sender::<Signal<Complex<f32>>>();
+        let decoder = DtmfDecoder::<f32>::new();
+        decoder.feed_from(&sender_connector);
+        let mut digits = decoder.subscribe();
+        let mut samples: Vec<Complex<f32>> = Vec::new();
+        // valid tone pairs, a repeated digit, a single tone, and strong twist
+        for (low, high, low_amplitude, high_amplitude) in [
+            (697.0, 1209.0, 0.3, 0.3),
+            (770.0, 1336.0, 0.3, 0.4),
+            (770.0, 1336.0, 0.3, 0.4),
+            (941.0, 1477.0, 0.4, 0.3),
+            (852.0, 0.0, 0.5, 0.0),
+            (852.0, 1633.0, 0.5, 0.1),
+            (941.0, 1633.0, 0.3, 0.3),
+        ] {
+            let start = samples.len();
+            samples.extend((0..480).map(|i| {
+                let t = (start + i) as f64 / 8000.0;
+                let x =
+                    low_amplitude * (TAU * low * t).sin() + high_amplitude * (TAU * high * t).sin();
+                Complex::new(x as f32, 0.0)
+            }));
+            samples.extend((0..480).map(|_| Complex::new(0.0, 0.0)));
+        }
+        let mut noise = 12345u32;
+        for sample in samples.iter_mut() {
+            noise = noise.wrapping_mul(1664525).wrapping_add(1013904223);
+            sample.re += (noise as f32 / u32::MAX as f32 - 0.5) * 0.01;
+        }
+        sender
+            .send(Signal::Samples {
+                sample_rate: 8000.0,
+                chunk: Chunk::from(samples),
+            })
+            .await
+            .unwrap();
+        let mut text = String::new();
+        while !text.ends_with('D') {
+            text.push(digits.recv().await.unwrap());
+        }
+        assert_eq!(text, "155#D");
+        assert_eq!(*decoder.last_digit().borrow(), Some('D'));
+    }
+    #[tokio::test]
+    async fn test_symbol_sync() {
+        use crate::math::sinc;
+        let rolloff = 0.35;
+        let raised_cosine = |t: f64| -> f64 {
+            if (2.0 * rolloff * t).abs() == 1.0 {
+                std::f64::consts::FRAC_PI_4 * sinc(0.5 / rolloff)
+            } else {
+                sinc(t) * (std::f64::consts::PI * rolloff * t).cos()
+                    / (1.0 - (2.0 * rolloff * t).powi(2))
+            }
+        };
+        let symbols: Vec<Complex<f64>> = (0..2000)
+            .map(|i| Complex::new([1.0, -1.0][i * 7 % 11 % 2], [1.0, -1.0][i * 5 % 13 % 2]))
+            .collect();
+        // 4.1 samples per symbol with initial timing offset, symbol rate
+        // slightly higher than nominal
+        let samples_per_symbol = 4.1;
+        let samples: Vec<Complex<f64>> = (0..8000)
+            .map(|n| {
+                let t = n as f64 / samples_per_symbol * 1.002 + 0.37;
+                let k = t.round() as isize;
+                let mut sum = Complex::from(0.0);
+                for k in (k - 12).max(0)..(k + 12).min(symbols.len() as isize) {
+                    sum += symbols[k as usize] * raised_cosine(t - k as f64);
+                }
+                sum
+            })
+            .collect();
+        let (sender, sender_connector) = new_sender::<Signal<Complex<f64>>>();
+        let sync = SymbolSync::<f64>::new(samples_per_symbol, 200.0);
+        let (mut receiver, receiver_connector) = new_receiver::<Signal<Complex<f64>>>();
+        sync.feed_from(&sender_connector);
+        sync.feed_into(&receiver_connector);
+        let join_handle = tokio::spawn(async move {
+            for chunk in samples.chunks(1000) {
+                sender
+                    .send(Signal::Samples {
+                        sample_rate: 41000.0,
+                        chunk: Chunk::from(chunk.to_vec()),
+                    })
+                    .await
+                    .unwrap();
+            }
+        });
+        let mut output: Vec<Complex<f64>> = Vec::new();
+        for _ in 0..8 {
+            let Signal::Samples { sample_rate, chunk } = receiver.recv().await.unwrap()
+            else { panic!(); };
+            assert_eq!(sample_rate, 10000.0);
+            output.extend_from_slice(&chunk);
+        }
+        join_handle.await.unwrap();
+        assert!(output.len() > 1900);
+        for symbol in output[1000..1900].iter() {
+            assert!((symbol.re.abs() - 1.0).abs() < 0.1);
+            assert!((symbol.im.abs() - 1.0).abs() < 0.1);
+        }
+        assert!(*sync.timing_error().borrow() < 0.05);
+    }
+    #[tokio::test]
+    async fn test_symbol_sync_short_chunks() {
+        let (sender, sender_connector) = new_sender::<Signal<Complex<f64>>>();
+        let sync = SymbolSync::<f64>::new(4.0, 200.0);
+        let (mut receiver, receiver_connector) = new_receiver::<Signal<Complex<f64>>>();
+        sync.feed_from(&sender_connector);
+        sync.feed_into(&receiver_connector);
+        let samples: Vec<Complex<f64>> = (0..700)
+            .map(|n| Complex::from([1.0, -1.0][n / 4 % 2]))
+            .collect();
+        let join_handle = tokio::spawn(async move {
+            for chunk in samples.chunks(7) {
+                sender
+                    .send(Signal::Samples {
+                        sample_rate: 40000.0,
+                        chunk: Chunk::from(chunk.to_vec()),
+                    })
+                    .await
+                    .unwrap();
+            }
+        });
+        let mut count = 0;
+        for _ in 0..100 {
+            let Signal::Samples { sample_rate, chunk } = receiver.recv().await.unwrap()
+            else { panic!(); };
+            assert_eq!(sample_rate, 10000.0);
+            count += chunk.len();
+        }
+        join_handle.await.unwrap();
+        assert!(count > 160);
+    }
+}
@@ -273,6 +273,25 @@ pub fn encode(text: &str) -> Result<Vec<Unit>, EncodeError> {
     Ok(output)
 }
 
+/// Characters supported by [`encode`] and [`decode`]
+const CHARACTERS: &str = "0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZ/+=-.,?():;&'!_$@\"";
+
+/// Decodes a sequence of [`Dit`]s and [`Dah`]s into a character
+///
+/// Other [`Unit`]s are ignored. Returns `None` if the sequence doesn't match
+/// any supported character.
+pub fn decode(units: &[Unit]) -> Option<char> {
+    let symbols = units.iter().copied().filter(|unit| unit.on());
+    CHARACTERS.chars().find(|&c| {
+        let mut buf = [0u8; 4];
+        let encoded = encode(c.encode_utf8(&mut buf)).unwrap();
+        encoded
+            .into_iter()
+            .filter(|unit| unit.on())
+            .eq(symbols.clone())
+    })
+}
+
 /// Keyer block which generates morse signals
 ///
 /// If not dropped, the keyer will send silence unless a message has been
@@ -470,4 +489,14 @@ mod tests {
             ]
         );
     }
+    #[test]
+    fn test_decode() {
+        assert_eq!(decode(&[Dit, Space, Dah]), Some('A'));
+        assert_eq!(decode(&[Dah, Dit, Dah, Dit]), Some('C'));
+        assert_eq!(decode(&[Dah; 5]), Some('0'));
+        assert_eq!(decode(&[Dah; 6]), None);
+        for c in CHARACTERS.chars() {
+            assert_eq!(decode(&encode(&c.to_string()).unwrap()), Some(c));
+        }
+    }
 }
@@ -1,15 +1,50 @@
 //! Sample-rate conversion
+//!
+//! All blocks in this module clear their filter state when an
+//! [interrupting] or [flushing] event is received. Before a flushing event
+//! is passed on, an incomplete output chunk is sent with its shorter length,
+//! such that the end of a finite stream is not lost.
+//!
+//! [interrupting]: Event::is_interrupt
+//! [flushing]: Event::is_flush
 
 use crate::bufferpool::*;
 use crate::flow::*;
 use crate::impl_block_trait;
+use crate::kernels::CicKernel;
 use crate::math::*;
 use crate::numbers::*;
 use crate::signal::*;
 use crate::windowing::{self, Window};
 
+use tokio::sync::watch;
 use tokio::task::spawn;
 
+/// Send incomplete output chunk (if any) when a [flushing] event is received
+///
+/// [flushing]: Event::is_flush
+async fn send_incomplete<Flt>(
+    sender: &Sender<Signal<Complex<Flt>>>,
+    buf_pool: &mut ChunkBufPool<Complex<Flt>>,
+    output_chunk: &mut ChunkBuf<Complex<Flt>>,
+    output_chunk_len: usize,
+    sample_rate: f64,
+) -> Result<(), SendError<Signal<Complex<Flt>>>>
+where
+    Flt: Float,
+{
+    if output_chunk.is_empty() {
+        return Ok(());
+    }
+    let chunk = std::mem::replace(output_chunk, buf_pool.get_with_capacity(output_chunk_len));
+    sender
+        .send(Signal::Samples {
+            sample_rate,
+            chunk: chunk.finalize(),
+        })
+        .await
+}
+
 /// Reduce sample rate
 pub struct Downsampler<Flt> {
     receiver_connector: ReceiverConnector<Signal<Complex<Flt>>>,
@@ -48,10 +83,36 @@ where
         bandwidth: f64,
         quality: f64,
     ) -> Self {
-        assert!(output_rate >= 0.0, "output sample rate must be positive");
+        Self::new_internal(output_chunk_len, output_rate, bandwidth, quality, false)
+    }
+    /// Create new `Downsampler` block which reduces the sample rate to at
+    /// most `max_output_rate`
+    ///
+    /// Unlike [`Downsampler::new`], [`Signal::Samples`] with a
+    /// [`sample_rate`] lower than `max_output_rate` are accepted, in which
+    /// case the output has the same sample rate as the input. The
+    /// [`sample_rate`] must be higher than `bandwidth`, though; otherwise a
+    /// panic occurs.
+    ///
+    /// [`sample_rate`]: Signal::Samples::sample_rate
+    pub fn with_max_output_rate(
+        output_chunk_len: usize,
+        max_output_rate: f64,
+        bandwidth: f64,
+    ) -> Self {
+        Self::new_internal(output_chunk_len, max_output_rate, bandwidth, 3.0, true)
+    }
+    fn new_internal(
+        output_chunk_len: usize,
+        max_output_rate: f64,
+        bandwidth: f64,
+        quality: f64,
+        adaptive: bool,
+    ) -> Self {
+        assert!(max_output_rate >= 0.0, "output sample rate must be positive");
         assert!(bandwidth >= 0.0, "bandwidth must be positive");
         assert!(
-            bandwidth < output_rate,
+            bandwidth < max_output_rate,
             "bandwidth must be smaller than output sample rate"
         );
         let (mut receiver, receiver_connector) = new_receiver::<Signal<Complex<Flt>>>();
@@ -59,7 +120,8 @@ where
         let mut buf_pool = ChunkBufPool::<Complex<Flt>>::new();
         let mut output_chunk = buf_pool.get_with_capacity(output_chunk_len);
         spawn(async move {
-            let margin = (output_rate - bandwidth) / 2.0;
+            let mut output_rate = max_output_rate;
+            let mut margin = (output_rate - bandwidth) / 2.0;
             let mut prev_input_rate: Option<f64> = None;
             let mut ir: Vec<Flt> = Default::default();
             let mut ringbuf: Vec<Complex<Flt>> = Default::default();
@@ -75,6 +137,25 @@ where
                         if Some(input_rate) != prev_input_rate {
                             prev_input_rate = Some(input_rate);
                             assert!(input_rate >= 0.0, "input sample rate must be positive");
+                            if adaptive {
+                                assert!(
+                                    input_rate > bandwidth,
+                                    "input sample rate must be greater than bandwidth"
+                                );
+                                let new_output_rate = max_output_rate.min(input_rate);
+                                if new_output_rate != output_rate && !output_chunk.is_empty() {
+                                    let Ok(()) = sender
+                                        .send(Signal::Samples {
+                                            sample_rate: output_rate,
+                                            chunk: output_chunk.finalize(),
+                                        })
+                                        .await
+                                    else { return; };
+                                    output_chunk = buf_pool.get_with_capacity(output_chunk_len);
+                                }
+                                output_rate = new_output_rate;
+                                margin = (output_rate - bandwidth) / 2.0;
+                            }
                             assert!(
                                 input_rate >= output_rate,
                                 "input sample rate must be greater than or equal to output sample rate"
@@ -132,8 +213,24 @@ where
                             }
                         }
                     }
-                    event @ Signal::Event { .. } => {
-                        let Ok(()) = sender.send(event).await else { return; };
+                    Signal::Event(event) => {
+                        if event.is_flush() {
+                            let Ok(()) = send_incomplete(
+                                &sender,
+                                &mut buf_pool,
+                                &mut output_chunk,
+                                output_chunk_len,
+                                output_rate,
+                            )
+                            .await
+                            else { return; };
+                        }
+                        if event.is_interrupt() || event.is_flush() {
+                            ringbuf.fill(Complex::from(Flt::zero()));
+                            ringbuf_pos = 0;
+                            pos = 0.0;
+                        }
+                        let Ok(()) = sender.send(Signal::Event(event)).await else { return; };
                     }
                 }
             }
@@ -266,8 +363,24 @@ where
                             pos -= output_rate;
                         }
                     }
-                    event @ Signal::Event { .. } => {
-                        let Ok(()) = sender.send(event).await else { return; };
+                    Signal::Event(event) => {
+                        if event.is_flush() {
+                            let Ok(()) = send_incomplete(
+                                &sender,
+                                &mut buf_pool,
+                                &mut output_chunk,
+                                output_chunk_len,
+                                output_rate,
+                            )
+                            .await
+                            else { return; };
+                        }
+                        if event.is_interrupt() || event.is_flush() {
+                            ringbuf.fill(Complex::from(Flt::zero()));
+                            ringbuf_pos = 0;
+                            pos = 0.0;
+                        }
+                        let Ok(()) = sender.send(Signal::Event(event)).await else { return; };
                     }
                 }
             }
@@ -279,5 +392,1005 @@ where
     }
 }
 
+/// Change sample rate by a rational factor using a polyphase filter
+///
+/// The output sample rate is the input sample rate multiplied with
+/// `interpolation` and divided by `decimation`.
+pub struct RationalResampler<Flt> {
+    receiver_connector: ReceiverConnector<Signal<Complex<Flt>>>,
+    sender_connector: SenderConnector<Signal<Complex<Flt>>>,
+}
+
+impl_block_trait! { <Flt> Consumer<Signal<Complex<Flt>>> for RationalResampler<Flt> }
+impl_block_trait! { <Flt> Producer<Signal<Complex<Flt>>> for RationalResampler<Flt> }
+
+impl<Flt> RationalResampler<Flt>
+where
+    Flt: Float,
+{
+    /// Create new `RationalResampler` block with given prototype filter
+    ///
+    /// The `impulse_response` of the low-pass prototype filter applies to the
+    /// intermediate sample rate, i.e. the input sample rate multiplied with
+    /// `interpolation`. For unity gain, the sum of all values in
+    /// `impulse_response` should be equal to `interpolation`.
+    pub fn new(
+        output_chunk_len: usize,
+        interpolation: usize,
+        decimation: usize,
+        impulse_response: &[f64],
+    ) -> Self {
+        assert!(interpolation > 0, "interpolation must be positive");
+        assert!(decimation > 0, "decimation must be positive");
+        assert!(
+            !impulse_response.is_empty(),
+            "impulse response must not be empty"
+        );
+        let (mut receiver, receiver_connector) = new_receiver::<Signal<Complex<Flt>>>();
+        let (sender, sender_connector) = new_sender::<Signal<Complex<Flt>>>();
+        let mut buf_pool = ChunkBufPool::<Complex<Flt>>::new();
+        let mut output_chunk = buf_pool.get_with_capacity(output_chunk_len);
+        let taps_per_phase = impulse_response.len().div_ceil(interpolation);
+        // filter coefficients for each phase, ordered from oldest to newest sample
+        let phases: Vec<Vec<Flt>> = (0..interpolation)
+            .map(|phase| {
+                (0..taps_per_phase)
+                    .rev()
+                    .map(|j| {
+                        let y = impulse_response
+                            .get(phase + j * interpolation)
+                            .copied()
+                            .unwrap_or(0.0);
+                        flt!(y)
+                    })
+                    .collect()
+            })
+            .collect();
+        spawn(async move {
+            // history is stored twice to allow contiguous access
+            let mut history = vec![Complex::from(Flt::zero()); 2 * taps_per_phase];
+            let mut history_pos: usize = 0;
+            let mut phase: usize = 0;
+            let mut output_rate = f64::NAN;
+            loop {
+                let Ok(signal) = receiver.recv().await else { return; };
+                match signal {
+                    Signal::Samples {
+                        sample_rate: input_rate,
+                        chunk: input_chunk,
+                    } => {
+                        output_rate = input_rate * interpolation as f64 / decimation as f64;
+                        for &sample in input_chunk.iter() {
+                            history[history_pos] = sample;
+                            history[history_pos + taps_per_phase] = sample;
+                            history_pos += 1;
+                            if history_pos == taps_per_phase {
+                                history_pos = 0;
+                            }
+                            let window = &history[history_pos..history_pos + taps_per_phase];
+                            while phase < interpolation {
+                                let mut sum: Complex<Flt> = Complex::from(Flt::zero());
+                                for (&x, &h) in window.iter().zip(phases[phase].iter()) {
+                                    sum += x * h;
+                                }
+                                output_chunk.push(sum);
+                                if output_chunk.len() >= output_chunk_len {
+                                    let Ok(()) = sender
+                                        .send(Signal::Samples {
+                                            sample_rate: output_rate,
+                                            chunk: output_chunk.finalize(),
+                                        })
+                                        .await
+                                    else { return; };
+                                    output_chunk = buf_pool.get_with_capacity(output_chunk_len);
+                                }
+                                phase += decimation;
+                            }
+                            phase -= interpolation;
+                        }
+                    }
+                    Signal::Event(event) => {
+                        if event.is_flush() {
+                            let Ok(()) = send_incomplete(
+                                &sender,
+                                &mut buf_pool,
+                                &mut output_chunk,
+                                output_chunk_len,
+                                output_rate,
+                            )
+                            .await
+                            else { return; };
+                        }
+                        if event.is_interrupt() || event.is_flush() {
+                            history.fill(Complex::from(Flt::zero()));
+                            history_pos = 0;
+                            phase = 0;
+                        }
+                        let Ok(()) = sender.send(Signal::Event(event)).await else { return; };
+                    }
+                }
+            }
+        });
+        Self {
+            receiver_connector,
+            sender_connector,
+        }
+    }
+    /// Create new `RationalResampler` block with automatically designed
+    /// anti-aliasing filter
+    ///
+    /// Common factors of `interpolation` and `decimation` are removed first.
+    /// Aliasing is suppressed for frequencies lower than 80% of the smaller
+    /// one of the input and output sample rate (see [`Downsampler::new`] for
+    /// a similar design).
+    pub fn new_antialiased(
+        output_chunk_len: usize,
+        interpolation: usize,
+        decimation: usize,
+    ) -> Self {
+        assert!(interpolation > 0, "interpolation must be positive");
+        assert!(decimation > 0, "decimation must be positive");
+        let gcd = num::integer::gcd(interpolation, decimation);
+        let interpolation = interpolation / gcd;
+        let decimation = decimation / gcd;
+        let relative_rate = (interpolation.max(decimation) as f64).recip();
+        let margin = relative_rate * 0.1;
+        let quality = 3.0;
+        let ir_len: usize = (quality / margin).ceil() as usize;
+        let ir_len_flt = ir_len as f64;
+        let window = windowing::Kaiser::with_null_at_bin(ir_len_flt * margin);
+        let mut ir: Vec<f64> = Vec::with_capacity(ir_len);
+        let mut sum = 0.0;
+        for i in 0..ir_len {
+            let x = (i as f64 + 0.5) - ir_len_flt / 2.0;
+            let y = sinc(x * relative_rate) * window.relative_value_at(x * 2.0 / ir_len_flt);
+            ir.push(y);
+            sum += y;
+        }
+        let scale = interpolation as f64 / sum;
+        for y in ir.iter_mut() {
+            *y *= scale;
+        }
+        Self::new(output_chunk_len, interpolation, decimation, &ir)
+    }
+}
+
+/// Change sample rate by an arbitrary (non-rational) and slowly varying
+/// factor using a polyphase filter bank with fractional-delay interpolation
+///
+/// The output is always labeled with the given `output_rate`. The actual
+/// deviation of the input sample rate from its nominal [`sample_rate`] (e.g.
+/// due to clock drift, as measured by a [`RateMonitor`]) may be set in parts
+/// per million with [`ArbitraryResampler::set_rate_error`], in which case
+/// the resampling ratio is corrected accordingly. Changes of the rate error
+/// are applied smoothly (with a time constant of one second), such that no
+/// audible artifacts occur.
+///
+/// The filter bank consists of 64 phases, where the output is linearly
+/// interpolated between adjacent phases. The cutoff frequency of the
+/// anti-aliasing filter is 90% of the smaller one of the input and output
+/// Nyquist frequency.
+///
+/// [`sample_rate`]: Signal::Samples::sample_rate
+/// [`RateMonitor`]: crate::blocks::analysis::RateMonitor
+pub struct ArbitraryResampler<Flt> {
+    receiver_connector: ReceiverConnector<Signal<Complex<Flt>>>,
+    sender_connector: SenderConnector<Signal<Complex<Flt>>>,
+    rate_error: watch::Sender<f64>,
+    ratio: watch::Receiver<f64>,
+}
+
+impl_block_trait! { <Flt> Consumer<Signal<Complex<Flt>>> for ArbitraryResampler<Flt> }
+impl_block_trait! { <Flt> Producer<Signal<Complex<Flt>>> for ArbitraryResampler<Flt> }
+
+impl<Flt> ArbitraryResampler<Flt>
+where
+    Flt: Float,
+{
+    const PHASES: usize = 64;
+    const SMOOTHING: f64 = 1.0;
+    /// Create new `ArbitraryResampler` block with given output chunk length
+    /// and output sample rate
+    pub fn new(output_chunk_len: usize, output_rate: f64) -> Self {
+        assert!(output_rate > 0.0, "output sample rate must be positive");
+        let (mut receiver, receiver_connector) = new_receiver::<Signal<Complex<Flt>>>();
+        let (sender, sender_connector) = new_sender::<Signal<Complex<Flt>>>();
+        let (rate_error_send, mut rate_error_recv) = watch::channel(0.0);
+        let (ratio_send, ratio) = watch::channel(f64::NAN);
+        let mut buf_pool = ChunkBufPool::<Complex<Flt>>::new();
+        let mut output_chunk = buf_pool.get_with_capacity(output_chunk_len);
+        spawn(async move {
+            let alpha = (Self::SMOOTHING * output_rate).recip();
+            let mut prev_input_rate: Option<f64> = None;
+            let mut target_error: f64 = 0.0;
+            let mut current_error: f64 = 0.0;
+            // coefficients for each phase (plus one), ordered from oldest to
+            // newest sample
+            let mut phases: Vec<Vec<Flt>> = Vec::new();
+            let mut taps: usize = 0;
+            // history is stored twice to allow contiguous access
+            let mut history: Vec<Complex<Flt>> = Vec::new();
+            let mut history_pos: usize = 0;
+            let mut frac: f64 = 0.0;
+            loop {
+                let Ok(signal) = receiver.recv().await else { return; };
+                match signal {
+                    Signal::Samples {
+                        sample_rate: input_rate,
+                        chunk: input_chunk,
+                    } => {
+                        if rate_error_recv.has_changed().unwrap_or(false) {
+                            target_error = *rate_error_recv.borrow_and_update();
+                        }
+                        if Some(input_rate) != prev_input_rate {
+                            prev_input_rate = Some(input_rate);
+                            assert!(input_rate > 0.0, "input sample rate must be positive");
+                            let cutoff = 0.45 * (output_rate / input_rate).min(1.0);
+                            taps = (16.0 / cutoff).ceil() as usize;
+                            let window = windowing::Kaiser::with_null_at_bin(6.0);
+                            let center = taps as f64 / 2.0;
+                            phases = (0..=Self::PHASES)
+                                .map(|phase| {
+                                    let offset = phase as f64 / Self::PHASES as f64;
+                                    let mut coeffs: Vec<f64> = (0..taps)
+                                        .rev()
+                                        .map(|k| {
+                                            let x = k as f64 + offset - center;
+                                            2.0 * cutoff
+                                                * sinc(2.0 * cutoff * x)
+                                                * window.relative_value_at(x / center)
+                                        })
+                                        .collect();
+                                    let sum: f64 = coeffs.iter().sum();
+                                    for y in coeffs.iter_mut() {
+                                        *y /= sum;
+                                    }
+                                    coeffs.into_iter().map(|y| flt!(y)).collect()
+                                })
+                                .collect();
+                            history = vec![Complex::from(Flt::zero()); 2 * taps];
+                            history_pos = 0;
+                            frac = 0.0;
+                        }
+                        let nominal_step = input_rate / output_rate;
+                        for &sample in input_chunk.iter() {
+                            history[history_pos] = sample;
+                            history[history_pos + taps] = sample;
+                            history_pos += 1;
+                            if history_pos == taps {
+                                history_pos = 0;
+                            }
+                            let window = &history[history_pos..history_pos + taps];
+                            while frac < 1.0 {
+                                let position = frac * Self::PHASES as f64;
+                                let phase = (position as usize).min(Self::PHASES - 1);
+                                let weight: Flt = flt!(position - phase as f64);
+                                let mut sum0: Complex<Flt> = Complex::from(Flt::zero());
+                                let mut sum1: Complex<Flt> = Complex::from(Flt::zero());
+                                for ((&x, &h0), &h1) in window
+                                    .iter()
+                                    .zip(phases[phase].iter())
+                                    .zip(phases[phase + 1].iter())
+                                {
+                                    sum0 += x * h0;
+                                    sum1 += x * h1;
+                                }
+                                output_chunk.push(sum0 + (sum1 - sum0) * weight);
+                                if output_chunk.len() >= output_chunk_len {
+                                    ratio_send.send_replace(
+                                        output_rate / input_rate / (1.0 + current_error * 1e-6),
+                                    );
+                                    let Ok(()) = sender
+                                        .send(Signal::Samples {
+                                            sample_rate: output_rate,
+                                            chunk: output_chunk.finalize(),
+                                        })
+                                        .await
+                                    else { return; };
+                                    output_chunk = buf_pool.get_with_capacity(output_chunk_len);
+                                }
+                                current_error += (target_error - current_error) * alpha;
+                                frac += nominal_step * (1.0 + current_error * 1e-6);
+                            }
+                            frac -= 1.0;
+                        }
+                    }
+                    Signal::Event(event) => {
+                        if event.is_flush() {
+                            let Ok(()) = send_incomplete(
+                                &sender,
+                                &mut buf_pool,
+                                &mut output_chunk,
+                                output_chunk_len,
+                                output_rate,
+                            )
+                            .await
+                            else { return; };
+                        }
+                        if event.is_interrupt() || event.is_flush() {
+                            history.fill(Complex::from(Flt::zero()));
+                            history_pos = 0;
+                            frac = 0.0;
+                        }
+                        let Ok(()) = sender.send(Signal::Event(event)).await else { return; };
+                    }
+                }
+            }
+        });
+        Self {
+            receiver_connector,
+            sender_connector,
+            rate_error: rate_error_send,
+            ratio,
+        }
+    }
+    /// Get deviation of the actual input sample rate from the nominal input
+    /// sample rate in parts per million
+    pub fn rate_error(&self) -> f64 {
+        *self.rate_error.borrow()
+    }
+    /// Set deviation of the actual input sample rate from the nominal input
+    /// sample rate in parts per million (e.g. the value of
+    /// [`RateMonitor::ppm`])
+    ///
+    /// [`RateMonitor::ppm`]: crate::blocks::analysis::RateMonitor::ppm
+    pub fn set_rate_error(&self, ppm: f64) {
+        self.rate_error.send_replace(ppm);
+    }
+    /// Get [`watch::Receiver`] of the effective resampling ratio (output
+    /// samples per input sample), which follows changes of the rate error
+    /// smoothly (`NaN` until the first chunk has been sent)
+    ///
+    /// The value is updated after each sent chunk.
+    pub fn ratio(&self) -> watch::Receiver<f64> {
+        self.ratio.clone()
+    }
+}
+
+/// Decimating cascaded integrator-comb (CIC) filter
+///
+/// This block reduces the sample rate by an integer `decimation` factor *R*
+/// without any multiplications (except for the optional gain compensation),
+/// which makes it suitable for high decimation ratios. The filter consists of
+/// `stages` (*N*) integrators followed by *N* comb filters with a
+/// `differential_delay` (*M*).
+///
+/// The DC gain of the filter is *(RM)<sup>N</sup>*. When implemented with
+/// integers, registers need to be wide enough to hold *N log₂(RM)* bits in
+/// addition to the input bits. As this block works with floating point
+/// numbers, there is no overflow, but the integrators accumulate rounding
+/// errors for large gains, so `f64` should be used in that case. If
+/// `compensate` is `true`, the output is multiplied with
+/// *1 / (RM)<sup>N</sup>* to achieve unity gain at DC.
+///
+/// Note that the frequency response of a CIC filter is not flat in the pass
+/// band and that it provides limited alias rejection, so it will usually be
+/// followed by a [`Downsampler`] or FIR filter.
+pub struct CicDecimator<Flt> {
+    receiver_connector: ReceiverConnector<Signal<Complex<Flt>>>,
+    sender_connector: SenderConnector<Signal<Complex<Flt>>>,
+}
+
+impl_block_trait! { <Flt> Consumer<Signal<Complex<Flt>>> for CicDecimator<Flt> }
+impl_block_trait! { <Flt> Producer<Signal<Complex<Flt>>> for CicDecimator<Flt> }
+
+impl<Flt> CicDecimator<Flt>
+where
+    Flt: Float,
+{
+    /// Create new `CicDecimator` block
+    pub fn new(
+        output_chunk_len: usize,
+        decimation: usize,
+        stages: usize,
+        differential_delay: usize,
+        compensate: bool,
+    ) -> Self {
+        assert!(decimation > 0, "decimation must be positive");
+        assert!(stages > 0, "number of stages must be positive");
+        assert!(
+            differential_delay > 0,
+            "differential delay must be positive"
+        );
+        let (mut receiver, receiver_connector) = new_receiver::<Signal<Complex<Flt>>>();
+        let (sender, sender_connector) = new_sender::<Signal<Complex<Flt>>>();
+        let mut buf_pool = ChunkBufPool::<Complex<Flt>>::new();
+        let mut output_chunk = buf_pool.get_with_capacity(output_chunk_len);
+        let gain: Flt = match compensate {
+            true => flt!(((decimation * differential_delay) as f64)
+                .powi(stages as i32)
+                .recip()),
+            false => Flt::one(),
+        };
+        let mut kernel = CicKernel::new(decimation, stages, differential_delay);
+        spawn(async move {
+            let mut output_rate = f64::NAN;
+            loop {
+                let Ok(signal) = receiver.recv().await else { return; };
+                match signal {
+                    Signal::Samples {
+                        sample_rate: input_rate,
+                        chunk: input_chunk,
+                    } => {
+                        output_rate = input_rate / decimation as f64;
+                        for &sample in input_chunk.iter() {
+                            let Some(value) = kernel.push(sample) else { continue; };
+                            output_chunk.push(value * gain);
+                            if output_chunk.len() >= output_chunk_len {
+                                let Ok(()) = sender
+                                    .send(Signal::Samples {
+                                        sample_rate: output_rate,
+                                        chunk: output_chunk.finalize(),
+                                    })
+                                    .await
+                                else { return; };
+                                output_chunk = buf_pool.get_with_capacity(output_chunk_len);
+                            }
+                        }
+                    }
+                    Signal::Event(event) => {
+                        if event.is_flush() {
+                            let Ok(()) = send_incomplete(
+                                &sender,
+                                &mut buf_pool,
+                                &mut output_chunk,
+                                output_chunk_len,
+                                output_rate,
+                            )
+                            .await
+                            else { return; };
+                        }
+                        if event.is_interrupt() || event.is_flush() {
+                            kernel.reset();
+                        }
+                        let Ok(()) = sender.send(Signal::Event(event)).await else { return; };
+                    }
+                }
+            }
+        });
+        Self {
+            receiver_connector,
+            sender_connector,
+        }
+    }
+}
+
+/// Decimating cascaded integrator-comb (CIC) filter working with integer
+/// samples
+///
+/// Same as [`CicDecimator`], but receives complex samples of type [`i16`]
+/// (e.g. read by a `SoapySdrRx` in `CS16` format) and uses integer
+/// arithmetic, such that no conversion to floats is needed at the full input
+/// rate. The registers have 64 bits and wrap on overflow, which yields exact
+/// results as long as *16 + N log₂(RM)* does not exceed 64 bits.
+///
+/// The output is converted to complex samples of type `Flt`, where full
+/// scale of the input corresponds to `1.0` (times the DC gain
+/// *(RM)<sup>N</sup>* unless `compensate` is `true`).
+pub struct IntCicDecimator<Flt> {
+    receiver_connector: ReceiverConnector<Signal<Complex<i16>>>,
+    sender_connector: SenderConnector<Signal<Complex<Flt>>>,
+}
+
+impl<Flt> Consumer<Signal<Complex<i16>>> for IntCicDecimator<Flt> {
+    fn receiver_connector(&self) -> &ReceiverConnector<Signal<Complex<i16>>> {
+        &self.receiver_connector
+    }
+}
+
+impl<Flt> Producer<Signal<Complex<Flt>>> for IntCicDecimator<Flt> {
+    fn sender_connector(&self) -> &SenderConnector<Signal<Complex<Flt>>> {
+        &self.sender_connector
+    }
+}
+
+impl<Flt> IntCicDecimator<Flt>
+where
+    Flt: Float,
+{
+    /// Create new `IntCicDecimator` block
+    pub fn new(
+        output_chunk_len: usize,
+        decimation: usize,
+        stages: usize,
+        differential_delay: usize,
+        compensate: bool,
+    ) -> Self {
+        assert!(decimation > 0, "decimation must be positive");
+        assert!(stages > 0, "number of stages must be positive");
+        assert!(
+            differential_delay > 0,
+            "differential delay must be positive"
+        );
+        let dc_gain = ((decimation * differential_delay) as f64).powi(stages as i32);
+        assert!(
+            16.0 + dc_gain.log2() <= 64.0,
+            "register growth exceeds 64 bits"
+        );
+        let (mut receiver, receiver_connector) = new_receiver::<Signal<Complex<i16>>>();
+        let (sender, sender_connector) = new_sender::<Signal<Complex<Flt>>>();
+        let mut buf_pool = ChunkBufPool::<Complex<Flt>>::new();
+        let mut output_chunk = buf_pool.get_with_capacity(output_chunk_len);
+        let gain: Flt = match compensate {
+            true => flt!((dc_gain * 32768.0).recip()),
+            false => flt!(32768.0f64.recip()),
+        };
+        spawn(async move {
+            let zero = Complex::new(0i64, 0i64);
+            let mut integrators: Vec<Complex<i64>> = vec![zero; stages];
+            let mut combs: Vec<Vec<Complex<i64>>> = vec![vec![zero; differential_delay]; stages];
+            let mut comb_pos: usize = 0;
+            let mut phase: usize = 0;
+            let mut output_rate = f64::NAN;
+            loop {
+                let Ok(signal) = receiver.recv().await else { return; };
+                match signal {
+                    Signal::Samples {
+                        sample_rate: input_rate,
+                        chunk: input_chunk,
+                    } => {
+                        output_rate = input_rate / decimation as f64;
+                        for &sample in input_chunk.iter() {
+                            let mut value = Complex::new(sample.re as i64, sample.im as i64);
+                            for integrator in integrators.iter_mut() {
+                                integrator.re = integrator.re.wrapping_add(value.re);
+                                integrator.im = integrator.im.wrapping_add(value.im);
+                                value = *integrator;
+                            }
+                            phase += 1;
+                            if phase < decimation {
+                                continue;
+                            }
+                            phase = 0;
+                            for comb in combs.iter_mut() {
+                                let delayed = comb[comb_pos];
+                                comb[comb_pos] = value;
+                                value.re = value.re.wrapping_sub(delayed.re);
+                                value.im = value.im.wrapping_sub(delayed.im);
+                            }
+                            comb_pos += 1;
+                            if comb_pos == differential_delay {
+                                comb_pos = 0;
+                            }
+                            output_chunk.push(Complex::new(flt!(value.re), flt!(value.im)) * gain);
+                            if output_chunk.len() >= output_chunk_len {
+                                let Ok(()) = sender
+                                    .send(Signal::Samples {
+                                        sample_rate: output_rate,
+                                        chunk: output_chunk.finalize(),
+                                    })
+                                    .await
+                                else { return; };
+                                output_chunk = buf_pool.get_with_capacity(output_chunk_len);
+                            }
+                        }
+                    }
+                    Signal::Event(event) => {
+                        if event.is_flush() {
+                            let Ok(()) = send_incomplete(
+                                &sender,
+                                &mut buf_pool,
+                                &mut output_chunk,
+                                output_chunk_len,
+                                output_rate,
+                            )
+                            .await
+                            else { return; };
+                        }
+                        if event.is_interrupt() || event.is_flush() {
+                            integrators.fill(zero);
+                            combs.iter_mut().for_each(|comb| comb.fill(zero));
+                            comb_pos = 0;
+                            phase = 0;
+                        }
+                        let Ok(()) = sender.send(Signal::Event(event)).await else { return; };
+                    }
+                }
+            }
+        });
+        Self {
+            receiver_connector,
+            sender_connector,
+        }
+    }
+}
+
+/// State of a single half-band decimation stage
+struct HalfBandStage<Flt> {
+    /// non-zero coefficients beside the center tap, from the center outwards
+    coeffs: Vec<Flt>,
+    /// history is stored twice to allow contiguous access
+    history: Vec<Complex<Flt>>,
+    history_pos: usize,
+    skip: bool,
+}
+
+impl<Flt> HalfBandStage<Flt>
+where
+    Flt: Float,
+{
+    fn new(coeffs: Vec<Flt>) -> Self {
+        let len = 4 * coeffs.len() - 1;
+        Self {
+            coeffs,
+            history: vec![Complex::from(Flt::zero()); 2 * len],
+            history_pos: 0,
+            skip: false,
+        }
+    }
+    /// Clear history
+    fn reset(&mut self) {
+        self.history.fill(Complex::from(Flt::zero()));
+        self.history_pos = 0;
+        self.skip = false;
+    }
+    /// Feed one input sample and return output sample for every second
+    /// input sample
+    fn push(&mut self, sample: Complex<Flt>) -> Option<Complex<Flt>> {
+        let len = self.history.len() / 2;
+        self.history[self.history_pos] = sample;
+        self.history[self.history_pos + len] = sample;
+        self.history_pos += 1;
+        if self.history_pos == len {
+            self.history_pos = 0;
+        }
+        self.skip = !self.skip;
+        if !self.skip {
+            return None;
+        }
+        let window = &self.history[self.history_pos..self.history_pos + len];
+        let center = len / 2;
+        let half: Flt = flt!(0.5);
+        let mut sum = window[center] * half;
+        for (j, &h) in self.coeffs.iter().enumerate() {
+            let offset = 2 * j + 1;
+            sum += (window[center - offset] + window[center + offset]) * h;
+        }
+        Some(sum)
+    }
+}
+
+/// Halve the sample rate (once or repeatedly) using half-band filters
+///
+/// A half-band filter has a cutoff frequency of half the Nyquist frequency,
+/// and every other tap (except the center tap) is zero. Together with the
+/// symmetry of the filter, this requires only `side_taps + 1`
+/// multiplications per output sample, where `side_taps` is the number of
+/// non-zero taps on each side of the center tap (the filter has
+/// `4 * side_taps - 1` taps in total).
+///
+/// With `stages` greater than one, the filter is applied repeatedly, such
+/// that the sample rate is reduced by a factor of 2<sup>`stages`</sup>.
+/// Signals above the cutoff frequency alias into the transition band of
+/// the filter, so only the lower part of the output band (e.g. 80% with
+/// `side_taps` of `8`) should be used, or the output should be filtered
+/// further.
+pub struct HalfBand<Flt> {
+    receiver_connector: ReceiverConnector<Signal<Complex<Flt>>>,
+    sender_connector: SenderConnector<Signal<Complex<Flt>>>,
+}
+
+impl_block_trait! { <Flt> Consumer<Signal<Complex<Flt>>> for HalfBand<Flt> }
+impl_block_trait! { <Flt> Producer<Signal<Complex<Flt>>> for HalfBand<Flt> }
+
+impl<Flt> HalfBand<Flt>
+where
+    Flt: Float,
+{
+    /// Create new `HalfBand` block which halves the sample rate once
+    pub fn new(output_chunk_len: usize, side_taps: usize) -> Self {
+        Self::with_stages(output_chunk_len, side_taps, 1)
+    }
+    /// Create new `HalfBand` block which halves the sample rate `stages`
+    /// times
+    pub fn with_stages(output_chunk_len: usize, side_taps: usize, stages: usize) -> Self {
+        assert!(side_taps > 0, "number of taps must be positive");
+        assert!(stages > 0, "number of stages must be positive");
+        let (mut receiver, receiver_connector) = new_receiver::<Signal<Complex<Flt>>>();
+        let (sender, sender_connector) = new_sender::<Signal<Complex<Flt>>>();
+        let mut buf_pool = ChunkBufPool::<Complex<Flt>>::new();
+        let mut output_chunk = buf_pool.get_with_capacity(output_chunk_len);
+        let coeffs = half_band_coeffs(side_taps);
+        let factor = (1usize << stages) as f64;
+        spawn(async move {
+            let mut stages: Vec<HalfBandStage<Flt>> = (0..stages)
+                .map(|_| HalfBandStage::new(coeffs.iter().map(|&h| flt!(h)).collect()))
+                .collect();
+            let mut output_rate = f64::NAN;
+            loop {
+                let Ok(signal) = receiver.recv().await else { return; };
+                match signal {
+                    Signal::Samples {
+                        sample_rate: input_rate,
+                        chunk: input_chunk,
+                    } => {
+                        output_rate = input_rate / factor;
+                        'samples: for &sample in input_chunk.iter() {
+                            let mut value = sample;
+                            for stage in stages.iter_mut() {
+                                let Some(output) = stage.push(value) else { continue 'samples; };
+                                value = output;
+                            }
+                            output_chunk.push(value);
+                            if output_chunk.len() >= output_chunk_len {
+                                let Ok(()) = sender
+                                    .send(Signal::Samples {
+                                        sample_rate: output_rate,
+                                        chunk: output_chunk.finalize(),
+                                    })
+                                    .await
+                                else { return; };
+                                output_chunk = buf_pool.get_with_capacity(output_chunk_len);
+                            }
+                        }
+                    }
+                    Signal::Event(event) => {
+                        if event.is_flush() {
+                            let Ok(()) = send_incomplete(
+                                &sender,
+                                &mut buf_pool,
+                                &mut output_chunk,
+                                output_chunk_len,
+                                output_rate,
+                            )
+                            .await
+                            else { return; };
+                        }
+                        if event.is_interrupt() || event.is_flush() {
+                            stages.iter_mut().for_each(HalfBandStage::reset);
+                        }
+                        let Ok(()) = sender.send(Signal::Event(event)).await else { return; };
+                    }
+                }
+            }
+        });
+        Self {
+            receiver_connector,
+            sender_connector,
+        }
+    }
+}
+
+/// Design non-zero coefficients beside the center tap (with value `0.5`) of
+/// a half-band filter with unity gain at DC
+fn half_band_coeffs(side_taps: usize) -> Vec<f64> {
+    let half_len = (2 * side_taps) as f64;
+    let window = windowing::Kaiser::with_beta(7.0);
+    let mut coeffs: Vec<f64> = (0..side_taps)
+        .map(|j| {
+            let x = (2 * j + 1) as f64;
+            0.5 * sinc(x / 2.0) * window.relative_value_at(x / half_len)
+        })
+        .collect();
+    let scale = 0.25 / coeffs.iter().sum::<f64>();
+    for h in coeffs.iter_mut() {
+        *h *= scale;
+    }
+    coeffs
+}
+
 #[cfg(test)]
-mod tests {}
+mod tests {
+    use super::*;
+    use crate::tests::{assert_approx, flushed_lens};
+    #[tokio::test]
+    async fn test_rational_resampler_dc() {
+        let (sender, sender_connector) = new_sender::<Signal<Complex<f64>>>();
+        let resampler = RationalResampler::<f64>::new_antialiased(30, 6, 4);
+        let (mut receiver, receiver_connector) = new_receiver::<Signal<Complex<f64>>>();
+        resampler.feed_from(&sender_connector);
+        resampler.feed_into(&receiver_connector);
+        spawn(async move {
+            loop {
+                let chunk = Chunk::from(vec![Complex::new(1.0, 0.0); 100]);
+                let signal = Signal::Samples {
+                    sample_rate: 48000.0,
+                    chunk,
+                };
+                let Ok(()) = sender.send(signal).await else { return; };
+            }
+        });
+        for _ in 0..10 {
+            receiver.recv().await.unwrap();
+        }
+        let Signal::Samples { sample_rate, chunk } = receiver.recv().await.unwrap()
+        else { panic!(); };
+        assert_eq!(sample_rate, 72000.0);
+        assert_eq!(chunk.len(), 30);
+        for sample in chunk.iter() {
+            assert!((sample.re - 1.0).abs() < 0.01);
+            assert!(sample.im.abs() < 1e-10);
+        }
+    }
+    #[tokio::test]
+    async fn test_flush() {
+        let input = vec![Complex::new(0.5, -0.25); 4800];
+        let downsampler = Downsampler::<f32>::new(256, 8000.0, 3000.0);
+        let lens = flushed_lens(&downsampler, 48000.0, input.clone(), 1700).await;
+        assert_eq!(lens, [800; 2]);
+        let resampler = RationalResampler::<f32>::new_antialiased(256, 3, 2);
+        let lens = flushed_lens(&resampler, 48000.0, input.clone(), 1700).await;
+        assert_eq!(lens, [7200; 2]);
+        let cic = CicDecimator::<f32>::new(256, 8, 3, 1, true);
+        let lens = flushed_lens(&cic, 48000.0, input.clone(), 1700).await;
+        assert_eq!(lens, [600; 2]);
+        let half_band = HalfBand::<f32>::with_stages(256, 8, 2);
+        let lens = flushed_lens(&half_band, 48000.0, input, 1700).await;
+        assert_eq!(lens, [1200; 2]);
+        let input = vec![Complex::new(0.5, -0.25); 800];
+        let upsampler = Upsampler::<f32>::new(256, 48000.0, 3000.0);
+        let lens = flushed_lens(&upsampler, 8000.0, input, 300).await;
+        assert_eq!(lens, [4800; 2]);
+        let input = vec![Complex::new(0.5, -0.25); 4410];
+        let arbitrary = ArbitraryResampler::<f32>::new(256, 48000.0);
+        for len in flushed_lens(&arbitrary, 44100.0, input, 1700).await {
+            assert!(len.abs_diff(4800) <= 1);
+        }
+        let input = vec![Complex::new(i16::MAX, 0); 4800];
+        let int_cic = IntCicDecimator::<f32>::new(256, 8, 3, 1, true);
+        let lens = flushed_lens(&int_cic, 48000.0, input, 1700).await;
+        assert_eq!(lens, [600; 2]);
+    }
+    #[tokio::test]
+    async fn test_downsampler_timestamps() {
+        let (sender, sender_connector) = new_sender::<Signal<Complex<f32>>>();
+        let downsampler = Downsampler::<f32>::new(400, 8000.0, 3000.0);
+        let (mut receiver, receiver_connector) = new_receiver::<Signal<Complex<f32>>>();
+        downsampler.feed_from(&sender_connector);
+        downsampler.feed_into(&receiver_connector);
+        tokio::spawn(async move {
+            sender.send_event(Timestamp(1_000_000_000)).await.unwrap();
+            for _ in 0..10 {
+                sender
+                    .send(Signal::Samples {
+                        sample_rate: 48000.0,
+                        chunk: Chunk::from(vec![Complex::from(0.0); 4500]),
+                    })
+                    .await
+                    .unwrap();
+            }
+        });
+        let mut tracker = TimestampTracker::new();
+        let mut timestamps = Vec::new();
+        while timestamps.len() < 18 {
+            let signal = receiver.recv().await.unwrap();
+            if let Some(timestamp) = tracker.update(&signal) {
+                assert_eq!(signal.sample_rate(), Some(8000.0));
+                assert_eq!(signal.sample_count(), 400);
+                timestamps.push(timestamp);
+            }
+        }
+        for (index, &timestamp) in timestamps.iter().enumerate() {
+            assert_eq!(timestamp, 1_000_000_000 + index as i64 * 50_000_000);
+        }
+    }
+    #[tokio::test]
+    async fn test_cic_decimator_dc() {
+        let (sender, sender_connector) = new_sender::<Signal<Complex<f64>>>();
+        let cic = CicDecimator::<f64>::new(10, 8, 3, 1, true);
+        let (mut receiver, receiver_connector) = new_receiver::<Signal<Complex<f64>>>();
+        cic.feed_from(&sender_connector);
+        cic.feed_into(&receiver_connector);
+        sender
+            .send(Signal::Samples {
+                sample_rate: 48000.0,
+                chunk: Chunk::from(vec![Complex::new(0.5, -1.0); 160]),
+            })
+            .await
+            .unwrap();
+        receiver.recv().await.unwrap();
+        let Signal::Samples { sample_rate, chunk } = receiver.recv().await.unwrap()
+        else { panic!(); };
+        assert_eq!(sample_rate, 6000.0);
+        for sample in chunk.iter() {
+            assert_approx(sample.re, 0.5);
+            assert_approx(sample.im, -1.0);
+        }
+    }
+    #[tokio::test]
+    async fn test_int_cic_decimator() {
+        let (sender, sender_connector) = new_sender::<Signal<Complex<i16>>>();
+        let cic = IntCicDecimator::<f32>::new(10, 16, 4, 1, true);
+        let (mut receiver, receiver_connector) = new_receiver::<Signal<Complex<f32>>>();
+        cic.feed_from(&sender_connector);
+        cic.feed_into(&receiver_connector);
+        sender
+            .send(Signal::Samples {
+                sample_rate: 1.6e6,
+                chunk: Chunk::from(vec![Complex::new(i16::MIN, 16384); 1600]),
+            })
+            .await
+            .unwrap();
+        let Signal::Samples { sample_rate, .. } = receiver.recv().await.unwrap()
+        else { panic!(); };
+        assert_eq!(sample_rate, 100000.0);
+        let Signal::Samples { chunk, .. } = receiver.recv().await.unwrap()
+        else { panic!(); };
+        for sample in chunk.iter() {
+            assert_eq!(*sample, Complex::new(-1.0, 0.5));
+        }
+    }
+    #[tokio::test]
+    async fn test_arbitrary_resampler() {
+        use std::f64::consts::TAU;
+        let (sender, sender_connector) = new_sender::<Signal<Complex<f64>>>();
+        let resampler = ArbitraryResampler::<f64>::new(4410, 44100.0);
+        let (mut receiver, receiver_connector) = new_receiver::<Signal<Complex<f64>>>();
+        resampler.feed_from(&sender_connector);
+        resampler.feed_into(&receiver_connector);
+        spawn(async move {
+            let mut offset: usize = 0;
+            loop {
+                let chunk: Vec<Complex<f64>> = (offset..offset + 4800)
+                    .map(|i| Complex::from_polar(1.0, TAU * 1000.0 * i as f64 / 48000.0))
+                    .collect();
+                offset += 4800;
+                let signal = Signal::Samples {
+                    sample_rate: 48000.0,
+                    chunk: Chunk::from(chunk),
+                };
+                let Ok(()) = sender.send(signal).await else { return; };
+            }
+        });
+        let check_increment = |chunk: &[Complex<f64>], expected: f64| {
+            let mut increment = 0.0;
+            for pair in chunk.windows(2) {
+                assert!((pair[0].norm() - 1.0).abs() < 1e-3);
+                increment += (pair[1] / pair[0]).arg();
+            }
+            increment /= (chunk.len() - 1) as f64;
+            assert!((increment - expected).abs() < expected * 1e-5);
+        };
+        for _ in 0..2 {
+            receiver.recv().await.unwrap();
+        }
+        let Signal::Samples { sample_rate, chunk } = receiver.recv().await.unwrap()
+        else { panic!(); };
+        assert_eq!(sample_rate, 44100.0);
+        assert_eq!(chunk.len(), 4410);
+        assert_approx(*resampler.ratio().borrow(), 44100.0 / 48000.0);
+        check_increment(&chunk, TAU * 1000.0 / 44100.0);
+        resampler.set_rate_error(1000.0);
+        for _ in 0..100 {
+            receiver.recv().await.unwrap();
+        }
+        let Signal::Samples { chunk, .. } = receiver.recv().await.unwrap()
+        else { panic!(); };
+        assert!((*resampler.ratio().borrow() * 48000.0 / 44100.0 - 1.0 / 1.001).abs() < 1e-6);
+        check_increment(&chunk, TAU * 1000.0 * 1.001 / 44100.0);
+    }
+    #[tokio::test]
+    async fn test_half_band() {
+        use std::f64::consts::TAU;
+        let (sender, sender_connector) = new_sender::<Signal<Complex<f64>>>();
+        let half_band = HalfBand::<f64>::with_stages(50, 8, 2);
+        let (mut receiver, receiver_connector) = new_receiver::<Signal<Complex<f64>>>();
+        half_band.feed_from(&sender_connector);
+        half_band.feed_into(&receiver_connector);
+        let tone = |freq: f64| -> Vec<Complex<f64>> {
+            (0..400)
+                .map(|i| Complex::from_polar(1.0, TAU * freq * i as f64 / 48000.0))
+                .collect()
+        };
+        for freq in [0.0, 2000.0, 20000.0] {
+            sender
+                .send(Signal::Samples {
+                    sample_rate: 48000.0,
+                    chunk: Chunk::from(tone(freq)),
+                })
+                .await
+                .unwrap();
+            receiver.recv().await.unwrap();
+            let Signal::Samples { sample_rate, chunk } = receiver.recv().await.unwrap()
+            else { panic!(); };
+            assert_eq!(sample_rate, 12000.0);
+            assert_eq!(chunk.len(), 50);
+            for sample in chunk.iter() {
+                match freq {
+                    f if f < 10000.0 => assert!((sample.norm() - 1.0).abs() < 1e-2),
+                    _ => assert!(sample.norm() < 1e-2),
+                }
+            }
+        }
+    }
+}
@@ -1,15 +1,46 @@
 //! Basic transformations
 
+use crate::blocks::analysis::Constellation;
+use crate::blocks::filters::design;
 use crate::bufferpool::*;
 use crate::flow::*;
 use crate::impl_block_trait;
 use crate::numbers::*;
 use crate::signal::*;
+use crate::simd;
+use crate::windowing::Hamming;
 
 use num::rational::Ratio;
 use tokio::sync::{mpsc, watch};
 use tokio::task::spawn;
 
+use std::collections::VecDeque;
+use std::sync::Arc;
+
+/// Types used for [`Signal::Event`]
+pub mod events {
+    use super::*;
+    /// Sent by [`Mixer`] when an input starts sending samples with a sample
+    /// rate different from the other inputs
+    ///
+    /// These samples are discarded.
+    #[derive(Clone, Debug)]
+    pub struct SampleRateMismatch {
+        /// Index of the input
+        pub input: usize,
+        /// Sample rate of the input
+        pub sample_rate: f64,
+        /// Sample rate of the output
+        pub expected: f64,
+    }
+    impl Event for SampleRateMismatch {
+        fn as_any(&self) -> &(dyn std::any::Any + Send + Sync) {
+            self
+        }
+    }
+}
+use events::*;
+
 /// Gain control
 ///
 /// Note that while this block works with generic [`Float`]s, the `gain` value
@@ -58,9 +89,8 @@ where
                             gain = flt!(gain_recv.borrow_and_update().clone())
                         }
                         let mut output_chunk = buf_pool.get_with_capacity(input_chunk.len());
-                        for sample in input_chunk.iter() {
-                            output_chunk.push(sample * gain);
-                        }
+                        output_chunk.extend_from_slice(&input_chunk);
+                        simd::scale(&mut output_chunk, gain);
                         let Ok(()) = sender
                             .send(Signal::Samples {
                                 sample_rate,
@@ -91,6 +121,132 @@ where
     }
 }
 
+/// Gain control with settings in decibels and smooth transitions
+///
+/// Like [`GainControl`], this block multiplies each sample with a gain.
+/// The gain may be specified linearly or in decibels, where a gain of
+/// `f64::NEG_INFINITY` decibels mutes the signal.
+///
+/// When the gain is changed, the applied gain ramps linearly from the old to
+/// the new value over the configured ramp time (see [`Gain::set_ramp_time`])
+/// to avoid audible clicks.
+pub struct Gain<Flt> {
+    receiver_connector: ReceiverConnector<Signal<Complex<Flt>>>,
+    sender_connector: SenderConnector<Signal<Complex<Flt>>>,
+    gain: watch::Sender<f64>,
+    ramp_time: watch::Sender<f64>,
+}
+
+impl_block_trait! { <Flt> Consumer<Signal<Complex<Flt>>> for Gain<Flt> }
+impl_block_trait! { <Flt> Producer<Signal<Complex<Flt>>> for Gain<Flt> }
+
+impl<Flt> Gain<Flt>
+where
+    Flt: Float,
+{
+    /// Create new `Gain` block with given linear `gain` and a ramp time of
+    /// 10 milliseconds
+    pub fn new(gain: f64) -> Self {
+        let (mut receiver, receiver_connector) = new_receiver::<Signal<Complex<Flt>>>();
+        let (sender, sender_connector) = new_sender::<Signal<Complex<Flt>>>();
+        let (gain_send, mut gain_recv) = watch::channel(gain);
+        let (ramp_time_send, ramp_time_recv) = watch::channel(0.01);
+        spawn(async move {
+            let mut buf_pool = ChunkBufPool::<Complex<Flt>>::new();
+            let mut target: Flt = flt!(gain);
+            let mut current: Flt = target;
+            let mut step: Flt = Flt::zero();
+            let mut remaining: usize = 0;
+            loop {
+                let Ok(signal) = receiver.recv().await else { return; };
+                match signal {
+                    Signal::Samples {
+                        sample_rate,
+                        chunk: input_chunk,
+                    } => {
+                        if gain_recv.has_changed().unwrap_or(false) {
+                            target = flt!(*gain_recv.borrow_and_update());
+                            let ramp_time = *ramp_time_recv.borrow();
+                            remaining = (ramp_time * sample_rate).round() as usize;
+                            if remaining == 0 {
+                                current = target;
+                            } else {
+                                step = (target - current) / flt!(remaining);
+                            }
+                        }
+                        let mut output_chunk = buf_pool.get_with_capacity(input_chunk.len());
+                        let ramp_len = remaining.min(input_chunk.len());
+                        for &sample in input_chunk[0..ramp_len].iter() {
+                            remaining -= 1;
+                            current = match remaining {
+                                0 => target,
+                                _ => current + step,
+                            };
+                            output_chunk.push(sample * current);
+                        }
+                        let offset = output_chunk.len();
+                        output_chunk.extend_from_slice(&input_chunk[ramp_len..]);
+                        simd::scale(&mut output_chunk[offset..], current);
+                        let Ok(()) = sender
+                            .send(Signal::Samples {
+                                sample_rate,
+                                chunk: output_chunk.finalize(),
+                            })
+                            .await
+                        else { return; };
+                    }
+                    event @ Signal::Event { .. } => {
+                        let Ok(()) = sender.send(event).await else { return; };
+                    }
+                }
+            }
+        });
+        Self {
+            receiver_connector,
+            sender_connector,
+            gain: gain_send,
+            ramp_time: ramp_time_send,
+        }
+    }
+    /// Create new `Gain` block with given gain in decibels
+    pub fn with_db(gain_db: f64) -> Self {
+        Self::new(db_to_linear(gain_db))
+    }
+    /// Get linear gain
+    pub fn gain(&self) -> f64 {
+        *self.gain.borrow()
+    }
+    /// Set linear gain
+    pub fn set_gain(&self, gain: f64) {
+        self.gain.send_replace(gain);
+    }
+    /// Get gain in decibels (`f64::NEG_INFINITY` if muted)
+    pub fn gain_db(&self) -> f64 {
+        amplitude_to_db(self.gain().abs())
+    }
+    /// Set gain in decibels (`f64::NEG_INFINITY` mutes the signal)
+    pub fn set_gain_db(&self, gain_db: f64) {
+        self.set_gain(db_to_linear(gain_db));
+    }
+    /// Get duration of the transition after a gain change in seconds
+    pub fn ramp_time(&self) -> f64 {
+        *self.ramp_time.borrow()
+    }
+    /// Set duration of the transition after a gain change in seconds
+    ///
+    /// A value of zero applies gain changes immediately.
+    pub fn set_ramp_time(&self, ramp_time: f64) {
+        assert!(ramp_time >= 0.0, "ramp time must not be negative");
+        self.ramp_time.send_replace(ramp_time);
+    }
+}
+
+/// Convert gain in decibels into linear gain
+fn db_to_linear(gain_db: f64) -> f64 {
+    assert!(!gain_db.is_nan(), "gain must not be NaN");
+    amplitude_from_db(gain_db)
+}
+
 /// Block which receives [`Signal<T>`] and applies a closure to every sample,
 /// e.g. to change amplitude or to apply a constant phase shift, before sending
 /// it further.
@@ -262,7 +418,105 @@ where
     }
 }
 
+/// Block which applies a closure to the samples of each received chunk,
+/// writing the results into an output chunk
+///
+/// The closure is called with the sample rate, the samples of the received
+/// chunk, and an (empty) output buffer taken from a [`ChunkBufPool`]. It may
+/// push any number of samples of a possibly different type into the output
+/// buffer, which is sent with the same sample rate afterwards (unless it is
+/// empty). As the closure is [`FnMut`], it may keep state across chunks,
+/// e.g. a filter history. Events are forwarded unchanged.
+///
+/// # Example
+///
+/// ```
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async move {
+/// use radiorust::{blocks::FnBlock, numbers::Complex};
+/// let mut phase = 0.0f32;
+/// let samples_to_phase_steps = FnBlock::<Complex<f32>, f32>::new(
+///     move |_sample_rate, input, output| {
+///         for sample in input {
+///             let next = sample.arg();
+///             output.push(next - phase);
+///             phase = next;
+///         }
+///     },
+/// );
+/// # });
+/// ```
+///
+/// See also [`MapSample`] for a block which applies a closure to every
+/// sample without changing the type.
+pub struct FnBlock<A, B = A> {
+    receiver_connector: ReceiverConnector<Signal<A>>,
+    sender_connector: SenderConnector<Signal<B>>,
+}
+
+impl<A, B> Consumer<Signal<A>> for FnBlock<A, B> {
+    fn receiver_connector(&self) -> &ReceiverConnector<Signal<A>> {
+        &self.receiver_connector
+    }
+}
+
+impl<A, B> Producer<Signal<B>> for FnBlock<A, B> {
+    fn sender_connector(&self) -> &SenderConnector<Signal<B>> {
+        &self.sender_connector
+    }
+}
+
+impl<A, B> FnBlock<A, B>
+where
+    A: Clone + Send + Sync + 'static,
+    B: Clone + Send + Sync + 'static,
+{
+    /// Creates a block which applies the given `closure` to each chunk
+    pub fn new<F>(mut closure: F) -> Self
+    where
+        F: FnMut(f64, &[A], &mut ChunkBuf<B>) + Send + 'static,
+    {
+        let (mut receiver, receiver_connector) = new_receiver::<Signal<A>>();
+        let (sender, sender_connector) = new_sender::<Signal<B>>();
+        spawn(async move {
+            let mut buf_pool = ChunkBufPool::<B>::new();
+            loop {
+                let Ok(signal) = receiver.recv().await else { return; };
+                match signal {
+                    Signal::Samples {
+                        sample_rate,
+                        chunk: input_chunk,
+                    } => {
+                        let mut output_chunk = buf_pool.get();
+                        closure(sample_rate, &input_chunk, &mut output_chunk);
+                        if output_chunk.is_empty() {
+                            continue;
+                        }
+                        let Ok(()) = sender
+                            .send(Signal::Samples {
+                                sample_rate,
+                                chunk: output_chunk.finalize(),
+                            })
+                            .await
+                        else { return; };
+                    }
+                    Signal::Event(event) => {
+                        let Ok(()) = sender.send(Signal::Event(event)).await else { return; };
+                    }
+                }
+            }
+        });
+        Self {
+            receiver_connector,
+            sender_connector,
+        }
+    }
+}
+
 /// Complex oscillator and mixer, which shifts all frequencies in an I/Q stream
+///
+/// Received [`CenterFrequency`] events are adjusted by the (quantized) shift
+/// and sent before the next chunk, e.g. a shift of +1 kHz lowers the center
+/// frequency by 1 kHz.
 pub struct FreqShifter<Flt> {
     receiver_connector: ReceiverConnector<Signal<Complex<Flt>>>,
     sender_connector: SenderConnector<Signal<Complex<Flt>>>,
@@ -307,6 +561,9 @@ where
             let mut phase_vec: Vec<Complex<Flt>> = Vec::new();
             let mut phase_idx: usize = 0;
             let mut prev_sample_rate: Option<f64> = None;
+            let mut actual_shift: f64 = 0.0;
+            let mut center_frequency: Option<f64> = None;
+            let mut announced_frequency: Option<f64> = None;
             let mut buf_pool = ChunkBufPool::<Complex<Flt>>::new();
             loop {
                 let Ok(signal) = receiver.recv().await else { return; };
@@ -328,6 +585,7 @@ where
                             let shift = shift_recv.borrow_and_update().clone();
                             let ratio: Ratio<isize> = freq_to_ratio(sample_rate, shift);
                             let (numer, denom): (isize, isize) = ratio.into();
+                            actual_shift = numer as f64 * sample_rate / denom as f64;
                             phase_vec.reserve(denom.try_into().unwrap());
                             let mut i: isize = 0;
                             for _ in 0..denom {
@@ -338,10 +596,29 @@ where
                                 i %= denom;
                             }
                         }
-                        let mut output_chunk = buf_pool.get_with_capacity(input_chunk.len());
-                        for sample in input_chunk.iter() {
-                            output_chunk.push(sample * phase_vec[phase_idx]);
-                            phase_idx += 1;
+                        if let Some(center_frequency) = center_frequency {
+                            let frequency = center_frequency - actual_shift;
+                            if announced_frequency != Some(frequency) {
+                                announced_frequency = Some(frequency);
+                                let Ok(()) = sender
+                                    .send(Signal::new_event(CenterFrequency(frequency)))
+                                    .await
+                                else { return; };
+                            }
+                        }
+                        let len = input_chunk.len();
+                        let mut output_chunk = buf_pool.get_with_capacity(len);
+                        output_chunk.resize(len, Complex::from(Flt::zero()));
+                        let mut pos: usize = 0;
+                        while pos < len {
+                            let n = (phase_vec.len() - phase_idx).min(len - pos);
+                            simd::complex_mul(
+                                &input_chunk[pos..pos + n],
+                                &phase_vec[phase_idx..phase_idx + n],
+                                &mut output_chunk[pos..pos + n],
+                            );
+                            pos += n;
+                            phase_idx += n;
                             if phase_idx == phase_vec.len() {
                                 phase_idx = 0;
                             }
@@ -354,9 +631,15 @@ where
                             .await
                         else { return; };
                     }
-                    event @ Signal::Event { .. } => {
-                        let Ok(()) = sender.send(event).await else { return; };
-                    }
+                    event @ Signal::Event { .. } => match event.center_frequency() {
+                        Some(frequency) => {
+                            center_frequency = Some(frequency);
+                            announced_frequency = None;
+                        }
+                        None => {
+                            let Ok(()) = sender.send(event).await else { return; };
+                        }
+                    },
                 }
             }
         });
@@ -390,28 +673,2627 @@ where
     }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    #[tokio::test]
-    async fn test_gain_control() {
-        let (sender, sender_connector) = new_sender::<Signal<Complex<f32>>>();
-        let attenuator = GainControl::new(0.25);
-        let (mut receiver, receiver_connector) = new_receiver::<Signal<Complex<f32>>>();
-        attenuator.feed_from(&sender_connector);
-        attenuator.feed_into(&receiver_connector);
-        sender
-            .send(Signal::Samples {
-                sample_rate: 48000.0,
-                chunk: Chunk::from(vec![Complex::new(32.0, -1.0), Complex::new(15.0, -2.0)]),
-            })
-            .await
-            .unwrap();
-        let Signal::Samples { chunk, .. } = receiver.recv().await.unwrap()
-        else { panic!(); };
-        assert_eq!(chunk[0].re, 8.0);
-        assert_eq!(chunk[0].im, -0.25);
-        assert_eq!(chunk[1].re, 3.75);
-        assert_eq!(chunk[1].im, -0.5);
+/// Automatic gain control
+///
+/// The block estimates the power of the received samples and applies a gain
+/// such that the RMS value of the output approaches `target_rms`. The power
+/// estimate follows rising input levels with the `attack` time constant and
+/// falling input levels with the `decay` time constant (both in seconds).
+///
+/// To avoid amplifying pure noise during silence, the gain is limited to a
+/// maximum value (see [`Agc::set_max_gain`]), which defaults to `1e6`.
+///
+/// The power estimate is reset when an [interrupting] event is received.
+///
+/// [interrupting]: Event::is_interrupt
+pub struct Agc<Flt> {
+    receiver_connector: ReceiverConnector<Signal<Complex<Flt>>>,
+    sender_connector: SenderConnector<Signal<Complex<Flt>>>,
+    max_gain: watch::Sender<f64>,
+    current_gain: watch::Receiver<f64>,
+}
+
+impl_block_trait! { <Flt> Consumer<Signal<Complex<Flt>>> for Agc<Flt> }
+impl_block_trait! { <Flt> Producer<Signal<Complex<Flt>>> for Agc<Flt> }
+
+impl<Flt> Agc<Flt>
+where
+    Flt: Float,
+{
+    /// Create new `Agc` block
+    pub fn new(target_rms: f64, attack: f64, decay: f64) -> Self {
+        assert!(target_rms > 0.0, "target RMS must be positive");
+        assert!(attack >= 0.0, "attack time must not be negative");
+        assert!(decay >= 0.0, "decay time must not be negative");
+        let (mut receiver, receiver_connector) = new_receiver::<Signal<Complex<Flt>>>();
+        let (sender, sender_connector) = new_sender::<Signal<Complex<Flt>>>();
+        let (max_gain_send, mut max_gain_recv) = watch::channel(1e6);
+        let (current_gain_send, current_gain) = watch::channel(1.0);
+        spawn(async move {
+            let mut buf_pool = ChunkBufPool::<Complex<Flt>>::new();
+            let mut powers: Vec<Flt> = Vec::new();
+            let target: Flt = flt!(target_rms);
+            let mut max_gain: Flt = flt!(1e6);
+            let mut power: Flt = Flt::zero();
+            let mut prev_sample_rate: Option<f64> = None;
+            let mut attack_coef: Flt = Flt::one();
+            let mut decay_coef: Flt = Flt::one();
+            loop {
+                let Ok(signal) = receiver.recv().await else { return; };
+                match signal {
+                    Signal::Samples {
+                        sample_rate,
+                        chunk: input_chunk,
+                    } => {
+                        if max_gain_recv.has_changed().unwrap_or(false) {
+                            max_gain = flt!(*max_gain_recv.borrow_and_update());
+                        }
+                        if Some(sample_rate) != prev_sample_rate {
+                            prev_sample_rate = Some(sample_rate);
+                            attack_coef = flt!(1.0 - (-(attack * sample_rate).recip()).exp());
+                            decay_coef = flt!(1.0 - (-(decay * sample_rate).recip()).exp());
+                        }
+                        let mut gain = Flt::one();
+                        powers.clear();
+                        powers.resize(input_chunk.len(), Flt::zero());
+                        simd::magnitude_squared(&input_chunk, &mut powers);
+                        let mut output_chunk = buf_pool.get_with_capacity(input_chunk.len());
+                        for (&sample, &sample_power) in input_chunk.iter().zip(powers.iter()) {
+                            let coef = if sample_power > power {
+                                attack_coef
+                            } else {
+                                decay_coef
+                            };
+                            power += (sample_power - power) * coef;
+                            gain = (target / power.sqrt()).min(max_gain);
+                            output_chunk.push(sample * gain);
+                        }
+                        current_gain_send.send_replace(gain.to_f64().unwrap());
+                        let Ok(()) = sender
+                            .send(Signal::Samples {
+                                sample_rate,
+                                chunk: output_chunk.finalize(),
+                            })
+                            .await
+                        else { return; };
+                    }
+                    Signal::Event(event) => {
+                        if event.is_interrupt() {
+                            power = Flt::zero();
+                        }
+                        let Ok(()) = sender.send(Signal::Event(event)).await else { return; };
+                    }
+                }
+            }
+        });
+        Self {
+            receiver_connector,
+            sender_connector,
+            max_gain: max_gain_send,
+            current_gain,
+        }
+    }
+    /// Get maximum gain
+    pub fn max_gain(&self) -> f64 {
+        *self.max_gain.borrow()
+    }
+    /// Set maximum gain
+    pub fn set_max_gain(&self, max_gain: f64) {
+        self.max_gain.send_replace(max_gain);
+    }
+    /// Get [`watch::Receiver`] of currently applied gain
+    ///
+    /// The value is updated once for every processed chunk.
+    pub fn current_gain(&self) -> watch::Receiver<f64> {
+        self.current_gain.clone()
+    }
+}
+
+/// Squelch, which mutes the signal when its power is below a threshold
+///
+/// The power is estimated by smoothing the squared magnitude of the samples
+/// with a time constant of 10 milliseconds and compared with the thresholds
+/// in decibels (where 0 dB corresponds to a power of `1.0`). To provide
+/// hysteresis, the squelch opens when the estimated power rises above
+/// `open_db` and closes when the estimated power stays below `close_db` for
+/// the duration of `hang_time` (in seconds).
+///
+/// When the squelch is closed, zeros are emitted instead of the received
+/// samples. An [interrupting] event resets the power estimate and closes the
+/// squelch.
+///
+/// [interrupting]: Event::is_interrupt
+pub struct Squelch<Flt> {
+    receiver_connector: ReceiverConnector<Signal<Complex<Flt>>>,
+    sender_connector: SenderConnector<Signal<Complex<Flt>>>,
+    thresholds: watch::Sender<(f64, f64)>,
+    open: watch::Receiver<bool>,
+}
+
+impl_block_trait! { <Flt> Consumer<Signal<Complex<Flt>>> for Squelch<Flt> }
+impl_block_trait! { <Flt> Producer<Signal<Complex<Flt>>> for Squelch<Flt> }
+
+impl<Flt> Squelch<Flt>
+where
+    Flt: Float,
+{
+    /// Create new `Squelch` block
+    pub fn new(open_db: f64, close_db: f64, hang_time: f64) -> Self {
+        assert!(
+            close_db <= open_db,
+            "close threshold must not exceed open threshold"
+        );
+        assert!(hang_time >= 0.0, "hang time must not be negative");
+        let (mut receiver, receiver_connector) = new_receiver::<Signal<Complex<Flt>>>();
+        let (sender, sender_connector) = new_sender::<Signal<Complex<Flt>>>();
+        let (thresholds_send, mut thresholds_recv) = watch::channel((open_db, close_db));
+        let (open_send, open) = watch::channel(false);
+        spawn(async move {
+            let to_power = |db: f64| -> Flt { flt!(from_db(db)) };
+            let mut buf_pool = ChunkBufPool::<Complex<Flt>>::new();
+            let mut open_power: Flt = to_power(open_db);
+            let mut close_power: Flt = to_power(close_db);
+            let mut power: Flt = Flt::zero();
+            let mut is_open = false;
+            let mut hang_remaining: usize = 0;
+            loop {
+                let Ok(signal) = receiver.recv().await else { return; };
+                match signal {
+                    Signal::Samples {
+                        sample_rate,
+                        chunk: input_chunk,
+                    } => {
+                        if thresholds_recv.has_changed().unwrap_or(false) {
+                            let (open_db, close_db) = *thresholds_recv.borrow_and_update();
+                            open_power = to_power(open_db);
+                            close_power = to_power(close_db);
+                        }
+                        let coef: Flt = flt!(1.0 - (-(0.01 * sample_rate).recip()).exp());
+                        let hang_len = (hang_time * sample_rate).round() as usize;
+                        let mut output_chunk = buf_pool.get_with_capacity(input_chunk.len());
+                        for &sample in input_chunk.iter() {
+                            power += (sample.norm_sqr() - power) * coef;
+                            if is_open {
+                                if power < close_power {
+                                    if hang_remaining == 0 {
+                                        is_open = false;
+                                    } else {
+                                        hang_remaining -= 1;
+                                    }
+                                } else {
+                                    hang_remaining = hang_len;
+                                }
+                            } else if power > open_power {
+                                is_open = true;
+                                hang_remaining = hang_len;
+                            }
+                            if is_open {
+                                output_chunk.push(sample);
+                            } else {
+                                output_chunk.push(Complex::from(Flt::zero()));
+                            }
+                        }
+                        open_send.send_if_modified(|open| {
+                            let modified = *open != is_open;
+                            *open = is_open;
+                            modified
+                        });
+                        let Ok(()) = sender
+                            .send(Signal::Samples {
+                                sample_rate,
+                                chunk: output_chunk.finalize(),
+                            })
+                            .await
+                        else { return; };
+                    }
+                    Signal::Event(event) => {
+                        if event.is_interrupt() {
+                            power = Flt::zero();
+                            is_open = false;
+                            hang_remaining = 0;
+                        }
+                        let Ok(()) = sender.send(Signal::Event(event)).await else { return; };
+                    }
+                }
+            }
+        });
+        Self {
+            receiver_connector,
+            sender_connector,
+            thresholds: thresholds_send,
+            open,
+        }
+    }
+    /// Get open and close thresholds in decibels
+    pub fn threshold_db(&self) -> (f64, f64) {
+        *self.thresholds.borrow()
+    }
+    /// Set open and close thresholds in decibels
+    pub fn set_threshold_db(&self, open_db: f64, close_db: f64) {
+        assert!(
+            close_db <= open_db,
+            "close threshold must not exceed open threshold"
+        );
+        self.thresholds.send_replace((open_db, close_db));
+    }
+    /// Get [`watch::Receiver`] indicating whether the squelch is open
+    ///
+    /// The value is updated after each processed chunk.
+    pub fn open(&self) -> watch::Receiver<bool> {
+        self.open.clone()
+    }
+}
+
+/// Mode of a [`Limiter`]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum LimiterMode {
+    /// Smoothly reduce the gain using lookahead, a soft knee, and release
+    Soft,
+    /// Clip the magnitude of each sample at the threshold
+    HardClip,
+}
+
+/// Limiter, which keeps the magnitude of the samples below a threshold
+///
+/// The threshold is given in decibels, where 0 dB corresponds to a magnitude
+/// of `1.0`. In [`LimiterMode::Soft`], the required gain reduction is
+/// determined with a soft knee of configurable width (defaulting to 6 dB)
+/// around the threshold. The signal is delayed by the `lookahead` time (in
+/// seconds), such that the gain can be reduced before peaks arrive. After
+/// peaks, the gain recovers with the `release` time constant (in seconds).
+///
+/// The delay is also applied in [`LimiterMode::HardClip`], such that
+/// switching modes doesn't cause discontinuities. When an [interrupting]
+/// event is received, the samples in the delay line are discarded and the
+/// gain is reset.
+///
+/// [interrupting]: Event::is_interrupt
+pub struct Limiter<Flt> {
+    receiver_connector: ReceiverConnector<Signal<Complex<Flt>>>,
+    sender_connector: SenderConnector<Signal<Complex<Flt>>>,
+    mode: watch::Sender<LimiterMode>,
+    thresholds: watch::Sender<(f64, f64)>,
+    reduction_db: watch::Receiver<f64>,
+}
+
+impl_block_trait! { <Flt> Consumer<Signal<Complex<Flt>>> for Limiter<Flt> }
+impl_block_trait! { <Flt> Producer<Signal<Complex<Flt>>> for Limiter<Flt> }
+
+impl<Flt> Limiter<Flt>
+where
+    Flt: Float,
+{
+    /// Create new `Limiter` block in [`LimiterMode::Soft`]
+    pub fn new(threshold_db: f64, lookahead: f64, release: f64) -> Self {
+        Self::with_mode(LimiterMode::Soft, threshold_db, lookahead, release)
+    }
+    /// Create new `Limiter` block in [`LimiterMode::HardClip`] without delay
+    pub fn hard_clip(threshold_db: f64) -> Self {
+        Self::with_mode(LimiterMode::HardClip, threshold_db, 0.0, 0.0)
+    }
+    /// Create new `Limiter` block with given mode
+    pub fn with_mode(mode: LimiterMode, threshold_db: f64, lookahead: f64, release: f64) -> Self {
+        assert!(lookahead >= 0.0, "lookahead must not be negative");
+        assert!(release >= 0.0, "release time must not be negative");
+        let (mut receiver, receiver_connector) = new_receiver::<Signal<Complex<Flt>>>();
+        let (sender, sender_connector) = new_sender::<Signal<Complex<Flt>>>();
+        let (mode_send, mode_recv) = watch::channel(mode);
+        let (thresholds_send, thresholds_recv) = watch::channel((threshold_db, 6.0));
+        let (reduction_db_send, reduction_db) = watch::channel(0.0);
+        spawn(async move {
+            let mut buf_pool = ChunkBufPool::<Complex<Flt>>::new();
+            let mut prev_sample_rate: Option<f64> = None;
+            let mut delay: VecDeque<Complex<Flt>> = VecDeque::new();
+            let mut minima: VecDeque<(u64, Flt)> = VecDeque::new();
+            let mut index: u64 = 0;
+            let mut lookahead_len: u64 = 0;
+            let mut release_coef: Flt = Flt::one();
+            let mut envelope: Flt = Flt::one();
+            loop {
+                let Ok(signal) = receiver.recv().await else { return; };
+                match signal {
+                    Signal::Samples {
+                        sample_rate,
+                        chunk: input_chunk,
+                    } => {
+                        if Some(sample_rate) != prev_sample_rate {
+                            prev_sample_rate = Some(sample_rate);
+                            lookahead_len = (lookahead * sample_rate).round() as u64;
+                            release_coef = match release * sample_rate {
+                                x if x > 0.0 => flt!(1.0 - (-x.recip()).exp()),
+                                _ => Flt::one(),
+                            };
+                            delay.clear();
+                            delay.resize(lookahead_len as usize, Complex::from(Flt::zero()));
+                            minima.clear();
+                        }
+                        let mode = *mode_recv.borrow();
+                        let (threshold_db, knee_db) = *thresholds_recv.borrow();
+                        let threshold: Flt = flt!(threshold_db);
+                        let knee: Flt = flt!(knee_db);
+                        let clip_level: Flt = flt!(amplitude_from_db(threshold_db));
+                        let mut min_gain = Flt::one();
+                        let mut output_chunk = buf_pool.get_with_capacity(input_chunk.len());
+                        for &sample in input_chunk.iter() {
+                            let level = amplitude_to_db(sample.norm());
+                            let overshoot = level - threshold;
+                            let reduction = if flt!(2) * overshoot < -knee {
+                                Flt::zero()
+                            } else if knee > Flt::zero() && flt!(2) * overshoot <= knee {
+                                let x = overshoot + knee / flt!(2);
+                                x * x / (flt!(2) * knee)
+                            } else {
+                                overshoot
+                            };
+                            let required = Flt::powf(flt!(10), -reduction / flt!(20));
+                            while let Some(&(_, value)) = minima.back() {
+                                if value < required {
+                                    break;
+                                }
+                                minima.pop_back();
+                            }
+                            minima.push_back((index, required));
+                            while minima.front().unwrap().0 + lookahead_len < index {
+                                minima.pop_front();
+                            }
+                            index += 1;
+                            let target = minima.front().unwrap().1;
+                            if target < envelope {
+                                envelope = target;
+                            } else {
+                                envelope += (target - envelope) * release_coef;
+                            }
+                            delay.push_back(sample);
+                            let delayed = delay.pop_front().unwrap();
+                            let gain = match mode {
+                                LimiterMode::Soft => envelope,
+                                LimiterMode::HardClip => {
+                                    let magnitude = delayed.norm();
+                                    match magnitude > clip_level {
+                                        true => clip_level / magnitude,
+                                        false => Flt::one(),
+                                    }
+                                }
+                            };
+                            min_gain = min_gain.min(gain);
+                            output_chunk.push(delayed * gain);
+                        }
+                        reduction_db_send
+                            .send_replace(-amplitude_to_db(min_gain.to_f64().unwrap()));
+                        let Ok(()) = sender
+                            .send(Signal::Samples {
+                                sample_rate,
+                                chunk: output_chunk.finalize(),
+                            })
+                            .await
+                        else { return; };
+                    }
+                    Signal::Event(event) => {
+                        if event.is_interrupt() {
+                            // reinitialize delay line with next samples
+                            prev_sample_rate = None;
+                            envelope = Flt::one();
+                        }
+                        let Ok(()) = sender.send(Signal::Event(event)).await else { return; };
+                    }
+                }
+            }
+        });
+        Self {
+            receiver_connector,
+            sender_connector,
+            mode: mode_send,
+            thresholds: thresholds_send,
+            reduction_db,
+        }
+    }
+    /// Get mode
+    pub fn mode(&self) -> LimiterMode {
+        *self.mode.borrow()
+    }
+    /// Set mode
+    pub fn set_mode(&self, mode: LimiterMode) {
+        self.mode.send_replace(mode);
+    }
+    /// Get threshold in decibels
+    pub fn threshold_db(&self) -> f64 {
+        self.thresholds.borrow().0
+    }
+    /// Set threshold in decibels
+    pub fn set_threshold_db(&self, threshold_db: f64) {
+        self.thresholds
+            .send_modify(|thresholds| thresholds.0 = threshold_db);
+    }
+    /// Get width of the soft knee in decibels
+    pub fn knee_db(&self) -> f64 {
+        self.thresholds.borrow().1
+    }
+    /// Set width of the soft knee in decibels
+    pub fn set_knee_db(&self, knee_db: f64) {
+        assert!(knee_db >= 0.0, "knee width must not be negative");
+        self.thresholds
+            .send_modify(|thresholds| thresholds.1 = knee_db);
+    }
+    /// Get [`watch::Receiver`] of the peak gain reduction in decibels
+    ///
+    /// The value is updated after each processed chunk and refers to the
+    /// strongest reduction within that chunk.
+    pub fn reduction_db(&self) -> watch::Receiver<f64> {
+        self.reduction_db.clone()
+    }
+}
+
+/// Spawn task which applies `op` to every sample while enabled
+///
+/// Used by [`IqSwap`] and [`Conjugate`].
+fn spawn_toggled_op<Flt: Float>(
+    mut receiver: Receiver<Signal<Complex<Flt>>>,
+    sender: Sender<Signal<Complex<Flt>>>,
+    enabled_recv: watch::Receiver<bool>,
+    op: fn(Complex<Flt>) -> Complex<Flt>,
+) {
+    spawn(async move {
+        let mut buf_pool = ChunkBufPool::<Complex<Flt>>::new();
+        loop {
+            let Ok(signal) = receiver.recv().await else { return; };
+            match signal {
+                Signal::Samples {
+                    sample_rate,
+                    chunk: input_chunk,
+                } if *enabled_recv.borrow() => {
+                    let mut output_chunk = buf_pool.get_with_capacity(input_chunk.len());
+                    output_chunk.extend(input_chunk.iter().copied().map(op));
+                    let Ok(()) = sender
+                        .send(Signal::Samples {
+                            sample_rate,
+                            chunk: output_chunk.finalize(),
+                        })
+                        .await
+                    else { return; };
+                }
+                signal => {
+                    let Ok(()) = sender.send(signal).await else { return; };
+                }
+            }
+        }
+    });
+}
+
+/// Block which swaps the real (I) and imaginary (Q) part of each sample
+///
+/// Swapping I and Q mirrors the spectrum around the center frequency, which
+/// corrects captures with reversed sidebands. When disabled, the signal is
+/// passed unchanged.
+pub struct IqSwap<Flt> {
+    receiver_connector: ReceiverConnector<Signal<Complex<Flt>>>,
+    sender_connector: SenderConnector<Signal<Complex<Flt>>>,
+    enabled: watch::Sender<bool>,
+}
+
+impl_block_trait! { <Flt> Consumer<Signal<Complex<Flt>>> for IqSwap<Flt> }
+impl_block_trait! { <Flt> Producer<Signal<Complex<Flt>>> for IqSwap<Flt> }
+
+impl<Flt> IqSwap<Flt>
+where
+    Flt: Float,
+{
+    /// Create new (enabled) `IqSwap` block
+    pub fn new() -> Self {
+        let (receiver, receiver_connector) = new_receiver::<Signal<Complex<Flt>>>();
+        let (sender, sender_connector) = new_sender::<Signal<Complex<Flt>>>();
+        let (enabled_send, enabled_recv) = watch::channel(true);
+        spawn_toggled_op(receiver, sender, enabled_recv, |sample| {
+            Complex::new(sample.im, sample.re)
+        });
+        Self {
+            receiver_connector,
+            sender_connector,
+            enabled: enabled_send,
+        }
+    }
+    /// Get whether I and Q are swapped
+    pub fn enabled(&self) -> bool {
+        *self.enabled.borrow()
+    }
+    /// Enable or disable swapping of I and Q
+    pub fn set_enabled(&self, enabled: bool) {
+        self.enabled.send_replace(enabled);
+    }
+}
+
+/// Block which replaces each sample with its complex conjugate (negating the
+/// imaginary part)
+///
+/// Like [`IqSwap`], conjugation mirrors the spectrum around the center
+/// frequency. When disabled, the signal is passed unchanged.
+pub struct Conjugate<Flt> {
+    receiver_connector: ReceiverConnector<Signal<Complex<Flt>>>,
+    sender_connector: SenderConnector<Signal<Complex<Flt>>>,
+    enabled: watch::Sender<bool>,
+}
+
+impl_block_trait! { <Flt> Consumer<Signal<Complex<Flt>>> for Conjugate<Flt> }
+impl_block_trait! { <Flt> Producer<Signal<Complex<Flt>>> for Conjugate<Flt> }
+
+impl<Flt> Conjugate<Flt>
+where
+    Flt: Float,
+{
+    /// Create new (enabled) `Conjugate` block
+    pub fn new() -> Self {
+        let (receiver, receiver_connector) = new_receiver::<Signal<Complex<Flt>>>();
+        let (sender, sender_connector) = new_sender::<Signal<Complex<Flt>>>();
+        let (enabled_send, enabled_recv) = watch::channel(true);
+        spawn_toggled_op(receiver, sender, enabled_recv, |sample| sample.conj());
+        Self {
+            receiver_connector,
+            sender_connector,
+            enabled: enabled_send,
+        }
+    }
+    /// Get whether samples are conjugated
+    pub fn enabled(&self) -> bool {
+        *self.enabled.borrow()
+    }
+    /// Enable or disable conjugation
+    pub fn set_enabled(&self, enabled: bool) {
+        self.enabled.send_replace(enabled);
+    }
+}
+
+/// Part of a complex sample extracted by [`ComplexToReal`]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ComplexToRealMode {
+    /// Real part
+    Real,
+    /// Imaginary part
+    Imaginary,
+    /// Magnitude (absolute value)
+    Magnitude,
+}
+
+/// Block which converts complex samples into real samples
+///
+/// The output samples have an imaginary part of zero.
+pub struct ComplexToReal<Flt> {
+    receiver_connector: ReceiverConnector<Signal<Complex<Flt>>>,
+    sender_connector: SenderConnector<Signal<Complex<Flt>>>,
+}
+
+impl_block_trait! { <Flt> Consumer<Signal<Complex<Flt>>> for ComplexToReal<Flt> }
+impl_block_trait! { <Flt> Producer<Signal<Complex<Flt>>> for ComplexToReal<Flt> }
+
+impl<Flt> ComplexToReal<Flt>
+where
+    Flt: Float,
+{
+    /// Create new `ComplexToReal` block which extracts the real part
+    pub fn new() -> Self {
+        Self::with_mode(ComplexToRealMode::Real)
+    }
+    /// Create new `ComplexToReal` block with given mode
+    pub fn with_mode(mode: ComplexToRealMode) -> Self {
+        let (mut receiver, receiver_connector) = new_receiver::<Signal<Complex<Flt>>>();
+        let (sender, sender_connector) = new_sender::<Signal<Complex<Flt>>>();
+        spawn(async move {
+            let mut buf_pool = ChunkBufPool::<Complex<Flt>>::new();
+            loop {
+                let Ok(signal) = receiver.recv().await else { return; };
+                match signal {
+                    Signal::Samples {
+                        sample_rate,
+                        chunk: input_chunk,
+                    } => {
+                        let mut output_chunk = buf_pool.get_with_capacity(input_chunk.len());
+                        output_chunk.extend(input_chunk.iter().map(|sample| {
+                            Complex::from(match mode {
+                                ComplexToRealMode::Real => sample.re,
+                                ComplexToRealMode::Imaginary => sample.im,
+                                ComplexToRealMode::Magnitude => sample.norm(),
+                            })
+                        }));
+                        let Ok(()) = sender
+                            .send(Signal::Samples {
+                                sample_rate,
+                                chunk: output_chunk.finalize(),
+                            })
+                            .await
+                        else { return; };
+                    }
+                    event @ Signal::Event { .. } => {
+                        let Ok(()) = sender.send(event).await else { return; };
+                    }
+                }
+            }
+        });
+        Self {
+            receiver_connector,
+            sender_connector,
+        }
+    }
+}
+
+/// Block which converts real samples into an analytic signal
+///
+/// The real part of the received samples is passed through a Hilbert
+/// transformer (see [`design::hilbert`]) to obtain the imaginary part of the
+/// output, while the real part is delayed by the same amount (`num_taps / 2`
+/// samples). Frequencies near DC and near the Nyquist frequency are
+/// attenuated in the imaginary part.
+///
+/// The history is cleared when an [interrupting] event is received.
+///
+/// [interrupting]: Event::is_interrupt
+pub struct RealToComplex<Flt> {
+    receiver_connector: ReceiverConnector<Signal<Complex<Flt>>>,
+    sender_connector: SenderConnector<Signal<Complex<Flt>>>,
+}
+
+impl_block_trait! { <Flt> Consumer<Signal<Complex<Flt>>> for RealToComplex<Flt> }
+impl_block_trait! { <Flt> Producer<Signal<Complex<Flt>>> for RealToComplex<Flt> }
+
+impl<Flt> RealToComplex<Flt>
+where
+    Flt: Float,
+{
+    /// Create new `RealToComplex` block with a Hilbert transformer of given
+    /// (odd) number of taps
+    pub fn new(num_taps: usize) -> Self {
+        let coeffs: Vec<Flt> = design::hilbert(num_taps, &Hamming);
+        let (mut receiver, receiver_connector) = new_receiver::<Signal<Complex<Flt>>>();
+        let (sender, sender_connector) = new_sender::<Signal<Complex<Flt>>>();
+        spawn(async move {
+            let mut buf_pool = ChunkBufPool::<Complex<Flt>>::new();
+            let history_len = num_taps - 1;
+            let mut extended: Vec<Flt> = vec![Flt::zero(); history_len];
+            loop {
+                let Ok(signal) = receiver.recv().await else { return; };
+                match signal {
+                    Signal::Samples {
+                        sample_rate,
+                        chunk: input_chunk,
+                    } => {
+                        extended.extend(input_chunk.iter().map(|sample| sample.re));
+                        let mut output_chunk = buf_pool.get_with_capacity(input_chunk.len());
+                        for i in 0..input_chunk.len() {
+                            let window = &extended[i..=i + history_len];
+                            let mut im: Flt = Flt::zero();
+                            for (&x, &h) in window.iter().rev().zip(coeffs.iter()) {
+                                im += x * h;
+                            }
+                            output_chunk.push(Complex::new(window[num_taps / 2], im));
+                        }
+                        extended.drain(0..input_chunk.len());
+                        let Ok(()) = sender
+                            .send(Signal::Samples {
+                                sample_rate,
+                                chunk: output_chunk.finalize(),
+                            })
+                            .await
+                        else { return; };
+                    }
+                    Signal::Event(event) => {
+                        if event.is_interrupt() {
+                            for x in extended.iter_mut() {
+                                *x = Flt::zero();
+                            }
+                        }
+                        let Ok(()) = sender.send(Signal::Event(event)).await
+                        else { return; };
+                    }
+                }
+            }
+        });
+        Self {
+            receiver_connector,
+            sender_connector,
+        }
+    }
+}
+
+/// Spawn task which applies `convert` to every sample
+///
+/// Used by [`Convert`] and [`ConvertReal`].
+fn spawn_convert<T, U>(
+    mut receiver: Receiver<Signal<T>>,
+    sender: Sender<Signal<U>>,
+    convert: fn(&T) -> U,
+) where
+    T: Clone + Send + Sync + 'static,
+    U: Clone + Send + Sync + 'static,
+{
+    spawn(async move {
+        let mut buf_pool = ChunkBufPool::<U>::new();
+        loop {
+            let Ok(signal) = receiver.recv().await else { return; };
+            match signal {
+                Signal::Samples {
+                    sample_rate,
+                    chunk: input_chunk,
+                } => {
+                    let mut output_chunk = buf_pool.get_with_capacity(input_chunk.len());
+                    output_chunk.extend(input_chunk.iter().map(convert));
+                    let Ok(()) = sender
+                        .send(Signal::Samples {
+                            sample_rate,
+                            chunk: output_chunk.finalize(),
+                        })
+                        .await
+                    else { return; };
+                }
+                Signal::Event(event) => {
+                    let Ok(()) = sender.send(Signal::Event(event)).await else { return; };
+                }
+            }
+        }
+    });
+}
+
+/// Convert [`Float`] `x` of type `A` to type `B`
+fn convert_float<A: Float, B: Float>(x: A) -> B {
+    <B as num::NumCast>::from(x).unwrap()
+}
+
+/// Block which converts complex samples from one [`Float`] type to another
+///
+/// This allows to mix precision domains, e.g. to receive `Complex<f32>`
+/// samples from hardware and process them with `f64` precision, or vice
+/// versa. Events are passed unchanged.
+///
+/// See [`ConvertReal`] for converting real samples.
+///
+/// # Example
+///
+/// ```
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async move {
+/// use radiorust::blocks::transform::Convert;
+/// let to_double_precision = Convert::<f32, f64>::new();
+/// # });
+/// ```
+pub struct Convert<A, B> {
+    receiver_connector: ReceiverConnector<Signal<Complex<A>>>,
+    sender_connector: SenderConnector<Signal<Complex<B>>>,
+}
+
+impl<A, B> Consumer<Signal<Complex<A>>> for Convert<A, B> {
+    fn receiver_connector(&self) -> &ReceiverConnector<Signal<Complex<A>>> {
+        &self.receiver_connector
+    }
+}
+
+impl<A, B> Producer<Signal<Complex<B>>> for Convert<A, B> {
+    fn sender_connector(&self) -> &SenderConnector<Signal<Complex<B>>> {
+        &self.sender_connector
+    }
+}
+
+impl<A, B> Convert<A, B>
+where
+    A: Float,
+    B: Float,
+{
+    /// Create new `Convert` block
+    pub fn new() -> Self {
+        let (receiver, receiver_connector) = new_receiver::<Signal<Complex<A>>>();
+        let (sender, sender_connector) = new_sender::<Signal<Complex<B>>>();
+        spawn_convert(receiver, sender, |sample| {
+            Complex::new(convert_float(sample.re), convert_float(sample.im))
+        });
+        Self {
+            receiver_connector,
+            sender_connector,
+        }
+    }
+}
+
+/// Convert complex integer sample to a [`Float`] with full scale
+/// corresponding to `1.0`
+fn convert_int<I, Flt>(x: &Complex<I>) -> Complex<Flt>
+where
+    I: num::PrimInt + num::Signed,
+    Flt: Float,
+{
+    let scale: Flt = (flt!(I::max_value()) + Flt::one()).recip();
+    Complex::new(flt!(x.re) * scale, flt!(x.im) * scale)
+}
+
+/// Block which converts complex samples from a signed integer type (e.g.
+/// [`i16`] as read by a `SoapySdrRx` in `CS16` format) to a [`Float`]
+/// type
+///
+/// The samples are scaled such that full scale of the integer type
+/// corresponds to `1.0` (e.g. `-32768` is converted to `-1.0`). Events are
+/// passed unchanged.
+pub struct ConvertInt<I, Flt> {
+    receiver_connector: ReceiverConnector<Signal<Complex<I>>>,
+    sender_connector: SenderConnector<Signal<Complex<Flt>>>,
+}
+
+impl<I, Flt> Consumer<Signal<Complex<I>>> for ConvertInt<I, Flt> {
+    fn receiver_connector(&self) -> &ReceiverConnector<Signal<Complex<I>>> {
+        &self.receiver_connector
+    }
+}
+
+impl<I, Flt> Producer<Signal<Complex<Flt>>> for ConvertInt<I, Flt> {
+    fn sender_connector(&self) -> &SenderConnector<Signal<Complex<Flt>>> {
+        &self.sender_connector
+    }
+}
+
+impl<I, Flt> ConvertInt<I, Flt>
+where
+    I: num::PrimInt + num::Signed + Send + Sync + 'static,
+    Flt: Float,
+{
+    /// Create new `ConvertInt` block
+    pub fn new() -> Self {
+        let (receiver, receiver_connector) = new_receiver::<Signal<Complex<I>>>();
+        let (sender, sender_connector) = new_sender::<Signal<Complex<Flt>>>();
+        spawn_convert(receiver, sender, convert_int::<I, Flt>);
+        Self {
+            receiver_connector,
+            sender_connector,
+        }
+    }
+}
+
+/// Block which converts real samples from one [`Float`] type to another
+///
+/// Same as [`Convert`], but for [`Signal<A>`] instead of
+/// `Signal<Complex<A>>`.
+pub struct ConvertReal<A, B> {
+    receiver_connector: ReceiverConnector<Signal<A>>,
+    sender_connector: SenderConnector<Signal<B>>,
+}
+
+impl<A, B> Consumer<Signal<A>> for ConvertReal<A, B> {
+    fn receiver_connector(&self) -> &ReceiverConnector<Signal<A>> {
+        &self.receiver_connector
+    }
+}
+
+impl<A, B> Producer<Signal<B>> for ConvertReal<A, B> {
+    fn sender_connector(&self) -> &SenderConnector<Signal<B>> {
+        &self.sender_connector
+    }
+}
+
+impl<A, B> ConvertReal<A, B>
+where
+    A: Float,
+    B: Float,
+{
+    /// Create new `ConvertReal` block
+    pub fn new() -> Self {
+        let (receiver, receiver_connector) = new_receiver::<Signal<A>>();
+        let (sender, sender_connector) = new_sender::<Signal<B>>();
+        spawn_convert(receiver, sender, |&sample| convert_float(sample));
+        Self {
+            receiver_connector,
+            sender_connector,
+        }
+    }
+}
+
+/// Phase detector of a [`CostasLoop`], depending on the modulation of the
+/// carrier
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum PhaseDetector {
+    /// Unmodulated carrier (plain phase-locked loop)
+    Carrier,
+    /// Binary phase-shift keying (two phase states)
+    Bpsk,
+    /// Quadrature phase-shift keying (four phase states)
+    Qpsk,
+}
+
+impl PhaseDetector {
+    /// Number of phase states
+    pub fn order(self) -> u32 {
+        match self {
+            PhaseDetector::Carrier => 1,
+            PhaseDetector::Bpsk => 2,
+            PhaseDetector::Qpsk => 4,
+        }
+    }
+    /// Phase error in radians of a sample which has already been corrected
+    fn error(self, sample: Complex<f64>) -> f64 {
+        let magnitude = sample.norm();
+        if magnitude == 0.0 {
+            return 0.0;
+        }
+        match self {
+            PhaseDetector::Carrier => sample.arg(),
+            PhaseDetector::Bpsk => sample.re.signum() * sample.im / magnitude,
+            PhaseDetector::Qpsk => {
+                (sample.re.signum() * sample.im - sample.im.signum() * sample.re)
+                    / (std::f64::consts::SQRT_2 * magnitude)
+            }
+        }
+    }
+    /// Value between `-1.0` and `1.0`, which is `1.0` if a sample (that has
+    /// already been corrected) has no phase error
+    fn lock_level(self, sample: Complex<f64>) -> f64 {
+        let magnitude = sample.norm();
+        if magnitude == 0.0 {
+            return 0.0;
+        }
+        let sample = sample / magnitude;
+        match self {
+            PhaseDetector::Carrier => sample.re,
+            PhaseDetector::Bpsk => sample.powi(2).re,
+            PhaseDetector::Qpsk => -sample.powi(4).re,
+        }
+    }
+}
+
+/// Carrier recovery block using a Costas loop (or plain phase-locked loop)
+///
+/// A numerically controlled oscillator (NCO) is locked to the residual
+/// carrier of the signal, which is then removed, i.e. the output is the
+/// phase-corrected signal. The loop is a second order loop with a damping
+/// factor of `1/√2` and a configurable (noise) bandwidth in hertz. The state
+/// of the NCO is kept across chunks and reset upon [interruption].
+///
+/// For [`PhaseDetector::Bpsk`] and [`PhaseDetector::Qpsk`], the phase of the
+/// output has an ambiguity of 180° or 90°, respectively, which must be
+/// resolved by the following stages (e.g. with differential coding or a sync
+/// word).
+///
+/// [interruption]: Event::is_interrupt
+pub struct CostasLoop<Flt> {
+    receiver_connector: ReceiverConnector<Signal<Complex<Flt>>>,
+    sender_connector: SenderConnector<Signal<Complex<Flt>>>,
+    loop_bandwidth: watch::Sender<f64>,
+    frequency: watch::Receiver<f64>,
+    phase_error: watch::Receiver<f64>,
+    locked: watch::Receiver<bool>,
+}
+
+impl_block_trait! { <Flt> Consumer<Signal<Complex<Flt>>> for CostasLoop<Flt> }
+impl_block_trait! { <Flt> Producer<Signal<Complex<Flt>>> for CostasLoop<Flt> }
+
+impl<Flt> CostasLoop<Flt>
+where
+    Flt: Float,
+{
+    const LOCK_THRESHOLD: f64 = 0.8;
+    const UNLOCK_THRESHOLD: f64 = 0.6;
+    /// Create new `CostasLoop` with given [`PhaseDetector`] and loop bandwidth
+    /// in hertz
+    pub fn new(detector: PhaseDetector, loop_bandwidth: f64) -> Self {
+        use std::f64::consts::TAU;
+        assert!(loop_bandwidth > 0.0, "loop bandwidth must be positive");
+        let (mut receiver, receiver_connector) = new_receiver::<Signal<Complex<Flt>>>();
+        let (sender, sender_connector) = new_sender::<Signal<Complex<Flt>>>();
+        let (loop_bandwidth_send, mut loop_bandwidth_recv) = watch::channel(loop_bandwidth);
+        let (frequency_send, frequency) = watch::channel(0.0);
+        let (phase_error_send, phase_error) = watch::channel(0.0);
+        let (locked_send, locked) = watch::channel(false);
+        spawn(async move {
+            let mut buf_pool = ChunkBufPool::<Complex<Flt>>::new();
+            let mut loop_bandwidth = loop_bandwidth;
+            let mut prev_sample_rate: Option<f64> = None;
+            // phase and frequency of NCO in radians (per sample)
+            let mut phase: f64 = 0.0;
+            let mut freq: f64 = 0.0;
+            let mut error_power: f64 = 0.0;
+            let mut lock_level: f64 = 0.0;
+            let mut is_locked = false;
+            loop {
+                let Ok(signal) = receiver.recv().await else { return; };
+                match signal {
+                    Signal::Samples {
+                        sample_rate,
+                        chunk: input_chunk,
+                    } => {
+                        if loop_bandwidth_recv.has_changed().unwrap_or(false) {
+                            loop_bandwidth = *loop_bandwidth_recv.borrow_and_update();
+                        }
+                        if let Some(prev_sample_rate) = prev_sample_rate {
+                            freq *= prev_sample_rate / sample_rate;
+                        }
+                        prev_sample_rate = Some(sample_rate);
+                        // natural frequency for noise bandwidth
+                        // `B = omega_n * (zeta + 1 / (4 * zeta)) / 2`
+                        let zeta = std::f64::consts::FRAC_1_SQRT_2;
+                        let omega_n = 2.0 * loop_bandwidth / sample_rate / (zeta + 0.25 / zeta);
+                        let alpha = 2.0 * zeta * omega_n;
+                        let beta = omega_n * omega_n;
+                        let lock_coef = 1.0 - (-loop_bandwidth / sample_rate).exp();
+                        let mut output_chunk = buf_pool.get_with_capacity(input_chunk.len());
+                        for &sample in input_chunk.iter() {
+                            let (sin, cos) = (-phase).sin_cos();
+                            let output = sample * Complex::new(flt!(cos), flt!(sin));
+                            output_chunk.push(output);
+                            let output = Complex::new(
+                                output.re.to_f64().unwrap(),
+                                output.im.to_f64().unwrap(),
+                            );
+                            let error = detector.error(output);
+                            freq += beta * error;
+                            phase = (phase + freq + alpha * error) % TAU;
+                            error_power += (error * error - error_power) * lock_coef;
+                            lock_level += (detector.lock_level(output) - lock_level) * lock_coef;
+                        }
+                        if is_locked {
+                            is_locked = lock_level > Self::UNLOCK_THRESHOLD;
+                        } else {
+                            is_locked = lock_level > Self::LOCK_THRESHOLD;
+                        }
+                        frequency_send.send_replace(freq * sample_rate / TAU);
+                        phase_error_send.send_replace(error_power.sqrt());
+                        locked_send.send_if_modified(|locked| {
+                            let modified = *locked != is_locked;
+                            *locked = is_locked;
+                            modified
+                        });
+                        let Ok(()) = sender
+                            .send(Signal::Samples {
+                                sample_rate,
+                                chunk: output_chunk.finalize(),
+                            })
+                            .await
+                        else { return; };
+                    }
+                    Signal::Event(event) => {
+                        if event.is_interrupt() {
+                            phase = 0.0;
+                            freq = 0.0;
+                            error_power = 0.0;
+                            lock_level = 0.0;
+                        }
+                        let Ok(()) = sender.send(Signal::Event(event)).await
+                        else { return; };
+                    }
+                }
+            }
+        });
+        Self {
+            receiver_connector,
+            sender_connector,
+            loop_bandwidth: loop_bandwidth_send,
+            frequency,
+            phase_error,
+            locked,
+        }
+    }
+    /// Get loop bandwidth in hertz
+    pub fn loop_bandwidth(&self) -> f64 {
+        *self.loop_bandwidth.borrow()
+    }
+    /// Set loop bandwidth in hertz
+    pub fn set_loop_bandwidth(&self, loop_bandwidth: f64) {
+        assert!(loop_bandwidth > 0.0, "loop bandwidth must be positive");
+        self.loop_bandwidth.send_replace(loop_bandwidth);
+    }
+    /// Get [`watch::Receiver`] of the estimated carrier frequency offset in
+    /// hertz
+    ///
+    /// The value is updated after each processed chunk.
+    pub fn frequency(&self) -> watch::Receiver<f64> {
+        self.frequency.clone()
+    }
+    /// Get [`watch::Receiver`] of the smoothed RMS phase error in radians
+    ///
+    /// The value is updated after each processed chunk.
+    pub fn phase_error(&self) -> watch::Receiver<f64> {
+        self.phase_error.clone()
+    }
+    /// Get [`watch::Receiver`] indicating whether the loop is locked
+    ///
+    /// The value is updated after each processed chunk.
+    pub fn locked(&self) -> watch::Receiver<bool> {
+        self.locked.clone()
+    }
+}
+
+/// Adaptation algorithm of an [`Equalizer`]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum EqualizerAlgorithm {
+    /// Decision-directed least mean squares, where the error is the distance
+    /// to the nearest point of the given [`Constellation`]
+    Lms(Constellation),
+    /// Constant modulus algorithm (blind), which drives the magnitude of the
+    /// output towards `1.0`
+    Cma,
+}
+
+/// Adaptive FIR equalizer operating on symbols (one sample per symbol)
+///
+/// The input is expected to be the output of a [`SymbolSync`] block, scaled
+/// to unit average power (e.g. with an [`Agc`] block). For
+/// [`EqualizerAlgorithm::Lms`], the carrier phase must be recovered already
+/// (e.g. with a [`CostasLoop`]), while [`EqualizerAlgorithm::Cma`] is
+/// insensitive to the phase (and also won't correct it).
+///
+/// The taps are initialized with a single center tap, i.e. the output is
+/// delayed by `num_taps / 2` symbols. The taps are kept across chunks, while
+/// the history of symbols is cleared upon [interruption].
+///
+/// [`SymbolSync`]: crate::blocks::modulation::SymbolSync
+/// [interruption]: Event::is_interrupt
+pub struct Equalizer<Flt> {
+    receiver_connector: ReceiverConnector<Signal<Complex<Flt>>>,
+    sender_connector: SenderConnector<Signal<Complex<Flt>>>,
+    num_taps: usize,
+    step_size: watch::Sender<f64>,
+    taps: watch::Receiver<Arc<[Complex<Flt>]>>,
+    mse: watch::Receiver<f64>,
+}
+
+impl_block_trait! { <Flt> Consumer<Signal<Complex<Flt>>> for Equalizer<Flt> }
+impl_block_trait! { <Flt> Producer<Signal<Complex<Flt>>> for Equalizer<Flt> }
+
+impl<Flt> Equalizer<Flt>
+where
+    Flt: Float,
+{
+    /// Time constant (in symbols) for smoothing the mean squared error
+    const MSE_SYMBOLS: f64 = 100.0;
+    /// Create new `Equalizer` with given algorithm, number of taps, and step
+    /// size (e.g. `0.01`)
+    pub fn new(algorithm: EqualizerAlgorithm, num_taps: usize, step_size: f64) -> Self {
+        assert!(num_taps > 0, "number of taps must be positive");
+        let (mut receiver, receiver_connector) = new_receiver::<Signal<Complex<Flt>>>();
+        let (sender, sender_connector) = new_sender::<Signal<Complex<Flt>>>();
+        let (step_size_send, mut step_size_recv) = watch::channel(step_size);
+        let mut weights: Vec<Complex<f64>> = vec![Complex::from(0.0); num_taps];
+        weights[num_taps / 2] = Complex::from(1.0);
+        let to_flt = |weights: &[Complex<f64>]| -> Arc<[Complex<Flt>]> {
+            weights
+                .iter()
+                .map(|w| Complex::new(flt!(w.re), flt!(w.im)))
+                .collect()
+        };
+        let (taps_send, taps) = watch::channel(to_flt(&weights));
+        let (mse_send, mse) = watch::channel(f64::NAN);
+        spawn(async move {
+            let mut buf_pool = ChunkBufPool::<Complex<Flt>>::new();
+            let mut step_size = step_size;
+            // most recent symbol first
+            let mut history: VecDeque<Complex<f64>> = vec![Complex::from(0.0); num_taps].into();
+            let mut mse: f64 = f64::NAN;
+            loop {
+                let Ok(signal) = receiver.recv().await else { return; };
+                match signal {
+                    Signal::Samples {
+                        sample_rate,
+                        chunk: input_chunk,
+                    } => {
+                        if step_size_recv.has_changed().unwrap_or(false) {
+                            step_size = *step_size_recv.borrow_and_update();
+                        }
+                        let mut output_chunk = buf_pool.get_with_capacity(input_chunk.len());
+                        for &sample in input_chunk.iter() {
+                            history.pop_back();
+                            history.push_front(Complex::new(
+                                sample.re.to_f64().unwrap(),
+                                sample.im.to_f64().unwrap(),
+                            ));
+                            let mut output: Complex<f64> = Complex::from(0.0);
+                            for (w, x) in weights.iter().zip(history.iter()) {
+                                output += w * x;
+                            }
+                            output_chunk.push(Complex::new(flt!(output.re), flt!(output.im)));
+                            let error = match algorithm {
+                                EqualizerAlgorithm::Lms(constellation) => {
+                                    constellation.nearest(output) - output
+                                }
+                                EqualizerAlgorithm::Cma => output * (1.0 - output.norm_sqr()),
+                            };
+                            for (w, x) in weights.iter_mut().zip(history.iter()) {
+                                *w += error * x.conj() * step_size;
+                            }
+                            if mse.is_nan() {
+                                mse = error.norm_sqr();
+                            } else {
+                                mse += (error.norm_sqr() - mse) / Self::MSE_SYMBOLS;
+                            }
+                        }
+                        taps_send.send_replace(to_flt(&weights));
+                        mse_send.send_replace(mse);
+                        let Ok(()) = sender
+                            .send(Signal::Samples {
+                                sample_rate,
+                                chunk: output_chunk.finalize(),
+                            })
+                            .await
+                        else { return; };
+                    }
+                    Signal::Event(event) => {
+                        if event.is_interrupt() {
+                            for x in history.iter_mut() {
+                                *x = Complex::from(0.0);
+                            }
+                        }
+                        let Ok(()) = sender.send(Signal::Event(event)).await
+                        else { return; };
+                    }
+                }
+            }
+        });
+        Self {
+            receiver_connector,
+            sender_connector,
+            num_taps,
+            step_size: step_size_send,
+            taps,
+            mse,
+        }
+    }
+    /// Number of taps
+    pub fn num_taps(&self) -> usize {
+        self.num_taps
+    }
+    /// Get step size
+    pub fn step_size(&self) -> f64 {
+        *self.step_size.borrow()
+    }
+    /// Set step size
+    pub fn set_step_size(&self, step_size: f64) {
+        self.step_size.send_replace(step_size);
+    }
+    /// Get [`watch::Receiver`] of the current taps
+    ///
+    /// The value is updated after each processed chunk.
+    pub fn taps(&self) -> watch::Receiver<Arc<[Complex<Flt>]>> {
+        self.taps.clone()
+    }
+    /// Get [`watch::Receiver`] of the smoothed mean squared error of the
+    /// adaptation algorithm (`NaN` until the first symbol has been processed)
+    ///
+    /// The value is updated after each processed chunk.
+    pub fn mse(&self) -> watch::Receiver<f64> {
+        self.mse.clone()
+    }
+}
+
+/// Mapping of audio channels performed by a [`ChannelMap`] block
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum ChannelMapping {
+    /// Pass both channels unchanged
+    Identity,
+    /// Mix both channels into the left channel (each attenuated by 3 dB to
+    /// avoid clipping) and mute the right channel
+    Downmix,
+    /// Copy the left channel to the right channel
+    Duplicate,
+    /// Swap left and right channel
+    Swap,
+    /// Custom matrix, where `[[ll, lr], [rl, rr]]` results in
+    /// `left = ll * left + lr * right` and `right = rl * left + rr * right`
+    Matrix([[f64; 2]; 2]),
+}
+
+impl ChannelMapping {
+    /// Mixing coefficients as matrix (see [`ChannelMapping::Matrix`])
+    pub fn matrix(self) -> [[f64; 2]; 2] {
+        use std::f64::consts::FRAC_1_SQRT_2;
+        match self {
+            ChannelMapping::Identity => [[1.0, 0.0], [0.0, 1.0]],
+            ChannelMapping::Downmix => [[FRAC_1_SQRT_2, FRAC_1_SQRT_2], [0.0, 0.0]],
+            ChannelMapping::Duplicate => [[1.0, 0.0], [1.0, 0.0]],
+            ChannelMapping::Swap => [[0.0, 1.0], [1.0, 0.0]],
+            ChannelMapping::Matrix(matrix) => matrix,
+        }
+    }
+}
+
+/// Block which downmixes, duplicates, or swaps the channels of a stereo
+/// audio signal
+///
+/// A stereo pair is represented by a single complex sample, where the real
+/// part is the left channel and the imaginary part is the right channel.
+/// Mono audio signals (e.g. from demodulators) carry the audio in the real
+/// part, so after a [`ChannelMapping::Downmix`] a signal can be used as mono
+/// signal, and [`ChannelMapping::Duplicate`] turns a mono signal into a
+/// stereo pair with the same audio on both channels. The right channel can be
+/// moved to the real part with [`ChannelMapping::Swap`], e.g. to feed the
+/// right input of a `StereoAudioPlayer` (with the `audio` feature).
+pub struct ChannelMap<Flt> {
+    receiver_connector: ReceiverConnector<Signal<Complex<Flt>>>,
+    sender_connector: SenderConnector<Signal<Complex<Flt>>>,
+    mapping: watch::Sender<ChannelMapping>,
+}
+
+impl_block_trait! { <Flt> Consumer<Signal<Complex<Flt>>> for ChannelMap<Flt> }
+impl_block_trait! { <Flt> Producer<Signal<Complex<Flt>>> for ChannelMap<Flt> }
+
+impl<Flt> ChannelMap<Flt>
+where
+    Flt: Float,
+{
+    /// Create new `ChannelMap` block with given `mapping`
+    pub fn new(mapping: ChannelMapping) -> Self {
+        let (mut receiver, receiver_connector) = new_receiver::<Signal<Complex<Flt>>>();
+        let (sender, sender_connector) = new_sender::<Signal<Complex<Flt>>>();
+        let (mapping_send, mut mapping_recv) = watch::channel(mapping);
+        spawn(async move {
+            let to_flt =
+                |matrix: [[f64; 2]; 2]| -> [[Flt; 2]; 2] { matrix.map(|row| row.map(|x| flt!(x))) };
+            let mut matrix = to_flt(mapping.matrix());
+            let mut buf_pool = ChunkBufPool::<Complex<Flt>>::new();
+            loop {
+                let Ok(signal) = receiver.recv().await else { return; };
+                match signal {
+                    Signal::Samples {
+                        sample_rate,
+                        chunk: input_chunk,
+                    } => {
+                        if mapping_recv.has_changed().unwrap_or(false) {
+                            matrix = to_flt(mapping_recv.borrow_and_update().matrix());
+                        }
+                        let [[ll, lr], [rl, rr]] = matrix;
+                        let mut output_chunk = buf_pool.get_with_capacity(input_chunk.len());
+                        output_chunk.extend(input_chunk.iter().map(|&Complex { re, im }| {
+                            Complex::new(ll * re + lr * im, rl * re + rr * im)
+                        }));
+                        let Ok(()) = sender
+                            .send(Signal::Samples {
+                                sample_rate,
+                                chunk: output_chunk.finalize(),
+                            })
+                            .await
+                        else { return; };
+                    }
+                    event @ Signal::Event { .. } => {
+                        let Ok(()) = sender.send(event).await else { return; };
+                    }
+                }
+            }
+        });
+        Self {
+            receiver_connector,
+            sender_connector,
+            mapping: mapping_send,
+        }
+    }
+    /// Get current mapping
+    pub fn mapping(&self) -> ChannelMapping {
+        *self.mapping.borrow()
+    }
+    /// Set mapping
+    pub fn set_mapping(&self, mapping: ChannelMapping) {
+        self.mapping.send_replace(mapping);
+    }
+}
+
+/// Delay line, which delays the samples by a (possibly fractional) number of
+/// samples
+///
+/// Integer delays are exact. Fractional delays are realized with a
+/// third-order Lagrange interpolator, which is exact for signals that are
+/// polynomials of degree three or less and has a flat frequency response for
+/// frequencies well below the Nyquist frequency. Fractional delays below one
+/// sample are less accurate, because the interpolator must be causal.
+///
+/// The delay line is filled with zeros when the block is created. The delay
+/// may be changed at runtime and applies from the next chunk on. When the
+/// delay is increased, samples which are older than the previous delay are
+/// not available and zeros are output in their place. The delay line is
+/// filled with zeros again when an [interrupting] event is received.
+///
+/// [interrupting]: Event::is_interrupt
+pub struct Delay<Flt> {
+    receiver_connector: ReceiverConnector<Signal<Complex<Flt>>>,
+    sender_connector: SenderConnector<Signal<Complex<Flt>>>,
+    delay: watch::Sender<f64>,
+}
+
+impl_block_trait! { <Flt> Consumer<Signal<Complex<Flt>>> for Delay<Flt> }
+impl_block_trait! { <Flt> Producer<Signal<Complex<Flt>>> for Delay<Flt> }
+
+/// Number of taps of the Lagrange interpolator used by [`Delay`]
+const DELAY_TAPS: usize = 4;
+
+/// Offset into the delay line and Lagrange coefficients for a delay of
+/// `delay` samples
+fn delay_coefficients<Flt: Float>(delay: f64) -> (usize, [Flt; DELAY_TAPS]) {
+    let offset = (delay.floor() as usize).saturating_sub(1);
+    let fraction = delay - offset as f64;
+    let mut coefficients = [Flt::one(); DELAY_TAPS];
+    for (k, coefficient) in coefficients.iter_mut().enumerate() {
+        let mut h = 1.0;
+        for j in (0..DELAY_TAPS).filter(|&j| j != k) {
+            h *= (fraction - j as f64) / (k as f64 - j as f64);
+        }
+        *coefficient = flt!(h);
+    }
+    (offset, coefficients)
+}
+
+impl<Flt> Delay<Flt>
+where
+    Flt: Float,
+{
+    /// Create new `Delay` block, which delays by `samples`
+    pub fn new(samples: usize) -> Self {
+        Self::with_fractional(samples as f64)
+    }
+    /// Create new `Delay` block, which delays by a possibly fractional number
+    /// of `samples`
+    pub fn with_fractional(samples: f64) -> Self {
+        assert!(samples >= 0.0, "delay must not be negative");
+        let (mut receiver, receiver_connector) = new_receiver::<Signal<Complex<Flt>>>();
+        let (sender, sender_connector) = new_sender::<Signal<Complex<Flt>>>();
+        let (delay_send, mut delay_recv) = watch::channel(samples);
+        spawn(async move {
+            let mut buf_pool = ChunkBufPool::<Complex<Flt>>::new();
+            let (mut offset, mut coefficients) = delay_coefficients::<Flt>(samples);
+            let mut history: VecDeque<Complex<Flt>> = VecDeque::new();
+            history.resize(offset + DELAY_TAPS, Complex::from(Flt::zero()));
+            loop {
+                let Ok(signal) = receiver.recv().await else { return; };
+                match signal {
+                    Signal::Samples {
+                        sample_rate,
+                        chunk: input_chunk,
+                    } => {
+                        if delay_recv.has_changed().unwrap_or(false) {
+                            (offset, coefficients) =
+                                delay_coefficients(*delay_recv.borrow_and_update());
+                            let len = offset + DELAY_TAPS;
+                            while history.len() > len {
+                                history.pop_front();
+                            }
+                            while history.len() < len {
+                                history.push_front(Complex::from(Flt::zero()));
+                            }
+                        }
+                        let mut output_chunk = buf_pool.get_with_capacity(input_chunk.len());
+                        for &sample in input_chunk.iter() {
+                            history.pop_front();
+                            history.push_back(sample);
+                            let mut output = Complex::from(Flt::zero());
+                            for (k, &coefficient) in coefficients.iter().enumerate() {
+                                output += history[DELAY_TAPS - 1 - k] * coefficient;
+                            }
+                            output_chunk.push(output);
+                        }
+                        let Ok(()) = sender
+                            .send(Signal::Samples {
+                                sample_rate,
+                                chunk: output_chunk.finalize(),
+                            })
+                            .await
+                        else { return; };
+                    }
+                    Signal::Event(event) => {
+                        if event.is_interrupt() {
+                            history.iter_mut().for_each(|x| *x = Complex::from(Flt::zero()));
+                        }
+                        let Ok(()) = sender.send(Signal::Event(event)).await else { return; };
+                    }
+                }
+            }
+        });
+        Self {
+            receiver_connector,
+            sender_connector,
+            delay: delay_send,
+        }
+    }
+    /// Get delay in samples
+    pub fn delay(&self) -> f64 {
+        *self.delay.borrow()
+    }
+    /// Set delay in samples (may be fractional)
+    pub fn set_delay(&self, samples: f64) {
+        assert!(samples >= 0.0, "delay must not be negative");
+        self.delay.send_replace(samples);
+    }
+    /// Get delay in seconds for given `sample_rate`
+    pub fn delay_seconds(&self, sample_rate: f64) -> f64 {
+        self.delay() / sample_rate
+    }
+    /// Set delay in seconds for given `sample_rate`
+    pub fn set_delay_seconds(&self, seconds: f64, sample_rate: f64) {
+        self.set_delay(seconds * sample_rate);
+    }
+}
+
+/// Output of a [`ComplexSplit`] block, which acts as a
+/// [`Producer<Signal<Flt>>`]
+pub struct ComplexSplitOutput<Flt> {
+    sender_connector: SenderConnector<Signal<Flt>>,
+}
+
+impl_block_trait! { <Flt> Producer<Signal<Flt>> for ComplexSplitOutput<Flt> }
+
+/// Block which splits complex samples into two real streams with the
+/// in-phase (I) and quadrature (Q) components
+///
+/// Events are forwarded to both outputs. Both outputs are lossless, i.e. the
+/// slower consumer determines the speed of both outputs.
+///
+/// See [`ComplexJoin`] for the inverse operation.
+pub struct ComplexSplit<Flt> {
+    receiver_connector: ReceiverConnector<Signal<Complex<Flt>>>,
+    outputs: [ComplexSplitOutput<Flt>; 2],
+}
+
+impl_block_trait! { <Flt> Consumer<Signal<Complex<Flt>>> for ComplexSplit<Flt> }
+
+impl<Flt> ComplexSplit<Flt>
+where
+    Flt: Float,
+{
+    /// Create new `ComplexSplit` block
+    pub fn new() -> Self {
+        let (mut receiver, receiver_connector) = new_receiver::<Signal<Complex<Flt>>>();
+        let (i_sender, i_sender_connector) = new_sender::<Signal<Flt>>();
+        let (q_sender, q_sender_connector) = new_sender::<Signal<Flt>>();
+        spawn(async move {
+            let mut buf_pool = ChunkBufPool::<Flt>::new();
+            loop {
+                let Ok(signal) = receiver.recv().await else { return; };
+                match signal {
+                    Signal::Samples {
+                        sample_rate,
+                        chunk: input_chunk,
+                    } => {
+                        let mut i_chunk = buf_pool.get_with_capacity(input_chunk.len());
+                        let mut q_chunk = buf_pool.get_with_capacity(input_chunk.len());
+                        for sample in input_chunk.iter() {
+                            i_chunk.push(sample.re);
+                            q_chunk.push(sample.im);
+                        }
+                        let Ok(()) = i_sender
+                            .send(Signal::Samples {
+                                sample_rate,
+                                chunk: i_chunk.finalize(),
+                            })
+                            .await
+                        else { return; };
+                        let Ok(()) = q_sender
+                            .send(Signal::Samples {
+                                sample_rate,
+                                chunk: q_chunk.finalize(),
+                            })
+                            .await
+                        else { return; };
+                    }
+                    Signal::Event(event) => {
+                        let Ok(()) = i_sender.send(Signal::Event(event.clone())).await
+                        else { return; };
+                        let Ok(()) = q_sender.send(Signal::Event(event)).await else { return; };
+                    }
+                }
+            }
+        });
+        Self {
+            receiver_connector,
+            outputs: [
+                ComplexSplitOutput {
+                    sender_connector: i_sender_connector,
+                },
+                ComplexSplitOutput {
+                    sender_connector: q_sender_connector,
+                },
+            ],
+        }
+    }
+    /// Output with the in-phase (real) components
+    pub fn in_phase(&self) -> &ComplexSplitOutput<Flt> {
+        &self.outputs[0]
+    }
+    /// Output with the quadrature (imaginary) components
+    pub fn quadrature(&self) -> &ComplexSplitOutput<Flt> {
+        &self.outputs[1]
+    }
+}
+
+/// Input of a [`ComplexJoin`] block, which acts as a
+/// [`Consumer<Signal<Flt>>`]
+pub struct ComplexJoinInput<Flt> {
+    receiver_connector: ReceiverConnector<Signal<Flt>>,
+}
+
+impl_block_trait! { <Flt> Consumer<Signal<Flt>> for ComplexJoinInput<Flt> }
+
+/// Block which joins two aligned real streams with the in-phase (I) and
+/// quadrature (Q) components into complex samples
+///
+/// Both inputs must be sample-aligned. Chunks of the inputs do not need to
+/// have the same length, as samples are buffered until they are available on
+/// both inputs. The sample rate of the in-phase input is used for the
+/// output.
+///
+/// Events of the in-phase input are forwarded, while events of the
+/// quadrature input are discarded (such that events are not duplicated when
+/// both inputs originate from a [`ComplexSplit`]). Upon [interruption] of
+/// either input, the buffered samples of both inputs are discarded.
+///
+/// [interruption]: Event::is_interrupt
+pub struct ComplexJoin<Flt> {
+    inputs: [ComplexJoinInput<Flt>; 2],
+    sender_connector: SenderConnector<Signal<Complex<Flt>>>,
+}
+
+impl_block_trait! { <Flt> Producer<Signal<Complex<Flt>>> for ComplexJoin<Flt> }
+
+impl<Flt> ComplexJoin<Flt>
+where
+    Flt: Float,
+{
+    /// Create new `ComplexJoin` block
+    pub fn new() -> Self {
+        let (sender, sender_connector) = new_sender::<Signal<Complex<Flt>>>();
+        let (forward_send, mut forward_recv) = mpsc::channel::<(usize, Signal<Flt>)>(1);
+        let inputs = [0, 1].map(|index| {
+            let (mut receiver, receiver_connector) = new_receiver::<Signal<Flt>>();
+            let forward_send = forward_send.clone();
+            spawn(async move {
+                loop {
+                    let Ok(signal) = receiver.recv().await else { return; };
+                    let Ok(()) = forward_send.send((index, signal)).await else { return; };
+                }
+            });
+            ComplexJoinInput { receiver_connector }
+        });
+        spawn(async move {
+            let mut buf_pool = ChunkBufPool::<Complex<Flt>>::new();
+            let mut sample_rate: f64 = 0.0;
+            let mut buffers: [VecDeque<Flt>; 2] = Default::default();
+            loop {
+                let Some((index, signal)) = forward_recv.recv().await else { return; };
+                match signal {
+                    Signal::Samples {
+                        sample_rate: input_sample_rate,
+                        chunk: input_chunk,
+                    } => {
+                        if index == 0 {
+                            sample_rate = input_sample_rate;
+                        }
+                        buffers[index].extend(input_chunk.iter().copied());
+                        let len = buffers[0].len().min(buffers[1].len());
+                        if len == 0 {
+                            continue;
+                        }
+                        let mut output_chunk = buf_pool.get_with_capacity(len);
+                        let [i_buffer, q_buffer] = &mut buffers;
+                        output_chunk.extend(
+                            i_buffer
+                                .drain(0..len)
+                                .zip(q_buffer.drain(0..len))
+                                .map(|(re, im)| Complex::new(re, im)),
+                        );
+                        let Ok(()) = sender
+                            .send(Signal::Samples {
+                                sample_rate,
+                                chunk: output_chunk.finalize(),
+                            })
+                            .await
+                        else { return; };
+                    }
+                    Signal::Event(event) => {
+                        if event.is_interrupt() {
+                            buffers.iter_mut().for_each(VecDeque::clear);
+                        }
+                        if index == 0 {
+                            let Ok(()) = sender.send(Signal::Event(event)).await else { return; };
+                        }
+                    }
+                }
+            }
+        });
+        Self {
+            inputs,
+            sender_connector,
+        }
+    }
+    /// Input for the in-phase (real) components
+    pub fn in_phase(&self) -> &ComplexJoinInput<Flt> {
+        &self.inputs[0]
+    }
+    /// Input for the quadrature (imaginary) components
+    pub fn quadrature(&self) -> &ComplexJoinInput<Flt> {
+        &self.inputs[1]
+    }
+}
+
+/// Input of a [`Mixer`] block, which acts as a
+/// [`Consumer<Signal<Complex<Flt>>>`]
+pub struct MixerInput<Flt> {
+    receiver_connector: ReceiverConnector<Signal<Complex<Flt>>>,
+    gain: watch::Sender<f64>,
+}
+
+impl_block_trait! { <Flt> Consumer<Signal<Complex<Flt>>> for MixerInput<Flt> }
+
+impl<Flt> MixerInput<Flt> {
+    /// Get gain which samples of this input are multiplied with
+    pub fn gain(&self) -> f64 {
+        *self.gain.borrow()
+    }
+    /// Set gain which samples of this input are multiplied with
+    pub fn set_gain(&self, gain: f64) {
+        self.gain.send_replace(gain);
+    }
+}
+
+/// Block which sums several audio streams with individual gains
+///
+/// Each of the [inputs] is multiplied with its gain (defaulting to `1.0`)
+/// before the inputs are summed up. The real and imaginary part of the sum
+/// are then clamped to the range from `-limit` to `limit` (see
+/// [`Mixer::set_limit`]), such that e.g. two demodulated channels can be
+/// monitored together without clipping in an `AudioPlayer`.
+///
+/// Samples are buffered until they have been received on all inputs. As
+/// sources may run at slightly different rates (or stop sending, e.g. when
+/// disconnected), an input lagging behind by more than the
+/// [maximum delay] is considered silent for the missing samples, which
+/// bounds the delay and memory usage.
+///
+/// All inputs must have the same sample rate. The sample rate of the first
+/// received samples is used for the output and may only change when no
+/// samples are buffered. Samples with a different sample rate are discarded,
+/// and a [`SampleRateMismatch`] event is sent (and a warning logged) when
+/// an input starts sending them. Events of the first input are forwarded,
+/// while events of the other inputs are discarded. Upon
+/// [interruption] of an input, the buffered samples of that input are
+/// discarded.
+///
+/// [inputs]: Mixer::inputs
+/// [maximum delay]: Mixer::set_max_delay
+/// [interruption]: Event::is_interrupt
+pub struct Mixer<Flt> {
+    inputs: Vec<MixerInput<Flt>>,
+    sender_connector: SenderConnector<Signal<Complex<Flt>>>,
+    limit: watch::Sender<f64>,
+    max_delay: watch::Sender<f64>,
+}
+
+impl_block_trait! { <Flt> Producer<Signal<Complex<Flt>>> for Mixer<Flt> }
+
+impl<Flt> Mixer<Flt>
+where
+    Flt: Float,
+{
+    /// Create new `Mixer` block with `input_count` inputs
+    ///
+    /// The limit defaults to `1.0` and the maximum delay to 0.1 seconds.
+    pub fn new(input_count: usize) -> Self {
+        assert!(input_count > 0, "mixer requires at least one input");
+        let (sender, sender_connector) = new_sender::<Signal<Complex<Flt>>>();
+        let (forward_send, mut forward_recv) = mpsc::channel::<(usize, Signal<Complex<Flt>>)>(1);
+        let (limit_send, limit_recv) = watch::channel(1.0);
+        let (max_delay_send, max_delay_recv) = watch::channel(0.1);
+        let mut gains = Vec::with_capacity(input_count);
+        let inputs = (0..input_count)
+            .map(|index| {
+                let (mut receiver, receiver_connector) = new_receiver::<Signal<Complex<Flt>>>();
+                let (gain, gain_recv) = watch::channel(1.0);
+                gains.push(gain_recv);
+                let forward_send = forward_send.clone();
+                spawn(async move {
+                    loop {
+                        let Ok(signal) = receiver.recv().await else { return; };
+                        let Ok(()) = forward_send.send((index, signal)).await else { return; };
+                    }
+                });
+                MixerInput {
+                    receiver_connector,
+                    gain,
+                }
+            })
+            .collect();
+        spawn(async move {
+            let mut buf_pool = ChunkBufPool::<Complex<Flt>>::new();
+            let mut sample_rate: Option<f64> = None;
+            let mut buffers: Vec<VecDeque<Complex<Flt>>> = vec![VecDeque::new(); input_count];
+            let mut mismatched = vec![false; input_count];
+            loop {
+                let Some((index, signal)) = forward_recv.recv().await else { return; };
+                match signal {
+                    Signal::Samples {
+                        sample_rate: input_sample_rate,
+                        chunk: input_chunk,
+                    } => {
+                        let idle = buffers.iter().all(VecDeque::is_empty);
+                        let rate = match sample_rate {
+                            Some(rate) if rate != input_sample_rate && !idle => {
+                                if !mismatched[index] {
+                                    mismatched[index] = true;
+                                    event!(
+                                        WARN,
+                                        "mixer input {index} has sample rate \
+                                        {input_sample_rate} instead of {rate}"
+                                    );
+                                    let Ok(()) = sender
+                                        .send(Signal::new_event(SampleRateMismatch {
+                                            input: index,
+                                            sample_rate: input_sample_rate,
+                                            expected: rate,
+                                        }))
+                                        .await
+                                    else { return; };
+                                }
+                                continue;
+                            }
+                            _ => input_sample_rate,
+                        };
+                        sample_rate = Some(rate);
+                        mismatched[index] = false;
+                        buffers[index].extend(input_chunk.iter().copied());
+                        let max_delay = (*max_delay_recv.borrow() * rate).ceil() as usize;
+                        let shortest = buffers.iter().map(VecDeque::len).min().unwrap();
+                        let longest = buffers.iter().map(VecDeque::len).max().unwrap();
+                        let len = shortest.max(longest.saturating_sub(max_delay));
+                        if len == 0 {
+                            continue;
+                        }
+                        let limit = flt!(*limit_recv.borrow());
+                        let mut output_chunk = buf_pool.get_with_capacity(len);
+                        output_chunk.resize(len, Complex::from(Flt::zero()));
+                        for (buffer, gain) in buffers.iter_mut().zip(gains.iter()) {
+                            let gain = flt!(*gain.borrow());
+                            let available = buffer.len().min(len);
+                            for (sum, sample) in
+                                output_chunk.iter_mut().zip(buffer.drain(0..available))
+                            {
+                                *sum += sample * gain;
+                            }
+                        }
+                        for sample in output_chunk.iter_mut() {
+                            sample.re = sample.re.max(-limit).min(limit);
+                            sample.im = sample.im.max(-limit).min(limit);
+                        }
+                        let Ok(()) = sender
+                            .send(Signal::Samples {
+                                sample_rate: rate,
+                                chunk: output_chunk.finalize(),
+                            })
+                            .await
+                        else { return; };
+                    }
+                    Signal::Event(event) => {
+                        if event.is_interrupt() {
+                            buffers[index].clear();
+                        }
+                        if index == 0 {
+                            let Ok(()) = sender.send(Signal::Event(event)).await else { return; };
+                        }
+                    }
+                }
+            }
+        });
+        Self {
+            inputs,
+            sender_connector,
+            limit: limit_send,
+            max_delay: max_delay_send,
+        }
+    }
+    /// Inputs of the mixer
+    pub fn inputs(&self) -> &[MixerInput<Flt>] {
+        &self.inputs
+    }
+    /// Input with given `index`
+    pub fn input(&self, index: usize) -> &MixerInput<Flt> {
+        &self.inputs[index]
+    }
+    /// Get maximum magnitude of the real and imaginary part of the output
+    pub fn limit(&self) -> f64 {
+        *self.limit.borrow()
+    }
+    /// Set maximum magnitude of the real and imaginary part of the output
+    pub fn set_limit(&self, limit: f64) {
+        self.limit.send_replace(limit);
+    }
+    /// Get maximum delay in seconds by which an input may lag behind
+    pub fn max_delay(&self) -> f64 {
+        *self.max_delay.borrow()
+    }
+    /// Set maximum delay in seconds by which an input may lag behind
+    pub fn set_max_delay(&self, max_delay: f64) {
+        assert!(max_delay >= 0.0, "maximum delay must not be negative");
+        self.max_delay.send_replace(max_delay);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::assert_approx;
+    #[tokio::test]
+    async fn test_gain_control() {
+        let (sender, sender_connector) = new_sender::<Signal<Complex<f32>>>();
+        let attenuator = GainControl::new(0.25);
+        let (mut receiver, receiver_connector) = new_receiver::<Signal<Complex<f32>>>();
+        attenuator.feed_from(&sender_connector);
+        attenuator.feed_into(&receiver_connector);
+        sender
+            .send(Signal::Samples {
+                sample_rate: 48000.0,
+                chunk: Chunk::from(vec![Complex::new(32.0, -1.0), Complex::new(15.0, -2.0)]),
+            })
+            .await
+            .unwrap();
+        let Signal::Samples { chunk, .. } = receiver.recv().await.unwrap()
+        else { panic!(); };
+        assert_eq!(chunk[0].re, 8.0);
+        assert_eq!(chunk[0].im, -0.25);
+        assert_eq!(chunk[1].re, 3.75);
+        assert_eq!(chunk[1].im, -0.5);
+    }
+    #[tokio::test]
+    async fn test_channel_map() {
+        let (sender, sender_connector) = new_sender::<Signal<Complex<f64>>>();
+        let channel_map = ChannelMap::<f64>::new(ChannelMapping::Downmix);
+        let (mut receiver, receiver_connector) = new_receiver::<Signal<Complex<f64>>>();
+        channel_map.feed_from(&sender_connector);
+        channel_map.feed_into(&receiver_connector);
+        for (mapping, expected) in [
+            (
+                ChannelMapping::Downmix,
+                Complex::new(3.0 / 2f64.sqrt(), 0.0),
+            ),
+            (ChannelMapping::Duplicate, Complex::new(1.0, 1.0)),
+            (ChannelMapping::Swap, Complex::new(2.0, 1.0)),
+            (ChannelMapping::Identity, Complex::new(1.0, 2.0)),
+        ] {
+            channel_map.set_mapping(mapping);
+            sender
+                .send(Signal::Samples {
+                    sample_rate: 48000.0,
+                    chunk: Chunk::from(vec![Complex::new(1.0, 2.0)]),
+                })
+                .await
+                .unwrap();
+            let Signal::Samples { chunk, .. } = receiver.recv().await.unwrap()
+            else { panic!(); };
+            assert_approx(chunk[0].re, expected.re);
+            assert_approx(chunk[0].im, expected.im);
+        }
+    }
+    #[tokio::test]
+    async fn test_gain() {
+        let (sender, sender_connector) = new_sender::<Signal<Complex<f64>>>();
+        let gain = Gain::<f64>::with_db(20.0);
+        let (mut receiver, receiver_connector) = new_receiver::<Signal<Complex<f64>>>();
+        gain.feed_from(&sender_connector);
+        gain.feed_into(&receiver_connector);
+        assert_approx(gain.gain(), 10.0);
+        gain.set_ramp_time(1.0);
+        for (gain_db, expected) in [
+            (20.0, vec![10.0; 6]),
+            (f64::NEG_INFINITY, vec![7.5, 5.0, 2.5, 0.0, 0.0, 0.0]),
+        ] {
+            gain.set_gain_db(gain_db);
+            sender
+                .send(Signal::Samples {
+                    sample_rate: 4.0,
+                    chunk: Chunk::from(vec![Complex::new(1.0, -1.0); 6]),
+                })
+                .await
+                .unwrap();
+            let Signal::Samples { chunk, .. } = receiver.recv().await.unwrap()
+            else { panic!(); };
+            for (output, expected) in chunk.iter().zip(expected) {
+                assert_approx(output.re, expected);
+                assert_approx(-output.im, expected);
+            }
+        }
+        assert_eq!(gain.gain(), 0.0);
+        assert_eq!(gain.gain_db(), f64::NEG_INFINITY);
+    }
+    #[tokio::test]
+    async fn test_freq_shifter_roundtrip() {
+        let (sender, sender_connector) = new_sender::<Signal<Complex<f64>>>();
+        let shift_up = FreqShifter::<f64>::with_shift(1234.0);
+        let shift_down = FreqShifter::<f64>::with_shift(-1234.0);
+        let (mut receiver, receiver_connector) = new_receiver::<Signal<Complex<f64>>>();
+        shift_up.feed_from(&sender_connector);
+        shift_down.feed_from(&shift_up);
+        shift_down.feed_into(&receiver_connector);
+        let input: Vec<Complex<f64>> = (0..100)
+            .map(|i| Complex::new((i as f64 * 0.1).sin(), (i as f64 * 0.37).cos()))
+            .collect();
+        for part in input.chunks(30) {
+            sender
+                .send(Signal::Samples {
+                    sample_rate: 48000.0,
+                    chunk: Chunk::from(part.to_vec()),
+                })
+                .await
+                .unwrap();
+            let Signal::Samples { chunk, .. } = receiver.recv().await.unwrap()
+            else { panic!(); };
+            for (output, expected) in chunk.iter().zip(part.iter()) {
+                assert_approx(output.re, expected.re);
+                assert_approx(output.im, expected.im);
+            }
+        }
+    }
+    #[tokio::test]
+    async fn test_freq_shifter_center_frequency() {
+        let (sender, sender_connector) = new_sender::<Signal<Complex<f64>>>();
+        let freq_shifter = FreqShifter::<f64>::with_shift(1000.0);
+        let (mut receiver, receiver_connector) = new_receiver::<Signal<Complex<f64>>>();
+        freq_shifter.feed_from(&sender_connector);
+        freq_shifter.feed_into(&receiver_connector);
+        sender
+            .send(Signal::new_event(CenterFrequency(100e6)))
+            .await
+            .unwrap();
+        for shift in [1000.0, -500.0] {
+            freq_shifter.set_shift(shift);
+            sender
+                .send(Signal::Samples {
+                    sample_rate: 48000.0,
+                    chunk: Chunk::from(vec![Complex::from(1.0)]),
+                })
+                .await
+                .unwrap();
+            assert_eq!(
+                receiver.recv().await.unwrap().center_frequency(),
+                Some(100e6 - shift)
+            );
+            let Signal::Samples { .. } = receiver.recv().await.unwrap()
+            else { panic!(); };
+        }
+    }
+    #[tokio::test]
+    async fn test_agc() {
+        let (sender, sender_connector) = new_sender::<Signal<Complex<f64>>>();
+        let agc = Agc::<f64>::new(0.5, 0.001, 0.1);
+        let (mut receiver, receiver_connector) = new_receiver::<Signal<Complex<f64>>>();
+        agc.feed_from(&sender_connector);
+        agc.feed_into(&receiver_connector);
+        for _ in 0..10 {
+            sender
+                .send(Signal::Samples {
+                    sample_rate: 48000.0,
+                    chunk: Chunk::from(vec![Complex::new(0.0, 0.1); 1000]),
+                })
+                .await
+                .unwrap();
+            receiver.recv().await.unwrap();
+        }
+        assert!((*agc.current_gain().borrow() - 5.0).abs() < 1e-6);
+    }
+    #[tokio::test]
+    async fn test_squelch() {
+        let (sender, sender_connector) = new_sender::<Signal<Complex<f32>>>();
+        let squelch = Squelch::<f32>::new(-20.0, -30.0, 0.01);
+        let (mut receiver, receiver_connector) = new_receiver::<Signal<Complex<f32>>>();
+        squelch.feed_from(&sender_connector);
+        squelch.feed_into(&receiver_connector);
+        for (amplitude, expect_open) in [(0.01, false), (1.0, true), (0.0, false)] {
+            sender
+                .send(Signal::Samples {
+                    sample_rate: 48000.0,
+                    chunk: Chunk::from(vec![Complex::new(amplitude, 0.0); 4800]),
+                })
+                .await
+                .unwrap();
+            let Signal::Samples { chunk, .. } = receiver.recv().await.unwrap()
+            else { panic!(); };
+            assert_eq!(*squelch.open().borrow(), expect_open);
+            assert_eq!(chunk[4799].re, if expect_open { amplitude } else { 0.0 });
+        }
+    }
+    #[tokio::test]
+    async fn test_limiter() {
+        let (sender, sender_connector) = new_sender::<Signal<Complex<f64>>>();
+        let limiter = Limiter::<f64>::new(-6.0, 0.001, 0.01);
+        let clipper = Limiter::<f64>::hard_clip(0.0);
+        let (mut receiver1, receiver1_connector) = new_receiver::<Signal<Complex<f64>>>();
+        let (mut receiver2, receiver2_connector) = new_receiver::<Signal<Complex<f64>>>();
+        limiter.feed_from(&sender_connector);
+        clipper.feed_from(&sender_connector);
+        limiter.feed_into(&receiver1_connector);
+        clipper.feed_into(&receiver2_connector);
+        let mut input = vec![Complex::new(0.1, 0.0); 4800];
+        input[2000] = Complex::new(0.0, -4.0);
+        sender
+            .send(Signal::Samples {
+                sample_rate: 48000.0,
+                chunk: Chunk::from(input),
+            })
+            .await
+            .unwrap();
+        let Signal::Samples { chunk: output1, .. } = receiver1.recv().await.unwrap()
+        else { panic!(); };
+        let Signal::Samples { chunk: output2, .. } = receiver2.recv().await.unwrap()
+        else { panic!(); };
+        let threshold = 10.0f64.powf(-6.0 / 20.0);
+        assert_approx(output1[1000].re, 0.1);
+        assert_approx(output1[2048].im, -threshold);
+        assert!(output1
+            .iter()
+            .all(|sample| sample.norm() <= threshold + 1e-9));
+        assert!(output1[2100].re < 0.1);
+        assert!(output1[4799].re > 0.099);
+        assert_approx(
+            *limiter.reduction_db().borrow(),
+            6.0 + 20.0 * 4.0f64.log10(),
+        );
+        assert_eq!(output2[2000], Complex::new(0.0, -1.0));
+        assert_eq!(output2[2001], Complex::new(0.1, 0.0));
+    }
+    #[tokio::test]
+    async fn test_iq_swap_and_conjugate() {
+        let (sender, sender_connector) = new_sender::<Signal<Complex<f32>>>();
+        let iq_swap = IqSwap::<f32>::new();
+        let conjugate = Conjugate::<f32>::new();
+        let (mut receiver1, receiver1_connector) = new_receiver::<Signal<Complex<f32>>>();
+        let (mut receiver2, receiver2_connector) = new_receiver::<Signal<Complex<f32>>>();
+        iq_swap.feed_from(&sender_connector);
+        conjugate.feed_from(&sender_connector);
+        iq_swap.feed_into(&receiver1_connector);
+        conjugate.feed_into(&receiver2_connector);
+        for enabled in [true, false] {
+            iq_swap.set_enabled(enabled);
+            conjugate.set_enabled(enabled);
+            sender
+                .send(Signal::Samples {
+                    sample_rate: 48000.0,
+                    chunk: Chunk::from(vec![Complex::new(1.0, 2.0)]),
+                })
+                .await
+                .unwrap();
+            let Signal::Samples { chunk: output1, .. } = receiver1.recv().await.unwrap()
+            else { panic!(); };
+            let Signal::Samples { chunk: output2, .. } = receiver2.recv().await.unwrap()
+            else { panic!(); };
+            if enabled {
+                assert_eq!(&*output1, &[Complex::new(2.0, 1.0)]);
+                assert_eq!(&*output2, &[Complex::new(1.0, -2.0)]);
+            } else {
+                assert_eq!(&*output1, &[Complex::new(1.0, 2.0)]);
+                assert_eq!(&*output2, &[Complex::new(1.0, 2.0)]);
+            }
+        }
+    }
+    #[tokio::test]
+    async fn test_convert() {
+        let (sender, sender_connector) = new_sender::<Signal<Complex<f32>>>();
+        let to_f64 = Convert::<f32, f64>::new();
+        let to_f32 = Convert::<f64, f32>::new();
+        let (mut receiver, receiver_connector) = new_receiver::<Signal<Complex<f32>>>();
+        to_f64.feed_from(&sender_connector);
+        to_f32.feed_from(&to_f64);
+        to_f32.feed_into(&receiver_connector);
+        sender
+            .send(Signal::Samples {
+                sample_rate: 48000.0,
+                chunk: Chunk::from(vec![Complex::new(0.25, -1.5)]),
+            })
+            .await
+            .unwrap();
+        let Signal::Samples { chunk, .. } = receiver.recv().await.unwrap()
+        else { panic!(); };
+        assert_eq!(&*chunk, &[Complex::new(0.25, -1.5)]);
+        let (sender, sender_connector) = new_sender::<Signal<f64>>();
+        let to_f32 = ConvertReal::<f64, f32>::new();
+        let (mut receiver, receiver_connector) = new_receiver::<Signal<f32>>();
+        to_f32.feed_from(&sender_connector);
+        to_f32.feed_into(&receiver_connector);
+        sender
+            .send(Signal::Samples {
+                sample_rate: 48000.0,
+                chunk: Chunk::from(vec![0.1, 2.0]),
+            })
+            .await
+            .unwrap();
+        let Signal::Samples { chunk, .. } = receiver.recv().await.unwrap()
+        else { panic!(); };
+        assert_eq!(&*chunk, &[0.1f32, 2.0]);
+    }
+    #[tokio::test]
+    async fn test_complex_to_real() {
+        let (sender, sender_connector) = new_sender::<Signal<Complex<f64>>>();
+        let to_real = ComplexToReal::<f64>::new();
+        let to_magnitude = ComplexToReal::<f64>::with_mode(ComplexToRealMode::Magnitude);
+        let (mut receiver1, receiver1_connector) = new_receiver::<Signal<Complex<f64>>>();
+        let (mut receiver2, receiver2_connector) = new_receiver::<Signal<Complex<f64>>>();
+        to_real.feed_from(&sender_connector);
+        to_magnitude.feed_from(&sender_connector);
+        to_real.feed_into(&receiver1_connector);
+        to_magnitude.feed_into(&receiver2_connector);
+        sender
+            .send(Signal::Samples {
+                sample_rate: 48000.0,
+                chunk: Chunk::from(vec![Complex::new(3.0, -4.0)]),
+            })
+            .await
+            .unwrap();
+        let Signal::Samples { chunk: output1, .. } = receiver1.recv().await.unwrap()
+        else { panic!(); };
+        let Signal::Samples { chunk: output2, .. } = receiver2.recv().await.unwrap()
+        else { panic!(); };
+        assert_eq!(&*output1, &[Complex::new(3.0, 0.0)]);
+        assert_eq!(&*output2, &[Complex::new(5.0, 0.0)]);
+    }
+    #[tokio::test]
+    async fn test_real_to_complex() {
+        use std::f64::consts::TAU;
+        let (sender, sender_connector) = new_sender::<Signal<Complex<f64>>>();
+        let to_complex = RealToComplex::<f64>::new(65);
+        let (mut receiver, receiver_connector) = new_receiver::<Signal<Complex<f64>>>();
+        to_complex.feed_from(&sender_connector);
+        to_complex.feed_into(&receiver_connector);
+        let omega = TAU * 5000.0 / 48000.0;
+        let mut output: Vec<Complex<f64>> = Vec::new();
+        for part in 0..10 {
+            let input: Vec<Complex<f64>> = (part * 30..(part + 1) * 30)
+                .map(|i| Complex::from((omega * i as f64).cos()))
+                .collect();
+            sender
+                .send(Signal::Samples {
+                    sample_rate: 48000.0,
+                    chunk: Chunk::from(input),
+                })
+                .await
+                .unwrap();
+            let Signal::Samples { chunk, .. } = receiver.recv().await.unwrap()
+            else { panic!(); };
+            output.extend_from_slice(&chunk);
+        }
+        for (i, sample) in output.iter().enumerate().skip(64) {
+            let expected = Complex::from_polar(1.0, omega * (i - 32) as f64);
+            assert!((sample - expected).norm() < 0.01);
+        }
+    }
+    async fn costas_output(
+        detector: PhaseDetector,
+        symbols: &[Complex<f64>],
+    ) -> (Vec<Complex<f64>>, CostasLoop<f64>) {
+        use std::f64::consts::TAU;
+        let (sender, sender_connector) = new_sender::<Signal<Complex<f64>>>();
+        let costas = CostasLoop::<f64>::new(detector, 50.0);
+        let (mut receiver, receiver_connector) = new_receiver::<Signal<Complex<f64>>>();
+        costas.feed_from(&sender_connector);
+        costas.feed_into(&receiver_connector);
+        let samples: Vec<Complex<f64>> = (0..48000)
+            .map(|i| {
+                symbols[i / 10 % symbols.len()]
+                    * Complex::from_polar(1.0, TAU * 30.0 * i as f64 / 48000.0 + 0.3)
+            })
+            .collect();
+        let join_handle = tokio::spawn(async move {
+            for chunk in samples.chunks(4800) {
+                sender
+                    .send(Signal::Samples {
+                        sample_rate: 48000.0,
+                        chunk: Chunk::from(chunk.to_vec()),
+                    })
+                    .await
+                    .unwrap();
+            }
+        });
+        let mut output: Vec<Complex<f64>> = Vec::new();
+        while output.len() < 48000 {
+            let Signal::Samples { chunk, .. } = receiver.recv().await.unwrap() else {
+                panic!();
+            };
+            output.extend_from_slice(&chunk);
+        }
+        join_handle.await.unwrap();
+        (output, costas)
+    }
+    #[tokio::test]
+    async fn test_costas_loop() {
+        use std::f64::consts::TAU;
+        let qpsk: Vec<Complex<f64>> = [0, 3, 1, 1, 2, 0, 3, 2, 2, 1, 0, 3]
+            .iter()
+            .map(|&k| Complex::from_polar(1.0, TAU * (k as f64 + 0.5) / 4.0))
+            .collect();
+        let bpsk: Vec<Complex<f64>> = [1.0, -1.0, -1.0, 1.0, 1.0, 1.0, -1.0]
+            .iter()
+            .map(|&x| Complex::from(x))
+            .collect();
+        for (detector, symbols) in [
+            (PhaseDetector::Carrier, vec![Complex::from(1.0)]),
+            (PhaseDetector::Bpsk, bpsk),
+            (PhaseDetector::Qpsk, qpsk),
+        ] {
+            let (output, costas) = costas_output(detector, &symbols).await;
+            assert!((*costas.frequency().borrow() - 30.0).abs() < 1.0);
+            assert!(*costas.phase_error().borrow() < 0.05);
+            assert!(*costas.locked().borrow());
+            // remove phase ambiguity
+            let rotation = symbols[2400 % symbols.len()] / output[24000];
+            for (i, sample) in output.iter().enumerate().skip(24000) {
+                assert!((sample * rotation - symbols[i / 10 % symbols.len()]).norm() < 0.05);
+            }
+        }
+    }
+    #[tokio::test]
+    async fn test_equalizer() {
+        use crate::math::NoiseGenerator;
+        let mut noise = NoiseGenerator::with_seed(1);
+        let symbols: Vec<Complex<f64>> = (0..8000)
+            .map(|_| {
+                let re = if noise.uniform() < 0.5 { -1.0 } else { 1.0 };
+                let im = if noise.uniform() < 0.5 { -1.0 } else { 1.0 };
+                Complex::new(re, im) * std::f64::consts::FRAC_1_SQRT_2
+            })
+            .collect();
+        let channel = [
+            Complex::from(1.0),
+            Complex::from(0.3),
+            Complex::new(0.0, -0.2),
+        ];
+        let received: Vec<Complex<f64>> = (0..symbols.len())
+            .map(|i| {
+                let mut sum = Complex::from(0.0);
+                for (k, h) in channel.iter().enumerate() {
+                    if i >= k {
+                        sum += h * symbols[i - k];
+                    }
+                }
+                sum
+            })
+            .collect();
+        for algorithm in [
+            EqualizerAlgorithm::Lms(Constellation::Qpsk),
+            EqualizerAlgorithm::Cma,
+        ] {
+            let (sender, sender_connector) = new_sender::<Signal<Complex<f64>>>();
+            let equalizer = Equalizer::<f64>::new(algorithm, 11, 0.005);
+            let (mut receiver, receiver_connector) = new_receiver::<Signal<Complex<f64>>>();
+            equalizer.feed_from(&sender_connector);
+            equalizer.feed_into(&receiver_connector);
+            assert_eq!(equalizer.num_taps(), 11);
+            assert!(equalizer.mse().borrow().is_nan());
+            let chunks: Vec<Vec<Complex<f64>>> =
+                received.chunks(1000).map(|chunk| chunk.to_vec()).collect();
+            let join_handle = tokio::spawn(async move {
+                for chunk in chunks {
+                    sender
+                        .send(Signal::Samples {
+                            sample_rate: 1000.0,
+                            chunk: Chunk::from(chunk),
+                        })
+                        .await
+                        .unwrap();
+                }
+            });
+            let mut output: Vec<Complex<f64>> = Vec::new();
+            while output.len() < symbols.len() {
+                let Signal::Samples { chunk, .. } = receiver.recv().await.unwrap()
+                else { panic!(); };
+                output.extend_from_slice(&chunk);
+            }
+            join_handle.await.unwrap();
+            assert!(*equalizer.mse().borrow() < 0.01);
+            assert_eq!(equalizer.taps().borrow().len(), 11);
+            // CMA leaves a phase ambiguity
+            let rotation = symbols[7000 - 5] / output[7000];
+            for i in 7000..symbols.len() {
+                assert!((output[i] * rotation - symbols[i - 5]).norm() < 0.25);
+            }
+        }
+    }
+    #[tokio::test]
+    async fn test_delay() {
+        let (sender, sender_connector) = new_sender::<Signal<Complex<f64>>>();
+        let delay = Delay::<f64>::new(3);
+        let (mut receiver, receiver_connector) = new_receiver::<Signal<Complex<f64>>>();
+        delay.feed_from(&sender_connector);
+        delay.feed_into(&receiver_connector);
+        let ramp = |start: usize, len: usize| -> Chunk<Complex<f64>> {
+            (start..start + len)
+                .map(|x| Complex::new(x as f64, -(x as f64)))
+                .collect::<Vec<_>>()
+                .into()
+        };
+        let mut start = 1;
+        let mut expected = vec![0.0, 0.0, 0.0, 1.0, 2.0, 3.0, 4.0, 5.0];
+        for delay_samples in [3.0, 2.5, 0.25] {
+            delay.set_delay(delay_samples);
+            for _ in 0..2 {
+                sender
+                    .send(Signal::Samples {
+                        sample_rate: 10.0,
+                        chunk: ramp(start, 4),
+                    })
+                    .await
+                    .unwrap();
+                start += 4;
+            }
+            let mut output = Vec::new();
+            while output.len() < 8 {
+                let Signal::Samples { chunk, .. } = receiver.recv().await.unwrap()
+                else { panic!(); };
+                output.extend(chunk.iter().copied());
+            }
+            if delay_samples != 3.0 {
+                // skip transition caused by changing the delay
+                output.drain(0..4);
+                expected = (start - 4..start)
+                    .map(|x| x as f64 - delay_samples)
+                    .collect();
+            }
+            for (output, &expected) in output.iter().zip(expected.iter()) {
+                assert_approx(output.re, expected);
+                assert_approx(-output.im, expected);
+            }
+        }
+        assert_approx(delay.delay_seconds(10.0), 0.025);
+    }
+    #[tokio::test]
+    async fn test_convert_int() {
+        let (sender, sender_connector) = new_sender::<Signal<Complex<i16>>>();
+        let convert = ConvertInt::<i16, f32>::new();
+        let (mut receiver, receiver_connector) = new_receiver::<Signal<Complex<f32>>>();
+        convert.feed_from(&sender_connector);
+        convert.feed_into(&receiver_connector);
+        sender
+            .send(Signal::Samples {
+                sample_rate: 48000.0,
+                chunk: Chunk::from(vec![Complex::new(-32768, 16384), Complex::new(0, 32767)]),
+            })
+            .await
+            .unwrap();
+        let Signal::Samples { sample_rate, chunk } = receiver.recv().await.unwrap()
+        else { panic!(); };
+        assert_eq!(sample_rate, 48000.0);
+        assert_eq!(chunk[0], Complex::new(-1.0, 0.5));
+        assert_eq!(chunk[1], Complex::new(0.0, 32767.0 / 32768.0));
+    }
+    #[tokio::test]
+    async fn test_fn_block() {
+        let (sender, sender_connector) = new_sender::<Signal<Complex<f64>>>();
+        let mut total = 0.0;
+        let block = FnBlock::<Complex<f64>, f64>::new(move |_, input, output| {
+            for sample in input {
+                total += sample.re;
+                output.push(total);
+            }
+        });
+        let (mut receiver, receiver_connector) = new_receiver::<Signal<f64>>();
+        block.feed_from(&sender_connector);
+        block.feed_into(&receiver_connector);
+        for (values, expected) in [(vec![1.0, 2.0], vec![1.0, 3.0]), (vec![3.0], vec![6.0])] {
+            sender
+                .send(Signal::Samples {
+                    sample_rate: 48000.0,
+                    chunk: values.into_iter().map(Complex::from).collect::<Vec<_>>().into(),
+                })
+                .await
+                .unwrap();
+            let Signal::Samples { sample_rate, chunk } = receiver.recv().await.unwrap()
+            else { panic!(); };
+            assert_eq!(sample_rate, 48000.0);
+            assert_eq!(&chunk[..], &expected[..]);
+        }
+        sender
+            .send(Signal::Samples {
+                sample_rate: 48000.0,
+                chunk: Chunk::from(vec![]),
+            })
+            .await
+            .unwrap();
+        sender
+            .send(Signal::new_event(crate::blocks::io::raw::events::EndOfFile))
+            .await
+            .unwrap();
+        let Signal::Event(event) = receiver.recv().await.unwrap() else { panic!(); };
+        assert!(event.is_flush());
+    }
+    #[tokio::test]
+    async fn test_mixer() {
+        let mixer = Mixer::<f32>::new(2);
+        let senders: Vec<_> = mixer
+            .inputs()
+            .iter()
+            .map(|input| {
+                let (sender, sender_connector) = new_sender::<Signal<Complex<f32>>>();
+                input.feed_from(&sender_connector);
+                sender
+            })
+            .collect();
+        let (mut receiver, receiver_connector) = new_receiver::<Signal<Complex<f32>>>();
+        mixer.feed_into(&receiver_connector);
+        mixer.input(1).set_gain(0.5);
+        mixer.set_max_delay(0.01);
+        let samples = |values: &[f32], sample_rate| Signal::Samples {
+            sample_rate,
+            chunk: Chunk::from(
+                values
+                    .iter()
+                    .map(|&re| Complex::new(re, 0.0))
+                    .collect::<Vec<_>>(),
+            ),
+        };
+        senders[0]
+            .send(samples(&[0.25, 0.5, 0.75], 1000.0))
+            .await
+            .unwrap();
+        let (result, signal) = tokio::join!(
+            senders[1].send(samples(&[0.5, 1.0], 1000.0)),
+            receiver.recv()
+        );
+        result.unwrap();
+        let Signal::Samples { sample_rate, chunk } = signal.unwrap() else {
+            panic!("expected samples");
+        };
+        assert_eq!(sample_rate, 1000.0);
+        assert_eq!(&chunk[..], &[Complex::new(0.5, 0.0), Complex::new(1.0, 0.0)]);
+        // samples with different rate are discarded and reported
+        let (result, signal) = tokio::join!(
+            senders[1].send(samples(&[1.0], 2000.0)),
+            receiver.recv()
+        );
+        result.unwrap();
+        let Signal::Event(event) = signal.unwrap() else {
+            panic!("expected event");
+        };
+        let mismatch = event.as_any().downcast_ref::<SampleRateMismatch>().unwrap();
+        assert_eq!(mismatch.input, 1);
+        assert_eq!(mismatch.sample_rate, 2000.0);
+        // output is clamped
+        let (result, signal) = tokio::join!(
+            senders[1].send(samples(&[1.0], 1000.0)),
+            receiver.recv()
+        );
+        result.unwrap();
+        let Signal::Samples { chunk, .. } = signal.unwrap() else {
+            panic!("expected samples");
+        };
+        assert_eq!(&chunk[..], &[Complex::new(1.0, 0.0)]);
+        // lagging input is considered silent after the maximum delay
+        let (result, signal) = tokio::join!(
+            senders[0].send(samples(&[0.125; 15], 1000.0)),
+            receiver.recv()
+        );
+        result.unwrap();
+        let Signal::Samples { chunk, .. } = signal.unwrap() else {
+            panic!("expected samples");
+        };
+        assert_eq!(&chunk[..], &[Complex::new(0.125, 0.0); 5]);
+    }
+    #[tokio::test]
+    async fn test_complex_split_join() {
+        let (sender, sender_connector) = new_sender::<Signal<Complex<f32>>>();
+        let split = ComplexSplit::<f32>::new();
+        let join = ComplexJoin::<f32>::new();
+        let (mut i_receiver, i_receiver_connector) = new_receiver::<Signal<f32>>();
+        let (mut receiver, receiver_connector) = new_receiver::<Signal<Complex<f32>>>();
+        split.feed_from(&sender_connector);
+        split.in_phase().feed_into(&i_receiver_connector);
+        join.in_phase().feed_from(split.in_phase());
+        join.quadrature().feed_from(split.quadrature());
+        join.feed_into(&receiver_connector);
+        let samples: Vec<Complex<f32>> = (0..10).map(|i| Complex::new(i as f32, -i as f32)).collect();
+        sender
+            .send(Signal::Samples {
+                sample_rate: 48000.0,
+                chunk: Chunk::from(samples.clone()),
+            })
+            .await
+            .unwrap();
+        let Signal::Samples { chunk, .. } = i_receiver.recv().await.unwrap() else { panic!(); };
+        assert_eq!(&chunk[..], &[0.0, 1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 9.0]);
+        let Signal::Samples { sample_rate, chunk } = receiver.recv().await.unwrap()
+        else { panic!(); };
+        assert_eq!(sample_rate, 48000.0);
+        assert_eq!(&chunk[..], &samples[..]);
+        // unaligned chunks of the inputs
+        let join = ComplexJoin::<f32>::new();
+        let (i_sender, i_sender_connector) = new_sender::<Signal<f32>>();
+        let (q_sender, q_sender_connector) = new_sender::<Signal<f32>>();
+        let (mut receiver, receiver_connector) = new_receiver::<Signal<Complex<f32>>>();
+        join.in_phase().feed_from(&i_sender_connector);
+        join.quadrature().feed_from(&q_sender_connector);
+        join.feed_into(&receiver_connector);
+        let samples = |values: &[f32]| Signal::Samples {
+            sample_rate: 48000.0,
+            chunk: Chunk::from(values.to_vec()),
+        };
+        i_sender.send(samples(&[1.0, 2.0, 3.0])).await.unwrap();
+        q_sender.send(samples(&[4.0])).await.unwrap();
+        let Signal::Samples { chunk, .. } = receiver.recv().await.unwrap() else { panic!(); };
+        assert_eq!(&chunk[..], &[Complex::new(1.0, 4.0)]);
+        q_sender.send(samples(&[5.0, 6.0, 7.0])).await.unwrap();
+        let Signal::Samples { chunk, .. } = receiver.recv().await.unwrap() else { panic!(); };
+        assert_eq!(&chunk[..], &[Complex::new(2.0, 5.0), Complex::new(3.0, 6.0)]);
     }
 }
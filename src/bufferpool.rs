@@ -19,13 +19,18 @@
 //! non-recyclable. This can be used where only a single `Chunk<T>` is needed
 //! and recycling doesn't give any advantages.
 //!
+//! A `ChunkBufPool` may be limited to a maximum number of buffers (see
+//! [`ChunkBufPool::with_capacity`]) and provides [statistics] about the
+//! buffers.
+//!
 //! [blocks]: crate::blocks
 //! [finalized]: ChunkBuf::finalize
+//! [statistics]: ChunkBufPool::stats
 
-use tokio::sync::mpsc;
+use tokio::sync::{mpsc, watch};
 
 use std::mem::take;
-use std::ops::{Deref, DerefMut, Range};
+use std::ops::{Bound, Deref, DerefMut, Range, RangeBounds};
 use std::sync::Arc;
 
 /// Buffer for reading that gets recycled when dropped
@@ -37,6 +42,11 @@ use std::sync::Arc;
 /// A `Chunk<T>` is read-only with the exception that parts at the beginning
 /// may be discarded.
 ///
+/// Cloning a chunk or taking a part of it (see [`Chunk::slice`] and
+/// [`Chunk::separate_beginning`]) does not copy any data. Instead, the
+/// resulting chunks share the same internal buffer. As chunks are read-only,
+/// this aliasing cannot be observed.
+///
 /// When dropped, the underlying buffer gets recycled by sending it back to the
 /// originating [`ChunkBufPool<T>`] if no other chunks (clones or separated
 /// chunks) are left sharing the same internal buffer.
@@ -77,6 +87,29 @@ impl<T> Chunk<T> {
             recycler: self.recycler.clone(),
         }
     }
+    /// Return new [`Chunk<T>`] consisting of the elements in the given
+    /// `range` (relative to the beginning of this chunk)
+    ///
+    /// No data will be copied for this operation.
+    pub fn slice<R: RangeBounds<usize>>(&self, range: R) -> Self {
+        let len = self.range.end - self.range.start;
+        let start = match range.start_bound() {
+            Bound::Included(&start) => start,
+            Bound::Excluded(&start) => start + 1,
+            Bound::Unbounded => 0,
+        };
+        let end = match range.end_bound() {
+            Bound::Included(&end) => end + 1,
+            Bound::Excluded(&end) => end,
+            Bound::Unbounded => len,
+        };
+        assert!(start <= end && end <= len, "range out of bounds");
+        Chunk {
+            buffer: self.buffer.clone(),
+            range: self.range.start + start..self.range.start + end,
+            recycler: self.recycler.clone(),
+        }
+    }
 }
 
 impl<T> Drop for Chunk<T> {
@@ -128,18 +161,15 @@ pub struct ChunkBuf<T> {
 }
 
 impl<T> ChunkBuf<T> {
-    fn new(buffer: Vec<T>, recycler: mpsc::UnboundedSender<Vec<T>>) -> Self {
-        ChunkBuf {
-            buffer,
-            recycler: Some(recycler),
-        }
+    fn new(buffer: Vec<T>, recycler: Option<mpsc::UnboundedSender<Vec<T>>>) -> Self {
+        ChunkBuf { buffer, recycler }
     }
     /// Convert into [`Chunk<T>`]
     ///
     /// This method is also invoked when using [`From`] or [`Into`] to convert
     /// a `ChunkBuf<T>` into a `Chunk<T>`.
     pub fn finalize(mut self) -> Chunk<T> {
-        Chunk::new(take(&mut self.buffer), Some(self.recycler.take().unwrap()))
+        Chunk::new(take(&mut self.buffer), self.recycler.take())
     }
 }
 
@@ -164,6 +194,20 @@ impl<T> DerefMut for ChunkBuf<T> {
     }
 }
 
+/// Statistics of a [`ChunkBufPool`], see [`ChunkBufPool::stats`]
+#[derive(Clone, Copy, PartialEq, Eq, Default, Debug)]
+pub struct PoolStats {
+    /// Number of buffers available for reuse
+    pub idle: usize,
+    /// Number of recyclable buffers currently in use (as [`ChunkBuf`] or
+    /// [`Chunk`])
+    pub in_flight: usize,
+    /// Total number of buffers allocated by the pool (including
+    /// non-recyclable buffers allocated when the maximum number of buffers
+    /// was reached)
+    pub allocations: u64,
+}
+
 /// Pool to obtain [`ChunkBuf<T>`]s
 ///
 /// [`ChunkBufPool::get`] will either reuse a previously [recycled buffer] or
@@ -171,6 +215,19 @@ impl<T> DerefMut for ChunkBuf<T> {
 /// When it's known how many elements will be filled into a `ChunkBuf<T>`, then
 /// [`ChunkBufPool::get_with_capacity`] can be used.
 ///
+/// A buffer is recycled when the `ChunkBuf<T>` or the last clone of the
+/// resulting [`Chunk`] is dropped, keeping its allocated capacity. Idle
+/// buffers are grouped by capacity (in power-of-two size classes), and the
+/// smallest idle buffer with sufficient capacity is reused, such that a mix
+/// of small and large requests doesn't cause repeated reallocation. A new
+/// buffer is only allocated if no idle buffer is large enough.
+///
+/// If the pool has been created with [`ChunkBufPool::with_capacity`], the
+/// number of recyclable buffers is limited. When all of these buffers are in
+/// use, [`ChunkBufPool::get`] and [`ChunkBufPool::get_with_capacity`]
+/// allocate a buffer which is not recycled, while [`ChunkBufPool::acquire`]
+/// waits until a buffer has been recycled.
+///
 /// [recycled buffer]: Chunk
 ///
 /// # Example
@@ -187,37 +244,247 @@ impl<T> DerefMut for ChunkBuf<T> {
 pub struct ChunkBufPool<T> {
     recycler: mpsc::UnboundedSender<Vec<T>>,
     dispenser: mpsc::UnboundedReceiver<Vec<T>>,
+    /// idle buffers, indexed by [`size_class`] of their capacity
+    idle: Vec<Vec<Vec<T>>>,
+    idle_count: usize,
+    max_buffers: Option<usize>,
+    buffers: usize,
+    allocations: u64,
+    in_flight: watch::Sender<usize>,
+}
+
+/// Size class of a buffer with given capacity
+///
+/// Buffers of size class `n > 0` have a capacity of at least
+/// 2<sup>`n-1`</sup> and less than 2<sup>`n`</sup>.
+fn size_class(capacity: usize) -> usize {
+    (usize::BITS - capacity.leading_zeros()) as usize
 }
 
 impl<T> ChunkBufPool<T> {
     /// Create a new `ChunkBufPool<T>`
     pub fn new() -> Self {
+        Self::new_internal(None)
+    }
+    /// Create a new `ChunkBufPool<T>` which recycles at most `max_buffers`
+    /// buffers
+    pub fn with_capacity(max_buffers: usize) -> Self {
+        assert!(
+            max_buffers > 0,
+            "maximum number of buffers must be positive"
+        );
+        Self::new_internal(Some(max_buffers))
+    }
+    fn new_internal(max_buffers: Option<usize>) -> Self {
         let (recycler, dispenser) = mpsc::unbounded_channel::<Vec<T>>();
         Self {
             recycler,
             dispenser,
+            idle: Vec::new(),
+            idle_count: 0,
+            max_buffers,
+            buffers: 0,
+            allocations: 0,
+            in_flight: watch::channel(0).0,
         }
     }
-    /// Get a new [`ChunkBuf<T>`]
-    pub fn get(&mut self) -> ChunkBuf<T> {
-        let buffer = match self.dispenser.try_recv() {
-            Ok(mut buffer) => {
-                buffer.clear();
-                buffer
+    fn collect(&mut self) {
+        while let Ok(buffer) = self.dispenser.try_recv() {
+            let class = size_class(buffer.capacity());
+            if self.idle.len() <= class {
+                self.idle.resize_with(class + 1, Vec::new);
             }
-            Err(_) => Vec::new(),
+            self.idle[class].push(buffer);
+            self.idle_count += 1;
+        }
+    }
+    /// Remove smallest idle buffer with at least given `capacity`
+    fn pop_fitting(&mut self, capacity: usize) -> Option<Vec<T>> {
+        let class = size_class(capacity);
+        let buffer = match self.idle.get_mut(class).and_then(|buffers| {
+            let pos = buffers.iter().position(|b| b.capacity() >= capacity)?;
+            Some(buffers.swap_remove(pos))
+        }) {
+            Some(buffer) => Some(buffer),
+            None => self
+                .idle
+                .iter_mut()
+                .skip(class + 1)
+                .find_map(|buffers| buffers.pop()),
         };
-        ChunkBuf::new(buffer, self.recycler.clone())
+        if buffer.is_some() {
+            self.idle_count -= 1;
+        }
+        buffer
+    }
+    /// Remove largest idle buffer
+    fn pop_largest(&mut self) -> Option<Vec<T>> {
+        let buffer = self.idle.iter_mut().rev().find_map(|buffers| buffers.pop());
+        if buffer.is_some() {
+            self.idle_count -= 1;
+        }
+        buffer
+    }
+    fn update_in_flight(&self) {
+        let in_flight = self.buffers - self.idle_count;
+        self.in_flight.send_if_modified(|value| {
+            let changed = *value != in_flight;
+            *value = in_flight;
+            changed
+        });
+    }
+    fn exhausted(&self) -> bool {
+        match self.max_buffers {
+            Some(max_buffers) => self.buffers >= max_buffers,
+            None => false,
+        }
+    }
+    fn reuse(&mut self, mut buffer: Vec<T>, capacity: usize) -> ChunkBuf<T> {
+        buffer.clear();
+        buffer.reserve(capacity);
+        let chunk_buf = ChunkBuf::new(buffer, Some(self.recycler.clone()));
+        self.update_in_flight();
+        chunk_buf
+    }
+    fn take(&mut self, capacity: usize) -> ChunkBuf<T> {
+        self.collect();
+        if let Some(buffer) = self.pop_fitting(capacity) {
+            return self.reuse(buffer, capacity);
+        }
+        if self.exhausted() {
+            // growing an idle buffer is better than not recycling at all
+            if let Some(buffer) = self.pop_largest() {
+                return self.reuse(buffer, capacity);
+            }
+        }
+        self.allocations += 1;
+        let buffer = Vec::with_capacity(capacity);
+        if !self.exhausted() {
+            self.buffers += 1;
+            self.update_in_flight();
+            ChunkBuf::new(buffer, Some(self.recycler.clone()))
+        } else {
+            ChunkBuf::new(buffer, None)
+        }
+    }
+    /// Get a new [`ChunkBuf<T>`]
+    pub fn get(&mut self) -> ChunkBuf<T> {
+        self.take(0)
     }
     /// Get a new [`ChunkBuf<T>`] with at least the specified `capacity`
+    ///
+    /// The smallest idle buffer which is large enough is reused; otherwise a
+    /// new buffer is allocated (or, if the maximum number of buffers is in
+    /// use, an idle buffer is enlarged), such that the returned buffer never
+    /// reallocates before `capacity` elements have been pushed.
     pub fn get_with_capacity(&mut self, capacity: usize) -> ChunkBuf<T> {
-        let buffer = match self.dispenser.try_recv() {
-            Ok(mut buffer) => {
-                buffer.clear();
-                buffer
+        self.take(capacity)
+    }
+    /// Get a new [`ChunkBuf<T>`] with at least the specified `capacity`,
+    /// waiting until a buffer has been recycled if the maximum number of
+    /// buffers is in use
+    pub async fn acquire(&mut self, capacity: usize) -> ChunkBuf<T> {
+        self.collect();
+        if self.idle_count == 0 && self.exhausted() {
+            let buffer = self.dispenser.recv().await.unwrap();
+            return self.reuse(buffer, capacity);
+        }
+        self.take(capacity)
+    }
+    /// Current statistics
+    pub fn stats(&mut self) -> PoolStats {
+        self.collect();
+        self.update_in_flight();
+        PoolStats {
+            idle: self.idle_count,
+            in_flight: self.buffers - self.idle_count,
+            allocations: self.allocations,
+        }
+    }
+    /// [`watch::Receiver`] of number of recyclable buffers in use
+    ///
+    /// The value is updated whenever a buffer is obtained from the pool or
+    /// [`ChunkBufPool::stats`] is called. A steadily increasing value
+    /// indicates that [`Chunk`]s are not dropped.
+    pub fn in_flight(&self) -> watch::Receiver<usize> {
+        self.in_flight.subscribe()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[tokio::test]
+    async fn test_pool_capacity() {
+        let mut buf_pool = ChunkBufPool::<u8>::with_capacity(2);
+        let chunk1 = buf_pool.get_with_capacity(4).finalize();
+        let chunk2 = buf_pool.get().finalize();
+        let in_flight = buf_pool.in_flight();
+        assert_eq!(*in_flight.borrow(), 2);
+        let chunk3 = buf_pool.get().finalize();
+        assert_eq!(
+            buf_pool.stats(),
+            PoolStats {
+                idle: 0,
+                in_flight: 2,
+                allocations: 3,
             }
-            Err(_) => Vec::with_capacity(capacity),
-        };
-        ChunkBuf::new(buffer, self.recycler.clone())
+        );
+        drop(chunk3);
+        assert_eq!(buf_pool.stats().idle, 0);
+        let chunk1_clone = chunk1.clone();
+        drop(chunk1);
+        assert_eq!(buf_pool.stats().idle, 0);
+        drop(chunk1_clone);
+        assert_eq!(buf_pool.stats().idle, 1);
+        let _chunk4 = buf_pool.acquire(4).await;
+        let join_handle = tokio::spawn(async move {
+            let _chunk5 = buf_pool.acquire(4).await;
+            buf_pool.stats()
+        });
+        tokio::task::yield_now().await;
+        drop(chunk2);
+        let stats = join_handle.await.unwrap();
+        assert_eq!(stats.in_flight, 2);
+        assert_eq!(stats.allocations, 3);
+    }
+    #[test]
+    fn test_pool_size_classes() {
+        let mut buf_pool = ChunkBufPool::<u8>::new();
+        let small = buf_pool.get_with_capacity(10);
+        let large = buf_pool.get_with_capacity(1000);
+        drop((small, large));
+        let buf = buf_pool.get_with_capacity(500);
+        assert!(buf.capacity() >= 1000);
+        let buf2 = buf_pool.get_with_capacity(8);
+        assert!(buf2.capacity() >= 10 && buf2.capacity() < 1000);
+        assert_eq!(buf_pool.stats().allocations, 2);
+        drop((buf, buf2));
+        let buf = buf_pool.get_with_capacity(2000);
+        assert!(buf.capacity() >= 2000);
+        assert_eq!(buf_pool.stats().allocations, 3);
+        assert_eq!(buf_pool.stats().idle, 2);
+        let mut limited_pool = ChunkBufPool::<u8>::with_capacity(1);
+        drop(limited_pool.get_with_capacity(10));
+        let buf = limited_pool.get_with_capacity(100);
+        assert!(buf.capacity() >= 100);
+        assert_eq!(limited_pool.stats().allocations, 1);
+        assert_eq!(limited_pool.stats().in_flight, 1);
+    }
+    #[test]
+    fn test_chunk_slice() {
+        let mut buf_pool = ChunkBufPool::<u8>::new();
+        let mut chunk_buf = buf_pool.get();
+        chunk_buf.extend_from_slice(&[1, 2, 3, 4, 5]);
+        let mut chunk = chunk_buf.finalize();
+        chunk.discard_beginning(1);
+        let slice = chunk.slice(1..=2);
+        assert_eq!(&*slice, &[3, 4]);
+        assert_eq!(&*slice.slice(1..), &[4]);
+        assert_eq!(&*chunk.slice(..), &[2, 3, 4, 5]);
+        drop(chunk);
+        assert_eq!(buf_pool.stats().idle, 0);
+        drop(slice);
+        assert_eq!(buf_pool.stats().idle, 1);
     }
 }
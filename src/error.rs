@@ -0,0 +1,91 @@
+//! Errors reported by background tasks of blocks
+//!
+//! Most blocks report errors through their own error types (e.g.
+//! [`std::io::Error`] for file I/O). The [`Error`] type in this module
+//! combines these with failures of background tasks, such that a supervisor
+//! can tell a hardware error from a panic.
+
+use std::any::Any;
+use std::error::Error as StdError;
+use std::fmt;
+use std::io;
+
+/// Error which ended the background task of a block
+#[derive(Debug)]
+pub enum Error {
+    /// Error reported by SoapySDR
+    #[cfg(feature = "soapysdr")]
+    SoapySdr(soapysdr::Error),
+    /// I/O error
+    Io(io::Error),
+    /// The background task (or a thread it used) panicked with the given
+    /// message
+    Panic(String),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            #[cfg(feature = "soapysdr")]
+            Error::SoapySdr(err) => write!(f, "SoapySDR error: {err}"),
+            Error::Io(err) => write!(f, "I/O error: {err}"),
+            Error::Panic(message) => write!(f, "background task panicked: {message}"),
+        }
+    }
+}
+
+impl StdError for Error {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        match self {
+            #[cfg(feature = "soapysdr")]
+            Error::SoapySdr(err) => Some(err),
+            Error::Io(err) => Some(err),
+            Error::Panic(_) => None,
+        }
+    }
+}
+
+#[cfg(feature = "soapysdr")]
+impl From<soapysdr::Error> for Error {
+    fn from(err: soapysdr::Error) -> Self {
+        Error::SoapySdr(err)
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(err: io::Error) -> Self {
+        Error::Io(err)
+    }
+}
+
+impl Error {
+    /// Create [`Error::Panic`] from the payload of a panic
+    pub fn from_panic(payload: Box<dyn Any + Send>) -> Self {
+        let message = match payload.downcast::<String>() {
+            Ok(message) => *message,
+            Err(payload) => match payload.downcast::<&'static str>() {
+                Ok(message) => message.to_string(),
+                Err(_) => "unknown panic payload".to_string(),
+            },
+        };
+        Error::Panic(message)
+    }
+    /// Return true if this is an [`Error::Panic`]
+    pub fn is_panic(&self) -> bool {
+        matches!(self, Error::Panic(_))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn test_from_panic() {
+        let payload = std::panic::catch_unwind(|| panic!("value {}", 42)).unwrap_err();
+        let err = Error::from_panic(payload);
+        assert!(err.is_panic());
+        assert_eq!(err.to_string(), "background task panicked: value 42");
+        let payload = std::panic::catch_unwind(|| panic!("static")).unwrap_err();
+        assert!(matches!(Error::from_panic(payload), Error::Panic(m) if m == "static"));
+    }
+}
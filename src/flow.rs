@@ -25,6 +25,34 @@
 //! of received values. This value is determined by the
 //! [`Message::disconnection`] method.
 //!
+//! # Reconnection at runtime
+//!
+//! A `Consumer` may be connected to a different `Producer` at any time by
+//! calling [`Consumer::feed_from`] again (or disconnected by calling
+//! [`Consumer::feed_from_none`]). The previous `Producer` is not affected
+//! otherwise. When a [`Receiver`] notices the change, it stops receiving from
+//! the previous `Sender` and returns the [`Message::disconnection`] value
+//! (if any) before any value from the new `Sender`. Values which have been
+//! sent by the previous `Sender` but not received yet are dropped for that
+//! `Receiver`.
+//!
+//! Note that a `Sender` without any `Receiver`s waits (see [`Sender::send`]).
+//! To keep a chain running while nothing is connected to its end, terminate
+//! it with a block that doesn't apply backpressure, e.g. a [`Splitter`] with
+//! a [`Lossy`] output.
+//!
+//! # Resetting blocks
+//!
+//! Blocks clear their internal state (e.g. filter history, loop states, or
+//! averages) when they receive an [interrupting] event, like after an
+//! overflow, a retune, or a seek, and pass the event on to downstream blocks.
+//! [`Consumer::reset`] uses the same mechanism to reset a block on request:
+//! its [`Receiver`]s return the [`Message::reset`] value (if any) before the
+//! next value from the `Sender`, such that the block and all blocks
+//! downstream of it are reset.
+//!
+//! [interrupting]: crate::signal::Event::is_interrupt
+//!
 //! # Implementing a `Producer` or `Consumer`
 //!
 //! Upon creation, `Producer`s use the [`new_sender`] function to create a pair
@@ -55,19 +83,59 @@
 //! behavior, including dropping data in case of congestion and countermeasures
 //! against latency.
 //!
+//! A [`SenderObserver`], obtained through [`SenderConnector::observer`],
+//! allows monitoring whether values are pending and how many values have been
+//! sent, which helps finding the block limiting throughput of a chain.
+//!
 //! [`blocks`]: crate::blocks
 //! [`Buffer`]: crate::blocks::buffering::Buffer
+//! [`Splitter`]: crate::blocks::buffering::Splitter
+//! [`Lossy`]: crate::blocks::buffering::BranchPolicy::Lossy
+//!
+//! # Profiling
+//!
+//! When the `profiling` feature is enabled, each [`Receiver`] measures the
+//! time from returning a value until it is called again, i.e. the time its
+//! task spends handling each received value. The accumulated `Profile` of
+//! a block can be retrieved with `profile` (or with
+//! `ReceiverConnector::profile` and `SenderObserver::blocked_time`).
+//! Without the feature, no measurements are made.
+//!
+//! # Describing the topology
+//!
+//! A [`Topology`] collects a [`BlockInfo`] for each block of a chain
+//! (including the types of the passed signals and the sample rate last
+//! received) and determines which blocks are connected with each other, such
+//! that the chain can be rendered with Graphviz using [`Topology::to_dot`].
+//!
+//! # Shutdown
+//!
+//! Blocks stop working when dropped, but their background tasks terminate
+//! asynchronously. A [`Graph`] can be used to own several blocks, drop them
+//! together, and wait until the tasks of observed [`Producer`]s have
+//! terminated.
 
 use crate::sync::broadcast_bp;
 
 use tokio::select;
 use tokio::sync::watch;
 
-use std::future::pending;
+use std::any::{type_name, Any};
+use std::fmt::Write as _;
+use std::future::{pending, Future};
+use std::marker::PhantomData;
+use std::ops::Index;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+#[cfg(feature = "profiling")]
+use std::sync::Mutex;
+#[cfg(feature = "profiling")]
+use std::time::{Duration, Instant};
 
 pub use crate::sync::broadcast_bp::{
-    channel as new_sender, Enlister as SenderConnector, RecvError, Reservation, RsrvError,
-    SendError, Sender,
+    channel as new_sender, ChannelId, Enlister as SenderConnector, Observer as SenderObserver,
+    RecvError, Reservation, RsrvError, SendError, Sender,
 };
 
 /// Types that can be used as message from [`Sender`] to [`Receiver`]
@@ -75,6 +143,20 @@ pub trait Message: Sized + Clone {
     /// Return message that indicates disconnection or `None` if not
     /// supported
     fn disconnection() -> Option<Self>;
+    /// Return message that requests resetting the receiving block or `None`
+    /// if not supported
+    ///
+    /// See [`Consumer::reset`].
+    fn reset() -> Option<Self> {
+        None
+    }
+    /// Return sample rate of the message or `None` if not applicable
+    ///
+    /// The sample rate of the most recently received message is reported by
+    /// [`ReceiverConnector::sample_rate`].
+    fn sample_rate(&self) -> Option<f64> {
+        None
+    }
 }
 
 /// Wrapper implementing [`Message`], which doesn't provide a value that
@@ -102,6 +184,10 @@ where
 #[derive(Debug)]
 pub struct ReceiverConnector<T> {
     enlister_tx: watch::Sender<Option<broadcast_bp::Enlister<T>>>,
+    reset_tx: watch::Sender<()>,
+    sample_rate: Arc<AtomicU64>,
+    #[cfg(feature = "profiling")]
+    profile: Arc<Mutex<Profile>>,
 }
 
 /// Receiver that can be dynamically connected to a [`Sender`]
@@ -117,18 +203,103 @@ pub struct ReceiverConnector<T> {
 #[derive(Debug)]
 pub struct Receiver<T> {
     enlister_rx: watch::Receiver<Option<broadcast_bp::Enlister<T>>>,
+    reset_rx: watch::Receiver<()>,
     inner_receiver: Option<broadcast_bp::Receiver<T>>,
+    sample_rate: Arc<AtomicU64>,
+    #[cfg(feature = "profiling")]
+    profiler: Profiler,
 }
 
 impl<T> Clone for Receiver<T> {
     fn clone(&self) -> Self {
         Self {
             enlister_rx: self.enlister_rx.clone(),
+            reset_rx: self.reset_rx.clone(),
             inner_receiver: self.inner_receiver.clone(),
+            sample_rate: self.sample_rate.clone(),
+            #[cfg(feature = "profiling")]
+            profiler: Profiler::new(self.profiler.profile.clone()),
+        }
+    }
+}
+
+/// Accumulated processing statistics of a block (requires `profiling`
+/// feature)
+///
+/// See [module level documentation] on profiling.
+///
+/// [module level documentation]: self#profiling
+#[cfg(feature = "profiling")]
+#[derive(Clone, Copy, PartialEq, Eq, Default, Debug)]
+pub struct Profile {
+    /// Number of received values which have been handled completely
+    pub count: u64,
+    /// Total time spent handling received values
+    pub busy: Duration,
+    /// Part of [`busy`](Self::busy) spent waiting until values could be
+    /// sent (only filled in by [`profile`])
+    pub blocked: Duration,
+}
+
+#[cfg(feature = "profiling")]
+impl Profile {
+    /// Time spent processing, i.e. [`busy`](Self::busy) time without
+    /// [`blocked`](Self::blocked) time
+    pub fn processing(&self) -> Duration {
+        self.busy.saturating_sub(self.blocked)
+    }
+    /// Average processing time per received value
+    pub fn processing_per_value(&self) -> Duration {
+        match u32::try_from(self.count) {
+            Ok(0) => Duration::ZERO,
+            Ok(count) => self.processing() / count,
+            Err(_) => self.processing().div_f64(self.count as f64),
+        }
+    }
+}
+
+#[cfg(feature = "profiling")]
+#[derive(Debug)]
+struct Profiler {
+    profile: Arc<Mutex<Profile>>,
+    busy_since: Option<Instant>,
+}
+
+#[cfg(feature = "profiling")]
+impl Profiler {
+    fn new(profile: Arc<Mutex<Profile>>) -> Self {
+        Self {
+            profile,
+            busy_since: None,
+        }
+    }
+    fn start(&mut self) {
+        self.busy_since = Some(Instant::now());
+    }
+    fn stop(&mut self) {
+        if let Some(since) = self.busy_since.take() {
+            let mut profile = self.profile.lock().unwrap();
+            profile.count += 1;
+            profile.busy += since.elapsed();
         }
     }
 }
 
+/// Obtain accumulated [`Profile`] of a block (requires `profiling` feature)
+///
+/// The [`blocked`](Profile::blocked) time is the time the block's
+/// [`Sender`] waited for the receiving blocks.
+#[cfg(feature = "profiling")]
+pub fn profile<B, T, U>(block: &B) -> Profile
+where
+    B: Consumer<T> + Producer<U>,
+{
+    Profile {
+        blocked: block.sender_connector().observer().blocked_time(),
+        ..block.receiver_connector().profile()
+    }
+}
+
 /// Create a [`Receiver`] with an associated [`ReceiverConnector`]
 ///
 /// Alternatively, you can use [`ReceiverConnector::new`] and
@@ -144,6 +315,10 @@ impl<T> ReceiverConnector<T> {
     pub fn new() -> Self {
         Self {
             enlister_tx: watch::channel(None).0,
+            reset_tx: watch::channel(()).0,
+            sample_rate: Arc::new(AtomicU64::new(f64::NAN.to_bits())),
+            #[cfg(feature = "profiling")]
+            profile: Default::default(),
         }
     }
     /// Connect associated [`Receiver`]s with a [`Sender`]
@@ -154,6 +329,27 @@ impl<T> ReceiverConnector<T> {
     pub fn disconnect(&self) {
         self.enlister_tx.send_replace(None);
     }
+    /// Make associated [`Receiver`]s return the [`Message::reset`] value
+    /// (if any) before receiving further values
+    ///
+    /// Multiple requests which occur before the `Receiver` is polled result
+    /// in a single reset message.
+    pub fn reset(&self) {
+        self.reset_tx.send_replace(());
+    }
+    /// Identifier of the channel of the connected [`Sender`], if connected
+    pub fn connected_channel(&self) -> Option<ChannelId> {
+        self.enlister_tx
+            .borrow()
+            .as_ref()
+            .map(|enlister| enlister.channel_id())
+    }
+    /// Sample rate of the value most recently received by any associated
+    /// [`Receiver`] (see [`Message::sample_rate`])
+    pub fn sample_rate(&self) -> Option<f64> {
+        let sample_rate = f64::from_bits(self.sample_rate.load(Ordering::Relaxed));
+        (!sample_rate.is_nan()).then_some(sample_rate)
+    }
     /// Obtain an associated [`Receiver`]
     pub fn stream(&self) -> Receiver<T> {
         let mut enlister_rx = self.enlister_tx.subscribe();
@@ -163,9 +359,22 @@ impl<T> ReceiverConnector<T> {
             .map(|x| x.subscribe());
         Receiver {
             enlister_rx,
+            reset_rx: self.reset_tx.subscribe(),
             inner_receiver,
+            sample_rate: self.sample_rate.clone(),
+            #[cfg(feature = "profiling")]
+            profiler: Profiler::new(self.profile.clone()),
         }
     }
+    /// Accumulated [`Profile`] of all associated [`Receiver`]s (requires
+    /// `profiling` feature)
+    ///
+    /// The [`blocked`](Profile::blocked) time is always zero; use [`profile`]
+    /// to include it.
+    #[cfg(feature = "profiling")]
+    pub fn profile(&self) -> Profile {
+        *self.profile.lock().unwrap()
+    }
 }
 
 impl<T> Receiver<T>
@@ -174,6 +383,20 @@ where
 {
     /// Receive data from connected [`Sender`]
     pub async fn recv(&mut self) -> Result<T, RecvError> {
+        #[cfg(feature = "profiling")]
+        self.profiler.stop();
+        let result = self.recv_unprofiled().await;
+        if let Some(sample_rate) = result.as_ref().ok().and_then(Message::sample_rate) {
+            self.sample_rate
+                .store(sample_rate.to_bits(), Ordering::Relaxed);
+        }
+        #[cfg(feature = "profiling")]
+        if result.is_ok() {
+            self.profiler.start();
+        }
+        result
+    }
+    async fn recv_unprofiled(&mut self) -> Result<T, RecvError> {
         let change = |this: &mut Self| {
             let was_connected = this.inner_receiver.is_some();
             this.inner_receiver = this
@@ -181,6 +404,12 @@ where
                 .borrow_and_update()
                 .as_ref()
                 .map(|x| x.subscribe());
+            event!(
+                DEBUG,
+                signal_type = std::any::type_name::<T>(),
+                connected = this.inner_receiver.is_some(),
+                "receiver connection changed"
+            );
             if was_connected {
                 Message::disconnection()
             } else {
@@ -188,9 +417,26 @@ where
             }
         };
         let mut unchangeable = false;
+        let mut unresettable = false;
         loop {
+            if self.reset_rx.has_changed().unwrap_or(false) {
+                self.reset_rx.borrow_and_update();
+                if let Some(message) = T::reset() {
+                    return Ok(message);
+                }
+            }
+            let reset_rx = &mut self.reset_rx;
+            let reset_requested = async {
+                if unresettable || reset_rx.changed().await.is_err() {
+                    unresettable = true;
+                    pending::<()>().await;
+                }
+            };
             if let Some(inner_receiver) = self.inner_receiver.as_mut() {
                 select! {
+                    _ = reset_requested => if let Some(message) = T::reset() {
+                        return Ok(message);
+                    },
                     result = async {
                         if unchangeable {
                             pending::<()>().await;
@@ -212,13 +458,18 @@ where
                     }
                 }
             } else {
-                match self.enlister_rx.changed().await {
-                    Ok(()) => {
-                        if let Some(message) = change(self) {
-                            return Ok(message);
+                select! {
+                    _ = reset_requested => if let Some(message) = T::reset() {
+                        return Ok(message);
+                    },
+                    result = self.enlister_rx.changed() => match result {
+                        Ok(()) => {
+                            if let Some(message) = change(self) {
+                                return Ok(message);
+                            }
                         }
-                    }
-                    Err(_) => return Err(RecvError),
+                        Err(_) => return Err(RecvError),
+                    },
                 }
             }
         }
@@ -264,6 +515,19 @@ pub trait Consumer<T> {
     fn feed_from_none(&self) {
         self.receiver_connector().disconnect();
     }
+    /// Request the block to reset its internal state
+    ///
+    /// For [`Signal`]s, the block receives a [`Reset`] event before the next
+    /// value and handles it like any other [interrupting] event, i.e. it
+    /// clears its state and passes the event on, so that downstream blocks
+    /// are reset as well. See also [`ReceiverConnector::reset`].
+    ///
+    /// [`Signal`]: crate::signal::Signal
+    /// [`Reset`]: crate::signal::Reset
+    /// [interrupting]: crate::signal::Event::is_interrupt
+    fn reset(&self) {
+        self.receiver_connector().reset();
+    }
 }
 
 impl<T> Consumer<T> for ReceiverConnector<T> {
@@ -272,5 +536,494 @@ impl<T> Consumer<T> for ReceiverConnector<T> {
     }
 }
 
+/// Type of data passed through an input or output of a block
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct PortInfo {
+    /// Name of the type of the passed values (see [`std::any::type_name`])
+    pub signal_type: &'static str,
+    /// Identifier of the channel (for inputs: `None` if not connected)
+    pub channel: Option<ChannelId>,
+}
+
+/// Description of a block for debugging purposes
+///
+/// See [`Topology`].
+#[derive(Clone, PartialEq, Debug)]
+pub struct BlockInfo {
+    /// Name of the block
+    pub name: String,
+    /// Input, unless the block is a source
+    pub input: Option<PortInfo>,
+    /// Output, unless the block is a sink
+    pub output: Option<PortInfo>,
+    /// Sample rate most recently received, if known
+    pub sample_rate: Option<f64>,
+}
+
+impl BlockInfo {
+    /// Describe block without input or output
+    pub fn new<S: Into<String>>(name: S) -> Self {
+        Self {
+            name: name.into(),
+            input: None,
+            output: None,
+            sample_rate: None,
+        }
+    }
+    /// Describe input of a [`Consumer`]
+    pub fn with_input<T, C: Consumer<T>>(mut self, consumer: &C) -> Self {
+        let receiver_connector = consumer.receiver_connector();
+        self.input = Some(PortInfo {
+            signal_type: type_name::<T>(),
+            channel: receiver_connector.connected_channel(),
+        });
+        self.sample_rate = receiver_connector.sample_rate();
+        self
+    }
+    /// Describe output of a [`Producer`]
+    pub fn with_output<T, P: Producer<T>>(mut self, producer: &P) -> Self {
+        self.output = Some(PortInfo {
+            signal_type: type_name::<T>(),
+            channel: Some(producer.sender_connector().channel_id()),
+        });
+        self
+    }
+}
+
+/// Connection between two blocks of a [`Topology`]
+#[derive(Clone, PartialEq, Debug)]
+pub struct Edge {
+    /// Index of the [`Producer`]
+    pub from: usize,
+    /// Index of the [`Consumer`]
+    pub to: usize,
+    /// Name of the type of the passed values
+    pub signal_type: &'static str,
+    /// Sample rate most recently received by the `Consumer`, if known
+    pub sample_rate: Option<f64>,
+}
+
+/// Description of connected blocks for debugging purposes
+///
+/// ```
+/// # #[tokio::main]
+/// # async fn main() {
+/// use radiorust::flow::Topology;
+/// use radiorust::prelude::*;
+/// let source = blocks::io::Silence::<Complex<f32>>::new(1024, 48000.0);
+/// let sink = blocks::io::Blackhole::<Complex<f32>>::new();
+/// sink.feed_from(&source);
+/// let mut topology = Topology::new();
+/// topology.add_source("silence", &source);
+/// topology.add_sink("blackhole", &sink);
+/// assert_eq!(topology.edges().len(), 1);
+/// println!("{}", topology.to_dot());
+/// # }
+/// ```
+#[derive(Clone, Default, Debug)]
+pub struct Topology {
+    blocks: Vec<BlockInfo>,
+}
+
+impl Topology {
+    /// Create empty `Topology`
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Add [`BlockInfo`] and return its index
+    pub fn add(&mut self, info: BlockInfo) -> usize {
+        self.blocks.push(info);
+        self.blocks.len() - 1
+    }
+    /// Add [`Producer`] which is not a [`Consumer`] and return its index
+    pub fn add_source<T, P: Producer<T>>(&mut self, name: &str, block: &P) -> usize {
+        self.add(BlockInfo::new(name).with_output(block))
+    }
+    /// Add [`Consumer`] which is not a [`Producer`] and return its index
+    pub fn add_sink<T, C: Consumer<T>>(&mut self, name: &str, block: &C) -> usize {
+        self.add(BlockInfo::new(name).with_input(block))
+    }
+    /// Add block which is both [`Consumer`] and [`Producer`] and return its
+    /// index
+    pub fn add_block<T, U, B>(&mut self, name: &str, block: &B) -> usize
+    where
+        B: Consumer<T> + Producer<U>,
+    {
+        self.add(
+            BlockInfo::new(name)
+                .with_input::<T, B>(block)
+                .with_output::<U, B>(block),
+        )
+    }
+    /// Added blocks
+    pub fn blocks(&self) -> &[BlockInfo] {
+        &self.blocks
+    }
+    /// Connections between added blocks
+    pub fn edges(&self) -> Vec<Edge> {
+        let mut edges = Vec::new();
+        for (from, producer) in self.blocks.iter().enumerate() {
+            let Some(output) = &producer.output else { continue; };
+            for (to, consumer) in self.blocks.iter().enumerate() {
+                let Some(input) = &consumer.input else { continue; };
+                if input.channel.is_some() && input.channel == output.channel {
+                    edges.push(Edge {
+                        from,
+                        to,
+                        signal_type: output.signal_type,
+                        sample_rate: consumer.sample_rate,
+                    });
+                }
+            }
+        }
+        edges
+    }
+    /// Render as graph in the DOT language of Graphviz
+    pub fn to_dot(&self) -> String {
+        fn escape(label: &str) -> String {
+            label.replace('\\', "\\\\").replace('"', "\\\"")
+        }
+        let mut dot = String::from("digraph {\n");
+        for (index, block) in self.blocks.iter().enumerate() {
+            writeln!(dot, "    {index} [label=\"{}\"];", escape(&block.name)).unwrap();
+        }
+        for edge in self.edges() {
+            let mut label = escape(&short_type_name(edge.signal_type));
+            if let Some(sample_rate) = edge.sample_rate {
+                write!(label, "\\n{sample_rate} Hz").unwrap();
+            }
+            writeln!(dot, "    {} -> {} [label=\"{label}\"];", edge.from, edge.to).unwrap();
+        }
+        dot.push_str("}\n");
+        dot
+    }
+}
+
+/// Remove module paths from a type name
+fn short_type_name(name: &str) -> String {
+    let mut short = String::with_capacity(name.len());
+    let mut segment_start = 0;
+    for (pos, c) in name.char_indices() {
+        if c.is_alphanumeric() || c == '_' || c == ':' {
+            if c == ':' && name[pos + 1..].starts_with(':') {
+                segment_start = pos + 2;
+            }
+            continue;
+        }
+        short.push_str(&name[segment_start..pos]);
+        short.push(c);
+        segment_start = pos + c.len_utf8();
+    }
+    short.push_str(&name[segment_start..]);
+    short
+}
+
+type ShutdownFn =
+    Box<dyn FnOnce(Box<dyn Any + Send>) -> Pin<Box<dyn Future<Output = ()> + Send>> + Send>;
+
+struct GraphEntry {
+    block: Box<dyn Any + Send>,
+    shutdown: Option<ShutdownFn>,
+}
+
+/// Key to access a block owned by a [`Graph`]
+///
+/// Obtained through [`Graph::add`] or [`Graph::add_with_shutdown`] and used
+/// by indexing the `Graph`, e.g. `graph[key]`.
+pub struct BlockKey<B> {
+    index: usize,
+    phantom: PhantomData<fn() -> B>,
+}
+
+impl<B> Clone for BlockKey<B> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<B> Copy for BlockKey<B> {}
+
+/// Collection of blocks which are shut down together
+///
+/// Blocks added to a `Graph` are owned by the `Graph` and can be accessed
+/// through the returned [`BlockKey`]. When calling [`Graph::shutdown`], the
+/// blocks are dropped in the order they have been added (which should be
+/// the topological order, i.e. sources first). Blocks which require an
+/// asynchronous shutdown procedure (e.g. deactivating hardware streams) can
+/// be added with [`Graph::add_with_shutdown`].
+///
+/// ```
+/// # #[tokio::main]
+/// # async fn main() {
+/// use radiorust::flow::Graph;
+/// use radiorust::prelude::*;
+/// let mut graph = Graph::new();
+/// let source = graph.add(blocks::io::Silence::<Complex<f32>>::new(1024, 48000.0));
+/// let sink = graph.add(blocks::io::Blackhole::<Complex<f32>>::new());
+/// graph[sink].feed_from(&graph[source]);
+/// graph.observe(source);
+/// graph.shutdown().await;
+/// # }
+/// ```
+pub struct Graph {
+    entries: Vec<GraphEntry>,
+    observers: Vec<Pin<Box<dyn Future<Output = ()> + Send>>>,
+}
+
+impl Graph {
+    /// Create empty `Graph`
+    pub fn new() -> Self {
+        Self {
+            entries: Vec::new(),
+            observers: Vec::new(),
+        }
+    }
+    /// Add block, which is dropped on shutdown
+    pub fn add<B>(&mut self, block: B) -> BlockKey<B>
+    where
+        B: Send + 'static,
+    {
+        self.entries.push(GraphEntry {
+            block: Box::new(block),
+            shutdown: None,
+        });
+        BlockKey {
+            index: self.entries.len() - 1,
+            phantom: PhantomData,
+        }
+    }
+    /// Add block, which is passed to the given `shutdown` closure on shutdown
+    ///
+    /// The future returned by the closure is awaited before the next block
+    /// is shut down.
+    pub fn add_with_shutdown<B, F, R>(&mut self, block: B, shutdown: F) -> BlockKey<B>
+    where
+        B: Send + 'static,
+        F: FnOnce(B) -> R + Send + 'static,
+        R: Future<Output = ()> + Send + 'static,
+    {
+        self.entries.push(GraphEntry {
+            block: Box::new(block),
+            shutdown: Some(Box::new(move |block| {
+                Box::pin(shutdown(*block.downcast::<B>().unwrap()))
+            })),
+        });
+        BlockKey {
+            index: self.entries.len() - 1,
+            phantom: PhantomData,
+        }
+    }
+    /// Wait during shutdown until the background task of the [`Producer`]
+    /// with the given `key` has terminated (i.e. dropped its [`Sender`])
+    pub fn observe<T, P>(&mut self, key: BlockKey<P>)
+    where
+        T: Send + 'static,
+        P: Producer<T> + 'static,
+    {
+        let observer = self[key].sender_connector().observer();
+        self.observers
+            .push(Box::pin(async move { observer.closed().await }));
+    }
+    /// Drop all blocks in the order they have been added, and wait until
+    /// all shutdown procedures have completed and all observed background
+    /// tasks have terminated
+    pub async fn shutdown(self) {
+        for entry in self.entries {
+            match entry.shutdown {
+                Some(shutdown) => shutdown(entry.block).await,
+                None => drop(entry.block),
+            }
+        }
+        for observer in self.observers {
+            observer.await;
+        }
+    }
+}
+
+impl<B: 'static> Index<BlockKey<B>> for Graph {
+    type Output = B;
+    fn index(&self, key: BlockKey<B>) -> &B {
+        self.entries[key.index].block.downcast_ref().unwrap()
+    }
+}
+
 #[cfg(test)]
-mod tests {}
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+    #[tokio::test]
+    async fn test_graph_shutdown() {
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let mut graph = Graph::new();
+        for name in ["source", "sink"] {
+            let log = log.clone();
+            graph.add_with_shutdown(name, move |name| async move {
+                log.lock().unwrap().push(name);
+            });
+        }
+        let (sender, sender_connector) = new_sender::<SimpleMessage<i32>>();
+        let key = graph.add(sender_connector);
+        graph.observe(key);
+        assert!(!graph[key].observer().is_closed());
+        let join_handle = tokio::spawn(graph.shutdown());
+        drop(sender);
+        join_handle.await.unwrap();
+        assert_eq!(*log.lock().unwrap(), vec!["source", "sink"]);
+    }
+    #[tokio::test]
+    async fn test_reset() {
+        use crate::blocks::Nop;
+        use crate::signal::{Reset, Signal};
+        let (sender, sender_connector) = new_sender::<Signal<i32>>();
+        let nop = Nop::<Signal<i32>>::new();
+        let (mut receiver, receiver_connector) = new_receiver::<Signal<i32>>();
+        nop.feed_from(&sender_connector);
+        receiver_connector.connect(nop.sender_connector());
+        nop.reset();
+        nop.reset();
+        let (result, signals) = tokio::join!(
+            sender.send(Signal::Samples {
+                sample_rate: 48000.0,
+                chunk: vec![1].into(),
+            }),
+            async { [receiver.recv().await, receiver.recv().await] }
+        );
+        result.unwrap();
+        let [Ok(Signal::Event(event)), Ok(Signal::Samples { chunk, .. })] = signals else {
+            panic!("expected reset event followed by samples");
+        };
+        assert!(event.as_any().is::<Reset>());
+        assert_eq!(&chunk[..], &[1]);
+    }
+    #[test]
+    fn test_short_type_name() {
+        assert_eq!(
+            short_type_name("radiorust::signal::Signal<num_complex::Complex<f32>>"),
+            "Signal<Complex<f32>>"
+        );
+        assert_eq!(short_type_name("(a::B, [c::D; 2])"), "(B, [D; 2])");
+    }
+    #[tokio::test]
+    async fn test_topology() {
+        use crate::blocks::Nop;
+        use crate::signal::Signal;
+        let (sender, sender_connector) = new_sender::<Signal<i32>>();
+        let nop = Nop::<Signal<i32>>::new();
+        let (mut receiver, receiver_connector) = new_receiver::<Signal<i32>>();
+        nop.feed_from(&sender_connector);
+        receiver_connector.connect(nop.sender_connector());
+        let unconnected = Nop::<Signal<i32>>::new();
+        let (result, signal) = tokio::join!(
+            sender.send(Signal::Samples {
+                sample_rate: 48000.0,
+                chunk: vec![1].into(),
+            }),
+            receiver.recv()
+        );
+        result.unwrap();
+        signal.unwrap();
+        let mut topology = Topology::new();
+        topology.add_source("source", &sender_connector);
+        topology.add_block("nop \"1\"", &nop);
+        topology.add_block("nop 2", &unconnected);
+        topology.add_sink("sink", &receiver_connector);
+        assert_eq!(topology.blocks()[1].sample_rate, Some(48000.0));
+        assert_eq!(topology.blocks()[2].input.as_ref().unwrap().channel, None);
+        let edges = topology.edges();
+        assert_eq!(edges.len(), 2);
+        assert_eq!((edges[0].from, edges[0].to), (0, 1));
+        assert_eq!((edges[1].from, edges[1].to), (1, 3));
+        assert_eq!(
+            topology.to_dot(),
+            "digraph {\n    0 [label=\"source\"];\n    1 [label=\"nop \\\"1\\\"\"];\n    \
+             2 [label=\"nop 2\"];\n    3 [label=\"sink\"];\n    \
+             0 -> 1 [label=\"Signal<i32>\\n48000 Hz\"];\n    \
+             1 -> 3 [label=\"Signal<i32>\\n48000 Hz\"];\n}\n"
+        );
+    }
+    #[tokio::test]
+    async fn test_reconnect() {
+        use crate::signal::{Disconnection, Signal};
+        let (mut receiver, receiver_connector) = new_receiver::<Signal<i32>>();
+        let (sender_a, sender_connector_a) = new_sender::<Signal<i32>>();
+        let (sender_b, sender_connector_b) = new_sender::<Signal<i32>>();
+        let samples = |value: i32| Signal::Samples {
+            sample_rate: 1.0,
+            chunk: vec![value].into(),
+        };
+        receiver_connector.connect(&sender_connector_a);
+        let (result, signal) = tokio::join!(sender_a.send(samples(1)), receiver.recv());
+        result.unwrap();
+        let Signal::Samples { chunk, .. } = signal.unwrap() else { panic!(); };
+        assert_eq!(&*chunk, &[1]);
+        receiver_connector.connect(&sender_connector_b);
+        let Signal::Event(event) = receiver.recv().await.unwrap() else { panic!(); };
+        assert!(event.as_any().is::<Disconnection>());
+        assert_eq!(sender_connector_a.observer().receiver_count(), 0);
+        let (result, signal) = tokio::join!(sender_b.send(samples(2)), receiver.recv());
+        result.unwrap();
+        let Signal::Samples { chunk, .. } = signal.unwrap() else { panic!(); };
+        assert_eq!(&*chunk, &[2]);
+    }
+    #[tokio::test]
+    async fn test_sender_observer() {
+        use crate::signal::Signal;
+        let (sender, sender_connector) = new_sender::<Signal<i32>>();
+        let (mut receiver, receiver_connector) = new_receiver::<Signal<i32>>();
+        receiver_connector.connect(&sender_connector);
+        let observer = sender_connector.observer();
+        let samples = |value: i32| Signal::Samples {
+            sample_rate: 1.0,
+            chunk: vec![value].into(),
+        };
+        let (result, signal) = tokio::join!(sender.send(samples(1)), receiver.recv());
+        result.unwrap();
+        signal.unwrap();
+        assert_eq!(observer.receiver_count(), 1);
+        assert_eq!(observer.pending(), 0);
+        assert_eq!(observer.sent_count(), 1);
+        sender.send(samples(2)).await.unwrap();
+        assert_eq!(observer.pending(), 1);
+        assert_eq!(observer.sent_count(), 2);
+        let Signal::Samples { chunk, .. } = receiver.recv().await.unwrap() else { panic!(); };
+        assert_eq!(&*chunk, &[2]);
+        assert_eq!(observer.pending(), 0);
+        drop(sender);
+        assert!(observer.is_closed());
+    }
+    #[cfg(feature = "profiling")]
+    #[tokio::test]
+    async fn test_profile() {
+        use crate::blocks::Nop;
+        use crate::signal::Signal;
+        use std::time::Duration;
+        let (sender, sender_connector) = new_sender::<Signal<i32>>();
+        let nop = Nop::<Signal<i32>>::new();
+        let (mut receiver, receiver_connector) = new_receiver::<Signal<i32>>();
+        nop.feed_from(&sender_connector);
+        receiver_connector.connect(nop.sender_connector());
+        let consumer = tokio::spawn(async move {
+            for value in 0..3 {
+                tokio::time::sleep(Duration::from_millis(50)).await;
+                let Signal::Samples { chunk, .. } = receiver.recv().await.unwrap()
+                else { panic!(); };
+                assert_eq!(chunk[0], value);
+            }
+        });
+        for value in 0..3 {
+            sender
+                .send(Signal::Samples {
+                    sample_rate: 1.0,
+                    chunk: vec![value].into(),
+                })
+                .await
+                .unwrap();
+        }
+        consumer.await.unwrap();
+        let profile = profile(&nop);
+        // the first two values have been handled completely, and sending the
+        // third value waited for the consumer to receive the second value
+        assert!(profile.count >= 2);
+        assert!(profile.blocked >= Duration::from_millis(50));
+    }
+}
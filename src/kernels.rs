@@ -0,0 +1,262 @@
+//! Computational kernels which are independent of the [`flow`] layer
+//!
+//! The types in this module contain the per-sample math of some blocks
+//! (e.g. [`FirFilter`], [`Biquad`], and [`CicDecimator`]) but no channels or
+//! background tasks. They can be used to process samples in synchronous code
+//! or to build custom blocks. The blocks are thin wrappers which feed each
+//! received chunk through a kernel.
+//!
+//! [`flow`]: crate::flow
+//! [`FirFilter`]: crate::blocks::filters::FirFilter
+//! [`Biquad`]: crate::blocks::filters::Biquad
+//! [`CicDecimator`]: crate::blocks::resampling::CicDecimator
+
+use crate::numbers::*;
+
+use std::sync::Arc;
+
+/// FIR filter using direct convolution, which keeps the end of each
+/// processed slice as history
+#[derive(Clone, Debug)]
+pub struct FirKernel<Flt> {
+    coeffs: Arc<[Flt]>,
+    history: Vec<Complex<Flt>>,
+    extended: Vec<Complex<Flt>>,
+}
+
+impl<Flt> FirKernel<Flt>
+where
+    Flt: Float,
+{
+    /// Create new `FirKernel` with given coefficients (impulse response) and
+    /// zeroed history
+    pub fn new(coeffs: Arc<[Flt]>) -> Self {
+        assert!(!coeffs.is_empty(), "coefficients must not be empty");
+        let history = vec![Complex::from(Flt::zero()); coeffs.len() - 1];
+        Self {
+            coeffs,
+            history,
+            extended: Vec::new(),
+        }
+    }
+    /// Get coefficients
+    pub fn coeffs(&self) -> &Arc<[Flt]> {
+        &self.coeffs
+    }
+    /// Set coefficients
+    ///
+    /// The most recent samples are kept as history (or zero-padded if they
+    /// are fewer than needed), such that the output stays continuous.
+    pub fn set_coeffs(&mut self, coeffs: Arc<[Flt]>) {
+        assert!(!coeffs.is_empty(), "coefficients must not be empty");
+        let history_len = coeffs.len() - 1;
+        if self.history.len() > history_len {
+            self.history.drain(0..self.history.len() - history_len);
+        } else {
+            let missing = history_len - self.history.len();
+            self.history
+                .splice(0..0, vec![Complex::from(Flt::zero()); missing]);
+        }
+        self.coeffs = coeffs;
+    }
+    /// Filter `input` and append the result (of same length) to `output`
+    pub fn process(&mut self, input: &[Complex<Flt>], output: &mut Vec<Complex<Flt>>) {
+        let history_len = self.history.len();
+        let extended = &mut self.extended;
+        extended.clear();
+        extended.extend_from_slice(&self.history);
+        extended.extend_from_slice(input);
+        for i in 0..input.len() {
+            let mut sum: Complex<Flt> = Complex::from(Flt::zero());
+            for (&x, &h) in extended[i..=i + history_len]
+                .iter()
+                .rev()
+                .zip(self.coeffs.iter())
+            {
+                sum += x * h;
+            }
+            output.push(sum);
+        }
+        self.history.clear();
+        self.history
+            .extend_from_slice(&extended[extended.len() - history_len..]);
+    }
+    /// Append the tail of the impulse response (i.e. the response to the
+    /// history) to `output` and zero the history
+    pub fn flush(&mut self, output: &mut Vec<Complex<Flt>>) {
+        let tail = vec![Complex::from(Flt::zero()); self.history.len()];
+        self.process(&tail, output);
+    }
+    /// Zero the history
+    pub fn reset(&mut self) {
+        self.history.fill(Complex::from(Flt::zero()));
+    }
+}
+
+/// Second-order IIR section (biquad) in direct form II transposed
+///
+/// The transfer function is
+/// *H(z) = (b0 + b1 z<sup>−1</sup> + b2 z<sup>−2</sup>) /
+/// (1 + a1 z<sup>−1</sup> + a2 z<sup>−2</sup>)*.
+#[derive(Clone, Debug)]
+pub struct BiquadKernel<Flt> {
+    b: [Flt; 3],
+    a: [Flt; 2],
+    s1: Complex<Flt>,
+    s2: Complex<Flt>,
+}
+
+impl<Flt> Default for BiquadKernel<Flt>
+where
+    Flt: Float,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<Flt> BiquadKernel<Flt>
+where
+    Flt: Float,
+{
+    /// Create new `BiquadKernel` which passes the signal unchanged
+    pub fn new() -> Self {
+        let zero = Complex::from(Flt::zero());
+        Self {
+            b: [Flt::one(), Flt::zero(), Flt::zero()],
+            a: [Flt::zero(), Flt::zero()],
+            s1: zero,
+            s2: zero,
+        }
+    }
+    /// Set feedforward coefficients `b` (`[b0, b1, b2]`) and feedback
+    /// coefficients `a` (`[a1, a2]`), keeping the state
+    pub fn set_coeffs(&mut self, b: [Flt; 3], a: [Flt; 2]) {
+        self.b = b;
+        self.a = a;
+    }
+    /// Process a single sample
+    pub fn process(&mut self, x: Complex<Flt>) -> Complex<Flt> {
+        let y = x * self.b[0] + self.s1;
+        self.s1 = x * self.b[1] - y * self.a[0] + self.s2;
+        self.s2 = x * self.b[2] - y * self.a[1];
+        y
+    }
+    /// Return true if the state is finite
+    pub fn is_finite(&self) -> bool {
+        [self.s1, self.s2]
+            .iter()
+            .all(|s| s.re.is_finite() && s.im.is_finite())
+    }
+    /// Zero the state
+    pub fn reset(&mut self) {
+        let zero = Complex::from(Flt::zero());
+        self.s1 = zero;
+        self.s2 = zero;
+    }
+}
+
+/// Decimating cascaded integrator-comb (CIC) filter without gain
+/// compensation
+///
+/// See [`CicDecimator`] for a description of the parameters.
+///
+/// [`CicDecimator`]: crate::blocks::resampling::CicDecimator
+#[derive(Clone, Debug)]
+pub struct CicKernel<Flt> {
+    decimation: usize,
+    integrators: Vec<Complex<Flt>>,
+    combs: Vec<Vec<Complex<Flt>>>,
+    comb_pos: usize,
+    phase: usize,
+}
+
+impl<Flt> CicKernel<Flt>
+where
+    Flt: Float,
+{
+    /// Create new `CicKernel` with zeroed state
+    pub fn new(decimation: usize, stages: usize, differential_delay: usize) -> Self {
+        assert!(decimation > 0, "decimation must be positive");
+        assert!(stages > 0, "number of stages must be positive");
+        assert!(
+            differential_delay > 0,
+            "differential delay must be positive"
+        );
+        let zero = Complex::from(Flt::zero());
+        Self {
+            decimation,
+            integrators: vec![zero; stages],
+            combs: vec![vec![zero; differential_delay]; stages],
+            comb_pos: 0,
+            phase: 0,
+        }
+    }
+    /// Process a single input sample and return an output sample for every
+    /// `decimation`th input sample
+    pub fn push(&mut self, sample: Complex<Flt>) -> Option<Complex<Flt>> {
+        let mut value = sample;
+        for integrator in self.integrators.iter_mut() {
+            *integrator += value;
+            value = *integrator;
+        }
+        self.phase += 1;
+        if self.phase < self.decimation {
+            return None;
+        }
+        self.phase = 0;
+        for comb in self.combs.iter_mut() {
+            let delayed = comb[self.comb_pos];
+            comb[self.comb_pos] = value;
+            value -= delayed;
+        }
+        self.comb_pos += 1;
+        if self.comb_pos == self.combs[0].len() {
+            self.comb_pos = 0;
+        }
+        Some(value)
+    }
+    /// Zero the state
+    pub fn reset(&mut self) {
+        let zero = Complex::from(Flt::zero());
+        self.integrators.fill(zero);
+        self.combs.iter_mut().for_each(|comb| comb.fill(zero));
+        self.comb_pos = 0;
+        self.phase = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn test_fir_kernel() {
+        let mut kernel = FirKernel::<f64>::new(vec![1.0, 2.0, 3.0].into());
+        let mut output = Vec::new();
+        kernel.process(&[Complex::from(1.0)], &mut output);
+        kernel.process(&[Complex::from(0.0)], &mut output);
+        kernel.flush(&mut output);
+        let expected: Vec<Complex<f64>> = [1.0, 2.0, 3.0, 0.0]
+            .into_iter()
+            .map(Complex::from)
+            .collect();
+        assert_eq!(output, expected);
+        kernel.process(&[Complex::from(1.0)], &mut output);
+        kernel.set_coeffs(vec![1.0, 1.0].into());
+        kernel.reset();
+        output.clear();
+        kernel.process(&[Complex::from(1.0); 2], &mut output);
+        assert_eq!(output, vec![Complex::from(1.0), Complex::from(2.0)]);
+    }
+    #[test]
+    fn test_cic_kernel() {
+        let mut kernel = CicKernel::<f64>::new(4, 3, 1);
+        let outputs: Vec<Complex<f64>> = (0..40)
+            .filter_map(|_| kernel.push(Complex::from(1.0)))
+            .collect();
+        assert_eq!(outputs.len(), 10);
+        assert_eq!(outputs[9], Complex::from(64.0));
+        kernel.reset();
+        assert_eq!(kernel.push(Complex::from(1.0)), None);
+    }
+}
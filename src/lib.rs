@@ -4,11 +4,32 @@
 //!
 //! For getting started, have a look at the [`blocks`] module for a selection
 //! of ready-to-use signal processing blocks and see the "Hello World" example
-//! below.
+//! below. For simple scripts which don't use `async` code, see the
+//! [`blocking`] module.
+//!
+//! # Cargo features
+//!
+//! No features are enabled by default, such that the crate (including all
+//! signal processing blocks and file or network I/O) builds without any
+//! hardware support libraries installed.
+//!
+//! * `soapysdr`: SoapySDR support in [`blocks::io::rf`] (requires the
+//!   SoapySDR system library)
+//! * `audio` (or its alias `cpal`): audio interface support in
+//!   [`blocks::io::audio`] (requires ALSA on Linux)
+//! * `full-io`: all of the above
+//! * `simd`: explicit SIMD instructions in the [`simd`] module
+//! * `image`: writing spectrograms to PNG files in `blocks::io::image`
+//! * `tracing`: spans and events (e.g. of stream errors, overflows, or
+//!   reconnections) through the [`tracing`](https://docs.rs/tracing) crate
+//!   (whose `log` feature forwards them as log records if no subscriber is
+//!   set up)
+//! * `profiling`: measurement of processing time per block (see
+//!   [`flow`](flow#profiling))
 //!
 //! # Hello World example
 //!
-//! The following example requires the `cpal` feature to be enabled.
+//! The following example requires the `audio` feature to be enabled.
 //!
 //! ```
 //! use radiorust::prelude::*;
@@ -16,7 +37,7 @@
 //! #[tokio::main]
 //! async fn main() {
 //! # #[cfg(any())]
-//! # #[cfg(feature = "cpal")]
+//! # #[cfg(feature = "audio")]
 //! # {
 //!     let morse_keyer = blocks::morse::Keyer::with_message(
 //!         4096,
@@ -37,23 +58,79 @@
 
 #![warn(missing_docs)]
 
+#[macro_use]
+mod logging;
+
+pub mod blocking;
 pub mod blocks;
 pub mod bufferpool;
+pub mod error;
 pub mod flow;
+pub mod kernels;
 pub mod math;
 pub mod metering;
 pub mod numbers;
 pub mod prelude;
 pub mod signal;
+pub mod simd;
 pub mod sync;
 pub mod windowing;
 
 #[cfg(test)]
 mod tests {
+    use crate::bufferpool::Chunk;
+    use crate::flow::*;
+    use crate::signal::*;
     const PRECISION: f64 = 1e-10;
     pub(crate) fn assert_approx(a: f64, b: f64) {
         if !((a - b).abs() <= PRECISION || (a / b).ln().abs() <= PRECISION) {
             panic!("{a} and {b} are not approximately equal");
         }
     }
+    /// Send two segments of `input` (split into chunks of `chunk_len`), each
+    /// followed by a [`Flush`], through `block` and return the number of
+    /// samples received before each flushing event
+    pub(crate) async fn flushed_lens<T, U, B>(
+        block: &B,
+        input_rate: f64,
+        input: Vec<T>,
+        chunk_len: usize,
+    ) -> Vec<usize>
+    where
+        T: Clone + Send + Sync + 'static,
+        U: Clone + Send + Sync + 'static,
+        B: Consumer<Signal<T>> + Producer<Signal<U>>,
+    {
+        let (sender, sender_connector) = new_sender::<Signal<T>>();
+        let (mut receiver, receiver_connector) = new_receiver::<Signal<U>>();
+        block.feed_from(&sender_connector);
+        block.feed_into(&receiver_connector);
+        tokio::spawn(async move {
+            for _ in 0..2 {
+                for part in input.chunks(chunk_len) {
+                    sender
+                        .send(Signal::Samples {
+                            sample_rate: input_rate,
+                            chunk: Chunk::from(part.to_vec()),
+                        })
+                        .await
+                        .unwrap();
+                }
+                sender.send_event(Flush).await.unwrap();
+            }
+        });
+        let mut lens = Vec::new();
+        let mut len = 0;
+        while lens.len() < 2 {
+            match receiver.recv().await.unwrap() {
+                Signal::Samples { chunk, .. } => len += chunk.len(),
+                Signal::Event(event) => {
+                    assert!(event.is_flush());
+                    lens.push(len);
+                    len = 0;
+                }
+            }
+        }
+        lens
+    }
 }
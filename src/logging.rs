@@ -0,0 +1,80 @@
+//! Internal diagnostics through the [`tracing`] crate
+//!
+//! The [`event!`] and [`instrument!`] macros forward to [`tracing::event!`]
+//! and [`tracing::Instrument`] when the `tracing` feature is enabled.
+//! Otherwise, their arguments are type-checked but never evaluated, and
+//! futures are returned unchanged.
+//!
+//! Fields are written as with [`tracing`], but always need an explicit value
+//! (optionally with a `%` or `?` sigil for [`Display`] or [`Debug`]).
+//!
+//! [`tracing`]: https://docs.rs/tracing
+//! [`Display`]: std::fmt::Display
+//! [`Debug`]: std::fmt::Debug
+
+/// Emit event with given [`tracing::Level`] constant, fields, and format
+/// arguments
+///
+/// ```ignore
+/// event!(WARN, channel = channel, error = %err, "stream error");
+/// ```
+#[cfg(feature = "tracing")]
+macro_rules! event {
+    ($level:ident, $($arg:tt)+) => {
+        ::tracing::event!(::tracing::Level::$level, $($arg)+)
+    };
+}
+
+#[cfg(not(feature = "tracing"))]
+macro_rules! event {
+    (
+        $level:ident,
+        $($field:ident = $(%)? $(?)? $value:expr,)*
+        $fmt:literal $(, $arg:expr)* $(,)?
+    ) => {
+        if false {
+            $(let _ = &$value;)*
+            let _ = format_args!($fmt $(, $arg)*);
+        }
+    };
+}
+
+/// Attach a span with given [`tracing::Level`] constant, name, and
+/// (optional) fields to a future
+///
+/// ```ignore
+/// let task = spawn(instrument!(INFO, "capture", { channel = channel }, async move {
+///     /* … */
+/// }));
+/// ```
+#[cfg(feature = "tracing")]
+#[allow(unused_macros)]
+macro_rules! instrument {
+    ($level:ident, $name:literal, { $($field:tt)* }, $future:expr) => {{
+        let span = ::tracing::span!(::tracing::Level::$level, $name, $($field)*);
+        ::tracing::Instrument::instrument($future, span)
+    }};
+    ($level:ident, $name:literal, $future:expr) => {{
+        let span = ::tracing::span!(::tracing::Level::$level, $name);
+        ::tracing::Instrument::instrument($future, span)
+    }};
+}
+
+#[cfg(not(feature = "tracing"))]
+#[allow(unused_macros)]
+macro_rules! instrument {
+    (
+        $level:ident,
+        $name:literal,
+        { $($field:ident = $(%)? $(?)? $value:expr),* $(,)? },
+        $future:expr
+    ) => {{
+        if false {
+            $(let _ = &$value;)*
+        }
+        $future
+    }};
+    ($level:ident, $name:literal, $future:expr) => {
+        $future
+    };
+}
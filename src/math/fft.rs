@@ -0,0 +1,71 @@
+//! Fast Fourier transforms
+//!
+//! The functions in this module wrap the [`easyfft`] crate, which plans
+//! each FFT size only once per thread and caches the plan (along with a
+//! scratch buffer). Thus repeated transforms of the same size (e.g. in a
+//! block processing chunks) do not involve any planning cost.
+//!
+//! None of the transforms are normalized, i.e. a forward transform followed
+//! by an inverse transform scales the data by its length.
+
+use crate::numbers::*;
+
+use easyfft::prelude::*;
+
+/// Forward FFT in place
+pub fn forward<Flt: Float>(data: &mut [Complex<Flt>]) {
+    data.fft_mut();
+}
+
+/// Inverse FFT in place
+pub fn inverse<Flt: Float>(data: &mut [Complex<Flt>]) {
+    data.ifft_mut();
+}
+
+/// Forward FFT of real input, returning the `input.len() / 2 + 1`
+/// non-negative frequency bins
+///
+/// The remaining bins are the complex conjugates of the returned bins (in
+/// reverse order).
+pub fn real_forward<Flt: Float>(input: &[Flt]) -> Vec<Complex<Flt>> {
+    if input.is_empty() {
+        return Vec::new();
+    }
+    Box::<[Complex<Flt>]>::from(input.real_fft()).into_vec()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn test_fft() {
+        let input: Vec<Complex<f64>> = (0..12)
+            .map(|i| Complex::new((0.5 * i as f64).sin(), (1.7 * i as f64).cos()))
+            .collect();
+        let mut data = input.clone();
+        forward(&mut data);
+        for (k, &bin) in data.iter().enumerate() {
+            let mut expected = Complex::from(0.0);
+            for (n, &x) in input.iter().enumerate() {
+                let angle = -std::f64::consts::TAU * (k * n) as f64 / input.len() as f64;
+                expected += x * Complex::from_polar(1.0, angle);
+            }
+            assert!((bin - expected).norm() < 1e-9);
+        }
+        inverse(&mut data);
+        for (x, y) in data.iter().zip(input.iter()) {
+            assert!((x / input.len() as f64 - y).norm() < 1e-9);
+        }
+        for len in [7, 8] {
+            let real: Vec<f64> = (0..len).map(|i| (0.9 * i as f64).sin() + 0.3).collect();
+            let mut data: Vec<Complex<f64>> = real.iter().map(|&x| Complex::from(x)).collect();
+            forward(&mut data);
+            let bins = real_forward(&real);
+            assert_eq!(bins.len(), len / 2 + 1);
+            for (x, y) in bins.iter().zip(data.iter()) {
+                assert!((x - y).norm() < 1e-9);
+            }
+        }
+        assert!(real_forward::<f64>(&[]).is_empty());
+    }
+}
@@ -2,6 +2,9 @@
 
 use crate::numbers::*;
 
+pub mod fft;
+pub mod window;
+
 /// Modified Bessel function of the first kind of order zero
 #[allow(non_snake_case)]
 pub fn bessel_I0<Flt: Float>(x: Flt) -> Flt {
@@ -48,6 +51,52 @@ pub fn sinc<Flt: Float>(x: Flt) -> Flt {
     }
 }
 
+/// Pseudo-random generator for (Gaussian) noise
+///
+/// Uses the xorshift64* algorithm, which is fast but not suitable for
+/// cryptographic purposes.
+#[derive(Clone, Debug)]
+pub struct NoiseGenerator {
+    state: u64,
+}
+
+impl NoiseGenerator {
+    /// Create new generator seeded from the system time
+    pub fn new() -> Self {
+        use std::time::{SystemTime, UNIX_EPOCH};
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_nanos() as u64)
+            .unwrap_or(0);
+        Self::with_seed(nanos)
+    }
+    /// Create new generator with given `seed`
+    pub fn with_seed(seed: u64) -> Self {
+        Self {
+            state: (seed ^ 0x9E3779B97F4A7C15) | 1,
+        }
+    }
+    /// Uniformly distributed value in the interval *(0, 1]*
+    pub fn uniform(&mut self) -> f64 {
+        self.state ^= self.state >> 12;
+        self.state ^= self.state << 25;
+        self.state ^= self.state >> 27;
+        let value = self.state.wrapping_mul(0x2545F4914F6CDD1D);
+        ((value >> 11) + 1) as f64 / (1u64 << 53) as f64
+    }
+    /// Complex value with normally distributed real and imaginary part, where
+    /// the expected squared norm is unity
+    pub fn complex_gaussian(&mut self) -> Complex<f64> {
+        use std::f64::consts::TAU;
+        let radius = (-self.uniform().ln()).sqrt();
+        Complex::from_polar(radius, TAU * self.uniform())
+    }
+    /// Normally distributed value with unit variance
+    pub fn gaussian(&mut self) -> f64 {
+        self.complex_gaussian().re * std::f64::consts::SQRT_2
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -83,4 +132,19 @@ mod tests {
         assert_approx(sinc(2345.0), 0.0);
         assert_approx(sinc(-2345.0), 0.0);
     }
+    #[test]
+    fn test_noise_generator() {
+        let mut generator = NoiseGenerator::with_seed(1);
+        let n = 100000;
+        let mut power = 0.0;
+        let mut mean = Complex::from(0.0);
+        for _ in 0..n {
+            let sample = generator.complex_gaussian();
+            power += sample.norm_sqr();
+            mean += sample;
+        }
+        assert!((power / n as f64 - 1.0).abs() < 0.02);
+        assert!((mean / n as f64).norm() < 0.02);
+        assert!((0..1000).all(|_| (0.0..=1.0).contains(&generator.uniform())));
+    }
 }
@@ -0,0 +1,138 @@
+//! Sampled window functions
+//!
+//! The functions in this module return symmetric windows of a given length
+//! with a peak value of `1.0`, based on the window types in the
+//! [`windowing`] module. The constants provide the coherent gain (mean value)
+//! and the equivalent noise bandwidth (ENBW, in bins) of each window for
+//! large lengths; use [`coherent_gain`] and [`enbw`] for the exact values of a
+//! sampled window (e.g. for Kaiser or Tukey windows).
+//!
+//! [`windowing`]: crate::windowing
+
+use crate::windowing::*;
+
+/// Coherent gain of the rectangular window
+pub const RECTANGULAR_COHERENT_GAIN: f64 = 1.0;
+/// Equivalent noise bandwidth (in bins) of the rectangular window
+pub const RECTANGULAR_ENBW: f64 = 1.0;
+/// Coherent gain of the Hann window
+pub const HANN_COHERENT_GAIN: f64 = 0.5;
+/// Equivalent noise bandwidth (in bins) of the Hann window
+pub const HANN_ENBW: f64 = 1.5;
+/// Coherent gain of the Hamming window
+pub const HAMMING_COHERENT_GAIN: f64 = 0.54;
+/// Equivalent noise bandwidth (in bins) of the Hamming window
+pub const HAMMING_ENBW: f64 = 1.3628;
+/// Coherent gain of the Blackman window
+pub const BLACKMAN_COHERENT_GAIN: f64 = 0.42;
+/// Equivalent noise bandwidth (in bins) of the Blackman window
+pub const BLACKMAN_ENBW: f64 = 1.7268;
+/// Coherent gain of the Blackman-Harris window
+pub const BLACKMAN_HARRIS_COHERENT_GAIN: f64 = 0.35875;
+/// Equivalent noise bandwidth (in bins) of the Blackman-Harris window
+pub const BLACKMAN_HARRIS_ENBW: f64 = 2.0044;
+/// Coherent gain of the flat top window
+pub const FLATTOP_COHERENT_GAIN: f64 = 0.21557895;
+/// Equivalent noise bandwidth (in bins) of the flat top window
+pub const FLATTOP_ENBW: f64 = 3.7702;
+
+/// Sample window of given length, normalized to a peak value of `1.0`
+pub fn sample<W: Window>(window: &W, len: usize) -> Vec<f32> {
+    if len == 1 {
+        return vec![1.0];
+    }
+    let peak = window.relative_value_at(0.0);
+    (0..len)
+        .map(|i| {
+            let x = 2.0 * i as f64 / (len - 1) as f64 - 1.0;
+            (window.relative_value_at(x) / peak) as f32
+        })
+        .collect()
+}
+
+/// Hann window of given length
+pub fn hann(len: usize) -> Vec<f32> {
+    sample(&Hann, len)
+}
+
+/// Hamming window of given length
+pub fn hamming(len: usize) -> Vec<f32> {
+    sample(&Hamming, len)
+}
+
+/// Blackman window of given length
+pub fn blackman(len: usize) -> Vec<f32> {
+    sample(&Blackman, len)
+}
+
+/// Blackman-Harris window (four terms) of given length
+pub fn blackman_harris(len: usize) -> Vec<f32> {
+    sample(&BlackmanHarris, len)
+}
+
+/// Kaiser window of given length and `beta` parameter
+pub fn kaiser(len: usize, beta: f64) -> Vec<f32> {
+    sample(&Kaiser::with_beta(beta), len)
+}
+
+/// Flat top window of given length
+pub fn flattop(len: usize) -> Vec<f32> {
+    sample(&FlatTop, len)
+}
+
+/// Tukey window of given length, where a fraction of `alpha` is tapered
+pub fn tukey(len: usize, alpha: f64) -> Vec<f32> {
+    sample(&Tukey::with_alpha(alpha), len)
+}
+
+/// Coherent gain (mean value) of a sampled window
+pub fn coherent_gain(window: &[f32]) -> f64 {
+    window.iter().map(|&w| w as f64).sum::<f64>() / window.len() as f64
+}
+
+/// Equivalent noise bandwidth (in bins) of a sampled window
+pub fn enbw(window: &[f32]) -> f64 {
+    let sum: f64 = window.iter().map(|&w| w as f64).sum();
+    let sum_sqr: f64 = window.iter().map(|&w| (w as f64).powi(2)).sum();
+    window.len() as f64 * sum_sqr / (sum * sum)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn test_windows() {
+        let len = 4097;
+        for (window, gain, bandwidth) in [
+            (vec![1.0; len], RECTANGULAR_COHERENT_GAIN, RECTANGULAR_ENBW),
+            (hann(len), HANN_COHERENT_GAIN, HANN_ENBW),
+            (hamming(len), HAMMING_COHERENT_GAIN, HAMMING_ENBW),
+            (blackman(len), BLACKMAN_COHERENT_GAIN, BLACKMAN_ENBW),
+            (
+                blackman_harris(len),
+                BLACKMAN_HARRIS_COHERENT_GAIN,
+                BLACKMAN_HARRIS_ENBW,
+            ),
+            (flattop(len), FLATTOP_COHERENT_GAIN, FLATTOP_ENBW),
+            (tukey(len, 1.0), HANN_COHERENT_GAIN, HANN_ENBW),
+            (tukey(len, 0.0), RECTANGULAR_COHERENT_GAIN, RECTANGULAR_ENBW),
+        ] {
+            assert_eq!(window.len(), len);
+            assert_eq!(window[len / 2], 1.0);
+            for i in 0..len {
+                assert!((window[i] - window[len - 1 - i]).abs() < 1e-6);
+            }
+            assert!((coherent_gain(&window) - gain).abs() < 1e-3);
+            assert!((enbw(&window) - bandwidth).abs() < 1e-3);
+        }
+        let window = kaiser(65, 10.0);
+        assert_eq!(window[32], 1.0);
+        assert!(window[0] < 1e-3);
+        for i in 0..65 {
+            assert!((window[i] - window[64 - i]).abs() < 1e-6);
+        }
+        assert!(enbw(&window) > HANN_ENBW && enbw(&window) < BLACKMAN_HARRIS_ENBW);
+        assert_eq!(hann(1), vec![1.0]);
+        assert!(hann(0).is_empty());
+    }
+}
@@ -61,6 +61,48 @@ macro_rules! flt {
 }
 pub use flt;
 
+/// Convert power ratio into decibels (*10 log<sub>10</sub> x*)
+pub fn to_db<Flt: Float>(x: Flt) -> Flt {
+    flt!(10) * x.log10()
+}
+
+/// Convert decibels into power ratio (*10<sup>x/10</sup>*)
+pub fn from_db<Flt: Float>(db: Flt) -> Flt {
+    flt!(10).powf(db / flt!(10))
+}
+
+/// Convert amplitude ratio into decibels (*20 log<sub>10</sub> x*)
+pub fn amplitude_to_db<Flt: Float>(x: Flt) -> Flt {
+    flt!(20) * x.log10()
+}
+
+/// Convert decibels into amplitude ratio (*10<sup>x/20</sup>*)
+pub fn amplitude_from_db<Flt: Float>(db: Flt) -> Flt {
+    flt!(10).powf(db / flt!(20))
+}
+
+/// Mean power (mean square norm) of complex samples
+///
+/// Returns `NaN` if `samples` is empty.
+pub fn power<Flt: Float>(samples: &[Complex<Flt>]) -> Flt {
+    let mut sum = Flt::zero();
+    for sample in samples.iter() {
+        sum += sample.norm_sqr();
+    }
+    sum / flt!(samples.len())
+}
+
+/// Root mean square of real samples
+///
+/// Returns `NaN` if `samples` is empty.
+pub fn rms<Flt: Float>(samples: &[Flt]) -> Flt {
+    let mut sum = Flt::zero();
+    for &sample in samples.iter() {
+        sum += sample * sample;
+    }
+    (sum / flt!(samples.len())).sqrt()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -78,4 +120,27 @@ mod tests {
         }
         assert_eq!(inner(3.5f64), 7.0f64);
     }
+    #[test]
+    fn test_db() {
+        use crate::tests::assert_approx;
+        assert_approx(to_db(100.0), 20.0);
+        assert_approx(amplitude_to_db(100.0), 40.0);
+        assert_approx(from_db(-30.0), 0.001);
+        assert_approx(amplitude_from_db(-30.0), 0.001f64.sqrt());
+        assert_approx(to_db(2.0), 3.010299956639812);
+        assert_approx(amplitude_to_db(2.0), 6.020599913279624);
+        assert_approx(from_db(to_db(0.37)), 0.37);
+        assert_approx(amplitude_from_db(amplitude_to_db(0.37)), 0.37);
+        assert_eq!(to_db(0.0f32), f32::NEG_INFINITY);
+        assert_eq!(from_db(0.0f32), 1.0);
+    }
+    #[test]
+    fn test_power_rms() {
+        use crate::tests::assert_approx;
+        let samples = [Complex::new(1.0, 0.0), Complex::new(0.0, -2.0)];
+        assert_approx(power(&samples), 2.5);
+        assert_approx(rms(&[3.0, -3.0, 3.0, -3.0]), 3.0);
+        assert_approx(to_db(power(&samples)), amplitude_to_db(2.5f64.sqrt()));
+        assert!(power::<f64>(&[]).is_nan());
+    }
 }
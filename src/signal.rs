@@ -5,7 +5,7 @@
 //! [events]: Signal::Event
 
 use crate::bufferpool::Chunk;
-use crate::flow::Message;
+use crate::flow::{Message, SendError, Sender};
 
 use tokio::sync::mpsc;
 
@@ -23,6 +23,12 @@ pub trait Event: Any + Debug + Send + Sync {
         false
     }
     /// True if flushing of previously sent [`Signal::Samples`] data is desired
+    ///
+    /// Blocks which delay their output (e.g. filters with history) emit all
+    /// pending output before passing on a flushing event, such that the tail
+    /// of a finite stream (e.g. a file) is not truncated. Afterwards, they
+    /// continue with cleared state, i.e. subsequent samples start a new
+    /// segment. Blocks never terminate because of a flush.
     fn is_flush(&self) -> bool {
         false
     }
@@ -45,6 +51,156 @@ impl Event for Disconnection {
     }
 }
 
+/// Unit struct requesting downstream blocks to [flush] their state
+///
+/// This event is also an [interruption].
+///
+/// [flush]: Event::is_flush
+/// [interruption]: Event::is_interrupt
+#[derive(Clone, Debug)]
+pub struct Flush;
+
+impl Event for Flush {
+    fn is_interrupt(&self) -> bool {
+        true
+    }
+    fn is_flush(&self) -> bool {
+        true
+    }
+    fn as_any(&self) -> &(dyn std::any::Any + Send + Sync) {
+        self
+    }
+}
+
+/// Unit struct requesting a block to reset its internal state
+///
+/// This event is returned by a [`Receiver`] after [`Consumer::reset`] has been
+/// called. It is an [interruption], so blocks handle it like a discontinuity
+/// of the signal and pass it on to downstream blocks.
+///
+/// [`Receiver`]: crate::flow::Receiver
+/// [`Consumer::reset`]: crate::flow::Consumer::reset
+/// [interruption]: Event::is_interrupt
+#[derive(Clone, Debug)]
+pub struct Reset;
+
+impl Event for Reset {
+    fn is_interrupt(&self) -> bool {
+        true
+    }
+    fn as_any(&self) -> &(dyn std::any::Any + Send + Sync) {
+        self
+    }
+}
+
+/// Event announcing the center frequency of subsequent [`Signal::Samples`]
+///
+/// Sources which know the absolute frequency corresponding to DC (e.g. the
+/// SoapySDR receiver blocks) send this event with the frequency in hertz
+/// before the first chunk and whenever the frequency changes. Blocks which
+/// shift the spectrum (e.g. [`FreqShifter`] or [`Channelizer`]) adjust the
+/// frequency accordingly, while all other blocks pass the event on unchanged.
+///
+/// [`FreqShifter`]: crate::blocks::FreqShifter
+/// [`Channelizer`]: crate::blocks::analysis::Channelizer
+#[derive(Clone, Debug)]
+pub struct CenterFrequency(pub f64);
+
+impl Event for CenterFrequency {
+    fn as_any(&self) -> &(dyn std::any::Any + Send + Sync) {
+        self
+    }
+}
+
+/// Event announcing the time of the next sample in nanoseconds
+///
+/// Subsequent samples are equally spaced according to their sample rate, so
+/// sources (e.g. the SoapySDR receiver blocks) only send this event before
+/// the first chunk and after a discontinuity. The timestamp of every chunk
+/// can then be calculated by counting samples, e.g. using a
+/// [`TimestampTracker`]. Because the time is associated with a position in
+/// the stream, it stays valid when passing blocks which change the sample
+/// rate.
+#[derive(Clone, Debug)]
+pub struct Timestamp(pub i64);
+
+impl Event for Timestamp {
+    fn as_any(&self) -> &(dyn std::any::Any + Send + Sync) {
+        self
+    }
+}
+
+/// Helper to calculate the timestamp of each chunk from [`Timestamp`] events
+///
+/// The timestamp is forgotten when an [interrupting] event is received.
+///
+/// [interrupting]: Event::is_interrupt
+///
+/// # Example
+///
+/// ```
+/// use radiorust::bufferpool::Chunk;
+/// use radiorust::signal::{Signal, Timestamp, TimestampTracker};
+/// let mut tracker = TimestampTracker::new();
+/// let samples = Signal::Samples {
+///     sample_rate: 1000.0,
+///     chunk: Chunk::from(vec![0.0; 10]),
+/// };
+/// assert_eq!(tracker.update(&samples), None);
+/// assert_eq!(tracker.update(&Signal::<f64>::new_event(Timestamp(5000))), None);
+/// assert_eq!(tracker.update(&samples), Some(5000));
+/// assert_eq!(tracker.update(&samples), Some(10005000));
+/// ```
+#[derive(Clone, Debug)]
+pub struct TimestampTracker {
+    base: Option<i64>,
+    sample_rate: f64,
+    sample_count: u64,
+}
+
+impl TimestampTracker {
+    /// Create new tracker which has not seen any timestamp yet
+    pub fn new() -> Self {
+        Self {
+            base: None,
+            sample_rate: f64::NAN,
+            sample_count: 0,
+        }
+    }
+    fn elapsed(&self) -> i64 {
+        match self.sample_count {
+            0 => 0,
+            count => (count as f64 * 1e9 / self.sample_rate).round() as i64,
+        }
+    }
+    /// Process [`Signal`] and return timestamp of first sample in nanoseconds
+    /// (if the signal contains samples and the time is known)
+    pub fn update<T>(&mut self, signal: &Signal<T>) -> Option<i64> {
+        match signal {
+            Signal::Samples { sample_rate, chunk } => {
+                let base = self.base?;
+                if *sample_rate != self.sample_rate {
+                    self.base = Some(base + self.elapsed());
+                    self.sample_rate = *sample_rate;
+                    self.sample_count = 0;
+                }
+                let timestamp = self.base.unwrap() + self.elapsed();
+                self.sample_count += chunk.len() as u64;
+                Some(timestamp)
+            }
+            Signal::Event(event) => {
+                if let Some(Timestamp(timestamp)) = event.as_any().downcast_ref() {
+                    self.base = Some(*timestamp);
+                    self.sample_count = 0;
+                } else if event.is_interrupt() {
+                    self.base = None;
+                }
+                None
+            }
+        }
+    }
+}
+
 type BoxedCallback = Box<dyn FnMut(&Arc<dyn Event>) + Send>;
 
 struct IdentifiedCallback {
@@ -194,6 +350,23 @@ impl<T> Signal<T> {
             Signal::Event(_) => true,
         }
     }
+    /// Center frequency in hertz if message is a [`CenterFrequency`] event
+    pub fn center_frequency(&self) -> Option<f64> {
+        match self {
+            Signal::Samples { .. } => None,
+            Signal::Event(event) => event
+                .as_any()
+                .downcast_ref::<CenterFrequency>()
+                .map(|x| x.0),
+        }
+    }
+    /// Time in nanoseconds if message is a [`Timestamp`] event
+    pub fn timestamp(&self) -> Option<i64> {
+        match self {
+            Signal::Samples { .. } => None,
+            Signal::Event(event) => event.as_any().downcast_ref::<Timestamp>().map(|x| x.0),
+        }
+    }
     /// Duration in seconds (or `0.0` for [events])
     ///
     /// [events]: Signal::Event
@@ -203,6 +376,70 @@ impl<T> Signal<T> {
             _ => 0.0,
         }
     }
+    /// Number of samples (or `0` for [events])
+    ///
+    /// [events]: Signal::Event
+    pub fn sample_count(&self) -> usize {
+        match self {
+            Signal::Samples { chunk, .. } => chunk.len(),
+            _ => 0,
+        }
+    }
+    /// Sample rate if message contains [sample data]
+    ///
+    /// [sample data]: Signal::Samples
+    pub fn sample_rate(&self) -> Option<f64> {
+        match self {
+            Signal::Samples { sample_rate, .. } => Some(*sample_rate),
+            _ => None,
+        }
+    }
+    /// Transform [sample data] with closure while keeping the sample rate,
+    /// and pass [events] unchanged
+    ///
+    /// [sample data]: Signal::Samples
+    /// [events]: Signal::Event
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use radiorust::bufferpool::Chunk;
+    /// use radiorust::signal::Signal;
+    /// let samples = Signal::Samples {
+    ///     sample_rate: 1000.0,
+    ///     chunk: Chunk::from(vec![1.0, 2.0]),
+    /// };
+    /// let doubled = samples.map_samples(|chunk| {
+    ///     Chunk::from(chunk.iter().map(|x| 2.0 * x).collect::<Vec<f64>>())
+    /// });
+    /// assert_eq!(doubled.sample_rate(), Some(1000.0));
+    /// assert_eq!(doubled.sample_count(), 2);
+    /// ```
+    pub fn map_samples<U, F>(self, func: F) -> Signal<U>
+    where
+        F: FnOnce(Chunk<T>) -> Chunk<U>,
+    {
+        match self {
+            Signal::Samples { sample_rate, chunk } => Signal::Samples {
+                sample_rate,
+                chunk: func(chunk),
+            },
+            Signal::Event(event) => Signal::Event(event),
+        }
+    }
+}
+
+impl<T> Sender<Signal<T>>
+where
+    T: Clone,
+{
+    /// Send an [`Event`] as [`Signal::Event`]
+    ///
+    /// Like with [`Sender::send`], the event is received after all
+    /// previously sent values and before all subsequently sent values.
+    pub async fn send_event<E: Event>(&self, event: E) -> Result<(), SendError<Signal<T>>> {
+        self.send(Signal::new_event(event)).await
+    }
 }
 
 impl<T> Message for Signal<T>
@@ -212,4 +449,94 @@ where
     fn disconnection() -> Option<Self> {
         Some(Signal::new_event(Disconnection))
     }
+    fn reset() -> Option<Self> {
+        Some(Signal::new_event(Reset))
+    }
+    fn sample_rate(&self) -> Option<f64> {
+        match self {
+            Signal::Samples { sample_rate, .. } => Some(*sample_rate),
+            Signal::Event(_) => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    fn samples(sample_rate: f64, len: usize) -> Signal<f64> {
+        Signal::Samples {
+            sample_rate,
+            chunk: Chunk::from(vec![0.0; len]),
+        }
+    }
+    #[test]
+    fn test_timestamp_tracker_chunks() {
+        let mut tracker = TimestampTracker::new();
+        assert_eq!(tracker.update(&samples(1000.0, 10)), None);
+        tracker.update(&Signal::<f64>::new_event(Timestamp(1_000_000_000)));
+        assert_eq!(tracker.update(&samples(1000.0, 10)), Some(1_000_000_000));
+        assert_eq!(tracker.update(&samples(1000.0, 250)), Some(1_010_000_000));
+        assert_eq!(tracker.update(&samples(1000.0, 1)), Some(1_260_000_000));
+        // events which are no interruption keep the time
+        tracker.update(&Signal::<f64>::new_event(CenterFrequency(1e6)));
+        assert_eq!(tracker.update(&samples(1000.0, 1)), Some(1_261_000_000));
+        // a new timestamp replaces the counted time
+        tracker.update(&Signal::<f64>::new_event(Timestamp(5_000)));
+        assert_eq!(tracker.update(&samples(1000.0, 3)), Some(5_000));
+        assert_eq!(tracker.update(&samples(1000.0, 3)), Some(3_005_000));
+        tracker.update(&Signal::<f64>::new_event(Disconnection));
+        assert_eq!(tracker.update(&samples(1000.0, 3)), None);
+    }
+    #[test]
+    fn test_timestamp_tracker_rate_change() {
+        let mut tracker = TimestampTracker::new();
+        tracker.update(&Signal::<f64>::new_event(Timestamp(0)));
+        // one third of a second, which is not a whole number of nanoseconds
+        assert_eq!(tracker.update(&samples(3000.0, 1000)), Some(0));
+        assert_eq!(tracker.update(&samples(48000.0, 4800)), Some(333_333_333));
+        assert_eq!(tracker.update(&samples(48000.0, 4800)), Some(433_333_333));
+        assert_eq!(tracker.update(&samples(8000.0, 400)), Some(533_333_333));
+        assert_eq!(tracker.update(&samples(8000.0, 400)), Some(583_333_333));
+        // many chunks don't accumulate rounding errors
+        let mut timestamp = None;
+        for _ in 0..300 {
+            timestamp = tracker.update(&samples(3000.0, 1));
+        }
+        assert_eq!(timestamp, Some(633_333_333 + 99_666_667));
+    }
+    #[test]
+    fn test_signal_helpers() {
+        let samples = Signal::Samples {
+            sample_rate: 4000.0,
+            chunk: Chunk::from(vec![1.0f64; 100]),
+        };
+        assert!(!samples.is_event());
+        assert_eq!(samples.duration(), 0.025);
+        assert_eq!(samples.sample_count(), 100);
+        assert_eq!(samples.sample_rate(), Some(4000.0));
+        let event = Signal::<f64>::new_event(CenterFrequency(7e6));
+        assert!(event.is_event());
+        assert_eq!(event.duration(), 0.0);
+        assert_eq!(event.sample_count(), 0);
+        assert_eq!(event.sample_rate(), None);
+        assert_eq!(event.center_frequency(), Some(7e6));
+    }
+    #[test]
+    fn test_map_samples() {
+        let samples = Signal::Samples {
+            sample_rate: 4000.0,
+            chunk: Chunk::from(vec![1.0f64, 2.0, 3.0]),
+        };
+        let mapped = samples.map_samples(|chunk| {
+            Chunk::from(chunk.iter().map(|&x| x as i32 * 10).collect::<Vec<i32>>())
+        });
+        let Signal::Samples { sample_rate, chunk } = mapped else { panic!(); };
+        assert_eq!(sample_rate, 4000.0);
+        assert_eq!(&chunk[..], &[10, 20, 30]);
+        let event: Arc<dyn Event> = Arc::new(Timestamp(1234));
+        let mapped = Signal::<f64>::Event(event.clone())
+            .map_samples(|_| -> Chunk<i32> { panic!("closure called for event") });
+        let Signal::Event(mapped_event) = mapped else { panic!(); };
+        assert!(Arc::ptr_eq(&mapped_event, &event));
+    }
 }
@@ -0,0 +1,198 @@
+//! Bulk operations on slices of complex numbers
+//!
+//! The functions in this module process whole slices at once. When the `simd`
+//! feature is enabled and the CPU supports AVX, slices of [`f32`] based
+//! numbers are processed with explicit SIMD instructions. Otherwise (and for
+//! [`f64`]), the scalar implementations in the [`scalar`] module are used.
+//!
+//! All functions panic if the given slices differ in length.
+
+use crate::numbers::*;
+
+/// Scalar implementations, which are used as fallback
+pub mod scalar {
+    use super::*;
+    /// Multiply `a` and `b` element-wise and store the products in `output`
+    pub fn complex_mul<Flt: Float>(
+        a: &[Complex<Flt>],
+        b: &[Complex<Flt>],
+        output: &mut [Complex<Flt>],
+    ) {
+        for ((out, &x), &y) in output.iter_mut().zip(a.iter()).zip(b.iter()) {
+            *out = x * y;
+        }
+    }
+    /// Store the squared magnitude of each element of `input` in `output`
+    pub fn magnitude_squared<Flt: Float>(input: &[Complex<Flt>], output: &mut [Flt]) {
+        for (out, x) in output.iter_mut().zip(input.iter()) {
+            *out = x.re * x.re + x.im * x.im;
+        }
+    }
+    /// Multiply each element of `data` with `factor`
+    pub fn scale<Flt: Float>(data: &mut [Complex<Flt>], factor: Flt) {
+        for x in data.iter_mut() {
+            x.re *= factor;
+            x.im *= factor;
+        }
+    }
+}
+
+#[cfg(all(feature = "simd", target_arch = "x86_64"))]
+mod avx {
+    use super::*;
+
+    use std::any::TypeId;
+    use std::arch::x86_64::*;
+
+    pub(super) fn available<Flt: Float>() -> bool {
+        TypeId::of::<Flt>() == TypeId::of::<f32>() && is_x86_feature_detected!("avx")
+    }
+
+    /// Reinterpret slice of `A` as slice of `B`
+    ///
+    /// # Safety
+    ///
+    /// `A` and `B` must be the same type.
+    pub(super) unsafe fn cast<A, B>(slice: &[A]) -> &[B] {
+        std::slice::from_raw_parts(slice.as_ptr() as *const B, slice.len())
+    }
+
+    /// Reinterpret mutable slice of `A` as mutable slice of `B`
+    ///
+    /// # Safety
+    ///
+    /// `A` and `B` must be the same type.
+    pub(super) unsafe fn cast_mut<A, B>(slice: &mut [A]) -> &mut [B] {
+        std::slice::from_raw_parts_mut(slice.as_mut_ptr() as *mut B, slice.len())
+    }
+
+    #[target_feature(enable = "avx")]
+    pub(super) unsafe fn complex_mul(
+        a: &[Complex<f32>],
+        b: &[Complex<f32>],
+        output: &mut [Complex<f32>],
+    ) {
+        let head = output.len() - output.len() % 4;
+        let a_ptr = a.as_ptr() as *const f32;
+        let b_ptr = b.as_ptr() as *const f32;
+        let out_ptr = output.as_mut_ptr() as *mut f32;
+        for i in (0..head).step_by(4) {
+            let x = _mm256_loadu_ps(a_ptr.add(2 * i));
+            let y = _mm256_loadu_ps(b_ptr.add(2 * i));
+            let y_re = _mm256_moveldup_ps(y);
+            let y_im = _mm256_movehdup_ps(y);
+            let x_swapped = _mm256_permute_ps(x, 0b10_11_00_01);
+            let z = _mm256_addsub_ps(_mm256_mul_ps(x, y_re), _mm256_mul_ps(x_swapped, y_im));
+            _mm256_storeu_ps(out_ptr.add(2 * i), z);
+        }
+        scalar::complex_mul(&a[head..], &b[head..], &mut output[head..]);
+    }
+
+    #[target_feature(enable = "avx")]
+    pub(super) unsafe fn magnitude_squared(input: &[Complex<f32>], output: &mut [f32]) {
+        let head = output.len() - output.len() % 8;
+        let in_ptr = input.as_ptr() as *const f32;
+        let out_ptr = output.as_mut_ptr();
+        for i in (0..head).step_by(8) {
+            let x0 = _mm256_loadu_ps(in_ptr.add(2 * i));
+            let x1 = _mm256_loadu_ps(in_ptr.add(2 * i + 8));
+            let lo = _mm256_permute2f128_ps(x0, x1, 0x20);
+            let hi = _mm256_permute2f128_ps(x0, x1, 0x31);
+            let z = _mm256_hadd_ps(_mm256_mul_ps(lo, lo), _mm256_mul_ps(hi, hi));
+            _mm256_storeu_ps(out_ptr.add(i), z);
+        }
+        scalar::magnitude_squared(&input[head..], &mut output[head..]);
+    }
+
+    #[target_feature(enable = "avx")]
+    pub(super) unsafe fn scale(data: &mut [Complex<f32>], factor: f32) {
+        let head = data.len() - data.len() % 4;
+        let ptr = data.as_mut_ptr() as *mut f32;
+        let factor_vec = _mm256_set1_ps(factor);
+        for i in (0..head).step_by(4) {
+            let x = _mm256_loadu_ps(ptr.add(2 * i));
+            _mm256_storeu_ps(ptr.add(2 * i), _mm256_mul_ps(x, factor_vec));
+        }
+        scalar::scale(&mut data[head..], factor);
+    }
+}
+
+/// Multiply `a` and `b` element-wise and store the products in `output`
+pub fn complex_mul<Flt: Float>(
+    a: &[Complex<Flt>],
+    b: &[Complex<Flt>],
+    output: &mut [Complex<Flt>],
+) {
+    assert!(
+        a.len() == output.len() && b.len() == output.len(),
+        "slices must have equal length"
+    );
+    #[cfg(all(feature = "simd", target_arch = "x86_64"))]
+    if avx::available::<Flt>() {
+        // SAFETY: `Flt` is `f32` and AVX is supported
+        unsafe { avx::complex_mul(avx::cast(a), avx::cast(b), avx::cast_mut(output)) };
+        return;
+    }
+    scalar::complex_mul(a, b, output);
+}
+
+/// Store the squared magnitude of each element of `input` in `output`
+pub fn magnitude_squared<Flt: Float>(input: &[Complex<Flt>], output: &mut [Flt]) {
+    assert!(input.len() == output.len(), "slices must have equal length");
+    #[cfg(all(feature = "simd", target_arch = "x86_64"))]
+    if avx::available::<Flt>() {
+        // SAFETY: `Flt` is `f32` and AVX is supported
+        unsafe { avx::magnitude_squared(avx::cast(input), avx::cast_mut(output)) };
+        return;
+    }
+    scalar::magnitude_squared(input, output);
+}
+
+/// Multiply each element of `data` with `factor`
+pub fn scale<Flt: Float>(data: &mut [Complex<Flt>], factor: Flt) {
+    #[cfg(all(feature = "simd", target_arch = "x86_64"))]
+    if avx::available::<Flt>() {
+        // SAFETY: `Flt` is `f32` and AVX is supported
+        unsafe { avx::scale(avx::cast_mut(data), factor.to_f32().unwrap()) };
+        return;
+    }
+    scalar::scale(data, factor);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    fn test_input<Flt: Float>(len: usize, offset: f64) -> Vec<Complex<Flt>> {
+        (0..len)
+            .map(|i| {
+                let x = i as f64 + offset;
+                Complex::new(flt!((0.7 * x).sin()), flt!((1.3 * x).cos() * 2.0))
+            })
+            .collect()
+    }
+    fn test_generic<Flt: Float>() {
+        for len in [0, 1, 7, 8, 37] {
+            let a = test_input::<Flt>(len, 0.0);
+            let b = test_input::<Flt>(len, 0.5);
+            let mut products = vec![Complex::from(Flt::zero()); len];
+            complex_mul(&a, &b, &mut products);
+            let mut magnitudes = vec![Flt::zero(); len];
+            magnitude_squared(&a, &mut magnitudes);
+            let mut scaled = a.clone();
+            scale(&mut scaled, flt!(-1.5));
+            for i in 0..len {
+                assert!((products[i] - a[i] * b[i]).norm() < flt!(1e-6));
+                assert!((magnitudes[i] - a[i].norm_sqr()).abs() < flt!(1e-6));
+                assert!((scaled[i] - a[i] * flt!(-1.5)).norm() < flt!(1e-6));
+            }
+        }
+    }
+    #[test]
+    fn test_bulk_ops_f32() {
+        test_generic::<f32>();
+    }
+    #[test]
+    fn test_bulk_ops_f64() {
+        test_generic::<f64>();
+    }
+}
@@ -11,6 +11,8 @@ use tokio::sync::Notify;
 use std::error::Error;
 use std::fmt;
 use std::sync::{Arc, Mutex, MutexGuard};
+#[cfg(feature = "profiling")]
+use std::time::{Duration, Instant};
 
 /// Error returned by [`Sender::send`] if there are no [`Enlister`]s or
 /// [`Receiver`]s
@@ -64,6 +66,13 @@ impl fmt::Display for RecvError {
 
 impl Error for RecvError {}
 
+/// Identifier of a channel, which is shared by its [`Sender`]s, [`Enlister`]s,
+/// and [`Observer`]s
+///
+/// Identifiers may be reused after a channel has been dropped.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub struct ChannelId(usize);
+
 #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
 enum Slot {
     A,
@@ -90,6 +99,9 @@ struct Synced<T> {
     elst_count: usize,
     rcvr_count: usize,
     unseen: usize,
+    sent_count: u64,
+    #[cfg(feature = "profiling")]
+    blocked: Duration,
 }
 
 #[derive(Debug)]
@@ -111,6 +123,26 @@ pub struct Enlister<T> {
     shared: Arc<Shared<T>>,
 }
 
+/// Handle allowing observation of channel state without keeping the channel
+/// open
+///
+/// Unlike an [`Enlister`], an `Observer` does not prevent [`Sender::send`]
+/// from failing. An `Observer` may be used to monitor congestion: a value
+/// which is still pending (see [`Observer::pending`]) indicates that a
+/// [`Receiver`] hasn't kept up with the [`Sender`].
+#[derive(Debug)]
+pub struct Observer<T> {
+    shared: Arc<Shared<T>>,
+}
+
+impl<T> Clone for Observer<T> {
+    fn clone(&self) -> Self {
+        Self {
+            shared: self.shared.clone(),
+        }
+    }
+}
+
 /// Guarantee to send one value from [`Sender`] to [`Receiver`]s immediately
 #[derive(Debug)]
 pub struct Reservation<'a, T> {
@@ -126,6 +158,9 @@ pub struct Receiver<T> {
 }
 
 impl<T> Shared<T> {
+    fn channel_id(self: &Arc<Self>) -> ChannelId {
+        ChannelId(Arc::as_ptr(self) as usize)
+    }
     fn subscribe(self: &Arc<Self>) -> Receiver<T> {
         let mut synced = self.synced.lock().unwrap();
         synced.rcvr_count = synced.rcvr_count.checked_add(1).unwrap();
@@ -214,6 +249,9 @@ pub fn channel<T>() -> (Sender<T>, Enlister<T>) {
             elst_count: 1,
             rcvr_count: 0,
             unseen: 0,
+            sent_count: 0,
+            #[cfg(feature = "profiling")]
+            blocked: Duration::ZERO,
         }),
         notify_sndr: Notify::new(),
         notify_rcvr: Notify::new(),
@@ -228,6 +266,8 @@ impl<T> Sender<T> {
     /// The returned [`Reservation`] handle may be used to send a value
     /// immediately (through [`Reservation::send`], which is not `async`).
     pub async fn reserve(&self) -> Result<Reservation<'_, T>, RsrvError> {
+        #[cfg(feature = "profiling")]
+        let mut waiting_since: Option<Instant> = None;
         let synced = loop {
             {
                 let synced = self.shared.synced.lock().unwrap();
@@ -237,10 +277,20 @@ impl<T> Sender<T> {
                 if synced.elst_count == 0 && synced.rcvr_count == 0 {
                     return Err(RsrvError);
                 }
+                #[cfg(feature = "profiling")]
+                waiting_since.get_or_insert_with(Instant::now);
                 self.shared.notify_sndr.notified()
             }
             .await;
         };
+        #[cfg(feature = "profiling")]
+        let synced = {
+            let mut synced = synced;
+            if let Some(since) = waiting_since {
+                synced.blocked += since.elapsed();
+            }
+            synced
+        };
         Ok(Reservation {
             shared: &self.shared,
             synced,
@@ -287,6 +337,7 @@ impl<T> Reservation<'_, T> {
         self.synced.slot = self.synced.slot.change();
         self.synced.data = Some(value);
         self.synced.unseen = self.synced.rcvr_count;
+        self.synced.sent_count = self.synced.sent_count.wrapping_add(1);
         self.shared.notify_rcvr.notify_waiters();
     }
 }
@@ -296,6 +347,62 @@ impl<T> Enlister<T> {
     pub fn subscribe(&self) -> Receiver<T> {
         self.shared.subscribe()
     }
+    /// Identifier of the channel
+    pub fn channel_id(&self) -> ChannelId {
+        self.shared.channel_id()
+    }
+    /// Create an [`Observer`] for the channel
+    pub fn observer(&self) -> Observer<T> {
+        Observer {
+            shared: self.shared.clone(),
+        }
+    }
+}
+
+impl<T> Observer<T> {
+    /// Identifier of the channel
+    pub fn channel_id(&self) -> ChannelId {
+        self.shared.channel_id()
+    }
+    /// Return true if all [`Sender`]s have been dropped
+    pub fn is_closed(&self) -> bool {
+        self.shared.synced.lock().unwrap().sndr_count == 0
+    }
+    /// Number of [`Receiver`]s which have not received the most recently sent
+    /// value yet
+    ///
+    /// As the channel has a capacity of `1`, a non-zero value means that the
+    /// channel is full.
+    pub fn pending(&self) -> usize {
+        self.shared.synced.lock().unwrap().unseen
+    }
+    /// Number of [`Receiver`]s
+    pub fn receiver_count(&self) -> usize {
+        self.shared.synced.lock().unwrap().rcvr_count
+    }
+    /// Total number of values sent through the channel
+    pub fn sent_count(&self) -> u64 {
+        self.shared.synced.lock().unwrap().sent_count
+    }
+    /// Total time [`Sender`]s waited for [`Receiver`]s to receive the
+    /// previously sent value (requires `profiling` feature)
+    #[cfg(feature = "profiling")]
+    pub fn blocked_time(&self) -> Duration {
+        self.shared.synced.lock().unwrap().blocked
+    }
+    /// Wait until all [`Sender`]s have been dropped
+    pub async fn closed(&self) {
+        loop {
+            {
+                let synced = self.shared.synced.lock().unwrap();
+                if synced.sndr_count == 0 {
+                    return;
+                }
+                self.shared.notify_rcvr.notified()
+            }
+            .await;
+        }
+    }
 }
 
 impl<T> Receiver<T>
@@ -373,4 +480,24 @@ mod tests {
         assert_eq!(vec2, vec![1, 5, 3]);
         assert_eq!(vec3, vec![1, 5, 3]);
     }
+    #[tokio::test]
+    async fn test_observer() {
+        let (sender, enlister) = channel::<i32>();
+        let observer = enlister.observer();
+        let mut receiver = enlister.subscribe();
+        assert_eq!(observer.receiver_count(), 1);
+        sender.send(1).await.unwrap();
+        assert_eq!(observer.pending(), 1);
+        receiver.recv().await.unwrap();
+        assert_eq!(observer.pending(), 0);
+        assert_eq!(observer.sent_count(), 1);
+        drop(receiver);
+        drop(enlister);
+        assert!(!observer.is_closed());
+        assert!(sender.send(2).await.is_err());
+        assert_eq!(observer.sent_count(), 1);
+        let join_handle = tokio::spawn(async move { observer.closed().await });
+        drop(sender);
+        join_handle.await.unwrap();
+    }
 }
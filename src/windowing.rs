@@ -19,6 +19,103 @@ impl Window for Rectangular {
     }
 }
 
+/// Hann window
+#[derive(Clone, Debug)]
+pub struct Hann;
+
+impl Window for Hann {
+    fn relative_value_at(&self, x: f64) -> f64 {
+        use std::f64::consts::PI;
+        0.5 + 0.5 * (PI * x).cos()
+    }
+}
+
+/// Hamming window
+#[derive(Clone, Debug)]
+pub struct Hamming;
+
+impl Window for Hamming {
+    fn relative_value_at(&self, x: f64) -> f64 {
+        use std::f64::consts::PI;
+        0.54 + 0.46 * (PI * x).cos()
+    }
+}
+
+/// Blackman window
+#[derive(Clone, Debug)]
+pub struct Blackman;
+
+impl Window for Blackman {
+    fn relative_value_at(&self, x: f64) -> f64 {
+        use std::f64::consts::PI;
+        0.42 + 0.5 * (PI * x).cos() + 0.08 * (2.0 * PI * x).cos()
+    }
+}
+
+/// Blackman-Harris window (four terms)
+#[derive(Clone, Debug)]
+pub struct BlackmanHarris;
+
+impl Window for BlackmanHarris {
+    fn relative_value_at(&self, x: f64) -> f64 {
+        use std::f64::consts::PI;
+        0.35875
+            + 0.48829 * (PI * x).cos()
+            + 0.14128 * (2.0 * PI * x).cos()
+            + 0.01168 * (3.0 * PI * x).cos()
+    }
+}
+
+/// Flat top window (as used by MATLAB), suitable for accurate amplitude
+/// measurements
+#[derive(Clone, Debug)]
+pub struct FlatTop;
+
+impl Window for FlatTop {
+    fn relative_value_at(&self, x: f64) -> f64 {
+        use std::f64::consts::PI;
+        0.21557895
+            + 0.41663158 * (PI * x).cos()
+            + 0.277263158 * (2.0 * PI * x).cos()
+            + 0.083578947 * (3.0 * PI * x).cos()
+            + 0.006947368 * (4.0 * PI * x).cos()
+    }
+}
+
+/// Tukey (tapered cosine) window
+#[derive(Clone, Debug)]
+pub struct Tukey {
+    alpha: f64,
+}
+
+impl Window for Tukey {
+    fn relative_value_at(&self, x: f64) -> f64 {
+        use std::f64::consts::PI;
+        let flat = 1.0 - self.alpha;
+        let x = x.abs();
+        if x <= flat {
+            1.0
+        } else {
+            0.5 + 0.5 * (PI * (x - flat) / self.alpha).cos()
+        }
+    }
+}
+
+impl Tukey {
+    /// Tukey window where a fraction of `alpha` (from `0.0` to `1.0`) is
+    /// tapered
+    ///
+    /// An `alpha` of `0.0` results in a rectangular window, while an `alpha`
+    /// of `1.0` results in a Hann window.
+    pub fn with_alpha(alpha: f64) -> Self {
+        assert!(
+            (0.0..=1.0).contains(&alpha),
+            "alpha must be between 0.0 and 1.0"
+        );
+        Self { alpha }
+    }
+}
+
 /// Kaiser window
 #[derive(Clone, Debug)]
 pub struct Kaiser {